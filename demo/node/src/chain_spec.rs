@@ -3,12 +3,13 @@ use partner_chains_demo_runtime::{
 	AccountId, CrossChainPublic, SLOT_DURATION, SessionConfig, Signature, WASM_BINARY,
 	opaque::SessionKeys,
 };
+use partner_chains_node_builder::chain_spec::PartnerChainsChainSpecExtension;
 use sc_service::ChainType;
 use sidechain_domain::ScEpochDuration;
 use sp_core::{Pair, Public};
 use sp_runtime::traits::{IdentifyAccount, Verify};
 
-pub type ChainSpec = sc_service::GenericChainSpec;
+pub type ChainSpec = sc_service::GenericChainSpec<PartnerChainsChainSpecExtension>;
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct AuthorityKeys {
@@ -76,7 +77,8 @@ pub fn pc_create_chain_spec(config: &CreateChainSpecConfig<SessionKeys>) -> serd
 	};
 	let genesis_json = serde_json::to_value(runtime_genesis_config)
 		.expect("Genesis config must be serialized correctly");
-	let chain_spec = ChainSpec::builder(runtime_wasm(), None)
+	let extension = PartnerChainsChainSpecExtension::new(config.finality.clone());
+	let chain_spec = ChainSpec::builder(runtime_wasm(), extension)
 		.with_name("Partner Chains Demo")
 		.with_id("partner_chains_demo")
 		.with_chain_type(ChainType::Live)
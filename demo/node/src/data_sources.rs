@@ -7,15 +7,18 @@ use sidechain_mc_hash::McHashDataSource;
 use sp_block_participation::inherent_data::BlockParticipationDataSource;
 use sp_governed_map::GovernedMapDataSource;
 use sp_partner_chains_bridge::TokenBridgeDataSource;
-use std::{error::Error, sync::Arc};
+use std::{error::Error, path::Path, sync::Arc};
 
 pub const DATA_SOURCE_VAR: &str = "CARDANO_DATA_SOURCE";
+pub const SECONDARY_DATA_SOURCE_VAR: &str = "CARDANO_SECONDARY_DATA_SOURCE";
+pub const PERSISTENT_CACHE_VAR: &str = "DATA_SOURCE_PERSISTENT_CACHE";
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum DataSourceType {
 	DbSync,
 	Mock,
 	Dolos,
+	Replay,
 }
 
 impl DataSourceType {
@@ -25,6 +28,13 @@ impl DataSourceType {
 
 		env_value.parse().map_err(|err: String| err.into())
 	}
+
+	pub fn secondary_from_env() -> Result<Option<Self>, Box<dyn Error + Send + Sync + 'static>> {
+		match std::env::var(SECONDARY_DATA_SOURCE_VAR) {
+			Ok(env_value) => Ok(Some(env_value.parse()?)),
+			Err(_) => Ok(None),
+		}
+	}
 }
 
 impl std::str::FromStr for DataSourceType {
@@ -35,9 +45,11 @@ impl std::str::FromStr for DataSourceType {
 			"db-sync" => Ok(DataSourceType::DbSync),
 			"mock" => Ok(DataSourceType::Mock),
 			"dolos" => Ok(DataSourceType::Dolos),
-			_ => {
-				Err(format!("Invalid data source type: {}. Valid options: db-sync, mock, dolos", s))
-			},
+			"replay" => Ok(DataSourceType::Replay),
+			_ => Err(format!(
+				"Invalid data source type: {}. Valid options: db-sync, mock, dolos, replay",
+				s
+			)),
 		}
 	}
 }
@@ -48,6 +60,7 @@ impl std::fmt::Display for DataSourceType {
 			DataSourceType::DbSync => write!(f, "db-sync"),
 			DataSourceType::Mock => write!(f, "mock"),
 			DataSourceType::Dolos => write!(f, "dolos"),
+			DataSourceType::Replay => write!(f, "replay"),
 		}
 	}
 }
@@ -63,11 +76,49 @@ pub struct DataSources {
 }
 
 pub(crate) async fn create_cached_data_sources(
+	node_data_path: &Path,
 	metrics_opt: Option<McFollowerMetrics>,
 ) -> std::result::Result<DataSources, ServiceError> {
 	let data_source_type = DataSourceType::from_env()
 		.map_err(|err| ServiceError::Application(err.to_string().into()))?;
 
+	let data_sources = create_data_sources(data_source_type, metrics_opt.clone()).await?;
+
+	let data_sources = match DataSourceType::secondary_from_env()
+		.map_err(|err| ServiceError::Application(err.to_string().into()))?
+	{
+		Some(secondary_type) => {
+			let secondary = create_data_sources(secondary_type, metrics_opt.clone()).await?;
+			with_failover(data_sources, secondary, metrics_opt).map_err(|err| {
+				ServiceError::Application(
+					format!("Failed to create failover data sources: {err}").into(),
+				)
+			})?
+		},
+		None => data_sources,
+	};
+
+	let data_sources = if persistent_cache_enabled() {
+		with_persistent_cache(data_sources, &node_data_path.join("mc_data_cache")).map_err(
+			|err| {
+				ServiceError::Application(
+					format!("Failed to open persistent data source cache: {err}").into(),
+				)
+			},
+		)?
+	} else {
+		data_sources
+	};
+
+	with_recording_from_env(data_sources).map_err(|err| {
+		ServiceError::Application(format!("Failed to set up data source recording: {err}").into())
+	})
+}
+
+async fn create_data_sources(
+	data_source_type: DataSourceType,
+	metrics_opt: Option<McFollowerMetrics>,
+) -> std::result::Result<DataSources, ServiceError> {
 	match data_source_type {
 		DataSourceType::DbSync => {
 			create_cached_db_sync_data_sources(metrics_opt).await.map_err(|err| {
@@ -84,12 +135,142 @@ pub(crate) async fn create_cached_data_sources(
 		DataSourceType::Dolos => create_dolos_data_sources().await.map_err(|err| {
 			ServiceError::Application(format!("Failed to create dolos data sources: {err}").into())
 		}),
+
+		DataSourceType::Replay => create_replay_data_sources().map_err(|err| {
+			ServiceError::Application(format!("Failed to create replay data sources: {err}").into())
+		}),
 	}
 }
 
+pub fn with_failover(
+	primary: DataSources,
+	secondary: DataSources,
+	metrics_opt: Option<McFollowerMetrics>,
+) -> std::result::Result<DataSources, Box<dyn Error + Send + Sync + 'static>> {
+	use partner_chains_failover_data_sources::*;
+	let health = Arc::new(FailoverHealth::new(
+		primary.sidechain_rpc.clone(),
+		secondary.sidechain_rpc.clone(),
+		sidechain_domain::mainchain_epoch::MainchainEpochConfig::read_from_env()?,
+		FailoverConfig::from_env()?,
+		metrics_opt,
+	));
+	Ok(DataSources {
+		mc_hash: Arc::new(FailoverDataSource::new(
+			primary.mc_hash,
+			secondary.mc_hash,
+			health.clone(),
+		)),
+		authority_selection: Arc::new(FailoverDataSource::new(
+			primary.authority_selection,
+			secondary.authority_selection,
+			health.clone(),
+		)),
+		sidechain_rpc: Arc::new(FailoverDataSource::new(
+			primary.sidechain_rpc,
+			secondary.sidechain_rpc,
+			health.clone(),
+		)),
+		block_participation: Arc::new(FailoverDataSource::new(
+			primary.block_participation,
+			secondary.block_participation,
+			health.clone(),
+		)),
+		governed_map: Arc::new(FailoverDataSource::new(
+			primary.governed_map,
+			secondary.governed_map,
+			health.clone(),
+		)),
+		bridge: Arc::new(FailoverDataSource::new(primary.bridge, secondary.bridge, health)),
+	})
+}
+
+fn persistent_cache_enabled() -> bool {
+	std::env::var(PERSISTENT_CACHE_VAR).is_ok_and(|value| value.to_lowercase() == "true")
+}
+
+pub fn with_persistent_cache(
+	data_sources: DataSources,
+	path: &Path,
+) -> std::result::Result<DataSources, Box<dyn Error + Send + Sync + 'static>> {
+	use partner_chains_persistent_cache_data_sources::*;
+	let cache = Arc::new(PersistentCache::open(path, data_sources.mc_hash.clone())?);
+	Ok(DataSources {
+		mc_hash: Arc::new(PersistentCacheDataSource::new(data_sources.mc_hash, cache.clone())),
+		authority_selection: Arc::new(PersistentCacheDataSource::new(
+			data_sources.authority_selection,
+			cache.clone(),
+		)),
+		sidechain_rpc: data_sources.sidechain_rpc,
+		block_participation: Arc::new(PersistentCacheDataSource::new(
+			data_sources.block_participation,
+			cache.clone(),
+		)),
+		governed_map: Arc::new(PersistentCacheDataSource::new(
+			data_sources.governed_map,
+			cache.clone(),
+		)),
+		bridge: Arc::new(PersistentCacheDataSource::new(data_sources.bridge, cache)),
+	})
+}
+
+pub fn with_recording_from_env(
+	data_sources: DataSources,
+) -> std::result::Result<DataSources, Box<dyn Error + Send + Sync + 'static>> {
+	use partner_chains_mock_data_sources::{DataSourceRecorder, RecordingDataSource};
+	let Some(recorder) = DataSourceRecorder::new_from_env()? else {
+		return Ok(data_sources);
+	};
+	let recorder = Arc::new(recorder);
+	Ok(DataSources {
+		mc_hash: Arc::new(RecordingDataSource::new(data_sources.mc_hash, recorder.clone())),
+		authority_selection: Arc::new(RecordingDataSource::new(
+			data_sources.authority_selection,
+			recorder.clone(),
+		)),
+		sidechain_rpc: Arc::new(RecordingDataSource::new(
+			data_sources.sidechain_rpc,
+			recorder.clone(),
+		)),
+		block_participation: Arc::new(RecordingDataSource::new(
+			data_sources.block_participation,
+			recorder.clone(),
+		)),
+		governed_map: Arc::new(RecordingDataSource::new(
+			data_sources.governed_map,
+			recorder.clone(),
+		)),
+		bridge: Arc::new(RecordingDataSource::new(data_sources.bridge, recorder)),
+	})
+}
+
+pub fn create_replay_data_sources()
+-> std::result::Result<DataSources, Box<dyn Error + Send + Sync + 'static>> {
+	let replay = Arc::new(partner_chains_mock_data_sources::ReplayDataSource::new_from_env()?);
+	Ok(DataSources {
+		mc_hash: replay.clone(),
+		authority_selection: replay.clone(),
+		sidechain_rpc: replay.clone(),
+		block_participation: replay.clone(),
+		governed_map: replay.clone(),
+		bridge: replay,
+	})
+}
+
 pub fn create_mock_data_sources()
 -> std::result::Result<DataSources, Box<dyn Error + Send + Sync + 'static>> {
 	use partner_chains_mock_data_sources::*;
+	if std::env::var("MOCK_SCENARIO_FILE").is_ok() {
+		let scenario = Arc::new(ScenarioDataSource::new_from_env()?);
+		return Ok(DataSources {
+			sidechain_rpc: scenario.clone(),
+			mc_hash: scenario.clone(),
+			authority_selection: scenario.clone(),
+			block_participation: scenario.clone(),
+			governed_map: scenario.clone(),
+			bridge: scenario,
+		});
+	}
 	let block = Arc::new(BlockDataSourceMock::new_from_env()?);
 	Ok(DataSources {
 		sidechain_rpc: Arc::new(SidechainRpcDataSourceMock::new(block.clone())),
@@ -1,280 +0,0 @@
-use authority_selection_inherents::{
-	AriadneInherentDataProvider as AriadneIDP, AuthoritySelectionDataSource,
-};
-use derive_new::new;
-use jsonrpsee::core::async_trait;
-use partner_chains_demo_runtime::{
-	AccountId, BlockAuthor, CrossChainPublic,
-	opaque::{Block, SessionKeys},
-};
-use sc_consensus_aura::{SlotDuration, find_pre_digest};
-use sc_service::Arc;
-use sidechain_domain::{
-	DelegatorKey, McBlockHash, ScEpochNumber, mainchain_epoch::MainchainEpochConfig,
-};
-use sidechain_mc_hash::{McHashDataSource, McHashInherentDataProvider as McHashIDP};
-use sp_api::ProvideRuntimeApi;
-use sp_block_participation::{
-	BlockParticipationApi,
-	inherent_data::{BlockParticipationDataSource, BlockParticipationInherentDataProvider},
-};
-use sp_blockchain::HeaderBackend;
-use sp_consensus_aura::{
-	Slot, inherents::InherentDataProvider as AuraIDP, sr25519::AuthorityPair as AuraPair,
-};
-use sp_core::Pair;
-use sp_governed_map::{GovernedMapDataSource, GovernedMapIDPApi, GovernedMapInherentDataProvider};
-use sp_inherents::CreateInherentDataProviders;
-use sp_partner_chains_bridge::{
-	TokenBridgeDataSource, TokenBridgeIDPRuntimeApi, TokenBridgeInherentDataProvider,
-};
-use sp_partner_chains_consensus_aura::CurrentSlotProvider;
-use sp_runtime::traits::{Block as BlockT, Header, Zero};
-use sp_session_validator_management::SessionValidatorManagementApi;
-use sp_timestamp::{InherentDataProvider as TimestampIDP, Timestamp};
-use std::error::Error;
-use time_source::TimeSource;
-
-#[derive(new)]
-pub struct ProposalCIDP<T> {
-	config: CreateInherentDataConfig,
-	client: Arc<T>,
-	mc_hash_data_source: Arc<dyn McHashDataSource + Send + Sync>,
-	authority_selection_data_source: Arc<dyn AuthoritySelectionDataSource + Send + Sync>,
-	block_participation_data_source: Arc<dyn BlockParticipationDataSource + Send + Sync>,
-	governed_map_data_source: Arc<dyn GovernedMapDataSource + Send + Sync>,
-	bridge_data_source: Arc<dyn TokenBridgeDataSource<AccountId> + Send + Sync>,
-}
-
-#[async_trait]
-impl<T> CreateInherentDataProviders<Block, ()> for ProposalCIDP<T>
-where
-	T: ProvideRuntimeApi<Block> + Send + Sync + 'static,
-	T: HeaderBackend<Block>,
-	T::Api: SessionValidatorManagementApi<Block, CrossChainPublic, SessionKeys, ScEpochNumber>,
-	T::Api: BlockParticipationApi<Block, BlockAuthor, Slot>,
-	T::Api: GovernedMapIDPApi<Block>,
-	T::Api: TokenBridgeIDPRuntimeApi<Block>,
-{
-	type InherentDataProviders = (
-		AuraIDP,
-		TimestampIDP,
-		McHashIDP,
-		AriadneIDP,
-		BlockParticipationInherentDataProvider<BlockAuthor, DelegatorKey, Slot>,
-		GovernedMapInherentDataProvider,
-		TokenBridgeInherentDataProvider<AccountId>,
-	);
-
-	async fn create_inherent_data_providers(
-		&self,
-		parent_hash: <Block as BlockT>::Hash,
-		_extra_args: (),
-	) -> Result<Self::InherentDataProviders, Box<dyn std::error::Error + Send + Sync>> {
-		let Self {
-			config,
-			client,
-			mc_hash_data_source,
-			authority_selection_data_source,
-			block_participation_data_source,
-			governed_map_data_source,
-			bridge_data_source,
-		} = self;
-		let CreateInherentDataConfig { mc_epoch_config, slot_duration, time_source, .. } = config;
-
-		let (slot, timestamp) = timestamp_and_slot_cidp(*slot_duration, time_source.clone());
-		let parent_header = client.expect_header(parent_hash)?;
-
-		// note: We pass slot start time to `McHashIDP` instead of timestamp for backward compatibility
-		// with the old `McHashIDP` version that was slot-based.
-		let slot_start_timestamp = config.slot_start_time(*slot);
-		let mc_hash = McHashIDP::new_proposal(
-			parent_header,
-			mc_hash_data_source.as_ref(),
-			slot_start_timestamp,
-		)
-		.await?;
-
-		let ariadne_data_provider = AriadneIDP::new(
-			client.as_ref(),
-			config.sc_epoch_duration_millis,
-			mc_epoch_config,
-			parent_hash,
-			(*timestamp).as_millis(),
-			authority_selection_data_source.as_ref(),
-			mc_hash.mc_epoch(),
-		)
-		.await?;
-
-		let payouts = BlockParticipationInherentDataProvider::new(
-			client.as_ref(),
-			block_participation_data_source.as_ref(),
-			parent_hash,
-			*slot,
-			mc_epoch_config,
-		)
-		.await?;
-
-		let governed_map = GovernedMapInherentDataProvider::new(
-			client.as_ref(),
-			parent_hash,
-			mc_hash.mc_hash(),
-			mc_hash.previous_mc_hash(),
-			governed_map_data_source.as_ref(),
-		)
-		.await?;
-
-		let bridge = TokenBridgeInherentDataProvider::new(
-			client.as_ref(),
-			parent_hash,
-			mc_hash.mc_hash(),
-			bridge_data_source.as_ref(),
-		)
-		.await?;
-
-		Ok((slot, timestamp, mc_hash, ariadne_data_provider, payouts, governed_map, bridge))
-	}
-}
-
-#[derive(new)]
-pub struct VerifierCIDP<T> {
-	config: CreateInherentDataConfig,
-	client: Arc<T>,
-	mc_hash_data_source: Arc<dyn McHashDataSource + Send + Sync>,
-	authority_selection_data_source: Arc<dyn AuthoritySelectionDataSource + Send + Sync>,
-	block_participation_data_source: Arc<dyn BlockParticipationDataSource + Send + Sync>,
-	governed_map_data_source: Arc<dyn GovernedMapDataSource + Send + Sync>,
-	bridge_data_source: Arc<dyn TokenBridgeDataSource<AccountId> + Send + Sync>,
-}
-
-impl<T: Send + Sync> CurrentSlotProvider for VerifierCIDP<T> {
-	fn slot(&self) -> Slot {
-		*timestamp_and_slot_cidp(self.config.slot_duration, self.config.time_source.clone()).0
-	}
-}
-
-#[async_trait]
-impl<T> CreateInherentDataProviders<Block, (Slot, McBlockHash)> for VerifierCIDP<T>
-where
-	T: ProvideRuntimeApi<Block> + Send + Sync + HeaderBackend<Block>,
-	T::Api: SessionValidatorManagementApi<Block, CrossChainPublic, SessionKeys, ScEpochNumber>,
-	T::Api: BlockParticipationApi<Block, BlockAuthor, Slot>,
-	T::Api: GovernedMapIDPApi<Block>,
-	T::Api: TokenBridgeIDPRuntimeApi<Block>,
-{
-	type InherentDataProviders = (
-		TimestampIDP,
-		AriadneIDP,
-		BlockParticipationInherentDataProvider<BlockAuthor, DelegatorKey, Slot>,
-		GovernedMapInherentDataProvider,
-		TokenBridgeInherentDataProvider<AccountId>,
-	);
-
-	async fn create_inherent_data_providers(
-		&self,
-		parent_hash: <Block as BlockT>::Hash,
-		(verified_block_slot, mc_hash): (Slot, McBlockHash),
-	) -> Result<Self::InherentDataProviders, Box<dyn Error + Send + Sync>> {
-		let Self {
-			config,
-			client,
-			mc_hash_data_source,
-			authority_selection_data_source,
-			block_participation_data_source,
-			governed_map_data_source,
-			bridge_data_source,
-		} = self;
-		let CreateInherentDataConfig { mc_epoch_config, .. } = config;
-
-		// note: Because it's not exposed during block verification, we are approximating the block
-		// timestamp by the starting timestamp of the slots. This is also needed for backward compatibility
-		// of [McHashIDP] for chains that used the old slot-based version of it.
-		let timestamp = TimestampIDP::new(config.slot_start_time(verified_block_slot));
-
-		let parent_header = client.expect_header(parent_hash)?;
-		let parent_slot = slot_from_predigest(&parent_header)?;
-		let parent_slot_timestamp = parent_slot.map(|slot| config.slot_start_time(slot));
-
-		let mc_state_reference = McHashIDP::new_verification(
-			parent_header,
-			parent_slot_timestamp,
-			*timestamp,
-			mc_hash.clone(),
-			mc_hash_data_source.as_ref(),
-		)
-		.await?;
-
-		let ariadne_data_provider = AriadneIDP::new(
-			client.as_ref(),
-			config.sc_epoch_duration_millis,
-			mc_epoch_config,
-			parent_hash,
-			(*timestamp).as_millis(),
-			authority_selection_data_source.as_ref(),
-			mc_state_reference.epoch,
-		)
-		.await?;
-
-		let payouts = BlockParticipationInherentDataProvider::new(
-			client.as_ref(),
-			block_participation_data_source.as_ref(),
-			parent_hash,
-			verified_block_slot,
-			mc_epoch_config,
-		)
-		.await?;
-
-		let governed_map = GovernedMapInherentDataProvider::new(
-			client.as_ref(),
-			parent_hash,
-			mc_hash.clone(),
-			mc_state_reference.previous_mc_hash(),
-			governed_map_data_source.as_ref(),
-		)
-		.await?;
-
-		let bridge = TokenBridgeInherentDataProvider::new(
-			client.as_ref(),
-			parent_hash,
-			mc_hash,
-			bridge_data_source.as_ref(),
-		)
-		.await?;
-
-		Ok((timestamp, ariadne_data_provider, payouts, governed_map, bridge))
-	}
-}
-
-pub fn slot_from_predigest(
-	header: &<Block as BlockT>::Header,
-) -> Result<Option<Slot>, Box<dyn Error + Send + Sync>> {
-	if header.number().is_zero() {
-		// genesis block doesn't have a slot
-		Ok(None)
-	} else {
-		Ok(Some(find_pre_digest::<Block, <AuraPair as Pair>::Signature>(header)?))
-	}
-}
-
-#[derive(new, Clone)]
-pub struct CreateInherentDataConfig {
-	pub mc_epoch_config: MainchainEpochConfig,
-	pub slot_duration: SlotDuration,
-	pub sc_epoch_duration_millis: u64,
-	pub time_source: Arc<dyn TimeSource + Send + Sync>,
-}
-
-impl CreateInherentDataConfig {
-	pub fn slot_start_time(&self, slot: Slot) -> Timestamp {
-		Timestamp::new(self.slot_duration.as_millis() * u64::from(slot))
-	}
-}
-
-fn timestamp_and_slot_cidp(
-	slot_duration: SlotDuration,
-	time_source: Arc<dyn TimeSource + Send + Sync>,
-) -> (AuraIDP, TimestampIDP) {
-	let timestamp = TimestampIDP::new(Timestamp::new(time_source.get_current_time_millis()));
-	let slot = AuraIDP::from_timestamp_and_slot_duration(*timestamp, slot_duration);
-	(slot, timestamp)
-}
@@ -3,7 +3,7 @@
 
 pub mod chain_spec;
 mod data_sources;
-mod inherent_data;
+mod node_builder;
 pub mod rpc;
 pub mod service;
 pub mod staging;
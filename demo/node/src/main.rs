@@ -6,7 +6,7 @@ mod chain_spec;
 mod cli;
 mod command;
 mod data_sources;
-mod inherent_data;
+mod node_builder;
 mod rpc;
 mod service;
 mod staging;
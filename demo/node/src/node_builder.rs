@@ -0,0 +1,75 @@
+use crate::data_sources::DataSources;
+use parity_scale_codec::{Decode, Encode};
+use partner_chains_demo_runtime::{
+	AccountId, BlockAuthor, CrossChainPublic, RuntimeCall, UncheckedExtrinsic,
+	opaque::{Block, SessionKeys},
+};
+use partner_chains_node_builder::{
+	CreateInherentDataConfig, PartnerChainsFeature, PartnerChainsNodeBuilder,
+	diagnostics::InherentExtrinsicDecoder, features::*,
+};
+use sidechain_domain::DelegatorKey;
+use sp_inherents::InherentIdentifier;
+use sp_partner_chains_bridge::TokenBridgeTransfersV1;
+use sp_runtime::OpaqueExtrinsic;
+use std::sync::Arc;
+
+/// Creates a [PartnerChainsNodeBuilder] with all Partner Chains features used by the demo runtime
+pub fn partner_chains_node_builder<C: 'static>(
+	config: CreateInherentDataConfig,
+	client: Arc<C>,
+	data_sources: &DataSources,
+) -> PartnerChainsNodeBuilder<Block, C>
+where
+	CommitteeSelectionFeature<CrossChainPublic, SessionKeys>: PartnerChainsFeature<Block, C>,
+	BlockParticipationFeature<BlockAuthor, DelegatorKey>: PartnerChainsFeature<Block, C>,
+	GovernedMapFeature: PartnerChainsFeature<Block, C>,
+	BridgeFeature<AccountId>: PartnerChainsFeature<Block, C>,
+{
+	PartnerChainsNodeBuilder::new(config, client, data_sources.mc_hash.clone())
+		.with_committee_selection::<CrossChainPublic, SessionKeys>(
+			data_sources.authority_selection.clone(),
+		)
+		.with_block_participation::<BlockAuthor, DelegatorKey>(
+			data_sources.block_participation.clone(),
+		)
+		.with_governed_map(data_sources.governed_map.clone())
+		.with_bridge::<AccountId>(data_sources.bridge.clone())
+}
+
+/// Extracts payloads of Partner Chains inherents from extrinsics of the demo runtime
+///
+/// Bounded collections used by the pallets' calls are encoded the same way as their unbounded
+/// counterparts used in the inherent data, so most call arguments are re-encoded without conversion.
+pub struct DemoInherentExtrinsicDecoder;
+
+impl InherentExtrinsicDecoder<Block> for DemoInherentExtrinsicDecoder {
+	fn decode_inherent(
+		&self,
+		extrinsic: &OpaqueExtrinsic,
+	) -> Option<(InherentIdentifier, Vec<u8>)> {
+		let extrinsic = UncheckedExtrinsic::decode(&mut extrinsic.encode().as_slice()).ok()?;
+		match extrinsic.function {
+			RuntimeCall::GovernedMap(pallet_governed_map::Call::register_changes { changes }) => {
+				Some((sp_governed_map::INHERENT_IDENTIFIER, changes.encode()))
+			},
+			RuntimeCall::Bridge(pallet_partner_chains_bridge::Call::handle_transfers {
+				transfers,
+				data_checkpoint,
+			}) => Some((
+				sp_partner_chains_bridge::INHERENT_IDENTIFIER,
+				TokenBridgeTransfersV1 { transfers: transfers.into_inner(), data_checkpoint }
+					.encode(),
+			)),
+			RuntimeCall::SessionCommitteeManagement(
+				pallet_session_validator_management::Call::set {
+					validators, for_epoch_number, ..
+				},
+			) => Some((
+				sp_session_validator_management::INHERENT_IDENTIFIER,
+				(for_epoch_number, validators).encode(),
+			)),
+			_ => None,
+		}
+	}
+}
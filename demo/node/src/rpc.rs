@@ -6,32 +6,24 @@
 #![warn(missing_docs)]
 
 use crate::data_sources::DataSources;
-use authority_selection_inherents::CandidateValidationApi;
 use jsonrpsee::RpcModule;
 use pallet_block_producer_fees_rpc::*;
 use pallet_block_producer_metadata_rpc::*;
-use pallet_session_validator_management_rpc::*;
-use pallet_sidechain_rpc::*;
-use partner_chains_demo_runtime::{
-	AccountId, Balance, Nonce,
-	opaque::{Block, SessionKeys},
-};
-use partner_chains_demo_runtime::{BlockNumber, BlockProducerMetadataType, CrossChainPublic, Hash};
+use pallet_missed_slots_rpc::*;
+use partner_chains_demo_runtime::{AccountId, Balance, Nonce, opaque::Block};
+use partner_chains_demo_runtime::{BlockAuthor, BlockNumber, BlockProducerMetadataType, Hash};
+use partner_chains_node_builder::PartnerChainsNodeBuilder;
 use sc_consensus_grandpa::{
 	FinalityProofProvider, GrandpaJustificationStream, SharedAuthoritySet, SharedVoterState,
 };
 use sc_consensus_grandpa_rpc::{Grandpa, GrandpaApiServer};
 use sc_rpc::SubscriptionTaskExecutor;
 use sc_transaction_pool_api::TransactionPool;
-use sidechain_domain::ScEpochNumber;
-use sidechain_domain::mainchain_epoch::MainchainEpochConfig;
 use sp_api::{CallApiAt, ProvideRuntimeApi};
 use sp_block_builder::BlockBuilder;
 use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
-use sp_session_validator_management_query::SessionValidatorManagementQuery;
-use sp_sidechain::GetEpochDurationApi;
+use sp_sidechain::{GetEpochDurationApi, GetEpochScheduleApi};
 use std::sync::Arc;
-use time_source::TimeSource;
 
 /// Extra dependencies for GRANDPA
 pub struct GrandpaDeps<B> {
@@ -48,7 +40,7 @@ pub struct GrandpaDeps<B> {
 }
 
 /// Full client dependencies.
-pub struct FullDeps<C, P, B, T> {
+pub struct FullDeps<C, P, B> {
 	/// The client instance to use.
 	pub client: Arc<C>,
 	/// Transaction pool instance.
@@ -57,13 +49,13 @@ pub struct FullDeps<C, P, B, T> {
 	pub grandpa: GrandpaDeps<B>,
 	/// Data sources.
 	pub data_sources: DataSources,
-	/// Source of system time
-	pub time_source: Arc<T>,
+	/// Builder providing RPC modules of Partner Chains features
+	pub node_builder: PartnerChainsNodeBuilder<Block, C>,
 }
 
 /// Instantiate all full RPC extensions.
-pub fn create_full<C, P, B, T>(
-	deps: FullDeps<C, P, B, T>,
+pub fn create_full<C, P, B>(
+	deps: FullDeps<C, P, B>,
 ) -> Result<RpcModule<()>, Box<dyn std::error::Error + Send + Sync>>
 where
 	C: ProvideRuntimeApi<Block>,
@@ -78,38 +70,25 @@ where
 	C::Api: sp_sidechain::GetSidechainStatus<Block>,
 	C::Api: sp_block_producer_fees::BlockProducerFeesApi<Block, AccountId>,
 	C::Api: sp_block_producer_metadata::BlockProducerMetadataApi<Block, BlockProducerMetadataType>,
-	C::Api: sp_session_validator_management::SessionValidatorManagementApi<
-			Block,
-			CrossChainPublic,
-			SessionKeys,
-			ScEpochNumber,
-		>,
-	C::Api: CandidateValidationApi<Block>,
+	C::Api: sp_missed_slots::MissedSlotsApi<Block, BlockAuthor>,
 	C::Api: GetEpochDurationApi<Block>,
+	C::Api: GetEpochScheduleApi<Block>,
 	P: TransactionPool + 'static,
 	B: sc_client_api::Backend<Block> + Send + Sync + 'static,
 	B::State: sc_client_api::backend::StateBackend<sp_runtime::traits::HashingFor<Block>>,
-	T: TimeSource + Send + Sync + 'static,
 {
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApiServer};
 	use substrate_frame_rpc_system::{System, SystemApiServer};
 
 	let mut module = RpcModule::new(());
-	let FullDeps { client, pool, grandpa, data_sources, time_source } = deps;
+	let FullDeps { client, pool, grandpa, data_sources, node_builder } = deps;
 
 	module.merge(System::new(client.clone(), pool.clone()).into_rpc())?;
 	module.merge(TransactionPayment::new(client.clone()).into_rpc())?;
-	module.merge(
-		SidechainRpc::new(
-			client.clone(),
-			MainchainEpochConfig::read_from_env().unwrap(),
-			data_sources.sidechain_rpc.clone(),
-			time_source.clone(),
-		)
-		.into_rpc(),
-	)?;
+	module.merge(node_builder.rpc_module(data_sources.sidechain_rpc.clone())?)?;
 	module.merge(BlockProducerFeesRpc::new(client.clone()).into_rpc())?;
 	module.merge(BlockProducerMetadataRpc::new(client.clone()).into_rpc())?;
+	module.merge(MissedSlotsRpc::new(client.clone()).into_rpc())?;
 
 	let GrandpaDeps {
 		shared_voter_state,
@@ -128,13 +107,6 @@ where
 		)
 		.into_rpc(),
 	)?;
-	module.merge(
-		SessionValidatorManagementRpc::new(Arc::new(SessionValidatorManagementQuery::new(
-			client.clone(),
-			data_sources.authority_selection.clone(),
-		)))
-		.into_rpc(),
-	)?;
 
 	// Extend this RPC with a custom API by using the following syntax.
 	// `YourRpcStruct` should have a reference to a client, which is needed
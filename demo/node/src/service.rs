@@ -1,28 +1,29 @@
 //! Service and ServiceFactory implementation. Specialized wrapper over substrate service.
 
 use crate::data_sources::DataSources;
-use crate::inherent_data::{CreateInherentDataConfig, ProposalCIDP, VerifierCIDP};
+use crate::node_builder::{DemoInherentExtrinsicDecoder, partner_chains_node_builder};
 use crate::rpc::GrandpaDeps;
 use authority_selection_inherents::AuthoritySelectionDataSource;
 use partner_chains_data_source_metrics::{McFollowerMetrics, register_metrics_warn_errors};
 use partner_chains_demo_runtime::{self, RuntimeApi, opaque::Block};
+use partner_chains_node_builder::{
+	CreateInherentDataConfig, PartnerChainsNodeBuilder, chain_spec::FinalityConfig,
+	diagnostics::InherentDiagnosticsConfig,
+};
 use sc_client_api::BlockBackend;
 use sc_consensus_aura::{ImportQueueParams, SlotProportion, StartAuraParams};
 use sc_consensus_grandpa::SharedVoterState;
 pub use sc_executor::WasmExecutor;
-use sc_partner_chains_consensus_aura::import_queue as partner_chains_aura_import_queue;
+use sc_partner_chains_consensus_aura::import_queue::RuntimeEquivocationReporter;
 use sc_service::{Configuration, TaskManager, WarpSyncConfig, error::Error as ServiceError};
 use sc_telemetry::{Telemetry, TelemetryWorker};
 use sc_transaction_pool_api::OffchainTransactionPoolFactory;
 use sidechain_domain::mainchain_epoch::MainchainEpochConfig;
-use sidechain_mc_hash::McHashInherentDigest;
+use sidechain_mc_hash::monitor::McReferenceMonitor;
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::HeaderBackend;
 use sp_consensus_aura::AuraApi;
-use sp_consensus_aura::sr25519::AuthorityPair as AuraPair;
-use sp_partner_chains_consensus_aura::block_proposal::PartnerChainsProposerFactory;
 use sp_runtime::traits::Block as BlockT;
-use sp_sidechain::GetEpochDurationApi;
 use std::{sync::Arc, time::Duration};
 use time_source::SystemTimeSource;
 use tokio::task;
@@ -34,9 +35,8 @@ pub(crate) type FullClient =
 type FullBackend = sc_service::TFullBackend<Block>;
 type FullSelectChain = sc_consensus::LongestChain<FullBackend, Block>;
 
-/// The minimum period of blocks on which justifications will be
-/// imported and generated.
-const GRANDPA_JUSTIFICATION_PERIOD: u32 = 512;
+/// Time after which a main chain reference that does not advance is reported as stalled.
+const MC_REFERENCE_STALL_THRESHOLD: Duration = Duration::from_secs(10 * 60);
 
 /// This function provides dependencies of [partner_chains_node_commands::PartnerChainsSubcommand].
 /// It is not mandatory to have such a dedicated function, [new_partial] could be enough,
@@ -50,7 +50,7 @@ pub fn new_pc_command_deps(
 	let data_sources = task::block_in_place(|| {
 		config
 			.tokio_handle
-			.block_on(crate::data_sources::create_cached_data_sources(None))
+			.block_on(crate::data_sources::create_cached_data_sources(&config.data_path, None))
 	})?;
 	let executor = sc_service::new_wasm_executor(&config.executor);
 	let (client, _, _, task_manager) =
@@ -80,16 +80,17 @@ pub fn new_partial(
 			Option<Telemetry>,
 			DataSources,
 			Option<McFollowerMetrics>,
-			CreateInherentDataConfig,
+			PartnerChainsNodeBuilder<Block, FullClient>,
 		),
 	>,
 	ServiceError,
 > {
 	let mc_follower_metrics = register_metrics_warn_errors(config.prometheus_registry());
 	let data_sources = task::block_in_place(|| {
-		config
-			.tokio_handle
-			.block_on(crate::data_sources::create_cached_data_sources(mc_follower_metrics.clone()))
+		config.tokio_handle.block_on(crate::data_sources::create_cached_data_sources(
+			&config.data_path,
+			mc_follower_metrics.clone(),
+		))
 	})?;
 
 	let telemetry = config
@@ -131,9 +132,10 @@ pub fn new_partial(
 		.build(),
 	);
 
+	let finality_config = FinalityConfig::from_chain_spec(config.chain_spec.as_ref())?;
 	let (grandpa_block_import, grandpa_link) = sc_consensus_grandpa::block_import(
 		client.clone(),
-		GRANDPA_JUSTIFICATION_PERIOD,
+		finality_config.justification_period,
 		&client,
 		select_chain.clone(),
 		telemetry.as_ref().map(|x| x.handle()),
@@ -143,48 +145,37 @@ pub fn new_partial(
 		.runtime_api()
 		.slot_duration(client.info().best_hash)
 		.expect("Aura slot duration must be configured in the runtime");
-	let sc_epoch_duration_millis = client
-		.runtime_api()
-		.get_epoch_duration_millis(client.info().best_hash)
-		.expect("Epoch duration must be configured in the runtime");
-
 	let time_source = Arc::new(SystemTimeSource);
 	let epoch_config = MainchainEpochConfig::read_from_env()
 		.map_err(|err| ServiceError::Application(err.into()))?;
-	let inherent_config = CreateInherentDataConfig::new(
-		epoch_config,
-		slot_duration,
-		sc_epoch_duration_millis,
-		time_source,
-	);
-
-	let import_queue = partner_chains_aura_import_queue::import_queue::<
-		AuraPair,
-		_,
-		_,
-		_,
-		_,
-		_,
-		McHashInherentDigest,
-	>(ImportQueueParams {
-		block_import: grandpa_block_import.clone(),
-		justification_import: Some(Box::new(grandpa_block_import.clone())),
-		client: client.clone(),
-		create_inherent_data_providers: VerifierCIDP::new(
-			inherent_config.clone(),
+	let inherent_config = CreateInherentDataConfig::new(epoch_config, slot_duration, time_source);
+	let mc_reference_monitor =
+		McReferenceMonitor::new(MC_REFERENCE_STALL_THRESHOLD, config.prometheus_registry())
+			.map_err(|err| ServiceError::Application(err.into()))?;
+	let node_builder = partner_chains_node_builder(inherent_config, client.clone(), &data_sources)
+		.with_inherent_diagnostics(InherentDiagnosticsConfig::new(
+			config.base_path.path(),
+			Arc::new(DemoInherentExtrinsicDecoder),
+		))
+		.with_mc_reference_monitor(mc_reference_monitor);
+
+	let import_queue = node_builder.import_queue(
+		ImportQueueParams {
+			block_import: grandpa_block_import.clone(),
+			justification_import: Some(Box::new(grandpa_block_import.clone())),
+			client: client.clone(),
+			create_inherent_data_providers: (),
+			spawner: &task_manager.spawn_essential_handle(),
+			registry: config.prometheus_registry(),
+			check_for_equivocation: Default::default(),
+			telemetry: telemetry.as_ref().map(|x| x.handle()),
+			compatibility_mode: Default::default(),
+		},
+		RuntimeEquivocationReporter::new(
 			client.clone(),
-			data_sources.mc_hash.clone(),
-			data_sources.authority_selection.clone(),
-			data_sources.block_participation.clone(),
-			data_sources.governed_map.clone(),
-			data_sources.bridge.clone(),
+			OffchainTransactionPoolFactory::new(transaction_pool.clone()),
 		),
-		spawner: &task_manager.spawn_essential_handle(),
-		registry: config.prometheus_registry(),
-		check_for_equivocation: Default::default(),
-		telemetry: telemetry.as_ref().map(|x| x.handle()),
-		compatibility_mode: Default::default(),
-	})?;
+	)?;
 
 	Ok(sc_service::PartialComponents {
 		client,
@@ -200,7 +191,7 @@ pub fn new_partial(
 			telemetry,
 			data_sources,
 			mc_follower_metrics,
-			inherent_config,
+			node_builder,
 		),
 	})
 }
@@ -233,7 +224,7 @@ pub async fn new_full_base<Network: sc_network::NetworkBackend<Block, <Block as
 		keystore_container,
 		select_chain,
 		transaction_pool,
-		other: (block_import, grandpa_link, mut telemetry, data_sources, _, inherent_config),
+		other: (block_import, grandpa_link, mut telemetry, data_sources, _, node_builder),
 	} = new_partial(&config)?;
 
 	let metrics = Network::register_notification_metrics(config.prometheus_registry());
@@ -280,6 +271,7 @@ pub async fn new_full_base<Network: sc_network::NetworkBackend<Block, <Block as
 	let backoff_authoring_blocks: Option<()> = None;
 	let name = config.network.node_name.clone();
 	let enable_grandpa = !config.disable_grandpa;
+	let finality_config = FinalityConfig::from_chain_spec(config.chain_spec.as_ref())?;
 	let prometheus_registry = config.prometheus_registry().cloned();
 	let shared_voter_state = SharedVoterState::empty();
 
@@ -291,6 +283,7 @@ pub async fn new_full_base<Network: sc_network::NetworkBackend<Block, <Block as
 		let shared_authority_set = grandpa_link.shared_authority_set().clone();
 		let justification_stream = grandpa_link.justification_stream();
 		let data_sources = data_sources.clone();
+		let node_builder = node_builder.clone();
 
 		move |subscription_executor| {
 			let grandpa = GrandpaDeps {
@@ -308,7 +301,7 @@ pub async fn new_full_base<Network: sc_network::NetworkBackend<Block, <Block as
 				pool: pool.clone(),
 				grandpa,
 				data_sources: data_sources.clone(),
-				time_source: Arc::new(SystemTimeSource),
+				node_builder: node_builder.clone(),
 			};
 			crate::rpc::create_full(deps).map_err(Into::into)
 		}
@@ -337,37 +330,14 @@ pub async fn new_full_base<Network: sc_network::NetworkBackend<Block, <Block as
 			prometheus_registry.as_ref(),
 			telemetry.as_ref().map(|x| x.handle()),
 		);
-		let proposer_factory: PartnerChainsProposerFactory<_, _, McHashInherentDigest> =
-			PartnerChainsProposerFactory::new(basic_authorship_proposer_factory);
-
-		let aura = sc_partner_chains_consensus_aura::start_aura::<
-			AuraPair,
-			_,
-			_,
-			_,
-			_,
-			_,
-			_,
-			_,
-			_,
-			_,
-			_,
-			McHashInherentDigest,
-		>(StartAuraParams {
-			slot_duration: inherent_config.slot_duration,
+
+		let aura = node_builder.start_aura(StartAuraParams {
+			slot_duration: node_builder.inherent_data_config().slot_duration,
 			client: client.clone(),
 			select_chain,
 			block_import,
-			proposer_factory,
-			create_inherent_data_providers: ProposalCIDP::new(
-				inherent_config,
-				client.clone(),
-				data_sources.mc_hash.clone(),
-				data_sources.authority_selection.clone(),
-				data_sources.block_participation,
-				data_sources.governed_map,
-				data_sources.bridge.clone(),
-			),
+			proposer_factory: basic_authorship_proposer_factory,
+			create_inherent_data_providers: (),
 			force_authoring,
 			backoff_authoring_blocks,
 			keystore: keystore_container.keystore(),
@@ -392,11 +362,10 @@ pub async fn new_full_base<Network: sc_network::NetworkBackend<Block, <Block as
 		let keystore = if role.is_authority() { Some(keystore_container.keystore()) } else { None };
 
 		let grandpa_config = sc_consensus_grandpa::Config {
-			// FIXME #1578 make this available through chainspec
-			gossip_duration: Duration::from_millis(333),
-			justification_generation_period: GRANDPA_JUSTIFICATION_PERIOD,
+			gossip_duration: finality_config.gossip_duration(),
+			justification_generation_period: finality_config.justification_period,
 			name: Some(name),
-			observer_enabled: false,
+			observer_enabled: finality_config.observer_enabled,
 			keystore,
 			local_role: role,
 			telemetry: telemetry.as_ref().map(|x| x.handle()),
@@ -92,7 +92,7 @@ pub fn staging_sudo_key() -> AccountId {
 }
 
 pub fn staging_config() -> Result<ChainSpec, envy::Error> {
-	Ok(ChainSpec::builder(runtime_wasm(), None)
+	Ok(ChainSpec::builder(runtime_wasm(), Default::default())
 		.with_name("Staging")
 		.with_id("staging")
 		.with_chain_type(ChainType::Local)
@@ -60,7 +60,7 @@ pub fn chain_spec() -> Result<ChainSpec, envy::Error> {
 	};
 	let genesis_json = serde_json::to_value(runtime_genesis_config)
 		.expect("Genesis config must be serialized correctly");
-	Ok(ChainSpec::builder(runtime_wasm(), None)
+	Ok(ChainSpec::builder(runtime_wasm(), Default::default())
 		.with_name("Partner Chains Template")
 		.with_id("partner_chains_template")
 		.with_chain_type(ChainType::Live)
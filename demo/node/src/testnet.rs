@@ -26,7 +26,7 @@ pub fn authority_keys(
 }
 
 pub fn development_config() -> Result<ChainSpec, envy::Error> {
-	Ok(ChainSpec::builder(runtime_wasm(), None)
+	Ok(ChainSpec::builder(runtime_wasm(), Default::default())
 		.with_name("Development")
 		.with_id("dev")
 		.with_chain_type(ChainType::Development)
@@ -149,7 +149,7 @@ pub fn testnet_sudo_key() -> AccountId {
 }
 
 pub fn local_testnet_config() -> Result<ChainSpec, envy::Error> {
-	Ok(ChainSpec::builder(runtime_wasm(), None)
+	Ok(ChainSpec::builder(runtime_wasm(), Default::default())
 		.with_name("Local Testnet")
 		.with_id("local_testnet")
 		.with_chain_type(ChainType::Local)
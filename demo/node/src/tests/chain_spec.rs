@@ -1,5 +1,5 @@
 use crate::chain_spec::pc_create_chain_spec;
-use partner_chains_cli::{CreateChainSpecConfig, ParsedPermissionedCandidatesKeys};
+use partner_chains_cli::{CreateChainSpecConfig, FinalityConfig, ParsedPermissionedCandidatesKeys};
 use partner_chains_demo_runtime::opaque::SessionKeys;
 use pretty_assertions::assert_eq;
 use sidechain_domain::{AssetName, MainchainAddress, PolicyId, UtxoId};
@@ -30,9 +30,24 @@ fn pc_create_chain_spec_test() {
 		.unwrap(),
 		governed_map_validator_address: Some(MainchainAddress::from_str("addr_govmap").unwrap()),
 		governed_map_asset_policy_id: Some(PolicyId([5u8; 28])),
+		finality: FinalityConfig {
+			gossip_duration_millis: 1000,
+			justification_period: 64,
+			observer_enabled: true,
+		},
 	};
 
 	let json = pc_create_chain_spec(&config);
+
+	assert_eq!(
+		json.get("finality").unwrap(),
+		&serde_json::json!({
+			"gossipDurationMillis": 1000,
+			"justificationPeriod": 64,
+			"observerEnabled": true
+		})
+	);
+
 	let config = json.pointer("/genesis/runtimeGenesis/config").unwrap().clone();
 	let config_obj = config.as_object().unwrap().clone();
 
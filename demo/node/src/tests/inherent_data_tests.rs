@@ -1,4 +1,5 @@
-use crate::inherent_data::{ProposalCIDP, VerifierCIDP};
+use crate::data_sources::DataSources;
+use crate::node_builder::partner_chains_node_builder;
 use crate::tests::mock::{
 	block_participation_data, past_block_author, past_block_slot, test_create_inherent_data_config,
 };
@@ -8,8 +9,10 @@ use authority_selection_inherents::{
 	AuthoritySelectionInputs, mock::MockAuthoritySelectionDataSource,
 };
 use partner_chains_demo_runtime::{AccountId, BlockAuthor};
-use partner_chains_mock_data_sources::StakeDistributionDataSourceMock;
-use partner_chains_mock_data_sources::{GovernedMapDataSourceMock, TokenBridgeDataSourceMock};
+use partner_chains_mock_data_sources::{
+	BlockDataSourceMock, GovernedMapDataSourceMock, SidechainRpcDataSourceMock,
+	StakeDistributionDataSourceMock, TokenBridgeDataSourceMock,
+};
 use sidechain_domain::{
 	DelegatorKey, MainchainBlock, McBlockHash, McBlockNumber, McEpochNumber, McSlotNumber,
 	ScEpochNumber,
@@ -22,6 +25,19 @@ use sp_inherents::{InherentData, InherentDataProvider};
 use sp_timestamp::Timestamp;
 use std::sync::Arc;
 
+fn test_data_sources(mc_hash_data_source: MockMcHashDataSource) -> DataSources {
+	DataSources {
+		mc_hash: Arc::new(mc_hash_data_source),
+		authority_selection: Arc::new(MockAuthoritySelectionDataSource::default()),
+		sidechain_rpc: Arc::new(SidechainRpcDataSourceMock::new(Arc::new(
+			BlockDataSourceMock::new(1000),
+		))),
+		block_participation: Arc::new(StakeDistributionDataSourceMock::new()),
+		governed_map: Arc::new(GovernedMapDataSourceMock::default()),
+		bridge: Arc::new(TokenBridgeDataSourceMock::<AccountId>::new()),
+	}
+}
+
 #[tokio::test]
 async fn block_proposal_cidp_should_be_created_correctly() {
 	let parent_stable_block = MainchainBlock {
@@ -41,18 +57,16 @@ async fn block_proposal_cidp_should_be_created_correctly() {
 	let mc_hash_data_source =
 		MockMcHashDataSource::from(vec![parent_stable_block.clone(), stable_block.clone()]);
 
-	let inherent_data_providers = ProposalCIDP::new(
+	let inherent_data_providers = partner_chains_node_builder(
 		test_create_inherent_data_config(),
-		TestApi::new(ScEpochNumber(2))
-			.with_headers([(mock_header().hash(), mock_header())])
-			.with_pariticipation_data(vec![(past_block_slot(), past_block_author())])
-			.into(),
-		Arc::new(mc_hash_data_source),
-		Arc::new(MockAuthoritySelectionDataSource::default()),
-		Arc::new(StakeDistributionDataSourceMock::new()),
-		Arc::new(GovernedMapDataSourceMock::default()),
-		Arc::new(TokenBridgeDataSourceMock::<AccountId>::new()),
+		Arc::new(
+			TestApi::new(ScEpochNumber(2))
+				.with_headers([(mock_header().hash(), mock_header())])
+				.with_pariticipation_data(vec![(past_block_slot(), past_block_author())]),
+		),
+		&test_data_sources(mc_hash_data_source),
 	)
+	.proposal_cidp()
 	.create_inherent_data_providers(mock_header().hash(), ())
 	.await
 	.unwrap();
@@ -122,17 +136,15 @@ async fn block_verification_cidp_should_be_created_correctly() {
 
 	let create_inherent_data_config = test_create_inherent_data_config();
 
-	let verifier_cidp = VerifierCIDP::new(
+	let verifier_cidp = partner_chains_node_builder(
 		create_inherent_data_config.clone(),
-		TestApi::new(ScEpochNumber(2))
-			.with_pariticipation_data(vec![(past_block_slot(), past_block_author())])
-			.into(),
-		Arc::new(mc_hash_data_source),
-		Arc::new(MockAuthoritySelectionDataSource::default()),
-		Arc::new(StakeDistributionDataSourceMock::new()),
-		Arc::new(GovernedMapDataSourceMock::default()),
-		Arc::new(TokenBridgeDataSourceMock::new()),
-	);
+		Arc::new(
+			TestApi::new(ScEpochNumber(2))
+				.with_pariticipation_data(vec![(past_block_slot(), past_block_author())]),
+		),
+		&test_data_sources(mc_hash_data_source),
+	)
+	.verifier_cidp();
 
 	let inherent_data_providers = verifier_cidp
 		.create_inherent_data_providers(mock_header().hash(), (30.into(), mc_block_hash))
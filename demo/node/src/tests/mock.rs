@@ -1,8 +1,8 @@
 #![allow(clippy::type_complexity)]
 
-use crate::inherent_data::CreateInherentDataConfig;
 use hex_literal::hex;
 use partner_chains_demo_runtime::{BlockAuthor, CrossChainPublic};
+use partner_chains_node_builder::CreateInherentDataConfig;
 use sc_consensus_aura::SlotDuration;
 use sidechain_domain::mainchain_epoch::MainchainEpochConfig;
 use sidechain_domain::*;
@@ -26,6 +26,10 @@ const SLOTS_PER_EPOCH: u64 = 10;
 const SC_EPOCH_DURATION_MILLIS: u64 = SLOT_DURATION * SLOTS_PER_EPOCH;
 const MC_EPOCH_DURATION_MILLIS: u64 = SC_EPOCH_DURATION_MILLIS * 10;
 
+pub fn mock_epoch_schedule() -> ScEpochSchedule {
+	ScEpochSchedule::new(ScEpochDuration::from_millis(SC_EPOCH_DURATION_MILLIS))
+}
+
 pub fn test_epoch_config() -> MainchainEpochConfig {
 	MainchainEpochConfig {
 		first_epoch_timestamp_millis: Timestamp::from_unix_millis(0),
@@ -56,7 +60,6 @@ pub fn test_create_inherent_data_config() -> CreateInherentDataConfig {
 	CreateInherentDataConfig {
 		mc_epoch_config: test_epoch_config(),
 		slot_duration: SlotDuration::from_millis(SLOT_DURATION),
-		sc_epoch_duration_millis: SC_EPOCH_DURATION_MILLIS,
 		time_source: Arc::new(time_source::MockedTimeSource { current_time_millis: 30000 }),
 	}
 }
@@ -1,5 +1,7 @@
-use super::mock::mock_genesis_utxo;
-use authority_selection_inherents::AuthoritySelectionInputs;
+use super::mock::{mock_epoch_schedule, mock_genesis_utxo};
+use authority_selection_inherents::{
+	AuthoritySelectionInputs, PermissionedCandidateDataError, RegistrationDataError, StakeError,
+};
 use partner_chains_demo_runtime::opaque::SessionKeys;
 use partner_chains_demo_runtime::{BlockAuthor, CrossChainPublic};
 use sidechain_domain::*;
@@ -14,7 +16,7 @@ use sp_runtime::Digest;
 use sp_runtime::key_types::{AURA, GRANDPA};
 use sp_runtime::traits::{Block as BlockT, Header as HeaderT, NumberFor, Zero};
 use sp_session_validator_management::CommitteeMember;
-use sp_sidechain::GetGenesisUtxo;
+use sp_sidechain::{GetEpochScheduleApi, GetGenesisUtxo, GetMcReferencePolicyApi};
 use std::collections::HashMap;
 
 type Hash = <Block as BlockT>::Hash;
@@ -70,6 +72,14 @@ sp_api::mock_impl_runtime_apis! {
 		fn genesis_utxo() -> UtxoId { mock_genesis_utxo() }
 	}
 
+	impl GetEpochScheduleApi<Block> for TestApi {
+		fn get_epoch_schedule() -> ScEpochSchedule { mock_epoch_schedule() }
+	}
+
+	impl GetMcReferencePolicyApi<Block> for TestApi {
+		fn get_mc_reference_policy() -> McReferencePolicy { McReferencePolicy::default() }
+	}
+
 	impl sp_session_validator_management::SessionValidatorManagementApi<Block, CrossChainPublic, SessionKeys, ScEpochNumber> for TestApi {
 		fn get_current_committee() -> (ScEpochNumber, Vec<CommitteeMember<CrossChainPublic, SessionKeys>>) {
 			unimplemented!()
@@ -100,6 +110,27 @@ sp_api::mock_impl_runtime_apis! {
 				permissioned_candidates_policy_id: PolicyId::default(),
 			}
 		}
+		fn get_token_staking_config() -> Option<sidechain_domain::TokenStakingConfig> {
+			None
+		}
+	}
+
+	impl sp_sidechain::GetSidechainStatus<Block> for TestApi {
+		fn get_sidechain_status() -> sp_sidechain::SidechainStatus {
+			unimplemented!()
+		}
+	}
+
+	impl authority_selection_inherents::CandidateValidationApi<Block> for TestApi {
+		fn validate_registered_candidate_data(_mainchain_pub_key: &StakePoolPublicKey, _registration_data: &RegistrationData) -> Option<RegistrationDataError> {
+			unimplemented!()
+		}
+		fn validate_stake(_stake: Option<StakeDelegation>) -> Option<StakeError> {
+			unimplemented!()
+		}
+		fn validate_permissioned_candidate_data(_candidate: PermissionedCandidateData) -> Option<PermissionedCandidateDataError> {
+			unimplemented!()
+		}
 	}
 
 	impl sp_block_participation::BlockParticipationApi<Block, BlockAuthor, Slot> for TestApi {
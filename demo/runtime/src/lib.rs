@@ -12,8 +12,8 @@ extern crate alloc;
 use alloc::collections::BTreeMap;
 use alloc::string::String;
 use authority_selection_inherents::{
-	PermissionedCandidateDataError, RegistrationDataError, StakeError, select_authorities,
-	validate_permissioned_candidate_data,
+	DeprioritizeCandidates, PermissionedCandidateDataError, RegistrationDataError, StakeError,
+	select_authorities_deprioritizing, validate_permissioned_candidate_data,
 };
 use frame_support::dynamic_params::{dynamic_pallet_params, dynamic_params};
 use frame_support::genesis_builder_helper::{build_state, get_preset};
@@ -39,7 +39,7 @@ use serde::{Deserialize, Serialize};
 use sidechain_domain::byte_string::{BoundedString, ByteString, SizedByteString};
 use sidechain_domain::{
 	CrossChainPublicKey, DelegatorKey, MainchainKeyHash, PermissionedCandidateData,
-	RegistrationData, ScEpochNumber, StakeDelegation, StakePoolPublicKey, UtxoId,
+	RegistrationData, ScEpochNumber, ScEpochSchedule, StakeDelegation, StakePoolPublicKey, UtxoId,
 };
 use sp_api::impl_runtime_apis;
 use sp_block_participation::AsCardanoSPO;
@@ -151,7 +151,7 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	//   `spec_version`, and `authoring_version` are the same between Wasm and native.
 	// This value is set to 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
 	//   the compatible custom types.
-	spec_version: 181,
+	spec_version: 182,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
 	transaction_version: 1,
@@ -357,11 +357,12 @@ impl pallet_session_validator_management::Config for Runtime {
 		input: AuthoritySelectionInputs,
 		sidechain_epoch: ScEpochNumber,
 	) -> Option<BoundedVec<CommitteeMemberOf<Self>, Self::MaxValidators>> {
-		select_authorities::<sidechain_domain::cross_chain_app::Public, SessionKeys, MaxValidators>(
-			Sidechain::genesis_utxo(),
-			input,
-			sidechain_epoch,
-		)
+		select_authorities_deprioritizing::<
+			sidechain_domain::cross_chain_app::Public,
+			SessionKeys,
+			MaxValidators,
+			OfflineCandidates,
+		>(Sidechain::genesis_utxo(), input, sidechain_epoch)
 	}
 
 	fn current_epoch_number() -> ScEpochNumber {
@@ -382,6 +383,7 @@ impl pallet_sidechain::Config for Runtime {
 		*pallet_aura::CurrentSlot::<Runtime>::get() * SLOT_DURATION
 	}
 	type OnNewEpoch = TestHelperPallet;
+	type GovernanceOrigin = EnsureRoot<Self::AccountId>;
 }
 
 pub type BeneficiaryId = sidechain_domain::byte_string::SizedByteString<32>;
@@ -399,6 +401,7 @@ pub type BeneficiaryId = sidechain_domain::byte_string::SizedByteString<32>;
 	Hash,
 	PartialOrd,
 	Ord,
+	Serialize,
 )]
 pub enum BlockAuthor {
 	Incentivized(CrossChainPublic, StakePoolPublicKey),
@@ -419,6 +422,7 @@ impl From<CommitteeMember<CrossChainPublic, SessionKeys>> for BlockAuthor {
 			CommitteeMember::Registered { id, stake_pool_pub_key, .. } => {
 				BlockAuthor::Incentivized(id, stake_pool_pub_key)
 			},
+			CommitteeMember::TokenStaked { id, .. } => BlockAuthor::ProBono(id),
 		}
 	}
 }
@@ -505,6 +509,127 @@ impl pallet_block_production_log::Config for Runtime {
 	type GetAuthor = FromFindAuthorIndex<Runtime, Aura, u32>;
 }
 
+impl<LocalCall> frame_system::offchain::CreateTransactionBase<LocalCall> for Runtime
+where
+	RuntimeCall: From<LocalCall>,
+{
+	type Extrinsic = UncheckedExtrinsic;
+	type RuntimeCall = RuntimeCall;
+}
+
+impl<LocalCall> frame_system::offchain::CreateBare<LocalCall> for Runtime
+where
+	RuntimeCall: From<LocalCall>,
+{
+	fn create_bare(call: Self::RuntimeCall) -> Self::Extrinsic {
+		UncheckedExtrinsic::new_bare(call)
+	}
+}
+
+impl pallet_aura_equivocation::Config for Runtime {
+	type AuthorityId = AuraId;
+	type Offender = CommitteeMemberOf<Runtime>;
+	type FindOffender = pallet_aura_equivocation::FromCurrentCommittee<Runtime>;
+	type SessionAuthorities = pallet_aura_equivocation::AuraSessionAuthorities<Runtime>;
+	type CurrentEpochSlots = pallet_aura_equivocation::SidechainEpochSlots<Runtime>;
+	type OnEquivocation = ();
+	type MaxRecordedOffences = ConstU32<1024>;
+	type WeightInfo = pallet_aura_equivocation::weights::SubstrateWeight<Runtime>;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = PalletAuraEquivocationBenchmarkHelper;
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+pub struct PalletAuraEquivocationBenchmarkHelper;
+
+#[cfg(feature = "runtime-benchmarks")]
+impl pallet_aura_equivocation::benchmarking::BenchmarkHelper<Runtime>
+	for PalletAuraEquivocationBenchmarkHelper
+{
+	fn register_offender(authority_id: &AuraId, _slot: Slot) {
+		let keys = SessionKeys {
+			aura: authority_id.clone(),
+			grandpa: sp_core::ed25519::Public::from_raw([0; 32]).into(),
+		};
+		let member = CommitteeMember::permissioned(
+			CrossChainPublic::from(sp_core::ecdsa::Public::from_raw([0; 33])),
+			keys,
+		);
+		pallet_session_validator_management::CurrentCommittee::<Runtime>::mutate(|info| {
+			assert!(info.committee.try_push(member).is_ok(), "committee is not full")
+		});
+		pallet_aura::Authorities::<Runtime>::put(BoundedVec::truncate_from(vec![
+			authority_id.clone(),
+		]));
+	}
+}
+
+parameter_types! {
+	pub const MaxMissedSlotsPerBlock: u32 = 1000;
+	pub const MissedSlotsEpochsToKeep: u32 = 3;
+	pub const MaxPrunedEntriesPerBlock: u32 = 100;
+	pub const OfflineThreshold: Perbill = Perbill::from_percent(50);
+}
+
+impl pallet_missed_slots::Config for Runtime {
+	type BlockProducerId = BlockAuthor;
+	type SlotAssignment = pallet_missed_slots::RoundRobinFromCurrentCommittee<Runtime>;
+	type MaxMissedSlotsPerBlock = MaxMissedSlotsPerBlock;
+	type EpochsToKeep = MissedSlotsEpochsToKeep;
+	type MaxPrunedEntriesPerBlock = MaxPrunedEntriesPerBlock;
+	type OfflineThreshold = OfflineThreshold;
+	type WeightInfo = pallet_missed_slots::weights::SubstrateWeight<Runtime>;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = PalletMissedSlotsBenchmarkHelper;
+
+	fn current_slot() -> Slot {
+		pallet_aura::CurrentSlot::<Runtime>::get()
+	}
+
+	fn current_epoch_number() -> ScEpochNumber {
+		Sidechain::current_epoch_number()
+	}
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+pub struct PalletMissedSlotsBenchmarkHelper;
+
+#[cfg(feature = "runtime-benchmarks")]
+impl pallet_missed_slots::benchmarking::BenchmarkHelper<Runtime>
+	for PalletMissedSlotsBenchmarkHelper
+{
+	fn set_up_slot_assignment() {
+		pallet_session_validator_management::CurrentCommittee::<Runtime>::mutate(|info| {
+			if info.committee.is_empty() {
+				let keys = SessionKeys::from((
+					sp_core::sr25519::Public::from_raw([0; 32]),
+					sp_core::ed25519::Public::from_raw([0; 32]),
+				));
+				let member = CommitteeMember::permissioned(
+					CrossChainPublic::from(sp_core::ecdsa::Public::from_raw([0; 33])),
+					keys,
+				);
+				assert!(info.committee.try_push(member).is_ok(), "committee is not full")
+			}
+		});
+	}
+
+	fn block_producer(i: u32) -> BlockAuthor {
+		let mut key = [0; 33];
+		key[..4].copy_from_slice(&i.to_le_bytes());
+		BlockAuthor::ProBono(CrossChainPublic::from(sp_core::ecdsa::Public::from_raw(key)))
+	}
+}
+
+/// Deprioritizes candidates that are chronically offline according to the missed slots pallet
+pub struct OfflineCandidates;
+
+impl DeprioritizeCandidates<CrossChainPublic, SessionKeys> for OfflineCandidates {
+	fn is_deprioritized(candidate: &CommitteeMember<CrossChainPublic, SessionKeys>) -> bool {
+		MissedSlots::is_chronically_offline(&BlockAuthor::from(candidate.clone()))
+	}
+}
+
 parameter_types! {
 	/// Amount of tokens to burn when making irreversible, forever association
 	pub const AddressAssociationBurnAmount: Balance = 1_000_000;
@@ -715,6 +840,8 @@ construct_runtime!(
 		BlockProducerMetadata: pallet_block_producer_metadata,
 		BlockProductionLog: pallet_block_production_log,
 		BlockParticipation: pallet_block_participation,
+		AuraEquivocation: pallet_aura_equivocation,
+		MissedSlots: pallet_missed_slots,
 		// We exclude pallet's extrinsics to make them unavailable to users to submit.
 		// This is to ensure that registrations on Cardano coming in throught the
 		// `SessionCommitteeManagement` pallet are the only source of truth about keys
@@ -755,6 +882,7 @@ pub type SignedPayload = generic::SignedPayload<RuntimeCall, SignedExtra>;
 pub type Migrations = (
 	pallet_session_validator_management::migrations::v1::LegacyToV1Migration<Runtime>,
 	pallet_sidechain::migrations::v1::LegacyToV1Migration<Runtime, SLOT_DURATION>,
+	pallet_sidechain::migrations::v2::V1ToV2Migration<Runtime>,
 	// More migrations can be added here
 );
 /// Executive: handles dispatch to the various modules.
@@ -781,6 +909,8 @@ mod benches {
 		[pallet_block_participation, BlockParticipation]
 		[pallet_governed_map, GovernedMap]
 		[pallet_partner_chains_bridge, Bridge]
+		[pallet_aura_equivocation, AuraEquivocation]
+		[pallet_missed_slots, MissedSlots]
 		[pallet_session_validator_management, SessionCommitteeManagement]
 	);
 }
@@ -875,6 +1005,23 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl sp_missed_slots::MissedSlotsApi<Block, BlockAuthor> for Runtime {
+		fn get_slot_stats(epoch: ScEpochNumber) -> Vec<(BlockAuthor, sp_missed_slots::SlotStats)> {
+			MissedSlots::slot_stats(epoch)
+		}
+		fn get_offline_block_producers() -> Vec<BlockAuthor> {
+			MissedSlots::offline_block_producers()
+		}
+	}
+
+	impl sp_aura_equivocation::AuraEquivocationApi<Block, AuraId> for Runtime {
+		fn submit_report_equivocation_unsigned_extrinsic(
+			equivocation_proof: sp_aura_equivocation::EquivocationProof<Header, AuraId>,
+		) -> Option<()> {
+			AuraEquivocation::submit_unsigned_equivocation_report(equivocation_proof)
+		}
+	}
+
 	impl sp_session::SessionKeys<Block> for Runtime {
 		fn generate_session_keys(seed: Option<Vec<u8>>) -> Vec<u8> {
 			// despite being named "generate" this function also adds generated keys to local keystore
@@ -1056,6 +1203,18 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl sp_sidechain::GetEpochScheduleApi<Block> for Runtime {
+		fn get_epoch_schedule() -> ScEpochSchedule {
+			Sidechain::epoch_schedule()
+		}
+	}
+
+	impl sp_sidechain::GetMcReferencePolicyApi<Block> for Runtime {
+		fn get_mc_reference_policy() -> sidechain_domain::McReferencePolicy {
+			Sidechain::mc_reference_policy()
+		}
+	}
+
 	impl sp_block_producer_metadata::BlockProducerMetadataApi<Block, BlockProducerMetadataType> for Runtime
 	{
 		fn get_metadata_for(
@@ -1072,7 +1231,7 @@ impl_runtime_apis! {
 		}
 	}
 
-	#[api_version(3)]
+	#[api_version(4)]
 	impl sp_session_validator_management::SessionValidatorManagementApi<
 		Block,
 		CrossChainPublic,
@@ -1094,6 +1253,9 @@ impl_runtime_apis! {
 		fn get_main_chain_scripts() -> sp_session_validator_management::MainChainScripts {
 			SessionCommitteeManagement::get_main_chain_scripts()
 		}
+		fn get_token_staking_config() -> Option<sidechain_domain::TokenStakingConfig> {
+			SessionCommitteeManagement::get_token_staking_config()
+		}
 	}
 
 	impl authority_selection_inherents::CandidateValidationApi<Block> for Runtime {
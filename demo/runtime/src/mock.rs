@@ -183,6 +183,8 @@ pub fn create_inherent_data_struct(
 			permissioned_candidates: vec![],
 			registered_candidates: candidates,
 			epoch_nonce: EpochNonce(DUMMY_EPOCH_NONCE.to_vec()),
+			token_staked_candidates: vec![],
+			token_staking: None,
 		}),
 	}
 }
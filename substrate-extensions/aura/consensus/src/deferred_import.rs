@@ -0,0 +1,120 @@
+//! Deferred verification of blocks that can not be verified at the time they are received.
+//!
+//! Blocks can be received before they can be verified by the node, either because they were produced in
+//! a slot that has not started yet according to the local clock, or because the main chain block referenced
+//! by them was not yet observed by the local main chain data source. Instead of rejecting such blocks, and
+//! forcing them to be synced from peers again, the verifier can retry their verification for a bounded time.
+//!
+//! Inherent data providers factories signal that inherent data of a verified block is not available yet by
+//! returning [InherentDataNotAvailable] error.
+
+use sp_consensus_slots::Slot;
+use std::time::Duration;
+use tokio::time::Instant;
+
+pub use sp_partner_chains_consensus_aura::InherentDataNotAvailable;
+
+/// Configuration of deferred verification of blocks.
+///
+/// Note that the import queue verifies blocks one by one and the verifier waits between attempts,
+/// so verification of all blocks received after a deferred block is delayed by up to
+/// [Self::max_delay]. [Self::default] values are suited for nodes following the tip of the chain,
+/// nodes syncing from peers should use [Self::disabled].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DeferredImportConfig {
+	/// Maximum number of slots by which a block's slot can be ahead of the current slot, for its verification
+	/// to be deferred. Blocks from one slot ahead are always accepted to allow for small clock drift.
+	pub max_future_slots: u64,
+	/// Maximum time for which verification of a single block can be deferred
+	pub max_delay: Duration,
+	/// Time between consecutive verification attempts of a deferred block
+	pub retry_interval: Duration,
+}
+
+impl DeferredImportConfig {
+	/// Configuration that disables deferring, rejecting all blocks that can not be verified immediately
+	pub fn disabled() -> Self {
+		Self { max_future_slots: 0, max_delay: Duration::ZERO, retry_interval: Duration::ZERO }
+	}
+}
+
+impl Default for DeferredImportConfig {
+	fn default() -> Self {
+		Self {
+			max_future_slots: 2,
+			max_delay: Duration::from_secs(30),
+			retry_interval: Duration::from_millis(500),
+		}
+	}
+}
+
+/// Tracks the time for which verification of a single block has been deferred
+pub(crate) struct Deferral {
+	config: DeferredImportConfig,
+	started: Instant,
+}
+
+impl Deferral {
+	pub(crate) fn new(config: DeferredImportConfig) -> Self {
+		Self { config, started: Instant::now() }
+	}
+
+	/// Returns true if verification of a block from `block_slot` can be deferred at `slot_now`
+	pub(crate) fn accepts_future_slot(&self, block_slot: Slot, slot_now: Slot) -> bool {
+		*block_slot <= (*slot_now).saturating_add(1).saturating_add(self.config.max_future_slots)
+	}
+
+	/// Waits until the next verification attempt. Returns false without waiting if the next attempt
+	/// would exceed the maximum deferral time.
+	pub(crate) async fn wait(&self) -> bool {
+		if self.started.elapsed() + self.config.retry_interval >= self.config.max_delay {
+			return false;
+		}
+		tokio::time::sleep(self.config.retry_interval).await;
+		true
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn config(max_future_slots: u64, max_delay_millis: u64) -> DeferredImportConfig {
+		DeferredImportConfig {
+			max_future_slots,
+			max_delay: Duration::from_millis(max_delay_millis),
+			retry_interval: Duration::from_millis(10),
+		}
+	}
+
+	#[test]
+	fn accepts_blocks_up_to_max_future_slots_after_drift_allowance() {
+		let deferral = Deferral::new(config(2, 100));
+		assert!(deferral.accepts_future_slot(12.into(), 10.into()));
+		assert!(deferral.accepts_future_slot(13.into(), 10.into()));
+		assert!(!deferral.accepts_future_slot(14.into(), 10.into()));
+	}
+
+	#[test]
+	fn disabled_config_accepts_only_drift_allowance() {
+		let deferral = Deferral::new(DeferredImportConfig::disabled());
+		assert!(deferral.accepts_future_slot(11.into(), 10.into()));
+		assert!(!deferral.accepts_future_slot(12.into(), 10.into()));
+	}
+
+	#[tokio::test(start_paused = true)]
+	async fn waits_until_max_delay_is_exceeded() {
+		let deferral = Deferral::new(config(0, 35));
+		let mut attempts = 0;
+		while deferral.wait().await {
+			attempts += 1;
+		}
+		assert_eq!(attempts, 3);
+	}
+
+	#[tokio::test]
+	async fn disabled_config_does_not_wait() {
+		let deferral = Deferral::new(DeferredImportConfig::disabled());
+		assert!(!deferral.wait().await);
+	}
+}
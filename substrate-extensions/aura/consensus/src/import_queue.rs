@@ -19,8 +19,11 @@
 
 //! Module implementing the logic for verifying and importing AuRa blocks.
 
-use crate::{AuthorityId, LOG_TARGET, authorities};
-use log::{debug, info, trace};
+use crate::{
+	AuthorityId, LOG_TARGET, authorities,
+	deferred_import::{Deferral, DeferredImportConfig, InherentDataNotAvailable},
+};
+use log::{debug, info, trace, warn};
 use parity_scale_codec::Codec;
 use sc_client_api::{BlockOf, UsageProvider, backend::AuxStore};
 use sc_consensus::{
@@ -33,14 +36,18 @@ use sc_consensus_aura::{
 };
 use sc_consensus_slots::{CheckedHeader, check_equivocation};
 use sc_telemetry::{CONSENSUS_DEBUG, CONSENSUS_TRACE, TelemetryHandle, telemetry};
+use sc_transaction_pool_api::OffchainTransactionPoolFactory;
 use sp_api::{ApiExt, ProvideRuntimeApi};
+use sp_aura_equivocation::{AuraEquivocationApi, EquivocationProof};
 use sp_block_builder::BlockBuilder as BlockBuilderApi;
 use sp_blockchain::{HeaderBackend, HeaderMetadata};
 use sp_consensus::Error as ConsensusError;
 use sp_consensus_aura::AuraApi;
 use sp_consensus_slots::Slot;
 use sp_core::crypto::Pair;
-use sp_inherents::{CreateInherentDataProviders, InherentData, InherentDataProvider};
+use sp_inherents::{
+	CreateInherentDataProviders, InherentData, InherentDataProvider, InherentIdentifier,
+};
 use sp_partner_chains_consensus_aura::{CurrentSlotProvider, InherentDigest};
 use sp_runtime::{
 	DigestItem,
@@ -48,25 +55,107 @@ use sp_runtime::{
 };
 use std::{fmt::Debug, marker::PhantomData, sync::Arc};
 
+/// Reports equivocations of block producers detected during block verification
+pub trait EquivocationReporter<B: BlockT, Id>: Send + Sync {
+	/// Reports the equivocation proven by `equivocation_proof`
+	fn report_equivocation(
+		&self,
+		equivocation_proof: EquivocationProof<B::Header, Id>,
+	) -> Result<(), String>;
+}
+
+/// Reporter that does not report equivocations. They are only logged by the verifier.
+impl<B: BlockT, Id> EquivocationReporter<B, Id> for () {
+	fn report_equivocation(
+		&self,
+		_equivocation_proof: EquivocationProof<B::Header, Id>,
+	) -> Result<(), String> {
+		Ok(())
+	}
+}
+
+/// [EquivocationReporter] submitting equivocation reports as unsigned extrinsics, using
+/// [AuraEquivocationApi] of the runtime at the best block.
+pub struct RuntimeEquivocationReporter<B: BlockT, C> {
+	client: Arc<C>,
+	offchain_tx_pool_factory: OffchainTransactionPoolFactory<B>,
+}
+
+impl<B: BlockT, C> RuntimeEquivocationReporter<B, C> {
+	/// Creates a reporter submitting extrinsics to the transaction pool of `offchain_tx_pool_factory`
+	pub fn new(
+		client: Arc<C>,
+		offchain_tx_pool_factory: OffchainTransactionPoolFactory<B>,
+	) -> Self {
+		Self { client, offchain_tx_pool_factory }
+	}
+}
+
+impl<B, C, Id> EquivocationReporter<B, Id> for RuntimeEquivocationReporter<B, C>
+where
+	B: BlockT,
+	C: ProvideRuntimeApi<B> + HeaderBackend<B> + Send + Sync,
+	C::Api: AuraEquivocationApi<B, Id>,
+	Id: Codec,
+{
+	fn report_equivocation(
+		&self,
+		equivocation_proof: EquivocationProof<B::Header, Id>,
+	) -> Result<(), String> {
+		let best_hash = self.client.info().best_hash;
+		let mut runtime_api = self.client.runtime_api();
+		runtime_api
+			.register_extension(self.offchain_tx_pool_factory.offchain_transaction_pool(best_hash));
+		runtime_api
+			.submit_report_equivocation_unsigned_extrinsic(best_hash, equivocation_proof)
+			.map_err(|e| e.to_string())?
+			.ok_or_else(|| "Runtime failed to submit the equivocation report".to_string())
+	}
+}
+
+/// Inherent check of a block that was rejected by the runtime during verification
+pub struct InherentCheckFailure<'a, B: BlockT> {
+	/// Hash of the parent of the rejected block
+	pub parent_hash: B::Hash,
+	/// The block whose inherents were rejected
+	pub block: &'a B,
+	/// Inherent data created locally for verification of the block
+	pub inherent_data: &'a InherentData,
+	/// Identifiers and SCALE encoded errors of the inherents rejected by the runtime
+	pub errors: Vec<(InherentIdentifier, Vec<u8>)>,
+}
+
+/// Handles blocks rejected by the inherent checks of the runtime, eg. to diagnose the cause of the rejection.
+///
+/// Handling does not affect the verification result, the block is rejected regardless.
+#[async_trait::async_trait]
+pub trait InherentCheckFailureHandler<B: BlockT>: Send + Sync {
+	/// Handles the rejection of inherents of `failure.block`
+	async fn handle_inherent_check_failure(&self, failure: InherentCheckFailure<'_, B>);
+}
+
 /// check a header has been signed by the right key. If the slot is too far in the future, an error
 /// will be returned. If it's successful, returns the pre-header and the digest item
 /// containing the seal.
 ///
 /// This digest item will always return `Some` when used with `as_aura_seal`.
 #[allow(clippy::type_complexity)]
-fn check_header<C, B: BlockT, P: Pair>(
+fn check_header<C, B: BlockT, P: Pair, R: EquivocationReporter<B, AuthorityId<P>>>(
 	client: &C,
 	slot_now: Slot,
 	header: B::Header,
 	hash: B::Hash,
 	authorities: &[AuthorityId<P>],
 	check_for_equivocation: CheckForEquivocation,
+	equivocation_reporter: &R,
 ) -> Result<CheckedHeader<B::Header, (Slot, DigestItem)>, Error<B>>
 where
 	P::Public: Codec,
 	P::Signature: Codec,
 	C: AuxStore,
 {
+	// Sealed header is used for equivocation checks, so that the proof can be verified by the runtime
+	let sealed_header = header.clone();
 	let check_result = sc_consensus_aura::standalone::check_header_slot_and_seal::<B, P>(
 		slot_now,
 		header,
@@ -80,7 +169,7 @@ where
 			let should_equiv_check = matches!(check_for_equivocation, CheckForEquivocation::Yes);
 			if let (true, Some(expected)) = (should_equiv_check, expected_author) {
 				if let Some(equivocation_proof) =
-					check_equivocation(client, slot_now, slot, &header, expected)
+					check_equivocation(client, slot_now, slot, &sealed_header, expected)
 						.map_err(Error::Client)?
 				{
 					info!(
@@ -90,6 +179,10 @@ where
 						equivocation_proof.first_header.hash(),
 						equivocation_proof.second_header.hash(),
 					);
+					if let Err(err) = equivocation_reporter.report_equivocation(equivocation_proof)
+					{
+						warn!(target: LOG_TARGET, "Error reporting equivocation: {}", err);
+					}
 				}
 			}
 
@@ -107,12 +200,19 @@ where
 }
 
 /// A verifier for Aura blocks, with added ID phantom type.
-pub struct AuraVerifier<C, P: Pair, CIDP, B: BlockT, ID> {
+///
+/// Equivocations detected by the verifier are reported using `R`. Verification of blocks that can not be
+/// verified yet is deferred according to [DeferredImportConfig], by default it is disabled. Blocks rejected by
+/// the inherent checks are passed to the [InherentCheckFailureHandler], if one is set.
+pub struct AuraVerifier<C, P: Pair, CIDP, B: BlockT, ID, R = ()> {
 	client: Arc<C>,
 	create_inherent_data_providers: CIDP,
 	check_for_equivocation: CheckForEquivocation,
 	telemetry: Option<TelemetryHandle>,
 	compatibility_mode: CompatibilityMode<NumberFor<B>>,
+	equivocation_reporter: R,
+	deferred_import: DeferredImportConfig,
+	inherent_check_failure_handler: Option<Arc<dyn InherentCheckFailureHandler<B>>>,
 	_phantom: PhantomData<(fn() -> P, ID)>,
 }
 
@@ -123,6 +223,26 @@ impl<C, P: Pair, CIDP, B: BlockT, ID> AuraVerifier<C, P, CIDP, B, ID> {
 		check_for_equivocation: CheckForEquivocation,
 		telemetry: Option<TelemetryHandle>,
 		compatibility_mode: CompatibilityMode<NumberFor<B>>,
+	) -> Self {
+		Self::new_with_equivocation_reporter(
+			client,
+			create_inherent_data_providers,
+			check_for_equivocation,
+			telemetry,
+			compatibility_mode,
+			(),
+		)
+	}
+}
+
+impl<C, P: Pair, CIDP, B: BlockT, ID, R> AuraVerifier<C, P, CIDP, B, ID, R> {
+	pub(crate) fn new_with_equivocation_reporter(
+		client: Arc<C>,
+		create_inherent_data_providers: CIDP,
+		check_for_equivocation: CheckForEquivocation,
+		telemetry: Option<TelemetryHandle>,
+		compatibility_mode: CompatibilityMode<NumberFor<B>>,
+		equivocation_reporter: R,
 	) -> Self {
 		Self {
 			client: client.clone(),
@@ -130,13 +250,27 @@ impl<C, P: Pair, CIDP, B: BlockT, ID> AuraVerifier<C, P, CIDP, B, ID> {
 			check_for_equivocation,
 			telemetry,
 			compatibility_mode,
+			equivocation_reporter,
+			deferred_import: DeferredImportConfig::disabled(),
+			inherent_check_failure_handler: None,
 			_phantom: PhantomData,
 		}
 	}
+
+	pub(crate) fn with_deferred_import(self, deferred_import: DeferredImportConfig) -> Self {
+		Self { deferred_import, ..self }
+	}
+
+	pub(crate) fn with_inherent_check_failure_handler(
+		self,
+		inherent_check_failure_handler: Option<Arc<dyn InherentCheckFailureHandler<B>>>,
+	) -> Self {
+		Self { inherent_check_failure_handler, ..self }
+	}
 }
 
 #[async_trait::async_trait]
-impl<B, C, P, CIDP, ID> Verifier<B> for AuraVerifier<C, P, CIDP, B, ID>
+impl<B, C, P, CIDP, ID, R> Verifier<B> for AuraVerifier<C, P, CIDP, B, ID, R>
 where
 	B: BlockT,
 	C: HeaderBackend<B>
@@ -154,6 +288,7 @@ where
 		+ Send
 		+ Sync,
 	ID: InherentDigest + Send + Sync + 'static,
+	R: EquivocationReporter<B, AuthorityId<P>> + 'static,
 {
 	async fn verify(
 		&self,
@@ -181,26 +316,31 @@ where
 		)
 		.map_err(|e| format!("Could not fetch authorities at {:?}: {}", parent_hash, e))?;
 
-		let slot_now = self.create_inherent_data_providers.slot();
-
-		// we add one to allow for some small drift.
-		// FIXME #1019 in the future, alter this queue to allow deferring of
-		// headers
-		let checked_header = check_header::<C, B, P>(
-			&self.client,
-			slot_now + 1,
-			block.header.clone(),
-			hash,
-			&authorities[..],
-			self.check_for_equivocation,
-		)
-		.map_err(|e| e.to_string())?;
-		let inherent_digest = <ID as InherentDigest>::value_from_digest(
-			block.header.digest().logs(),
-		)
-		.map_err(|e| {
-			format!("Failed to retrieve inherent digest from header at {:?}: {}", parent_hash, e)
-		})?;
+		let deferral = Deferral::new(self.deferred_import);
+		let checked_header = loop {
+			let slot_now = self.create_inherent_data_providers.slot();
+
+			// we add one to allow for some small drift.
+			let checked_header = check_header::<C, B, P, R>(
+				&self.client,
+				slot_now + 1,
+				block.header.clone(),
+				hash,
+				&authorities[..],
+				self.check_for_equivocation,
+				&self.equivocation_reporter,
+			)
+			.map_err(|e| e.to_string())?;
+			match checked_header {
+				CheckedHeader::Deferred(_, slot)
+					if deferral.accepts_future_slot(slot, slot_now) && deferral.wait().await =>
+				{
+					debug!(target: LOG_TARGET, "Retrying verification of {:?} from future slot {}", hash, slot);
+				},
+				checked_header => break checked_header,
+			}
+		};
+		let inherent_digest = inherent_digest_value::<B, ID>(&block.header)?;
 		match checked_header {
 			CheckedHeader::Checked(pre_header, (slot, seal)) => {
 				// if the body is passed through, we need to use the runtime
@@ -209,12 +349,15 @@ where
 				if let Some(inner_body) = block.body.take() {
 					let new_block = B::new(pre_header.clone(), inner_body);
 
-					let inherent_data_providers = create_inherent_data_provider(
-						&self.create_inherent_data_providers,
-						parent_hash,
-						(slot, inherent_digest),
-					)
-					.await?;
+					let inherent_data_providers =
+						create_verification_inherent_data_provider::<CIDP, B, ID>(
+							&self.create_inherent_data_providers,
+							&block.header,
+							slot,
+							inherent_digest,
+							&deferral,
+						)
+						.await?;
 
 					// skip the inherents verification if the runtime API is old or not expected to
 					// exist.
@@ -226,7 +369,17 @@ where
 					{
 						let inherent_data =
 							create_inherent_data::<B>(&inherent_data_providers).await?;
-						sp_block_builder::check_inherents_with_data(
+						if let Some(handler) = self.inherent_check_failure_handler.as_ref() {
+							check_inherents_with_failure_handler(
+								self.client.as_ref(),
+								handler.as_ref(),
+								parent_hash,
+								&new_block,
+								&inherent_data_providers,
+								inherent_data,
+							)
+							.await?;
+						} else if let Err(e) = sp_block_builder::check_inherents_with_data(
 							self.client.clone(),
 							parent_hash,
 							new_block.clone(),
@@ -234,7 +387,9 @@ where
 							inherent_data,
 						)
 						.await
-						.map_err(|e| format!("Error checking block inherents {:?}", e))?;
+						{
+							return Err(format!("Error checking block inherents {:?}", e));
+						}
 					}
 
 					let (_, inner_body) = new_block.deconstruct();
@@ -274,6 +429,170 @@ where
 
 /// Start an import queue for the Aura consensus algorithm.
 pub fn import_queue<P, Block, I, C, S, CIDP, ID>(
+	params: ImportQueueParams<Block, I, C, S, CIDP>,
+) -> Result<DefaultImportQueue<Block>, sp_consensus::Error>
+where
+	Block: BlockT,
+	C::Api: BlockBuilderApi<Block> + AuraApi<Block, AuthorityId<P>> + ApiExt<Block>,
+	C: 'static
+		+ ProvideRuntimeApi<Block>
+		+ BlockOf
+		+ Send
+		+ Sync
+		+ AuxStore
+		+ UsageProvider<Block>
+		+ HeaderBackend<Block>
+		+ HeaderMetadata<Block, Error = sp_blockchain::Error>,
+	I: BlockImport<Block, Error = ConsensusError> + Send + Sync + 'static,
+	P: Pair + 'static,
+	P::Public: Codec + Debug,
+	P::Signature: Codec,
+	S: sp_core::traits::SpawnEssentialNamed,
+	CIDP: CurrentSlotProvider
+		+ CreateInherentDataProviders<Block, (Slot, <ID as InherentDigest>::Value)>
+		+ Sync
+		+ Send
+		+ 'static,
+	ID: InherentDigest + Send + Sync + 'static,
+{
+	import_queue_with_equivocation_reporting::<P, Block, I, C, S, CIDP, ID, ()>(params, ())
+}
+
+/// Start an import queue for the Aura consensus algorithm, reporting detected equivocations
+/// of block producers using `equivocation_reporter`.
+///
+/// See [import_queue] for the description of the other parameters.
+pub fn import_queue_with_equivocation_reporting<P, Block, I, C, S, CIDP, ID, R>(
+	params: ImportQueueParams<Block, I, C, S, CIDP>,
+	equivocation_reporter: R,
+) -> Result<DefaultImportQueue<Block>, sp_consensus::Error>
+where
+	Block: BlockT,
+	C::Api: BlockBuilderApi<Block> + AuraApi<Block, AuthorityId<P>> + ApiExt<Block>,
+	C: 'static
+		+ ProvideRuntimeApi<Block>
+		+ BlockOf
+		+ Send
+		+ Sync
+		+ AuxStore
+		+ UsageProvider<Block>
+		+ HeaderBackend<Block>
+		+ HeaderMetadata<Block, Error = sp_blockchain::Error>,
+	I: BlockImport<Block, Error = ConsensusError> + Send + Sync + 'static,
+	P: Pair + 'static,
+	P::Public: Codec + Debug,
+	P::Signature: Codec,
+	S: sp_core::traits::SpawnEssentialNamed,
+	CIDP: CurrentSlotProvider
+		+ CreateInherentDataProviders<Block, (Slot, <ID as InherentDigest>::Value)>
+		+ Sync
+		+ Send
+		+ 'static,
+	ID: InherentDigest + Send + Sync + 'static,
+	R: EquivocationReporter<Block, AuthorityId<P>> + 'static,
+{
+	import_queue_with_deferred_import::<P, Block, I, C, S, CIDP, ID, R>(
+		params,
+		equivocation_reporter,
+		DeferredImportConfig::disabled(),
+	)
+}
+
+/// Start an import queue for the Aura consensus algorithm, reporting detected equivocations
+/// of block producers using `equivocation_reporter` and deferring verification of blocks
+/// according to `deferred_import`.
+///
+/// Blocks from future slots within [DeferredImportConfig::max_future_slots] and blocks for which
+/// `create_inherent_data_providers` returns [InherentDataNotAvailable] error are verified again
+/// until [DeferredImportConfig::max_delay] passes, instead of being rejected immediately.
+///
+/// Retries happen inside the verifier, so the import queue does not verify any other block while
+/// a block is deferred. Keep [DeferredImportConfig::max_delay] short, or use [import_queue] to
+/// reject such blocks immediately.
+///
+/// See [import_queue] for the description of the other parameters.
+pub fn import_queue_with_deferred_import<P, Block, I, C, S, CIDP, ID, R>(
+	params: ImportQueueParams<Block, I, C, S, CIDP>,
+	equivocation_reporter: R,
+	deferred_import: DeferredImportConfig,
+) -> Result<DefaultImportQueue<Block>, sp_consensus::Error>
+where
+	Block: BlockT,
+	C::Api: BlockBuilderApi<Block> + AuraApi<Block, AuthorityId<P>> + ApiExt<Block>,
+	C: 'static
+		+ ProvideRuntimeApi<Block>
+		+ BlockOf
+		+ Send
+		+ Sync
+		+ AuxStore
+		+ UsageProvider<Block>
+		+ HeaderBackend<Block>
+		+ HeaderMetadata<Block, Error = sp_blockchain::Error>,
+	I: BlockImport<Block, Error = ConsensusError> + Send + Sync + 'static,
+	P: Pair + 'static,
+	P::Public: Codec + Debug,
+	P::Signature: Codec,
+	S: sp_core::traits::SpawnEssentialNamed,
+	CIDP: CurrentSlotProvider
+		+ CreateInherentDataProviders<Block, (Slot, <ID as InherentDigest>::Value)>
+		+ Sync
+		+ Send
+		+ 'static,
+	ID: InherentDigest + Send + Sync + 'static,
+	R: EquivocationReporter<Block, AuthorityId<P>> + 'static,
+{
+	build_import_queue::<P, Block, I, C, S, CIDP, ID, R>(
+		params,
+		equivocation_reporter,
+		deferred_import,
+		None,
+	)
+}
+
+/// Start an import queue for the Aura consensus algorithm, like [import_queue_with_deferred_import],
+/// additionally passing blocks rejected by the inherent checks of the runtime to `inherent_check_failure_handler`.
+///
+/// See [import_queue] for the description of the other parameters.
+pub fn import_queue_with_inherent_check_failure_handler<P, Block, I, C, S, CIDP, ID, R>(
+	params: ImportQueueParams<Block, I, C, S, CIDP>,
+	equivocation_reporter: R,
+	deferred_import: DeferredImportConfig,
+	inherent_check_failure_handler: Arc<dyn InherentCheckFailureHandler<Block>>,
+) -> Result<DefaultImportQueue<Block>, sp_consensus::Error>
+where
+	Block: BlockT,
+	C::Api: BlockBuilderApi<Block> + AuraApi<Block, AuthorityId<P>> + ApiExt<Block>,
+	C: 'static
+		+ ProvideRuntimeApi<Block>
+		+ BlockOf
+		+ Send
+		+ Sync
+		+ AuxStore
+		+ UsageProvider<Block>
+		+ HeaderBackend<Block>
+		+ HeaderMetadata<Block, Error = sp_blockchain::Error>,
+	I: BlockImport<Block, Error = ConsensusError> + Send + Sync + 'static,
+	P: Pair + 'static,
+	P::Public: Codec + Debug,
+	P::Signature: Codec,
+	S: sp_core::traits::SpawnEssentialNamed,
+	CIDP: CurrentSlotProvider
+		+ CreateInherentDataProviders<Block, (Slot, <ID as InherentDigest>::Value)>
+		+ Sync
+		+ Send
+		+ 'static,
+	ID: InherentDigest + Send + Sync + 'static,
+	R: EquivocationReporter<Block, AuthorityId<P>> + 'static,
+{
+	build_import_queue::<P, Block, I, C, S, CIDP, ID, R>(
+		params,
+		equivocation_reporter,
+		deferred_import,
+		Some(inherent_check_failure_handler),
+	)
+}
+
+fn build_import_queue<P, Block, I, C, S, CIDP, ID, R>(
 	ImportQueueParams {
 		block_import,
 		justification_import,
@@ -285,6 +604,9 @@ pub fn import_queue<P, Block, I, C, S, CIDP, ID>(
 		telemetry,
 		compatibility_mode,
 	}: ImportQueueParams<Block, I, C, S, CIDP>,
+	equivocation_reporter: R,
+	deferred_import: DeferredImportConfig,
+	inherent_check_failure_handler: Option<Arc<dyn InherentCheckFailureHandler<Block>>>,
 ) -> Result<DefaultImportQueue<Block>, sp_consensus::Error>
 where
 	Block: BlockT,
@@ -309,29 +631,120 @@ where
 		+ Send
 		+ 'static,
 	ID: InherentDigest + Send + Sync + 'static,
+	R: EquivocationReporter<Block, AuthorityId<P>> + 'static,
 {
-	let verifier = AuraVerifier::<_, P, _, _, ID>::new(
+	let verifier = AuraVerifier::<_, P, _, _, ID, R>::new_with_equivocation_reporter(
 		client,
 		create_inherent_data_providers,
 		check_for_equivocation,
 		telemetry,
 		compatibility_mode,
-	);
+		equivocation_reporter,
+	)
+	.with_deferred_import(deferred_import)
+	.with_inherent_check_failure_handler(inherent_check_failure_handler);
 
 	Ok(BasicQueue::new(verifier, Box::new(block_import), justification_import, spawner, registry))
 }
 
-async fn create_inherent_data_provider<CIDP, B: BlockT, ExtraArg>(
+fn inherent_digest_value<B: BlockT, ID: InherentDigest>(
+	header: &B::Header,
+) -> Result<ID::Value, String> {
+	ID::value_from_digest(header.digest().logs()).map_err(|e| {
+		format!(
+			"Failed to retrieve inherent digest from header at {:?}: {}",
+			header.parent_hash(),
+			e
+		)
+	})
+}
+
+/// Creates inherent data providers for verification of the block with `header`, retrying
+/// while inherent data is not available yet, until `deferral` time is exceeded
+async fn create_verification_inherent_data_provider<CIDP, B: BlockT, ID: InherentDigest>(
 	cidp: &CIDP,
-	hash: B::Hash,
-	extra_arg: ExtraArg,
+	header: &B::Header,
+	slot: Slot,
+	inherent_digest: ID::Value,
+	deferral: &Deferral,
 ) -> Result<CIDP::InherentDataProviders, String>
 where
-	CIDP: CreateInherentDataProviders<B, ExtraArg>,
+	CIDP: CreateInherentDataProviders<B, (Slot, ID::Value)>,
+{
+	let parent_hash = *header.parent_hash();
+	let mut inherent_digest = Some(inherent_digest);
+	loop {
+		let inherent_digest = match inherent_digest.take() {
+			Some(inherent_digest) => inherent_digest,
+			None => inherent_digest_value::<B, ID>(header)?,
+		};
+		match cidp.create_inherent_data_providers(parent_hash, (slot, inherent_digest)).await {
+			Err(err) if err.is::<InherentDataNotAvailable>() && deferral.wait().await => {
+				debug!(
+					target: LOG_TARGET,
+					"Retrying verification of block at slot {} with parent {:?}: {}", slot, parent_hash, err
+				);
+			},
+			result => {
+				return result
+					.map_err(|e| Error::<B>::Client(sp_blockchain::Error::Application(e)).into());
+			},
+		}
+	}
+}
+
+/// Checks inherents of `block` like [sp_block_builder::check_inherents_with_data], additionally passing the
+/// block to `handler` together with the errors of all inherents rejected by the runtime.
+///
+/// The runtime's inherent check is run only once, its result is used both to verify the block and by `handler`.
+async fn check_inherents_with_failure_handler<B, C>(
+	client: &C,
+	handler: &dyn InherentCheckFailureHandler<B>,
+	parent_hash: B::Hash,
+	block: &B,
+	inherent_data_providers: &impl InherentDataProvider,
+	inherent_data: InherentData,
+) -> Result<(), String>
+where
+	B: BlockT,
+	C: ProvideRuntimeApi<B>,
+	C::Api: BlockBuilderApi<B>,
 {
-	cidp.create_inherent_data_providers(hash, extra_arg)
-		.await
-		.map_err(|e| Error::<B>::Client(sp_blockchain::Error::Application(e)).into())
+	let result = client
+		.runtime_api()
+		.check_inherents(parent_hash, block.clone(), inherent_data.clone())
+		.map_err(|e| format!("Error checking block inherents {:?}", e))?;
+	if result.ok() {
+		return Ok(());
+	}
+	let errors: Vec<(InherentIdentifier, Vec<u8>)> = result.into_errors().collect();
+	let mut rejection = None;
+	for (identifier, error) in errors.iter() {
+		match inherent_data_providers.try_handle_error(identifier, error).await {
+			Some(Ok(())) => {},
+			Some(Err(e)) => {
+				rejection = Some(format!("Error checking block inherents {:?}", e));
+				break;
+			},
+			None => {
+				rejection = Some(format!(
+					"Error checking block inherents: inherent {:?} is unknown to the inherent data provider",
+					identifier
+				));
+				break;
+			},
+		}
+	}
+	let Some(rejection) = rejection else { return Ok(()) };
+	handler
+		.handle_inherent_check_failure(InherentCheckFailure {
+			parent_hash,
+			block,
+			inherent_data: &inherent_data,
+			errors,
+		})
+		.await;
+	Err(rejection)
 }
 
 async fn create_inherent_data<B: BlockT>(
@@ -17,6 +17,7 @@
 // Additional modifications by Input Output Global, Inc.
 // Copyright (C) 2024, Input Output Global, Inc.
 
+pub mod deferred_import;
 pub mod import_queue;
 
 use futures::prelude::*;
@@ -9,3 +9,27 @@ pub trait CurrentSlotProvider {
 	/// Returns the current slot, according to wall-time and slot duration configuration.
 	fn slot(&self) -> Slot;
 }
+
+/// Error signalling that inherent data providers for a verified block can not be created yet,
+/// but may be created later, eg. when the main chain block referenced by the block is observed
+/// by the node's data sources.
+///
+/// Inherent data providers factories used for block verification should wrap such errors in this type,
+/// so that verification of the block can be deferred instead of the block being rejected.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct InherentDataNotAvailable(pub Box<dyn std::error::Error + Send + Sync>);
+
+#[cfg(feature = "std")]
+impl std::fmt::Display for InherentDataNotAvailable {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "Inherent data not available yet: {}", self.0)
+	}
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InherentDataNotAvailable {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		Some(self.0.as_ref())
+	}
+}
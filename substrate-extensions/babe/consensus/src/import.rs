@@ -0,0 +1,180 @@
+//! Block import and inherent data providers passing inherent digest of imported blocks to inherent checks
+
+use log::debug;
+use parking_lot::Mutex;
+use sc_consensus::block_import::{BlockCheckParams, BlockImport, BlockImportParams, ImportResult};
+use sc_consensus_babe::find_pre_digest;
+use sp_consensus::Error as ConsensusError;
+use sp_consensus_slots::Slot;
+use sp_inherents::CreateInherentDataProviders;
+use sp_partner_chains_consensus_aura::InherentDigest;
+use sp_runtime::traits::{Block as BlockT, Header};
+use std::sync::Arc;
+
+const LOG_TARGET: &str = "partner-chains-babe";
+
+/// Slot and inherent digest value of the block being imported
+struct ImportedBlock<B: BlockT, Value> {
+	parent_hash: B::Hash,
+	slot: Slot,
+	inherent_digest: Value,
+}
+
+/// Context shared between [InherentDigestBlockImport] and [InherentDigestCIDP].
+///
+/// BABE block import creates inherent data providers for inherent checks of imported blocks with only
+/// the parent hash available. This context carries the slot and the inherent digest value of the block
+/// being imported from [InherentDigestBlockImport], which should wrap BABE block import, to [InherentDigestCIDP],
+/// which should be passed to BABE block import as its inherent data providers factory.
+pub struct InherentDigestContext<B: BlockT, ID: InherentDigest> {
+	imported_block: Arc<Mutex<Option<ImportedBlock<B, ID::Value>>>>,
+	import_lock: Arc<futures::lock::Mutex<()>>,
+}
+
+impl<B: BlockT, ID: InherentDigest> Clone for InherentDigestContext<B, ID> {
+	fn clone(&self) -> Self {
+		Self { imported_block: self.imported_block.clone(), import_lock: self.import_lock.clone() }
+	}
+}
+
+impl<B: BlockT, ID: InherentDigest> Default for InherentDigestContext<B, ID> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<B: BlockT, ID: InherentDigest> InherentDigestContext<B, ID> {
+	/// Creates a new context
+	pub fn new() -> Self {
+		Self {
+			imported_block: Arc::new(Mutex::new(None)),
+			import_lock: Arc::new(futures::lock::Mutex::new(())),
+		}
+	}
+
+	/// Wraps `inner` inherent data providers factory, that requires the slot and inherent digest value of
+	/// the verified block, into one that can be used by BABE block import.
+	pub fn create_inherent_data_providers<CIDP>(
+		&self,
+		inner: CIDP,
+	) -> InherentDigestCIDP<B, CIDP, ID> {
+		InherentDigestCIDP { inner, context: self.clone() }
+	}
+
+	/// Wraps `inner` BABE block import, making the slot and inherent digest value of imported blocks
+	/// available to inherent data providers created using [Self::create_inherent_data_providers].
+	pub fn block_import<I>(&self, inner: I) -> InherentDigestBlockImport<B, I, ID> {
+		InherentDigestBlockImport { inner, context: self.clone() }
+	}
+
+	fn take_imported_block(&self, parent_hash: B::Hash) -> Result<(Slot, ID::Value), String> {
+		let imported_block = self.imported_block.lock().take().ok_or_else(|| {
+			format!(
+				"No block with parent {parent_hash:?} is being imported by Partner Chains block import"
+			)
+		})?;
+		if imported_block.parent_hash != parent_hash {
+			return Err(format!(
+				"Inherent data providers requested for parent {parent_hash:?}, but imported block's parent is {:?}",
+				imported_block.parent_hash
+			));
+		}
+		Ok((imported_block.slot, imported_block.inherent_digest))
+	}
+}
+
+/// Inherent data providers factory for BABE block import, that passes the slot and inherent digest value
+/// of the imported block to the wrapped factory. See [InherentDigestContext].
+pub struct InherentDigestCIDP<B: BlockT, CIDP, ID: InherentDigest> {
+	inner: CIDP,
+	context: InherentDigestContext<B, ID>,
+}
+
+impl<B: BlockT, CIDP: Clone, ID: InherentDigest> Clone for InherentDigestCIDP<B, CIDP, ID> {
+	fn clone(&self) -> Self {
+		Self { inner: self.inner.clone(), context: self.context.clone() }
+	}
+}
+
+#[async_trait::async_trait]
+impl<B, CIDP, ID> CreateInherentDataProviders<B, ()> for InherentDigestCIDP<B, CIDP, ID>
+where
+	B: BlockT,
+	CIDP: CreateInherentDataProviders<B, (Slot, ID::Value)>,
+	ID: InherentDigest + Send + Sync + 'static,
+{
+	type InherentDataProviders = CIDP::InherentDataProviders;
+
+	async fn create_inherent_data_providers(
+		&self,
+		parent: B::Hash,
+		_extra_args: (),
+	) -> Result<Self::InherentDataProviders, Box<dyn std::error::Error + Send + Sync>> {
+		let (slot, inherent_digest) = self.context.take_imported_block(parent)?;
+		self.inner.create_inherent_data_providers(parent, (slot, inherent_digest)).await
+	}
+}
+
+/// Block import wrapping BABE block import, that verifies the inherent digest of imported blocks and makes
+/// it available to [InherentDigestCIDP]. See [InherentDigestContext].
+pub struct InherentDigestBlockImport<B: BlockT, I, ID: InherentDigest> {
+	inner: I,
+	context: InherentDigestContext<B, ID>,
+}
+
+impl<B: BlockT, I: Clone, ID: InherentDigest> Clone for InherentDigestBlockImport<B, I, ID> {
+	fn clone(&self) -> Self {
+		Self { inner: self.inner.clone(), context: self.context.clone() }
+	}
+}
+
+#[async_trait::async_trait]
+impl<B, I, ID> BlockImport<B> for InherentDigestBlockImport<B, I, ID>
+where
+	B: BlockT,
+	I: BlockImport<B, Error = ConsensusError> + Send + Sync,
+	ID: InherentDigest + Send + Sync + 'static,
+{
+	type Error = ConsensusError;
+
+	async fn check_block(&self, block: BlockCheckParams<B>) -> Result<ImportResult, Self::Error> {
+		self.inner.check_block(block).await
+	}
+
+	async fn import_block(&self, block: BlockImportParams<B>) -> Result<ImportResult, Self::Error> {
+		// Imports are serialized, so that inherent data providers are created for the right block
+		let _import_guard = self.context.import_lock.lock().await;
+
+		if !block.with_state() && !block.state_action.skip_execution_checks() {
+			let imported_block = imported_block::<B, ID>(&block)?;
+			debug!(
+				target: LOG_TARGET,
+				"Importing block {:?} at slot {} with inherent digest",
+				block.post_hash(),
+				imported_block.slot
+			);
+			*self.context.imported_block.lock() = Some(imported_block);
+		}
+
+		let result = self.inner.import_block(block).await;
+		// Inherent checks may be skipped by the wrapped block import, in which case the context is not consumed
+		self.context.imported_block.lock().take();
+		result
+	}
+}
+
+fn imported_block<B: BlockT, ID: InherentDigest>(
+	block: &BlockImportParams<B>,
+) -> Result<ImportedBlock<B, ID::Value>, ConsensusError> {
+	let slot = find_pre_digest::<B>(&block.header)
+		.map_err(|e| ConsensusError::ClientImport(e.to_string()))?
+		.slot();
+	let inherent_digest = ID::value_from_digest(block.header.digest().logs()).map_err(|e| {
+		ConsensusError::ClientImport(format!(
+			"Failed to retrieve inherent digest from header at {:?}: {}",
+			block.header.parent_hash(),
+			e
+		))
+	})?;
+	Ok(ImportedBlock { parent_hash: *block.header.parent_hash(), slot, inherent_digest })
+}
@@ -0,0 +1,113 @@
+//! Partner Chains extension of the Substrate BABE consensus.
+//!
+//! ## Purpose of this crate
+//!
+//! Partner Chains put parts of blocks' inherent data, eg. the main chain reference block hash, into their headers'
+//! digests, as defined by [InherentDigest]. `sc-partner-chains-consensus-aura` supports this for Aura by verifying
+//! blocks with inherent data providers created for the slot and inherent digest of the verified block.
+//! This crate provides the same support for chains using BABE, with its VRF-based slot assignment:
+//! - block proposals are wrapped by [PartnerChainsProposerFactory], adding inherent digests to proposed blocks
+//! - [InherentDigestBlockImport] wraps BABE block import and rejects blocks without valid inherent digest
+//! - [InherentDigestCIDP] passes the slot and the inherent digest value of the imported block to the inherent
+//!   data providers used by BABE block import to check the block's inherents
+//!
+//! ## Usage - PC Builder
+//!
+//! ### Node
+//!
+//! The inherent data providers factory used for verification should take the slot and the inherent digest
+//! value of the verified block as its extra arguments, exactly as in the case of Aura. Its inherent data providers
+//! should include a slot provider, as required by BABE, eg. `sp_consensus_babe::inherents::InherentDataProvider`.
+//!
+//! Block import should be created using [InherentDigestContext], and blocks should be authored using the same
+//! block import and a proposer factory wrapped by [PartnerChainsProposerFactory], eg.:
+//!
+//! ```rust
+//! use sc_consensus::BlockImport;
+//! use sc_partner_chains_consensus_babe::*;
+//! use sp_consensus::{Environment, Error as ConsensusError};
+//! use sp_consensus_slots::Slot;
+//! use sp_inherents::CreateInherentDataProviders;
+//! use sp_runtime::traits::Block as BlockT;
+//!
+//! /// `create_babe_block_import` should create BABE block import using the given inherent data providers
+//! /// factory, eg. with `sc_consensus_babe::block_import`
+//! fn partner_chains_babe_block_import<Block, ID, VerifierCIDP, BabeBlockImport>(
+//!     verifier_cidp: VerifierCIDP,
+//!     create_babe_block_import: impl FnOnce(InherentDigestCIDP<Block, VerifierCIDP, ID>) -> BabeBlockImport,
+//! ) -> InherentDigestBlockImport<Block, BabeBlockImport, ID>
+//! where
+//!     Block: BlockT,
+//!     ID: InherentDigest + Send + Sync + 'static,
+//!     VerifierCIDP: CreateInherentDataProviders<Block, (Slot, ID::Value)>,
+//!     BabeBlockImport: BlockImport<Block, Error = ConsensusError> + Send + Sync,
+//! {
+//!     let inherent_digest_context = InherentDigestContext::<Block, ID>::new();
+//!     let babe_block_import =
+//!         create_babe_block_import(inherent_digest_context.create_inherent_data_providers(verifier_cidp));
+//!     inherent_digest_context.block_import(babe_block_import)
+//! }
+//!
+//! /// The returned proposer factory should be passed as `env` to `sc_consensus_babe::start_babe`
+//! fn partner_chains_proposer_factory<Block, ID, ProposerFactory>(
+//!     proposer_factory: ProposerFactory,
+//! ) -> PartnerChainsProposerFactory<Block, ProposerFactory, ID>
+//! where
+//!     Block: BlockT,
+//!     ID: InherentDigest + Send + Sync + 'static,
+//!     ProposerFactory: Environment<Block>,
+//! {
+//!     PartnerChainsProposerFactory::new(proposer_factory)
+//! }
+//! ```
+//!
+//! The block import returned by `partner_chains_babe_block_import` should be passed both to
+//! `sc_consensus_babe::import_queue`, with the `BabeLink` returned by `sc_consensus_babe::block_import`,
+//! and to `sc_consensus_babe::start_babe`, which should use the inherent data providers factory for proposals.
+//!
+//! ### Runtime
+//!
+//! The runtime should include `pallet_babe` instead of `pallet_aura` and implement `sp_consensus_babe::BabeApi`.
+//! Partner Chain toolkit's committee selection sets BABE authorities through `pallet_session`, eg.:
+//!
+//! ```rust,ignore
+//! parameter_types! {
+//!     pub const EpochDuration: u64 = EPOCH_DURATION_IN_SLOTS;
+//!     pub const ExpectedBlockTime: u64 = MILLISECS_PER_BLOCK;
+//!     pub const MaxAuthorities: u32 = 1024;
+//! }
+//!
+//! impl pallet_babe::Config for Runtime {
+//!     type EpochDuration = EpochDuration;
+//!     type ExpectedBlockTime = ExpectedBlockTime;
+//!     type EpochChangeTrigger = pallet_babe::ExternalTrigger;
+//!     type DisabledValidators = Session;
+//!     type WeightInfo = ();
+//!     type MaxAuthorities = MaxAuthorities;
+//!     type MaxNominators = ConstU32<0>;
+//!     type KeyOwnerProof = sp_core::Void;
+//!     type EquivocationReportSystem = ();
+//! }
+//!
+//! impl_opaque_keys! {
+//!     pub struct SessionKeys {
+//!         pub babe: Babe,
+//!         pub grandpa: Grandpa,
+//!     }
+//! }
+//! ```
+//!
+//! `pallet_babe::ExternalTrigger` makes BABE epochs follow sessions, which are rotated by
+//! `pallet_session_validator_management` at Partner Chain epoch boundaries. Slot-based features, eg.
+//! `pallet_block_production_log`, should read the current slot from `pallet_babe::CurrentSlot`.
+
+mod import;
+
+#[cfg(test)]
+mod tests;
+
+pub use import::{InherentDigestBlockImport, InherentDigestCIDP, InherentDigestContext};
+pub use sp_partner_chains_consensus_aura::{
+	InherentDigest,
+	block_proposal::{PartnerChainsProposer, PartnerChainsProposerFactory},
+};
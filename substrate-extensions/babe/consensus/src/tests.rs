@@ -0,0 +1,172 @@
+use crate::*;
+use parking_lot::Mutex;
+use sc_consensus::block_import::{BlockCheckParams, BlockImport, BlockImportParams, ImportResult};
+use sp_consensus::{BlockOrigin, Error as ConsensusError};
+use sp_consensus_babe::digests::{CompatibleDigestItem, PreDigest, SecondaryPlainPreDigest};
+use sp_consensus_slots::Slot;
+use sp_core::H256;
+use sp_inherents::{CreateInherentDataProviders, InherentData, InherentIdentifier};
+use sp_runtime::generic::Header;
+use sp_runtime::traits::BlakeTwo256;
+use sp_runtime::{Digest, DigestItem, OpaqueExtrinsic};
+use std::error::Error;
+use std::sync::Arc;
+
+type Block = sp_runtime::generic::Block<Header<u32, BlakeTwo256>, OpaqueExtrinsic>;
+
+const PARENT_HASH: H256 = H256([1; 32]);
+
+const TEST_INHERENT_IDENTIFIER: InherentIdentifier = *b"testdgst";
+
+struct TestInherentDigest;
+
+impl InherentDigest for TestInherentDigest {
+	type Value = u64;
+
+	fn from_inherent_data(
+		inherent_data: &InherentData,
+	) -> Result<Vec<DigestItem>, Box<dyn Error + Send + Sync>> {
+		let value = inherent_data
+			.get_data::<u64>(&TEST_INHERENT_IDENTIFIER)?
+			.ok_or("Test inherent data not found")?;
+		Ok(vec![DigestItem::Other(value.to_le_bytes().to_vec())])
+	}
+
+	fn value_from_digest(
+		digests: &[DigestItem],
+	) -> Result<Self::Value, Box<dyn Error + Send + Sync>> {
+		digests
+			.iter()
+			.find_map(|digest| match digest {
+				DigestItem::Other(bytes) => {
+					bytes.as_slice().try_into().ok().map(u64::from_le_bytes)
+				},
+				_ => None,
+			})
+			.ok_or("Test inherent digest not found".into())
+	}
+}
+
+/// Records extra arguments it is called with
+#[derive(Clone, Default)]
+struct TestCIDP {
+	calls: Arc<Mutex<Vec<(H256, Slot, u64)>>>,
+}
+
+#[async_trait::async_trait]
+impl CreateInherentDataProviders<Block, (Slot, u64)> for TestCIDP {
+	type InherentDataProviders = ();
+
+	async fn create_inherent_data_providers(
+		&self,
+		parent: H256,
+		(slot, inherent_digest): (Slot, u64),
+	) -> Result<Self::InherentDataProviders, Box<dyn Error + Send + Sync>> {
+		self.calls.lock().push((parent, slot, inherent_digest));
+		Ok(())
+	}
+}
+
+/// Mimics BABE block import, creating inherent data providers for the imported block's parent
+struct TestBabeBlockImport {
+	cidp: InherentDigestCIDP<Block, TestCIDP, TestInherentDigest>,
+	parent_hash_override: Option<H256>,
+}
+
+#[async_trait::async_trait]
+impl BlockImport<Block> for TestBabeBlockImport {
+	type Error = ConsensusError;
+
+	async fn check_block(
+		&self,
+		_block: BlockCheckParams<Block>,
+	) -> Result<ImportResult, Self::Error> {
+		Ok(ImportResult::imported(false))
+	}
+
+	async fn import_block(
+		&self,
+		block: BlockImportParams<Block>,
+	) -> Result<ImportResult, Self::Error> {
+		let parent = self.parent_hash_override.unwrap_or(block.header.parent_hash);
+		self.cidp
+			.create_inherent_data_providers(parent, ())
+			.await
+			.map_err(|e| ConsensusError::ClientImport(e.to_string()))?;
+		Ok(ImportResult::imported(false))
+	}
+}
+
+fn block_import_params(slot: u64, inherent_digest: Option<u64>) -> BlockImportParams<Block> {
+	let pre_digest = PreDigest::SecondaryPlain(SecondaryPlainPreDigest {
+		authority_index: 0,
+		slot: slot.into(),
+	});
+	let mut logs = vec![DigestItem::babe_pre_digest(pre_digest)];
+	if let Some(value) = inherent_digest {
+		logs.push(DigestItem::Other(value.to_le_bytes().to_vec()));
+	}
+	let header = Header {
+		parent_hash: PARENT_HASH,
+		number: 1,
+		state_root: Default::default(),
+		extrinsics_root: Default::default(),
+		digest: Digest { logs },
+	};
+	BlockImportParams::new(BlockOrigin::NetworkBroadcast, header)
+}
+
+fn setup(
+	parent_hash_override: Option<H256>,
+) -> (TestCIDP, InherentDigestBlockImport<Block, TestBabeBlockImport, TestInherentDigest>) {
+	let context = InherentDigestContext::<Block, TestInherentDigest>::new();
+	let cidp = TestCIDP::default();
+	let babe_block_import = TestBabeBlockImport {
+		cidp: context.create_inherent_data_providers(cidp.clone()),
+		parent_hash_override,
+	};
+	(cidp, context.block_import(babe_block_import))
+}
+
+#[tokio::test]
+async fn passes_slot_and_inherent_digest_of_imported_block_to_inherent_data_providers() {
+	let (cidp, block_import) = setup(None);
+
+	let result = block_import.import_block(block_import_params(42, Some(1337))).await;
+
+	assert!(result.is_ok());
+	assert_eq!(*cidp.calls.lock(), vec![(PARENT_HASH, Slot::from(42), 1337)]);
+}
+
+#[tokio::test]
+async fn rejects_block_without_inherent_digest() {
+	let (cidp, block_import) = setup(None);
+
+	let result = block_import.import_block(block_import_params(42, None)).await;
+
+	assert!(matches!(result, Err(ConsensusError::ClientImport(_))));
+	assert!(cidp.calls.lock().is_empty());
+}
+
+#[tokio::test]
+async fn fails_when_inherent_data_providers_are_requested_for_other_parent() {
+	let (cidp, block_import) = setup(Some(H256([2; 32])));
+
+	let result = block_import.import_block(block_import_params(42, Some(1337))).await;
+
+	assert!(result.is_err());
+	assert!(cidp.calls.lock().is_empty());
+}
+
+#[tokio::test]
+async fn imported_block_is_not_reused_for_next_import() {
+	let (cidp, block_import) = setup(None);
+
+	block_import.import_block(block_import_params(42, Some(1337))).await.unwrap();
+	block_import.import_block(block_import_params(43, Some(1338))).await.unwrap();
+
+	assert_eq!(
+		*cidp.calls.lock(),
+		vec![(PARENT_HASH, Slot::from(42), 1337), (PARENT_HASH, Slot::from(43), 1338)]
+	);
+}
@@ -0,0 +1,123 @@
+#![cfg(feature = "runtime-benchmarks")]
+//! Benchmarking setup for pallet-aura-equivocation
+//!
+//! ## Running benchmarks
+//!
+//! Benchmarks generate the offender's Aura key in the keystore of the benchmarking environment and
+//! report an equivocation of two headers sealed with it in the last slot of the current epoch. To benchmark
+//! this pallet, the PC Builder should define a `BenchmarkHelper` type implementing the [BenchmarkHelper] trait,
+//! which makes the generated key known to the pallet's [Config::FindOffender] and makes it the author of the
+//! slot according to [Config::SessionAuthorities]. For a runtime using [crate::FromCurrentCommittee] and
+//! [crate::AuraSessionAuthorities], it should add a committee member with the given Aura key to the current
+//! committee and set it as the only Aura authority:
+//!
+//! ```rust,ignore
+//! pub struct MyBenchmarkHelper;
+//!
+//! impl pallet_aura_equivocation::benchmarking::BenchmarkHelper<Runtime> for MyBenchmarkHelper {
+//!     fn register_offender(authority_id: &AuraId, _slot: Slot) {
+//!         let keys = SessionKeys { aura: authority_id.clone(), grandpa: GrandpaId::from(ed25519::Public::from_raw([0; 32])) };
+//!         let member = CommitteeMember::permissioned(CrossChainPublic::from(ecdsa::Public::from_raw([0; 33])), keys);
+//!         pallet_session_validator_management::CurrentCommittee::<Runtime>::mutate(|info| {
+//!             info.committee.try_push(member).expect("committee is not full")
+//!         });
+//!         pallet_aura::Authorities::<Runtime>::put(BoundedVec::truncate_from(vec![authority_id.clone()]));
+//!     }
+//! }
+//! ```
+//!
+//! The worst case, in which the offence storage is full, is only measured if the current epoch ends after
+//! slot [Config::MaxRecordedOffences].
+//!
+//! Assuming that the runtime crate uses the feature flag `runtime-benchmarks` to enable benchmarking features,
+//! this helper should be then added to the pallet's config:
+//! ```rust,ignore
+//! #[cfg(feature = "runtime-benchmarks")]
+//! type BenchmarkHelper = MyBenchmarkHelper;
+//! ```
+//! and the pallet's own `runtime-benchmarks` feature should be enabled and the pallet should be included in the
+//! runtime's benchmarks list:
+//! ```rust, ignore
+//! define_benchmarks!(
+//!     ...,
+//!        [pallet_aura_equivocation, AuraEquivocation]
+//! )
+//! ```
+//!
+//! Afterwards, the pallet can be benchmarked using Polkadot SDK's [omini-bencher](https://github.com/paritytech/polkadot-sdk/tree/master/substrate/utils/frame/omni-bencher).
+
+use super::*;
+use frame_benchmarking::v2::*;
+use frame_support::{BoundedVec, traits::Get};
+use frame_system::{RawOrigin, pallet_prelude::HeaderFor};
+use sp_application_crypto::RuntimeAppPublic;
+use sp_aura_equivocation::{EquivocationProof, Slot};
+use sp_consensus_aura::digests::CompatibleDigestItem;
+use sp_runtime::{Digest, DigestItem, traits::Header};
+use sp_std::{boxed::Box, vec};
+
+/// Helper trait for setting up the runtime for benchmarks
+pub trait BenchmarkHelper<T: Config> {
+	/// Should make `authority_id` known to [Config::FindOffender] and the author of `slot`
+	/// according to [Config::SessionAuthorities]
+	fn register_offender(authority_id: &T::AuthorityId, slot: Slot);
+}
+
+type SignatureOf<T> = <<T as Config>::AuthorityId as RuntimeAppPublic>::Signature;
+
+fn sealed_header<T: Config>(offender: &T::AuthorityId, slot: Slot, number: u32) -> HeaderFor<T> {
+	let pre_digest = <DigestItem as CompatibleDigestItem<SignatureOf<T>>>::aura_pre_digest(slot);
+	let mut header = HeaderFor::<T>::new(
+		number.into(),
+		Default::default(),
+		Default::default(),
+		Default::default(),
+		Digest { logs: vec![pre_digest] },
+	);
+	let signature = offender
+		.sign(&header.hash().as_ref())
+		.expect("The offender key was generated in the keystore");
+	header
+		.digest_mut()
+		.push(<DigestItem as CompatibleDigestItem<SignatureOf<T>>>::aura_seal(signature));
+	header
+}
+
+#[benchmarks]
+mod benchmarks {
+	use super::*;
+
+	// The offence storage is full, so the offence of the oldest slot is removed
+	#[benchmark]
+	fn report_equivocation() {
+		let (_, next_epoch_start) = T::CurrentEpochSlots::get();
+		let slot = next_epoch_start.saturating_sub(1u64);
+		let offender = T::AuthorityId::generate_pair(None);
+		T::BenchmarkHelper::register_offender(&offender, slot);
+		let recorded_offender =
+			T::FindOffender::find_offender(&offender).expect("BenchmarkHelper registers offender");
+
+		let max_offences = u64::from(T::MaxRecordedOffences::get());
+		let recorded_slots: BoundedVec<Slot, T::MaxRecordedOffences> = BoundedVec::truncate_from(
+			(*slot.saturating_sub(max_offences)..*slot).map(Slot::from).collect(),
+		);
+		for recorded_slot in recorded_slots.iter() {
+			Offences::<T>::insert(*recorded_slot, recorded_offender.clone(), ());
+		}
+		OffenceSlots::<T>::put(recorded_slots);
+
+		let equivocation_proof = EquivocationProof {
+			offender: offender.clone(),
+			slot,
+			first_header: sealed_header::<T>(&offender, slot, 1),
+			second_header: sealed_header::<T>(&offender, slot, 2),
+		};
+
+		#[extrinsic_call]
+		report_equivocation_unsigned(RawOrigin::None, Box::new(equivocation_proof));
+
+		assert!(Offences::<T>::contains_key(slot, &recorded_offender));
+	}
+
+	impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
+}
@@ -0,0 +1,396 @@
+//! A Substrate pallet recording equivocations of Aura block producers as offences of committee members.
+//!
+//! ## Purpose of this pallet
+//!
+//! Aura does not punish block producers that sign two different blocks for the same slot. Partner Chains
+//! Aura import queue detects such equivocations and reports them to the runtime using the
+//! `sp_aura_equivocation::AuraEquivocationApi` runtime API, which should submit an unsigned
+//! [Call::report_equivocation_unsigned] extrinsic through [Pallet::submit_unsigned_equivocation_report].
+//! This pallet verifies the reported proofs, checks that the offender is the Aura author of the proof's
+//! slot in the current session and that the slot belongs to the current epoch, identifies the committee member
+//! that committed the offence and records it in the [Offences] storage, which retains offences of at most
+//! [Config::MaxRecordedOffences] latest slots. Partner Chains can react to recorded offences, eg. by
+//! penalizing the offender or excluding it from future committee selection, by configuring the
+//! [Config::OnEquivocation] hook.
+//!
+//! ## Usage - PC Builder
+//!
+//! ### Adding to the runtime
+//!
+//! The pallet requires the runtime to support creation of unsigned transactions through
+//! [frame_system::offchain::CreateBare]. An example configuration for a runtime using Aura consensus and
+//! Partner Chain toolkit's session management pallet might look like this:
+//!
+//! ```rust,ignore
+//! impl pallet_aura_equivocation::Config for Runtime {
+//!     type AuthorityId = AuraId;
+//!     type Offender = CommitteeMemberOf<Runtime>;
+//!     type FindOffender = FromCurrentCommittee<Runtime>;
+//!     type SessionAuthorities = AuraSessionAuthorities<Runtime>;
+//!     type CurrentEpochSlots = SidechainEpochSlots<Runtime>;
+//!     type OnEquivocation = ();
+//!     type MaxRecordedOffences = ConstU32<1024>;
+//!     type WeightInfo = pallet_aura_equivocation::weights::SubstrateWeight<Runtime>;
+//!     #[cfg(feature = "runtime-benchmarks")]
+//!     type BenchmarkHelper = MyBenchmarkHelper;
+//! }
+//! ```
+//!
+//! [FromCurrentCommittee] finds the offender in the current committee of `pallet_session_validator_management`
+//! by its Aura key, which requires the committee members' authority keys to implement
+//! [sp_runtime::traits::OpaqueKeys]. [AuraSessionAuthorities] provides the authorities of `pallet_aura`
+//! and [SidechainEpochSlots] computes the slots of the current epoch of `pallet_sidechain`.
+//! Consequently equivocations can only be reported during the committee's epoch.
+//!
+//! The runtime should also implement `sp_aura_equivocation::AuraEquivocationApi` by calling
+//! [Pallet::submit_unsigned_equivocation_report].
+//!
+//! ## Usage - PC user
+//!
+//! This pallet does not expose any user-facing functionalities. Equivocation reports are only accepted
+//! from the local node and are not propagated to other nodes.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![deny(missing_docs)]
+
+pub mod weights;
+
+/// Pallet benchmarking code
+#[cfg(feature = "runtime-benchmarks")]
+pub mod benchmarking;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+use core::marker::PhantomData;
+use frame_support::weights::Weight;
+
+pub use pallet::*;
+pub use weights::WeightInfo;
+
+/// Source of the committee member that owns a block producer's Aura key
+pub trait FindOffender<AuthorityId, Offender> {
+	/// Returns the offender owning `authority_id`, if it is known
+	fn find_offender(authority_id: &AuthorityId) -> Option<Offender>;
+}
+
+/// [FindOffender] implementation that finds the offender in the current committee
+/// of `pallet_session_validator_management`.
+pub struct FromCurrentCommittee<T>(PhantomData<T>);
+
+/// [Config::SessionAuthorities] implementation returning the authorities of `pallet_aura`
+pub struct AuraSessionAuthorities<T>(PhantomData<T>);
+
+/// [Config::CurrentEpochSlots] implementation computing the slots of the current epoch
+/// of `pallet_sidechain` using the slot duration of `pallet_aura`
+pub struct SidechainEpochSlots<T>(PhantomData<T>);
+
+/// Hook called when an equivocation is recorded
+pub trait OnEquivocation<Offender> {
+	/// Handles the equivocation of `offender` in `slot` and returns the weight consumed
+	fn on_equivocation(offender: &Offender, slot: sp_aura_equivocation::Slot) -> Weight;
+}
+
+impl<Offender> OnEquivocation<Offender> for () {
+	fn on_equivocation(_offender: &Offender, _slot: sp_aura_equivocation::Slot) -> Weight {
+		Weight::zero()
+	}
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::offchain::{CreateBare, SubmitTransaction};
+	use frame_system::pallet_prelude::*;
+	use sp_application_crypto::RuntimeAppPublic;
+	use sp_aura_equivocation::{EquivocationProof, Slot, check_equivocation_proof};
+	use sp_runtime::traits::Member;
+	use sp_std::boxed::Box;
+	use sp_std::vec::Vec;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + CreateBare<Call<Self>> {
+		/// Aura authority ID of block producers
+		type AuthorityId: Member + Parameter + RuntimeAppPublic + MaxEncodedLen;
+
+		/// Type identifying the committee member that committed the offence
+		type Offender: Member + Parameter + MaxEncodedLen;
+
+		/// Source of the offender owning the Aura key of the equivocating block producer
+		type FindOffender: FindOffender<Self::AuthorityId, Self::Offender>;
+
+		/// Aura authorities of the current session, in the order in which they are assigned to slots.
+		///
+		/// Only the authority at index `slot % authorities.len()` can author blocks in `slot`,
+		/// so equivocations of other authorities are rejected.
+		type SessionAuthorities: Get<Vec<Self::AuthorityId>>;
+
+		/// Slots of the current epoch, as the first slot of the epoch and the first slot of the next one.
+		///
+		/// Equivocations in other slots are rejected, because the session's authorities are not the ones
+		/// assigned to them.
+		type CurrentEpochSlots: Get<(Slot, Slot)>;
+
+		/// Hook called for each recorded equivocation.
+		///
+		/// Partner Chains can use it to penalize the offender or exclude it from future committees.
+		type OnEquivocation: OnEquivocation<Self::Offender>;
+
+		/// Maximum number of offences kept in [Offences].
+		///
+		/// When it is exceeded, offences of the oldest slot are removed and reports of equivocations
+		/// in slots not later than the removed one are rejected. It should be positive and large enough
+		/// to cover all slots in which an equivocation can still be reported.
+		#[pallet::constant]
+		type MaxRecordedOffences: Get<u32>;
+
+		/// Weight functions of the pallet
+		type WeightInfo: WeightInfo;
+
+		/// Helper for setting up benchmarks
+		#[cfg(feature = "runtime-benchmarks")]
+		type BenchmarkHelper: benchmarking::BenchmarkHelper<Self>;
+	}
+
+	/// Equivocation proof type accepted by the pallet
+	pub type EquivocationProofOf<T> = EquivocationProof<HeaderFor<T>, <T as Config>::AuthorityId>;
+
+	/// Recorded offences, by the slot of the equivocation and the offender
+	#[pallet::storage]
+	pub type Offences<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, Slot, Blake2_128Concat, T::Offender, (), OptionQuery>;
+
+	/// Distinct slots of the offences kept in [Offences], in ascending order
+	#[pallet::storage]
+	pub type OffenceSlots<T: Config> =
+		StorageValue<_, BoundedVec<Slot, T::MaxRecordedOffences>, ValueQuery>;
+
+	/// Latest slot whose offences were removed from [Offences]. Reports of equivocations in slots
+	/// not later than it are rejected.
+	#[pallet::storage]
+	pub type PrunedUpTo<T: Config> = StorageValue<_, Slot, OptionQuery>;
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The proof does not consist of two different headers sealed by the offender for the same slot
+		InvalidEquivocationProof,
+		/// The offender's Aura key does not belong to a known committee member
+		UnknownOffender,
+		/// An equivocation of this offender in this slot was already recorded
+		DuplicateOffenceReport,
+		/// Offences of this slot are not kept anymore
+		StaleOffenceReport,
+		/// The slot of the equivocation is not in the current epoch
+		SlotOutsideCurrentEpoch,
+		/// The offender is not the Aura author of the slot of the equivocation
+		NotSlotAuthor,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Records the equivocation proven by `equivocation_proof`.
+		///
+		/// This extrinsic must be unsigned and is only accepted from the local node.
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::report_equivocation())]
+		pub fn report_equivocation_unsigned(
+			origin: OriginFor<T>,
+			equivocation_proof: Box<EquivocationProofOf<T>>,
+		) -> DispatchResultWithPostInfo {
+			ensure_none(origin)?;
+			let slot = equivocation_proof.slot;
+			let offender = Self::check_report(&equivocation_proof)?;
+
+			log::warn!("⚔️ Block producer {offender:?} equivocated in slot {slot:?}");
+			Self::record_offence(slot, offender.clone());
+			let hook_weight = T::OnEquivocation::on_equivocation(&offender, slot);
+
+			Ok(Some(T::WeightInfo::report_equivocation().saturating_add(hook_weight)).into())
+		}
+	}
+
+	#[pallet::validate_unsigned]
+	impl<T: Config> ValidateUnsigned for Pallet<T> {
+		type Call = Call<T>;
+
+		fn validate_unsigned(source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+			let Call::report_equivocation_unsigned { equivocation_proof } = call else {
+				return InvalidTransaction::Call.into();
+			};
+			match source {
+				TransactionSource::Local | TransactionSource::InBlock => {},
+				_ => {
+					log::warn!("Rejecting equivocation report from {source:?} source");
+					return InvalidTransaction::Call.into();
+				},
+			}
+			Self::check_report(equivocation_proof).map_err(to_invalid_transaction)?;
+
+			ValidTransaction::with_tag_prefix("AuraEquivocation")
+				.priority(TransactionPriority::MAX)
+				.and_provides((equivocation_proof.offender.clone(), equivocation_proof.slot))
+				.longevity(64)
+				.propagate(false)
+				.build()
+		}
+
+		fn pre_dispatch(call: &Self::Call) -> Result<(), TransactionValidityError> {
+			let Call::report_equivocation_unsigned { equivocation_proof } = call else {
+				return Err(InvalidTransaction::Call.into());
+			};
+			Self::check_report(equivocation_proof)
+				.map(|_| ())
+				.map_err(to_invalid_transaction)
+		}
+	}
+
+	fn to_invalid_transaction<T: Config>(error: Error<T>) -> TransactionValidityError {
+		match error {
+			Error::InvalidEquivocationProof => InvalidTransaction::BadProof,
+			Error::UnknownOffender | Error::NotSlotAuthor => InvalidTransaction::BadSigner,
+			Error::DuplicateOffenceReport
+			| Error::StaleOffenceReport
+			| Error::SlotOutsideCurrentEpoch => InvalidTransaction::Stale,
+			_ => InvalidTransaction::Call,
+		}
+		.into()
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Submits an unsigned extrinsic reporting the equivocation proven by `equivocation_proof`.
+		///
+		/// Should be called from the implementation of `sp_aura_equivocation::AuraEquivocationApi`.
+		/// Returns `None` if the extrinsic could not be submitted.
+		pub fn submit_unsigned_equivocation_report(
+			equivocation_proof: EquivocationProofOf<T>,
+		) -> Option<()> {
+			let slot = equivocation_proof.slot;
+			let call = Call::report_equivocation_unsigned {
+				equivocation_proof: Box::new(equivocation_proof),
+			};
+			let xt = T::create_bare(call.into());
+			match SubmitTransaction::<T, Call<T>>::submit_transaction(xt) {
+				Ok(()) => {
+					log::info!("Submitted equivocation report for slot {slot:?}");
+					Some(())
+				},
+				Err(()) => {
+					log::error!("Error submitting equivocation report for slot {slot:?}");
+					None
+				},
+			}
+		}
+
+		/// Returns the offenders that equivocated in `slot`
+		pub fn offenders_at(slot: Slot) -> Vec<T::Offender> {
+			Offences::<T>::iter_key_prefix(slot).collect()
+		}
+
+		/// Records the offence, removing offences of the oldest slot if the storage is full
+		fn record_offence(slot: Slot, offender: T::Offender) {
+			let mut slots = OffenceSlots::<T>::get();
+			if !slots.contains(&slot) {
+				if slots.is_full() && !slots.is_empty() {
+					let oldest = slots.remove(0);
+					let _ = Offences::<T>::clear_prefix(oldest, u32::MAX, None);
+					PrunedUpTo::<T>::put(oldest);
+				}
+				let position = slots.partition_point(|recorded| *recorded < slot);
+				if slots.try_insert(position, slot).is_err() {
+					return;
+				}
+				OffenceSlots::<T>::put(slots);
+			}
+			Offences::<T>::insert(slot, offender, ());
+		}
+
+		fn check_report(
+			equivocation_proof: &EquivocationProofOf<T>,
+		) -> Result<T::Offender, Error<T>> {
+			if !check_equivocation_proof(equivocation_proof) {
+				return Err(Error::InvalidEquivocationProof);
+			}
+			let slot = equivocation_proof.slot;
+			if PrunedUpTo::<T>::get().is_some_and(|pruned| slot <= pruned) {
+				return Err(Error::StaleOffenceReport);
+			}
+			let (epoch_start, next_epoch_start) = T::CurrentEpochSlots::get();
+			if slot < epoch_start || slot >= next_epoch_start {
+				return Err(Error::SlotOutsideCurrentEpoch);
+			}
+			if Self::slot_author(slot).as_ref() != Some(&equivocation_proof.offender) {
+				return Err(Error::NotSlotAuthor);
+			}
+			let offender = T::FindOffender::find_offender(&equivocation_proof.offender)
+				.ok_or(Error::UnknownOffender)?;
+			if Offences::<T>::contains_key(slot, &offender) {
+				return Err(Error::DuplicateOffenceReport);
+			}
+			Ok(offender)
+		}
+
+		/// Returns the Aura authority of the current session assigned to `slot`
+		fn slot_author(slot: Slot) -> Option<T::AuthorityId> {
+			let authorities = T::SessionAuthorities::get();
+			let len = u64::try_from(authorities.len()).ok().filter(|len| *len > 0)?;
+			let index = usize::try_from(*slot % len).ok()?;
+			authorities.into_iter().nth(index)
+		}
+	}
+}
+
+mod source_impls {
+	use super::*;
+	use frame_support::traits::Get;
+	use pallet_session_validator_management as psvm;
+	use sp_application_crypto::RuntimeAppPublic;
+	use sp_aura_equivocation::Slot;
+	use sp_runtime::SaturatedConversion;
+	use sp_runtime::traits::OpaqueKeys;
+	use sp_std::vec::Vec;
+
+	impl<T: pallet_aura::Config> Get<Vec<T::AuthorityId>> for AuraSessionAuthorities<T> {
+		fn get() -> Vec<T::AuthorityId> {
+			pallet_aura::Authorities::<T>::get().into_inner()
+		}
+	}
+
+	impl<T: pallet_sidechain::Config + pallet_aura::Config> Get<(Slot, Slot)>
+		for SidechainEpochSlots<T>
+	{
+		fn get() -> (Slot, Slot) {
+			let schedule = pallet_sidechain::Pallet::<T>::epoch_schedule();
+			let epoch = pallet_sidechain::Pallet::<T>::current_epoch_number();
+			let slot_duration: u64 = pallet_aura::Pallet::<T>::slot_duration().saturated_into();
+			let slot_at = |millis: u64| Slot::from(millis / slot_duration.max(1));
+			(
+				slot_at(schedule.epoch_start_millis(epoch)),
+				slot_at(schedule.epoch_start_millis(epoch.next())),
+			)
+		}
+	}
+
+	impl<AuthorityId, Offender, T> FindOffender<AuthorityId, Offender> for FromCurrentCommittee<T>
+	where
+		AuthorityId: RuntimeAppPublic + parity_scale_codec::Decode + PartialEq,
+		T: psvm::Config,
+		T::AuthorityKeys: OpaqueKeys,
+		psvm::CommitteeMemberOf<T>: Into<Offender>,
+	{
+		fn find_offender(authority_id: &AuthorityId) -> Option<Offender> {
+			let committee = psvm::Pallet::<T>::current_committee_storage().committee;
+			let member = committee.into_iter().find(|member| {
+				member.authority_keys().get::<AuthorityId>(AuthorityId::ID).as_ref()
+					== Some(authority_id)
+			})?;
+			Some(member.into())
+		}
+	}
+}
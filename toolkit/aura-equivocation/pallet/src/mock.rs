@@ -0,0 +1,200 @@
+use frame_support::traits::{ConstU16, ConstU32, ConstU64, Get};
+use frame_support::weights::Weight;
+use frame_system::pallet_prelude::HeaderFor;
+use sp_application_crypto::Pair;
+use sp_aura_equivocation::{EquivocationProof, Slot};
+use sp_consensus_aura::digests::CompatibleDigestItem;
+use sp_consensus_aura::sr25519::{AuthorityId, AuthorityPair, AuthoritySignature};
+use sp_core::H256;
+use sp_keystore::{KeystoreExt, testing::MemoryKeystore};
+use sp_runtime::{
+	BuildStorage, Digest, DigestItem,
+	traits::{BlakeTwo256, Header, IdentityLookup},
+};
+use std::cell::RefCell;
+
+type AccountId = u32;
+type Block = frame_system::mocking::MockBlock<Test>;
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+
+pub(crate) type Offender = u64;
+
+pub(crate) const KNOWN_OFFENDER: Offender = 42;
+
+frame_support::construct_runtime!(
+	pub enum Test {
+		System: frame_system,
+		AuraEquivocation: crate::pallet,
+	}
+);
+
+impl frame_system::Config for Test {
+	type BaseCallFilter = frame_support::traits::Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeCall = RuntimeCall;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type RuntimeEvent = RuntimeEvent;
+	type BlockHashCount = ConstU64<250>;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type ExtensionsWeightInfo = ();
+	type SS58Prefix = ConstU16<42>;
+	type OnSetCode = ();
+	type MaxConsumers = ConstU32<16>;
+	type Nonce = u64;
+	type Block = Block;
+	type RuntimeTask = RuntimeTask;
+	type SingleBlockMigrations = ();
+	type MultiBlockMigrator = ();
+	type PreInherents = ();
+	type PostInherents = ();
+	type PostTransactions = ();
+}
+
+impl<LocalCall> frame_system::offchain::CreateTransactionBase<LocalCall> for Test
+where
+	RuntimeCall: From<LocalCall>,
+{
+	type Extrinsic = UncheckedExtrinsic;
+	type RuntimeCall = RuntimeCall;
+}
+
+impl<LocalCall> frame_system::offchain::CreateBare<LocalCall> for Test
+where
+	RuntimeCall: From<LocalCall>,
+{
+	fn create_bare(call: Self::RuntimeCall) -> Self::Extrinsic {
+		UncheckedExtrinsic::new_bare(call)
+	}
+}
+
+pub struct MockFindOffender;
+
+impl crate::FindOffender<AuthorityId, Offender> for MockFindOffender {
+	fn find_offender(authority_id: &AuthorityId) -> Option<Offender> {
+		let registered = REGISTERED_OFFENDER.with(|r| r.borrow().as_ref() == Some(authority_id));
+		(*authority_id == offender_pair().public() || registered).then_some(KNOWN_OFFENDER)
+	}
+}
+
+thread_local! {
+	pub static REGISTERED_OFFENDER: RefCell<Option<AuthorityId>> = const { RefCell::new(None) };
+}
+
+thread_local! {
+	pub static SESSION_AUTHORITIES: RefCell<Vec<AuthorityId>> = RefCell::new(vec![offender_pair().public()]);
+	pub static CURRENT_EPOCH_SLOTS: RefCell<(Slot, Slot)> = RefCell::new((0.into(), 100.into()));
+}
+
+pub struct MockSessionAuthorities;
+
+impl Get<Vec<AuthorityId>> for MockSessionAuthorities {
+	fn get() -> Vec<AuthorityId> {
+		SESSION_AUTHORITIES.with(|a| a.borrow().clone())
+	}
+}
+
+pub fn set_session_authorities(authorities: Vec<AuthorityId>) {
+	SESSION_AUTHORITIES.with(|a| *a.borrow_mut() = authorities);
+}
+
+pub struct MockCurrentEpochSlots;
+
+impl Get<(Slot, Slot)> for MockCurrentEpochSlots {
+	fn get() -> (Slot, Slot) {
+		CURRENT_EPOCH_SLOTS.with(|s| *s.borrow())
+	}
+}
+
+pub fn set_current_epoch_slots(epoch_start: u64, next_epoch_start: u64) {
+	CURRENT_EPOCH_SLOTS.with(|s| *s.borrow_mut() = (epoch_start.into(), next_epoch_start.into()));
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+pub struct MockBenchmarkHelper;
+
+#[cfg(feature = "runtime-benchmarks")]
+impl crate::benchmarking::BenchmarkHelper<Test> for MockBenchmarkHelper {
+	fn register_offender(authority_id: &AuthorityId, _slot: Slot) {
+		REGISTERED_OFFENDER.with(|r| *r.borrow_mut() = Some(authority_id.clone()));
+		set_session_authorities(vec![authority_id.clone()]);
+	}
+}
+
+thread_local! {
+	pub static RECORDED_EQUIVOCATIONS: RefCell<Vec<(Offender, Slot)>> = const { RefCell::new(vec![]) };
+}
+
+pub struct MockOnEquivocation;
+
+impl crate::OnEquivocation<Offender> for MockOnEquivocation {
+	fn on_equivocation(offender: &Offender, slot: Slot) -> Weight {
+		RECORDED_EQUIVOCATIONS.with(|r| r.borrow_mut().push((*offender, slot)));
+		Weight::zero()
+	}
+}
+
+impl crate::pallet::Config for Test {
+	type AuthorityId = AuthorityId;
+	type Offender = Offender;
+	type FindOffender = MockFindOffender;
+	type SessionAuthorities = MockSessionAuthorities;
+	type CurrentEpochSlots = MockCurrentEpochSlots;
+	type OnEquivocation = MockOnEquivocation;
+	type MaxRecordedOffences = ConstU32<3>;
+	type WeightInfo = ();
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = MockBenchmarkHelper;
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let storage = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+	let mut ext = sp_io::TestExternalities::new(storage);
+	ext.register_extension(KeystoreExt::new(MemoryKeystore::new()));
+	ext
+}
+
+pub fn offender_pair() -> AuthorityPair {
+	AuthorityPair::from_seed(&[1; 32])
+}
+
+pub fn unknown_pair() -> AuthorityPair {
+	AuthorityPair::from_seed(&[2; 32])
+}
+
+pub fn sealed_header(pair: &AuthorityPair, slot: u64, number: u64) -> HeaderFor<Test> {
+	let pre_digest =
+		<DigestItem as CompatibleDigestItem<AuthoritySignature>>::aura_pre_digest(slot.into());
+	let mut header = HeaderFor::<Test>::new(
+		number,
+		H256::default(),
+		H256::default(),
+		H256::repeat_byte(1),
+		Digest { logs: vec![pre_digest] },
+	);
+	let signature = pair.sign(header.hash().as_ref());
+	header.digest_mut().push(CompatibleDigestItem::aura_seal(signature));
+	header
+}
+
+pub fn equivocation_proof(
+	pair: &AuthorityPair,
+	slot: u64,
+) -> EquivocationProof<HeaderFor<Test>, AuthorityId> {
+	EquivocationProof {
+		offender: pair.public(),
+		slot: slot.into(),
+		first_header: sealed_header(pair, slot, 1),
+		second_header: sealed_header(pair, slot, 2),
+	}
+}
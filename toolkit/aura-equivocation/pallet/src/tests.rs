@@ -0,0 +1,160 @@
+use super::*;
+use frame_support::{assert_noop, assert_ok, pallet_prelude::*};
+use frame_system::RawOrigin;
+use mock::*;
+use sp_aura_equivocation::Slot;
+
+fn report(proof: EquivocationProofOf<Test>) -> DispatchResultWithPostInfo {
+	AuraEquivocation::report_equivocation_unsigned(RawOrigin::None.into(), Box::new(proof))
+}
+
+#[test]
+fn records_offence_and_calls_hook() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(report(equivocation_proof(&offender_pair(), 7)));
+
+		assert_eq!(AuraEquivocation::offenders_at(7.into()), vec![KNOWN_OFFENDER]);
+		assert_eq!(
+			RECORDED_EQUIVOCATIONS.with(|r| r.borrow().clone()),
+			vec![(KNOWN_OFFENDER, 7.into())]
+		);
+	});
+}
+
+#[test]
+fn rejects_duplicate_report() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(report(equivocation_proof(&offender_pair(), 7)));
+
+		assert_noop!(
+			report(equivocation_proof(&offender_pair(), 7)),
+			Error::<Test>::DuplicateOffenceReport
+		);
+	});
+}
+
+#[test]
+fn rejects_invalid_proof() {
+	new_test_ext().execute_with(|| {
+		let mut proof = equivocation_proof(&offender_pair(), 7);
+		proof.second_header = proof.first_header.clone();
+
+		assert_noop!(report(proof), Error::<Test>::InvalidEquivocationProof);
+	});
+}
+
+#[test]
+fn rejects_unknown_offender() {
+	new_test_ext().execute_with(|| {
+		set_session_authorities(vec![unknown_pair().public()]);
+
+		assert_noop!(
+			report(equivocation_proof(&unknown_pair(), 7)),
+			Error::<Test>::UnknownOffender
+		);
+	});
+}
+
+#[test]
+fn rejects_report_of_slot_outside_current_epoch() {
+	new_test_ext().execute_with(|| {
+		set_current_epoch_slots(10, 20);
+
+		assert_noop!(
+			report(equivocation_proof(&offender_pair(), 9)),
+			Error::<Test>::SlotOutsideCurrentEpoch
+		);
+		assert_noop!(
+			report(equivocation_proof(&offender_pair(), 20)),
+			Error::<Test>::SlotOutsideCurrentEpoch
+		);
+		assert_ok!(report(equivocation_proof(&offender_pair(), 19)));
+	});
+}
+
+#[test]
+fn rejects_report_of_authority_that_is_not_the_author_of_the_slot() {
+	new_test_ext().execute_with(|| {
+		set_session_authorities(vec![offender_pair().public(), unknown_pair().public()]);
+
+		assert_noop!(report(equivocation_proof(&offender_pair(), 7)), Error::<Test>::NotSlotAuthor);
+		assert_ok!(report(equivocation_proof(&offender_pair(), 8)));
+	});
+}
+
+#[test]
+fn rejects_signed_report() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			AuraEquivocation::report_equivocation_unsigned(
+				RawOrigin::Signed(1).into(),
+				Box::new(equivocation_proof(&offender_pair(), 7))
+			),
+			DispatchError::BadOrigin
+		);
+	});
+}
+
+#[test]
+fn accepts_unsigned_transaction_only_from_local_node() {
+	new_test_ext().execute_with(|| {
+		let call = Call::report_equivocation_unsigned {
+			equivocation_proof: Box::new(equivocation_proof(&offender_pair(), 7)),
+		};
+
+		assert!(AuraEquivocation::validate_unsigned(TransactionSource::Local, &call).is_ok());
+		assert_eq!(
+			AuraEquivocation::validate_unsigned(TransactionSource::External, &call),
+			InvalidTransaction::Call.into()
+		);
+	});
+}
+
+#[test]
+fn rejects_unsigned_transaction_with_already_reported_equivocation() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(report(equivocation_proof(&offender_pair(), 7)));
+		let call = Call::report_equivocation_unsigned {
+			equivocation_proof: Box::new(equivocation_proof(&offender_pair(), 7)),
+		};
+
+		assert_eq!(
+			AuraEquivocation::validate_unsigned(TransactionSource::Local, &call),
+			InvalidTransaction::Stale.into()
+		);
+	});
+}
+
+#[test]
+fn removes_offence_of_the_oldest_slot_when_storage_is_full() {
+	new_test_ext().execute_with(|| {
+		for slot in [2, 4, 1, 3] {
+			assert_ok!(report(equivocation_proof(&offender_pair(), slot)));
+		}
+
+		assert_eq!(AuraEquivocation::offenders_at(1.into()), vec![]);
+		assert_eq!(
+			OffenceSlots::<Test>::get().into_inner(),
+			vec![Slot::from(2), Slot::from(3), Slot::from(4)]
+		);
+		assert_eq!(PrunedUpTo::<Test>::get(), Some(1.into()));
+	});
+}
+
+#[test]
+fn rejects_report_of_pruned_slot() {
+	new_test_ext().execute_with(|| {
+		for slot in [2, 3, 4, 5] {
+			assert_ok!(report(equivocation_proof(&offender_pair(), slot)));
+		}
+
+		assert_noop!(
+			report(equivocation_proof(&offender_pair(), 2)),
+			Error::<Test>::StaleOffenceReport
+		);
+		assert_noop!(
+			report(equivocation_proof(&offender_pair(), 1)),
+			Error::<Test>::StaleOffenceReport
+		);
+	});
+}
@@ -0,0 +1,66 @@
+//! Weights for pallet_aura_equivocation
+//!
+//! These weights are conservative estimates, to be replaced with the results of the pallet's benchmarks.
+//! The dominant cost of the extrinsic is verification of two sr25519 seal signatures and a lookup of
+//! the offender in the current committee. The estimates assume that the offence storage is full,
+//! so the offence of the oldest slot is removed.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(missing_docs)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for pallet_aura_equivocation.
+pub trait WeightInfo {
+	fn report_equivocation() -> Weight;
+}
+
+/// Weights for pallet_aura_equivocation using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: `AuraEquivocation::PrunedUpTo` (r:1 w:1)
+	/// Proof: `AuraEquivocation::PrunedUpTo` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `AuraEquivocation::Offences` (r:1 w:2)
+	/// Proof: `AuraEquivocation::Offences` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `SessionCommitteeManagement::CurrentCommittee` (r:1 w:0)
+	/// Proof: `SessionCommitteeManagement::CurrentCommittee` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `AuraEquivocation::OffenceSlots` (r:1 w:1)
+	/// Proof: `AuraEquivocation::OffenceSlots` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `Aura::Authorities` (r:1 w:0)
+	/// Proof: `Aura::Authorities` (`max_values`: Some(1), `max_size`: Some(32771), added: 33266, mode: `MaxEncodedLen`)
+	/// Storage: `Aura::CurrentSlot` (r:1 w:0)
+	/// Proof: `Aura::CurrentSlot` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `Sidechain::EpochSchedule` (r:1 w:0)
+	/// Proof: `Sidechain::EpochSchedule` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	fn report_equivocation() -> Weight {
+		Weight::from_parts(160_000_000, 64_000)
+			.saturating_add(T::DbWeight::get().reads(7_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	/// Storage: `AuraEquivocation::PrunedUpTo` (r:1 w:1)
+	/// Proof: `AuraEquivocation::PrunedUpTo` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `AuraEquivocation::Offences` (r:1 w:2)
+	/// Proof: `AuraEquivocation::Offences` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `SessionCommitteeManagement::CurrentCommittee` (r:1 w:0)
+	/// Proof: `SessionCommitteeManagement::CurrentCommittee` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `AuraEquivocation::OffenceSlots` (r:1 w:1)
+	/// Proof: `AuraEquivocation::OffenceSlots` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `Aura::Authorities` (r:1 w:0)
+	/// Proof: `Aura::Authorities` (`max_values`: Some(1), `max_size`: Some(32771), added: 33266, mode: `MaxEncodedLen`)
+	/// Storage: `Aura::CurrentSlot` (r:1 w:0)
+	/// Proof: `Aura::CurrentSlot` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `Sidechain::EpochSchedule` (r:1 w:0)
+	/// Proof: `Sidechain::EpochSchedule` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	fn report_equivocation() -> Weight {
+		Weight::from_parts(160_000_000, 64_000)
+			.saturating_add(RocksDbWeight::get().reads(7_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+	}
+}
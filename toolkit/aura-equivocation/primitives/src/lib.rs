@@ -0,0 +1,73 @@
+//! Primitives for detecting and reporting equivocations of Aura block producers.
+//!
+//! An equivocation happens when a block producer signs two different block headers for the same slot.
+//! Partner Chains Aura import queue detects such headers and submits an [EquivocationProof] to the
+//! runtime using [AuraEquivocationApi]. The runtime is expected to check the proof using
+//! [check_equivocation_proof] and record the offence. The intended runtime component is
+//! `pallet_aura_equivocation`.
+//!
+//! ## Usage
+//!
+//! Implement [AuraEquivocationApi] for the runtime, using the pallet:
+//! ```rust,ignore
+//! impl sp_aura_equivocation::AuraEquivocationApi<Block, AuraId> for Runtime {
+//!     fn submit_report_equivocation_unsigned_extrinsic(
+//!         equivocation_proof: sp_aura_equivocation::EquivocationProof<<Block as BlockT>::Header, AuraId>,
+//!     ) -> Option<()> {
+//!         AuraEquivocation::submit_unsigned_equivocation_report(equivocation_proof)
+//!     }
+//! }
+//! ```
+//! and use `sc_partner_chains_consensus_aura::import_queue::import_queue_with_equivocation_reporting`
+//! to create the node's import queue.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![deny(missing_docs)]
+
+#[cfg(test)]
+mod tests;
+
+use parity_scale_codec::Codec;
+use sp_application_crypto::RuntimeAppPublic;
+use sp_consensus_aura::digests::CompatibleDigestItem;
+pub use sp_consensus_slots::{EquivocationProof, Slot};
+use sp_runtime::{DigestItem, traits::Header};
+
+/// Checks whether `proof` is a valid proof of its offender's equivocation.
+///
+/// The proof is valid if it consists of two different headers of blocks in the proof's slot,
+/// both sealed with a valid signature of the offender.
+pub fn check_equivocation_proof<H, AuthorityId>(proof: &EquivocationProof<H, AuthorityId>) -> bool
+where
+	H: Header,
+	AuthorityId: RuntimeAppPublic,
+	AuthorityId::Signature: Codec,
+{
+	let sealed_slot = |header: &H| -> Option<Slot> {
+		let mut header = header.clone();
+		let seal = header.digest_mut().pop()?;
+		let signature = CompatibleDigestItem::<AuthorityId::Signature>::as_aura_seal(&seal)?;
+		let slot = header.digest().logs().iter().find_map(|log: &DigestItem| {
+			CompatibleDigestItem::<AuthorityId::Signature>::as_aura_pre_digest(log)
+		})?;
+		let pre_hash = header.hash();
+		proof.offender.verify(&pre_hash.as_ref(), &signature).then_some(slot)
+	};
+
+	proof.first_header.hash() != proof.second_header.hash()
+		&& sealed_slot(&proof.first_header) == Some(proof.slot)
+		&& sealed_slot(&proof.second_header) == Some(proof.slot)
+}
+
+sp_api::decl_runtime_apis! {
+	/// Runtime API used by the import queue to report equivocations of Aura block producers
+	pub trait AuraEquivocationApi<AuthorityId: Codec> {
+		/// Submits an unsigned extrinsic reporting the equivocation proven by `equivocation_proof`.
+		///
+		/// Returns `None` if the extrinsic could not be submitted. Requires the offchain
+		/// transaction pool extension to be registered for the runtime API call.
+		fn submit_report_equivocation_unsigned_extrinsic(
+			equivocation_proof: EquivocationProof<Block::Header, AuthorityId>,
+		) -> Option<()>;
+	}
+}
@@ -0,0 +1,74 @@
+use super::*;
+use sp_consensus_aura::sr25519::{AuthorityId, AuthorityPair, AuthoritySignature};
+use sp_core::{H256, Pair};
+use sp_runtime::{Digest, generic, traits::BlakeTwo256};
+
+type TestHeader = generic::Header<u32, BlakeTwo256>;
+
+fn sealed_header(pair: &AuthorityPair, slot: u64, number: u32) -> TestHeader {
+	let pre_digest =
+		<DigestItem as CompatibleDigestItem<AuthoritySignature>>::aura_pre_digest(slot.into());
+	let mut header = TestHeader::new(
+		number,
+		H256::default(),
+		H256::default(),
+		H256::repeat_byte(1),
+		Digest { logs: vec![pre_digest] },
+	);
+	let signature = pair.sign(header.hash().as_ref());
+	header.digest_mut().push(CompatibleDigestItem::aura_seal(signature));
+	header
+}
+
+fn proof(
+	offender: AuthorityId,
+	slot: u64,
+	first_header: TestHeader,
+	second_header: TestHeader,
+) -> EquivocationProof<TestHeader, AuthorityId> {
+	EquivocationProof { offender, slot: slot.into(), first_header, second_header }
+}
+
+#[test]
+fn accepts_two_headers_signed_by_offender_for_the_same_slot() {
+	let pair = AuthorityPair::from_seed(&[1; 32]);
+	let proof = proof(pair.public(), 7, sealed_header(&pair, 7, 1), sealed_header(&pair, 7, 2));
+
+	assert!(check_equivocation_proof(&proof));
+}
+
+#[test]
+fn rejects_the_same_header_twice() {
+	let pair = AuthorityPair::from_seed(&[1; 32]);
+	let header = sealed_header(&pair, 7, 1);
+	let proof = proof(pair.public(), 7, header.clone(), header);
+
+	assert!(!check_equivocation_proof(&proof));
+}
+
+#[test]
+fn rejects_headers_of_different_slots() {
+	let pair = AuthorityPair::from_seed(&[1; 32]);
+	let proof = proof(pair.public(), 7, sealed_header(&pair, 7, 1), sealed_header(&pair, 8, 2));
+
+	assert!(!check_equivocation_proof(&proof));
+}
+
+#[test]
+fn rejects_headers_signed_by_someone_else() {
+	let pair = AuthorityPair::from_seed(&[1; 32]);
+	let other = AuthorityPair::from_seed(&[2; 32]);
+	let proof = proof(pair.public(), 7, sealed_header(&pair, 7, 1), sealed_header(&other, 7, 2));
+
+	assert!(!check_equivocation_proof(&proof));
+}
+
+#[test]
+fn rejects_unsealed_headers() {
+	let pair = AuthorityPair::from_seed(&[1; 32]);
+	let mut first_header = sealed_header(&pair, 7, 1);
+	first_header.digest_mut().pop();
+	let proof = proof(pair.public(), 7, first_header, sealed_header(&pair, 7, 2));
+
+	assert!(!check_equivocation_proof(&proof));
+}
@@ -8,7 +8,7 @@ use {
 	crate::authority_selection_inputs::AuthoritySelectionInputsCreationError,
 	sidechain_domain::mainchain_epoch::MainchainEpochDerivation,
 	sidechain_domain::*,
-	sp_api::ProvideRuntimeApi,
+	sp_api::{ApiExt, ProvideRuntimeApi},
 	sp_inherents::{InherentData, InherentIdentifier},
 	sp_runtime::traits::Block as BlockT,
 	sp_session_validator_management::{
@@ -32,7 +32,7 @@ impl AriadneInherentDataProvider {
 	///
 	/// Parameters:
 	/// - `client`: runtime client capable of providing [SessionValidatorManagementApi] runtime API
-	/// - `sc_epoch_duration_millis`: duration of the Partner Chain epoch in milliseconds
+	/// - `sc_epoch_schedule`: Partner Chain epoch schedule, see `sp_sidechain::GetEpochScheduleApi`
 	/// - `mc_epoch_config`: main chain epoch configuration
 	/// - `parent_hash`: parent hash of the current block
 	/// - `timestamp_millis`: timestamp of current block in milliseconds
@@ -40,7 +40,7 @@ impl AriadneInherentDataProvider {
 	/// - `mc_reference_epoch`: latest stable mainchain epoch
 	pub async fn new<Block, AuthorityId, AuthorityKeys, T>(
 		client: &T,
-		sc_epoch_duration_millis: u64,
+		sc_epoch_schedule: &ScEpochSchedule,
 		mc_epoch_config: &MainchainEpochConfig,
 		parent_hash: <Block as BlockT>::Hash,
 		timestamp_millis: u64,
@@ -56,7 +56,7 @@ impl AriadneInherentDataProvider {
 	{
 		let for_mc_epoch = mc_epoch_for_next_ariadne_cidp(
 			client,
-			sc_epoch_duration_millis,
+			sc_epoch_schedule,
 			mc_epoch_config,
 			parent_hash,
 			timestamp_millis,
@@ -66,9 +66,15 @@ impl AriadneInherentDataProvider {
 		// We could accept mc_reference at last slot of data_epoch, but calculations are much easier like that.
 		// Additionally, in current implementation, the inequality below is always true, thus there is no need to make it more accurate.
 		let scripts = client.runtime_api().get_main_chain_scripts(parent_hash)?;
+		let token_staking = get_token_staking_config(client, parent_hash)?;
 		if data_epoch < mc_reference_epoch {
-			Ok(AriadneInherentDataProvider::from_mc_data(data_source, for_mc_epoch, scripts)
-				.await?)
+			Ok(AriadneInherentDataProvider::from_mc_data(
+				data_source,
+				for_mc_epoch,
+				scripts,
+				token_staking,
+			)
+			.await?)
 		} else {
 			Ok(AriadneInherentDataProvider { data: None })
 		}
@@ -78,13 +84,19 @@ impl AriadneInherentDataProvider {
 		candidate_data_source: &(dyn AuthoritySelectionDataSource + Send + Sync),
 		for_epoch: McEpochNumber,
 		scripts: MainChainScripts,
+		token_staking: Option<TokenStakingConfig>,
 	) -> Result<Self, InherentProviderCreationError> {
 		use crate::authority_selection_inputs::authority_selection_inputs_from_mc_data;
 
 		Ok(Self {
 			data: Some(
-				authority_selection_inputs_from_mc_data(candidate_data_source, for_epoch, scripts)
-					.await?,
+				authority_selection_inputs_from_mc_data(
+					candidate_data_source,
+					for_epoch,
+					scripts,
+					token_staking,
+				)
+				.await?,
 			),
 		})
 	}
@@ -106,12 +118,44 @@ pub enum InherentProviderCreationError {
 	/// Data source call failed.
 	#[error("Data source call failed: {0}")]
 	DataSourceError(#[from] Box<dyn std::error::Error + Send + Sync>),
+	/// Timestamp is earlier than the first epoch covered by the Partner Chain epoch schedule.
+	#[error("Timestamp {0} is earlier than the start of the Partner Chain epoch schedule")]
+	TimestampBeforeEpochSchedule(u64),
+}
+
+/// Returns token-based staking configuration of the runtime.
+/// Runtimes with [SessionValidatorManagementApi] older than version 4 do not support token-based staking.
+#[cfg(feature = "std")]
+fn get_token_staking_config<Block, AuthorityId, AuthorityKeys, T>(
+	client: &T,
+	parent_hash: <Block as BlockT>::Hash,
+) -> Result<Option<TokenStakingConfig>, InherentProviderCreationError>
+where
+	Block: BlockT,
+	AuthorityKeys: Decode + Encode,
+	AuthorityId: Decode + Encode,
+	T: ProvideRuntimeApi<Block> + Send + Sync,
+	T::Api: SessionValidatorManagementApi<Block, AuthorityId, AuthorityKeys, ScEpochNumber>,
+{
+	let api = client.runtime_api();
+	let api_version = api
+		.api_version::<dyn SessionValidatorManagementApi<
+			Block,
+			AuthorityId,
+			AuthorityKeys,
+			ScEpochNumber,
+		>>(parent_hash)?
+		.unwrap_or(1);
+	if api_version < 4 {
+		return Ok(None);
+	}
+	Ok(api.get_token_staking_config(parent_hash)?)
 }
 
 #[cfg(feature = "std")]
 fn mc_epoch_for_next_ariadne_cidp<Block, AuthorityId, AuthorityKeys, T>(
 	client: &T,
-	sc_epoch_duration_millis: u64,
+	sc_epoch_schedule: &ScEpochSchedule,
 	epoch_config: &MainchainEpochConfig,
 	parent_hash: <Block as BlockT>::Hash,
 	timestamp_millis: u64,
@@ -135,10 +179,12 @@ where
 			// We first convert the timestamp to PC epoch number and then use the starting time of this
 			// to ensure that all timestamps within a PC epoch produce the same candidates. This is
 			// necessary in case the boundaries of PC epochs and MC epochs do not align.
-			let current_pc_epoch = timestamp_millis / sc_epoch_duration_millis;
-			current_pc_epoch * sc_epoch_duration_millis
+			let current_pc_epoch = sc_epoch_schedule.epoch_for_timestamp(timestamp_millis).ok_or(
+				InherentProviderCreationError::TimestampBeforeEpochSchedule(timestamp_millis),
+			)?;
+			sc_epoch_schedule.epoch_start_millis(current_pc_epoch)
 		} else {
-			next_unset_epoch.0 * sc_epoch_duration_millis
+			sc_epoch_schedule.epoch_start_millis(next_unset_epoch)
 		}
 	};
 
@@ -183,9 +229,11 @@ mod tests {
 	use crate::ariadne_inherent_data_provider::AriadneInherentDataProvider;
 	use crate::mock::MockAuthoritySelectionDataSource;
 	use crate::runtime_api_mock::*;
+	use crate::tests::AccountKeys;
 	use sidechain_domain::mainchain_epoch::*;
 	use sp_core::H256;
 	use sp_core::offchain::Timestamp;
+	use std::str::FromStr;
 
 	const TIMESTAMP: u64 = 400_000;
 
@@ -196,7 +244,7 @@ mod tests {
 		let mc_reference_epoch = McEpochNumber(1);
 		let empty_ariadne_idp = AriadneInherentDataProvider::new(
 			&client(next_unset_epoch_number),
-			sc_epoch_duration_millis(),
+			&sc_epoch_schedule(),
 			&epoch_config(),
 			H256::zero(),
 			// This is the timestamp that will be used to calculate current_epoch_number
@@ -219,7 +267,7 @@ mod tests {
 		let mc_reference_epoch = McEpochNumber(5);
 		let ariadne_idp = AriadneInherentDataProvider::new(
 			&client(next_unset_epoch_number),
-			sc_epoch_duration_millis(),
+			&sc_epoch_schedule(),
 			&epoch_config(),
 			H256::zero(),
 			// This is the timestamp that will be used to calculate current_epoch_number
@@ -244,7 +292,7 @@ mod tests {
 		let mc_reference_epoch = McEpochNumber(5);
 		let ariadne_idp = AriadneInherentDataProvider::new(
 			&client(next_unset_epoch_number),
-			sc_epoch_duration_millis(),
+			&sc_epoch_schedule(),
 			&epoch_config(),
 			H256::zero(),
 			// This is the timestamp that will be used to calculate current_epoch_number
@@ -260,12 +308,115 @@ mod tests {
 		assert!(ariadne_idp.unwrap().data.is_some());
 	}
 
+	#[tokio::test]
+	async fn token_staked_candidates_are_provided_when_token_staking_is_configured() {
+		let token_staked_candidates = vec![TokenStakedCandidateRegistrations {
+			sidechain_pub_key: SidechainPublicKey(vec![1; 33]),
+			registrations: vec![],
+			stake: Some(StakeDelegation(100)),
+		}];
+		let data_source = MockAuthoritySelectionDataSource::default()
+			.with_token_staked_candidates(token_staking_config(), token_staked_candidates.clone());
+
+		let client_without_token_staking = client(ScEpochNumber(42));
+		let ariadne_idp = AriadneInherentDataProvider::new(
+			&client_without_token_staking,
+			&sc_epoch_schedule(),
+			&epoch_config(),
+			H256::zero(),
+			TIMESTAMP,
+			&data_source,
+			McEpochNumber(5),
+		)
+		.await
+		.unwrap();
+		assert_eq!(ariadne_idp.data.unwrap().token_staked_candidates, vec![]);
+
+		let client_with_token_staking =
+			TestApi { token_staking: Some(token_staking_config()), ..client(ScEpochNumber(42)) };
+		let ariadne_idp = AriadneInherentDataProvider::new(
+			&client_with_token_staking,
+			&sc_epoch_schedule(),
+			&epoch_config(),
+			H256::zero(),
+			TIMESTAMP,
+			&data_source,
+			McEpochNumber(5),
+		)
+		.await
+		.unwrap();
+		assert_eq!(ariadne_idp.data.unwrap().token_staked_candidates, token_staked_candidates);
+	}
+
+	#[tokio::test]
+	async fn error_if_token_staking_is_configured_and_data_source_does_not_support_it() {
+		use crate::ariadne_inherent_data_provider::InherentProviderCreationError::InputsCreationError;
+		use crate::authority_selection_inputs::AuthoritySelectionInputsCreationError::GetTokenStakedCandidatesQuery;
+
+		let client =
+			TestApi { token_staking: Some(token_staking_config()), ..client(ScEpochNumber(42)) };
+		let ariadne_idp = AriadneInherentDataProvider::new(
+			&client,
+			&sc_epoch_schedule(),
+			&epoch_config(),
+			H256::zero(),
+			TIMESTAMP,
+			&MockAuthoritySelectionDataSource::default(),
+			McEpochNumber(5),
+		)
+		.await;
+
+		assert!(matches!(
+			ariadne_idp.unwrap_err(),
+			InputsCreationError(GetTokenStakedCandidatesQuery(_, _, _))
+		));
+	}
+
+	#[test]
+	fn mc_epoch_for_next_committee_is_computed_according_to_epoch_schedule() {
+		let sc_epoch_schedule = ScEpochSchedule {
+			next_change: Some(ScEpochDurationChange {
+				epoch: ScEpochNumber(2),
+				epoch_duration: ScEpochDuration::from_millis(sc_epoch_duration_millis() / 10),
+			}),
+			..sc_epoch_schedule()
+		};
+
+		// epoch 42 starts after 2 epochs of 1 hour and 40 epochs of 6 minutes
+		let mc_epoch =
+			mc_epoch_for_next_ariadne_cidp::<Block, CrossChainPublicKey, AccountKeys, _>(
+				&client(ScEpochNumber(42)),
+				&sc_epoch_schedule,
+				&epoch_config(),
+				H256::zero(),
+				TIMESTAMP,
+			)
+			.unwrap();
+
+		assert_eq!(mc_epoch, McEpochNumber(0));
+	}
+
 	fn sc_epoch_duration_millis() -> u64 {
 		60 * 60 * 1000
 	}
 
+	fn sc_epoch_schedule() -> ScEpochSchedule {
+		ScEpochSchedule::new(ScEpochDuration::from_millis(sc_epoch_duration_millis()))
+	}
+
 	fn client(next_unset_epoch_number: ScEpochNumber) -> TestApi {
-		TestApi { next_unset_epoch_number }
+		TestApi { next_unset_epoch_number, token_staking: None }
+	}
+
+	fn token_staking_config() -> TokenStakingConfig {
+		TokenStakingConfig {
+			staking_address: MainchainAddress::from_str("addr_test1_token_staking").unwrap(),
+			staking_token: AssetId {
+				policy_id: PolicyId([7; 28]),
+				asset_name: AssetName::decode_hex("7374616b65").unwrap(),
+			},
+			lovelace_per_token: 1_000_000,
+		}
 	}
 
 	fn epoch_config() -> MainchainEpochConfig {
@@ -1,4 +1,5 @@
 //! Types for authority selection
+use parity_scale_codec::{Decode, Encode};
 use plutus::*;
 use sidechain_domain::*;
 
@@ -33,9 +34,17 @@ pub enum AuthoritySelectionInputsCreationError {
 	#[cfg_attr(feature = "std", error("Failed to get epoch nonce for epoch: {0}: {1}."))]
 	/// Failed to get epoch nonce for epoch
 	GetEpochNonceQuery(McEpochNumber, Box<dyn std::error::Error + Send + Sync>),
+	#[cfg_attr(
+		feature = "std",
+		error(
+			"Failed to get token staked candidates for epoch: {0}, committee candidate address: {1}: {2}."
+		)
+	)]
+	/// Failed to get registered candidates with token-based stake for epoch
+	GetTokenStakedCandidatesQuery(McEpochNumber, String, Box<dyn std::error::Error + Send + Sync>),
 }
 
-#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, Encode, Decode)]
 /// Ariadne selection algorithm parameters owned by the Partner Chain Governance Authority.
 pub struct AriadneParameters {
 	/// D-parameter for Ariadne committee selection. See [DParameter] for details.
@@ -67,6 +76,20 @@ pub trait AuthoritySelectionDataSource {
 		committee_candidate_address: MainchainAddress,
 	) -> Result<Vec<CandidateRegistrations>, Box<dyn std::error::Error + Send + Sync>>;
 
+	/// Returns the list of registrations with token-based stake that is effective for the given epoch.
+	/// The data from the latest block of `data_epoch(epoch)` will be used if available, otherwise returns data at the latest block of the chain.
+	/// Each item is a list of one candidate registrations, with the candidate's stake computed according to `token_staking`.
+	///
+	/// Data sources not supporting token-based staking return an error.
+	async fn get_token_staked_candidates(
+		&self,
+		_epoch: McEpochNumber,
+		_committee_candidate_address: MainchainAddress,
+		_token_staking: TokenStakingConfig,
+	) -> Result<Vec<TokenStakedCandidateRegistrations>, Box<dyn std::error::Error + Send + Sync>> {
+		Err("Token-based staking is not supported by this data source".into())
+	}
+
 	/// Returns Cardano Epoch Nonce. None, if the nonce for given epoch is not known yet.
 	async fn get_epoch_nonce(
 		&self,
@@ -92,6 +115,7 @@ pub(crate) async fn authority_selection_inputs_from_mc_data(
 	candidate_data_source: &(dyn AuthoritySelectionDataSource + Send + Sync),
 	for_epoch: McEpochNumber,
 	scripts: sp_session_validator_management::MainChainScripts,
+	token_staking: Option<TokenStakingConfig>,
 ) -> Result<AuthoritySelectionInputs, AuthoritySelectionInputsCreationError> {
 	let ariadne_parameters_response = candidate_data_source
 		.get_ariadne_parameters(
@@ -139,11 +163,30 @@ pub(crate) async fn authority_selection_inputs_from_mc_data(
 		.await
 		.map_err(|err| AuthoritySelectionInputsCreationError::GetEpochNonceQuery(for_epoch, err))?;
 	let epoch_nonce = epoch_nonce_response.unwrap_or(EpochNonce(vec![]));
+	let token_staked_candidates = match token_staking.clone() {
+		None => Vec::new(),
+		Some(token_staking) => candidate_data_source
+			.get_token_staked_candidates(
+				for_epoch,
+				scripts.committee_candidate_address.clone(),
+				token_staking,
+			)
+			.await
+			.map_err(|err| {
+				AuthoritySelectionInputsCreationError::GetTokenStakedCandidatesQuery(
+					for_epoch,
+					scripts.committee_candidate_address.to_string(),
+					err,
+				)
+			})?,
+	};
 
 	Ok(AuthoritySelectionInputs {
 		d_parameter,
 		permissioned_candidates,
 		registered_candidates,
 		epoch_nonce,
+		token_staked_candidates,
+		token_staking,
 	})
 }
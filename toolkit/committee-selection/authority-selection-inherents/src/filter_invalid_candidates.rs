@@ -39,6 +39,17 @@ pub struct CandidateWithStake<TAccountId, TAccountKeys> {
 	pub account_keys: TAccountKeys,
 }
 
+#[derive(Clone, Debug, Encode, Decode, PartialEq, Eq, PartialOrd, Ord)]
+/// Type representing a registered candidate with token-based stake.
+pub struct TokenStakedCandidate<TAccountId, TAccountKeys> {
+	/// Amount of staking tokens locked for the candidate
+	pub stake: StakeDelegation,
+	/// Account id of the candidate
+	pub account_id: TAccountId,
+	/// Account keys of the candidate
+	pub account_keys: TAccountKeys,
+}
+
 #[derive(Clone, Debug, Encode, Decode, PartialEq, Eq, PartialOrd, Ord)]
 /// Type representing a permissioned candidate.
 pub struct PermissionedCandidate<TAccountId, TAccountKeys> {
@@ -56,6 +67,8 @@ pub enum Candidate<TAccountId, TAccountKeys> {
 	Permissioned(PermissionedCandidate<TAccountId, TAccountKeys>),
 	/// A registered candidate
 	Registered(CandidateWithStake<TAccountId, TAccountKeys>),
+	/// A registered candidate with token-based stake
+	TokenStaked(TokenStakedCandidate<TAccountId, TAccountKeys>),
 }
 
 impl<AuthorityId, AuthorityKeys> From<Candidate<AuthorityId, AuthorityKeys>>
@@ -71,6 +84,9 @@ impl<AuthorityId, AuthorityKeys> From<Candidate<AuthorityId, AuthorityKeys>>
 				keys: member.account_keys,
 				stake_pool_pub_key: member.stake_pool_pub_key,
 			},
+			Candidate::TokenStaked(member) => {
+				Self::TokenStaked { id: member.account_id, keys: member.account_keys }
+			},
 		}
 	}
 }
@@ -81,6 +97,7 @@ impl<TAccountId, TAccountKeys> Candidate<TAccountId, TAccountKeys> {
 		match self {
 			Candidate::Permissioned(c) => &c.account_id,
 			Candidate::Registered(c) => &c.account_id,
+			Candidate::TokenStaked(c) => &c.account_id,
 		}
 	}
 
@@ -89,6 +106,7 @@ impl<TAccountId, TAccountKeys> Candidate<TAccountId, TAccountKeys> {
 		match self {
 			Candidate::Permissioned(c) => &c.account_keys,
 			Candidate::Registered(c) => &c.account_keys,
+			Candidate::TokenStaked(c) => &c.account_keys,
 		}
 	}
 
@@ -97,6 +115,7 @@ impl<TAccountId, TAccountKeys> Candidate<TAccountId, TAccountKeys> {
 		match self {
 			Candidate::Permissioned(_) => None,
 			Candidate::Registered(c) => Some(c.stake_delegation),
+			Candidate::TokenStaked(c) => Some(c.stake),
 		}
 	}
 }
@@ -123,6 +142,38 @@ where
 		})
 		.collect()
 }
+
+/// Get the valid candidates with token-based stake from the registrations from inherent data
+///
+/// The weight of such candidate is the amount of staking tokens locked for it multiplied by `lovelace_per_token`
+/// (see [TokenStakingConfig::lovelace_per_token]), so it is comparable with the weight of ADA-staked candidates.
+pub fn filter_token_staked_candidates_registrations<
+	TAccountId,
+	TAccountKeys: MaybeFromCandidateKeys,
+>(
+	candidate_registrations: Vec<TokenStakedCandidateRegistrations>,
+	genesis_utxo: UtxoId,
+	lovelace_per_token: u64,
+) -> Vec<(Candidate<TAccountId, TAccountKeys>, selection::Weight)>
+where
+	TAccountId: From<ecdsa::Public>,
+{
+	candidate_registrations
+		.into_iter()
+		.flat_map(|candidate_registrations| {
+			select_latest_valid_token_staked_candidate::<TAccountId, TAccountKeys>(
+				candidate_registrations,
+				genesis_utxo,
+			)
+		})
+		.map(|c| {
+			let weight =
+				selection::Weight::from(c.stake.0) * selection::Weight::from(lovelace_per_token);
+			(Candidate::TokenStaked(c), weight)
+		})
+		.collect()
+}
+
 /// Filters invalid candidates from a list of [PermissionedCandidateData].
 pub fn filter_invalid_permissioned_candidates<TAccountId, TAccountKeys: MaybeFromCandidateKeys>(
 	permissioned_candidates: Vec<PermissionedCandidateData>,
@@ -175,6 +226,35 @@ where
 	})
 }
 
+fn select_latest_valid_token_staked_candidate<TAccountId, TAccountKeys: MaybeFromCandidateKeys>(
+	candidate_registrations: TokenStakedCandidateRegistrations,
+	genesis_utxo: UtxoId,
+) -> Option<TokenStakedCandidate<TAccountId, TAccountKeys>>
+where
+	TAccountId: From<ecdsa::Public>,
+{
+	let stake = validate_stake(candidate_registrations.stake).ok()?;
+	let sidechain_pub_key = candidate_registrations.sidechain_pub_key;
+
+	let ((account_id, account_keys), _) = candidate_registrations
+		.registrations
+		.into_iter()
+		.filter_map(|registration_data| {
+			match validate_token_staked_registration_data::<TAccountKeys>(
+				&sidechain_pub_key,
+				&registration_data,
+				genesis_utxo,
+			) {
+				Ok(candidate) => Some((candidate, registration_data.utxo_info)),
+				Err(_) => None,
+			}
+		})
+		// Get the latest valid registration of the authority candidate
+		.max_by_key(|(_, utxo_info)| utxo_info.ordering_key())?;
+
+	Some(TokenStakedCandidate { account_id: account_id.into(), account_keys, stake })
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Encode, Decode, TypeInfo)]
 #[cfg_attr(feature = "std", derive(thiserror::Error, Serialize, Deserialize))]
 /// Stake validation error type
@@ -278,11 +358,51 @@ pub fn validate_registration_data<TAccountKeys: MaybeFromCandidateKeys>(
 		&registration_data.sidechain_signature,
 		&signed_message_encoded,
 	)?;
-	verify_tx_inputs(registration_data)?;
+	verify_tx_inputs(&registration_data.tx_inputs, registration_data.registration_utxo)?;
 
 	Ok((sidechain_pub_key, account_keys))
 }
 
+/// Validates registration data provided by an authority candidate with token-based stake.
+///
+/// Validates:
+/// * Account keys and Partner Chain public keys of the candidate
+/// * sidechain signature
+/// * transaction inputs contain correct registration utxo
+///
+/// Such registrations carry no main chain signature. The candidate is identified by its Partner Chain public key,
+/// which is also the key token holders lock their stake for, so the sidechain signature is the proof of ownership.
+pub fn validate_token_staked_registration_data<TAccountKeys: MaybeFromCandidateKeys>(
+	sidechain_pub_key: &SidechainPublicKey,
+	registration_data: &TokenStakedRegistrationData,
+	genesis_utxo: UtxoId,
+) -> Result<(ecdsa::Public, TAccountKeys), RegistrationDataError> {
+	let account_keys = MaybeFromCandidateKeys::maybe_from(&registration_data.keys)
+		.ok_or(RegistrationDataError::InvalidAccountKeys)?;
+	let ecdsa_pub_key = ecdsa::Public::from(
+		<[u8; 33]>::try_from(sidechain_pub_key.0.clone())
+			.map_err(|_| RegistrationDataError::InvalidSidechainPubKey)?,
+	);
+
+	let signed_message = RegisterValidatorSignedMessage {
+		genesis_utxo,
+		sidechain_pub_key: sidechain_pub_key.0.clone(),
+		registration_utxo: registration_data.registration_utxo,
+	};
+
+	let signed_message_encoded = minicbor::to_vec(signed_message.to_datum())
+		.expect("`RegisterValidatorSignedMessage` should always be encodable");
+
+	verify_sidechain_signature(
+		ecdsa_pub_key,
+		&registration_data.sidechain_signature,
+		&signed_message_encoded,
+	)?;
+	verify_tx_inputs(&registration_data.tx_inputs, registration_data.registration_utxo)?;
+
+	Ok((ecdsa_pub_key, account_keys))
+}
+
 /// Validates stake delegation. Stake must be known and positive.
 pub fn validate_stake(stake: Option<StakeDelegation>) -> Result<StakeDelegation, StakeError> {
 	match stake {
@@ -335,8 +455,11 @@ fn verify_sidechain_signature(
 	if is_valid { Ok(()) } else { Err(RegistrationDataError::InvalidSidechainSignature) }
 }
 
-fn verify_tx_inputs(registration_data: &RegistrationData) -> Result<(), RegistrationDataError> {
-	if registration_data.tx_inputs.contains(&registration_data.registration_utxo) {
+fn verify_tx_inputs(
+	tx_inputs: &[UtxoId],
+	registration_utxo: UtxoId,
+) -> Result<(), RegistrationDataError> {
+	if tx_inputs.contains(&registration_utxo) {
 		Ok(())
 	} else {
 		Err(RegistrationDataError::InvalidTxInput)
@@ -646,6 +769,78 @@ mod tests {
 		assert_eq!(valid_candidates[1].0.stake_delegation(), Some(StakeDelegation(2)));
 	}
 
+	fn token_staked_registrations(
+		registration_data: &RegistrationData,
+		stake: Option<StakeDelegation>,
+	) -> TokenStakedCandidateRegistrations {
+		TokenStakedCandidateRegistrations {
+			sidechain_pub_key: registration_data.sidechain_pub_key.clone(),
+			registrations: vec![TokenStakedRegistrationData {
+				registration_utxo: registration_data.registration_utxo,
+				sidechain_signature: registration_data.sidechain_signature.clone(),
+				utxo_info: registration_data.utxo_info.clone(),
+				tx_inputs: registration_data.tx_inputs.clone(),
+				keys: registration_data.keys.clone(),
+			}],
+			stake,
+		}
+	}
+
+	#[test]
+	fn should_select_token_staked_candidates_weighted_by_normalized_token_stake() {
+		let (_, registration_data, genesis_utxo) = create_valid_parameters();
+		let candidate_registrations = vec![
+			token_staked_registrations(&registration_data, Some(StakeDelegation(0))),
+			token_staked_registrations(&registration_data, None),
+			token_staked_registrations(&registration_data, Some(StakeDelegation(5000))),
+		];
+
+		let valid_candidates = filter_token_staked_candidates_registrations::<AccountId, AccountKeys>(
+			candidate_registrations,
+			genesis_utxo,
+			1_000_000,
+		);
+
+		assert_eq!(valid_candidates.len(), 1);
+		assert_eq!(valid_candidates[0].0.stake_delegation(), Some(StakeDelegation(5000)));
+		assert_eq!(valid_candidates[0].1, 5_000_000_000);
+		assert!(matches!(valid_candidates[0].0, Candidate::TokenStaked(_)));
+	}
+
+	#[test]
+	fn should_filter_out_token_staked_candidates_with_invalid_sidechain_signature() {
+		let (_, registration_data, genesis_utxo) = create_valid_parameters();
+		let mut registrations =
+			token_staked_registrations(&registration_data, Some(StakeDelegation(5000)));
+		registrations.sidechain_pub_key = SidechainPublicKey(
+			ecdsa::Pair::from_seed_slice(&[1u8; 32]).unwrap().public().0.to_vec(),
+		);
+
+		let valid_candidates = filter_token_staked_candidates_registrations::<AccountId, AccountKeys>(
+			vec![registrations],
+			genesis_utxo,
+			1,
+		);
+
+		assert!(valid_candidates.is_empty());
+	}
+
+	#[test]
+	fn should_filter_out_token_staked_candidates_with_invalid_tx_input() {
+		let (_, registration_data, genesis_utxo) = create_valid_parameters();
+		let mut registrations =
+			token_staked_registrations(&registration_data, Some(StakeDelegation(5000)));
+		registrations.registrations[0].tx_inputs = vec![];
+
+		let valid_candidates = filter_token_staked_candidates_registrations::<AccountId, AccountKeys>(
+			vec![registrations],
+			genesis_utxo,
+			1,
+		);
+
+		assert!(valid_candidates.is_empty());
+	}
+
 	#[test]
 	fn should_filter_out_permissioned_candidates_with_invalid_keys() {
 		let valid_sidechain_pub_key = SidechainPublicKey(
@@ -52,11 +52,14 @@ pub use {
 	authority_selection_inputs::{AriadneParameters, AuthoritySelectionInputs},
 	filter_invalid_candidates::{
 		PermissionedCandidateDataError, RegisterValidatorSignedMessage, RegistrationDataError,
-		StakeError, filter_trustless_candidates_registrations,
-		runtime_decl_for_candidate_validation_api, validate_permissioned_candidate_data,
-		validate_registration_data, validate_stake,
+		StakeError, filter_token_staked_candidates_registrations,
+		filter_trustless_candidates_registrations, runtime_decl_for_candidate_validation_api,
+		validate_permissioned_candidate_data, validate_registration_data, validate_stake,
+		validate_token_staked_registration_data,
+	},
+	select_authorities::{
+		DeprioritizeCandidates, select_authorities, select_authorities_deprioritizing,
 	},
-	select_authorities::select_authorities,
 };
 #[cfg(feature = "std")]
 pub use {
@@ -14,6 +14,10 @@ pub struct MockAuthoritySelectionDataSource {
 	pub permissioned_candidates: Vec<Option<Vec<PermissionedCandidateData>>>,
 	/// Number of permissioned candidates.
 	pub num_permissioned_candidates: u16,
+	/// Token-based staking configuration supported by the mock and candidates with token-based stake returned
+	/// for every epoch. Token-based staking is not supported if `None`.
+	pub token_staked_candidates:
+		Option<(TokenStakingConfig, Vec<TokenStakedCandidateRegistrations>)>,
 }
 
 impl Default for MockAuthoritySelectionDataSource {
@@ -22,6 +26,7 @@ impl Default for MockAuthoritySelectionDataSource {
 			candidates: vec![vec![], vec![]],
 			permissioned_candidates: vec![Some(vec![]), Some(vec![])],
 			num_permissioned_candidates: 3,
+			token_staked_candidates: None,
 		}
 	}
 }
@@ -44,6 +49,15 @@ impl MockAuthoritySelectionDataSource {
 	pub fn with_num_permissioned_candidates(self, num_permissioned_candidates: u16) -> Self {
 		Self { num_permissioned_candidates, ..self }
 	}
+
+	/// Sets token-based staking configuration and candidates with token-based stake
+	pub fn with_token_staked_candidates(
+		self,
+		token_staking: TokenStakingConfig,
+		candidates: Vec<TokenStakedCandidateRegistrations>,
+	) -> Self {
+		Self { token_staked_candidates: Some((token_staking, candidates)), ..self }
+	}
 }
 
 #[async_trait::async_trait]
@@ -74,6 +88,18 @@ impl AuthoritySelectionDataSource for MockAuthoritySelectionDataSource {
 		Ok(self.candidates.get(epoch_number.0 as usize).cloned().unwrap_or(vec![]))
 	}
 
+	async fn get_token_staked_candidates(
+		&self,
+		_epoch: McEpochNumber,
+		_committee_candidate_address: MainchainAddress,
+		token_staking: TokenStakingConfig,
+	) -> Result<Vec<TokenStakedCandidateRegistrations>, Box<dyn std::error::Error + Send + Sync>> {
+		match &self.token_staked_candidates {
+			Some((config, candidates)) if *config == token_staking => Ok(candidates.clone()),
+			_ => Err("mock was called with unexpected token staking configuration".into()),
+		}
+	}
+
 	async fn get_epoch_nonce(
 		&self,
 		_epoch: McEpochNumber,
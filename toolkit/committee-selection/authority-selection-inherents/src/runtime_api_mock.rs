@@ -12,6 +12,7 @@ pub type Block = sp_runtime::generic::Block<
 #[derive(Clone)]
 pub struct TestApi {
 	pub next_unset_epoch_number: ScEpochNumber,
+	pub token_staking: Option<TokenStakingConfig>,
 }
 
 impl sp_api::ProvideRuntimeApi<Block> for TestApi {
@@ -45,5 +46,8 @@ sp_api::mock_impl_runtime_apis! {
 				permissioned_candidates_policy_id: PolicyId::default(),
 			}
 		}
+		fn get_token_staking_config() -> Option<TokenStakingConfig> {
+			self.token_staking.clone()
+		}
 	}
 }
@@ -3,8 +3,10 @@
 use crate::MaybeFromCandidateKeys;
 use crate::authority_selection_inputs::AuthoritySelectionInputs;
 use crate::filter_invalid_candidates::{
-	filter_invalid_permissioned_candidates, filter_trustless_candidates_registrations,
+	Candidate, filter_invalid_permissioned_candidates,
+	filter_token_staked_candidates_registrations, filter_trustless_candidates_registrations,
 };
+use alloc::collections::BTreeSet;
 use log::{info, warn};
 use plutus::*;
 use sidechain_domain::{EpochNonce, ScEpochNumber, UtxoId};
@@ -12,6 +14,19 @@ use sp_core::{Get, U256, ecdsa};
 use sp_runtime::BoundedVec;
 use sp_session_validator_management::CommitteeMember;
 
+/// Hook used during committee selection to deprioritize some of the valid candidates,
+/// eg. ones that were chronically offline in the previous epochs.
+pub trait DeprioritizeCandidates<TAccountId, TAccountKeys> {
+	/// Returns true if `candidate` should only be considered when there are not enough other candidates
+	fn is_deprioritized(candidate: &CommitteeMember<TAccountId, TAccountKeys>) -> bool;
+}
+
+impl<TAccountId, TAccountKeys> DeprioritizeCandidates<TAccountId, TAccountKeys> for () {
+	fn is_deprioritized(_candidate: &CommitteeMember<TAccountId, TAccountKeys>) -> bool {
+		false
+	}
+}
+
 /// Selects authorities using the Ariadne selection algorithm and data sourced from Partner Chains smart contracts on Cardano.
 /// Seed is constructed from the MC epoch nonce and the sidechain epoch.
 pub fn select_authorities<
@@ -23,7 +38,27 @@ pub fn select_authorities<
 	input: AuthoritySelectionInputs,
 	sidechain_epoch: ScEpochNumber,
 ) -> Option<BoundedVec<CommitteeMember<TAccountId, TAccountKeys>, MaxAuthorities>> {
-	Some(BoundedVec::truncate_from(select_candidates::<TAccountId, TAccountKeys>(
+	select_authorities_deprioritizing::<TAccountId, TAccountKeys, MaxAuthorities, ()>(
+		genesis_utxo,
+		input,
+		sidechain_epoch,
+	)
+}
+
+/// Selects authorities like [select_authorities], but excludes candidates deprioritized by `D`
+/// from the registered and permissioned candidate pools, as long as each pool has at least as many
+/// other valid candidates as it has seats.
+pub fn select_authorities_deprioritizing<
+	TAccountId: Clone + Ord + From<ecdsa::Public>,
+	TAccountKeys: Clone + Ord + MaybeFromCandidateKeys,
+	MaxAuthorities: Get<u32>,
+	D: DeprioritizeCandidates<TAccountId, TAccountKeys>,
+>(
+	genesis_utxo: UtxoId,
+	input: AuthoritySelectionInputs,
+	sidechain_epoch: ScEpochNumber,
+) -> Option<BoundedVec<CommitteeMember<TAccountId, TAccountKeys>, MaxAuthorities>> {
+	Some(BoundedVec::truncate_from(select_candidates::<TAccountId, TAccountKeys, D>(
 		genesis_utxo,
 		input,
 		sidechain_epoch,
@@ -33,19 +68,52 @@ pub fn select_authorities<
 fn select_candidates<
 	TAccountId: Clone + Ord + From<ecdsa::Public>,
 	TAccountKeys: Clone + Ord + MaybeFromCandidateKeys,
+	D: DeprioritizeCandidates<TAccountId, TAccountKeys>,
 >(
 	genesis_utxo: UtxoId,
 	input: AuthoritySelectionInputs,
 	sidechain_epoch: ScEpochNumber,
 ) -> Option<Vec<CommitteeMember<TAccountId, TAccountKeys>>> {
-	let valid_registered_candidates = filter_trustless_candidates_registrations::<
+	let mut valid_registered_candidates = filter_trustless_candidates_registrations::<
 		TAccountId,
 		TAccountKeys,
 	>(input.registered_candidates, genesis_utxo);
+	// Candidates with token-based stake compete for the same seats as the ADA-staked ones,
+	// with their token stake converted to lovelace. A candidate with valid registrations of both kinds
+	// is considered only once, with its ADA-based stake.
+	let lovelace_per_token = input.token_staking.map_or(1, |config| config.lovelace_per_token);
+	let ada_staked_ids: BTreeSet<TAccountId> = (valid_registered_candidates.iter())
+		.map(|(candidate, _)| candidate.account_id().clone())
+		.collect();
+	let token_staked_candidates = filter_token_staked_candidates_registrations::<
+		TAccountId,
+		TAccountKeys,
+	>(
+		input.token_staked_candidates, genesis_utxo, lovelace_per_token
+	);
+	for (candidate, weight) in token_staked_candidates {
+		if ada_staked_ids.contains(candidate.account_id()) {
+			info!(
+				"💼 Skipping token-based stake of a candidate already registered with ADA-based stake"
+			);
+		} else {
+			valid_registered_candidates.push((candidate, weight));
+		}
+	}
 	let valid_permissioned_candidates = filter_invalid_permissioned_candidates::<
 		TAccountId,
 		TAccountKeys,
 	>(input.permissioned_candidates);
+	let valid_registered_candidates = without_deprioritized::<_, _, _, D>(
+		valid_registered_candidates,
+		input.d_parameter.num_registered_candidates,
+		|(candidate, _)| candidate,
+	);
+	let valid_permissioned_candidates = without_deprioritized::<_, _, _, D>(
+		valid_permissioned_candidates,
+		input.d_parameter.num_permissioned_candidates,
+		|candidate| candidate,
+	);
 	let valid_permissioned_count = valid_permissioned_candidates.len();
 	let valid_registered_count = valid_registered_candidates.len();
 
@@ -70,6 +138,35 @@ fn select_candidates<
 	}
 }
 
+/// Removes candidates deprioritized by `D` from `candidates`, unless fewer than `seats` (or none) would remain
+fn without_deprioritized<T, TAccountId, TAccountKeys, D>(
+	candidates: Vec<T>,
+	seats: u16,
+	candidate: impl Fn(&T) -> &Candidate<TAccountId, TAccountKeys>,
+) -> Vec<T>
+where
+	TAccountId: Clone,
+	TAccountKeys: Clone,
+	D: DeprioritizeCandidates<TAccountId, TAccountKeys>,
+{
+	let deprioritized: Vec<bool> = candidates
+		.iter()
+		.map(|c| D::is_deprioritized(&candidate(c).clone().into()))
+		.collect();
+	let deprioritized_count = deprioritized.iter().filter(|d| **d).count();
+	let other_count = candidates.len() - deprioritized_count;
+	if deprioritized_count == 0 || other_count == 0 || other_count < usize::from(seats) {
+		return candidates;
+	}
+	info!("💤 Excluding {deprioritized_count} deprioritized candidates from committee selection");
+	candidates
+		.into_iter()
+		.zip(deprioritized)
+		.filter(|(_, deprioritized)| !deprioritized)
+		.map(|(candidate, _)| candidate)
+		.collect()
+}
+
 /// Generate 32 byte seed from epoch nonce and Partner Chain epoch number
 pub fn seed_from_nonce_and_sc_epoch(
 	epoch_nonce: &EpochNonce,
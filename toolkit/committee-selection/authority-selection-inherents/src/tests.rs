@@ -1,7 +1,10 @@
 use crate::MaybeFromCandidateKeys;
 use crate::authority_selection_inputs::AuthoritySelectionInputs;
 use crate::filter_invalid_candidates::RegisterValidatorSignedMessage;
-use crate::select_authorities::select_authorities;
+use crate::select_authorities::{
+	DeprioritizeCandidates, select_authorities, select_authorities_deprioritizing,
+};
+use core::str::FromStr;
 use hex_literal::hex;
 use num_bigint::BigInt;
 use parity_scale_codec::Encode;
@@ -12,6 +15,7 @@ use sidechain_domain::{CandidateKey, CandidateKeys};
 use sp_core::{ConstU32, Pair, ecdsa, ed25519};
 use sp_runtime::traits::Zero;
 use sp_runtime::{BoundToRuntimeAppPublic, RuntimeAppPublic, impl_opaque_keys};
+use sp_session_validator_management::CommitteeMember;
 
 #[test]
 fn registration_message_encoding() {
@@ -361,6 +365,74 @@ fn ariadne_does_not_return_empty_committee() {
 	assert_eq!(calculated_committee, None);
 }
 
+struct DeprioritizeAliceAndFerdie;
+
+impl DeprioritizeCandidates<AccountId, AccountKeys> for DeprioritizeAliceAndFerdie {
+	fn is_deprioritized(candidate: &CommitteeMember<AccountId, AccountKeys>) -> bool {
+		["alice", "ferdie"].contains(&account_id_to_name(candidate.authority_id()))
+	}
+}
+
+#[test]
+fn ariadne_excludes_deprioritized_candidates_when_there_are_enough_others() {
+	// P: [alice, bob, charlie, dave]
+	// R: [ferdie, greg, henry]
+	// D-param: (3, 2)
+	let permissioned_validators = vec![ALICE, BOB, CHARLIE, DAVE];
+	let registered_validators = vec![FERDIE, GREG, HENRY];
+	let d_parameter = DParameter { num_permissioned_candidates: 3, num_registered_candidates: 2 };
+	let authority_selection_inputs = create_authority_selection_inputs(
+		&permissioned_validators,
+		&registered_validators,
+		d_parameter,
+	);
+	let calculated_committee =
+		select_authorities_deprioritizing::<
+			AccountId,
+			AccountKeys,
+			MaxValidators,
+			DeprioritizeAliceAndFerdie,
+		>(UtxoId::default(), authority_selection_inputs, ScEpochNumber::zero());
+
+	let committee = calculated_committee.unwrap();
+	let mut committee_names = committee
+		.iter()
+		.map(|member| account_id_to_name(member.authority_id()))
+		.collect::<Vec<_>>();
+	committee_names.sort();
+	assert_eq!(committee_names, vec!["bob", "charlie", "dave", "greg", "henry"]);
+}
+
+#[test]
+fn ariadne_keeps_deprioritized_candidates_when_there_are_not_enough_others() {
+	// P: [alice, bob]
+	// R: [ferdie]
+	// D-param: (2, 1)
+	let permissioned_validators = vec![ALICE, BOB];
+	let registered_validators = vec![FERDIE];
+	let d_parameter = DParameter { num_permissioned_candidates: 2, num_registered_candidates: 1 };
+	let authority_selection_inputs = create_authority_selection_inputs(
+		&permissioned_validators,
+		&registered_validators,
+		d_parameter,
+	);
+	let calculated_committee =
+		select_authorities_deprioritizing::<
+			AccountId,
+			AccountKeys,
+			MaxValidators,
+			DeprioritizeAliceAndFerdie,
+		>(UtxoId::default(), authority_selection_inputs, ScEpochNumber::zero());
+
+	let committee = calculated_committee.unwrap();
+	let mut committee_names = committee
+		.iter()
+		.map(|member| account_id_to_name(member.authority_id()))
+		.collect::<Vec<_>>();
+	committee_names.sort();
+	assert_eq!(committee_names, vec!["alice", "bob", "ferdie"]);
+}
+
 // helpers
 
 const DUMMY_EPOCH_NONCE: &[u8] = &[1u8, 2u8, 3u8];
@@ -427,6 +499,146 @@ pub fn create_authority_selection_inputs(
 		permissioned_candidates: permissioned_candidates_data,
 		registered_candidates: epoch_candidates,
 		epoch_nonce: EpochNonce(DUMMY_EPOCH_NONCE.to_vec()),
+		token_staked_candidates: vec![],
+		token_staking: None,
+	}
+}
+
+fn create_token_staked_candidates(
+	validators: &[MockValidator],
+) -> Vec<TokenStakedCandidateRegistrations> {
+	validators
+		.iter()
+		.map(|validator| {
+			let signed_message = RegisterValidatorSignedMessage {
+				genesis_utxo: UtxoId::default(),
+				sidechain_pub_key: validator.sidechain_pub_key().0,
+				registration_utxo: UtxoId::default(),
+			};
+			let signed_message_encoded = minicbor::to_vec(signed_message.to_datum()).unwrap();
+			let sidechain_signature = validator.ecdsa_pair().sign(&signed_message_encoded[..]);
+
+			TokenStakedCandidateRegistrations {
+				sidechain_pub_key: validator.sidechain_pub_key(),
+				registrations: vec![TokenStakedRegistrationData {
+					registration_utxo: signed_message.registration_utxo,
+					sidechain_signature: SidechainSignature(sidechain_signature.0[..64].to_vec()),
+					utxo_info: UtxoInfo::default(),
+					tx_inputs: vec![signed_message.registration_utxo],
+					keys: validator.keys(),
+				}],
+				stake: Some(StakeDelegation(validator.stake)),
+			}
+		})
+		.collect()
+}
+
+#[test]
+fn token_staked_candidates_compete_for_registered_seats() {
+	// P: [alice]
+	// R: []
+	// Token staked: [bob]
+	// D-param: (1, 2)
+	let d_parameter = DParameter { num_permissioned_candidates: 1, num_registered_candidates: 2 };
+	let mut authority_selection_inputs =
+		create_authority_selection_inputs(&[ALICE], &[], d_parameter);
+	authority_selection_inputs.token_staked_candidates = create_token_staked_candidates(&[BOB]);
+	authority_selection_inputs.token_staking = Some(token_staking_config(1_000_000));
+
+	let committee = select_authorities::<AccountId, AccountKeys, MaxValidators>(
+		UtxoId::default(),
+		authority_selection_inputs,
+		ScEpochNumber::zero(),
+	)
+	.unwrap();
+
+	let mut committee_names = committee
+		.iter()
+		.map(|member| account_id_to_name(member.authority_id()))
+		.collect::<Vec<_>>();
+	committee_names.sort();
+	assert_eq!(committee_names, vec!["alice", "bob", "bob"]);
+	assert!(
+		committee
+			.iter()
+			.filter(|member| account_id_to_name(member.authority_id()) == "bob")
+			.all(|member| matches!(member, CommitteeMember::TokenStaked { .. }))
+	);
+}
+
+#[test]
+fn token_stake_is_converted_to_lovelace_when_competing_with_ada_stake() {
+	// P: [alice]
+	// R: [charlie with 5M ADA]
+	// Token staked: [dave with 5000 tokens worth 1000 ADA each]
+	// D-param: (1, 4)
+	let charlie = MockValidator { stake: 5_000_000_000_000, ..CHARLIE };
+	let dave = MockValidator { stake: 5_000, ..DAVE };
+	let d_parameter = DParameter { num_permissioned_candidates: 1, num_registered_candidates: 4 };
+	let mut authority_selection_inputs =
+		create_authority_selection_inputs(&[ALICE], &[charlie], d_parameter);
+	authority_selection_inputs.token_staked_candidates = create_token_staked_candidates(&[dave]);
+	authority_selection_inputs.token_staking = Some(token_staking_config(1_000_000_000));
+
+	let committee = select_authorities::<AccountId, AccountKeys, MaxValidators>(
+		UtxoId::default(),
+		authority_selection_inputs,
+		ScEpochNumber::zero(),
+	)
+	.unwrap();
+
+	// Both registered candidates have equal weight, so each is guaranteed half of registered seats
+	let mut committee_names = committee
+		.iter()
+		.map(|member| account_id_to_name(member.authority_id()))
+		.collect::<Vec<_>>();
+	committee_names.sort();
+	assert_eq!(committee_names, vec!["alice", "charlie", "charlie", "dave", "dave"]);
+}
+
+#[test]
+fn candidate_registered_with_both_stake_kinds_is_selected_by_ada_stake_only() {
+	// P: [alice]
+	// R: [bob with 3M ADA]
+	// Token staked: [bob and charlie, both with 3000 tokens worth 1000 ADA each]
+	// D-param: (1, 2)
+	let bob_ada_staked = MockValidator { stake: 3_000_000_000_000, ..BOB };
+	let bob_token_staked = MockValidator { stake: 3_000, ..BOB };
+	let charlie = MockValidator { stake: 3_000, ..CHARLIE };
+	let d_parameter = DParameter { num_permissioned_candidates: 1, num_registered_candidates: 2 };
+	let mut authority_selection_inputs =
+		create_authority_selection_inputs(&[ALICE], &[bob_ada_staked], d_parameter);
+	authority_selection_inputs.token_staked_candidates =
+		create_token_staked_candidates(&[bob_token_staked, charlie]);
+	authority_selection_inputs.token_staking = Some(token_staking_config(1_000_000_000));
+
+	let committee = select_authorities::<AccountId, AccountKeys, MaxValidators>(
+		UtxoId::default(),
+		authority_selection_inputs,
+		ScEpochNumber::zero(),
+	)
+	.unwrap();
+
+	let mut committee_names = committee
+		.iter()
+		.map(|member| account_id_to_name(member.authority_id()))
+		.collect::<Vec<_>>();
+	committee_names.sort();
+	assert_eq!(committee_names, vec!["alice", "bob", "charlie"]);
+	for member in committee.iter() {
+		match account_id_to_name(member.authority_id()) {
+			"bob" => assert!(matches!(member, CommitteeMember::Registered { .. })),
+			"charlie" => assert!(matches!(member, CommitteeMember::TokenStaked { .. })),
+			_ => assert!(matches!(member, CommitteeMember::Permissioned { .. })),
+		}
+	}
+}
+
+fn token_staking_config(lovelace_per_token: u64) -> TokenStakingConfig {
+	TokenStakingConfig {
+		staking_address: MainchainAddress::from_str("addr_test1_token_staking").unwrap(),
+		staking_token: AssetId { policy_id: PolicyId([7; 28]), asset_name: AssetName::empty() },
+		lovelace_per_token,
 	}
 }
 
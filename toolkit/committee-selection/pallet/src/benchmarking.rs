@@ -2,6 +2,7 @@ use crate::*;
 use frame_benchmarking::v2::*;
 use frame_support::BoundedVec;
 use frame_system::RawOrigin;
+use sidechain_domain::TokenStakingConfig;
 use sp_core::Get;
 use sp_runtime::traits::One;
 use sp_std::vec::Vec;
@@ -57,5 +58,17 @@ pub mod benchmarks {
 		_(RawOrigin::Root, Default::default(), Default::default(), Default::default());
 	}
 
+	#[benchmark]
+	fn set_token_staking_config() {
+		let token_staking = TokenStakingConfig {
+			staking_address: Default::default(),
+			staking_token: Default::default(),
+			lovelace_per_token: 1,
+		};
+
+		#[extrinsic_call]
+		_(RawOrigin::Root, Some(token_staking));
+	}
+
 	impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
 }
@@ -264,7 +264,7 @@ pub mod pallet {
 	use frame_system::pallet_prelude::*;
 	use log::{info, warn};
 	use sidechain_domain::byte_string::SizedByteString;
-	use sidechain_domain::{MainchainAddress, PolicyId, ScEpochNumber};
+	use sidechain_domain::{MainchainAddress, PolicyId, ScEpochNumber, TokenStakingConfig};
 	use sp_core::blake2_256;
 	use sp_runtime::traits::{MaybeSerializeDeserialize, One, Zero};
 	use sp_session_validator_management::*;
@@ -378,6 +378,12 @@ pub mod pallet {
 	pub type MainChainScriptsConfiguration<T: Config> =
 		StorageValue<_, MainChainScripts, ValueQuery>;
 
+	/// Configuration of token-based staking. Candidates with token-based stake are not
+	/// considered in committee selection while it is not set.
+	#[pallet::storage]
+	pub type TokenStakingConfiguration<T: Config> =
+		StorageValue<_, TokenStakingConfig, OptionQuery>;
+
 	/// Stores the current version of `AuthorityKeys` type.
 	///
 	/// This value is different from the pallet's storage version and is only used for versioning
@@ -397,6 +403,8 @@ pub mod pallet {
 		InvalidEpoch,
 		/// [Pallet::set] has been called a second time for the same next epoch
 		NextCommitteeAlreadySet,
+		/// [Pallet::set_token_staking_config] has been called with zero `lovelace_per_token`
+		ZeroLovelacePerToken,
 	}
 
 	#[pallet::genesis_config]
@@ -577,6 +585,24 @@ pub mod pallet {
 			MainChainScriptsConfiguration::<T>::put(new_scripts);
 			Ok(())
 		}
+
+		/// Sets or removes the configuration of token-based staking used for committee selection.
+		///
+		/// This extrinsic must be run either using `sudo` or some other chain governance mechanism.
+		#[pallet::call_index(2)]
+		#[pallet::weight(T::WeightInfo::set_token_staking_config())]
+		pub fn set_token_staking_config(
+			origin: OriginFor<T>,
+			token_staking: Option<TokenStakingConfig>,
+		) -> DispatchResult {
+			T::MainChainScriptsOrigin::ensure_origin(origin)?;
+			ensure!(
+				token_staking.as_ref().is_none_or(|config| config.lovelace_per_token > 0),
+				Error::<T>::ZeroLovelacePerToken
+			);
+			TokenStakingConfiguration::<T>::set(token_staking);
+			Ok(())
+		}
 	}
 
 	impl<T: Config> Pallet<T> {
@@ -673,6 +699,11 @@ pub mod pallet {
 		pub fn get_main_chain_scripts() -> MainChainScripts {
 			MainChainScriptsConfiguration::<T>::get()
 		}
+
+		/// Returns token-based staking configuration, if set.
+		pub fn get_token_staking_config() -> Option<TokenStakingConfig> {
+			TokenStakingConfiguration::<T>::get()
+		}
 	}
 }
 
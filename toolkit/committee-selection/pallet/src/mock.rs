@@ -326,6 +326,8 @@ pub fn create_inherent_data(validators: &[MockValidator]) -> (InherentData, Size
 			.collect(),
 		registered_candidates: vec![],
 		epoch_nonce: EpochNonce::default(),
+		token_staked_candidates: vec![],
+		token_staking: None,
 	};
 
 	inherent_data
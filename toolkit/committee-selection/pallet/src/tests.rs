@@ -1,12 +1,15 @@
+use crate::Error;
 use crate::mock::*;
-use frame_support::{assert_ok, inherent::ProvideInherent, traits::Hooks};
-use sidechain_domain::ScEpochNumber;
+use core::str::FromStr;
+use frame_support::{assert_err, assert_ok, inherent::ProvideInherent, traits::Hooks};
+use sidechain_domain::{
+	AssetId, AssetName, MainchainAddress, PolicyId, ScEpochNumber, TokenStakingConfig,
+};
 use sp_session_validator_management::CommitteeMember;
 
 mod inherent_tests {
 	use super::*;
-	use crate::{CommitteeInfo, Error, pallet};
-	use frame_support::assert_err;
+	use crate::{CommitteeInfo, pallet};
 	use sidechain_domain::byte_string::SizedByteString;
 	use sp_runtime::DispatchError;
 	use sp_session_validator_management::InherentError;
@@ -403,6 +406,57 @@ fn get_authority_at_works() {
 	});
 }
 
+#[test]
+fn token_staking_config_can_be_set_and_removed_by_root() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(SessionCommitteeManagement::get_token_staking_config(), None);
+		let config = TokenStakingConfig {
+			staking_address: MainchainAddress::from_str("addr_test1_token_staking").unwrap(),
+			staking_token: AssetId { policy_id: PolicyId([7; 28]), asset_name: AssetName::empty() },
+			lovelace_per_token: 1_000_000,
+		};
+
+		assert!(
+			SessionCommitteeManagement::set_token_staking_config(
+				RuntimeOrigin::none(),
+				Some(config.clone())
+			)
+			.is_err()
+		);
+		assert_ok!(SessionCommitteeManagement::set_token_staking_config(
+			RuntimeOrigin::root(),
+			Some(config.clone())
+		));
+		assert_eq!(SessionCommitteeManagement::get_token_staking_config(), Some(config));
+
+		assert_ok!(SessionCommitteeManagement::set_token_staking_config(
+			RuntimeOrigin::root(),
+			None
+		));
+		assert_eq!(SessionCommitteeManagement::get_token_staking_config(), None);
+	});
+}
+
+#[test]
+fn token_staking_config_with_zero_lovelace_per_token_is_rejected() {
+	new_test_ext().execute_with(|| {
+		let config = TokenStakingConfig {
+			staking_address: MainchainAddress::from_str("addr_test1_token_staking").unwrap(),
+			staking_token: AssetId { policy_id: PolicyId([7; 28]), asset_name: AssetName::empty() },
+			lovelace_per_token: 0,
+		};
+
+		assert_err!(
+			SessionCommitteeManagement::set_token_staking_config(
+				RuntimeOrigin::root(),
+				Some(config)
+			),
+			Error::<Test>::ZeroLovelacePerToken
+		);
+		assert_eq!(SessionCommitteeManagement::get_token_staking_config(), None);
+	});
+}
+
 pub(crate) fn increment_epoch() {
 	mock_pallet::CurrentEpoch::<Test>::put(current_epoch_number().next());
 }
@@ -38,6 +38,7 @@ use core::marker::PhantomData;
 pub trait WeightInfo {
 	fn set(v: u32, ) -> Weight;
 	fn set_main_chain_scripts() -> Weight;
+	fn set_token_staking_config() -> Weight;
 }
 
 /// Weights for pallet_session_validator_management using the Substrate node and recommended hardware.
@@ -69,6 +70,16 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 		Weight::from_parts(2_000_000, 0)
 			.saturating_add(T::DbWeight::get().writes(1_u64))
 	}
+	/// Storage: `SessionCommitteeManagement::TokenStakingConfiguration` (r:0 w:1)
+	/// Proof: `SessionCommitteeManagement::TokenStakingConfiguration` (`max_values`: Some(1), `max_size`: Some(191), added: 686, mode: `MaxEncodedLen`)
+	fn set_token_staking_config() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 3_000_000 picoseconds.
+		Weight::from_parts(3_000_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
 }
 
 // For backwards compatibility and tests
@@ -99,4 +110,14 @@ impl WeightInfo for () {
 		Weight::from_parts(2_000_000, 0)
 			.saturating_add(RocksDbWeight::get().writes(1_u64))
 	}
+	/// Storage: `SessionCommitteeManagement::TokenStakingConfiguration` (r:0 w:1)
+	/// Proof: `SessionCommitteeManagement::TokenStakingConfiguration` (`max_values`: Some(1), `max_size`: Some(191), added: 686, mode: `MaxEncodedLen`)
+	fn set_token_staking_config() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 3_000_000 picoseconds.
+		Weight::from_parts(3_000_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
 }
@@ -14,7 +14,8 @@ use parity_scale_codec::DecodeWithMemTracking;
 use scale_info::TypeInfo;
 use sidechain_domain::{
 	CandidateRegistrations, DParameter, EpochNonce, MainchainAddress, PermissionedCandidateData,
-	PolicyId, StakePoolPublicKey, byte_string::SizedByteString,
+	PolicyId, StakePoolPublicKey, TokenStakedCandidateRegistrations, TokenStakingConfig,
+	byte_string::SizedByteString,
 };
 use sp_core::{Decode, Encode, MaxEncodedLen};
 use sp_inherents::{InherentIdentifier, IsFatalError};
@@ -96,6 +97,13 @@ pub enum CommitteeMember<AuthorityId, AuthorityKeys> {
 		/// Stake pool pub key of the candidate
 		stake_pool_pub_key: StakePoolPublicKey,
 	},
+	/// A registered candidate with token-based stake
+	TokenStaked {
+		/// Authority id of the candidate
+		id: AuthorityId,
+		/// Authority keys of the candidate
+		keys: AuthorityKeys,
+	},
 }
 
 impl<AuthorityId, AuthorityKeys> From<(AuthorityId, AuthorityKeys)>
@@ -120,6 +128,7 @@ impl<AuthorityId, AuthorityKeys> CommitteeMember<AuthorityId, AuthorityKeys> {
 		match self {
 			Self::Permissioned { id, .. } => id.clone(),
 			Self::Registered { id, .. } => id.clone(),
+			Self::TokenStaked { id, .. } => id.clone(),
 		}
 	}
 
@@ -131,6 +140,7 @@ impl<AuthorityId, AuthorityKeys> CommitteeMember<AuthorityId, AuthorityKeys> {
 		match self {
 			Self::Permissioned { keys, .. } => keys.clone(),
 			Self::Registered { keys, .. } => keys.clone(),
+			Self::TokenStaked { keys, .. } => keys.clone(),
 		}
 	}
 
@@ -144,6 +154,7 @@ impl<AuthorityId, AuthorityKeys> CommitteeMember<AuthorityId, AuthorityKeys> {
 			Self::Registered { id, keys, stake_pool_pub_key } => {
 				CommitteeMember::Registered { id, keys: f(keys), stake_pool_pub_key }
 			},
+			Self::TokenStaked { id, keys } => CommitteeMember::TokenStaked { id, keys: f(keys) },
 		}
 	}
 }
@@ -210,10 +221,16 @@ pub struct AuthoritySelectionInputs {
 	pub registered_candidates: Vec<CandidateRegistrations>,
 	/// Nonce for queried epoch.
 	pub epoch_nonce: EpochNonce,
+	/// List of registered candidates with token-based stake for committee selection.
+	/// Empty if token-based staking is not configured for the chain.
+	pub token_staked_candidates: Vec<TokenStakedCandidateRegistrations>,
+	/// Token-based staking configuration with which `token_staked_candidates` were obtained.
+	/// `None` if token-based staking is not configured for the chain.
+	pub token_staking: Option<TokenStakingConfig>,
 }
 
 sp_api::decl_runtime_apis! {
-	#[api_version(3)]
+	#[api_version(4)]
 	/// Runtime API declaration for Session Validator Management
 	pub trait SessionValidatorManagementApi<
 		AuthorityId,
@@ -225,6 +242,9 @@ sp_api::decl_runtime_apis! {
 	{
 		/// Returns main chain scripts
 		fn get_main_chain_scripts() -> MainChainScripts;
+		/// Returns token-based staking configuration, `None` if token-based staking is not enabled
+		#[api_version(4)]
+		fn get_token_staking_config() -> Option<TokenStakingConfig>;
 		/// Returns next unset [sidechain_domain::ScEpochNumber]
 		fn get_next_unset_epoch_number() -> ScEpochNumber;
 
@@ -0,0 +1,397 @@
+//! Differential comparison of db-sync and Dolos data sources.
+//!
+//! The same queries are run against both implementations and any differences between their
+//! results are reported, grouped by the data source method.
+
+use crate::Result;
+use authority_selection_inherents::AuthoritySelectionDataSource;
+use partner_chains_db_sync_data_sources as db_sync;
+use partner_chains_dolos_data_sources as dolos;
+use serde::Serialize;
+use sidechain_domain::*;
+use sp_block_participation::inherent_data::BlockParticipationDataSource;
+use sp_governed_map::{GovernedMapDataSource, MainChainScriptsV1};
+use sp_partner_chains_bridge::{MainChainScripts, TokenBridgeDataSource};
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+
+/// Bridge recipient addresses are compared as raw bytes
+type RecipientAddress = Vec<u8>;
+
+const STAKE_CACHE_SIZE: usize = 100;
+
+#[derive(Debug, clap::Parser)]
+pub(crate) struct CompareCmd {
+	/// First Cardano epoch to compare
+	#[arg(long)]
+	from_epoch: McEpochNumber,
+	/// Last Cardano epoch to compare, inclusive
+	#[arg(long)]
+	to_epoch: McEpochNumber,
+	/// Address of the committee candidate validator. Candidates and stake distribution are compared if set.
+	#[arg(long)]
+	committee_candidate_address: Option<MainchainAddress>,
+	/// D-parameter policy ID. Ariadne parameters are compared if both policy IDs are set.
+	#[arg(long)]
+	d_parameter_policy_id: Option<PolicyId>,
+	/// Permissioned candidates policy ID. Ariadne parameters are compared if both policy IDs are set.
+	#[arg(long)]
+	permissioned_candidates_policy_id: Option<PolicyId>,
+	/// Cardano block hashes at which governed map and bridge transfers are compared
+	#[arg(long = "block")]
+	blocks: Vec<McBlockHash>,
+	/// Address of the governed map validator. Governed map is compared if both governed map arguments are set.
+	#[arg(long)]
+	governed_map_validator_address: Option<MainchainAddress>,
+	/// Governed map asset policy ID. Governed map is compared if both governed map arguments are set.
+	#[arg(long)]
+	governed_map_policy_id: Option<PolicyId>,
+	/// Bridge token policy ID. Bridge transfers are compared if it and the illiquid supply address are set.
+	#[arg(long)]
+	bridge_token_policy_id: Option<PolicyId>,
+	/// Bridge token asset name, empty if not set
+	#[arg(long)]
+	bridge_token_asset_name: Option<AssetName>,
+	/// Address of the illiquid supply validator
+	#[arg(long)]
+	illiquid_supply_validator_address: Option<MainchainAddress>,
+	/// Maximum number of bridge transfers queried for every pair of consecutive blocks
+	#[arg(long, default_value_t = 1000)]
+	max_transfers: u32,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub(crate) struct ComparisonReport {
+	summary: BTreeMap<&'static str, MethodSummary>,
+	discrepancies: Vec<Discrepancy>,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct MethodSummary {
+	checked: usize,
+	discrepancies: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct Discrepancy {
+	method: &'static str,
+	arguments: String,
+	db_sync: String,
+	dolos: String,
+}
+
+impl ComparisonReport {
+	fn check<T: PartialEq + Debug>(
+		&mut self,
+		method: &'static str,
+		arguments: String,
+		db_sync: Result<T>,
+		dolos: Result<T>,
+	) {
+		let summary = self.summary.entry(method).or_default();
+		summary.checked += 1;
+		let matches = match (&db_sync, &dolos) {
+			(Ok(db_sync), Ok(dolos)) => db_sync == dolos,
+			_ => false,
+		};
+		if !matches {
+			log::warn!("Discrepancy in {method}({arguments})");
+			summary.discrepancies += 1;
+			self.discrepancies.push(Discrepancy {
+				method,
+				arguments,
+				db_sync: format_result(db_sync),
+				dolos: format_result(dolos),
+			});
+		}
+	}
+}
+
+fn format_result<T: Debug>(result: Result<T>) -> String {
+	match result {
+		Ok(value) => format!("{value:?}"),
+		Err(err) => format!("Error: {err}"),
+	}
+}
+
+struct DataSources<Candidates, Stake, GovernedMap, Bridge> {
+	candidates: Candidates,
+	stake: Stake,
+	governed_map: GovernedMap,
+	bridge: Bridge,
+}
+
+async fn db_sync_data_sources() -> Result<(
+	db_sync::BlockDataSourceImpl,
+	DataSources<
+		db_sync::CandidatesDataSourceImpl,
+		db_sync::StakeDistributionDataSourceImpl,
+		db_sync::GovernedMapDataSourceImpl,
+		db_sync::TokenBridgeDataSourceImpl,
+	>,
+)> {
+	let pool = db_sync::get_connection_from_env().await?;
+	let block = db_sync::BlockDataSourceImpl::new_from_env(pool.clone()).await?;
+	let data_sources = DataSources {
+		candidates: db_sync::CandidatesDataSourceImpl::new(pool.clone(), None).await?,
+		stake: db_sync::StakeDistributionDataSourceImpl::new(pool.clone(), None, STAKE_CACHE_SIZE),
+		governed_map: db_sync::GovernedMapDataSourceImpl::new(pool.clone(), None).await?,
+		bridge: db_sync::TokenBridgeDataSourceImpl::new(pool, None),
+	};
+	Ok((block, data_sources))
+}
+
+fn dolos_data_sources() -> Result<
+	DataSources<
+		dolos::AuthoritySelectionDataSourceImpl,
+		dolos::StakeDistributionDataSourceImpl,
+		dolos::GovernedMapDataSourceImpl,
+		dolos::TokenBridgeDataSourceImpl<RecipientAddress>,
+	>,
+> {
+	let client = dolos::get_connection_from_env()?;
+	Ok(DataSources {
+		candidates: dolos::AuthoritySelectionDataSourceImpl::new(client.clone()),
+		stake: dolos::StakeDistributionDataSourceImpl::new(client.clone()),
+		governed_map: dolos::GovernedMapDataSourceImpl::new(client.clone()),
+		bridge: dolos::TokenBridgeDataSourceImpl::new(client),
+	})
+}
+
+impl CompareCmd {
+	pub(crate) async fn run(self) -> Result<ComparisonReport> {
+		let (block_source, db_sync) = db_sync_data_sources().await?;
+		let dolos = dolos_data_sources()?;
+		let mut report = ComparisonReport::default();
+
+		for epoch in (self.from_epoch.0..=self.to_epoch.0).map(McEpochNumber) {
+			log::info!("Comparing data for epoch {epoch}");
+			self.compare_epoch(epoch, &db_sync, &dolos, &mut report).await;
+		}
+
+		let mut blocks = Vec::new();
+		for hash in &self.blocks {
+			let block = block_source
+				.get_block_by_hash(hash.clone())
+				.await?
+				.ok_or(format!("Could not find block {hash:?} in db-sync"))?;
+			blocks.push(block);
+		}
+		blocks.sort_by_key(|block| block.number);
+		self.compare_blocks(&blocks, &db_sync, &dolos, &mut report).await;
+
+		Ok(report)
+	}
+
+	async fn compare_epoch(
+		&self,
+		epoch: McEpochNumber,
+		db_sync: &DataSources<
+			impl AuthoritySelectionDataSource,
+			impl BlockParticipationDataSource,
+			impl GovernedMapDataSource,
+			impl TokenBridgeDataSource<RecipientAddress>,
+		>,
+		dolos: &DataSources<
+			impl AuthoritySelectionDataSource,
+			impl BlockParticipationDataSource,
+			impl GovernedMapDataSource,
+			impl TokenBridgeDataSource<RecipientAddress>,
+		>,
+		report: &mut ComparisonReport,
+	) {
+		report.check(
+			"get_epoch_nonce",
+			format!("{epoch}"),
+			db_sync.candidates.get_epoch_nonce(epoch).await,
+			dolos.candidates.get_epoch_nonce(epoch).await,
+		);
+
+		if let (Some(d_parameter_policy), Some(permissioned_candidates_policy)) =
+			(&self.d_parameter_policy_id, &self.permissioned_candidates_policy_id)
+		{
+			report.check(
+				"get_ariadne_parameters",
+				format!("{epoch}"),
+				(db_sync.candidates)
+					.get_ariadne_parameters(
+						epoch,
+						d_parameter_policy.clone(),
+						permissioned_candidates_policy.clone(),
+					)
+					.await,
+				(dolos.candidates)
+					.get_ariadne_parameters(
+						epoch,
+						d_parameter_policy.clone(),
+						permissioned_candidates_policy.clone(),
+					)
+					.await,
+			);
+		}
+
+		if let Some(address) = &self.committee_candidate_address {
+			let db_sync_candidates =
+				(db_sync.candidates.get_candidates(epoch, address.clone()).await)
+					.map(normalize_candidates);
+			let dolos_candidates = (dolos.candidates.get_candidates(epoch, address.clone()).await)
+				.map(normalize_candidates);
+
+			// Stake is queried for pools of all candidates known to db-sync
+			let pool_hashes: Vec<MainchainKeyHash> = match &db_sync_candidates {
+				Ok(candidates) => {
+					candidates.iter().map(|c| c.stake_pool_public_key.hash()).collect()
+				},
+				Err(_) => vec![],
+			};
+
+			report.check(
+				"get_candidates",
+				format!("{epoch}"),
+				db_sync_candidates,
+				dolos_candidates,
+			);
+
+			if !pool_hashes.is_empty() {
+				report.check(
+					"get_stake_pool_delegation_distribution_for_pools",
+					format!("{epoch}, {} pools", pool_hashes.len()),
+					(db_sync.stake)
+						.get_stake_pool_delegation_distribution_for_pools(epoch, &pool_hashes)
+						.await
+						.map(|distribution| distribution.0),
+					(dolos.stake)
+						.get_stake_pool_delegation_distribution_for_pools(epoch, &pool_hashes)
+						.await
+						.map(|distribution| distribution.0),
+				);
+			}
+		}
+	}
+
+	async fn compare_blocks(
+		&self,
+		blocks: &[MainchainBlock],
+		db_sync: &DataSources<
+			impl AuthoritySelectionDataSource,
+			impl BlockParticipationDataSource,
+			impl GovernedMapDataSource,
+			impl TokenBridgeDataSource<RecipientAddress>,
+		>,
+		dolos: &DataSources<
+			impl AuthoritySelectionDataSource,
+			impl BlockParticipationDataSource,
+			impl GovernedMapDataSource,
+			impl TokenBridgeDataSource<RecipientAddress>,
+		>,
+		report: &mut ComparisonReport,
+	) {
+		if let (Some(validator_address), Some(asset_policy_id)) =
+			(&self.governed_map_validator_address, &self.governed_map_policy_id)
+		{
+			let scripts = MainChainScriptsV1 {
+				validator_address: validator_address.clone(),
+				asset_policy_id: asset_policy_id.clone(),
+			};
+			for block in blocks {
+				log::info!("Comparing governed map at block {}", block.number);
+				report.check(
+					"get_state_at_block",
+					format!("{:?}", block.hash),
+					db_sync
+						.governed_map
+						.get_state_at_block(block.hash.clone(), scripts.clone())
+						.await,
+					dolos
+						.governed_map
+						.get_state_at_block(block.hash.clone(), scripts.clone())
+						.await,
+				);
+			}
+			for pair in blocks.windows(2) {
+				let (since, up_to) = (&pair[0], &pair[1]);
+				report.check(
+					"get_mapping_changes",
+					format!("{:?}, {:?}", since.hash, up_to.hash),
+					(db_sync.governed_map)
+						.get_mapping_changes(
+							Some(since.hash.clone()),
+							up_to.hash.clone(),
+							scripts.clone(),
+						)
+						.await
+						.map(normalize_mapping_changes),
+					(dolos.governed_map)
+						.get_mapping_changes(
+							Some(since.hash.clone()),
+							up_to.hash.clone(),
+							scripts.clone(),
+						)
+						.await
+						.map(normalize_mapping_changes),
+				);
+			}
+		}
+
+		if let (Some(token_policy_id), Some(illiquid_supply_validator_address)) =
+			(&self.bridge_token_policy_id, &self.illiquid_supply_validator_address)
+		{
+			let scripts = MainChainScripts {
+				token_policy_id: token_policy_id.clone(),
+				token_asset_name: self.bridge_token_asset_name.clone().unwrap_or_default(),
+				illiquid_circulation_supply_validator_address: illiquid_supply_validator_address
+					.clone(),
+			};
+			for pair in blocks.windows(2) {
+				let (since, up_to) = (&pair[0], &pair[1]);
+				log::info!(
+					"Comparing bridge transfers in blocks {}..{}",
+					since.number,
+					up_to.number
+				);
+				let checkpoint =
+					sp_partner_chains_bridge::BridgeDataCheckpoint::Block(since.number);
+				report.check(
+					"get_transfers",
+					format!("{:?}, {:?}", checkpoint, up_to.hash),
+					(db_sync.bridge)
+						.get_transfers(
+							scripts.clone(),
+							checkpoint.clone(),
+							self.max_transfers,
+							up_to.hash.clone(),
+						)
+						.await,
+					(dolos.bridge)
+						.get_transfers(
+							scripts.clone(),
+							checkpoint.clone(),
+							self.max_transfers,
+							up_to.hash.clone(),
+						)
+						.await,
+				);
+			}
+		}
+	}
+}
+
+/// Candidates order is not meaningful and is not guaranteed to be the same by both data sources
+fn normalize_candidates(
+	mut candidates: Vec<CandidateRegistrations>,
+) -> Vec<CandidateRegistrations> {
+	for candidate in candidates.iter_mut() {
+		(candidate.registrations)
+			.sort_by_key(|r| (r.registration_utxo.tx_hash.0, r.registration_utxo.index.0));
+	}
+	candidates.sort_by(|a, b| a.stake_pool_public_key.cmp(&b.stake_pool_public_key));
+	candidates
+}
+
+/// Only one change per key is returned, so order of the changes is not meaningful
+fn normalize_mapping_changes(
+	mut changes: Vec<(String, Option<byte_string::ByteString>)>,
+) -> Vec<(String, Option<byte_string::ByteString>)> {
+	changes.sort_by(|a, b| a.0.cmp(&b.0));
+	changes
+}
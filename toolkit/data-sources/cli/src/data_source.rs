@@ -0,0 +1,343 @@
+//! Construction of the data sources queried by the CLI commands.
+//!
+//! Methods whose parameters can not be passed as command line arguments are wrapped,
+//! as are the results that can not be serialized to JSON.
+
+use crate::Result;
+use authority_selection_inherents::AuthoritySelectionDataSource;
+use pallet_sidechain_rpc::SidechainRpcDataSource;
+use partner_chains_db_sync_data_sources as db_sync;
+use partner_chains_dolos_data_sources as dolos;
+use partner_chains_mock_data_sources as mock;
+use serde::Serialize;
+use sidechain_domain::byte_string::ByteString;
+use sidechain_domain::*;
+use sidechain_mc_hash::McHashDataSource;
+use sp_block_participation::inherent_data::BlockParticipationDataSource;
+use sp_governed_map::{GovernedMapDataSource, MainChainScriptsV1};
+use sp_partner_chains_bridge::{
+	BridgeDataCheckpoint, BridgeTransferV1, MainChainScripts, TokenBridgeDataSource,
+};
+use sp_timestamp::Timestamp;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Bridge recipient addresses are served as raw bytes
+type RecipientAddress = Vec<u8>;
+
+const STAKE_CACHE_SIZE: usize = 100;
+
+/// Data source implementation queried by the commands
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub(crate) enum Backend {
+	/// Cardano db-sync, configured with `DB_SYNC_POSTGRES_CONNECTION_STRING`
+	DbSync,
+	/// Dolos MiniBF API, configured with `DOLOS_MINIBF_URL`
+	Dolos,
+	/// Mock data sources, serving the scenario from `MOCK_SCENARIO_FILE` if it is set
+	Mock,
+}
+
+fn use_mock_scenario() -> bool {
+	std::env::var("MOCK_SCENARIO_FILE").is_ok()
+}
+
+async fn db_sync_block() -> Result<Arc<db_sync::BlockDataSourceImpl>> {
+	let pool = db_sync::get_connection_from_env().await?;
+	Ok(Arc::new(db_sync::BlockDataSourceImpl::new_from_env(pool).await?))
+}
+
+async fn dolos_block() -> Result<Arc<dolos::BlockDataSourceImpl>> {
+	let client = dolos::get_connection_from_env()?;
+	Ok(Arc::new(dolos::BlockDataSourceImpl::new_from_env(client).await?))
+}
+
+pub(crate) struct McHashDataSourceWrapper {
+	inner: Arc<dyn McHashDataSource + Send + Sync>,
+}
+
+impl McHashDataSourceWrapper {
+	pub(crate) async fn get_latest_stable_block_for(
+		&self,
+		reference_timestamp: u64,
+	) -> Result<Option<MainchainBlock>> {
+		self.inner
+			.get_latest_stable_block_for(Timestamp::new(reference_timestamp))
+			.await
+	}
+
+	pub(crate) async fn get_stable_block_for(
+		&self,
+		hash: McBlockHash,
+		reference_timestamp: u64,
+	) -> Result<Option<MainchainBlock>> {
+		self.inner.get_stable_block_for(hash, Timestamp::new(reference_timestamp)).await
+	}
+
+	pub(crate) async fn get_block_by_hash(
+		&self,
+		hash: McBlockHash,
+	) -> Result<Option<MainchainBlock>> {
+		self.inner.get_block_by_hash(hash).await
+	}
+}
+
+pub(crate) async fn mc_hash(backend: Backend) -> Result<McHashDataSourceWrapper> {
+	let inner: Arc<dyn McHashDataSource + Send + Sync> = match backend {
+		Backend::DbSync => {
+			Arc::new(db_sync::McHashDataSourceImpl::new(db_sync_block().await?, None))
+		},
+		Backend::Dolos => Arc::new(dolos::McHashDataSourceImpl::new(dolos_block().await?)),
+		Backend::Mock if use_mock_scenario() => Arc::new(mock::ScenarioDataSource::new_from_env()?),
+		Backend::Mock => Arc::new(mock::McHashDataSourceMock::new(Arc::new(
+			mock::BlockDataSourceMock::new_from_env()?,
+		))),
+	};
+	Ok(McHashDataSourceWrapper { inner })
+}
+
+pub(crate) async fn sidechain_rpc(
+	backend: Backend,
+) -> Result<Arc<dyn SidechainRpcDataSource + Send + Sync>> {
+	Ok(match backend {
+		Backend::DbSync => {
+			Arc::new(db_sync::SidechainRpcDataSourceImpl::new(db_sync_block().await?, None))
+		},
+		Backend::Dolos => {
+			Arc::new(dolos::SidechainRpcDataSourceImpl::new(dolos::get_connection_from_env()?))
+		},
+		Backend::Mock if use_mock_scenario() => Arc::new(mock::ScenarioDataSource::new_from_env()?),
+		Backend::Mock => Arc::new(mock::SidechainRpcDataSourceMock::new(Arc::new(
+			mock::BlockDataSourceMock::new_from_env()?,
+		))),
+	})
+}
+
+pub(crate) async fn candidate(
+	backend: Backend,
+) -> Result<Arc<dyn AuthoritySelectionDataSource + Send + Sync>> {
+	Ok(match backend {
+		Backend::DbSync => Arc::new(
+			db_sync::CandidatesDataSourceImpl::new(db_sync::get_connection_from_env().await?, None)
+				.await?,
+		),
+		Backend::Dolos => Arc::new(dolos::AuthoritySelectionDataSourceImpl::new(
+			dolos::get_connection_from_env()?,
+		)),
+		Backend::Mock if use_mock_scenario() => Arc::new(mock::ScenarioDataSource::new_from_env()?),
+		Backend::Mock => Arc::new(mock::AuthoritySelectionDataSourceMock::new_from_env()?),
+	})
+}
+
+pub(crate) struct GovernedMapDataSourceWrapper {
+	inner: Arc<dyn GovernedMapDataSource + Send + Sync>,
+}
+
+impl GovernedMapDataSourceWrapper {
+	pub(crate) async fn get_mapping_changes(
+		&self,
+		up_to_mc_block: McBlockHash,
+		validator_address: MainchainAddress,
+		asset_policy_id: PolicyId,
+		since_mc_block: Option<McBlockHash>,
+	) -> Result<Vec<(String, Option<ByteString>)>> {
+		let scripts = MainChainScriptsV1 { validator_address, asset_policy_id };
+		self.inner.get_mapping_changes(since_mc_block, up_to_mc_block, scripts).await
+	}
+
+	pub(crate) async fn get_state_at_block(
+		&self,
+		mc_block: McBlockHash,
+		validator_address: MainchainAddress,
+		asset_policy_id: PolicyId,
+	) -> Result<BTreeMap<String, ByteString>> {
+		let scripts = MainChainScriptsV1 { validator_address, asset_policy_id };
+		self.inner.get_state_at_block(mc_block, scripts).await
+	}
+}
+
+pub(crate) async fn governed_map(backend: Backend) -> Result<GovernedMapDataSourceWrapper> {
+	let inner: Arc<dyn GovernedMapDataSource + Send + Sync> = match backend {
+		Backend::DbSync => Arc::new(
+			db_sync::GovernedMapDataSourceImpl::new(
+				db_sync::get_connection_from_env().await?,
+				None,
+			)
+			.await?,
+		),
+		Backend::Dolos => {
+			Arc::new(dolos::GovernedMapDataSourceImpl::new(dolos::get_connection_from_env()?))
+		},
+		Backend::Mock if use_mock_scenario() => Arc::new(mock::ScenarioDataSource::new_from_env()?),
+		Backend::Mock => Arc::new(mock::GovernedMapDataSourceMock::default()),
+	};
+	Ok(GovernedMapDataSourceWrapper { inner })
+}
+
+/// Delegation to a single stake pool, in a form that can be serialized to JSON
+#[derive(Serialize)]
+pub(crate) struct PoolDelegationOutput {
+	total_stake: u64,
+	delegators: Vec<DelegatorOutput>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct DelegatorOutput {
+	stake_key_hash: String,
+	script_hash: Option<String>,
+	amount: u64,
+}
+
+pub(crate) struct StakeDistributionDataSourceWrapper {
+	inner: Arc<dyn BlockParticipationDataSource + Send + Sync>,
+}
+
+impl StakeDistributionDataSourceWrapper {
+	pub(crate) async fn get_stake_pool_delegation_distribution_for_pools(
+		&self,
+		epoch: McEpochNumber,
+		pool_hashes: Vec<MainchainKeyHash>,
+	) -> Result<BTreeMap<String, PoolDelegationOutput>> {
+		let distribution = (self.inner)
+			.get_stake_pool_delegation_distribution_for_pools(epoch, &pool_hashes)
+			.await?;
+		let output = distribution.0.into_iter().map(|(pool, delegation)| {
+			let delegators = (delegation.delegators.into_iter())
+				.map(|(key, amount)| {
+					let (stake_key_hash, script_hash) = match key {
+						DelegatorKey::StakeKeyHash(hash) => (hash, None),
+						DelegatorKey::ScriptKeyHash { hash_raw, script_hash } => {
+							(hash_raw, Some(format!("0x{}", hex::encode(script_hash))))
+						},
+					};
+					let stake_key_hash = format!("0x{}", hex::encode(stake_key_hash));
+					DelegatorOutput { stake_key_hash, script_hash, amount: amount.0 }
+				})
+				.collect();
+			let pool = format!("0x{}", hex::encode(pool.0));
+			(pool, PoolDelegationOutput { total_stake: delegation.total_stake.0, delegators })
+		});
+		Ok(output.collect())
+	}
+}
+
+pub(crate) async fn stake_distribution(
+	backend: Backend,
+) -> Result<StakeDistributionDataSourceWrapper> {
+	let inner: Arc<dyn BlockParticipationDataSource + Send + Sync> = match backend {
+		Backend::DbSync => Arc::new(db_sync::StakeDistributionDataSourceImpl::new(
+			db_sync::get_connection_from_env().await?,
+			None,
+			STAKE_CACHE_SIZE,
+		)),
+		Backend::Dolos => {
+			Arc::new(dolos::StakeDistributionDataSourceImpl::new(dolos::get_connection_from_env()?))
+		},
+		Backend::Mock if use_mock_scenario() => Arc::new(mock::ScenarioDataSource::new_from_env()?),
+		Backend::Mock => Arc::new(mock::StakeDistributionDataSourceMock::new()),
+	};
+	Ok(StakeDistributionDataSourceWrapper { inner })
+}
+
+/// Bridge data checkpoint argument: either a Cardano block number or a `<tx hash>#<index>` UTXO
+#[derive(Debug, Clone)]
+pub(crate) struct Checkpoint(BridgeDataCheckpoint);
+
+impl FromStr for Checkpoint {
+	type Err = String;
+
+	fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+		if let Ok(block_number) = s.parse::<u32>() {
+			return Ok(Self(BridgeDataCheckpoint::Block(McBlockNumber(block_number))));
+		}
+		let utxo = UtxoId::from_str(s).map_err(|err| {
+			format!("Checkpoint should be a block number or a '<hash>#<index>' UTXO: {err}")
+		})?;
+		Ok(Self(BridgeDataCheckpoint::Utxo(utxo)))
+	}
+}
+
+/// Bridge transfer, in a form that can be serialized to JSON
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum TransferOutput {
+	User { token_amount: u64, recipient: String },
+	Reserve { token_amount: u64 },
+	Invalid { token_amount: u64, utxo_id: String },
+}
+
+impl From<BridgeTransferV1<RecipientAddress>> for TransferOutput {
+	fn from(transfer: BridgeTransferV1<RecipientAddress>) -> Self {
+		match transfer {
+			BridgeTransferV1::UserTransfer { token_amount, recipient } => {
+				Self::User { token_amount, recipient: format!("0x{}", hex::encode(recipient)) }
+			},
+			BridgeTransferV1::ReserveTransfer { token_amount } => Self::Reserve { token_amount },
+			BridgeTransferV1::InvalidTransfer { token_amount, utxo_id } => {
+				Self::Invalid { token_amount, utxo_id: utxo_id.to_string() }
+			},
+		}
+	}
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum CheckpointOutput {
+	Block(u32),
+	Utxo(String),
+}
+
+#[derive(Serialize)]
+pub(crate) struct TransfersOutput {
+	transfers: Vec<TransferOutput>,
+	checkpoint: CheckpointOutput,
+}
+
+pub(crate) struct TokenBridgeDataSourceWrapper {
+	inner: Arc<dyn TokenBridgeDataSource<RecipientAddress> + Send + Sync>,
+}
+
+impl TokenBridgeDataSourceWrapper {
+	pub(crate) async fn get_transfers(
+		&self,
+		current_mc_block: McBlockHash,
+		max_transfers: u32,
+		token_policy_id: PolicyId,
+		illiquid_supply_validator_address: MainchainAddress,
+		data_checkpoint: Checkpoint,
+		token_asset_name: Option<AssetName>,
+	) -> Result<TransfersOutput> {
+		let scripts = MainChainScripts {
+			token_policy_id,
+			token_asset_name: token_asset_name.unwrap_or_default(),
+			illiquid_circulation_supply_validator_address: illiquid_supply_validator_address,
+		};
+		let (transfers, checkpoint) = (self.inner)
+			.get_transfers(scripts, data_checkpoint.0, max_transfers, current_mc_block)
+			.await?;
+		let checkpoint = match checkpoint {
+			BridgeDataCheckpoint::Block(number) => CheckpointOutput::Block(number.0),
+			BridgeDataCheckpoint::Utxo(utxo) => CheckpointOutput::Utxo(utxo.to_string()),
+		};
+		Ok(TransfersOutput {
+			transfers: transfers.into_iter().map(Into::into).collect(),
+			checkpoint,
+		})
+	}
+}
+
+pub(crate) async fn bridge(backend: Backend) -> Result<TokenBridgeDataSourceWrapper> {
+	let inner: Arc<dyn TokenBridgeDataSource<RecipientAddress> + Send + Sync> = match backend {
+		Backend::DbSync => Arc::new(db_sync::TokenBridgeDataSourceImpl::new(
+			db_sync::get_connection_from_env().await?,
+			None,
+		)),
+		Backend::Dolos => {
+			Arc::new(dolos::TokenBridgeDataSourceImpl::new(dolos::get_connection_from_env()?))
+		},
+		Backend::Mock if use_mock_scenario() => Arc::new(mock::ScenarioDataSource::new_from_env()?),
+		Backend::Mock => Arc::new(mock::TokenBridgeDataSourceMock::new()),
+	};
+	Ok(TokenBridgeDataSourceWrapper { inner })
+}
@@ -1,28 +1,63 @@
 #![deny(missing_docs)]
-//! This crate provides CLI allowing usage of db-sync data sources.
-//! It can be extended with more data sources.
+//! This crate provides CLI allowing usage of db-sync, Dolos and mock data sources.
+//! The data source implementation is chosen with the `--backend` option.
 //!
 //! `follower_commands` macro is used to generate [clap] commands.
 //! Command level doc comments are supported, but parameter level doc comments are not supported.
+//!
+//! `compare` command runs the same queries against db-sync and Dolos data sources and reports discrepancies.
 
 use authority_selection_inherents::AuthoritySelectionDataSource;
 use clap::Parser;
-use partner_chains_db_sync_data_sources::{BlockDataSourceImpl, CandidatesDataSourceImpl, PgPool};
+use data_source::{Backend, Checkpoint};
+use pallet_sidechain_rpc::SidechainRpcDataSource;
 use sidechain_domain::*;
-use sp_timestamp::Timestamp;
 use std::error::Error;
 
+mod compare;
+mod data_source;
+
 type Result<T> = std::result::Result<T, Box<dyn Error + Send + Sync>>;
 
 #[tokio::main]
 async fn main() {
 	env_logger::builder().filter_level(log::LevelFilter::Info).init();
-	match Command::parse().run().await {
+	match Cli::parse().run().await {
 		Ok(resp) => println!("{resp}"),
 		Err(err) => log::error!("{}", err.to_string()),
 	}
 }
 
+#[derive(Debug, clap::Parser)]
+struct Cli {
+	/// Data source implementation to query
+	#[arg(long, global = true, value_enum, default_value_t = Backend::DbSync)]
+	backend: Backend,
+	#[command(subcommand)]
+	command: CliCommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+enum CliCommand {
+	#[command(flatten)]
+	Query(Command),
+	/// Runs the same queries for a range of epochs and blocks against db-sync and Dolos data sources and reports discrepancies.
+	/// `--backend` is ignored by this command.
+	Compare(compare::CompareCmd),
+}
+
+impl Cli {
+	async fn run(self) -> Result<String> {
+		match self.command {
+			CliCommand::Query(command) => command.run(self.backend).await,
+			CliCommand::Compare(command) => {
+				let report = command.run().await?;
+				Ok(serde_json::to_string_pretty(&report)?)
+			},
+		}
+	}
+}
+
 macro_rules! follower_commands {
 	(
 		$(
@@ -34,7 +69,7 @@ macro_rules! follower_commands {
 			}
 		)*
 	) => {
-		#[derive(Debug, clap::Parser)]
+		#[derive(Debug, clap::Subcommand)]
 		#[allow(non_camel_case_types)]
 		enum Command {
 			$(
@@ -50,12 +85,12 @@ macro_rules! follower_commands {
 			),*
 		}
 		impl Command {
-			async fn run(self) -> Result<String> {
+			async fn run(self, backend: Backend) -> Result<String> {
 				match self {
 					$(
 						$(
 							Command::$method { $($arg),* } => {
-								let data_source = crate::data_source::$ds().await.expect("Failed to create data source");
+								let data_source = crate::data_source::$ds(backend).await?;
 								let result = data_source.$method($($arg),*).await?;
 								let result = serde_json::to_string_pretty(&result)?;
 								Ok(result)
@@ -69,13 +104,17 @@ macro_rules! follower_commands {
 }
 
 follower_commands! {
-	block {
-		/// Returns the latest block recorded by DB-Sync
+	sidechain_rpc {
+		/// Returns the latest Cardano block known to the data source
 		async fn get_latest_block_info();
+	}
+	mc_hash {
 		/// Returns data of block that was the latest stable block at given timestamp
 		async fn get_latest_stable_block_for(1 reference_timestamp_millis: u64);
 		/// Returns data of the block identified by given hash, but only if the block can be considered stable in relation to reference timestamp
 		async fn get_stable_block_for(1 hash: McBlockHash, 2 reference_timestamp_millis: u64);
+		/// Returns data of the block identified by given hash
+		async fn get_block_by_hash(1 hash: McBlockHash);
 	}
 	candidate {
 		/// Returns values of D-parameter and Permissioned Candidates effective at given epoch. Policy IDs should be hex encoded.
@@ -84,51 +123,21 @@ follower_commands! {
 		async fn get_candidates(1 epoch_number: McEpochNumber, 2 committee_candidate_validator_address: MainchainAddress);
 		/// Returns Cardano epoch nonce used by committee selection during given Cardano epoch. It is not nonce of the given epoch.
 		async fn get_epoch_nonce(1 epoch_number: McEpochNumber);
+		/// Returns the Cardano epoch from which committee selection data for given Cardano epoch is taken.
+		async fn data_epoch(1 for_epoch: McEpochNumber);
 	}
-}
-
-mod data_source {
-
-	use super::*;
-
-	async fn pool() -> Result<PgPool> {
-		partner_chains_db_sync_data_sources::get_connection_from_env().await
-	}
-
-	pub(crate) struct BlockDataSourceWrapper {
-		inner: BlockDataSourceImpl,
+	governed_map {
+		/// Returns governed map changes since the first block (exclusive), or since the beginning if not given, up to the block (inclusive).
+		async fn get_mapping_changes(1 up_to_mc_block: McBlockHash, 2 validator_address: MainchainAddress, 3 asset_policy_id: PolicyId, 4 since_mc_block: Option<McBlockHash>);
+		/// Returns the governed map state at given block.
+		async fn get_state_at_block(1 mc_block: McBlockHash, 2 validator_address: MainchainAddress, 3 asset_policy_id: PolicyId);
 	}
-
-	impl BlockDataSourceWrapper {
-		pub(crate) async fn get_latest_block_info(&self) -> Result<MainchainBlock> {
-			self.inner.get_latest_block_info().await
-		}
-
-		pub(crate) async fn get_latest_stable_block_for(
-			&self,
-			reference_timestamp: u64,
-		) -> Result<Option<MainchainBlock>> {
-			self.inner
-				.get_latest_stable_block_for(Timestamp::new(reference_timestamp))
-				.await
-		}
-
-		pub(crate) async fn get_stable_block_for(
-			&self,
-			hash: McBlockHash,
-			reference_timestamp: u64,
-		) -> Result<Option<MainchainBlock>> {
-			self.inner.get_stable_block_for(hash, Timestamp::new(reference_timestamp)).await
-		}
+	stake_distribution {
+		/// Returns stake delegated to given pools in given Cardano epoch. Pool hashes should be hex encoded.
+		async fn get_stake_pool_delegation_distribution_for_pools(1 epoch: McEpochNumber, 2 pool_hashes: Vec<MainchainKeyHash>);
 	}
-
-	pub(crate) async fn block() -> Result<BlockDataSourceWrapper> {
-		Ok(BlockDataSourceWrapper {
-			inner: BlockDataSourceImpl::new_from_env(pool().await?).await?,
-		})
-	}
-
-	pub(crate) async fn candidate() -> Result<CandidatesDataSourceImpl> {
-		CandidatesDataSourceImpl::new(pool().await?, None).await
+	bridge {
+		/// Returns bridge transfers after the data checkpoint up to the block, and the new checkpoint. Checkpoint is either a block number or a UTXO. Asset name is empty if not given.
+		async fn get_transfers(1 current_mc_block: McBlockHash, 2 max_transfers: u32, 3 token_policy_id: PolicyId, 4 illiquid_supply_validator_address: MainchainAddress, 5 data_checkpoint: Checkpoint, 6 token_asset_name: Option<AssetName>);
 	}
 }
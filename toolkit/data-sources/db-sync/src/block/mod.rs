@@ -72,12 +72,22 @@ impl BlockDataSourceImpl {
 		&self,
 		reference_timestamp: Timestamp,
 	) -> Result<Option<MainchainBlock>, Box<dyn std::error::Error + Send + Sync>> {
+		Ok(self.get_latest_stable_block_and_tip_for(reference_timestamp).await?.0)
+	}
+
+	/// Returns the same block as [get_latest_stable_block_for][Self::get_latest_stable_block_for]
+	/// together with the latest _unstable_ Cardano block it was selected relative to
+	pub async fn get_latest_stable_block_and_tip_for(
+		&self,
+		reference_timestamp: Timestamp,
+	) -> Result<(Option<MainchainBlock>, MainchainBlock), Box<dyn std::error::Error + Send + Sync>>
+	{
 		let reference_timestamp = BlockDataSourceImpl::timestamp_to_db_type(reference_timestamp)?;
 		let latest = self.get_latest_block_info().await?;
 		let offset = self.security_parameter + self.block_stability_margin;
 		let stable = latest.number.saturating_sub(offset).into();
 		let block = self.get_latest_block(stable, reference_timestamp).await?;
-		Ok(block.map(From::from))
+		Ok((block.map(From::from), latest))
 	}
 
 	/// Finds a block by its `hash` and verifies that it is stable in reference to `reference_timestamp`
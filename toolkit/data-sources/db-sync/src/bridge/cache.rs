@@ -5,6 +5,8 @@ use futures::lock::Mutex;
 use sidechain_domain::{MainchainBlock, McBlockHash, UtxoId};
 use std::{cmp::min, collections::HashMap, error::Error, sync::Arc};
 
+const CACHE_NAME: &str = "bridge_transfers";
+
 /// Bridge transfer data source with block range-based caching
 ///
 /// This data source caches utxos in some range [from_block, to_block] (inclusive) and serves
@@ -112,6 +114,7 @@ impl TokenUtxoCache {
 }
 
 observed_async_trait!(
+	error_kind = crate::error_kind;
 	impl<RecipientAddress> TokenBridgeDataSource<RecipientAddress> for CachedTokenBridgeDataSourceImpl
 	where
 		RecipientAddress: Debug,
@@ -135,8 +138,16 @@ observed_async_trait!(
 
 			let utxos =
 				match self.try_serve_from_cache(&data_checkpoint, to_block, max_transfers).await {
-					Some(utxos) => utxos,
+					Some(utxos) => {
+						if let Some(metrics) = &self.metrics_opt {
+							metrics.cache_hit(CACHE_NAME);
+						}
+						utxos
+					},
 					None => {
+						if let Some(metrics) = &self.metrics_opt {
+							metrics.cache_miss(CACHE_NAME);
+						}
 						self.fill_cache(main_chain_scripts, &data_checkpoint, to_block).await?;
 						self.try_serve_from_cache(&data_checkpoint, to_block, max_transfers)
 							.await
@@ -231,7 +242,11 @@ impl CachedTokenBridgeDataSourceImpl {
 		utxos: Vec<BridgeUtxo>,
 	) {
 		let mut cache = self.cache.lock().await;
+		let evicted = cache.transfers.len() as u64;
 		cache.set_cached_transfers(start_block, end_block, utxos);
+		if let Some(metrics) = &self.metrics_opt {
+			metrics.cache_evicted(CACHE_NAME, evicted);
+		}
 	}
 
 	async fn get_latest_stable_block(
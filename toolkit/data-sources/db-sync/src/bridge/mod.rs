@@ -74,6 +74,7 @@ impl TokenBridgeDataSourceImpl {
 }
 
 observed_async_trait!(
+	error_kind = crate::error_kind;
 	impl<RecipientAddress> TokenBridgeDataSource<RecipientAddress> for TokenBridgeDataSourceImpl
 	where
 		RecipientAddress: Debug,
@@ -9,10 +9,12 @@ use authority_selection_inherents::*;
 use figment::{Figment, providers::Env};
 use log::info;
 use lru::LruCache;
+use partner_chains_data_source_metrics::McFollowerMetrics;
 use serde::Deserialize;
 use sidechain_domain::*;
 use std::{
 	error::Error,
+	hash::Hash,
 	sync::{Arc, Mutex},
 };
 
@@ -20,6 +22,10 @@ pub type ArcMut<T> = Arc<Mutex<T>>;
 
 type AriadneParametersCacheKey = (McEpochNumber, PolicyId, PolicyId);
 type CandidatesCacheKey = (McEpochNumber, String);
+
+const CANDIDATES_CACHE_NAME: &str = "candidates";
+const ARIADNE_PARAMETERS_CACHE_NAME: &str = "ariadne_parameters";
+
 /// Cached candidate data source
 pub struct CandidateDataSourceCached {
 	inner: CandidatesDataSourceImpl,
@@ -65,6 +71,17 @@ impl AuthoritySelectionDataSource for CandidateDataSourceCached {
 		}
 	}
 
+	async fn get_token_staked_candidates(
+		&self,
+		epoch: McEpochNumber,
+		committee_candidate_address: MainchainAddress,
+		token_staking: TokenStakingConfig,
+	) -> Result<Vec<TokenStakedCandidateRegistrations>, Box<dyn std::error::Error + Send + Sync>> {
+		self.inner
+			.get_token_staked_candidates(epoch, committee_candidate_address, token_staking)
+			.await
+	}
+
 	async fn get_epoch_nonce(
 		&self,
 		epoch: McEpochNumber,
@@ -135,14 +152,16 @@ impl CandidateDataSourceCached {
 		if let Ok(mut cache) = self.get_candidates_for_epoch_cache.lock() {
 			if let Some(resp) = cache.get(&key) {
 				log::debug!("Serving cached candidates for epoch: {:?}", epoch.0);
+				self.record_cache_hit(CANDIDATES_CACHE_NAME);
 				return Ok(resp.clone());
 			}
 		}
+		self.record_cache_miss(CANDIDATES_CACHE_NAME);
 
 		let response = self.inner.get_candidates(epoch, committee_candidate_address).await?;
 		if let Ok(mut cache) = self.get_candidates_for_epoch_cache.lock() {
 			log::debug!("Caching candidates for epoch: {:?}", epoch.0);
-			cache.put(key, response.clone());
+			self.put_to_cache(&mut cache, key, response.clone(), CANDIDATES_CACHE_NAME);
 		}
 		Ok(response)
 	}
@@ -159,9 +178,11 @@ impl CandidateDataSourceCached {
 		if let Ok(mut cache) = self.get_ariadne_parameters_for_epoch_cache.lock() {
 			if let Some(resp) = cache.get(&key) {
 				log::debug!("Serving cached ariadne parameters for epoch: {:?}", epoch.0);
+				self.record_cache_hit(ARIADNE_PARAMETERS_CACHE_NAME);
 				return Ok(resp.clone());
 			}
 		}
+		self.record_cache_miss(ARIADNE_PARAMETERS_CACHE_NAME);
 
 		let response = self
 			.inner
@@ -169,11 +190,41 @@ impl CandidateDataSourceCached {
 			.await?;
 		if let Ok(mut cache) = self.get_ariadne_parameters_for_epoch_cache.lock() {
 			log::debug!("Caching ariadne parameters for epoch: {:?}", epoch.0);
-			cache.put(key, response.clone());
+			self.put_to_cache(&mut cache, key, response.clone(), ARIADNE_PARAMETERS_CACHE_NAME);
 		}
 		Ok(response)
 	}
 
+	fn metrics_opt(&self) -> Option<&McFollowerMetrics> {
+		self.inner.metrics_opt.as_ref()
+	}
+
+	fn record_cache_hit(&self, cache_name: &str) {
+		if let Some(metrics) = self.metrics_opt() {
+			metrics.cache_hit(cache_name);
+		}
+	}
+
+	fn record_cache_miss(&self, cache_name: &str) {
+		if let Some(metrics) = self.metrics_opt() {
+			metrics.cache_miss(cache_name);
+		}
+	}
+
+	fn put_to_cache<K: Hash + Eq, V>(
+		&self,
+		cache: &mut LruCache<K, V>,
+		key: K,
+		value: V,
+		cache_name: &str,
+	) {
+		let evicted = cache.len() == cache.cap().get() && !cache.contains(&key);
+		cache.put(key, value);
+		if let Some(metrics) = self.metrics_opt().filter(|_| evicted) {
+			metrics.cache_evicted(cache_name, 1);
+		}
+	}
+
 	async fn can_use_caching_for_request(
 		&self,
 		request_epoch: McEpochNumber,
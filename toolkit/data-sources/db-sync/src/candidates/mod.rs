@@ -2,9 +2,10 @@
 use crate::DataSourceError::*;
 use crate::db_model::{
 	self, Address, Asset, BlockNumber, DbSyncConfigurationProvider, EpochNumber, MainchainTxOutput,
-	StakePoolEntry,
+	StakePoolEntry, TokenStakeOutput,
 };
 use authority_selection_inherents::*;
+use cardano_serialization_lib::PlutusData;
 use itertools::Itertools;
 use log::error;
 use partner_chains_data_source_metrics::{McFollowerMetrics, observed_async_trait};
@@ -31,11 +32,10 @@ struct ParsedCandidate {
 
 #[derive(Debug)]
 struct RegisteredCandidate {
-	stake_pool_pub_key: StakePoolPublicKey,
+	stake_ownership: StakeOwnership,
 	registration_utxo: UtxoId,
 	tx_inputs: Vec<UtxoId>,
 	sidechain_signature: SidechainSignature,
-	mainchain_signature: MainchainSignature,
 	cross_chain_signature: CrossChainSignature,
 	sidechain_pub_key: SidechainPublicKey,
 	cross_chain_pub_key: CrossChainPublicKey,
@@ -54,6 +54,7 @@ pub struct CandidatesDataSourceImpl {
 }
 
 observed_async_trait!(
+error_kind = crate::error_kind;
 impl AuthoritySelectionDataSource for CandidatesDataSourceImpl {
 	async fn get_ariadne_parameters(
 			&self,
@@ -100,15 +101,33 @@ impl AuthoritySelectionDataSource for CandidatesDataSourceImpl {
 		let epoch = EpochNumber::from(self.get_epoch_of_data_storage(epoch)?);
 		let candidates = self.get_registered_candidates(epoch, committee_candidate_address).await?;
 		let stake_map = Self::make_stake_map(db_model::get_stake_distribution(&self.pool, epoch).await?);
-		Ok(Self::group_candidates_by_mc_pub_key(candidates).into_iter().map(|(mainchain_pub_key, candidate_registrations)| {
+		Ok(Self::group_candidates_by_mc_pub_key(candidates).into_iter().map(|(mainchain_pub_key, registrations)| {
 			CandidateRegistrations {
 				stake_pool_public_key: mainchain_pub_key.clone(),
-				registrations: candidate_registrations.into_iter().map(Self::make_registration_data).collect(),
+				registrations,
 				stake_delegation: Self::get_stake_delegation(&stake_map, &mainchain_pub_key),
 			}
 		}).collect())
 	}
 
+	async fn get_token_staked_candidates(
+			&self,
+			epoch: McEpochNumber,
+			committee_candidate_address: MainchainAddress,
+			token_staking: TokenStakingConfig
+	) -> Result<Vec<TokenStakedCandidateRegistrations>, Box<dyn std::error::Error + Send + Sync>> {
+		let epoch = EpochNumber::from(self.get_epoch_of_data_storage(epoch)?);
+		let candidates = self.get_registered_candidates(epoch, committee_candidate_address).await?;
+		let token_stake_map = self.get_token_stake_map(epoch, &token_staking).await?;
+		Ok(Self::group_token_staked_candidates_by_sidechain_pub_key(candidates).into_iter().map(|(sidechain_pub_key, registrations)| {
+			TokenStakedCandidateRegistrations {
+				stake: Self::get_token_stake(&token_stake_map, &sidechain_pub_key),
+				sidechain_pub_key,
+				registrations,
+			}
+		}).collect())
+	}
+
 	async fn get_epoch_nonce(&self, epoch: McEpochNumber) -> Result<Option<EpochNonce>, Box<dyn std::error::Error + Send + Sync>> {
 		let epoch = self.get_epoch_of_data_storage(epoch)?;
 		let nonce = db_model::get_epoch_nonce(&self.pool, EpochNumber(epoch.0)).await?;
@@ -126,8 +145,8 @@ impl CandidatesDataSourceImpl {
 		pool: PgPool,
 		metrics_opt: Option<McFollowerMetrics>,
 	) -> Result<CandidatesDataSourceImpl, Box<dyn std::error::Error + Send + Sync>> {
-		db_model::create_idx_ma_tx_out_ident(&pool).await?;
-		db_model::create_idx_tx_out_address(&pool).await?;
+		db_model::create_idx_ma_tx_out_ident(&pool, metrics_opt.as_ref()).await?;
+		db_model::create_idx_tx_out_address(&pool, metrics_opt.as_ref()).await?;
 		Ok(Self {
 			pool: pool.clone(),
 			metrics_opt,
@@ -174,17 +193,71 @@ impl CandidatesDataSourceImpl {
 		self.convert_utxos_to_candidates(&active_utxos)
 	}
 
+	/// Returns the token stake of all token stakers as of the last block of `epoch`,
+	/// or `None` if there is no such block yet.
+	async fn get_token_stake_map(
+		&self,
+		epoch: EpochNumber,
+		token_staking: &TokenStakingConfig,
+	) -> Result<
+		Option<HashMap<SidechainPublicKey, StakeDelegation>>,
+		Box<dyn std::error::Error + Send + Sync>,
+	> {
+		let Some(block) = self.get_last_block_for_epoch(epoch).await? else { return Ok(None) };
+		let outputs = db_model::get_token_stake_outputs(
+			&self.pool,
+			&Address(token_staking.staking_address.to_string()),
+			block,
+			&token_staking.staking_token.clone().into(),
+			self.db_sync_config.get_tx_in_config().await?,
+		)
+		.await?;
+		Ok(Some(Self::make_token_stake_map(outputs)))
+	}
+
 	fn group_candidates_by_mc_pub_key(
 		candidates: Vec<RegisteredCandidate>,
-	) -> HashMap<StakePoolPublicKey, Vec<RegisteredCandidate>> {
-		candidates.into_iter().into_group_map_by(|c| c.stake_pool_pub_key.clone())
+	) -> HashMap<StakePoolPublicKey, Vec<RegistrationData>> {
+		candidates
+			.into_iter()
+			.filter_map(|c| match c.stake_ownership.clone() {
+				StakeOwnership::AdaBased(staking) => {
+					Some((staking.pub_key, Self::make_registration_data(c, staking.signature)))
+				},
+				StakeOwnership::TokenBased => None,
+			})
+			.into_group_map()
 	}
 
-	fn make_registration_data(c: RegisteredCandidate) -> RegistrationData {
+	fn group_token_staked_candidates_by_sidechain_pub_key(
+		candidates: Vec<RegisteredCandidate>,
+	) -> HashMap<SidechainPublicKey, Vec<TokenStakedRegistrationData>> {
+		candidates
+			.into_iter()
+			.filter(|c| c.stake_ownership == StakeOwnership::TokenBased)
+			.map(|c| {
+				(
+					c.sidechain_pub_key,
+					TokenStakedRegistrationData {
+						registration_utxo: c.registration_utxo,
+						sidechain_signature: c.sidechain_signature,
+						utxo_info: c.utxo_info,
+						tx_inputs: c.tx_inputs,
+						keys: c.keys,
+					},
+				)
+			})
+			.into_group_map()
+	}
+
+	fn make_registration_data(
+		c: RegisteredCandidate,
+		mainchain_signature: MainchainSignature,
+	) -> RegistrationData {
 		RegistrationData {
 			registration_utxo: c.registration_utxo,
 			sidechain_signature: c.sidechain_signature,
-			mainchain_signature: c.mainchain_signature,
+			mainchain_signature,
 			cross_chain_signature: c.cross_chain_signature,
 			sidechain_pub_key: c.sidechain_pub_key,
 			cross_chain_pub_key: c.cross_chain_pub_key,
@@ -203,6 +276,32 @@ impl CandidatesDataSourceImpl {
 			.collect()
 	}
 
+	/// Sums token amounts per candidate. Datum of a token stake UTXO is the Partner Chain public key
+	/// of the candidate, UTXOs with other datums are ignored.
+	fn make_token_stake_map(
+		outputs: Vec<TokenStakeOutput>,
+	) -> HashMap<SidechainPublicKey, StakeDelegation> {
+		let mut stake_map = HashMap::new();
+		for output in outputs {
+			let Some(staker) = decode_token_staker(&output.datum.0) else {
+				error!("Invalid token stake datum: {:?}", output.datum);
+				continue;
+			};
+			let amount = u64::try_from(output.quantity.0).unwrap_or(u64::MAX);
+			let stake = stake_map.entry(staker).or_insert(StakeDelegation(0));
+			stake.0 = stake.0.saturating_add(amount);
+		}
+		stake_map
+	}
+
+	fn get_token_stake(
+		token_stake_map: &Option<HashMap<SidechainPublicKey, StakeDelegation>>,
+		sidechain_pub_key: &SidechainPublicKey,
+	) -> Option<StakeDelegation> {
+		let token_stake_map = token_stake_map.as_ref()?;
+		Some(token_stake_map.get(sidechain_pub_key).cloned().unwrap_or(StakeDelegation(0)))
+	}
+
 	fn get_stake_delegation(
 		stake_map: &HashMap<MainchainKeyHash, StakeDelegation>,
 		stake_pool_pub_key: &StakePoolPublicKey,
@@ -237,8 +336,7 @@ impl CandidatesDataSourceImpl {
 						aura_pub_key,
 						grandpa_pub_key,
 					} => Ok(RegisteredCandidate {
-						stake_pool_pub_key: stake_ownership.pub_key,
-						mainchain_signature: stake_ownership.signature,
+						stake_ownership,
 						// For now we use the same key for both cross chain and sidechain actions
 						cross_chain_pub_key: CrossChainPublicKey(sidechain_pub_key.0.clone()),
 						cross_chain_signature: CrossChainSignature(sidechain_signature.0.clone()),
@@ -257,8 +355,7 @@ impl CandidatesDataSourceImpl {
 						own_pkh: _own_pkh,
 						keys,
 					} => Ok(RegisteredCandidate {
-						stake_pool_pub_key: stake_ownership.pub_key,
-						mainchain_signature: stake_ownership.signature,
+						stake_ownership,
 						// For now we use the same key for both cross chain and sidechain actions
 						cross_chain_pub_key: CrossChainPublicKey(sidechain_pub_key.0.clone()),
 						cross_chain_signature: CrossChainSignature(sidechain_signature.0.clone()),
@@ -324,3 +421,7 @@ impl CandidatesDataSourceImpl {
 		})
 	}
 }
+
+fn decode_token_staker(datum: &PlutusData) -> Option<SidechainPublicKey> {
+	Some(SidechainPublicKey(datum.as_bytes()?))
+}
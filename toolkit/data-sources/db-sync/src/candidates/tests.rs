@@ -447,3 +447,47 @@ fn leader_candidate_spo_c() -> CandidateRegistrations {
 			stake_delegation: Some(StakeDelegation(123456789)),
 		}
 }
+
+#[test]
+fn make_token_stake_map_sums_token_amounts_per_candidate() {
+	use crate::db_datum::DbDatum;
+	use crate::db_model::{NativeTokenAmount, TokenStakeOutput};
+	use cardano_serialization_lib::{PlutusData, PlutusList};
+
+	let candidate_a = SidechainPublicKey(
+		hex!("02dbfc8b66c22f931a6647fd86db2fc073dd564b99837226a1bdfe7a99578854ec").to_vec(),
+	);
+	let candidate_b = SidechainPublicKey(
+		hex!("03b827f4da9711bab7292e5695576a841a4d20af9a07b1ba7a230168d2a78e9df4").to_vec(),
+	);
+	let output = |candidate: &SidechainPublicKey, quantity: u128| TokenStakeOutput {
+		datum: DbDatum(PlutusData::new_bytes(candidate.0.clone())),
+		quantity: NativeTokenAmount(quantity),
+	};
+	let invalid_datum_output = TokenStakeOutput {
+		datum: DbDatum(PlutusData::new_list(&PlutusList::new())),
+		quantity: NativeTokenAmount(1000),
+	};
+
+	let token_stake_map = Some(CandidatesDataSourceImpl::make_token_stake_map(vec![
+		output(&candidate_a, 100),
+		output(&candidate_a, 20),
+		output(&candidate_b, 7),
+		invalid_datum_output,
+	]));
+
+	let candidate_c = SidechainPublicKey(vec![0; 33]);
+	assert_eq!(
+		CandidatesDataSourceImpl::get_token_stake(&token_stake_map, &candidate_a),
+		Some(StakeDelegation(120))
+	);
+	assert_eq!(
+		CandidatesDataSourceImpl::get_token_stake(&token_stake_map, &candidate_b),
+		Some(StakeDelegation(7))
+	);
+	assert_eq!(
+		CandidatesDataSourceImpl::get_token_stake(&token_stake_map, &candidate_c),
+		Some(StakeDelegation(0))
+	);
+	assert_eq!(CandidatesDataSourceImpl::get_token_stake(&None, &candidate_a), None);
+}
@@ -5,6 +5,8 @@ use cardano_serialization_lib::PlutusData;
 use chrono::NaiveDateTime;
 pub use db_sync_sqlx::*;
 use log::info;
+#[cfg(any(feature = "candidate-source", feature = "governed-map"))]
+use partner_chains_data_source_metrics::McFollowerMetrics;
 use sidechain_domain::{
 	MainchainBlock, McBlockHash, McBlockNumber, McEpochNumber, McSlotNumber, McTxHash, UtxoId,
 	UtxoIndex,
@@ -154,6 +156,13 @@ pub(crate) struct StakePoolEntry {
 	pub stake: StakeDelegation,
 }
 
+#[cfg(feature = "candidate-source")]
+#[derive(Debug, Clone, sqlx::FromRow, PartialEq)]
+pub(crate) struct TokenStakeOutput {
+	pub datum: DbDatum,
+	pub quantity: NativeTokenAmount,
+}
+
 #[derive(Debug, Clone, sqlx::FromRow, PartialEq)]
 pub(crate) struct TokenTxOutput {
 	pub origin_tx_hash: [u8; 32],
@@ -762,10 +771,101 @@ pub(crate) async fn get_utxos_for_address_tx_in_consumed(
 		rows.into_iter().map(MainchainTxOutput::try_from).collect();
 	Ok(result?)
 }
+
+/// Returns datums and amounts of `asset` of all UTXOs at `address` that were unspent at `block`.
+#[cfg(feature = "candidate-source")]
+pub(crate) async fn get_token_stake_outputs(
+	pool: &Pool<Postgres>,
+	address: &Address,
+	block: BlockNumber,
+	asset: &Asset,
+	tx_in_configuration: TxInConfiguration,
+) -> Result<Vec<TokenStakeOutput>, SqlxError> {
+	match tx_in_configuration {
+		TxInConfiguration::Enabled => {
+			get_token_stake_outputs_tx_in_enabled(pool, address, block, asset).await
+		},
+		TxInConfiguration::Consumed => {
+			get_token_stake_outputs_tx_in_consumed(pool, address, block, asset).await
+		},
+	}
+}
+
+#[cfg(feature = "candidate-source")]
+pub(crate) async fn get_token_stake_outputs_tx_in_enabled(
+	pool: &Pool<Postgres>,
+	address: &Address,
+	block: BlockNumber,
+	asset: &Asset,
+) -> Result<Vec<TokenStakeOutput>, SqlxError> {
+	let query = "
+			SELECT
+				datum.value as datum,
+				ma_tx_out.quantity as quantity
+			FROM tx_out
+			INNER JOIN tx origin_tx			ON tx_out.tx_id = origin_tx.id
+			INNER JOIN block origin_block	ON origin_tx.block_id = origin_block.id
+			LEFT JOIN tx_in consuming_tx_in	ON tx_out.tx_id = consuming_tx_in.tx_out_id AND tx_out.index = consuming_tx_in.tx_out_index
+			LEFT JOIN tx consuming_tx		ON consuming_tx_in.tx_in_id = consuming_tx.id
+			LEFT JOIN block consuming_block	ON consuming_tx.block_id = consuming_block.id
+			INNER JOIN datum				ON tx_out.data_hash = datum.hash
+			INNER JOIN ma_tx_out			ON tx_out.id = ma_tx_out.tx_out_id
+			INNER JOIN multi_asset			ON multi_asset.id = ma_tx_out.ident
+			WHERE
+				tx_out.address = $1 AND origin_block.block_no <= $2
+				AND (consuming_tx_in.id IS NULL OR consuming_block.block_no > $2)
+				AND multi_asset.policy = $3
+				AND multi_asset.name = $4";
+	Ok(sqlx::query_as::<_, TokenStakeOutput>(query)
+		.bind(&address.0)
+		.bind(block)
+		.bind(&asset.policy_id.0)
+		.bind(&asset.asset_name.0)
+		.fetch_all(pool)
+		.await?)
+}
+
+#[cfg(feature = "candidate-source")]
+pub(crate) async fn get_token_stake_outputs_tx_in_consumed(
+	pool: &Pool<Postgres>,
+	address: &Address,
+	block: BlockNumber,
+	asset: &Asset,
+) -> Result<Vec<TokenStakeOutput>, SqlxError> {
+	let query = "
+			SELECT
+				datum.value as datum,
+				ma_tx_out.quantity as quantity
+			FROM tx_out
+			INNER JOIN tx origin_tx			ON tx_out.tx_id = origin_tx.id
+			INNER JOIN block origin_block	ON origin_tx.block_id = origin_block.id
+			LEFT JOIN tx consuming_tx		ON tx_out.consumed_by_tx_id = consuming_tx.id
+			LEFT JOIN block consuming_block	ON consuming_tx.block_id = consuming_block.id
+			INNER JOIN datum				ON tx_out.data_hash = datum.hash
+			INNER JOIN ma_tx_out			ON tx_out.id = ma_tx_out.tx_out_id
+			INNER JOIN multi_asset			ON multi_asset.id = ma_tx_out.ident
+			WHERE
+				tx_out.address = $1 AND origin_block.block_no <= $2
+				AND (tx_out.consumed_by_tx_id IS NULL OR consuming_block.block_no > $2)
+				AND multi_asset.policy = $3
+				AND multi_asset.name = $4";
+	Ok(sqlx::query_as::<_, TokenStakeOutput>(query)
+		.bind(&address.0)
+		.bind(block)
+		.bind(&asset.policy_id.0)
+		.bind(&asset.asset_name.0)
+		.fetch_all(pool)
+		.await?)
+}
+
 /// Used by `get_token_utxo_for_epoch` (CandidatesDataSourceImpl),
 #[cfg(feature = "candidate-source")]
-pub(crate) async fn create_idx_ma_tx_out_ident(pool: &Pool<Postgres>) -> Result<(), SqlxError> {
+pub(crate) async fn create_idx_ma_tx_out_ident(
+	pool: &Pool<Postgres>,
+	metrics_opt: Option<&McFollowerMetrics>,
+) -> Result<(), SqlxError> {
 	let exists = index_exists(pool, "idx_ma_tx_out_ident").await?;
+	set_index_present(metrics_opt, "idx_ma_tx_out_ident", exists);
 	if exists {
 		info!("Index 'idx_ma_tx_out_ident' already exists");
 	} else {
@@ -773,14 +873,19 @@ pub(crate) async fn create_idx_ma_tx_out_ident(pool: &Pool<Postgres>) -> Result<
 		info!("Executing '{}', this might take a while", sql);
 		sqlx::query(sql).execute(pool).await?;
 		info!("Index 'idx_ma_tx_out_ident' has been created");
+		set_index_present(metrics_opt, "idx_ma_tx_out_ident", true);
 	}
 	Ok(())
 }
 
 /// Used by multiple queries across functionalities.
 #[cfg(any(feature = "candidate-source", feature = "governed-map"))]
-pub(crate) async fn create_idx_tx_out_address(pool: &Pool<Postgres>) -> Result<(), SqlxError> {
+pub(crate) async fn create_idx_tx_out_address(
+	pool: &Pool<Postgres>,
+	metrics_opt: Option<&McFollowerMetrics>,
+) -> Result<(), SqlxError> {
 	let exists = index_exists(pool, "idx_tx_out_address").await?;
+	set_index_present(metrics_opt, "idx_tx_out_address", exists);
 	if exists {
 		info!("Index 'idx_tx_out_address' already exists");
 	} else {
@@ -788,10 +893,22 @@ pub(crate) async fn create_idx_tx_out_address(pool: &Pool<Postgres>) -> Result<(
 		info!("Executing '{}', this might take a long time", sql);
 		sqlx::query(sql).execute(pool).await?;
 		info!("Index 'idx_tx_out_address' has been created");
+		set_index_present(metrics_opt, "idx_tx_out_address", true);
 	}
 	Ok(())
 }
 
+/// Reports presence of the index in the `db_sync_index_present` metric.
+#[cfg(any(feature = "candidate-source", feature = "governed-map"))]
+fn set_index_present(metrics_opt: Option<&McFollowerMetrics>, index_name: &str, present: bool) {
+	if let Some(metrics) = metrics_opt {
+		metrics
+			.db_sync_index_present()
+			.with_label_values(&[index_name])
+			.set(present as u64);
+	}
+}
+
 /// Check if the index exists.
 async fn index_exists(pool: &Pool<Postgres>, index_name: &str) -> Result<bool, sqlx::Error> {
 	sqlx::query("select * from pg_indexes where indexname = $1")
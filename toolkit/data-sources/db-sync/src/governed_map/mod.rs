@@ -18,6 +18,8 @@ use std::sync::{Arc, Mutex};
 #[cfg(test)]
 mod tests;
 
+const CACHE_NAME: &str = "governed_map";
+
 /// Data source for the Governed Map feature of Partner Chains toolkit
 ///
 /// See documentation of [sp_governed_map] for a description of the feature
@@ -36,7 +38,7 @@ impl GovernedMapDataSourceImpl {
 		pool: PgPool,
 		metrics_opt: Option<McFollowerMetrics>,
 	) -> std::result::Result<Self, Box<dyn std::error::Error + Send + Sync>> {
-		crate::db_model::create_idx_tx_out_address(&pool).await?;
+		crate::db_model::create_idx_tx_out_address(&pool, metrics_opt.as_ref()).await?;
 		Ok(Self {
 			pool: pool.clone(),
 			metrics_opt,
@@ -46,6 +48,7 @@ impl GovernedMapDataSourceImpl {
 }
 
 observed_async_trait!(
+error_kind = crate::error_kind;
 impl GovernedMapDataSource for GovernedMapDataSourceImpl {
 	async fn get_state_at_block(
 		&self,
@@ -151,7 +154,7 @@ impl GovernedMapDataSourceCachedImpl {
 		cache_size: u16,
 		blocks: Arc<BlockDataSourceImpl>,
 	) -> std::result::Result<Self, Box<dyn std::error::Error + Send + Sync>> {
-		crate::db_model::create_idx_tx_out_address(&pool).await?;
+		crate::db_model::create_idx_tx_out_address(&pool, metrics_opt.as_ref()).await?;
 		let cache = Default::default();
 		Ok(Self {
 			pool: pool.clone(),
@@ -165,6 +168,7 @@ impl GovernedMapDataSourceCachedImpl {
 }
 
 observed_async_trait!(
+error_kind = crate::error_kind;
 impl GovernedMapDataSource for GovernedMapDataSourceCachedImpl {
 	async fn get_state_at_block(
 		&self,
@@ -207,8 +211,14 @@ impl GovernedMapDataSource for GovernedMapDataSourceCachedImpl {
 		if let Some(cached_changes) =
 			self.get_changes_from_cache(since_block_number, up_to_block_number).await?
 		{
+			if let Some(metrics) = &self.metrics_opt {
+				metrics.cache_hit(CACHE_NAME);
+			}
 			return Ok(cached_changes);
 		}
+		if let Some(metrics) = &self.metrics_opt {
+			metrics.cache_miss(CACHE_NAME);
+		}
 
 		let latest_block_timestamp = self.blocks.get_latest_block_info().await?.timestamp;
 		let latest_stable_block =
@@ -224,7 +234,10 @@ impl GovernedMapDataSource for GovernedMapDataSourceCachedImpl {
 			.await?;
 
 		if let Ok(mut cache) = self.cache.lock() {
-			cache.update(changes.clone());
+			let evicted = cache.update(changes.clone());
+			if let Some(metrics) = &self.metrics_opt {
+				metrics.cache_evicted(CACHE_NAME, evicted);
+			}
 		}
 
 		Ok(filter_changes_in_range(changes, since_block_number, up_to_block_number))
@@ -335,7 +348,9 @@ impl Cache {
 		Some(filter_changes_in_range(self.changes.clone(), since_block, up_to_block))
 	}
 
-	fn update(&mut self, changes: Vec<Change>) {
+	/// Replaces cached changes, returning the number of evicted ones
+	fn update(&mut self, changes: Vec<Change>) -> u64 {
+		let evicted = self.changes.len() as u64;
 		self.changes = changes;
 		let (lowest_block_number, highest_block_number) = self
 			.changes
@@ -346,6 +361,7 @@ impl Cache {
 			.unwrap_or((BlockNumber(0), BlockNumber(0)));
 		self.lowest_block_number = Some(lowest_block_number);
 		self.highest_block_number = Some(highest_block_number);
+		evicted
 	}
 
 	fn set_main_chain_scripts(&mut self, scripts: MainChainScriptsV1) {
@@ -175,6 +175,40 @@ pub enum DataSourceError {
 	InvalidData(String),
 }
 
+/// Returns the kind of an error returned by Db-Sync data sources, used as a metrics label
+#[cfg(any(
+	feature = "bridge",
+	feature = "candidate-source",
+	feature = "governed-map",
+	feature = "mc-hash",
+	feature = "sidechain-rpc",
+	feature = "block-participation"
+))]
+pub(crate) fn error_kind(error: &(dyn std::error::Error + Send + Sync + 'static)) -> &'static str {
+	if let Some(error) = error.downcast_ref::<DataSourceError>() {
+		match error {
+			DataSourceError::BadRequest(_) => "BadRequest",
+			DataSourceError::InternalDataSourceError(_) => "InternalDataSourceError",
+			DataSourceError::ExpectedDataNotFound(_) => "ExpectedDataNotFound",
+			DataSourceError::InvalidData(_) => "InvalidData",
+		}
+	} else if let Some(error) = error.downcast_ref::<sqlx::Error>() {
+		match error {
+			sqlx::Error::Database(_) => "Database",
+			sqlx::Error::Io(_) => "Io",
+			sqlx::Error::Tls(_) => "Tls",
+			sqlx::Error::Protocol(_) => "Protocol",
+			sqlx::Error::RowNotFound => "RowNotFound",
+			sqlx::Error::ColumnDecode { .. } | sqlx::Error::Decode(_) => "Decode",
+			sqlx::Error::PoolTimedOut => "PoolTimedOut",
+			sqlx::Error::PoolClosed => "PoolClosed",
+			_ => "Sqlx",
+		}
+	} else {
+		"other"
+	}
+}
+
 /// Result type used by Db-Sync data sources
 pub(crate) type Result<T> = std::result::Result<T, DataSourceError>;
 
@@ -209,6 +243,28 @@ mod tests {
 		}
 	}
 
+	#[cfg(any(
+		feature = "bridge",
+		feature = "candidate-source",
+		feature = "governed-map",
+		feature = "mc-hash",
+		feature = "sidechain-rpc",
+		feature = "block-participation"
+	))]
+	#[test]
+	fn error_kind_is_the_variant_of_the_boxed_error() {
+		use crate::{DataSourceError, error_kind};
+		type BoxedError = Box<dyn std::error::Error + Send + Sync>;
+
+		let not_found: BoxedError = DataSourceError::ExpectedDataNotFound("block".into()).into();
+		let timed_out: BoxedError = sqlx::Error::PoolTimedOut.into();
+		let other: BoxedError = "connection refused".into();
+
+		assert_eq!(error_kind(&*not_found), "ExpectedDataNotFound");
+		assert_eq!(error_kind(&*timed_out), "PoolTimedOut");
+		assert_eq!(error_kind(&*other), "other");
+	}
+
 	#[dtor]
 	fn on_shutdown() {
 		let (tx, rx) = mpsc::channel();
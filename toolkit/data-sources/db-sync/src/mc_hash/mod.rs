@@ -24,15 +24,26 @@ impl McHashDataSourceImpl {
 }
 
 observed_async_trait!(
+error_kind = crate::error_kind;
 impl McHashDataSource for McHashDataSourceImpl {
 	async fn get_latest_stable_block_for(
 		&self,
 		reference_timestamp: sp_timestamp::Timestamp,
 	) -> std::result::Result<Option<MainchainBlock>, Box<dyn std::error::Error + Send + Sync>> {
-		Ok(self
+		let (block, tip) = self
 			.inner
-			.get_latest_stable_block_for(Timestamp::new(reference_timestamp.as_millis()))
-			.await?)
+			.get_latest_stable_block_and_tip_for(Timestamp::new(reference_timestamp.as_millis()))
+			.await?;
+		if let Some(metrics) = &self.metrics_opt {
+			metrics.observe_mc_tip(tip.timestamp);
+			if let Some(block) = &block {
+				let reference_secs = reference_timestamp.as_millis() / 1000;
+				metrics
+					.mc_stable_block_lag_seconds()
+					.set(reference_secs.saturating_sub(block.timestamp));
+			}
+		}
+		Ok(block)
 	}
 
 	async fn get_stable_block_for(
@@ -52,5 +63,11 @@ impl McHashDataSource for McHashDataSourceImpl {
 	) -> std::result::Result<Option<MainchainBlock>, Box<dyn std::error::Error + Send + Sync>> {
 		Ok(self.inner.get_block_by_hash(hash).await?)
 	}
+
+	async fn get_tip(
+		&self,
+	) -> std::result::Result<Option<MainchainBlock>, Box<dyn std::error::Error + Send + Sync>> {
+		Ok(Some(self.inner.get_latest_block_info().await?))
+	}
 }
 );
@@ -21,11 +21,16 @@ impl SidechainRpcDataSourceImpl {
 }
 
 observed_async_trait!(
+error_kind = crate::error_kind;
 impl SidechainRpcDataSource for SidechainRpcDataSourceImpl {
 	async fn get_latest_block_info(
 		&self,
 	) -> Result<MainchainBlock, Box<dyn std::error::Error + Send + Sync>> {
-		self.inner.get_latest_block_info().await
+		let block = self.inner.get_latest_block_info().await?;
+		if let Some(metrics) = &self.metrics_opt {
+			metrics.observe_mc_tip(block.timestamp);
+		}
+		Ok(block)
 	}
 }
 );
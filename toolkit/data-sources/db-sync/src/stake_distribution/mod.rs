@@ -29,6 +29,7 @@ impl StakeDistributionDataSourceImpl {
 }
 
 observed_async_trait!(
+error_kind = crate::error_kind;
 impl BlockParticipationDataSource for StakeDistributionDataSourceImpl {
 	async fn get_stake_pool_delegation_distribution_for_pools(
 		&self,
@@ -46,6 +47,14 @@ impl BlockParticipationDataSource for StakeDistributionDataSourceImpl {
 				None => pool_hashes_to_query.push(pool_hash.0),
 			}
 		}
+		if let Some(metrics) = &self.metrics_opt {
+			let hits = pool_hashes.len() - pool_hashes_to_query.len();
+			metrics.cache_hit_count().with_label_values(&[CACHE_NAME]).inc_by(hits as u64);
+			metrics
+				.cache_miss_count()
+				.with_label_values(&[CACHE_NAME])
+				.inc_by(pool_hashes_to_query.len() as u64);
+		}
 		let rows = crate::db_model::get_stake_pool_delegations_for_pools(
 			&self.pool,
 			EpochNumber::from(epoch),
@@ -53,7 +62,10 @@ impl BlockParticipationDataSource for StakeDistributionDataSourceImpl {
 		)
 		.await?;
 		let mut queried_pool_delegations = rows_to_distribution(rows);
-		self.cache.put_distribution_for_pools(epoch, queried_pool_delegations.clone());
+		let evicted = self.cache.put_distribution_for_pools(epoch, queried_pool_delegations.clone());
+		if let Some(metrics) = &self.metrics_opt {
+			metrics.cache_evicted(CACHE_NAME, evicted);
+		}
 		stake_distribution.append(&mut queried_pool_delegations.0);
 		Ok(StakeDistribution(stake_distribution))
 	}
@@ -96,6 +108,8 @@ fn get_delegator_key(row: &StakePoolDelegationOutputRow) -> Result<DelegatorKey,
 	}
 }
 
+const CACHE_NAME: &str = "stake_distribution";
+
 type DistributionPerPoolCacheKey = (McEpochNumber, MainchainKeyHash);
 struct Cache {
 	distribution_per_pool_cache: Arc<Mutex<LruCache<DistributionPerPoolCacheKey, PoolDelegation>>>,
@@ -122,15 +136,23 @@ impl Cache {
 		}
 	}
 
+	/// Returns the number of evicted cache entries
 	fn put_distribution_for_pools(
 		&self,
 		epoch: McEpochNumber,
 		stake_distribution: StakeDistribution,
-	) {
+	) -> u64 {
+		let mut evicted = 0;
 		if let Ok(mut cache) = self.distribution_per_pool_cache.lock() {
 			for (pool_hash, pool_delegation) in stake_distribution.0 {
-				cache.put((epoch, pool_hash), pool_delegation);
+				let key = (epoch, pool_hash);
+				if let Some((evicted_key, _)) = cache.push(key, pool_delegation) {
+					if evicted_key != key {
+						evicted += 1;
+					}
+				}
 			}
 		}
+		evicted
 	}
 }
@@ -1,6 +1,9 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-use crate::{client::*, *};
+use crate::{
+	client::{minibf::format_asset_id, *},
+	*,
+};
 use async_trait::async_trait;
 use authority_selection_inherents::*;
 use blockfrost_openapi::models::{
@@ -121,19 +124,57 @@ impl AuthoritySelectionDataSource for AuthoritySelectionDataSourceImpl {
 
 		Ok(candidates
 			.into_iter()
-			.into_group_map_by(|c| c.stake_pool_pub_key.clone())
+			.filter_map(|c| match c.stake_ownership.clone() {
+				StakeOwnership::AdaBased(staking) => {
+					Some((staking.pub_key, Self::make_registration_data(c, staking.signature)))
+				},
+				StakeOwnership::TokenBased => None,
+			})
+			.into_group_map()
 			.into_iter()
-			.map(|(mainchain_pub_key, candidate_registrations)| CandidateRegistrations {
+			.map(|(mainchain_pub_key, registrations)| CandidateRegistrations {
 				stake_pool_public_key: mainchain_pub_key.clone(),
-				registrations: candidate_registrations
-					.into_iter()
-					.map(Self::make_registration_data)
-					.collect(),
+				registrations,
 				stake_delegation: Self::get_stake_delegation(&stake_map, &mainchain_pub_key),
 			})
 			.collect())
 	}
 
+	async fn get_token_staked_candidates(
+		&self,
+		epoch_number: McEpochNumber,
+		committee_candidate_address: MainchainAddress,
+		token_staking: TokenStakingConfig,
+	) -> Result<Vec<TokenStakedCandidateRegistrations>> {
+		let epoch = self.get_epoch_of_data_storage(epoch_number)?;
+		let candidates = self.get_registered_candidates(epoch, committee_candidate_address).await?;
+		let token_stake_map = self.get_token_stake_map(epoch, &token_staking).await?;
+
+		Ok(candidates
+			.into_iter()
+			.filter(|c| c.stake_ownership == StakeOwnership::TokenBased)
+			.map(|c| {
+				(
+					c.sidechain_pub_key,
+					TokenStakedRegistrationData {
+						registration_utxo: c.registration_utxo,
+						sidechain_signature: c.sidechain_signature,
+						utxo_info: c.utxo_info,
+						tx_inputs: c.tx_inputs,
+						keys: c.keys,
+					},
+				)
+			})
+			.into_group_map()
+			.into_iter()
+			.map(|(sidechain_pub_key, registrations)| TokenStakedCandidateRegistrations {
+				stake: Self::get_token_stake(&token_stake_map, &sidechain_pub_key),
+				sidechain_pub_key,
+				registrations,
+			})
+			.collect())
+	}
+
 	async fn get_epoch_nonce(&self, epoch_number: McEpochNumber) -> Result<Option<EpochNonce>> {
 		let epoch = self.get_epoch_of_data_storage(epoch_number)?;
 		let nonce: String = self.client.epochs_parameters(epoch).await?.nonce;
@@ -145,6 +186,12 @@ impl AuthoritySelectionDataSource for AuthoritySelectionDataSourceImpl {
 	}
 }
 
+/// Datum of a token stake UTXO is the Partner Chain public key of the candidate
+fn decode_token_staker(datum_hex: &str) -> Option<SidechainPublicKey> {
+	let datum = PlutusData::from_hex(datum_hex).ok()?;
+	Some(SidechainPublicKey(datum.as_bytes()?))
+}
+
 fn mckeyhash_from_bech32(bech32_str: &str) -> Result<MainchainKeyHash> {
 	let (_hrp, val) = bech32::decode(bech32_str).map_err(|e| e.to_string())?;
 	Ok(MainchainKeyHash(val.try_into().map_err(|_| "failed to convert vec to array")?))
@@ -152,11 +199,10 @@ fn mckeyhash_from_bech32(bech32_str: &str) -> Result<MainchainKeyHash> {
 
 #[derive(Debug)]
 struct RegisteredCandidate {
-	stake_pool_pub_key: StakePoolPublicKey,
+	stake_ownership: StakeOwnership,
 	registration_utxo: UtxoId,
 	tx_inputs: Vec<UtxoId>,
 	sidechain_signature: SidechainSignature,
-	mainchain_signature: MainchainSignature,
 	cross_chain_signature: CrossChainSignature,
 	sidechain_pub_key: SidechainPublicKey,
 	cross_chain_pub_key: CrossChainPublicKey,
@@ -172,11 +218,14 @@ struct ParsedCandidate {
 }
 
 impl AuthoritySelectionDataSourceImpl {
-	fn make_registration_data(c: RegisteredCandidate) -> RegistrationData {
+	fn make_registration_data(
+		c: RegisteredCandidate,
+		mainchain_signature: MainchainSignature,
+	) -> RegistrationData {
 		RegistrationData {
 			registration_utxo: c.registration_utxo,
 			sidechain_signature: c.sidechain_signature,
-			mainchain_signature: c.mainchain_signature,
+			mainchain_signature,
 			cross_chain_signature: c.cross_chain_signature,
 			sidechain_pub_key: c.sidechain_pub_key,
 			cross_chain_pub_key: c.cross_chain_pub_key,
@@ -202,6 +251,83 @@ impl AuthoritySelectionDataSourceImpl {
 		}
 	}
 
+	fn get_token_stake(
+		token_stake_map: &Option<HashMap<SidechainPublicKey, StakeDelegation>>,
+		sidechain_pub_key: &SidechainPublicKey,
+	) -> Option<StakeDelegation> {
+		let token_stake_map = token_stake_map.as_ref()?;
+		Some(token_stake_map.get(sidechain_pub_key).cloned().unwrap_or(StakeDelegation(0)))
+	}
+
+	/// Returns the token stake of all candidates as of the last block of `epoch`,
+	/// or `None` if there is no such block yet.
+	///
+	/// Token stake is the sum of staking token amounts in UTXOs at the token staking address
+	/// that were unspent at the last block of the epoch, per candidate identified by the UTXO datum.
+	/// MiniBF only serves the current UTXO set, so the snapshot is rebuilt from the address
+	/// transactions included up to that block: UTXOs they created at the address, minus the ones they spent.
+	async fn get_token_stake_map(
+		&self,
+		epoch: McEpochNumber,
+		token_staking: &TokenStakingConfig,
+	) -> Result<Option<HashMap<SidechainPublicKey, StakeDelegation>>> {
+		let Some(last_block) = self.get_last_block_for_epoch(epoch).await? else {
+			return Ok(None);
+		};
+		let last_block_height =
+			last_block.height.ok_or("last_block_for_epoch block height missing")?;
+		let address = token_staking.staking_address.to_string();
+		let unit = format_asset_id(&token_staking.staking_token);
+		let txs = self
+			.client
+			.addresses_transactions(token_staking.staking_address.clone())
+			.await?;
+		let futures = txs.into_iter().filter(|tx| tx.block_height <= last_block_height).map(
+			|tx| async move {
+				self.client.transactions_utxos(McTxHash::from_hex_unsafe(&tx.tx_hash)).await
+			},
+		);
+		let txs_utxos = futures::future::try_join_all(futures).await?;
+
+		let spent: HashSet<(String, i32)> = (txs_utxos.iter())
+			.flat_map(|utxos| utxos.inputs.iter())
+			.filter(|input| input.address == address && !input.collateral)
+			.filter(|input| input.reference != Some(true))
+			.map(|input| (input.tx_hash.clone(), input.output_index))
+			.collect();
+
+		let mut stake_map = HashMap::new();
+		for utxos in txs_utxos.iter() {
+			for output in utxos.outputs.iter() {
+				if output.address != address
+					|| output.collateral
+					|| spent.contains(&(utxos.hash.clone(), output.output_index))
+				{
+					continue;
+				}
+				let amount: u128 = (output.amount.iter())
+					.filter(|a| a.unit == unit)
+					.map(|a| a.quantity.parse::<u128>())
+					.sum::<std::result::Result<u128, _>>()?;
+				if amount == 0 {
+					continue;
+				}
+				let Some(candidate) = output.inline_datum.as_deref().and_then(decode_token_staker)
+				else {
+					log::error!(
+						"Invalid token stake datum at {}#{}",
+						utxos.hash,
+						output.output_index
+					);
+					continue;
+				};
+				let stake = stake_map.entry(candidate).or_insert(StakeDelegation(0));
+				stake.0 = stake.0.saturating_add(u64::try_from(amount).unwrap_or(u64::MAX));
+			}
+		}
+		Ok(Some(stake_map))
+	}
+
 	// Converters
 	async fn convert_utxos_to_candidates(
 		&self,
@@ -221,8 +347,7 @@ impl AuthoritySelectionDataSourceImpl {
 						aura_pub_key,
 						grandpa_pub_key,
 					} => Ok(RegisteredCandidate {
-						stake_pool_pub_key: stake_ownership.pub_key,
-						mainchain_signature: stake_ownership.signature,
+						stake_ownership,
 						// For now we use the same key for both cross chain and sidechain actions
 						cross_chain_pub_key: CrossChainPublicKey(sidechain_pub_key.0.clone()),
 						cross_chain_signature: CrossChainSignature(sidechain_signature.0.clone()),
@@ -241,8 +366,7 @@ impl AuthoritySelectionDataSourceImpl {
 						own_pkh: _own_pkh,
 						keys,
 					} => Ok(RegisteredCandidate {
-						stake_pool_pub_key: stake_ownership.pub_key,
-						mainchain_signature: stake_ownership.signature,
+						stake_ownership,
 						// For now we use the same key for both cross chain and sidechain actions
 						cross_chain_pub_key: CrossChainPublicKey(sidechain_pub_key.0.clone()),
 						cross_chain_signature: CrossChainSignature(sidechain_signature.0.clone()),
@@ -42,4 +42,8 @@ impl sidechain_mc_hash::McHashDataSource for McHashDataSourceImpl {
 	async fn get_block_by_hash(&self, hash: McBlockHash) -> Result<Option<MainchainBlock>> {
 		Ok(self.inner.get_block_by_hash(hash).await?)
 	}
+
+	async fn get_tip(&self) -> Result<Option<MainchainBlock>> {
+		Ok(Some(self.inner.get_latest_block_info().await?))
+	}
 }
@@ -64,16 +64,93 @@ fn rows_to_distribution(
 	StakeDistribution(res)
 }
 
+/// Decodes the delegator key from a bech32 reward address.
+///
+/// The credential of a script reward address is the hash of the script controlling it,
+/// which is the same value that Db-Sync stores in `stake_address.script_hash`.
 fn get_delegator_key(row: &EpochStakePoolContentInner) -> Result<DelegatorKey> {
 	let (_, stake_address_hash_raw) = bech32::decode(&row.stake_address)?;
 	match &stake_address_hash_raw[..] {
-		[0xe0 | 0xe1, rest @ ..] => Ok(DelegatorKey::StakeKeyHash(
-			rest.try_into().expect("infallible: stake_address_hash_raw is 29 bytes"),
-		)),
-		[0xf0 | 0xf1, rest @ ..] => Ok(DelegatorKey::ScriptKeyHash {
-			hash_raw: rest.try_into().expect("infallible: stake_address_hash_raw is 29 bytes"),
-			script_hash: [0; 28], // TODO add support for this in a follow up PR
-		}),
-		_ => Err(format!("invalid stake address hash: {}", row.stake_address).into()),
+		[0xe0 | 0xe1, rest @ ..] => {
+			Ok(DelegatorKey::StakeKeyHash(rest.try_into().map_err(|_| invalid_stake_address(row))?))
+		},
+		[0xf0 | 0xf1, rest @ ..] => {
+			let script_hash: [u8; 28] = rest.try_into().map_err(|_| invalid_stake_address(row))?;
+			Ok(DelegatorKey::ScriptKeyHash { hash_raw: script_hash, script_hash })
+		},
+		_ => Err(invalid_stake_address(row).into()),
+	}
+}
+
+fn invalid_stake_address(row: &EpochStakePoolContentInner) -> String {
+	format!("invalid stake address hash: {}", row.stake_address)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use hex_literal::hex;
+
+	fn stake_address(header: u8, credential: [u8; 28]) -> String {
+		let bytes = [&[header][..], &credential[..]].concat();
+		bech32::encode::<bech32::Bech32>(bech32::Hrp::parse_unchecked("stake_test"), &bytes)
+			.unwrap()
+	}
+
+	fn row(stake_address: String, amount: u64) -> EpochStakePoolContentInner {
+		EpochStakePoolContentInner { stake_address, amount: amount.to_string() }
+	}
+
+	const POOL: MainchainKeyHash =
+		MainchainKeyHash(hex!("38f4a58aaf3fec84f3410520c70ad75321fb651ada7ca026373ce486"));
+	const KEY_CREDENTIAL: [u8; 28] =
+		hex!("aa898fce3be344c6be2d86fe1c5918675c9b0672cda8ab809d262824");
+	const SCRIPT_CREDENTIAL: [u8; 28] =
+		hex!("49b16fb356be9e46778478f2c9601a24fa16c88b2a97681d5af06d01");
+
+	#[test]
+	fn resolves_script_hash_of_script_controlled_delegators() {
+		let key = get_delegator_key(&row(stake_address(0xf0, SCRIPT_CREDENTIAL), 1)).unwrap();
+
+		assert_eq!(
+			key,
+			DelegatorKey::ScriptKeyHash {
+				hash_raw: SCRIPT_CREDENTIAL,
+				script_hash: SCRIPT_CREDENTIAL
+			}
+		);
+	}
+
+	#[test]
+	fn aggregates_key_and_script_delegations() {
+		let rows = vec![
+			(POOL, row(stake_address(0xf0, SCRIPT_CREDENTIAL), 5000000000000)),
+			(POOL, row(stake_address(0xe0, KEY_CREDENTIAL), 997825743)),
+		];
+
+		let distribution = rows_to_distribution(rows);
+
+		let expected = PoolDelegation {
+			total_stake: StakeDelegation(5000997825743),
+			delegators: [
+				(
+					DelegatorKey::ScriptKeyHash {
+						hash_raw: SCRIPT_CREDENTIAL,
+						script_hash: SCRIPT_CREDENTIAL,
+					},
+					DelegatorStakeAmount(5000000000000),
+				),
+				(DelegatorKey::StakeKeyHash(KEY_CREDENTIAL), DelegatorStakeAmount(997825743)),
+			]
+			.into(),
+		};
+		assert_eq!(distribution.0.get(&POOL), Some(&expected));
+	}
+
+	#[test]
+	fn skips_invalid_stake_addresses() {
+		let rows = vec![(POOL, row(stake_address(0x70, SCRIPT_CREDENTIAL), 1))];
+
+		assert!(rows_to_distribution(rows).0.is_empty());
 	}
 }
@@ -0,0 +1,38 @@
+use crate::{FailoverDataSource, Result};
+use sidechain_domain::*;
+use sp_partner_chains_bridge::{
+	BridgeDataCheckpoint, BridgeTransferV1, MainChainScripts, TokenBridgeDataSource,
+};
+
+#[async_trait::async_trait]
+impl<RecipientAddress, P, S> TokenBridgeDataSource<RecipientAddress> for FailoverDataSource<P, S>
+where
+	RecipientAddress: Send + Sync,
+	P: TokenBridgeDataSource<RecipientAddress> + ?Sized,
+	S: TokenBridgeDataSource<RecipientAddress> + ?Sized,
+{
+	async fn get_transfers(
+		&self,
+		main_chain_scripts: MainChainScripts,
+		data_checkpoint: BridgeDataCheckpoint,
+		max_transfers: u32,
+		current_mc_block: McBlockHash,
+	) -> Result<(Vec<BridgeTransferV1<RecipientAddress>>, BridgeDataCheckpoint)> {
+		self.failover(
+			"get_transfers",
+			self.primary.get_transfers(
+				main_chain_scripts.clone(),
+				data_checkpoint.clone(),
+				max_transfers,
+				current_mc_block.clone(),
+			),
+			self.secondary.get_transfers(
+				main_chain_scripts,
+				data_checkpoint,
+				max_transfers,
+				current_mc_block,
+			),
+		)
+		.await
+	}
+}
@@ -0,0 +1,91 @@
+use crate::{FailoverDataSource, Result};
+use authority_selection_inherents::{AriadneParameters, AuthoritySelectionDataSource};
+use sidechain_domain::*;
+
+#[async_trait::async_trait]
+impl<P, S> AuthoritySelectionDataSource for FailoverDataSource<P, S>
+where
+	P: AuthoritySelectionDataSource + Send + Sync + ?Sized,
+	S: AuthoritySelectionDataSource + Send + Sync + ?Sized,
+{
+	async fn get_ariadne_parameters(
+		&self,
+		epoch_number: McEpochNumber,
+		d_parameter_policy: PolicyId,
+		permissioned_candidates_policy: PolicyId,
+	) -> Result<AriadneParameters> {
+		let min_epoch = self.data_epoch(epoch_number).await?.next();
+		self.failover_from_epoch(
+			"get_ariadne_parameters",
+			min_epoch,
+			self.primary.get_ariadne_parameters(
+				epoch_number,
+				d_parameter_policy.clone(),
+				permissioned_candidates_policy.clone(),
+			),
+			self.secondary.get_ariadne_parameters(
+				epoch_number,
+				d_parameter_policy,
+				permissioned_candidates_policy,
+			),
+		)
+		.await
+	}
+
+	async fn get_candidates(
+		&self,
+		epoch_number: McEpochNumber,
+		committee_candidate_address: MainchainAddress,
+	) -> Result<Vec<CandidateRegistrations>> {
+		let min_epoch = self.data_epoch(epoch_number).await?.next();
+		self.failover_from_epoch(
+			"get_candidates",
+			min_epoch,
+			self.primary.get_candidates(epoch_number, committee_candidate_address.clone()),
+			self.secondary.get_candidates(epoch_number, committee_candidate_address),
+		)
+		.await
+	}
+
+	async fn get_token_staked_candidates(
+		&self,
+		epoch_number: McEpochNumber,
+		committee_candidate_address: MainchainAddress,
+		token_staking: TokenStakingConfig,
+	) -> Result<Vec<TokenStakedCandidateRegistrations>> {
+		let min_epoch = self.data_epoch(epoch_number).await?.next();
+		self.failover_from_epoch(
+			"get_token_staked_candidates",
+			min_epoch,
+			self.primary.get_token_staked_candidates(
+				epoch_number,
+				committee_candidate_address.clone(),
+				token_staking.clone(),
+			),
+			self.secondary.get_token_staked_candidates(
+				epoch_number,
+				committee_candidate_address,
+				token_staking,
+			),
+		)
+		.await
+	}
+
+	async fn get_epoch_nonce(&self, epoch_number: McEpochNumber) -> Result<Option<EpochNonce>> {
+		self.failover_optional(
+			"get_epoch_nonce",
+			self.primary.get_epoch_nonce(epoch_number),
+			self.secondary.get_epoch_nonce(epoch_number),
+		)
+		.await
+	}
+
+	async fn data_epoch(&self, for_epoch: McEpochNumber) -> Result<McEpochNumber> {
+		self.failover(
+			"data_epoch",
+			self.primary.data_epoch(for_epoch),
+			self.secondary.data_epoch(for_epoch),
+		)
+		.await
+	}
+}
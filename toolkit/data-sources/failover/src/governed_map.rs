@@ -0,0 +1,42 @@
+use crate::{FailoverDataSource, Result};
+use sidechain_domain::byte_string::ByteString;
+use sidechain_domain::*;
+use sp_governed_map::{GovernedMapDataSource, MainChainScriptsV1};
+
+#[async_trait::async_trait]
+impl<P, S> GovernedMapDataSource for FailoverDataSource<P, S>
+where
+	P: GovernedMapDataSource + Send + Sync + ?Sized,
+	S: GovernedMapDataSource + Send + Sync + ?Sized,
+{
+	async fn get_mapping_changes(
+		&self,
+		since_mc_block: Option<McBlockHash>,
+		up_to_mc_block: McBlockHash,
+		scripts: MainChainScriptsV1,
+	) -> Result<Vec<(String, Option<ByteString>)>> {
+		self.failover(
+			"get_mapping_changes",
+			self.primary.get_mapping_changes(
+				since_mc_block.clone(),
+				up_to_mc_block.clone(),
+				scripts.clone(),
+			),
+			self.secondary.get_mapping_changes(since_mc_block, up_to_mc_block, scripts),
+		)
+		.await
+	}
+
+	async fn get_state_at_block(
+		&self,
+		mc_block: McBlockHash,
+		main_chain_scripts: MainChainScriptsV1,
+	) -> Result<BTreeMap<String, ByteString>> {
+		self.failover(
+			"get_state_at_block",
+			self.primary.get_state_at_block(mc_block.clone(), main_chain_scripts.clone()),
+			self.secondary.get_state_at_block(mc_block, main_chain_scripts),
+		)
+		.await
+	}
+}
@@ -0,0 +1,385 @@
+//! Crate providing data sources that switch between a primary and a secondary implementation
+//! of Partner Chains data source traits.
+//!
+//! # Usage
+//!
+//! All data sources of a node should share a single [FailoverHealth], that decides which
+//! backend is active, based on health checks of both backends:
+//! ```rust
+//! use pallet_sidechain_rpc::SidechainRpcDataSource;
+//! use partner_chains_failover_data_sources::*;
+//! use sidechain_domain::mainchain_epoch::MainchainEpochConfig;
+//! use std::sync::Arc;
+//!
+//! fn create_health(
+//!     primary: Arc<dyn SidechainRpcDataSource + Send + Sync>,
+//!     secondary: Arc<dyn SidechainRpcDataSource + Send + Sync>,
+//!     mc_epoch_config: MainchainEpochConfig,
+//! ) -> Result<Arc<FailoverHealth>, Box<dyn std::error::Error + Send + Sync>> {
+//!     let config = FailoverConfig::from_env()?;
+//!     Ok(Arc::new(FailoverHealth::new(primary, secondary, mc_epoch_config, config, None)))
+//! }
+//! ```
+//! Each pair of data sources is then wrapped with [FailoverDataSource::new].
+//!
+//! # Failover rules
+//!
+//! A backend is healthy if the age of its latest Cardano block is not greater than
+//! [FailoverConfig::max_block_age_slots] Cardano slots. The primary backend is used whenever
+//! it is healthy. The secondary backend is used when the primary is unhealthy, or when a query
+//! to the primary fails. Health of the backends is checked at most once per
+//! [FailoverConfig::health_check_interval_secs].
+//!
+//! Data of stable Cardano blocks does not change, so both backends return the same data for queries
+//! about the same stable block. The only difference between them is lag: a lagging backend may not know
+//! a block yet. Because of that, absence of a block in one backend is never trusted on its own: queries
+//! for a block that returned `None` are repeated on the other backend. Queries about a block that is not
+//! known to a backend, like Governed Map and bridge queries, fail, so they are retried on the other backend too.
+//!
+//! Queries about a Cardano epoch, like candidates, Ariadne parameters and stake distribution queries, would
+//! not fail on a lagging backend, but return data of the part of the epoch known to it. Before such query
+//! is served by a backend, its latest block is checked to be in a later epoch than the data epoch of the query
+//! (or in the queried epoch, for stake distribution, which is known from the epoch start). A backend that has
+//! not reached it is treated as failed, and the query fails if neither backend has reached it.
+#![deny(missing_docs)]
+
+use figment::{Figment, providers::Env};
+use log::{info, warn};
+use pallet_sidechain_rpc::SidechainRpcDataSource;
+use partner_chains_data_source_metrics::McFollowerMetrics;
+use serde::Deserialize;
+use sidechain_domain::McEpochNumber;
+use sidechain_domain::mainchain_epoch::MainchainEpochConfig;
+use std::error::Error;
+use std::fmt::Display;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+#[cfg(feature = "bridge")]
+mod bridge;
+#[cfg(feature = "candidate-source")]
+mod candidate;
+#[cfg(feature = "governed-map")]
+mod governed_map;
+#[cfg(feature = "mc-hash")]
+mod mc_hash;
+mod sidechain_rpc;
+#[cfg(feature = "block-participation")]
+mod stake_distribution;
+
+#[cfg(test)]
+mod tests;
+
+type Result<T> = std::result::Result<T, Box<dyn Error + Send + Sync>>;
+
+/// Configuration of the failover
+#[derive(Clone, Debug, Deserialize)]
+pub struct FailoverConfig {
+	/// Maximum age of the latest block of a healthy backend, in Cardano slots
+	#[serde(default = "default_max_block_age_slots", rename = "failover_max_block_age_slots")]
+	pub max_block_age_slots: u64,
+	/// Minimal interval between health checks, in seconds
+	#[serde(
+		default = "default_health_check_interval_secs",
+		rename = "failover_health_check_interval_secs"
+	)]
+	pub health_check_interval_secs: u64,
+}
+
+fn default_max_block_age_slots() -> u64 {
+	600
+}
+
+fn default_health_check_interval_secs() -> u64 {
+	10
+}
+
+impl Default for FailoverConfig {
+	fn default() -> Self {
+		Self {
+			max_block_age_slots: default_max_block_age_slots(),
+			health_check_interval_secs: default_health_check_interval_secs(),
+		}
+	}
+}
+
+impl FailoverConfig {
+	/// Reads the config from environment variables `FAILOVER_MAX_BLOCK_AGE_SLOTS` and
+	/// `FAILOVER_HEALTH_CHECK_INTERVAL_SECS`. Defaults are used for variables that are not set.
+	pub fn from_env() -> Result<Self> {
+		let config: Self = Figment::new()
+			.merge(Env::raw())
+			.extract()
+			.map_err(|e| format!("Failed to read failover config: {e}"))?;
+		info!("Using failover configuration: {config:?}");
+		Ok(config)
+	}
+}
+
+/// Backend of a [FailoverDataSource]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Backend {
+	/// Primary backend, used whenever it is healthy
+	Primary,
+	/// Secondary backend, used when the primary one is unhealthy
+	Secondary,
+}
+
+impl Backend {
+	fn other(self) -> Self {
+		match self {
+			Self::Primary => Self::Secondary,
+			Self::Secondary => Self::Primary,
+		}
+	}
+}
+
+impl Display for Backend {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Primary => write!(f, "primary"),
+			Self::Secondary => write!(f, "secondary"),
+		}
+	}
+}
+
+struct HealthState {
+	active: Backend,
+	last_check: Option<Instant>,
+}
+
+/// Decides which backend is active, shared by all [FailoverDataSource]s of a node
+pub struct FailoverHealth {
+	primary: Arc<dyn SidechainRpcDataSource + Send + Sync>,
+	secondary: Arc<dyn SidechainRpcDataSource + Send + Sync>,
+	max_block_age: Duration,
+	health_check_interval: Duration,
+	state: Mutex<HealthState>,
+	metrics_opt: Option<McFollowerMetrics>,
+}
+
+impl FailoverHealth {
+	/// Creates new health checker, using latest block info of `primary` and `secondary` for health checks
+	pub fn new(
+		primary: Arc<dyn SidechainRpcDataSource + Send + Sync>,
+		secondary: Arc<dyn SidechainRpcDataSource + Send + Sync>,
+		mc_epoch_config: MainchainEpochConfig,
+		config: FailoverConfig,
+		metrics_opt: Option<McFollowerMetrics>,
+	) -> Self {
+		let slot_duration_millis = mc_epoch_config.slot_duration_millis.millis();
+		Self {
+			primary,
+			secondary,
+			max_block_age: Duration::from_millis(slot_duration_millis * config.max_block_age_slots),
+			health_check_interval: Duration::from_secs(config.health_check_interval_secs),
+			state: Mutex::new(HealthState { active: Backend::Primary, last_check: None }),
+			metrics_opt,
+		}
+	}
+
+	/// Returns the active backend, checking health of the backends if the last check is too old
+	pub async fn active(&self) -> Backend {
+		let (active, check_due) = {
+			let state = self.state.lock().expect("Failover state lock is poisoned");
+			let check_due = (state.last_check)
+				.is_none_or(|last_check| last_check.elapsed() >= self.health_check_interval);
+			(state.active, check_due)
+		};
+		if !check_due {
+			return active;
+		}
+		let new_active = if self.is_healthy(Backend::Primary).await {
+			Backend::Primary
+		} else if self.is_healthy(Backend::Secondary).await {
+			Backend::Secondary
+		} else {
+			active
+		};
+		let mut state = self.state.lock().expect("Failover state lock is poisoned");
+		state.last_check = Some(Instant::now());
+		if state.active != new_active {
+			warn!("Health check switched active data source from {} to {new_active}", state.active);
+			self.count_failover("health_check", new_active);
+			state.active = new_active;
+		}
+		state.active
+	}
+
+	/// Marks `backend` as failed by `method`. Switches to the other backend if `backend` was active.
+	pub fn report_failure(
+		&self,
+		backend: Backend,
+		method: &str,
+		error: &(dyn Error + Send + Sync),
+	) {
+		warn!("Data source method {method} of {backend} data source failed: {error}");
+		let mut state = self.state.lock().expect("Failover state lock is poisoned");
+		if state.active == backend {
+			let new_active = backend.other();
+			warn!("Switching active data source to {new_active}");
+			self.count_failover(method, new_active);
+			state.active = new_active;
+			state.last_check = Some(Instant::now());
+		}
+	}
+
+	/// Returns an error if the latest block known to `backend` is in an epoch earlier than `epoch`
+	pub async fn ensure_reached_epoch(&self, backend: Backend, epoch: McEpochNumber) -> Result<()> {
+		let latest_block = self.source(backend).get_latest_block_info().await?;
+		if latest_block.epoch < epoch {
+			return Err(format!(
+				"Latest block of {backend} data source is in epoch {}, but epoch {epoch} is required",
+				latest_block.epoch
+			)
+			.into());
+		}
+		Ok(())
+	}
+
+	fn source(&self, backend: Backend) -> &Arc<dyn SidechainRpcDataSource + Send + Sync> {
+		match backend {
+			Backend::Primary => &self.primary,
+			Backend::Secondary => &self.secondary,
+		}
+	}
+
+	async fn is_healthy(&self, backend: Backend) -> bool {
+		match self.source(backend).get_latest_block_info().await {
+			Ok(block) => {
+				let age = block_age(block.timestamp);
+				if age > self.max_block_age {
+					warn!("Latest block of {backend} data source is {}s old", age.as_secs());
+				}
+				age <= self.max_block_age
+			},
+			Err(err) => {
+				warn!("Health check of {backend} data source failed: {err}");
+				false
+			},
+		}
+	}
+
+	fn count_failover(&self, method: &str, backend: Backend) {
+		if let Some(metrics) = &self.metrics_opt {
+			(metrics.failover_count())
+				.with_label_values(&[method, &backend.to_string()])
+				.inc();
+		}
+	}
+}
+
+fn block_age(block_timestamp_secs: u64) -> Duration {
+	let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+	now.saturating_sub(Duration::from_secs(block_timestamp_secs))
+}
+
+/// Data source switching between `primary` and `secondary` data sources, based on [FailoverHealth]
+pub struct FailoverDataSource<P: ?Sized, S: ?Sized> {
+	primary: Arc<P>,
+	secondary: Arc<S>,
+	health: Arc<FailoverHealth>,
+}
+
+impl<P: ?Sized, S: ?Sized> FailoverDataSource<P, S> {
+	/// Creates new data source, switching between `primary` and `secondary`
+	pub fn new(primary: Arc<P>, secondary: Arc<S>, health: Arc<FailoverHealth>) -> Self {
+		Self { primary, secondary, health }
+	}
+
+	/// Awaits the query of the active backend, and the query of the other backend if the first one failed
+	async fn failover<T>(
+		&self,
+		method: &str,
+		primary: impl Future<Output = Result<T>>,
+		secondary: impl Future<Output = Result<T>>,
+	) -> Result<T> {
+		match self.health.active().await {
+			Backend::Primary => match primary.await {
+				Ok(value) => Ok(value),
+				Err(err) => {
+					self.health.report_failure(Backend::Primary, method, err.as_ref());
+					secondary.await
+				},
+			},
+			Backend::Secondary => match secondary.await {
+				Ok(value) => Ok(value),
+				Err(err) => {
+					self.health.report_failure(Backend::Secondary, method, err.as_ref());
+					primary.await
+				},
+			},
+		}
+	}
+
+	/// Like [Self::failover], but uses a backend only if its latest block is in `min_epoch` or later,
+	/// because otherwise it would serve data of the part of an epoch known to it
+	async fn failover_from_epoch<T>(
+		&self,
+		method: &str,
+		min_epoch: McEpochNumber,
+		primary: impl Future<Output = Result<T>>,
+		secondary: impl Future<Output = Result<T>>,
+	) -> Result<T> {
+		let active = self.health.active().await;
+		let (first, second) = match active {
+			Backend::Primary => (Query::Primary(primary), Query::Secondary(secondary)),
+			Backend::Secondary => (Query::Secondary(secondary), Query::Primary(primary)),
+		};
+		let first_result = match self.health.ensure_reached_epoch(active, min_epoch).await {
+			Ok(()) => first.run().await,
+			Err(err) => Err(err),
+		};
+		match first_result {
+			Ok(value) => Ok(value),
+			Err(err) => {
+				self.health.report_failure(active, method, err.as_ref());
+				self.health.ensure_reached_epoch(active.other(), min_epoch).await?;
+				second.run().await
+			},
+		}
+	}
+
+	/// Like [Self::failover], but also queries the other backend when the active one returned `None`,
+	/// because it could be lagging behind
+	async fn failover_optional<T>(
+		&self,
+		method: &str,
+		primary: impl Future<Output = Result<Option<T>>>,
+		secondary: impl Future<Output = Result<Option<T>>>,
+	) -> Result<Option<T>> {
+		let active = self.health.active().await;
+		let (first, second) = match active {
+			Backend::Primary => (primary.await, Query::Secondary(secondary)),
+			Backend::Secondary => (secondary.await, Query::Primary(primary)),
+		};
+		match first {
+			Ok(Some(value)) => Ok(Some(value)),
+			Ok(None) => Ok(second.run().await.unwrap_or_else(|err| {
+				warn!(
+					"Data source method {method} of {} data source failed: {err}",
+					active.other()
+				);
+				None
+			})),
+			Err(err) => {
+				self.health.report_failure(active, method, err.as_ref());
+				second.run().await
+			},
+		}
+	}
+}
+
+enum Query<P, S> {
+	Primary(P),
+	Secondary(S),
+}
+
+impl<T, P: Future<Output = T>, S: Future<Output = T>> Query<P, S> {
+	async fn run(self) -> T {
+		match self {
+			Self::Primary(query) => query.await,
+			Self::Secondary(query) => query.await,
+		}
+	}
+}
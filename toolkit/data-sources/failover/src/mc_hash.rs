@@ -0,0 +1,50 @@
+use crate::{FailoverDataSource, Result};
+use sidechain_domain::*;
+use sidechain_mc_hash::McHashDataSource;
+use sp_timestamp::Timestamp;
+
+#[async_trait::async_trait]
+impl<P, S> McHashDataSource for FailoverDataSource<P, S>
+where
+	P: McHashDataSource + Send + Sync + ?Sized,
+	S: McHashDataSource + Send + Sync + ?Sized,
+{
+	async fn get_latest_stable_block_for(
+		&self,
+		reference_timestamp: Timestamp,
+	) -> Result<Option<MainchainBlock>> {
+		self.failover_optional(
+			"get_latest_stable_block_for",
+			self.primary.get_latest_stable_block_for(reference_timestamp),
+			self.secondary.get_latest_stable_block_for(reference_timestamp),
+		)
+		.await
+	}
+
+	async fn get_stable_block_for(
+		&self,
+		hash: McBlockHash,
+		reference_timestamp: Timestamp,
+	) -> Result<Option<MainchainBlock>> {
+		self.failover_optional(
+			"get_stable_block_for",
+			self.primary.get_stable_block_for(hash.clone(), reference_timestamp),
+			self.secondary.get_stable_block_for(hash, reference_timestamp),
+		)
+		.await
+	}
+
+	async fn get_block_by_hash(&self, hash: McBlockHash) -> Result<Option<MainchainBlock>> {
+		self.failover_optional(
+			"get_block_by_hash",
+			self.primary.get_block_by_hash(hash.clone()),
+			self.secondary.get_block_by_hash(hash),
+		)
+		.await
+	}
+
+	async fn get_tip(&self) -> Result<Option<MainchainBlock>> {
+		self.failover_optional("get_tip", self.primary.get_tip(), self.secondary.get_tip())
+			.await
+	}
+}
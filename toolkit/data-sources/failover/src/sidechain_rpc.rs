@@ -0,0 +1,19 @@
+use crate::{FailoverDataSource, Result};
+use pallet_sidechain_rpc::SidechainRpcDataSource;
+use sidechain_domain::MainchainBlock;
+
+#[async_trait::async_trait]
+impl<P, S> SidechainRpcDataSource for FailoverDataSource<P, S>
+where
+	P: SidechainRpcDataSource + Send + Sync + ?Sized,
+	S: SidechainRpcDataSource + Send + Sync + ?Sized,
+{
+	async fn get_latest_block_info(&self) -> Result<MainchainBlock> {
+		self.failover(
+			"get_latest_block_info",
+			self.primary.get_latest_block_info(),
+			self.secondary.get_latest_block_info(),
+		)
+		.await
+	}
+}
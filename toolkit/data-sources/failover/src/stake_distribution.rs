@@ -0,0 +1,26 @@
+use crate::{FailoverDataSource, Result};
+use sidechain_domain::*;
+use sp_block_participation::inherent_data::BlockParticipationDataSource;
+
+#[async_trait::async_trait]
+impl<P, S> BlockParticipationDataSource for FailoverDataSource<P, S>
+where
+	P: BlockParticipationDataSource + Send + Sync + ?Sized,
+	S: BlockParticipationDataSource + Send + Sync + ?Sized,
+{
+	async fn get_stake_pool_delegation_distribution_for_pools(
+		&self,
+		epoch: McEpochNumber,
+		pool_hashes: &[MainchainKeyHash],
+	) -> Result<StakeDistribution> {
+		self.failover_from_epoch(
+			"get_stake_pool_delegation_distribution_for_pools",
+			epoch,
+			self.primary
+				.get_stake_pool_delegation_distribution_for_pools(epoch, pool_hashes),
+			self.secondary
+				.get_stake_pool_delegation_distribution_for_pools(epoch, pool_hashes),
+		)
+		.await
+	}
+}
@@ -0,0 +1,170 @@
+use crate::*;
+use sidechain_domain::mainchain_epoch::{Duration as McDuration, Timestamp as McTimestamp};
+use sidechain_domain::*;
+use std::sync::Mutex;
+
+struct MockSource {
+	latest_block: Mutex<Result<MainchainBlock>>,
+}
+
+impl MockSource {
+	fn with_block_age(age_secs: u64) -> Arc<Self> {
+		Self::with_block_age_in_epoch(age_secs, 0)
+	}
+
+	fn with_block_age_in_epoch(age_secs: u64, epoch: u32) -> Arc<Self> {
+		let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+		let block = MainchainBlock { epoch: McEpochNumber(epoch), ..block(now - age_secs) };
+		Arc::new(Self { latest_block: Mutex::new(Ok(block)) })
+	}
+
+	fn fail(&self) {
+		*self.latest_block.lock().unwrap() = Err("connection refused".into());
+	}
+}
+
+#[async_trait::async_trait]
+impl SidechainRpcDataSource for MockSource {
+	async fn get_latest_block_info(&self) -> Result<MainchainBlock> {
+		match &*self.latest_block.lock().unwrap() {
+			Ok(block) => Ok(block.clone()),
+			Err(err) => Err(err.to_string().into()),
+		}
+	}
+}
+
+fn block(timestamp: u64) -> MainchainBlock {
+	MainchainBlock { timestamp, ..Default::default() }
+}
+
+fn mc_epoch_config() -> MainchainEpochConfig {
+	MainchainEpochConfig {
+		epoch_duration_millis: McDuration::from_millis(432000000),
+		slot_duration_millis: McDuration::from_millis(1000),
+		first_epoch_timestamp_millis: McTimestamp::from_unix_millis(0),
+		first_epoch_number: 0,
+		first_slot_number: 0,
+	}
+}
+
+fn failover_data_source(
+	primary: Arc<MockSource>,
+	secondary: Arc<MockSource>,
+	health_check_interval_secs: u64,
+) -> FailoverDataSource<MockSource, MockSource> {
+	let config = FailoverConfig { max_block_age_slots: 100, health_check_interval_secs };
+	let health = Arc::new(FailoverHealth::new(
+		primary.clone(),
+		secondary.clone(),
+		mc_epoch_config(),
+		config,
+		None,
+	));
+	FailoverDataSource::new(primary, secondary, health)
+}
+
+#[tokio::test]
+async fn uses_primary_when_it_is_healthy() {
+	let primary = MockSource::with_block_age(10);
+	let secondary = MockSource::with_block_age(20);
+	let data_source = failover_data_source(primary.clone(), secondary, 0);
+
+	let latest_block = data_source.get_latest_block_info().await.unwrap();
+
+	assert_eq!(latest_block, primary.get_latest_block_info().await.unwrap());
+	assert_eq!(data_source.health.active().await, Backend::Primary);
+}
+
+#[tokio::test]
+async fn switches_to_secondary_when_primary_lags_and_back_when_it_catches_up() {
+	let primary = MockSource::with_block_age(1000);
+	let secondary = MockSource::with_block_age(20);
+	let data_source = failover_data_source(primary.clone(), secondary.clone(), 0);
+
+	let latest_block = data_source.get_latest_block_info().await.unwrap();
+
+	assert_eq!(latest_block, secondary.get_latest_block_info().await.unwrap());
+	assert_eq!(data_source.health.active().await, Backend::Secondary);
+
+	let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+	*primary.latest_block.lock().unwrap() = Ok(block(now));
+	assert_eq!(data_source.health.active().await, Backend::Primary);
+}
+
+#[tokio::test]
+async fn switches_to_secondary_when_query_to_primary_fails() {
+	let primary = MockSource::with_block_age(10);
+	let secondary = MockSource::with_block_age(20);
+	let data_source = failover_data_source(primary.clone(), secondary.clone(), 3600);
+	assert_eq!(data_source.health.active().await, Backend::Primary);
+	primary.fail();
+
+	let latest_block = data_source.get_latest_block_info().await.unwrap();
+
+	assert_eq!(latest_block, secondary.get_latest_block_info().await.unwrap());
+	assert_eq!(data_source.health.active().await, Backend::Secondary);
+}
+
+#[tokio::test]
+async fn returns_none_only_if_both_backends_return_none() {
+	let data_source =
+		failover_data_source(MockSource::with_block_age(10), MockSource::with_block_age(10), 0);
+
+	let result = (data_source.failover_optional("test", async { Ok(None) }, async { Ok(Some(1)) }))
+		.await
+		.unwrap();
+	assert_eq!(result, Some(1));
+
+	let result = (data_source
+		.failover_optional::<u32>("test", async { Ok(None) }, async { Ok(None) }))
+	.await
+	.unwrap();
+	assert_eq!(result, None);
+
+	let result = (data_source
+		.failover_optional("test", async { Ok(None) }, async { Err("lagging".into()) }))
+	.await
+	.unwrap();
+	assert_eq!(result, None::<u32>);
+	assert_eq!(data_source.health.active().await, Backend::Primary);
+}
+
+#[tokio::test]
+async fn skips_active_backend_that_has_not_reached_the_epoch_of_the_query() {
+	// Primary is healthy, but has not seen the end of epoch 9 yet
+	let primary = MockSource::with_block_age_in_epoch(10, 9);
+	let secondary = MockSource::with_block_age_in_epoch(20, 10);
+	let data_source = failover_data_source(primary, secondary, 3600);
+
+	let result = (data_source.failover_from_epoch(
+		"test",
+		McEpochNumber(10),
+		async { Ok("primary") },
+		async { Ok("secondary") },
+	))
+	.await
+	.unwrap();
+
+	assert_eq!(result, "secondary");
+	assert_eq!(data_source.health.active().await, Backend::Secondary);
+}
+
+#[tokio::test]
+async fn does_not_serve_epoch_data_from_lagging_secondary() {
+	let primary = MockSource::with_block_age_in_epoch(10, 10);
+	let secondary = MockSource::with_block_age_in_epoch(20, 9);
+	let data_source = failover_data_source(primary, secondary, 3600);
+
+	let result = (data_source.failover_from_epoch(
+		"test",
+		McEpochNumber(10),
+		async { Err("connection refused".into()) },
+		async { Ok("secondary") },
+	))
+	.await;
+
+	assert_eq!(
+		result.unwrap_err().to_string(),
+		"Latest block of secondary data source is in epoch 9, but epoch 10 is required"
+	);
+}
@@ -0,0 +1,371 @@
+use crate::{
+	DataSourceError, Result,
+	client::{KupoApi, KupoClient, types::KupoPoint},
+	read_mc_epoch_config,
+};
+use async_trait::async_trait;
+use figment::{Figment, providers::Env};
+use futures::lock::Mutex as AsyncMutex;
+use log::{debug, info};
+use lru::LruCache;
+use ogmios_client::{
+	chain_sync::{ChainSync, FindIntersectionResponse, NextBlockResponse, Point, Tip},
+	jsonrpsee::{OgmiosClients, client_for_url},
+};
+use serde::Deserialize;
+use sidechain_domain::mainchain_epoch::{MainchainEpochConfig, MainchainEpochDerivation};
+use sidechain_domain::*;
+use sp_timestamp::Timestamp;
+use std::{
+	num::NonZeroUsize,
+	sync::{Arc, Mutex},
+	time::Duration,
+};
+
+/// Source of heights of Cardano blocks, which are not tracked by Kupo
+#[async_trait]
+pub trait BlockHeights {
+	/// Returns the most recent block of the chain and its height, `None` if the chain is empty
+	async fn tip(&self) -> Result<Option<(KupoPoint, u64)>>;
+
+	/// Returns the height of the block at `point`
+	async fn height(&self, point: &KupoPoint) -> Result<u64>;
+}
+
+/// [BlockHeights] implementation using the chain synchronization protocol of Ogmios
+pub struct OgmiosBlockHeights {
+	/// Ogmios keeps the chain synchronization state per connection,
+	/// so the client is used by a single request at a time.
+	client: AsyncMutex<OgmiosClients>,
+}
+
+/// Ogmios connection config used by [OgmiosBlockHeights]
+#[derive(Debug, Clone, Deserialize)]
+struct OgmiosConnectionConfig {
+	/// Ogmios WebSockets server, eg. `ws://localhost:1337`
+	ogmios_url: String,
+}
+
+impl OgmiosBlockHeights {
+	/// Connects to Ogmios at `url`. Chain synchronization is not available over HTTP,
+	/// so `url` has to use `ws` or `wss` scheme.
+	pub async fn connect(url: &str, timeout: Duration) -> Result<Self> {
+		if !url.starts_with("ws") {
+			return Err(format!("Ogmios url has to use ws or wss scheme, got: '{url}'").into());
+		}
+		let client = client_for_url(url, timeout).await?;
+		Ok(Self { client: AsyncMutex::new(client) })
+	}
+
+	/// Connects to Ogmios at url read from the `OGMIOS_URL` environment variable
+	pub async fn new_from_env() -> Result<Self> {
+		let config: OgmiosConnectionConfig = Figment::new()
+			.merge(Env::raw())
+			.extract()
+			.map_err(|e| format!("Failed to read Ogmios connection config: {e}"))?;
+		Self::connect(&config.ogmios_url, Duration::from_secs(30)).await
+	}
+}
+
+fn ogmios_error(e: impl ToString) -> DataSourceError {
+	DataSourceError::OgmiosError(e.to_string())
+}
+
+#[async_trait]
+impl BlockHeights for OgmiosBlockHeights {
+	async fn tip(&self) -> Result<Option<(KupoPoint, u64)>> {
+		let client = self.client.lock().await;
+		let FindIntersectionResponse { tip, .. } =
+			client.find_intersection(&[Point::Origin]).await.map_err(ogmios_error)?;
+		Ok(match tip {
+			Tip::Origin => None,
+			Tip::Block { slot, id, height } => {
+				Some((KupoPoint { slot_no: slot, header_hash: hex::encode(id) }, height))
+			},
+		})
+	}
+
+	async fn height(&self, point: &KupoPoint) -> Result<u64> {
+		let id = point.block_hash()?.0;
+		let ogmios_point = Point::Block { slot: point.slot_no, id };
+		let client = self.client.lock().await;
+		let FindIntersectionResponse { intersection, tip } =
+			client.find_intersection(&[ogmios_point]).await.map_err(ogmios_error)?;
+		if intersection != ogmios_point {
+			return Err(DataSourceError::ExpectedDataNotFound(format!("Block {point:?}")).into());
+		}
+		if tip.point() == ogmios_point {
+			return Ok(tip.height());
+		}
+		// The first response after finding an intersection is always a roll back to it
+		client.next_block().await.map_err(ogmios_error)?;
+		match client.next_block().await.map_err(ogmios_error)? {
+			NextBlockResponse::Forward { block, .. } if block.ancestor == Some(id) => {
+				Ok(block.height.saturating_sub(1))
+			},
+			response => Err(ogmios_error(format!(
+				"Unexpected response when reading height of block {point:?}: {response:?}"
+			))
+			.into()),
+		}
+	}
+}
+
+/// Block data source combining Kupo checkpoints with block heights read from [BlockHeights]
+pub struct BlockDataSourceImpl {
+	/// Kupo client
+	client: KupoClient,
+	/// Source of block heights
+	heights: Arc<dyn BlockHeights + Send + Sync>,
+	/// Cardano security parameter
+	///
+	/// This parameter controls how many confirmations (blocks on top) are required by
+	/// the Cardano node to consider a block to be stable. This is a network-wide parameter.
+	security_parameter: u32,
+	/// Minimal age of a block to be considered valid stable in relation to some given timestamp.
+	/// Equal to `security parameter / active slot coefficient`.
+	min_slot_boundary: Duration,
+	/// A characteristic of Ouroboros Praos, equal to `3 * security parameter / active slot coefficient`
+	max_slot_boundary: Duration,
+	/// Cardano main chain epoch configuration
+	mainchain_epoch_config: MainchainEpochConfig,
+	/// Additional offset applied when selecting the latest stable Cardano block
+	block_stability_margin: u32,
+	/// Blocks seen by the data source, by hash
+	blocks_cache: Mutex<LruCache<McBlockHash, MainchainBlock>>,
+}
+
+/// Configuration for [BlockDataSourceImpl]
+#[derive(Debug, Clone, Deserialize)]
+pub struct KupoBlockDataSourceConfig {
+	/// Cardano security parameter, ie. the number of confirmations needed to stabilize a block
+	pub cardano_security_parameter: u32,
+	/// Expected fraction of Cardano slots that will have a block produced
+	///
+	/// This value can be found in `shelley-genesis.json` file used by the Cardano node,
+	/// example: `"activeSlotsCoeff": 0.05`.
+	pub cardano_active_slots_coeff: f64,
+	/// Additional offset applied when selecting the latest stable Cardano block
+	///
+	/// This parameter should be 0 by default.
+	#[serde(default)]
+	pub block_stability_margin: u32,
+}
+
+impl KupoBlockDataSourceConfig {
+	/// Reads the config from environment
+	pub fn from_env() -> Result<Self> {
+		let config: Self = Figment::new()
+			.merge(Env::raw())
+			.extract()
+			.map_err(|e| format!("Failed to read block data source config: {e}"))?;
+		info!("Using block data source configuration: {config:?}");
+		Ok(config)
+	}
+}
+
+/// Number of blocks kept in the cache of [BlockDataSourceImpl]
+const BLOCKS_CACHE_SIZE: usize = 10_000;
+
+impl BlockDataSourceImpl {
+	/// Creates a new instance of [BlockDataSourceImpl], using passed configuration
+	pub fn new(
+		client: KupoClient,
+		heights: Arc<dyn BlockHeights + Send + Sync>,
+		KupoBlockDataSourceConfig {
+			cardano_security_parameter,
+			cardano_active_slots_coeff,
+			block_stability_margin,
+		}: KupoBlockDataSourceConfig,
+		mainchain_epoch_config: MainchainEpochConfig,
+	) -> Self {
+		let k: f64 = cardano_security_parameter.into();
+		let slot_duration: f64 = mainchain_epoch_config.slot_duration_millis.millis() as f64;
+		let min_slot_boundary =
+			Duration::from_millis((slot_duration * k / cardano_active_slots_coeff).round() as u64);
+		Self {
+			client,
+			heights,
+			security_parameter: cardano_security_parameter,
+			min_slot_boundary,
+			max_slot_boundary: 3 * min_slot_boundary,
+			mainchain_epoch_config,
+			block_stability_margin,
+			blocks_cache: Mutex::new(LruCache::new(
+				NonZeroUsize::new(BLOCKS_CACHE_SIZE).expect("cache size is not zero"),
+			)),
+		}
+	}
+
+	/// Creates a new instance of [BlockDataSourceImpl], reading configuration from the environment
+	pub fn new_from_env(
+		client: KupoClient,
+		heights: Arc<dyn BlockHeights + Send + Sync>,
+	) -> Result<Self> {
+		Ok(Self::new(
+			client,
+			heights,
+			KupoBlockDataSourceConfig::from_env()?,
+			read_mc_epoch_config()?,
+		))
+	}
+
+	/// Returns the latest _unstable_ Cardano block
+	pub async fn get_latest_block_info(&self) -> Result<MainchainBlock> {
+		let (point, height) = (self.heights.tip().await?)
+			.ok_or(DataSourceError::ExpectedDataNotFound("No latest block on chain.".into()))?;
+		self.make_block(&point, height)
+	}
+
+	/// Returns the latest _stable_ Cardano block that is within acceptable bounds from
+	/// `reference_timestamp`, accounting for the additional stability offset configured by
+	/// [block_stability_margin][Self::block_stability_margin].
+	pub async fn get_latest_stable_block_for(
+		&self,
+		reference_timestamp: Timestamp,
+	) -> Result<Option<MainchainBlock>> {
+		let latest = self.get_latest_block_info().await?;
+		let offset = self.security_parameter + self.block_stability_margin;
+		let max_stable_number = latest.number.saturating_sub(offset);
+		let min_slot = self.slot_at(self.min_block_allowed_time(reference_timestamp));
+		let max_slot = self.slot_at(self.max_block_allowed_time(reference_timestamp));
+		let mut point = self.client.checkpoint_at_or_before(max_slot).await?;
+		while let Some(current) = point {
+			if current.slot_no < min_slot.0 {
+				return Ok(None);
+			}
+			let block = self.block_at(&current).await?;
+			if block.number <= max_stable_number
+				&& self.is_block_time_valid(&block, reference_timestamp)
+			{
+				return Ok(Some(block));
+			}
+			let Some(previous_slot) = current.slot_no.checked_sub(1) else {
+				return Ok(None);
+			};
+			point = self.client.checkpoint_at_or_before(McSlotNumber(previous_slot)).await?;
+		}
+		Ok(None)
+	}
+
+	/// Finds a block by its `hash` and verifies that it is stable in reference to `reference_timestamp`
+	/// and returns its info
+	pub async fn get_stable_block_for(
+		&self,
+		hash: McBlockHash,
+		reference_timestamp: Timestamp,
+	) -> Result<Option<MainchainBlock>> {
+		let Some(block) = self.get_block_by_hash(hash).await? else {
+			return Ok(None);
+		};
+		let latest = self.get_latest_block_info().await?;
+		Ok(Some(block).filter(|block| {
+			block.number.saturating_add(self.security_parameter) <= latest.number
+				&& self.is_block_time_valid(block, reference_timestamp)
+		}))
+	}
+
+	/// Finds a block by its `hash` and returns its info
+	///
+	/// Only blocks that were seen before by this data source, or are still among
+	/// the checkpoints kept by Kupo, can be found.
+	pub async fn get_block_by_hash(&self, hash: McBlockHash) -> Result<Option<MainchainBlock>> {
+		if let Some(block) = self.get_cached(&hash) {
+			return Ok(Some(block));
+		}
+		debug!("Block by hash: {hash} not found in cache, searching Kupo checkpoints.");
+		let checkpoints = self.client.checkpoints().await?;
+		let hash_hex = hex::encode(hash.0);
+		match checkpoints.into_iter().find(|point| point.header_hash == hash_hex) {
+			Some(point) => Ok(Some(self.block_at(&point).await?)),
+			None => Ok(None),
+		}
+	}
+
+	/// Returns the block at `point`
+	pub(crate) async fn block_at(&self, point: &KupoPoint) -> Result<MainchainBlock> {
+		let hash = point.block_hash()?;
+		if let Some(block) = self.get_cached(&hash) {
+			return Ok(block);
+		}
+		let height = self.heights.height(point).await?;
+		self.make_block(point, height)
+	}
+
+	/// Returns the slot of a block with `number`, if the block was seen by the data source
+	pub(crate) fn cached_slot_of_block(&self, number: McBlockNumber) -> Option<McSlotNumber> {
+		let cache = self.blocks_cache.lock().ok()?;
+		cache
+			.iter()
+			.find(|(_, block)| block.number == number)
+			.map(|(_, block)| block.slot)
+	}
+
+	/// Returns the last slot of `epoch`
+	pub(crate) fn last_slot_of_epoch(&self, epoch: McEpochNumber) -> Result<McSlotNumber> {
+		let next_epoch_first_slot = (self.mainchain_epoch_config)
+			.first_slot_of_epoch(McEpochNumber(epoch.0 + 1))
+			.map_err(|e| DataSourceError::BadRequest(format!("{e:?}")))?;
+		Ok(McSlotNumber(next_epoch_first_slot.0.saturating_sub(1)))
+	}
+
+	fn make_block(&self, point: &KupoPoint, height: u64) -> Result<MainchainBlock> {
+		let slot = McSlotNumber(point.slot_no);
+		let epoch = (self.mainchain_epoch_config.epoch_for_slot(slot)).map_err(|e| {
+			DataSourceError::InvalidData(format!("Can not compute epoch of block {point:?}: {e:?}"))
+		})?;
+		let slots_since_first_slot = slot
+			.0
+			.checked_sub(self.mainchain_epoch_config.first_slot_number)
+			.ok_or_else(|| {
+				DataSourceError::InvalidData(format!("Block {point:?} is before the first slot"))
+			})?;
+		let timestamp_millis =
+			self.mainchain_epoch_config.first_epoch_timestamp_millis.unix_millis()
+				+ slots_since_first_slot
+					* self.mainchain_epoch_config.slot_duration_millis.millis();
+		let block = MainchainBlock {
+			number: McBlockNumber(height.try_into()?),
+			hash: point.block_hash()?,
+			epoch,
+			slot,
+			timestamp: timestamp_millis / 1000,
+		};
+		if let Ok(mut cache) = self.blocks_cache.lock() {
+			cache.put(block.hash.clone(), block.clone());
+		}
+		Ok(block)
+	}
+
+	fn get_cached(&self, hash: &McBlockHash) -> Option<MainchainBlock> {
+		self.blocks_cache.lock().ok()?.get(hash).cloned()
+	}
+
+	fn slot_at(&self, timestamp_millis: u64) -> McSlotNumber {
+		let timestamp = mainchain_epoch::Timestamp::from_unix_millis(timestamp_millis);
+		let slot = (self.mainchain_epoch_config)
+			.timestamp_to_mainchain_slot_number(timestamp)
+			.unwrap_or(self.mainchain_epoch_config.first_slot_number);
+		McSlotNumber(slot)
+	}
+
+	fn min_block_allowed_time(&self, reference_timestamp: Timestamp) -> u64 {
+		reference_timestamp
+			.as_millis()
+			.saturating_sub(self.max_slot_boundary.as_millis() as u64)
+	}
+
+	fn max_block_allowed_time(&self, reference_timestamp: Timestamp) -> u64 {
+		reference_timestamp
+			.as_millis()
+			.saturating_sub(self.min_slot_boundary.as_millis() as u64)
+	}
+
+	/// Rules for block selection and verification mandates that timestamp of the block
+	/// falls in a given range, calculated from the reference timestamp, which is either
+	/// PC current time or PC block timestamp.
+	fn is_block_time_valid(&self, block: &MainchainBlock, reference_timestamp: Timestamp) -> bool {
+		self.min_block_allowed_time(reference_timestamp) / 1000 <= block.timestamp
+			&& block.timestamp <= self.max_block_allowed_time(reference_timestamp) / 1000
+	}
+}
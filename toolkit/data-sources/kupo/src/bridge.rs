@@ -0,0 +1,205 @@
+use crate::{
+	DataSourceError, Result,
+	block::BlockDataSourceImpl,
+	client::{KupoApi, KupoClient, Pattern, types::KupoMatch},
+	datum::get_datum,
+};
+use partner_chains_plutus_data::bridge::{TokenTransferDatum, TokenTransferDatumV1};
+use sidechain_domain::*;
+use sp_partner_chains_bridge::{
+	BridgeDataCheckpoint, BridgeTransferV1, MainChainScripts, TokenBridgeDataSource,
+};
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+/// Kupo based data source for the Token Bridge feature of Partner Chains toolkit
+///
+/// See documentation of [sp_partner_chains_bridge] for a description of the feature
+pub struct TokenBridgeDataSourceImpl<RecipientAddress> {
+	/// Kupo client
+	client: KupoClient,
+	/// [BlockDataSourceImpl] instance shared with other data sources for cache reuse.
+	blocks: Arc<BlockDataSourceImpl>,
+	_phantom: PhantomData<RecipientAddress>,
+}
+
+impl<RecipientAddress> TokenBridgeDataSourceImpl<RecipientAddress> {
+	/// Creates a new instance of the data source
+	pub fn new(client: KupoClient, blocks: Arc<BlockDataSourceImpl>) -> Self {
+		Self { client, blocks, _phantom: PhantomData::default() }
+	}
+}
+
+#[async_trait::async_trait]
+impl<RecipientAddress: Send + Sync> TokenBridgeDataSource<RecipientAddress>
+	for TokenBridgeDataSourceImpl<RecipientAddress>
+where
+	RecipientAddress: Debug,
+	RecipientAddress: (for<'a> TryFrom<&'a [u8]>),
+{
+	async fn get_transfers(
+		&self,
+		main_chain_scripts: MainChainScripts,
+		data_checkpoint: BridgeDataCheckpoint,
+		max_transfers: u32,
+		current_mc_block_hash: McBlockHash,
+	) -> Result<(Vec<BridgeTransferV1<RecipientAddress>>, BridgeDataCheckpoint)> {
+		let current_mc_block = (self.blocks.get_block_by_hash(current_mc_block_hash.clone()))
+			.await?
+			.ok_or(DataSourceError::ExpectedDataNotFound(format!(
+				"Block hash: {current_mc_block_hash}"
+			)))?;
+
+		let data_checkpoint = self.resolve_checkpoint(data_checkpoint).await?;
+
+		let utxos = self
+			.get_bridge_utxos(
+				&main_chain_scripts,
+				data_checkpoint,
+				current_mc_block.slot,
+				max_transfers,
+			)
+			.await?;
+
+		let new_checkpoint = match utxos.last() {
+			None => BridgeDataCheckpoint::Block(current_mc_block.number),
+			Some(_) if (utxos.len() as u32) < max_transfers => {
+				BridgeDataCheckpoint::Block(current_mc_block.number)
+			},
+			Some(utxo) => BridgeDataCheckpoint::Utxo(utxo.utxo_id),
+		};
+
+		let transfers = utxos.into_iter().flat_map(utxo_to_transfer).collect();
+
+		Ok((transfers, new_checkpoint))
+	}
+}
+
+fn utxo_to_transfer<RecipientAddress>(
+	utxo: BridgeUtxo,
+) -> Option<BridgeTransferV1<RecipientAddress>>
+where
+	RecipientAddress: for<'a> TryFrom<&'a [u8]>,
+{
+	let token_delta = utxo.tokens_out.0.checked_sub(utxo.tokens_in.0)?;
+
+	if token_delta == 0 {
+		return None;
+	}
+
+	let token_amount = token_delta as u64;
+
+	let Some(datum) = utxo.datum else {
+		return Some(BridgeTransferV1::InvalidTransfer { token_amount, utxo_id: utxo.utxo_id });
+	};
+
+	let transfer = match TokenTransferDatum::try_from(datum) {
+		Ok(TokenTransferDatum::V1(TokenTransferDatumV1::UserTransfer { receiver })) => {
+			match RecipientAddress::try_from(receiver.0.as_ref()) {
+				Ok(recipient) => BridgeTransferV1::UserTransfer { token_amount, recipient },
+				Err(_) => BridgeTransferV1::InvalidTransfer { token_amount, utxo_id: utxo.utxo_id },
+			}
+		},
+		Ok(TokenTransferDatum::V1(TokenTransferDatumV1::ReserveTransfer)) => {
+			BridgeTransferV1::ReserveTransfer { token_amount }
+		},
+		Err(_) => BridgeTransferV1::InvalidTransfer { token_amount, utxo_id: utxo.utxo_id },
+	};
+
+	Some(transfer)
+}
+
+struct BridgeUtxo {
+	utxo_id: UtxoId,
+	tokens_out: NativeTokenAmount,
+	tokens_in: NativeTokenAmount,
+	datum: Option<cardano_serialization_lib::PlutusData>,
+}
+
+/// Kupo ordering key of an output: slot, transaction index and output index
+type UtxoOrderingKey = (u64, u32, u16);
+
+enum ResolvedBridgeDataCheckpoint {
+	/// Transfers created after the output with this ordering key are returned
+	Utxo(UtxoOrderingKey),
+	/// Transfers created after the block with this number are returned.
+	/// Slot of the block is known if the block was already seen by the data source.
+	Block { number: McBlockNumber, slot: Option<McSlotNumber> },
+}
+
+impl<RecipientAddress> TokenBridgeDataSourceImpl<RecipientAddress> {
+	async fn resolve_checkpoint(
+		&self,
+		checkpoint: BridgeDataCheckpoint,
+	) -> Result<ResolvedBridgeDataCheckpoint> {
+		match checkpoint {
+			BridgeDataCheckpoint::Utxo(utxo) => {
+				let output = (self.client.matches(&Pattern::OutputReference(utxo), None).await?)
+					.into_iter()
+					.next()
+					.ok_or(format!("Could not find data checkpoint: {checkpoint:?}"))?;
+				Ok(ResolvedBridgeDataCheckpoint::Utxo(output.ordering_key()))
+			},
+			BridgeDataCheckpoint::Block(number) => Ok(ResolvedBridgeDataCheckpoint::Block {
+				number,
+				slot: self.blocks.cached_slot_of_block(number),
+			}),
+		}
+	}
+
+	async fn is_after_checkpoint(
+		&self,
+		output: &KupoMatch,
+		checkpoint: &ResolvedBridgeDataCheckpoint,
+	) -> Result<bool> {
+		Ok(match checkpoint {
+			ResolvedBridgeDataCheckpoint::Utxo(key) => output.ordering_key() > *key,
+			ResolvedBridgeDataCheckpoint::Block { slot: Some(slot), .. } => {
+				output.created_at.slot_no > slot.0
+			},
+			ResolvedBridgeDataCheckpoint::Block { number, slot: None } => {
+				self.blocks.block_at(&output.created_at).await?.number > *number
+			},
+		})
+	}
+
+	async fn get_bridge_utxos(
+		&self,
+		main_chain_scripts: &MainChainScripts,
+		checkpoint: ResolvedBridgeDataCheckpoint,
+		to_slot: McSlotNumber,
+		max_utxos: u32,
+	) -> Result<Vec<BridgeUtxo>> {
+		let asset = main_chain_scripts.asset_id();
+		let pattern = Pattern::Address(
+			main_chain_scripts.illiquid_circulation_supply_validator_address.clone(),
+		);
+		let ics_outputs = self.client.matches(&pattern, Some(to_slot)).await?;
+
+		let mut outputs = Vec::new();
+		for output in ics_outputs.iter().filter(|output| output.value.asset_amount(&asset) > 0) {
+			if self.is_after_checkpoint(output, &checkpoint).await? {
+				outputs.push(output);
+			}
+		}
+		outputs.sort_by_key(|output| output.ordering_key());
+		outputs.truncate(max_utxos as usize);
+
+		let mut bridge_utxos = Vec::with_capacity(outputs.len());
+		for output in outputs {
+			let tokens_in = (ics_outputs.iter())
+				.filter(|input| input.is_spent_by(&output.transaction_id))
+				.map(|input| input.value.asset_amount(&asset))
+				.sum();
+			bridge_utxos.push(BridgeUtxo {
+				utxo_id: output.utxo_id()?,
+				tokens_out: NativeTokenAmount(output.value.asset_amount(&asset)),
+				tokens_in: NativeTokenAmount(tokens_in),
+				datum: get_datum(&self.client, output).await?,
+			});
+		}
+
+		Ok(bridge_utxos)
+	}
+}
@@ -0,0 +1,150 @@
+use crate::{
+	DataSourceError, Result,
+	block::BlockDataSourceImpl,
+	client::{KupoApi, KupoClient, Pattern, types::KupoMatch},
+	datum::get_datum,
+};
+use async_trait::async_trait;
+use authority_selection_inherents::*;
+use partner_chains_plutus_data::{
+	d_param::DParamDatum, permissioned_candidates::PermissionedCandidateDatums,
+	registered_candidates::RegisterValidatorDatum,
+};
+use sidechain_domain::*;
+use std::sync::Arc;
+
+/// Kupo based data source serving data for Partner Chain committee selection
+///
+/// Stake delegation and epoch nonce are not available in Kupo, so this data source can serve
+/// Ariadne parameters, but fails to provide registered candidates and epoch nonce. It should be
+/// used together with a data source providing them, eg. as the secondary one of a failover data
+/// source.
+pub struct AuthoritySelectionDataSourceImpl {
+	/// Kupo client
+	client: KupoClient,
+	/// [BlockDataSourceImpl] instance shared with other data sources for cache reuse.
+	blocks: Arc<BlockDataSourceImpl>,
+}
+
+impl AuthoritySelectionDataSourceImpl {
+	/// Creates a new instance of the data source
+	pub fn new(client: KupoClient, blocks: Arc<BlockDataSourceImpl>) -> Self {
+		Self { client, blocks }
+	}
+}
+
+#[async_trait]
+impl AuthoritySelectionDataSource for AuthoritySelectionDataSourceImpl {
+	async fn get_ariadne_parameters(
+		&self,
+		epoch_number: McEpochNumber,
+		d_parameter_policy: PolicyId,
+		permissioned_candidate_policy: PolicyId,
+	) -> Result<AriadneParameters> {
+		let epoch = self.get_epoch_of_data_storage(epoch_number)?;
+		let last_slot = self.blocks.last_slot_of_epoch(epoch)?;
+
+		let d_datum = (self.get_policy_datum(d_parameter_policy, last_slot).await?)
+			.ok_or(DataSourceError::ExpectedDataNotFound("DParameter Datum".to_string()))?;
+		let d_parameter = DParamDatum::try_from(d_datum)?.into();
+
+		let permissioned_candidates =
+			match self.get_policy_datum(permissioned_candidate_policy, last_slot).await? {
+				None => None,
+				Some(candidates_datum) => {
+					Some(PermissionedCandidateDatums::try_from(candidates_datum)?.into())
+				},
+			};
+
+		Ok(AriadneParameters { d_parameter, permissioned_candidates })
+	}
+
+	/// Kupo does not provide stake delegation, without which registered candidates can not be
+	/// validated. An error is returned when any Ada-based registration is present, so that the
+	/// committee is not silently selected from permissioned candidates only.
+	async fn get_candidates(
+		&self,
+		epoch_number: McEpochNumber,
+		committee_candidate_address: MainchainAddress,
+	) -> Result<Vec<CandidateRegistrations>> {
+		let epoch = self.get_epoch_of_data_storage(epoch_number)?;
+		let last_slot = self.blocks.last_slot_of_epoch(epoch)?;
+		let pattern = Pattern::Address(committee_candidate_address.clone());
+		let outputs = (self.client.matches(&pattern, Some(last_slot)).await?)
+			.into_iter()
+			.filter(|output| output.is_unspent_at(last_slot));
+
+		let mut ada_based_registrations = 0;
+		for output in outputs {
+			// Token-based registrations are served by `get_token_staked_candidates`
+			if let Some(StakeOwnership::AdaBased(_)) = self.parse_stake_ownership(&output).await? {
+				ada_based_registrations += 1;
+			}
+		}
+
+		if ada_based_registrations > 0 {
+			return Err(DataSourceError::ExpectedDataNotFound(format!(
+				"Stake delegation of {ada_based_registrations} candidates registered at {committee_candidate_address} in epoch {epoch}"
+			))
+			.into());
+		}
+		Ok(vec![])
+	}
+
+	async fn get_epoch_nonce(&self, epoch_number: McEpochNumber) -> Result<Option<EpochNonce>> {
+		Err(DataSourceError::ExpectedDataNotFound(format!("Epoch nonce of epoch {epoch_number}"))
+			.into())
+	}
+
+	async fn data_epoch(&self, for_epoch: McEpochNumber) -> Result<McEpochNumber> {
+		self.get_epoch_of_data_storage(for_epoch)
+	}
+}
+
+impl AuthoritySelectionDataSourceImpl {
+	/// Returns the datum of the latest output holding a token of `policy_id`, unspent at `slot`
+	async fn get_policy_datum(
+		&self,
+		policy_id: PolicyId,
+		slot: McSlotNumber,
+	) -> Result<Option<cardano_serialization_lib::PlutusData>> {
+		let pattern = Pattern::Policy(policy_id.clone());
+		let output = (self.client.matches(&pattern, Some(slot)).await?)
+			.into_iter()
+			.filter(|output| output.is_unspent_at(slot) && output.value.contains_policy(&policy_id))
+			.max_by_key(|output| output.ordering_key());
+		match output {
+			Some(output) => get_datum(&self.client, &output).await,
+			None => Ok(None),
+		}
+	}
+
+	/// Returns stake ownership of the registration, `None` if its datum is missing or invalid
+	async fn parse_stake_ownership(&self, output: &KupoMatch) -> Result<Option<StakeOwnership>> {
+		let utxo_id = output.utxo_id()?;
+		let Some(datum) = get_datum(&self.client, output).await? else {
+			log::error!("Missing registration datum for {utxo_id:?}");
+			return Ok(None);
+		};
+		match RegisterValidatorDatum::try_from(datum) {
+			Ok(datum) => Ok(Some(CandidateRegistration::from(datum).stake_ownership)),
+			Err(_) => {
+				log::error!("Invalid registration datum for {utxo_id:?}");
+				Ok(None)
+			},
+		}
+	}
+
+	fn get_epoch_of_data_storage(
+		&self,
+		epoch_of_data_usage: McEpochNumber,
+	) -> Result<McEpochNumber> {
+		offset_data_epoch(&epoch_of_data_usage).map_err(|offset| {
+			DataSourceError::BadRequest(format!(
+				"Minimum supported epoch of data usage is {offset}, but {} was provided",
+				epoch_of_data_usage.0
+			))
+			.into()
+		})
+	}
+}
@@ -0,0 +1,54 @@
+use crate::{
+	DataSourceError,
+	client::types::{KupoMatch, KupoPoint},
+};
+use async_trait::async_trait;
+use sidechain_domain::*;
+
+/// Pattern selecting outputs indexed by Kupo
+///
+/// See <https://cardanosolutions.github.io/kupo/#section/Patterns> for the pattern syntax.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Pattern {
+	/// Outputs at the address
+	Address(MainchainAddress),
+	/// Outputs containing any asset of the policy
+	Policy(PolicyId),
+	/// The output with given id
+	OutputReference(UtxoId),
+}
+
+impl std::fmt::Display for Pattern {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Pattern::Address(address) => write!(f, "{address}"),
+			Pattern::Policy(policy_id) => write!(f, "{}.*", hex::encode(policy_id.0)),
+			Pattern::OutputReference(UtxoId { tx_hash, index }) => {
+				write!(f, "{}@{}", index.0, hex::encode(tx_hash.0))
+			},
+		}
+	}
+}
+
+#[async_trait]
+/// Kupo HTTP API interface
+pub trait KupoApi {
+	/// Outputs matching `pattern`, both spent and unspent, created in or before `up_to_slot`.
+	async fn matches(
+		&self,
+		pattern: &Pattern,
+		up_to_slot: Option<McSlotNumber>,
+	) -> Result<Vec<KupoMatch>, DataSourceError>;
+
+	/// Hex encoded CBOR of the datum with given hash, `None` if the datum is not known to Kupo.
+	async fn datum(&self, datum_hash: &str) -> Result<Option<String>, DataSourceError>;
+
+	/// Recent checkpoints of Kupo, ordered from the most recent.
+	async fn checkpoints(&self) -> Result<Vec<KupoPoint>, DataSourceError>;
+
+	/// The most recent checkpoint at or before `slot`.
+	async fn checkpoint_at_or_before(
+		&self,
+		slot: McSlotNumber,
+	) -> Result<Option<KupoPoint>, DataSourceError>;
+}
@@ -0,0 +1,92 @@
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use sidechain_domain::*;
+use std::time::Duration;
+use ureq::Agent;
+
+use crate::{
+	DataSourceError,
+	client::{
+		api::{KupoApi, Pattern},
+		types::{KupoDatum, KupoMatch, KupoPoint},
+	},
+};
+
+/// Client of the Kupo HTTP API
+#[derive(Clone)]
+pub struct KupoClient {
+	agent: ureq::Agent,
+	addr: String,
+}
+
+impl KupoClient {
+	/// Creates a client of Kupo served at `addr`, eg. `http://localhost:1442`
+	pub fn new(addr: &str, timeout: Duration) -> Self {
+		let agent = Agent::config_builder().timeout_per_call(Some(timeout)).build().into();
+		KupoClient { agent, addr: addr.strip_suffix("/").unwrap_or(addr).to_string() }
+	}
+
+	async fn request<T: DeserializeOwned + std::fmt::Debug>(
+		&self,
+		method: &str,
+		query: &[(&str, String)],
+	) -> Result<T, DataSourceError> {
+		let mut req_url = url::Url::parse(&format!("{}/{}", self.addr, method))
+			.map_err(|e| DataSourceError::KupoCallError(e.to_string()))?;
+		if !query.is_empty() {
+			let mut query_pairs = url::form_urlencoded::Serializer::new(String::new());
+			query_pairs.extend_pairs(query.iter().map(|(k, v)| (k, v.as_str())));
+			req_url.set_query(Some(&query_pairs.finish()));
+		}
+		log::trace!("Kupo request: {req_url:?}");
+		let resp = self
+			.agent
+			.get(req_url.as_str())
+			.call()
+			.map_err(|e| DataSourceError::KupoCallError(e.to_string()))
+			.and_then(|mut r| {
+				r.body_mut()
+					.read_json()
+					.map_err(|e| DataSourceError::KupoResponseParseError(e.to_string()))
+			});
+		log::trace!("Kupo response: {resp:?}");
+		resp
+	}
+}
+
+#[async_trait]
+impl KupoApi for KupoClient {
+	async fn matches(
+		&self,
+		pattern: &Pattern,
+		up_to_slot: Option<McSlotNumber>,
+	) -> Result<Vec<KupoMatch>, DataSourceError> {
+		let mut query = vec![("order", "oldest_first".to_string())];
+		if let Some(slot) = up_to_slot {
+			// `created_before` bound is exclusive
+			query.push(("created_before", (slot.0 + 1).to_string()));
+		}
+		let matches: Vec<KupoMatch> = self.request(&format!("matches/{pattern}"), &query).await?;
+		// Filtering again, in case the server ignored the bound
+		Ok(matches
+			.into_iter()
+			.filter(|m| up_to_slot.is_none_or(|slot| m.created_at.slot_no <= slot.0))
+			.collect())
+	}
+
+	async fn datum(&self, datum_hash: &str) -> Result<Option<String>, DataSourceError> {
+		let datum: Option<KupoDatum> = self.request(&format!("datums/{datum_hash}"), &[]).await?;
+		Ok(datum.map(|d| d.datum))
+	}
+
+	async fn checkpoints(&self) -> Result<Vec<KupoPoint>, DataSourceError> {
+		self.request("checkpoints", &[]).await
+	}
+
+	async fn checkpoint_at_or_before(
+		&self,
+		slot: McSlotNumber,
+	) -> Result<Option<KupoPoint>, DataSourceError> {
+		self.request(&format!("checkpoints/{}", slot.0), &[]).await
+	}
+}
@@ -0,0 +1,6 @@
+//! Client of the Kupo HTTP API
+pub mod api;
+pub use api::{KupoApi, Pattern};
+pub mod kupo;
+pub use kupo::KupoClient;
+pub mod types;
@@ -0,0 +1,201 @@
+//! Types of the Kupo HTTP API responses
+use serde::Deserialize;
+use sidechain_domain::*;
+use std::collections::BTreeMap;
+
+/// Point on the chain, identifying a block
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct KupoPoint {
+	/// Slot of the block
+	pub slot_no: u64,
+	/// Hex encoded header hash of the block
+	pub header_hash: String,
+}
+
+impl KupoPoint {
+	/// Returns the header hash of the block
+	pub fn block_hash(&self) -> Result<McBlockHash, String> {
+		McBlockHash::decode_hex(&self.header_hash)
+			.map_err(|e| format!("Invalid header hash '{}': {e}", self.header_hash))
+	}
+}
+
+/// Point on the chain at which an output was spent
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct KupoSpentAt {
+	/// Slot of the block containing the spending transaction
+	pub slot_no: u64,
+	/// Hex encoded header hash of the block containing the spending transaction
+	pub header_hash: String,
+	/// Hex encoded id of the spending transaction. Not reported by older Kupo versions.
+	#[serde(default)]
+	pub transaction_id: Option<String>,
+}
+
+/// Value locked in an output
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+pub struct KupoValue {
+	/// Amount of lovelace
+	pub coins: u64,
+	/// Amounts of native assets, keyed by `{policy_id}.{asset_name}`, or `{policy_id}` for empty
+	/// asset names
+	#[serde(default)]
+	pub assets: BTreeMap<String, u128>,
+}
+
+impl KupoValue {
+	/// Returns the amount of `asset` in the value
+	pub fn asset_amount(&self, asset: &AssetId) -> u128 {
+		self.assets.get(&format_asset_id(asset)).copied().unwrap_or_default()
+	}
+
+	/// Returns true if the value contains any asset of `policy_id`
+	pub fn contains_policy(&self, policy_id: &PolicyId) -> bool {
+		let policy_id = hex::encode(policy_id.0);
+		self.assets
+			.iter()
+			.any(|(unit, amount)| *amount > 0 && unit.split('.').next() == Some(policy_id.as_str()))
+	}
+}
+
+/// Transaction output matching a pattern
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct KupoMatch {
+	/// Index of the transaction within its block
+	pub transaction_index: u32,
+	/// Hex encoded id of the transaction
+	pub transaction_id: String,
+	/// Index of the output within its transaction
+	pub output_index: u16,
+	/// Bech32 address of the output
+	pub address: String,
+	/// Value locked in the output
+	pub value: KupoValue,
+	/// Hex encoded hash of the datum of the output, present both for inline and hashed datums
+	pub datum_hash: Option<String>,
+	/// Point at which the output was created
+	pub created_at: KupoPoint,
+	/// Point at which the output was spent, `None` for unspent outputs
+	pub spent_at: Option<KupoSpentAt>,
+}
+
+impl KupoMatch {
+	/// Returns the id of the output
+	pub fn utxo_id(&self) -> Result<UtxoId, String> {
+		let tx_hash = McTxHash::decode_hex(&self.transaction_id)
+			.map_err(|e| format!("Invalid transaction id '{}': {e}", self.transaction_id))?;
+		Ok(UtxoId { tx_hash, index: UtxoIndex(self.output_index) })
+	}
+
+	/// Returns true if the output was created in or before `slot` and not spent until `slot`
+	pub fn is_unspent_at(&self, slot: McSlotNumber) -> bool {
+		self.created_at.slot_no <= slot.0
+			&& self.spent_at.as_ref().is_none_or(|spent_at| spent_at.slot_no > slot.0)
+	}
+
+	/// Returns true if the output was spent by transaction `tx_id`
+	pub fn is_spent_by(&self, tx_id: &str) -> bool {
+		self.spent_at.as_ref().and_then(|s| s.transaction_id.as_deref()) == Some(tx_id)
+	}
+
+	/// Key ordering the outputs in the order of their creation
+	pub fn ordering_key(&self) -> (u64, u32, u16) {
+		(self.created_at.slot_no, self.transaction_index, self.output_index)
+	}
+}
+
+/// Datum stored by Kupo
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct KupoDatum {
+	/// Hex encoded CBOR of the datum
+	pub datum: String,
+}
+
+/// Formats `asset` the way Kupo identifies assets: `{policy_id}.{asset_name}`, or `{policy_id}` for
+/// empty asset names
+pub fn format_asset_id(asset: &AssetId) -> String {
+	let policy_id = hex::encode(asset.policy_id.0);
+	if asset.asset_name.0.is_empty() {
+		policy_id
+	} else {
+		format!("{policy_id}.{}", hex::encode(asset.asset_name.0.as_slice()))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use hex_literal::hex;
+
+	const POLICY: PolicyId =
+		PolicyId(hex!("500000000000000000000000000000000000434845434b504f494e69"));
+
+	fn kupo_match() -> KupoMatch {
+		serde_json::from_value(serde_json::json!({
+			"transaction_index": 3,
+			"transaction_id": "bfc3ed6a8a1c5a4ef1b4d6a7e1f2c0d3bfc3ed6a8a1c5a4ef1b4d6a7e1f2c0d3",
+			"output_index": 1,
+			"address": "addr_test1wz5qc7fk2pat0058w4zwvkw35ytptej3nuc3je2kgtan5dq3rt4sc",
+			"value": {
+				"coins": 2000000,
+				"assets": {
+					"500000000000000000000000000000000000434845434b504f494e69.4c2d544f4b454e": 15
+				}
+			},
+			"datum_hash": null,
+			"script_hash": null,
+			"created_at": {
+				"slot_no": 100,
+				"header_hash": "0000000000000000000000000000000000000000000000000000000000000001"
+			},
+			"spent_at": {
+				"slot_no": 200,
+				"header_hash": "0000000000000000000000000000000000000000000000000000000000000002",
+				"transaction_id": "aa00000000000000000000000000000000000000000000000000000000000000"
+			}
+		}))
+		.unwrap()
+	}
+
+	#[test]
+	fn kupo_match_is_unspent_only_between_creation_and_spending() {
+		let output = kupo_match();
+		assert!(!output.is_unspent_at(McSlotNumber(99)));
+		assert!(output.is_unspent_at(McSlotNumber(100)));
+		assert!(output.is_unspent_at(McSlotNumber(199)));
+		assert!(!output.is_unspent_at(McSlotNumber(200)));
+	}
+
+	#[test]
+	fn kupo_match_reports_spending_transaction() {
+		let output = kupo_match();
+		assert!(
+			output.is_spent_by("aa00000000000000000000000000000000000000000000000000000000000000")
+		);
+		assert!(!output.is_spent_by(&output.transaction_id));
+	}
+
+	#[test]
+	fn kupo_value_reads_asset_amounts() {
+		let value = kupo_match().value;
+		let asset = AssetId {
+			policy_id: POLICY,
+			asset_name: AssetName::decode_hex("4c2d544f4b454e").unwrap(),
+		};
+		assert_eq!(value.asset_amount(&asset), 15);
+		assert_eq!(
+			value.asset_amount(&AssetId { policy_id: POLICY, asset_name: AssetName::empty() }),
+			0
+		);
+		assert!(value.contains_policy(&POLICY));
+		assert!(!value.contains_policy(&PolicyId([1; 28])));
+	}
+
+	#[test]
+	fn formats_asset_ids_like_kupo() {
+		assert_eq!(
+			format_asset_id(&AssetId { policy_id: POLICY, asset_name: AssetName::empty() }),
+			"500000000000000000000000000000000000434845434b504f494e69"
+		);
+	}
+}
@@ -0,0 +1,22 @@
+use crate::{
+	Result,
+	client::{KupoApi, types::KupoMatch},
+};
+use cardano_serialization_lib::PlutusData;
+
+/// Returns the datum of `output`, or `None` if the output has no datum or the datum is not known to Kupo
+pub(crate) async fn get_datum(
+	client: &(impl KupoApi + Sync),
+	output: &KupoMatch,
+) -> Result<Option<PlutusData>> {
+	let Some(datum_hash) = &output.datum_hash else { return Ok(None) };
+	let Some(datum) = client.datum(datum_hash).await? else {
+		log::warn!(
+			"Datum {datum_hash} of {}#{} is not known to Kupo",
+			output.transaction_id,
+			output.output_index
+		);
+		return Ok(None);
+	};
+	Ok(Some(PlutusData::from_hex(&datum)?))
+}
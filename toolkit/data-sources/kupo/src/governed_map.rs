@@ -0,0 +1,92 @@
+use crate::{
+	DataSourceError, Result,
+	block::BlockDataSourceImpl,
+	client::{KupoApi, KupoClient, Pattern},
+	datum::get_datum,
+};
+use async_trait::async_trait;
+use partner_chains_plutus_data::governed_map::GovernedMapDatum;
+use sidechain_domain::byte_string::ByteString;
+use sidechain_domain::*;
+use sp_governed_map::{GovernedMapDataSource, MainChainScriptsV1};
+use std::{collections::BTreeMap, sync::Arc};
+
+/// Kupo based data source for the Governed Map feature of Partner Chains toolkit
+///
+/// See documentation of [sp_governed_map] for a description of the feature
+pub struct GovernedMapDataSourceImpl {
+	/// Kupo client
+	client: KupoClient,
+	/// [BlockDataSourceImpl] instance shared with other data sources for cache reuse.
+	blocks: Arc<BlockDataSourceImpl>,
+}
+
+impl GovernedMapDataSourceImpl {
+	/// Creates a new instance of the data source
+	pub fn new(client: KupoClient, blocks: Arc<BlockDataSourceImpl>) -> Self {
+		Self { client, blocks }
+	}
+}
+
+#[async_trait]
+impl GovernedMapDataSource for GovernedMapDataSourceImpl {
+	async fn get_state_at_block(
+		&self,
+		mc_block: McBlockHash,
+		main_chain_scripts: MainChainScriptsV1,
+	) -> Result<BTreeMap<String, ByteString>> {
+		let block = (self.blocks.get_block_by_hash(mc_block.clone()).await?)
+			.ok_or(DataSourceError::ExpectedDataNotFound(format!("Block hash: {mc_block}")))?;
+		let pattern = Pattern::Address(main_chain_scripts.validator_address);
+		let mut outputs = (self.client.matches(&pattern, Some(block.slot)).await?)
+			.into_iter()
+			.filter(|output| {
+				output.is_unspent_at(block.slot)
+					&& output.value.contains_policy(&main_chain_scripts.asset_policy_id)
+			})
+			.collect::<Vec<_>>();
+		outputs.sort_by_key(|output| output.ordering_key());
+
+		let mut mappings = BTreeMap::new();
+		for output in outputs {
+			let Some(datum) = get_datum(&self.client, &output).await? else { continue };
+			match GovernedMapDatum::try_from(datum) {
+				Ok(GovernedMapDatum { key, value }) => {
+					mappings.insert(key, value);
+				},
+				Err(err) => log::warn!("Failed decoding map entry: {err}"),
+			}
+		}
+		Ok(mappings)
+	}
+
+	async fn get_mapping_changes(
+		&self,
+		since_mc_block: Option<McBlockHash>,
+		up_to_mc_block: McBlockHash,
+		scripts: MainChainScriptsV1,
+	) -> Result<Vec<(String, Option<ByteString>)>> {
+		let current_mappings = self.get_state_at_block(up_to_mc_block, scripts.clone()).await?;
+
+		let Some(since_mc_block) = since_mc_block else {
+			let changes =
+				current_mappings.into_iter().map(|(key, value)| (key, Some(value))).collect();
+			return Ok(changes);
+		};
+
+		let previous_mappings = self.get_state_at_block(since_mc_block, scripts).await?;
+
+		let mut changes = Vec::new();
+		for (key, value) in current_mappings.iter() {
+			if previous_mappings.get(key) != Some(value) {
+				changes.push((key.clone(), Some(value.clone())));
+			}
+		}
+		for key in previous_mappings.keys() {
+			if !current_mappings.contains_key(key) {
+				changes.push((key.clone(), None));
+			}
+		}
+		Ok(changes)
+	}
+}
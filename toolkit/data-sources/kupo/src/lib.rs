@@ -0,0 +1,149 @@
+//! Crate providing implementations of Partner Chain Data Sources that read from Kupo.
+//!
+//! [Kupo](https://cardanosolutions.github.io/kupo) is a lightweight Cardano chain indexer, usually
+//! run next to Ogmios, that indexes transaction outputs matching configured patterns. It requires
+//! considerably less resources than Db-Sync, at the cost of serving only the outputs it was
+//! configured to index. Kupo does not track block heights, so these are read from Ogmios using
+//! its chain synchronization protocol.
+//!
+//! # Kupo configuration
+//!
+//! Kupo should be run without `--prune-utxo`, so that spent outputs remain available for queries
+//! about past blocks, and with match patterns covering all outputs observed by the data sources:
+//! - committee candidate validator address,
+//! - D-parameter and permissioned candidates policies, as `{policy_id}.*` patterns,
+//! - governed map validator address,
+//! - illiquid circulation supply validator address.
+//!
+//! # Limitations
+//!
+//! - Kupo keeps checkpoints only for the recent blocks, so a Cardano block can be found by its hash
+//!   only if it is recent or was already seen by the data sources. Nodes syncing a chain from
+//!   scratch should use another data source, eg. as the primary one of a failover data source.
+//! - Stake delegation and epoch nonce are not available in Kupo. Querying registered candidates
+//!   fails if any Ada-based registration is present and querying the epoch nonce always fails,
+//!   so the candidates data source should be the secondary one of a failover data source.
+//!
+//! # Usage
+//!
+//! ```rust,ignore
+//! use partner_chains_kupo_data_sources::*;
+//! use std::sync::Arc;
+//!
+//! async fn create_data_sources()
+//! -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+//!     let client = get_connection_from_env()?;
+//!     let heights = Arc::new(OgmiosBlockHeights::new_from_env().await?);
+//!     let blocks = Arc::new(BlockDataSourceImpl::new_from_env(client.clone(), heights)?);
+//!     let mc_hash = McHashDataSourceImpl::new(blocks.clone());
+//!     let governed_map = GovernedMapDataSourceImpl::new(client.clone(), blocks.clone());
+//!     Ok(())
+//! }
+//! ```
+#![deny(missing_docs)]
+
+#[cfg(feature = "candidate-source")]
+mod candidate;
+#[cfg(feature = "candidate-source")]
+pub use candidate::AuthoritySelectionDataSourceImpl;
+
+#[cfg(feature = "governed-map")]
+mod governed_map;
+#[cfg(feature = "governed-map")]
+pub use governed_map::GovernedMapDataSourceImpl;
+
+#[cfg(feature = "mc-hash")]
+mod mc_hash;
+#[cfg(feature = "mc-hash")]
+pub use mc_hash::McHashDataSourceImpl;
+
+#[cfg(feature = "sidechain-rpc")]
+mod sidechain_rpc;
+#[cfg(feature = "sidechain-rpc")]
+pub use sidechain_rpc::SidechainRpcDataSourceImpl;
+
+#[cfg(feature = "bridge")]
+mod bridge;
+#[cfg(feature = "bridge")]
+pub use bridge::TokenBridgeDataSourceImpl;
+
+#[cfg(feature = "block-source")]
+mod block;
+#[cfg(feature = "block-source")]
+pub use block::{BlockDataSourceImpl, BlockHeights, KupoBlockDataSourceConfig, OgmiosBlockHeights};
+
+#[cfg(any(feature = "candidate-source", feature = "governed-map", feature = "bridge"))]
+mod datum;
+
+#[cfg(feature = "block-source")]
+use sidechain_domain::mainchain_epoch::MainchainEpochConfig;
+
+use crate::client::KupoClient;
+
+pub mod client;
+
+type ResultErr = Box<dyn std::error::Error + Send + Sync>;
+type Result<T> = std::result::Result<T, ResultErr>;
+
+/// Error type returned by Kupo based data sources
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum DataSourceError {
+	/// Indicates that the data source was asked for data it can not serve
+	#[error("Bad request: `{0}`.")]
+	BadRequest(String),
+	/// Indicates that Kupo client produced an error while calling endpoint
+	#[error("Kupo client call error: `{0}`.")]
+	KupoCallError(String),
+	/// Indicates that Kupo client produced an error while parsing response
+	#[error("Kupo client response parse error: `{0}`.")]
+	KupoResponseParseError(String),
+	/// Indicates that Ogmios client produced an error
+	#[error("Ogmios client error: `{0}`.")]
+	OgmiosError(String),
+	/// Indicates that expected data was not found when querying Kupo
+	#[error(
+		"'{0}' not found. Possible causes: data source configuration error, Kupo not synced fully, Kupo match patterns not covering the data, or data not set on the main chain."
+	)]
+	ExpectedDataNotFound(String),
+	/// Indicates that data returned by Kupo is invalid
+	#[error(
+		"Invalid data. {0} Possible cause is an error in Plutus scripts or data source is outdated."
+	)]
+	InvalidData(String),
+}
+
+/// Returns a [KupoClient] constructed using configuration read from environment
+///
+/// # Environment variables read:
+/// - `KUPO_URL`: Kupo HTTP server, eg. `http://localhost:1442`
+pub fn get_connection_from_env() -> Result<KupoClient> {
+	let config = ConnectionConfig::from_env()?;
+	Ok(KupoClient::new(config.kupo_url.as_str(), std::time::Duration::from_secs(30)))
+}
+
+/// Kupo connection config used when creating a [KupoClient].
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct ConnectionConfig {
+	/// Kupo HTTP server, eg. `http://localhost:1442`
+	pub(crate) kupo_url: String,
+}
+
+impl ConnectionConfig {
+	/// Reads Kupo connection config from the environment
+	pub fn from_env() -> Result<Self> {
+		let config: Self = figment::Figment::new()
+			.merge(figment::providers::Env::raw())
+			.extract()
+			.map_err(|e| format!("Failed to read Kupo data source connection: {e}"))?;
+		Ok(config)
+	}
+}
+
+/// Reads Cardano main chain epoch configuration from the environment.
+///
+/// See documentation of [MainchainEpochConfig::read_from_env] for the list of environment variables read.
+#[cfg(feature = "block-source")]
+pub fn read_mc_epoch_config() -> Result<MainchainEpochConfig> {
+	Ok(MainchainEpochConfig::read_from_env()
+		.map_err(|e| format!("Failed to read main chain config: {}", e))?)
+}
@@ -0,0 +1,44 @@
+use crate::Result;
+use crate::block::BlockDataSourceImpl;
+use async_trait::async_trait;
+use sidechain_domain::*;
+use std::sync::Arc;
+
+/// Kupo based implementation of [sidechain_mc_hash::McHashDataSource]
+pub struct McHashDataSourceImpl {
+	/// [BlockDataSourceImpl] instance shared with other data sources for cache reuse.
+	inner: Arc<BlockDataSourceImpl>,
+}
+
+impl McHashDataSourceImpl {
+	/// Creates a new instance of the data source
+	pub fn new(inner: Arc<BlockDataSourceImpl>) -> Self {
+		Self { inner }
+	}
+}
+
+#[async_trait]
+impl sidechain_mc_hash::McHashDataSource for McHashDataSourceImpl {
+	async fn get_latest_stable_block_for(
+		&self,
+		reference_timestamp: sp_timestamp::Timestamp,
+	) -> Result<Option<MainchainBlock>> {
+		self.inner.get_latest_stable_block_for(reference_timestamp).await
+	}
+
+	async fn get_stable_block_for(
+		&self,
+		hash: McBlockHash,
+		reference_timestamp: sp_timestamp::Timestamp,
+	) -> Result<Option<MainchainBlock>> {
+		self.inner.get_stable_block_for(hash, reference_timestamp).await
+	}
+
+	async fn get_block_by_hash(&self, hash: McBlockHash) -> Result<Option<MainchainBlock>> {
+		self.inner.get_block_by_hash(hash).await
+	}
+
+	async fn get_tip(&self) -> Result<Option<MainchainBlock>> {
+		Ok(Some(self.inner.get_latest_block_info().await?))
+	}
+}
@@ -0,0 +1,24 @@
+use crate::{Result, block::BlockDataSourceImpl};
+use pallet_sidechain_rpc::SidechainRpcDataSource;
+use sidechain_domain::MainchainBlock;
+use std::sync::Arc;
+
+/// Kupo based implementation of [SidechainRpcDataSource]
+pub struct SidechainRpcDataSourceImpl {
+	/// [BlockDataSourceImpl] instance shared with other data sources for cache reuse.
+	inner: Arc<BlockDataSourceImpl>,
+}
+
+impl SidechainRpcDataSourceImpl {
+	/// Creates a new instance of the data source
+	pub fn new(inner: Arc<BlockDataSourceImpl>) -> Self {
+		Self { inner }
+	}
+}
+
+#[async_trait::async_trait]
+impl SidechainRpcDataSource for SidechainRpcDataSourceImpl {
+	async fn get_latest_block_info(&self) -> Result<MainchainBlock> {
+		self.inner.get_latest_block_info().await
+	}
+}
@@ -0,0 +1,316 @@
+#![cfg(all(
+	feature = "candidate-source",
+	feature = "governed-map",
+	feature = "bridge",
+	feature = "mc-hash"
+))]
+
+use async_trait::async_trait;
+use authority_selection_inherents::AuthoritySelectionDataSource;
+use cardano_serialization_lib::PlutusData;
+use partner_chains_kupo_data_sources::{
+	client::{KupoClient, types::KupoPoint},
+	*,
+};
+use partner_chains_plutus_data::{
+	bridge::{TokenTransferDatum, TokenTransferDatumV1},
+	governed_map::GovernedMapDatum,
+	registered_candidates::candidate_registration_to_plutus_data,
+};
+use serde_json::{Value, json};
+use sidechain_domain::{
+	byte_string::ByteString,
+	mainchain_epoch::{Duration, MainchainEpochConfig, Timestamp},
+	*,
+};
+use sidechain_mc_hash::McHashDataSource;
+use sp_governed_map::{GovernedMapDataSource, MainChainScriptsV1};
+use sp_partner_chains_bridge::{
+	BridgeDataCheckpoint, BridgeTransferV1, MainChainScripts, TokenBridgeDataSource,
+};
+use std::{str::FromStr, sync::Arc};
+
+mod http_server;
+
+const BLOCK_HASH: &str = "0505050505050505050505050505050505050505050505050505050505050505";
+const POLICY: &str = "500000000000000000000000000000000000434845434b504f494e69";
+const TX_1: &str = "1111111111111111111111111111111111111111111111111111111111111111";
+const TX_2: &str = "2222222222222222222222222222222222222222222222222222222222222222";
+const TX_3: &str = "3333333333333333333333333333333333333333333333333333333333333333";
+
+/// Block heights of the test chain, where each block is 10 slots long
+struct FakeHeights;
+
+#[async_trait]
+impl BlockHeights for FakeHeights {
+	async fn tip(
+		&self,
+	) -> Result<Option<(KupoPoint, u64)>, Box<dyn std::error::Error + Send + Sync>> {
+		Ok(Some((block_point(), 50)))
+	}
+
+	async fn height(
+		&self,
+		point: &KupoPoint,
+	) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+		Ok(point.slot_no / 10)
+	}
+}
+
+fn block_point() -> KupoPoint {
+	KupoPoint { slot_no: 500, header_hash: BLOCK_HASH.to_string() }
+}
+
+fn blocks_for(addr: std::net::SocketAddr) -> (KupoClient, Arc<BlockDataSourceImpl>) {
+	let client = KupoClient::new(&format!("http://{addr}/"), std::time::Duration::from_secs(5));
+	let config = KupoBlockDataSourceConfig {
+		cardano_security_parameter: 5,
+		cardano_active_slots_coeff: 0.1,
+		block_stability_margin: 0,
+	};
+	let mc_epoch_config = MainchainEpochConfig {
+		epoch_duration_millis: Duration::from_millis(100_000),
+		slot_duration_millis: Duration::from_millis(1000),
+		first_epoch_timestamp_millis: Timestamp::from_unix_millis(0),
+		first_epoch_number: 0,
+		first_slot_number: 0,
+	};
+	let blocks =
+		BlockDataSourceImpl::new(client.clone(), Arc::new(FakeHeights), config, mc_epoch_config);
+	(client, Arc::new(blocks))
+}
+
+fn checkpoints() -> (&'static str, u16, Value) {
+	("/checkpoints", 200, json!([{ "slot_no": 500, "header_hash": BLOCK_HASH }]))
+}
+
+fn kupo_match(
+	tx_id: &str,
+	slot: u64,
+	assets: Value,
+	datum_hash: Option<&str>,
+	spent_at: Option<(u64, &str)>,
+) -> Value {
+	json!({
+		"transaction_index": 0,
+		"transaction_id": tx_id,
+		"output_index": 0,
+		"address": "addr_test1",
+		"value": { "coins": 2000000, "assets": assets },
+		"datum_hash": datum_hash,
+		"created_at": { "slot_no": slot, "header_hash": format!("{slot:064x}") },
+		"spent_at": spent_at.map(|(slot_no, transaction_id)| json!({
+			"slot_no": slot_no,
+			"header_hash": format!("{slot_no:064x}"),
+			"transaction_id": transaction_id
+		})),
+	})
+}
+
+fn datum_response(datum: PlutusData) -> Value {
+	json!({ "datum": datum.to_hex() })
+}
+
+fn registration_datum(stake_ownership: StakeOwnership) -> PlutusData {
+	candidate_registration_to_plutus_data(&CandidateRegistration {
+		stake_ownership,
+		partner_chain_pub_key: SidechainPublicKey(vec![2; 33]),
+		partner_chain_signature: SidechainSignature(vec![3; 64]),
+		own_pkh: MainchainKeyHash([4; 28]),
+		registration_utxo: UtxoId {
+			tx_hash: McTxHash::decode_hex(TX_3).unwrap(),
+			index: UtxoIndex(0),
+		},
+		keys: CandidateKeys(vec![]),
+	})
+}
+
+#[tokio::test]
+async fn mc_hash_finds_block_among_kupo_checkpoints() {
+	let (addr, _) = http_server::for_responses(vec![checkpoints()]).unwrap();
+	let (_, blocks) = blocks_for(addr);
+	let mc_hash = McHashDataSourceImpl::new(blocks);
+
+	let block = mc_hash
+		.get_block_by_hash(McBlockHash::decode_hex(BLOCK_HASH).unwrap())
+		.await
+		.unwrap()
+		.unwrap();
+
+	assert_eq!(block.number, McBlockNumber(50));
+	assert_eq!(block.slot, McSlotNumber(500));
+	assert_eq!(block.epoch, McEpochNumber(5));
+	assert_eq!(block.timestamp, 500);
+}
+
+#[tokio::test]
+async fn governed_map_returns_entries_unspent_at_the_block() {
+	let datum: PlutusData =
+		GovernedMapDatum { key: "key".into(), value: ByteString(vec![1, 2, 3]) }.into();
+	let (addr, _) = http_server::for_responses(vec![
+		checkpoints(),
+		(
+			"/matches/addr_test1gov",
+			200,
+			json!([
+				kupo_match(TX_1, 100, json!({ POLICY: 1 }), Some("d1"), None),
+				kupo_match(TX_2, 200, json!({ POLICY: 1 }), Some("d1"), Some((300, TX_3))),
+				kupo_match(TX_3, 300, json!({}), Some("d1"), None),
+			]),
+		),
+		("/datums/d1", 200, datum_response(datum)),
+	])
+	.unwrap();
+	let (client, blocks) = blocks_for(addr);
+	let governed_map = GovernedMapDataSourceImpl::new(client, blocks);
+
+	let state = governed_map
+		.get_state_at_block(
+			McBlockHash::decode_hex(BLOCK_HASH).unwrap(),
+			MainChainScriptsV1 {
+				validator_address: MainchainAddress::from_str("addr_test1gov").unwrap(),
+				asset_policy_id: PolicyId::decode_hex(POLICY).unwrap(),
+			},
+		)
+		.await
+		.unwrap();
+
+	assert_eq!(state, [("key".to_string(), ByteString(vec![1, 2, 3]))].into());
+}
+
+#[tokio::test]
+async fn bridge_returns_transfers_after_the_checkpoint_block() {
+	let reserve_datum: PlutusData =
+		TokenTransferDatum::V1(TokenTransferDatumV1::ReserveTransfer).into();
+	let token = format!("{POLICY}.544f4b454e");
+	let (addr, _) = http_server::for_responses(vec![
+		checkpoints(),
+		(
+			"/matches/addr_test1ics",
+			200,
+			json!([
+				kupo_match(TX_1, 300, json!({ &token: 30 }), None, Some((450, TX_2))),
+				kupo_match(TX_2, 450, json!({ &token: 100 }), Some("d1"), None),
+				kupo_match(TX_3, 480, json!({ &token: 5 }), None, None),
+			]),
+		),
+		("/datums/d1", 200, datum_response(reserve_datum)),
+	])
+	.unwrap();
+	let (client, blocks) = blocks_for(addr);
+	let bridge = TokenBridgeDataSourceImpl::<Vec<u8>>::new(client, blocks);
+
+	let (transfers, checkpoint) = bridge
+		.get_transfers(
+			MainChainScripts {
+				token_policy_id: PolicyId::decode_hex(POLICY).unwrap(),
+				token_asset_name: AssetName::decode_hex("544f4b454e").unwrap(),
+				illiquid_circulation_supply_validator_address: MainchainAddress::from_str(
+					"addr_test1ics",
+				)
+				.unwrap(),
+			},
+			BridgeDataCheckpoint::Block(McBlockNumber(40)),
+			10,
+			McBlockHash::decode_hex(BLOCK_HASH).unwrap(),
+		)
+		.await
+		.unwrap();
+
+	assert_eq!(
+		transfers,
+		vec![
+			BridgeTransferV1::ReserveTransfer { token_amount: 70 },
+			BridgeTransferV1::InvalidTransfer {
+				token_amount: 5,
+				utxo_id: UtxoId {
+					tx_hash: McTxHash::decode_hex(TX_3).unwrap(),
+					index: UtxoIndex(0)
+				},
+			},
+		]
+	);
+	assert_eq!(checkpoint, BridgeDataCheckpoint::Block(McBlockNumber(50)));
+}
+
+#[tokio::test]
+async fn candidates_fail_when_ada_based_registrations_need_stake_delegation() {
+	let ada_based = registration_datum(StakeOwnership::AdaBased(AdaBasedStaking {
+		pub_key: StakePoolPublicKey([1; 32]),
+		signature: MainchainSignature([5; 64]),
+	}));
+	let (addr, _) = http_server::for_responses(vec![
+		checkpoints(),
+		(
+			"/matches/addr_test1cand",
+			200,
+			json!([
+				kupo_match(TX_1, 100, json!({}), Some("d1"), None),
+				kupo_match(TX_2, 200, json!({}), Some("d1"), Some((250, TX_3))),
+			]),
+		),
+		("/datums/d1", 200, datum_response(ada_based)),
+	])
+	.unwrap();
+	let (client, blocks) = blocks_for(addr);
+	let candidates = AuthoritySelectionDataSourceImpl::new(client, blocks);
+
+	let result = candidates
+		.get_candidates(McEpochNumber(5), MainchainAddress::from_str("addr_test1cand").unwrap())
+		.await;
+
+	let err = result.unwrap_err().to_string();
+	assert!(err.contains("Stake delegation of 1 candidates"), "unexpected error: {err}");
+}
+
+#[tokio::test]
+async fn candidates_are_empty_when_there_are_no_ada_based_registrations() {
+	let token_based = registration_datum(StakeOwnership::TokenBased);
+	let (addr, _) = http_server::for_responses(vec![
+		checkpoints(),
+		(
+			"/matches/addr_test1cand",
+			200,
+			json!([
+				kupo_match(TX_1, 100, json!({}), Some("d1"), None),
+				kupo_match(TX_2, 200, json!({}), Some("d2"), None),
+			]),
+		),
+		("/datums/d1", 200, datum_response(token_based)),
+		("/datums/d2", 200, datum_response(PlutusData::new_bytes(vec![1, 2, 3]))),
+	])
+	.unwrap();
+	let (client, blocks) = blocks_for(addr);
+	let candidates = AuthoritySelectionDataSourceImpl::new(client, blocks);
+
+	let result = candidates
+		.get_candidates(McEpochNumber(5), MainchainAddress::from_str("addr_test1cand").unwrap())
+		.await
+		.unwrap();
+
+	assert_eq!(result, vec![]);
+}
+
+#[tokio::test]
+async fn candidates_fail_when_kupo_is_not_available() {
+	let (addr, _) = http_server::for_responses(vec![checkpoints()]).unwrap();
+	let (client, blocks) = blocks_for(addr);
+	let candidates = AuthoritySelectionDataSourceImpl::new(client, blocks);
+
+	let result = candidates
+		.get_candidates(McEpochNumber(5), MainchainAddress::from_str("addr_test1cand").unwrap())
+		.await;
+
+	assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn epoch_nonce_is_not_available() {
+	let (addr, _) = http_server::for_responses(vec![]).unwrap();
+	let (client, blocks) = blocks_for(addr);
+	let candidates = AuthoritySelectionDataSourceImpl::new(client, blocks);
+
+	let err = candidates.get_epoch_nonce(McEpochNumber(5)).await.unwrap_err().to_string();
+
+	assert!(err.contains("Epoch nonce of epoch 3"), "unexpected error: {err}");
+}
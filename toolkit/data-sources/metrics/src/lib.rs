@@ -1,7 +1,9 @@
 //! Substrate Prometheus metrics client for Partner Chain data sources
 use log::warn;
+use std::time::{SystemTime, UNIX_EPOCH};
 use substrate_prometheus_endpoint::{
-	CounterVec, HistogramOpts, HistogramVec, Opts, PrometheusError, Registry, U64, register,
+	CounterVec, Gauge, GaugeVec, HistogramOpts, HistogramVec, Opts, PrometheusError, Registry, U64,
+	register,
 };
 
 pub type MetricsRegistry = Registry;
@@ -11,6 +13,14 @@ pub type MetricsRegistry = Registry;
 pub struct McFollowerMetrics {
 	time_elapsed: HistogramVec,
 	call_count: CounterVec<U64>,
+	failover_count: CounterVec<U64>,
+	error_count: CounterVec<U64>,
+	cache_hit_count: CounterVec<U64>,
+	cache_miss_count: CounterVec<U64>,
+	cache_eviction_count: CounterVec<U64>,
+	mc_tip_age_seconds: Gauge<U64>,
+	mc_stable_block_lag_seconds: Gauge<U64>,
+	db_sync_index_present: GaugeVec<U64>,
 }
 
 impl McFollowerMetrics {
@@ -20,6 +30,47 @@ impl McFollowerMetrics {
 	pub fn call_count(&self) -> &CounterVec<U64> {
 		&self.call_count
 	}
+	pub fn failover_count(&self) -> &CounterVec<U64> {
+		&self.failover_count
+	}
+	pub fn error_count(&self) -> &CounterVec<U64> {
+		&self.error_count
+	}
+	pub fn cache_hit_count(&self) -> &CounterVec<U64> {
+		&self.cache_hit_count
+	}
+	pub fn cache_miss_count(&self) -> &CounterVec<U64> {
+		&self.cache_miss_count
+	}
+	pub fn cache_eviction_count(&self) -> &CounterVec<U64> {
+		&self.cache_eviction_count
+	}
+	pub fn mc_tip_age_seconds(&self) -> &Gauge<U64> {
+		&self.mc_tip_age_seconds
+	}
+	pub fn mc_stable_block_lag_seconds(&self) -> &Gauge<U64> {
+		&self.mc_stable_block_lag_seconds
+	}
+	pub fn db_sync_index_present(&self) -> &GaugeVec<U64> {
+		&self.db_sync_index_present
+	}
+	/// Counts a request served from the cache named `cache_name`
+	pub fn cache_hit(&self, cache_name: &str) {
+		self.cache_hit_count.with_label_values(&[cache_name]).inc();
+	}
+	/// Counts a request that could not be served from the cache named `cache_name`
+	pub fn cache_miss(&self, cache_name: &str) {
+		self.cache_miss_count.with_label_values(&[cache_name]).inc();
+	}
+	/// Counts `count` entries evicted from the cache named `cache_name`
+	pub fn cache_evicted(&self, cache_name: &str, count: u64) {
+		self.cache_eviction_count.with_label_values(&[cache_name]).inc_by(count);
+	}
+	/// Sets the age of the latest Cardano block, given its timestamp in seconds
+	pub fn observe_mc_tip(&self, tip_timestamp_secs: u64) {
+		let now_secs = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |now| now.as_secs());
+		self.mc_tip_age_seconds.set(now_secs.saturating_sub(tip_timestamp_secs));
+	}
 	pub fn register(registry: &Registry) -> Result<Self, PrometheusError> {
 		Ok(Self {
 			time_elapsed: register(
@@ -42,6 +93,80 @@ impl McFollowerMetrics {
 				)?,
 				registry,
 			)?,
+			failover_count: register(
+				CounterVec::new(
+					Opts::new(
+						"partner_chains_data_source_failover_count",
+						"Total number of switches between primary and secondary data sources",
+					),
+					&["method_name", "backend"],
+				)?,
+				registry,
+			)?,
+			error_count: register(
+				CounterVec::new(
+					Opts::new(
+						"partner_chains_data_source_method_error_count",
+						"Total number of failed data source method calls",
+					),
+					&["method_name", "error_kind"],
+				)?,
+				registry,
+			)?,
+			cache_hit_count: register(
+				CounterVec::new(
+					Opts::new(
+						"partner_chains_data_source_cache_hit_count",
+						"Total number of data source requests served from cache",
+					),
+					&["cache_name"],
+				)?,
+				registry,
+			)?,
+			cache_miss_count: register(
+				CounterVec::new(
+					Opts::new(
+						"partner_chains_data_source_cache_miss_count",
+						"Total number of data source requests that could not be served from cache",
+					),
+					&["cache_name"],
+				)?,
+				registry,
+			)?,
+			cache_eviction_count: register(
+				CounterVec::new(
+					Opts::new(
+						"partner_chains_data_source_cache_eviction_count",
+						"Total number of entries evicted from data source caches",
+					),
+					&["cache_name"],
+				)?,
+				registry,
+			)?,
+			mc_tip_age_seconds: register(
+				Gauge::new(
+					"partner_chains_data_source_mc_tip_age_seconds",
+					"Age of the latest Cardano block known to the data source",
+				)?,
+				registry,
+			)?,
+			mc_stable_block_lag_seconds: register(
+				Gauge::new(
+					"partner_chains_data_source_mc_stable_block_lag_seconds",
+					"Time between the reference timestamp and the latest stable Cardano block",
+				)?,
+				registry,
+			)?,
+			db_sync_index_present: register(
+				GaugeVec::new(
+					Opts::new(
+						"partner_chains_data_source_db_sync_index_present",
+						"Whether an index required by the data sources is present in db-sync database",
+					),
+					&["index_name"],
+				)?,
+				registry,
+			)?,
 		})
 	}
 }
@@ -62,12 +187,15 @@ pub fn register_metrics_warn_errors(
 pub use async_trait::async_trait;
 
 /// Logs each method invocation and each returned result.
+/// Errors are counted in the error count metric by the kind returned by the `error_kind` function,
+/// which should return the name of the error variant, eg. `ExpectedDataNotFound` or `PoolTimedOut`.
 /// Has to be made at the level of trait, because otherwise #[async_trait] is expanded first.
 /// '&self' matching yields "__self" identifier not found error, so "&$self:tt" is required.
 /// Works only if return type is Result.
 #[macro_export]
 macro_rules! observed_async_trait {
-	(impl $(<$($type_param:tt),+>)? $trait_name:ident $(<$($type_arg:ident),+>)? for $target_type:ty
+	(error_kind = $error_kind:expr;
+	impl $(<$($type_param:tt),+>)? $trait_name:ident $(<$($type_arg:ident),+>)? for $target_type:ty
 		$(where $($where_type:ident : $where_bound:tt ,)+)?
 
 		{
@@ -96,6 +224,10 @@ macro_rules! observed_async_trait {
 					},
 					Err(error) => {
 						log::error!("{} failed with {:?}", method_name, error);
+						if let Some(metrics) = &$self.metrics_opt {
+							let error_kind: &str = ($error_kind)(error);
+							metrics.error_count().with_label_values(&[method_name, error_kind]).inc();
+						}
 					},
 				};
 				result
@@ -107,13 +239,27 @@ macro_rules! observed_async_trait {
 
 pub mod mock {
 	use crate::McFollowerMetrics;
-	use substrate_prometheus_endpoint::{CounterVec, HistogramOpts, HistogramVec, Opts};
+	use substrate_prometheus_endpoint::{
+		CounterVec, Gauge, GaugeVec, HistogramOpts, HistogramVec, Opts,
+	};
 
 	pub fn test_metrics() -> McFollowerMetrics {
 		McFollowerMetrics {
 			time_elapsed: HistogramVec::new(HistogramOpts::new("test", "test"), &["method_name"])
 				.unwrap(),
 			call_count: CounterVec::new(Opts::new("test", "test"), &["method_name"]).unwrap(),
+			failover_count: CounterVec::new(Opts::new("test", "test"), &["method_name", "backend"])
+				.unwrap(),
+			error_count: CounterVec::new(Opts::new("test", "test"), &["method_name", "error_kind"])
+				.unwrap(),
+			cache_hit_count: CounterVec::new(Opts::new("test", "test"), &["cache_name"]).unwrap(),
+			cache_miss_count: CounterVec::new(Opts::new("test", "test"), &["cache_name"]).unwrap(),
+			cache_eviction_count: CounterVec::new(Opts::new("test", "test"), &["cache_name"])
+				.unwrap(),
+			mc_tip_age_seconds: Gauge::new("test", "test").unwrap(),
+			mc_stable_block_lag_seconds: Gauge::new("test", "test").unwrap(),
+			db_sync_index_present: GaugeVec::new(Opts::new("test", "test"), &["index_name"])
+				.unwrap(),
 		}
 	}
 }
@@ -135,7 +281,32 @@ mod tests {
 		async fn test_method_two(&self) -> Result<(), Infallible>;
 	}
 
+	#[derive(Debug)]
+	enum TestError {
+		NotFound(#[allow(dead_code)] String),
+	}
+
+	#[async_trait]
+	trait FailingMetricMacroTestTrait {
+		async fn failing_method(&self) -> Result<(), TestError>;
+	}
+
+	fn test_error_kind(error: &TestError) -> &'static str {
+		match error {
+			TestError::NotFound(_) => "NotFound",
+		}
+	}
+
 	observed_async_trait!(
+	error_kind = test_error_kind;
+	impl FailingMetricMacroTestTrait for MetricsMacroTestStruct {
+		async fn failing_method(&self) -> Result<(), TestError> {
+			Err(TestError::NotFound("block".into()))
+		}
+	});
+
+	observed_async_trait!(
+	error_kind = |_: &Infallible| "other";
 	impl MetricMacroTestTrait for MetricsMacroTestStruct {
 		async fn test_method_one(&self) -> Result<(), Infallible> {
 			tokio::time::sleep(core::time::Duration::from_millis(10)).await;
@@ -176,4 +347,15 @@ mod tests {
 		assert_eq!(counter_method_two.get(), 1);
 		assert_eq!(counter_method_random.get(), 0);
 	}
+
+	#[tokio::test]
+	async fn counts_errors_by_kind() {
+		let metrics = test_metrics();
+		let metrics_struct = MetricsMacroTestStruct { metrics_opt: Some(metrics.clone()) };
+
+		metrics_struct.failing_method().await.unwrap_err();
+
+		let counter = metrics.error_count().with_label_values(&["failing_method", "NotFound"]);
+		assert_eq!(counter.get(), 1);
+	}
 }
@@ -41,6 +41,20 @@
 //! ```
 //!
 //! After that they can be passed as dependencies to other Partner Chains toolkit components.
+//!
+//! # Scenarios
+//!
+//! [ScenarioDataSource] implements all data source traits at once, serving data of a simulated
+//! Cardano timeline described in a scenario file: D-Parameter and permissioned candidates changes,
+//! registrations, bridge transfers, governed map changes and stake distribution. All nodes
+//! using the same scenario file observe the same Cardano state, which makes it suitable for
+//! multi-node local testnets exercising all Partner Chains features.
+//!
+//! # Recording and replay
+//!
+//! This crate also provides [RecordingDataSource], which wraps any real data source and records
+//! all queries and their results to a file, and [ReplayDataSource], which serves the recorded
+//! results. Together they make it possible to reproduce a failing block import offline.
 
 #![deny(missing_docs)]
 
@@ -81,4 +95,15 @@ mod bridge;
 #[cfg(feature = "bridge")]
 pub use bridge::TokenBridgeDataSourceMock;
 
+#[cfg(feature = "scenario")]
+mod scenario;
+#[cfg(feature = "scenario")]
+pub use scenario::{
+	MockBridgeTransfer, MockDelegator, MockPoolDelegation, Scenario, ScenarioChange,
+	ScenarioDataSource, ScenarioEvent,
+};
+
+mod replay;
+pub use replay::{DataSourceRecorder, RecordingDataSource, ReplayDataSource};
+
 type Result<T> = std::result::Result<T, Box<dyn std::error::Error + Send + Sync>>;
@@ -0,0 +1,613 @@
+//! Recording and replaying of data source queries
+//!
+//! [RecordingDataSource] wraps any real data source and appends every query made to it,
+//! together with its result, to a file. [ReplayDataSource] reads such a file and serves
+//! the recorded results, which makes it possible to reproduce a failing block import
+//! offline, without access to the Cardano data source used when the failure happened.
+//!
+//! The file contains one JSON object per line, with the method name, SCALE-encoded request
+//! arguments and SCALE-encoded response or error message.
+//! All data sources of a node should share one [DataSourceRecorder] so that all queries
+//! end up in the same file, which can then be served by a single [ReplayDataSource].
+use crate::Result;
+use parity_scale_codec::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+use sidechain_domain::*;
+use std::collections::{HashMap, VecDeque};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct RecordedCall {
+	method: String,
+	request: String,
+	response: std::result::Result<String, String>,
+}
+
+/// Appends recorded data source calls to a file
+///
+/// Calls are written by a dedicated writer thread, so that recording does not block data source queries
+/// on file I/O. Calls recorded before the recorder is dropped are all written when it is dropped.
+pub struct DataSourceRecorder {
+	sender: Option<Sender<RecordedCall>>,
+	writer: Option<JoinHandle<()>>,
+}
+
+impl DataSourceRecorder {
+	/// Creates a recorder appending to the file at `path`. The file is created if it does not exist.
+	pub fn new(path: impl AsRef<Path>) -> Result<Self> {
+		let mut file = File::options().create(true).append(true).open(path)?;
+		let (sender, receiver) = mpsc::channel::<RecordedCall>();
+		let writer =
+			std::thread::Builder::new().name("data-source-recorder".into()).spawn(move || {
+				for call in receiver {
+					if let Err(err) = write_call(&mut file, &call) {
+						log::warn!("Failed to record data source call {}: {err}", call.method);
+					}
+				}
+			})?;
+		Ok(Self { sender: Some(sender), writer: Some(writer) })
+	}
+
+	/// Creates a recorder using the file path in `DATA_SOURCE_RECORDING_FILE` environment variable.
+	/// Returns `None` if the variable is not set.
+	pub fn new_from_env() -> Result<Option<Self>> {
+		match std::env::var("DATA_SOURCE_RECORDING_FILE") {
+			Ok(path) => {
+				log::info!("Recording data source calls to {path}");
+				Ok(Some(Self::new(path)?))
+			},
+			Err(_) => Ok(None),
+		}
+	}
+
+	fn record<T: Encode>(&self, method: &str, request: impl Encode, response: &Result<T>) {
+		let call = RecordedCall {
+			method: method.to_string(),
+			request: hex::encode(request.encode()),
+			response: match response {
+				Ok(response) => Ok(hex::encode(response.encode())),
+				Err(err) => Err(err.to_string()),
+			},
+		};
+		let sent = self.sender.as_ref().is_some_and(|sender| sender.send(call).is_ok());
+		if !sent {
+			log::warn!(
+				"Failed to record data source call {method}: recording file writer has stopped"
+			);
+		}
+	}
+}
+
+impl Drop for DataSourceRecorder {
+	fn drop(&mut self) {
+		// Closing the channel makes the writer finish after writing all calls recorded so far
+		drop(self.sender.take());
+		if let Some(writer) = self.writer.take()
+			&& writer.join().is_err()
+		{
+			log::warn!("Data source recording file writer has panicked");
+		}
+	}
+}
+
+fn write_call(file: &mut File, call: &RecordedCall) -> Result<()> {
+	let mut line = serde_json::to_string(call)?;
+	line.push('\n');
+	file.write_all(line.as_bytes())?;
+	Ok(())
+}
+
+/// Data source wrapper that records all queries made to the wrapped data source and their results
+pub struct RecordingDataSource<T: ?Sized> {
+	inner: Arc<T>,
+	recorder: Arc<DataSourceRecorder>,
+}
+
+impl<T: ?Sized> RecordingDataSource<T> {
+	/// Wraps `inner` data source, recording its calls with `recorder`
+	pub fn new(inner: Arc<T>, recorder: Arc<DataSourceRecorder>) -> Self {
+		Self { inner, recorder }
+	}
+
+	async fn recorded<R: Encode>(
+		&self,
+		method: &str,
+		request: impl Encode,
+		response: impl Future<Output = Result<R>>,
+	) -> Result<R> {
+		let response = response.await;
+		self.recorder.record(method, request, &response);
+		response
+	}
+}
+
+/// Data source serving responses recorded by [RecordingDataSource]
+///
+/// Responses to the same query are served in the order they were recorded. After all of them
+/// were served, the last one is repeated. Queries that were not recorded result in an error.
+pub struct ReplayDataSource {
+	calls: Mutex<HashMap<(String, String), VecDeque<std::result::Result<String, String>>>>,
+}
+
+impl ReplayDataSource {
+	/// Creates a data source serving calls recorded in the file at `path`
+	pub fn new_from_file(path: impl AsRef<Path>) -> Result<Self> {
+		let reader = BufReader::new(File::open(path)?);
+		let mut calls: HashMap<_, VecDeque<_>> = HashMap::new();
+		for line in reader.lines() {
+			let line = line?;
+			if line.trim().is_empty() {
+				continue;
+			}
+			let call: RecordedCall = serde_json::from_str(&line)?;
+			calls.entry((call.method, call.request)).or_default().push_back(call.response);
+		}
+		Ok(Self { calls: Mutex::new(calls) })
+	}
+
+	/// Creates a data source serving calls recorded in the file at path set in `DATA_SOURCE_REPLAY_FILE` environment variable
+	pub fn new_from_env() -> Result<Self> {
+		let path = std::env::var("DATA_SOURCE_REPLAY_FILE")
+			.map_err(|_| "DATA_SOURCE_REPLAY_FILE is not set")?;
+		log::info!("Replaying data source calls from {path}");
+		Self::new_from_file(path)
+	}
+
+	fn replay<T: Decode>(&self, method: &str, request: impl Encode) -> Result<T> {
+		let request = hex::encode(request.encode());
+		let mut calls = self.calls.lock().map_err(|_| "Replay data lock is poisoned")?;
+		let responses = calls
+			.get_mut(&(method.to_string(), request.clone()))
+			.ok_or_else(|| format!("No recorded response for {method} with request 0x{request}"))?;
+		let response =
+			if responses.len() > 1 { responses.pop_front() } else { responses.front().cloned() }
+				.ok_or_else(|| format!("No recorded response for {method}"))?;
+		match response {
+			Ok(response) => Ok(T::decode(&mut &hex::decode(response)?[..])?),
+			Err(err) => Err(err.into()),
+		}
+	}
+}
+
+#[cfg(feature = "mc-hash")]
+mod mc_hash {
+	use super::*;
+	use sidechain_mc_hash::McHashDataSource;
+	use sp_timestamp::Timestamp;
+
+	const GET_LATEST_STABLE_BLOCK_FOR: &str = "McHashDataSource::get_latest_stable_block_for";
+	const GET_STABLE_BLOCK_FOR: &str = "McHashDataSource::get_stable_block_for";
+	const GET_BLOCK_BY_HASH: &str = "McHashDataSource::get_block_by_hash";
+	const GET_TIP: &str = "McHashDataSource::get_tip";
+
+	#[async_trait::async_trait]
+	impl<T: McHashDataSource + Send + Sync + ?Sized> McHashDataSource for RecordingDataSource<T> {
+		async fn get_latest_stable_block_for(
+			&self,
+			reference_timestamp: Timestamp,
+		) -> Result<Option<MainchainBlock>> {
+			self.recorded(
+				GET_LATEST_STABLE_BLOCK_FOR,
+				reference_timestamp,
+				self.inner.get_latest_stable_block_for(reference_timestamp),
+			)
+			.await
+		}
+
+		async fn get_stable_block_for(
+			&self,
+			hash: McBlockHash,
+			reference_timestamp: Timestamp,
+		) -> Result<Option<MainchainBlock>> {
+			self.recorded(
+				GET_STABLE_BLOCK_FOR,
+				(&hash, reference_timestamp),
+				self.inner.get_stable_block_for(hash.clone(), reference_timestamp),
+			)
+			.await
+		}
+
+		async fn get_block_by_hash(&self, hash: McBlockHash) -> Result<Option<MainchainBlock>> {
+			self.recorded(GET_BLOCK_BY_HASH, &hash, self.inner.get_block_by_hash(hash.clone()))
+				.await
+		}
+
+		async fn get_tip(&self) -> Result<Option<MainchainBlock>> {
+			self.recorded(GET_TIP, (), self.inner.get_tip()).await
+		}
+	}
+
+	#[async_trait::async_trait]
+	impl McHashDataSource for ReplayDataSource {
+		async fn get_latest_stable_block_for(
+			&self,
+			reference_timestamp: Timestamp,
+		) -> Result<Option<MainchainBlock>> {
+			self.replay(GET_LATEST_STABLE_BLOCK_FOR, reference_timestamp)
+		}
+
+		async fn get_stable_block_for(
+			&self,
+			hash: McBlockHash,
+			reference_timestamp: Timestamp,
+		) -> Result<Option<MainchainBlock>> {
+			self.replay(GET_STABLE_BLOCK_FOR, (hash, reference_timestamp))
+		}
+
+		async fn get_block_by_hash(&self, hash: McBlockHash) -> Result<Option<MainchainBlock>> {
+			self.replay(GET_BLOCK_BY_HASH, hash)
+		}
+
+		async fn get_tip(&self) -> Result<Option<MainchainBlock>> {
+			self.replay(GET_TIP, ())
+		}
+	}
+}
+
+#[cfg(feature = "candidate-source")]
+mod candidate {
+	use super::*;
+	use authority_selection_inherents::{AriadneParameters, AuthoritySelectionDataSource};
+
+	const GET_ARIADNE_PARAMETERS: &str = "AuthoritySelectionDataSource::get_ariadne_parameters";
+	const GET_CANDIDATES: &str = "AuthoritySelectionDataSource::get_candidates";
+	const GET_TOKEN_STAKED_CANDIDATES: &str =
+		"AuthoritySelectionDataSource::get_token_staked_candidates";
+	const GET_EPOCH_NONCE: &str = "AuthoritySelectionDataSource::get_epoch_nonce";
+	const DATA_EPOCH: &str = "AuthoritySelectionDataSource::data_epoch";
+
+	#[async_trait::async_trait]
+	impl<T: AuthoritySelectionDataSource + Send + Sync + ?Sized> AuthoritySelectionDataSource
+		for RecordingDataSource<T>
+	{
+		async fn get_ariadne_parameters(
+			&self,
+			epoch_number: McEpochNumber,
+			d_parameter_policy: PolicyId,
+			permissioned_candidates_policy: PolicyId,
+		) -> Result<AriadneParameters> {
+			self.recorded(
+				GET_ARIADNE_PARAMETERS,
+				(epoch_number, &d_parameter_policy, &permissioned_candidates_policy),
+				self.inner.get_ariadne_parameters(
+					epoch_number,
+					d_parameter_policy.clone(),
+					permissioned_candidates_policy.clone(),
+				),
+			)
+			.await
+		}
+
+		async fn get_candidates(
+			&self,
+			epoch_number: McEpochNumber,
+			committee_candidate_address: MainchainAddress,
+		) -> Result<Vec<CandidateRegistrations>> {
+			self.recorded(
+				GET_CANDIDATES,
+				(epoch_number, &committee_candidate_address),
+				self.inner.get_candidates(epoch_number, committee_candidate_address.clone()),
+			)
+			.await
+		}
+
+		async fn get_token_staked_candidates(
+			&self,
+			epoch_number: McEpochNumber,
+			committee_candidate_address: MainchainAddress,
+			token_staking: TokenStakingConfig,
+		) -> Result<Vec<TokenStakedCandidateRegistrations>> {
+			self.recorded(
+				GET_TOKEN_STAKED_CANDIDATES,
+				(epoch_number, &committee_candidate_address, &token_staking),
+				self.inner.get_token_staked_candidates(
+					epoch_number,
+					committee_candidate_address.clone(),
+					token_staking.clone(),
+				),
+			)
+			.await
+		}
+
+		async fn get_epoch_nonce(&self, epoch_number: McEpochNumber) -> Result<Option<EpochNonce>> {
+			self.recorded(GET_EPOCH_NONCE, epoch_number, self.inner.get_epoch_nonce(epoch_number))
+				.await
+		}
+
+		async fn data_epoch(&self, for_epoch: McEpochNumber) -> Result<McEpochNumber> {
+			self.recorded(DATA_EPOCH, for_epoch, self.inner.data_epoch(for_epoch)).await
+		}
+	}
+
+	#[async_trait::async_trait]
+	impl AuthoritySelectionDataSource for ReplayDataSource {
+		async fn get_ariadne_parameters(
+			&self,
+			epoch_number: McEpochNumber,
+			d_parameter_policy: PolicyId,
+			permissioned_candidates_policy: PolicyId,
+		) -> Result<AriadneParameters> {
+			self.replay(
+				GET_ARIADNE_PARAMETERS,
+				(epoch_number, d_parameter_policy, permissioned_candidates_policy),
+			)
+		}
+
+		async fn get_candidates(
+			&self,
+			epoch_number: McEpochNumber,
+			committee_candidate_address: MainchainAddress,
+		) -> Result<Vec<CandidateRegistrations>> {
+			self.replay(GET_CANDIDATES, (epoch_number, committee_candidate_address))
+		}
+
+		async fn get_token_staked_candidates(
+			&self,
+			epoch_number: McEpochNumber,
+			committee_candidate_address: MainchainAddress,
+			token_staking: TokenStakingConfig,
+		) -> Result<Vec<TokenStakedCandidateRegistrations>> {
+			self.replay(
+				GET_TOKEN_STAKED_CANDIDATES,
+				(epoch_number, committee_candidate_address, token_staking),
+			)
+		}
+
+		async fn get_epoch_nonce(&self, epoch_number: McEpochNumber) -> Result<Option<EpochNonce>> {
+			self.replay(GET_EPOCH_NONCE, epoch_number)
+		}
+
+		async fn data_epoch(&self, for_epoch: McEpochNumber) -> Result<McEpochNumber> {
+			self.replay(DATA_EPOCH, for_epoch)
+		}
+	}
+}
+
+#[cfg(feature = "governed-map")]
+mod governed_map {
+	use super::*;
+	use sidechain_domain::byte_string::ByteString;
+	use sp_governed_map::{GovernedMapDataSource, MainChainScriptsV1};
+
+	const GET_MAPPING_CHANGES: &str = "GovernedMapDataSource::get_mapping_changes";
+	const GET_STATE_AT_BLOCK: &str = "GovernedMapDataSource::get_state_at_block";
+
+	#[async_trait::async_trait]
+	impl<T: GovernedMapDataSource + Send + Sync + ?Sized> GovernedMapDataSource
+		for RecordingDataSource<T>
+	{
+		async fn get_mapping_changes(
+			&self,
+			since_mc_block: Option<McBlockHash>,
+			up_to_mc_block: McBlockHash,
+			scripts: MainChainScriptsV1,
+		) -> Result<Vec<(String, Option<ByteString>)>> {
+			self.recorded(
+				GET_MAPPING_CHANGES,
+				(&since_mc_block, &up_to_mc_block, &scripts),
+				self.inner.get_mapping_changes(
+					since_mc_block.clone(),
+					up_to_mc_block.clone(),
+					scripts.clone(),
+				),
+			)
+			.await
+		}
+
+		async fn get_state_at_block(
+			&self,
+			mc_block: McBlockHash,
+			main_chain_scripts: MainChainScriptsV1,
+		) -> Result<BTreeMap<String, ByteString>> {
+			self.recorded(
+				GET_STATE_AT_BLOCK,
+				(&mc_block, &main_chain_scripts),
+				self.inner.get_state_at_block(mc_block.clone(), main_chain_scripts.clone()),
+			)
+			.await
+		}
+	}
+
+	#[async_trait::async_trait]
+	impl GovernedMapDataSource for ReplayDataSource {
+		async fn get_mapping_changes(
+			&self,
+			since_mc_block: Option<McBlockHash>,
+			up_to_mc_block: McBlockHash,
+			scripts: MainChainScriptsV1,
+		) -> Result<Vec<(String, Option<ByteString>)>> {
+			self.replay(GET_MAPPING_CHANGES, (since_mc_block, up_to_mc_block, scripts))
+		}
+
+		async fn get_state_at_block(
+			&self,
+			mc_block: McBlockHash,
+			main_chain_scripts: MainChainScriptsV1,
+		) -> Result<BTreeMap<String, ByteString>> {
+			self.replay(GET_STATE_AT_BLOCK, (mc_block, main_chain_scripts))
+		}
+	}
+}
+
+#[cfg(feature = "sidechain-rpc")]
+mod sidechain_rpc {
+	use super::*;
+	use pallet_sidechain_rpc::SidechainRpcDataSource;
+
+	const GET_LATEST_BLOCK_INFO: &str = "SidechainRpcDataSource::get_latest_block_info";
+
+	#[async_trait::async_trait]
+	impl<T: SidechainRpcDataSource + Send + Sync + ?Sized> SidechainRpcDataSource
+		for RecordingDataSource<T>
+	{
+		async fn get_latest_block_info(&self) -> Result<MainchainBlock> {
+			self.recorded(GET_LATEST_BLOCK_INFO, (), self.inner.get_latest_block_info())
+				.await
+		}
+	}
+
+	#[async_trait::async_trait]
+	impl SidechainRpcDataSource for ReplayDataSource {
+		async fn get_latest_block_info(&self) -> Result<MainchainBlock> {
+			self.replay(GET_LATEST_BLOCK_INFO, ())
+		}
+	}
+}
+
+#[cfg(feature = "block-participation")]
+mod block_participation {
+	use super::*;
+	use sp_block_participation::inherent_data::BlockParticipationDataSource;
+
+	const GET_STAKE_POOL_DELEGATION_DISTRIBUTION_FOR_POOLS: &str =
+		"BlockParticipationDataSource::get_stake_pool_delegation_distribution_for_pools";
+
+	#[async_trait::async_trait]
+	impl<T: BlockParticipationDataSource + Send + Sync + ?Sized> BlockParticipationDataSource
+		for RecordingDataSource<T>
+	{
+		async fn get_stake_pool_delegation_distribution_for_pools(
+			&self,
+			epoch: McEpochNumber,
+			pool_hashes: &[MainchainKeyHash],
+		) -> Result<StakeDistribution> {
+			self.recorded(
+				GET_STAKE_POOL_DELEGATION_DISTRIBUTION_FOR_POOLS,
+				(epoch, pool_hashes),
+				self.inner.get_stake_pool_delegation_distribution_for_pools(epoch, pool_hashes),
+			)
+			.await
+		}
+	}
+
+	#[async_trait::async_trait]
+	impl BlockParticipationDataSource for ReplayDataSource {
+		async fn get_stake_pool_delegation_distribution_for_pools(
+			&self,
+			epoch: McEpochNumber,
+			pool_hashes: &[MainchainKeyHash],
+		) -> Result<StakeDistribution> {
+			self.replay(GET_STAKE_POOL_DELEGATION_DISTRIBUTION_FOR_POOLS, (epoch, pool_hashes))
+		}
+	}
+}
+
+#[cfg(feature = "bridge")]
+mod bridge {
+	use super::*;
+	use sp_partner_chains_bridge::{
+		BridgeDataCheckpoint, BridgeTransferV1, MainChainScripts, TokenBridgeDataSource,
+	};
+
+	const GET_TRANSFERS: &str = "TokenBridgeDataSource::get_transfers";
+
+	#[async_trait::async_trait]
+	impl<RecipientAddress, T> TokenBridgeDataSource<RecipientAddress> for RecordingDataSource<T>
+	where
+		RecipientAddress: Encode + Send + Sync,
+		T: TokenBridgeDataSource<RecipientAddress> + ?Sized,
+	{
+		async fn get_transfers(
+			&self,
+			main_chain_scripts: MainChainScripts,
+			data_checkpoint: BridgeDataCheckpoint,
+			max_transfers: u32,
+			current_mc_block: McBlockHash,
+		) -> Result<(Vec<BridgeTransferV1<RecipientAddress>>, BridgeDataCheckpoint)> {
+			self.recorded(
+				GET_TRANSFERS,
+				(&main_chain_scripts, &data_checkpoint, max_transfers, &current_mc_block),
+				self.inner.get_transfers(
+					main_chain_scripts.clone(),
+					data_checkpoint.clone(),
+					max_transfers,
+					current_mc_block.clone(),
+				),
+			)
+			.await
+		}
+	}
+
+	#[async_trait::async_trait]
+	impl<RecipientAddress: Decode + Send + Sync> TokenBridgeDataSource<RecipientAddress>
+		for ReplayDataSource
+	{
+		async fn get_transfers(
+			&self,
+			main_chain_scripts: MainChainScripts,
+			data_checkpoint: BridgeDataCheckpoint,
+			max_transfers: u32,
+			current_mc_block: McBlockHash,
+		) -> Result<(Vec<BridgeTransferV1<RecipientAddress>>, BridgeDataCheckpoint)> {
+			self.replay(
+				GET_TRANSFERS,
+				(main_chain_scripts, data_checkpoint, max_transfers, current_mc_block),
+			)
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use tempfile::NamedTempFile;
+
+	fn block(number: u32) -> MainchainBlock {
+		MainchainBlock {
+			number: McBlockNumber(number),
+			hash: McBlockHash([number as u8; 32]),
+			epoch: McEpochNumber(number / 10),
+			slot: McSlotNumber(number.into()),
+			timestamp: number.into(),
+		}
+	}
+
+	#[test]
+	fn replays_recorded_responses_in_order_and_repeats_the_last_one() {
+		let file = NamedTempFile::new().unwrap();
+		let recorder = DataSourceRecorder::new(file.path()).unwrap();
+		recorder.record("method", 1u32, &Ok(block(1)));
+		recorder.record("method", 1u32, &Ok(block(2)));
+		recorder.record("method", 2u32, &Ok(block(3)));
+		drop(recorder);
+
+		let replay = ReplayDataSource::new_from_file(file.path()).unwrap();
+
+		assert_eq!(replay.replay::<MainchainBlock>("method", 1u32).unwrap(), block(1));
+		assert_eq!(replay.replay::<MainchainBlock>("method", 1u32).unwrap(), block(2));
+		assert_eq!(replay.replay::<MainchainBlock>("method", 1u32).unwrap(), block(2));
+		assert_eq!(replay.replay::<MainchainBlock>("method", 2u32).unwrap(), block(3));
+	}
+
+	#[test]
+	fn replays_recorded_errors() {
+		let file = NamedTempFile::new().unwrap();
+		let recorder = DataSourceRecorder::new(file.path()).unwrap();
+		recorder.record::<MainchainBlock>("method", (), &Err("db-sync is down".into()));
+		drop(recorder);
+
+		let replay = ReplayDataSource::new_from_file(file.path()).unwrap();
+
+		let err = replay.replay::<MainchainBlock>("method", ()).unwrap_err();
+		assert_eq!(err.to_string(), "db-sync is down");
+	}
+
+	#[test]
+	fn fails_for_calls_that_were_not_recorded() {
+		let file = NamedTempFile::new().unwrap();
+		let recorder = DataSourceRecorder::new(file.path()).unwrap();
+		recorder.record("method", 1u32, &Ok(block(1)));
+		drop(recorder);
+
+		let replay = ReplayDataSource::new_from_file(file.path()).unwrap();
+
+		assert!(replay.replay::<MainchainBlock>("method", 2u32).is_err());
+		assert!(replay.replay::<MainchainBlock>("other_method", 1u32).is_err());
+	}
+}
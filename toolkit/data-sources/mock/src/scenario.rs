@@ -0,0 +1,563 @@
+use crate::Result;
+use crate::candidate::{MockDParam, MockPermissionedCandidate, MockRegistration};
+use authority_selection_inherents::{AriadneParameters, AuthoritySelectionDataSource};
+use log::{debug, info};
+use pallet_sidechain_rpc::SidechainRpcDataSource;
+use parity_scale_codec::Decode;
+use serde::Deserialize;
+use sidechain_domain::byte_string::ByteString;
+use sidechain_domain::*;
+use sidechain_mc_hash::McHashDataSource;
+use sp_block_participation::inherent_data::BlockParticipationDataSource;
+use sp_governed_map::{GovernedMapDataSource, MainChainScriptsV1};
+use sp_partner_chains_bridge::{
+	BridgeDataCheckpoint, BridgeTransferV1, MainChainScripts, TokenBridgeDataSource,
+};
+use sp_timestamp::Timestamp;
+use std::collections::BTreeMap;
+
+/// Simulated Cardano timeline, read from a JSON file
+///
+/// Cardano blocks of the scenario are produced every `block_duration_millis`, starting with
+/// block 0 at `start_timestamp_millis`. Every `epoch_length_blocks` blocks form a Cardano epoch,
+/// starting with epoch 0. These values should match the main chain epoch configuration of the node.
+///
+/// The state of the simulated Cardano at a given block is the result of applying all `events`
+/// up to and including that block. All nodes using the same scenario file see the same state.
+#[derive(Deserialize, Clone, Debug)]
+pub struct Scenario {
+	/// Timestamp of the first block of the scenario
+	pub start_timestamp_millis: u64,
+	/// Time between consecutive blocks
+	#[serde(default = "default_block_duration_millis")]
+	pub block_duration_millis: u64,
+	/// Number of blocks in an epoch
+	pub epoch_length_blocks: u32,
+	/// Changes of the Cardano state, ordered by block number
+	pub events: Vec<ScenarioEvent>,
+}
+
+fn default_block_duration_millis() -> u64 {
+	20000
+}
+
+/// Change of the simulated Cardano state happening in a block
+#[derive(Deserialize, Clone, Debug)]
+pub struct ScenarioEvent {
+	/// Number of the block in which the change happens
+	pub block: u32,
+	/// The change
+	#[serde(flatten)]
+	pub change: ScenarioChange,
+}
+
+/// Change of the simulated Cardano state
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ScenarioChange {
+	/// Sets the Ariadne D-Parameter
+	DParameter(MockDParam),
+	/// Replaces the permissioned candidates list
+	PermissionedCandidates(Vec<MockPermissionedCandidate>),
+	/// Adds a candidate registration, replacing any previous registration of the same stake pool.
+	/// Status of the registration is ignored.
+	Register(MockRegistration),
+	/// Removes registration of the stake pool
+	Deregister {
+		/// Stake pool public key of the deregistered candidate
+		mainchain_pub_key: ByteString,
+	},
+	/// Sends tokens to the illiquid supply validator of the token bridge
+	BridgeTransfer(MockBridgeTransfer),
+	/// Inserts or updates a governed map entry
+	GovernedMapInsert {
+		/// Entry key
+		key: String,
+		/// Entry value
+		value: ByteString,
+	},
+	/// Removes a governed map entry
+	GovernedMapRemove {
+		/// Entry key
+		key: String,
+	},
+	/// Replaces the stake distribution
+	StakeDistribution(Vec<MockPoolDelegation>),
+}
+
+/// Token bridge transfer of the scenario
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum MockBridgeTransfer {
+	/// User transfer. It is served as an invalid transfer if `recipient` can not be decoded.
+	User {
+		/// Amount of tokens
+		amount: u64,
+		/// SCALE-encoded recipient address
+		recipient: ByteString,
+	},
+	/// Reserve transfer
+	Reserve {
+		/// Amount of tokens
+		amount: u64,
+	},
+	/// Transfer with invalid datum
+	Invalid {
+		/// Amount of tokens
+		amount: u64,
+	},
+}
+
+/// Stake delegated to a stake pool
+#[derive(Deserialize, Clone, Debug)]
+pub struct MockPoolDelegation {
+	/// Hash of the stake pool public key
+	pub pool: ByteString,
+	/// Delegators of the stake pool
+	pub delegators: Vec<MockDelegator>,
+}
+
+/// Stake of a single delegator
+#[derive(Deserialize, Clone, Debug)]
+pub struct MockDelegator {
+	/// Stake key hash of the delegator
+	pub stake_key_hash: ByteString,
+	/// Delegated amount
+	pub amount: u64,
+}
+
+/// Simulated Cardano state at a block
+#[derive(Default, Clone)]
+struct ScenarioState {
+	d_parameter: Option<MockDParam>,
+	permissioned: Option<Vec<MockPermissionedCandidate>>,
+	registrations: Vec<MockRegistration>,
+	transfers: Vec<(McBlockNumber, UtxoId, MockBridgeTransfer)>,
+	governed_map: BTreeMap<String, ByteString>,
+	stake_distribution: Vec<MockPoolDelegation>,
+}
+
+impl Scenario {
+	/// Reads the scenario from file
+	pub fn read(path: &str) -> Result<Self> {
+		info!("Reading mock Cardano scenario from: {path}");
+		let file = std::fs::File::open(path)?;
+		let mut scenario: Self = serde_json::from_reader(file)?;
+		if scenario.epoch_length_blocks == 0 || scenario.block_duration_millis == 0 {
+			return Err("Scenario epoch length and block duration must be positive".into());
+		}
+		scenario.events.sort_by_key(|event| event.block);
+		info!("Loaded {} scenario events", scenario.events.len());
+		Ok(scenario)
+	}
+
+	fn block(&self, number: u32) -> MainchainBlock {
+		let mut hash = [0u8; 32];
+		hash[..4].copy_from_slice(&number.to_be_bytes());
+		let timestamp_millis =
+			self.start_timestamp_millis + u64::from(number) * self.block_duration_millis;
+		MainchainBlock {
+			number: McBlockNumber(number),
+			hash: McBlockHash(hash),
+			epoch: McEpochNumber(number / self.epoch_length_blocks),
+			slot: McSlotNumber(number.into()),
+			timestamp: timestamp_millis / 1000,
+		}
+	}
+
+	fn block_at(&self, timestamp: Timestamp) -> Option<MainchainBlock> {
+		let millis = timestamp.as_millis().checked_sub(self.start_timestamp_millis)?;
+		let number = u32::try_from(millis / self.block_duration_millis).ok()?;
+		Some(self.block(number))
+	}
+
+	fn block_by_hash(&self, hash: &McBlockHash) -> Option<MainchainBlock> {
+		let number = u32::from_be_bytes(hash.0[..4].try_into().ok()?);
+		let block = self.block(number);
+		(block.hash == *hash).then_some(block)
+	}
+
+	fn last_block_of_epoch(&self, epoch: McEpochNumber) -> u32 {
+		(epoch.0 + 1) * self.epoch_length_blocks - 1
+	}
+
+	fn state_at(&self, block: u32) -> ScenarioState {
+		let mut state = ScenarioState::default();
+		let events = self.events.iter().take_while(|event| event.block <= block);
+		for (event_index, event) in events.enumerate() {
+			match &event.change {
+				ScenarioChange::DParameter(d_parameter) => {
+					state.d_parameter = Some(d_parameter.clone())
+				},
+				ScenarioChange::PermissionedCandidates(candidates) => {
+					state.permissioned = Some(candidates.clone())
+				},
+				ScenarioChange::Register(registration) => {
+					let key = &registration.mainchain_pub_key;
+					state.registrations.retain(|r| r.mainchain_pub_key != *key);
+					state.registrations.push(registration.clone());
+				},
+				ScenarioChange::Deregister { mainchain_pub_key } => {
+					state.registrations.retain(|r| r.mainchain_pub_key != *mainchain_pub_key)
+				},
+				ScenarioChange::BridgeTransfer(transfer) => {
+					let mut tx_hash = [0u8; 32];
+					tx_hash[..4].copy_from_slice(&event.block.to_be_bytes());
+					tx_hash[4..12].copy_from_slice(&(event_index as u64).to_be_bytes());
+					let utxo_id = UtxoId { tx_hash: McTxHash(tx_hash), index: UtxoIndex(0) };
+					state.transfers.push((McBlockNumber(event.block), utxo_id, transfer.clone()));
+				},
+				ScenarioChange::GovernedMapInsert { key, value } => {
+					state.governed_map.insert(key.clone(), value.clone());
+				},
+				ScenarioChange::GovernedMapRemove { key } => {
+					state.governed_map.remove(key);
+				},
+				ScenarioChange::StakeDistribution(distribution) => {
+					state.stake_distribution = distribution.clone()
+				},
+			}
+		}
+		state
+	}
+
+	fn state_at_epoch_end(&self, epoch: McEpochNumber) -> ScenarioState {
+		self.state_at(self.last_block_of_epoch(epoch))
+	}
+
+	fn state_at_block(&self, hash: &McBlockHash) -> Result<ScenarioState> {
+		let block = (self.block_by_hash(hash))
+			.ok_or_else(|| format!("Block {hash} is not a block of the scenario"))?;
+		Ok(self.state_at(block.number.0))
+	}
+}
+
+/// Mock data source serving data of a simulated Cardano timeline described by a [Scenario]
+///
+/// Unlike the other mock data sources, it serves consistent data for all data source traits,
+/// which makes it possible to exercise all Partner Chains features in local testnets.
+///
+/// An example scenario file can look like this:
+/// ```json
+#[doc = include_str!("../examples/scenario.json")]
+/// ```
+///
+/// The data source can be created using `ScenarioDataSource::new_from_env`, which reads
+/// the scenario from the file pointed to by the `MOCK_SCENARIO_FILE` environment variable.
+pub struct ScenarioDataSource {
+	scenario: Scenario,
+}
+
+impl ScenarioDataSource {
+	/// Creates new data source serving `scenario`
+	pub fn new(scenario: Scenario) -> Self {
+		Self { scenario }
+	}
+
+	/// Creates new data source, reading the scenario from the file pointed to by the
+	/// `MOCK_SCENARIO_FILE` environment variable
+	pub fn new_from_env() -> Result<Self> {
+		let path = std::env::var("MOCK_SCENARIO_FILE")
+			.map_err(|_| "MOCK_SCENARIO_FILE is not set".to_string())?;
+		Ok(Self::new(Scenario::read(&path)?))
+	}
+}
+
+#[async_trait::async_trait]
+impl McHashDataSource for ScenarioDataSource {
+	async fn get_latest_stable_block_for(
+		&self,
+		reference_timestamp: Timestamp,
+	) -> Result<Option<MainchainBlock>> {
+		Ok(self.scenario.block_at(reference_timestamp))
+	}
+
+	async fn get_stable_block_for(
+		&self,
+		hash: McBlockHash,
+		reference_timestamp: Timestamp,
+	) -> Result<Option<MainchainBlock>> {
+		let latest = self.scenario.block_at(reference_timestamp);
+		Ok((self.scenario.block_by_hash(&hash))
+			.filter(|block| latest.is_some_and(|latest| block.number <= latest.number)))
+	}
+
+	async fn get_block_by_hash(&self, hash: McBlockHash) -> Result<Option<MainchainBlock>> {
+		Ok(self.scenario.block_by_hash(&hash))
+	}
+
+	async fn get_tip(&self) -> Result<Option<MainchainBlock>> {
+		let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)?;
+		Ok(self.scenario.block_at(Timestamp::new(now.as_millis() as u64)))
+	}
+}
+
+#[async_trait::async_trait]
+impl SidechainRpcDataSource for ScenarioDataSource {
+	async fn get_latest_block_info(&self) -> Result<MainchainBlock> {
+		let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)?;
+		(self.scenario.block_at(Timestamp::new(now.as_millis() as u64)))
+			.ok_or_else(|| "Scenario has not started yet".into())
+	}
+}
+
+#[async_trait::async_trait]
+impl AuthoritySelectionDataSource for ScenarioDataSource {
+	async fn get_ariadne_parameters(
+		&self,
+		epoch_number: McEpochNumber,
+		_d_parameter_policy: PolicyId,
+		_permissioned_candidates_policy: PolicyId,
+	) -> Result<AriadneParameters> {
+		let state = self.scenario.state_at_epoch_end(epoch_number);
+		let d_parameter = (state.d_parameter)
+			.ok_or_else(|| format!("D-Parameter is not set in epoch {epoch_number}"))?;
+		debug!("Serving D-Parameter for epoch {epoch_number}: {}", d_parameter.info_string());
+		Ok(AriadneParameters {
+			d_parameter: d_parameter.into(),
+			permissioned_candidates: (state.permissioned)
+				.map(|candidates| candidates.into_iter().map(Into::into).collect()),
+		})
+	}
+
+	async fn get_candidates(
+		&self,
+		epoch_number: McEpochNumber,
+		_committee_candidate_address: MainchainAddress,
+	) -> Result<Vec<CandidateRegistrations>> {
+		let registrations = self.scenario.state_at_epoch_end(epoch_number).registrations;
+		debug!(
+			"Serving registrations for epoch {epoch_number}: {:?}",
+			registrations.iter().map(|r| r.info_string()).collect::<Vec<_>>()
+		);
+		Ok(registrations.into_iter().map(CandidateRegistrations::from).collect())
+	}
+
+	async fn get_epoch_nonce(&self, epoch_number: McEpochNumber) -> Result<Option<EpochNonce>> {
+		let mut nonce = vec![0u8; 32];
+		nonce[..4].copy_from_slice(&epoch_number.0.to_be_bytes());
+		Ok(Some(EpochNonce(nonce)))
+	}
+
+	async fn data_epoch(&self, for_epoch: McEpochNumber) -> Result<McEpochNumber> {
+		Ok(McEpochNumber(for_epoch.0.saturating_sub(2)))
+	}
+}
+
+#[async_trait::async_trait]
+impl GovernedMapDataSource for ScenarioDataSource {
+	async fn get_mapping_changes(
+		&self,
+		since_mc_block: Option<McBlockHash>,
+		up_to_mc_block: McBlockHash,
+		_scripts: MainChainScriptsV1,
+	) -> Result<Vec<(String, Option<ByteString>)>> {
+		let previous = match since_mc_block {
+			Some(since_mc_block) => self.scenario.state_at_block(&since_mc_block)?.governed_map,
+			None => BTreeMap::new(),
+		};
+		let current = self.scenario.state_at_block(&up_to_mc_block)?.governed_map;
+		let removed = (previous.keys())
+			.filter(|key| !current.contains_key(*key))
+			.map(|key| (key.clone(), None));
+		let changed = (current.iter())
+			.filter(|(key, value)| previous.get(*key) != Some(*value))
+			.map(|(key, value)| (key.clone(), Some(value.clone())));
+		Ok(removed.chain(changed).collect())
+	}
+
+	async fn get_state_at_block(
+		&self,
+		mc_block: McBlockHash,
+		_main_chain_scripts: MainChainScriptsV1,
+	) -> Result<BTreeMap<String, ByteString>> {
+		Ok(self.scenario.state_at_block(&mc_block)?.governed_map)
+	}
+}
+
+#[async_trait::async_trait]
+impl BlockParticipationDataSource for ScenarioDataSource {
+	async fn get_stake_pool_delegation_distribution_for_pools(
+		&self,
+		epoch: McEpochNumber,
+		pool_hashes: &[MainchainKeyHash],
+	) -> Result<StakeDistribution> {
+		let distribution = self.scenario.state_at_epoch_end(epoch).stake_distribution;
+		let mut result = BTreeMap::new();
+		for MockPoolDelegation { pool, delegators } in distribution {
+			let pool = MainchainKeyHash(pool.0.try_into().map_err(|_| "Pool hash is 28 bytes")?);
+			if !pool_hashes.contains(&pool) {
+				continue;
+			}
+			let mut delegation = PoolDelegation::default();
+			for MockDelegator { stake_key_hash, amount } in delegators {
+				let key_hash = (stake_key_hash.0.try_into())
+					.map_err(|_| "Delegator stake key hash is 28 bytes")?;
+				delegation.total_stake.0 += amount;
+				(delegation.delegators)
+					.insert(DelegatorKey::StakeKeyHash(key_hash), DelegatorStakeAmount(amount));
+			}
+			result.insert(pool, delegation);
+		}
+		Ok(StakeDistribution(result))
+	}
+}
+
+#[async_trait::async_trait]
+impl<RecipientAddress: Decode + Send + Sync> TokenBridgeDataSource<RecipientAddress>
+	for ScenarioDataSource
+{
+	async fn get_transfers(
+		&self,
+		_main_chain_scripts: MainChainScripts,
+		data_checkpoint: BridgeDataCheckpoint,
+		max_transfers: u32,
+		current_mc_block: McBlockHash,
+	) -> Result<(Vec<BridgeTransferV1<RecipientAddress>>, BridgeDataCheckpoint)> {
+		let current_mc_block = (self.scenario.block_by_hash(&current_mc_block))
+			.ok_or_else(|| format!("Block {current_mc_block} is not a block of the scenario"))?;
+		let all_transfers = self.scenario.state_at(current_mc_block.number.0).transfers;
+		let unprocessed = match &data_checkpoint {
+			BridgeDataCheckpoint::Block(number) => {
+				all_transfers.into_iter().filter(|(block, _, _)| block > number).collect()
+			},
+			BridgeDataCheckpoint::Utxo(utxo) => {
+				let position = (all_transfers.iter().position(|(_, id, _)| id == utxo))
+					.ok_or_else(|| format!("Unknown data checkpoint: {data_checkpoint:?}"))?;
+				all_transfers[position + 1..].to_vec()
+			},
+		};
+		let transfers: Vec<_> = unprocessed.into_iter().take(max_transfers as usize).collect();
+		let checkpoint = match transfers.last() {
+			Some((_, utxo_id, _)) if transfers.len() as u32 == max_transfers => {
+				BridgeDataCheckpoint::Utxo(*utxo_id)
+			},
+			_ => BridgeDataCheckpoint::Block(current_mc_block.number),
+		};
+		let transfers = transfers.into_iter().map(|(_, utxo_id, transfer)| match transfer {
+			MockBridgeTransfer::User { amount, recipient } => {
+				match RecipientAddress::decode(&mut &recipient.0[..]) {
+					Ok(recipient) => {
+						BridgeTransferV1::UserTransfer { token_amount: amount, recipient }
+					},
+					Err(_) => BridgeTransferV1::InvalidTransfer { token_amount: amount, utxo_id },
+				}
+			},
+			MockBridgeTransfer::Reserve { amount } => {
+				BridgeTransferV1::ReserveTransfer { token_amount: amount }
+			},
+			MockBridgeTransfer::Invalid { amount } => {
+				BridgeTransferV1::InvalidTransfer { token_amount: amount, utxo_id }
+			},
+		});
+		Ok((transfers.collect(), checkpoint))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn scenario(events: &str) -> ScenarioDataSource {
+		let json = format!(
+			r#"{{"start_timestamp_millis": 1000000, "epoch_length_blocks": 10, "events": {events}}}"#
+		);
+		let mut scenario: Scenario = serde_json::from_str(&json).unwrap();
+		scenario.events.sort_by_key(|event| event.block);
+		ScenarioDataSource::new(scenario)
+	}
+
+	fn hash(number: u32) -> McBlockHash {
+		let mut hash = [0u8; 32];
+		hash[..4].copy_from_slice(&number.to_be_bytes());
+		McBlockHash(hash)
+	}
+
+	async fn get_transfers(
+		data_source: &ScenarioDataSource,
+		scripts: MainChainScripts,
+		checkpoint: BridgeDataCheckpoint,
+		max_transfers: u32,
+		current_mc_block: McBlockHash,
+	) -> Result<(Vec<BridgeTransferV1<u32>>, BridgeDataCheckpoint)> {
+		(data_source.get_transfers(scripts, checkpoint, max_transfers, current_mc_block)).await
+	}
+
+	fn scripts() -> MainChainScripts {
+		MainChainScripts {
+			token_policy_id: Default::default(),
+			token_asset_name: Default::default(),
+			illiquid_circulation_supply_validator_address: Default::default(),
+		}
+	}
+
+	#[test]
+	fn derives_blocks_from_scenario_start() {
+		let data_source = scenario("[]");
+		let block = data_source.scenario.block_at(Timestamp::new(1000000 + 25 * 20000)).unwrap();
+
+		assert_eq!(block.number, McBlockNumber(25));
+		assert_eq!(block.epoch, McEpochNumber(2));
+		assert_eq!(data_source.scenario.block_by_hash(&block.hash), Some(block));
+		assert_eq!(data_source.scenario.block_at(Timestamp::new(999999)), None);
+	}
+
+	#[tokio::test]
+	async fn serves_governed_map_changes_between_blocks() {
+		let data_source = scenario(
+			r#"[
+				{"block": 1, "governed_map_insert": {"key": "a", "value": "0x01"}},
+				{"block": 1, "governed_map_insert": {"key": "b", "value": "0x02"}},
+				{"block": 5, "governed_map_remove": {"key": "a"}},
+				{"block": 6, "governed_map_insert": {"key": "b", "value": "0x03"}}
+			]"#,
+		);
+		let scripts = MainChainScriptsV1::default();
+
+		let changes = data_source.get_mapping_changes(None, hash(2), scripts.clone()).await;
+		assert_eq!(
+			changes.unwrap(),
+			vec![("a".into(), Some(ByteString(vec![1]))), ("b".into(), Some(ByteString(vec![2])))]
+		);
+		let changes =
+			data_source.get_mapping_changes(Some(hash(2)), hash(4), scripts.clone()).await;
+		assert_eq!(changes.unwrap(), vec![]);
+		let changes = data_source.get_mapping_changes(Some(hash(4)), hash(6), scripts).await;
+		assert_eq!(
+			changes.unwrap(),
+			vec![("a".into(), None), ("b".into(), Some(ByteString(vec![3])))]
+		);
+	}
+
+	#[tokio::test]
+	async fn serves_bridge_transfers_after_checkpoint() {
+		let data_source = scenario(
+			r#"[
+				{"block": 2, "bridge_transfer": {"reserve": {"amount": 1}}},
+				{"block": 3, "bridge_transfer": {"user": {"amount": 2, "recipient": "0x02000000"}}},
+				{"block": 3, "bridge_transfer": {"invalid": {"amount": 3}}},
+				{"block": 8, "bridge_transfer": {"reserve": {"amount": 4}}}
+			]"#,
+		);
+		let checkpoint = BridgeDataCheckpoint::Block(McBlockNumber(0));
+
+		let (transfers, checkpoint) =
+			get_transfers(&data_source, scripts(), checkpoint, 2, hash(5)).await.unwrap();
+		assert_eq!(
+			transfers,
+			vec![
+				BridgeTransferV1::ReserveTransfer { token_amount: 1 },
+				BridgeTransferV1::UserTransfer { token_amount: 2, recipient: 2 }
+			]
+		);
+		assert!(matches!(checkpoint, BridgeDataCheckpoint::Utxo(_)));
+
+		let (transfers, checkpoint) =
+			get_transfers(&data_source, scripts(), checkpoint, 2, hash(5)).await.unwrap();
+		assert!(matches!(
+			transfers[..],
+			[BridgeTransferV1::InvalidTransfer { token_amount: 3, .. }]
+		));
+		assert_eq!(checkpoint, BridgeDataCheckpoint::Block(McBlockNumber(5)));
+	}
+}
@@ -0,0 +1,35 @@
+use crate::{PersistentCacheDataSource, Result};
+use parity_scale_codec::{Decode, Encode};
+use sidechain_domain::*;
+use sp_partner_chains_bridge::{
+	BridgeDataCheckpoint, BridgeTransferV1, MainChainScripts, TokenBridgeDataSource,
+};
+
+#[async_trait::async_trait]
+impl<RecipientAddress, T> TokenBridgeDataSource<RecipientAddress> for PersistentCacheDataSource<T>
+where
+	RecipientAddress: Encode + Decode + Send + Sync,
+	T: TokenBridgeDataSource<RecipientAddress> + ?Sized,
+{
+	async fn get_transfers(
+		&self,
+		main_chain_scripts: MainChainScripts,
+		data_checkpoint: BridgeDataCheckpoint,
+		max_transfers: u32,
+		current_mc_block: McBlockHash,
+	) -> Result<(Vec<BridgeTransferV1<RecipientAddress>>, BridgeDataCheckpoint)> {
+		self.cache
+			.get_or_query(
+				"get_transfers",
+				(&main_chain_scripts, &data_checkpoint, max_transfers, &current_mc_block),
+				self.cache.is_stable_block(&current_mc_block),
+				self.inner.get_transfers(
+					main_chain_scripts.clone(),
+					data_checkpoint.clone(),
+					max_transfers,
+					current_mc_block.clone(),
+				),
+			)
+			.await
+	}
+}
@@ -0,0 +1,91 @@
+use crate::{PersistentCacheDataSource, Result};
+use authority_selection_inherents::{AriadneParameters, AuthoritySelectionDataSource};
+use sidechain_domain::*;
+
+#[async_trait::async_trait]
+impl<T> AuthoritySelectionDataSource for PersistentCacheDataSource<T>
+where
+	T: AuthoritySelectionDataSource + Send + Sync + ?Sized,
+{
+	async fn get_ariadne_parameters(
+		&self,
+		epoch_number: McEpochNumber,
+		d_parameter_policy: PolicyId,
+		permissioned_candidates_policy: PolicyId,
+	) -> Result<AriadneParameters> {
+		self.cache
+			.get_or_query(
+				"get_ariadne_parameters",
+				(epoch_number, &d_parameter_policy, &permissioned_candidates_policy),
+				self.is_stable_data_epoch(epoch_number),
+				self.inner.get_ariadne_parameters(
+					epoch_number,
+					d_parameter_policy.clone(),
+					permissioned_candidates_policy.clone(),
+				),
+			)
+			.await
+	}
+
+	async fn get_candidates(
+		&self,
+		epoch_number: McEpochNumber,
+		committee_candidate_address: MainchainAddress,
+	) -> Result<Vec<CandidateRegistrations>> {
+		self.cache
+			.get_or_query(
+				"get_candidates",
+				(epoch_number, &committee_candidate_address),
+				self.is_stable_data_epoch(epoch_number),
+				self.inner.get_candidates(epoch_number, committee_candidate_address.clone()),
+			)
+			.await
+	}
+
+	async fn get_token_staked_candidates(
+		&self,
+		epoch_number: McEpochNumber,
+		committee_candidate_address: MainchainAddress,
+		token_staking: TokenStakingConfig,
+	) -> Result<Vec<TokenStakedCandidateRegistrations>> {
+		self.cache
+			.get_or_query(
+				"get_token_staked_candidates",
+				(epoch_number, &committee_candidate_address, &token_staking),
+				self.is_stable_data_epoch(epoch_number),
+				self.inner.get_token_staked_candidates(
+					epoch_number,
+					committee_candidate_address.clone(),
+					token_staking.clone(),
+				),
+			)
+			.await
+	}
+
+	async fn get_epoch_nonce(&self, epoch_number: McEpochNumber) -> Result<Option<EpochNonce>> {
+		const GET_EPOCH_NONCE: &str = "get_epoch_nonce";
+		if let Some(nonce) = self.cache.get(GET_EPOCH_NONCE, &epoch_number) {
+			return Ok(Some(nonce));
+		}
+		let is_stable = self.cache.is_stable_epoch(epoch_number).await?;
+		let nonce = self.inner.get_epoch_nonce(epoch_number).await?;
+		if let Some(nonce) = &nonce {
+			if is_stable {
+				self.cache.put(GET_EPOCH_NONCE, &epoch_number, nonce);
+			}
+		}
+		Ok(nonce)
+	}
+
+	async fn data_epoch(&self, for_epoch: McEpochNumber) -> Result<McEpochNumber> {
+		self.inner.data_epoch(for_epoch).await
+	}
+}
+
+impl<T: AuthoritySelectionDataSource + Send + Sync + ?Sized> PersistentCacheDataSource<T> {
+	/// Candidates data for `epoch` is taken from the last block of its data epoch
+	async fn is_stable_data_epoch(&self, epoch: McEpochNumber) -> Result<bool> {
+		let data_epoch = self.inner.data_epoch(epoch).await?;
+		self.cache.is_stable_epoch(data_epoch).await
+	}
+}
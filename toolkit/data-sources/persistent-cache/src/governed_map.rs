@@ -0,0 +1,45 @@
+use crate::{PersistentCacheDataSource, Result};
+use sidechain_domain::byte_string::ByteString;
+use sidechain_domain::*;
+use sp_governed_map::{GovernedMapDataSource, MainChainScriptsV1};
+
+#[async_trait::async_trait]
+impl<T> GovernedMapDataSource for PersistentCacheDataSource<T>
+where
+	T: GovernedMapDataSource + Send + Sync + ?Sized,
+{
+	async fn get_mapping_changes(
+		&self,
+		since_mc_block: Option<McBlockHash>,
+		up_to_mc_block: McBlockHash,
+		scripts: MainChainScriptsV1,
+	) -> Result<Vec<(String, Option<ByteString>)>> {
+		self.cache
+			.get_or_query(
+				"get_mapping_changes",
+				(&since_mc_block, &up_to_mc_block, &scripts),
+				self.cache.is_stable_block(&up_to_mc_block),
+				self.inner.get_mapping_changes(
+					since_mc_block.clone(),
+					up_to_mc_block.clone(),
+					scripts.clone(),
+				),
+			)
+			.await
+	}
+
+	async fn get_state_at_block(
+		&self,
+		mc_block: McBlockHash,
+		main_chain_scripts: MainChainScriptsV1,
+	) -> Result<BTreeMap<String, ByteString>> {
+		self.cache
+			.get_or_query(
+				"get_state_at_block",
+				(&mc_block, &main_chain_scripts),
+				self.cache.is_stable_block(&mc_block),
+				self.inner.get_state_at_block(mc_block.clone(), main_chain_scripts.clone()),
+			)
+			.await
+	}
+}
@@ -0,0 +1,297 @@
+//! Crate providing a persistent on-disk cache of Cardano data served by Partner Chains data sources.
+//!
+//! The in-memory caches of the data sources are lost on every node restart, which causes all
+//! recent data to be queried again, and verification of old blocks during sync to query the
+//! data source for every block. [PersistentCacheDataSource] wraps a data source and stores
+//! its responses in a [PersistentCache] on disk, so they survive node restarts.
+//!
+//! # Invalidation
+//!
+//! Only data that can not change is stored, which means the cache never needs invalidation:
+//! - data queried for a Cardano block is stored only if the block is stable,
+//! - data queried for a Cardano epoch is stored only if the epoch from which the data is taken
+//!   ended before the epoch of the latest stable block.
+//!
+//! Data about unstable blocks and epochs is always queried from the wrapped data source.
+//!
+//! # Pruning
+//!
+//! Entries are removed from the cache after a retention period, [DEFAULT_RETENTION] unless configured
+//! with [PersistentCache::open_with_retention]. Pruning runs when the cache is opened and then at most
+//! once per hour when new data is stored, removing at most [MAX_PRUNED_ENTRIES] entries per run.
+//! Data of pruned entries is queried from the wrapped data source again when needed.
+//!
+//! # Usage
+//!
+//! All data sources of a node should share one [PersistentCache]:
+//! ```rust,no_run
+//! use partner_chains_persistent_cache_data_sources::*;
+//! use sidechain_mc_hash::McHashDataSource;
+//! use std::sync::Arc;
+//!
+//! fn wrap_mc_hash(
+//!     mc_hash: Arc<dyn McHashDataSource + Send + Sync>,
+//!     node_data_path: &std::path::Path,
+//! ) -> Result<Arc<dyn McHashDataSource + Send + Sync>, Box<dyn std::error::Error + Send + Sync>> {
+//!     let cache = Arc::new(PersistentCache::open(&node_data_path.join("mc_data_cache"), mc_hash.clone())?);
+//!     Ok(Arc::new(PersistentCacheDataSource::new(mc_hash, cache)))
+//! }
+//! ```
+#![deny(missing_docs)]
+
+use log::{debug, warn};
+use parity_scale_codec::{Decode, Encode};
+use sidechain_domain::*;
+use sidechain_mc_hash::McHashDataSource;
+use std::error::Error;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+#[cfg(feature = "bridge")]
+mod bridge;
+#[cfg(feature = "candidate-source")]
+mod candidate;
+#[cfg(feature = "governed-map")]
+mod governed_map;
+mod mc_hash;
+#[cfg(feature = "block-participation")]
+mod stake_distribution;
+
+#[cfg(test)]
+mod tests;
+
+type Result<T> = std::result::Result<T, Box<dyn Error + Send + Sync>>;
+
+const COLUMN: u8 = 0;
+/// Column indexing the entries of [COLUMN] by the time they were stored, used for pruning
+const STORED_AT_COLUMN: u8 = 1;
+
+/// Default period for which stored entries are retained
+pub const DEFAULT_RETENTION: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Maximal number of entries removed by a single pruning run
+pub const MAX_PRUNED_ENTRIES: usize = 10_000;
+
+/// Minimal interval between pruning runs
+const PRUNING_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Minimal interval between queries for the latest stable block
+const STABLE_TIP_REFRESH_INTERVAL: Duration = Duration::from_secs(20);
+
+pub(crate) const GET_BLOCK_BY_HASH: &str = "get_block_by_hash";
+
+/// On-disk store of stable Cardano data, shared by all [PersistentCacheDataSource]s of a node
+pub struct PersistentCache {
+	db: parity_db::Db,
+	blocks: Arc<dyn McHashDataSource + Send + Sync>,
+	stable_tip: Mutex<Option<(MainchainBlock, Instant)>>,
+	retention: Duration,
+	last_pruned: Mutex<Instant>,
+}
+
+impl PersistentCache {
+	/// Opens the cache database at `path`, creating it if it does not exist.
+	/// `blocks` is used to determine stability of Cardano blocks and epochs.
+	/// Entries are retained for [DEFAULT_RETENTION].
+	pub fn open(path: &Path, blocks: Arc<dyn McHashDataSource + Send + Sync>) -> Result<Self> {
+		Self::open_with_retention(path, blocks, DEFAULT_RETENTION)
+	}
+
+	/// Opens the cache database at `path` like [PersistentCache::open], retaining entries for `retention`
+	pub fn open_with_retention(
+		path: &Path,
+		blocks: Arc<dyn McHashDataSource + Send + Sync>,
+		retention: Duration,
+	) -> Result<Self> {
+		let mut options = parity_db::Options::with_columns(path, 2);
+		options.columns[usize::from(STORED_AT_COLUMN)].btree_index = true;
+		let db = parity_db::Db::open_or_create(&options)?;
+		log::info!("Using persistent data source cache at {}", path.display());
+		let cache = Self {
+			db,
+			blocks,
+			stable_tip: Mutex::new(None),
+			retention,
+			last_pruned: Mutex::new(Instant::now()),
+		};
+		cache.prune()?;
+		Ok(cache)
+	}
+
+	/// Removes entries stored more than the retention period ago, at most [MAX_PRUNED_ENTRIES] of them.
+	/// Returns the number of removed entries.
+	pub fn prune(&self) -> Result<usize> {
+		let cutoff = now_secs()?.saturating_sub(self.retention.as_secs());
+		let pruned = self.prune_stored_before(cutoff)?;
+		if pruned > 0 {
+			log::info!("Pruned {pruned} entries from persistent data source cache");
+		}
+		Ok(pruned)
+	}
+
+	fn prune_stored_before(&self, cutoff_secs: u64) -> Result<usize> {
+		let mut expired = Vec::new();
+		let mut iter = self.db.iter(STORED_AT_COLUMN)?;
+		iter.seek_to_first()?;
+		while expired.len() < MAX_PRUNED_ENTRIES {
+			let Some((index_key, _)) = iter.next()? else { break };
+			let stored_at = index_key
+				.split_first_chunk::<8>()
+				.map(|(stored_at, _)| u64::from_be_bytes(*stored_at));
+			if stored_at.is_some_and(|stored_at| stored_at >= cutoff_secs) {
+				break;
+			}
+			expired.push(index_key);
+		}
+		drop(iter);
+
+		let pruned = expired.len();
+		if pruned == 0 {
+			return Ok(0);
+		}
+		let changes = expired.into_iter().flat_map(|index_key| {
+			let key = index_key.get(8..).unwrap_or_default().to_vec();
+			[(STORED_AT_COLUMN, index_key, None), (COLUMN, key, None)]
+		});
+		self.db.commit(changes)?;
+		Ok(pruned)
+	}
+
+	fn prune_if_due(&self) {
+		{
+			let mut last_pruned = self.last_pruned.lock().expect("Pruning lock is poisoned");
+			if last_pruned.elapsed() < PRUNING_INTERVAL {
+				return;
+			}
+			*last_pruned = Instant::now();
+		}
+		if let Err(err) = self.prune() {
+			warn!("Failed to prune persistent data source cache: {err}");
+		}
+	}
+
+	fn get<T: Decode>(&self, method: &str, request: &impl Encode) -> Option<T> {
+		let key = (method, request).encode();
+		match self.db.get(COLUMN, &key) {
+			Ok(Some(value)) => match T::decode(&mut &value[..]) {
+				Ok(value) => {
+					debug!("Serving {method} from persistent cache");
+					Some(value)
+				},
+				Err(err) => {
+					warn!("Failed to decode {method} response from persistent cache: {err}");
+					None
+				},
+			},
+			Ok(None) => None,
+			Err(err) => {
+				warn!("Failed to read {method} response from persistent cache: {err}");
+				None
+			},
+		}
+	}
+
+	fn put(&self, method: &str, request: &impl Encode, response: &impl Encode) {
+		let key = (method, request).encode();
+		let stored_at = match now_secs() {
+			Ok(now) => now.to_be_bytes(),
+			Err(err) => {
+				warn!("Failed to store {method} response in persistent cache: {err}");
+				return;
+			},
+		};
+		let index_key = [&stored_at[..], &key[..]].concat();
+		let changes =
+			[(COLUMN, key, Some(response.encode())), (STORED_AT_COLUMN, index_key, Some(vec![]))];
+		if let Err(err) = self.db.commit(changes) {
+			warn!("Failed to store {method} response in persistent cache: {err}");
+		}
+		self.prune_if_due();
+	}
+
+	/// Serves `request` from the cache, or awaits `query` and stores its result if `is_stable`
+	/// resolves to true. Stability is checked before the query, so that the data is not stored
+	/// if it was queried before becoming stable.
+	async fn get_or_query<T: Encode + Decode>(
+		&self,
+		method: &str,
+		request: impl Encode,
+		is_stable: impl Future<Output = Result<bool>>,
+		query: impl Future<Output = Result<T>>,
+	) -> Result<T> {
+		if let Some(response) = self.get(method, &request) {
+			return Ok(response);
+		}
+		let is_stable = is_stable.await?;
+		let response = query.await?;
+		if is_stable {
+			self.put(method, &request, &response);
+		}
+		Ok(response)
+	}
+
+	async fn stable_tip(&self, refresh: bool) -> Result<Option<MainchainBlock>> {
+		let cached = self.stable_tip.lock().expect("Stable tip lock is poisoned").clone();
+		if let Some((tip, fetched_at)) = cached {
+			if !refresh || fetched_at.elapsed() < STABLE_TIP_REFRESH_INTERVAL {
+				return Ok(Some(tip));
+			}
+		}
+		let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as u64;
+		let tip = self.blocks.get_latest_stable_block_for(now.into()).await?;
+		if let Some(tip) = &tip {
+			*self.stable_tip.lock().expect("Stable tip lock is poisoned") =
+				Some((tip.clone(), Instant::now()));
+		}
+		Ok(tip)
+	}
+
+	/// Returns true if the stable tip satisfies `predicate`, refreshing the stable tip if needed
+	async fn stable_tip_satisfies(
+		&self,
+		predicate: impl Fn(&MainchainBlock) -> bool,
+	) -> Result<bool> {
+		if self.stable_tip(false).await?.is_some_and(|tip| predicate(&tip)) {
+			return Ok(true);
+		}
+		Ok(self.stable_tip(true).await?.is_some_and(|tip| predicate(&tip)))
+	}
+
+	/// Returns true if all blocks of `epoch` are stable
+	pub(crate) async fn is_stable_epoch(&self, epoch: McEpochNumber) -> Result<bool> {
+		self.stable_tip_satisfies(|tip| tip.epoch > epoch).await
+	}
+
+	/// Returns true if block with `hash` is stable. Stable blocks are stored in the cache.
+	pub(crate) async fn is_stable_block(&self, hash: &McBlockHash) -> Result<bool> {
+		if self.get::<MainchainBlock>(GET_BLOCK_BY_HASH, hash).is_some() {
+			return Ok(true);
+		}
+		let Some(block) = self.blocks.get_block_by_hash(hash.clone()).await? else {
+			return Ok(false);
+		};
+		let is_stable = self.stable_tip_satisfies(|tip| tip.number >= block.number).await?;
+		if is_stable {
+			self.put(GET_BLOCK_BY_HASH, hash, &block);
+		}
+		Ok(is_stable)
+	}
+}
+
+/// Data source wrapper serving stable data from a [PersistentCache]
+pub struct PersistentCacheDataSource<T: ?Sized> {
+	inner: Arc<T>,
+	cache: Arc<PersistentCache>,
+}
+
+impl<T: ?Sized> PersistentCacheDataSource<T> {
+	/// Wraps `inner` data source, storing its stable data in `cache`
+	pub fn new(inner: Arc<T>, cache: Arc<PersistentCache>) -> Self {
+		Self { inner, cache }
+	}
+}
+
+fn now_secs() -> Result<u64> {
+	Ok(SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs())
+}
@@ -0,0 +1,50 @@
+use crate::{GET_BLOCK_BY_HASH, PersistentCacheDataSource, Result};
+use sidechain_domain::*;
+use sidechain_mc_hash::McHashDataSource;
+use sp_timestamp::Timestamp;
+
+const GET_STABLE_BLOCK_FOR: &str = "get_stable_block_for";
+
+#[async_trait::async_trait]
+impl<T: McHashDataSource + Send + Sync + ?Sized> McHashDataSource for PersistentCacheDataSource<T> {
+	async fn get_latest_stable_block_for(
+		&self,
+		reference_timestamp: Timestamp,
+	) -> Result<Option<MainchainBlock>> {
+		self.inner.get_latest_stable_block_for(reference_timestamp).await
+	}
+
+	async fn get_stable_block_for(
+		&self,
+		hash: McBlockHash,
+		reference_timestamp: Timestamp,
+	) -> Result<Option<MainchainBlock>> {
+		let request = (&hash, reference_timestamp);
+		if let Some(block) = self.cache.get(GET_STABLE_BLOCK_FOR, &request) {
+			return Ok(Some(block));
+		}
+		let block = self.inner.get_stable_block_for(hash.clone(), reference_timestamp).await?;
+		// Only positive responses are final, a block that is not stable yet may become stable later
+		if let Some(block) = &block {
+			self.cache.put(GET_STABLE_BLOCK_FOR, &request, block);
+		}
+		Ok(block)
+	}
+
+	async fn get_block_by_hash(&self, hash: McBlockHash) -> Result<Option<MainchainBlock>> {
+		if let Some(block) = self.cache.get(GET_BLOCK_BY_HASH, &hash) {
+			return Ok(Some(block));
+		}
+		let block = self.inner.get_block_by_hash(hash.clone()).await?;
+		if let Some(block) = &block {
+			if self.cache.stable_tip_satisfies(|tip| tip.number >= block.number).await? {
+				self.cache.put(GET_BLOCK_BY_HASH, &hash, block);
+			}
+		}
+		Ok(block)
+	}
+
+	async fn get_tip(&self) -> Result<Option<MainchainBlock>> {
+		self.inner.get_tip().await
+	}
+}
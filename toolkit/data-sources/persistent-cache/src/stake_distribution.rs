@@ -0,0 +1,24 @@
+use crate::{PersistentCacheDataSource, Result};
+use sidechain_domain::*;
+use sp_block_participation::inherent_data::BlockParticipationDataSource;
+
+#[async_trait::async_trait]
+impl<T> BlockParticipationDataSource for PersistentCacheDataSource<T>
+where
+	T: BlockParticipationDataSource + Send + Sync + ?Sized,
+{
+	async fn get_stake_pool_delegation_distribution_for_pools(
+		&self,
+		epoch: McEpochNumber,
+		pool_hashes: &[MainchainKeyHash],
+	) -> Result<StakeDistribution> {
+		self.cache
+			.get_or_query(
+				"get_stake_pool_delegation_distribution_for_pools",
+				(epoch, pool_hashes),
+				self.cache.is_stable_epoch(epoch),
+				self.inner.get_stake_pool_delegation_distribution_for_pools(epoch, pool_hashes),
+			)
+			.await
+	}
+}
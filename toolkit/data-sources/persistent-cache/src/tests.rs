@@ -0,0 +1,360 @@
+use crate::*;
+use sp_timestamp::Timestamp;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+struct MockBlocks {
+	blocks: Vec<MainchainBlock>,
+	stable_tip_number: Mutex<u32>,
+	queries: AtomicU32,
+}
+
+impl MockBlocks {
+	fn new(stable_tip_number: u32) -> Arc<Self> {
+		let blocks = (0..10).map(block).collect();
+		Arc::new(Self {
+			blocks,
+			stable_tip_number: Mutex::new(stable_tip_number),
+			queries: 0.into(),
+		})
+	}
+
+	fn queries(&self) -> u32 {
+		self.queries.load(Ordering::SeqCst)
+	}
+
+	fn stable_block(&self, hash: &McBlockHash) -> Option<MainchainBlock> {
+		let stable_tip_number = *self.stable_tip_number.lock().unwrap();
+		self.blocks
+			.iter()
+			.find(|b| b.hash == *hash && b.number.0 <= stable_tip_number)
+			.cloned()
+	}
+}
+
+#[async_trait::async_trait]
+impl McHashDataSource for MockBlocks {
+	async fn get_latest_stable_block_for(
+		&self,
+		_reference_timestamp: Timestamp,
+	) -> Result<Option<MainchainBlock>> {
+		let stable_tip_number = *self.stable_tip_number.lock().unwrap();
+		Ok(Some(self.blocks[stable_tip_number as usize].clone()))
+	}
+
+	async fn get_stable_block_for(
+		&self,
+		hash: McBlockHash,
+		_reference_timestamp: Timestamp,
+	) -> Result<Option<MainchainBlock>> {
+		self.queries.fetch_add(1, Ordering::SeqCst);
+		Ok(self.stable_block(&hash))
+	}
+
+	async fn get_block_by_hash(&self, hash: McBlockHash) -> Result<Option<MainchainBlock>> {
+		self.queries.fetch_add(1, Ordering::SeqCst);
+		Ok(self.blocks.iter().find(|b| b.hash == hash).cloned())
+	}
+
+	async fn get_tip(&self) -> Result<Option<MainchainBlock>> {
+		Ok(self.blocks.last().cloned())
+	}
+}
+
+fn block(number: u32) -> MainchainBlock {
+	MainchainBlock {
+		number: McBlockNumber(number),
+		hash: McBlockHash([number as u8; 32]),
+		epoch: McEpochNumber(number / 2),
+		slot: McSlotNumber(number.into()),
+		timestamp: number.into(),
+	}
+}
+
+fn data_source(
+	path: &Path,
+	blocks: Arc<MockBlocks>,
+) -> PersistentCacheDataSource<dyn McHashDataSource + Send + Sync> {
+	let cache = Arc::new(PersistentCache::open(path, blocks.clone()).unwrap());
+	PersistentCacheDataSource::new(blocks, cache)
+}
+
+#[tokio::test]
+async fn stores_only_stable_blocks() {
+	let dir = tempfile::tempdir().unwrap();
+	let blocks = MockBlocks::new(5);
+	let data_source = data_source(dir.path(), blocks.clone());
+
+	for _ in 0..2 {
+		assert_eq!(data_source.get_block_by_hash(block(5).hash).await.unwrap(), Some(block(5)));
+		assert_eq!(data_source.get_block_by_hash(block(6).hash).await.unwrap(), Some(block(6)));
+	}
+
+	assert_eq!(blocks.queries(), 3);
+}
+
+#[tokio::test]
+async fn serves_stored_data_after_reopening() {
+	let dir = tempfile::tempdir().unwrap();
+	let blocks = MockBlocks::new(5);
+	{
+		let data_source = data_source(dir.path(), blocks.clone());
+		data_source.get_block_by_hash(block(3).hash).await.unwrap();
+	}
+
+	let data_source = data_source(dir.path(), blocks.clone());
+	assert_eq!(data_source.get_block_by_hash(block(3).hash).await.unwrap(), Some(block(3)));
+
+	assert_eq!(blocks.queries(), 1);
+}
+
+#[tokio::test]
+async fn stores_only_positive_stable_block_responses() {
+	let dir = tempfile::tempdir().unwrap();
+	let blocks = MockBlocks::new(5);
+	let data_source = data_source(dir.path(), blocks.clone());
+	let timestamp = Timestamp::new(0);
+
+	assert_eq!(data_source.get_stable_block_for(block(7).hash, timestamp).await.unwrap(), None);
+	*blocks.stable_tip_number.lock().unwrap() = 7;
+	for _ in 0..2 {
+		let stable_block =
+			data_source.get_stable_block_for(block(7).hash, timestamp).await.unwrap();
+		assert_eq!(stable_block, Some(block(7)));
+	}
+
+	assert_eq!(blocks.queries(), 2);
+}
+
+#[tokio::test]
+async fn prunes_entries_stored_before_cutoff() {
+	let dir = tempfile::tempdir().unwrap();
+	let cache = PersistentCache::open(dir.path(), MockBlocks::new(5)).unwrap();
+	cache.put("method", &1u32, &2u32);
+
+	assert_eq!(cache.prune_stored_before(0).unwrap(), 0);
+	assert_eq!(cache.get::<u32>("method", &1u32), Some(2));
+
+	assert_eq!(cache.prune_stored_before(u64::MAX).unwrap(), 1);
+	assert_eq!(cache.get::<u32>("method", &1u32), None);
+	assert_eq!(cache.prune_stored_before(u64::MAX).unwrap(), 0);
+}
+
+/// Data source counting its queries, with data of epoch `n` taken from epoch `n - 2`
+#[derive(Default)]
+struct MockDataSource {
+	queries: AtomicU32,
+}
+
+impl MockDataSource {
+	fn query(&self) {
+		self.queries.fetch_add(1, Ordering::SeqCst);
+	}
+
+	fn queries(&self) -> u32 {
+		self.queries.load(Ordering::SeqCst)
+	}
+}
+
+fn cached_data_source(
+	path: &Path,
+	inner: Arc<MockDataSource>,
+) -> PersistentCacheDataSource<MockDataSource> {
+	// stable tip is block 5 of epoch 2, so epochs 0 and 1 are stable
+	let cache = Arc::new(PersistentCache::open(path, MockBlocks::new(5)).unwrap());
+	PersistentCacheDataSource::new(inner, cache)
+}
+
+#[cfg(feature = "candidate-source")]
+mod candidate {
+	use super::*;
+	use authority_selection_inherents::{AriadneParameters, AuthoritySelectionDataSource};
+
+	#[async_trait::async_trait]
+	impl AuthoritySelectionDataSource for MockDataSource {
+		async fn get_ariadne_parameters(
+			&self,
+			_epoch_number: McEpochNumber,
+			_d_parameter: PolicyId,
+			_permissioned_candidates: PolicyId,
+		) -> Result<AriadneParameters> {
+			unimplemented!("not used in tests")
+		}
+
+		async fn get_candidates(
+			&self,
+			_epoch: McEpochNumber,
+			_committee_candidate_address: MainchainAddress,
+		) -> Result<Vec<CandidateRegistrations>> {
+			self.query();
+			Ok(vec![])
+		}
+
+		async fn get_epoch_nonce(&self, epoch: McEpochNumber) -> Result<Option<EpochNonce>> {
+			self.query();
+			Ok(Some(EpochNonce(vec![epoch.0 as u8])))
+		}
+
+		async fn data_epoch(&self, for_epoch: McEpochNumber) -> Result<McEpochNumber> {
+			Ok(McEpochNumber(for_epoch.0.saturating_sub(2)))
+		}
+	}
+
+	#[tokio::test]
+	async fn stores_candidates_only_for_stable_data_epochs() {
+		let dir = tempfile::tempdir().unwrap();
+		let inner = Arc::new(MockDataSource::default());
+		let data_source = cached_data_source(dir.path(), inner.clone());
+
+		for _ in 0..2 {
+			for epoch in [McEpochNumber(3), McEpochNumber(4)] {
+				data_source.get_candidates(epoch, MainchainAddress::default()).await.unwrap();
+			}
+		}
+
+		assert_eq!(inner.queries(), 3);
+	}
+
+	#[tokio::test]
+	async fn stores_epoch_nonce_only_for_stable_epochs() {
+		let dir = tempfile::tempdir().unwrap();
+		let inner = Arc::new(MockDataSource::default());
+		let data_source = cached_data_source(dir.path(), inner.clone());
+
+		for _ in 0..2 {
+			let nonce = data_source.get_epoch_nonce(McEpochNumber(1)).await.unwrap();
+			assert_eq!(nonce, Some(EpochNonce(vec![1])));
+			data_source.get_epoch_nonce(McEpochNumber(2)).await.unwrap();
+		}
+
+		assert_eq!(inner.queries(), 3);
+	}
+}
+
+#[cfg(feature = "governed-map")]
+mod governed_map {
+	use super::*;
+	use sidechain_domain::byte_string::ByteString;
+	use sp_governed_map::{GovernedMapDataSource, MainChainScriptsV1};
+
+	#[async_trait::async_trait]
+	impl GovernedMapDataSource for MockDataSource {
+		async fn get_state_at_block(
+			&self,
+			_mc_block: McBlockHash,
+			_main_chain_scripts: MainChainScriptsV1,
+		) -> Result<BTreeMap<String, ByteString>> {
+			self.query();
+			Ok(BTreeMap::from([("key".to_string(), ByteString::from(vec![1]))]))
+		}
+
+		async fn get_mapping_changes(
+			&self,
+			_since_mc_block: Option<McBlockHash>,
+			_up_to_mc_block: McBlockHash,
+			_main_chain_scripts: MainChainScriptsV1,
+		) -> Result<Vec<(String, Option<ByteString>)>> {
+			self.query();
+			Ok(vec![("key".to_string(), None)])
+		}
+	}
+
+	#[tokio::test]
+	async fn stores_governed_map_data_only_for_stable_blocks() {
+		let dir = tempfile::tempdir().unwrap();
+		let inner = Arc::new(MockDataSource::default());
+		let data_source = cached_data_source(dir.path(), inner.clone());
+		let scripts = MainChainScriptsV1::default();
+
+		for _ in 0..2 {
+			for mc_block in [block(5).hash, block(6).hash] {
+				let state = data_source
+					.get_state_at_block(mc_block.clone(), scripts.clone())
+					.await
+					.unwrap();
+				assert_eq!(state.len(), 1);
+				data_source.get_mapping_changes(None, mc_block, scripts.clone()).await.unwrap();
+			}
+		}
+
+		assert_eq!(inner.queries(), 6);
+	}
+}
+
+#[cfg(feature = "bridge")]
+mod bridge {
+	use super::*;
+	use sp_partner_chains_bridge::{
+		BridgeDataCheckpoint, BridgeTransferV1, MainChainScripts, TokenBridgeDataSource,
+	};
+
+	#[async_trait::async_trait]
+	impl TokenBridgeDataSource<Vec<u8>> for MockDataSource {
+		async fn get_transfers(
+			&self,
+			_main_chain_scripts: MainChainScripts,
+			_data_checkpoint: BridgeDataCheckpoint,
+			_max_transfers: u32,
+			_current_mc_block: McBlockHash,
+		) -> Result<(Vec<BridgeTransferV1<Vec<u8>>>, BridgeDataCheckpoint)> {
+			self.query();
+			Ok((vec![], BridgeDataCheckpoint::Block(McBlockNumber(5))))
+		}
+	}
+
+	#[tokio::test]
+	async fn stores_transfers_only_up_to_stable_blocks() {
+		let dir = tempfile::tempdir().unwrap();
+		let inner = Arc::new(MockDataSource::default());
+		let data_source = cached_data_source(dir.path(), inner.clone());
+		let checkpoint = BridgeDataCheckpoint::Block(McBlockNumber(0));
+
+		for _ in 0..2 {
+			for mc_block in [block(5).hash, block(6).hash] {
+				let (_, new_checkpoint) = data_source
+					.get_transfers(MainChainScripts::default(), checkpoint.clone(), 10, mc_block)
+					.await
+					.unwrap();
+				assert_eq!(new_checkpoint, BridgeDataCheckpoint::Block(McBlockNumber(5)));
+			}
+		}
+
+		assert_eq!(inner.queries(), 3);
+	}
+}
+
+#[cfg(feature = "block-participation")]
+mod stake_distribution {
+	use super::*;
+	use sp_block_participation::inherent_data::BlockParticipationDataSource;
+
+	#[async_trait::async_trait]
+	impl BlockParticipationDataSource for MockDataSource {
+		async fn get_stake_pool_delegation_distribution_for_pools(
+			&self,
+			_epoch: McEpochNumber,
+			_pool_hashes: &[MainchainKeyHash],
+		) -> Result<StakeDistribution> {
+			self.query();
+			Ok(StakeDistribution::default())
+		}
+	}
+
+	#[tokio::test]
+	async fn stores_stake_distribution_only_for_stable_epochs() {
+		let dir = tempfile::tempdir().unwrap();
+		let inner = Arc::new(MockDataSource::default());
+		let data_source = cached_data_source(dir.path(), inner.clone());
+		let pools = [MainchainKeyHash([1; 28])];
+
+		for _ in 0..2 {
+			for epoch in [McEpochNumber(1), McEpochNumber(2)] {
+				data_source
+					.get_stake_pool_delegation_distribution_for_pools(epoch, &pools)
+					.await
+					.unwrap();
+			}
+		}
+
+		assert_eq!(inner.queries(), 3);
+	}
+}
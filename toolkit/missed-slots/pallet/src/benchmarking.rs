@@ -0,0 +1,100 @@
+#![cfg(feature = "runtime-benchmarks")]
+//! Benchmarking setup for pallet-missed-slots
+//!
+//! ## Running benchmarks
+//!
+//! Benchmarks process a block after the maximum number of missed slots while removing statistics of a no longer
+//! retained epoch. To benchmark this pallet, the PC Builder should define a `BenchmarkHelper` type implementing
+//! the [BenchmarkHelper] trait. For a runtime using [crate::RoundRobinFromCurrentCommittee], it should make sure
+//! that the current committee is not empty:
+//!
+//! ```rust,ignore
+//! pub struct MyBenchmarkHelper;
+//!
+//! impl pallet_missed_slots::benchmarking::BenchmarkHelper<Runtime> for MyBenchmarkHelper {
+//!     fn set_up_slot_assignment() {
+//!         pallet_session_validator_management::CurrentCommittee::<Runtime>::mutate(|info| {
+//!             if info.committee.is_empty() {
+//!                 info.committee.try_push(some_committee_member()).expect("committee is not full")
+//!             }
+//!         });
+//!     }
+//!
+//!     fn block_producer(i: u32) -> BlockAuthor {
+//!         let mut key = [0; 33];
+//!         key[..4].copy_from_slice(&i.to_le_bytes());
+//!         BlockAuthor::ProBono(ecdsa::Public::from_raw(key).into())
+//!     }
+//! }
+//! ```
+//!
+//! Assuming that the runtime crate uses the feature flag `runtime-benchmarks` to enable benchmarking features,
+//! this helper should be then added to the pallet's config:
+//! ```rust,ignore
+//! #[cfg(feature = "runtime-benchmarks")]
+//! type BenchmarkHelper = MyBenchmarkHelper;
+//! ```
+//! and the pallet's own `runtime-benchmarks` feature should be enabled and the pallet should be included in the
+//! runtime's benchmarks list:
+//! ```rust, ignore
+//! define_benchmarks!(
+//!     ...,
+//!        [pallet_missed_slots, MissedSlots]
+//! )
+//! ```
+//!
+//! Afterwards, the pallet can be benchmarked using Polkadot SDK's [omini-bencher](https://github.com/paritytech/polkadot-sdk/tree/master/substrate/utils/frame/omni-bencher).
+
+use super::*;
+use frame_benchmarking::v2::*;
+use frame_support::traits::Get;
+use frame_system::pallet_prelude::BlockNumberFor;
+use sidechain_domain::ScEpochNumber;
+use sp_consensus_slots::Slot;
+use sp_missed_slots::SlotStats;
+
+/// Helper trait for setting up the runtime for benchmarks
+pub trait BenchmarkHelper<T: Config> {
+	/// Should make [Config::SlotAssignment] assign every slot to some block producer
+	fn set_up_slot_assignment();
+	/// Should return a different block producer for each `i`
+	fn block_producer(i: u32) -> T::BlockProducerId;
+}
+
+#[benchmarks]
+mod benchmarks {
+	use super::*;
+
+	// `m` slots were missed since the previous block and `p` statistics entries of an epoch that is not retained
+	// anymore are removed
+	#[benchmark]
+	fn on_initialize(
+		m: Linear<0, { T::MaxMissedSlotsPerBlock::get() }>,
+		p: Linear<0, { T::MaxPrunedEntriesPerBlock::get() }>,
+	) {
+		T::BenchmarkHelper::set_up_slot_assignment();
+
+		let epoch = ScEpochNumber(1000);
+		let pruned_epoch = ScEpochNumber(epoch.0 - u64::from(T::EpochsToKeep::get()));
+		for i in 0..p {
+			SlotStatistics::<T>::insert(
+				pruned_epoch,
+				T::BenchmarkHelper::block_producer(i),
+				SlotStats { assigned: 1, missed: 1 },
+			);
+		}
+		NextEpochToPrune::<T>::put(pruned_epoch);
+
+		let slot = Slot::from(1_000_000);
+		LastProcessed::<T>::put((epoch, Slot::from(*slot - u64::from(m) - 1)));
+
+		#[block]
+		{
+			Pallet::<T>::process_block(BlockNumberFor::<T>::from(1u32), slot, epoch);
+		}
+
+		assert_eq!(LastProcessed::<T>::get(), Some((epoch, slot)));
+	}
+
+	impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
+}
@@ -0,0 +1,335 @@
+//! A Substrate pallet counting slots assigned to and missed by Partner Chain committee members.
+//!
+//! ## Purpose of this pallet
+//!
+//! `pallet_block_production_log` records authors of produced blocks, but it does not track which committee
+//! members were expected to produce a block in a slot and failed to do so. This pallet keeps, for each
+//! Partner Chain epoch, the number of slots assigned to each block producer and the number of these slots
+//! that were missed, ie. in which no block was produced.
+//!
+//! The statistics are updated at initialization of every block: the current slot is counted as assigned
+//! to (and produced by) its author, and all slots since the previous block are counted as assigned to
+//! and missed by their expected authors. Statistics are kept for the last [Config::EpochsToKeep] epochs.
+//! Statistics of older epochs are removed gradually, at most [Config::MaxPrunedEntriesPerBlock] entries
+//! in each block.
+//!
+//! Block producers that missed at least [Config::OfflineThreshold] of their slots in the retained epochs are
+//! considered chronically offline. Partner Chains can use [Pallet::is_chronically_offline] to deprioritize
+//! such candidates during committee selection, eg. with `authority_selection_inherents::DeprioritizeCandidates`.
+//!
+//! ## Usage - PC Builder
+//!
+//! ### Adding to the runtime
+//!
+//! An example configuration for a runtime using Aura consensus and Partner Chain toolkit's session management
+//! pallet might look like this:
+//!
+//! ```rust,ignore
+//! parameter_types! {
+//!     pub const MaxMissedSlotsPerBlock: u32 = 1000;
+//!     pub const MissedSlotsEpochsToKeep: u32 = 3;
+//!     pub const MaxPrunedEntriesPerBlock: u32 = 100;
+//!     pub const OfflineThreshold: Perbill = Perbill::from_percent(50);
+//! }
+//!
+//! impl pallet_missed_slots::Config for Runtime {
+//!     type BlockProducerId = BlockAuthor;
+//!     type SlotAssignment = RoundRobinFromCurrentCommittee<Runtime>;
+//!     type MaxMissedSlotsPerBlock = MaxMissedSlotsPerBlock;
+//!     type EpochsToKeep = MissedSlotsEpochsToKeep;
+//!     type MaxPrunedEntriesPerBlock = MaxPrunedEntriesPerBlock;
+//!     type OfflineThreshold = OfflineThreshold;
+//!     type WeightInfo = pallet_missed_slots::weights::SubstrateWeight<Runtime>;
+//!     #[cfg(feature = "runtime-benchmarks")]
+//!     type BenchmarkHelper = MissedSlotsBenchmarkHelper;
+//!
+//!     fn current_slot() -> Slot {
+//!         pallet_aura::CurrentSlot::<Runtime>::get()
+//!     }
+//!
+//!     fn current_epoch_number() -> ScEpochNumber {
+//!         Sidechain::current_epoch_number()
+//!     }
+//! }
+//! ```
+//!
+//! [RoundRobinFromCurrentCommittee] assigns slots to the members of the current committee of
+//! `pallet_session_validator_management` in the same order as Aura does. Note that slots missed before
+//! an epoch change are assigned and counted using the committee and epoch of the first block after the change.
+//!
+//! [Config::MaxMissedSlotsPerBlock] limits the number of missed slots processed in a single block. If more slots
+//! were missed, eg. because the chain was stalled, only the most recent ones are counted.
+//!
+//! [Config::MaxPrunedEntriesPerBlock] limits the number of statistics entries of no longer retained epochs
+//! removed in a single block. It should be set so that statistics of an epoch are removed well before the next
+//! epoch ends, ie. to at least the committee size divided by the number of blocks in an epoch.
+//!
+//! #### Support for adding to a running chain
+//!
+//! The pallet can be added to an already live chain. Counting starts in the first block produced with the pallet.
+//!
+//! ### Exposing the statistics
+//!
+//! The runtime should implement `sp_missed_slots::MissedSlotsApi` using [Pallet::slot_stats] and
+//! [Pallet::offline_block_producers]. `pallet_missed_slots_rpc` exposes this API through Json RPC.
+//!
+//! ## Usage - PC user
+//!
+//! This pallet does not expose any user-facing functionalities.
+//!
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![deny(missing_docs)]
+
+pub mod weights;
+
+/// Pallet benchmarking code
+#[cfg(feature = "runtime-benchmarks")]
+pub mod benchmarking;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+use core::marker::PhantomData;
+
+pub use pallet::*;
+pub use weights::WeightInfo;
+
+/// Source of the block producer expected to produce a block in a slot
+pub trait SlotAssignment<BlockProducerId> {
+	/// Returns the block producer expected to produce a block in `slot`
+	fn expected_author(slot: sp_consensus_slots::Slot) -> Option<BlockProducerId>;
+}
+
+/// [SlotAssignment] implementation assigning slots to the current committee of
+/// `pallet_session_validator_management` in round-robin fashion, as done by Aura.
+pub struct RoundRobinFromCurrentCommittee<T>(PhantomData<T>);
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+	use sidechain_domain::ScEpochNumber;
+	use sp_consensus_slots::Slot;
+	use sp_missed_slots::SlotStats;
+	use sp_runtime::{Perbill, traits::Member};
+	use sp_std::{collections::btree_set::BTreeSet, vec::Vec};
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// ID type that can represent any block producer in the network
+		type BlockProducerId: Member + Parameter + MaxEncodedLen + Ord;
+
+		/// Source of block producers expected to produce blocks in given slots
+		type SlotAssignment: SlotAssignment<Self::BlockProducerId>;
+
+		/// Maximum number of missed slots processed in a single block
+		#[pallet::constant]
+		type MaxMissedSlotsPerBlock: Get<u32>;
+
+		/// Number of epochs, including the current one, for which the statistics are kept
+		#[pallet::constant]
+		type EpochsToKeep: Get<u32>;
+
+		/// Maximum number of statistics entries of no longer retained epochs removed in a single block
+		#[pallet::constant]
+		type MaxPrunedEntriesPerBlock: Get<u32>;
+
+		/// Share of missed slots in the retained epochs at which a block producer is considered chronically offline
+		#[pallet::constant]
+		type OfflineThreshold: Get<Perbill>;
+
+		/// Weight functions of the pallet
+		type WeightInfo: WeightInfo;
+
+		/// Helper for setting up the runtime for benchmarks
+		#[cfg(feature = "runtime-benchmarks")]
+		type BenchmarkHelper: benchmarking::BenchmarkHelper<Self>;
+
+		/// Returns the current slot
+		fn current_slot() -> Slot;
+
+		/// Returns the current Partner Chain epoch number
+		fn current_epoch_number() -> ScEpochNumber;
+	}
+
+	/// Epoch and slot of the last block processed by the pallet
+	#[pallet::storage]
+	pub type LastProcessed<T: Config> = StorageValue<_, (ScEpochNumber, Slot), OptionQuery>;
+
+	/// Oldest epoch whose statistics may not have been removed yet
+	#[pallet::storage]
+	pub type NextEpochToPrune<T: Config> = StorageValue<_, ScEpochNumber, ValueQuery>;
+
+	/// Cursor for continuing removal of statistics of [NextEpochToPrune] that was not completed in a single block
+	#[pallet::storage]
+	#[pallet::unbounded]
+	pub type PruningCursor<T: Config> = StorageValue<_, Vec<u8>, OptionQuery>;
+
+	/// Slot statistics of block producers by epoch
+	#[pallet::storage]
+	pub type SlotStatistics<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		ScEpochNumber,
+		Blake2_128Concat,
+		T::BlockProducerId,
+		SlotStats,
+		ValueQuery,
+	>;
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		/// Block initialization hook that counts the current slot and slots missed since the previous block
+		fn on_initialize(block: BlockNumberFor<T>) -> Weight {
+			Self::process_block(block, T::current_slot(), T::current_epoch_number())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		pub(crate) fn process_block(
+			block: BlockNumberFor<T>,
+			slot: Slot,
+			epoch: ScEpochNumber,
+		) -> Weight {
+			let mut missed_slots = 0;
+
+			if let Some((_, last_slot)) = LastProcessed::<T>::get() {
+				if slot <= last_slot {
+					log::warn!(
+						"🕳️ Missed slots update skipped - block {block:?} slot {slot:?} is not after the last processed slot {last_slot:?}"
+					);
+					return T::DbWeight::get().reads(1);
+				}
+				let skipped = *slot - *last_slot - 1;
+				let to_process = skipped.min(T::MaxMissedSlotsPerBlock::get().into());
+				if to_process < skipped {
+					log::warn!(
+						"🕳️ Only {to_process} of {skipped} slots missed before block {block:?} are counted"
+					);
+				}
+				for missed_slot in (*slot - to_process)..*slot {
+					if let Some(author) = T::SlotAssignment::expected_author(missed_slot.into()) {
+						log::debug!("🕳️ {author:?} missed slot {missed_slot}");
+						Self::record(epoch, &author, SlotStats { assigned: 1, missed: 1 });
+						missed_slots += 1;
+					}
+				}
+			} else {
+				NextEpochToPrune::<T>::put(epoch);
+			}
+
+			if let Some(author) = T::SlotAssignment::expected_author(slot) {
+				Self::record(epoch, &author, SlotStats { assigned: 1, missed: 0 });
+			}
+			LastProcessed::<T>::put((epoch, slot));
+			let pruned_entries = Self::prune(epoch);
+
+			T::WeightInfo::on_initialize(missed_slots, pruned_entries)
+		}
+
+		/// Returns slot statistics of all block producers that were assigned slots in `epoch`
+		pub fn slot_stats(epoch: ScEpochNumber) -> Vec<(T::BlockProducerId, SlotStats)> {
+			SlotStatistics::<T>::iter_prefix(epoch).collect()
+		}
+
+		/// Returns slot statistics of `block_producer` summed over all retained epochs
+		pub fn retained_slot_stats(block_producer: &T::BlockProducerId) -> SlotStats {
+			let mut total = SlotStats::default();
+			for epoch in Self::retained_epochs() {
+				total.add(&SlotStatistics::<T>::get(epoch, block_producer));
+			}
+			total
+		}
+
+		/// Returns true if `block_producer` missed at least [Config::OfflineThreshold] of its slots
+		/// in the retained epochs
+		pub fn is_chronically_offline(block_producer: &T::BlockProducerId) -> bool {
+			Self::is_offline(&Self::retained_slot_stats(block_producer))
+		}
+
+		/// Returns all block producers that are chronically offline
+		pub fn offline_block_producers() -> Vec<T::BlockProducerId> {
+			let block_producers: BTreeSet<T::BlockProducerId> = Self::retained_epochs()
+				.flat_map(|epoch| SlotStatistics::<T>::iter_key_prefix(epoch))
+				.collect();
+			block_producers.into_iter().filter(Self::is_chronically_offline).collect()
+		}
+
+		fn is_offline(stats: &SlotStats) -> bool {
+			stats.assigned > 0
+				&& Perbill::from_rational(stats.missed, stats.assigned)
+					>= T::OfflineThreshold::get()
+		}
+
+		fn retained_epochs() -> impl Iterator<Item = ScEpochNumber> {
+			let current_epoch = LastProcessed::<T>::get().map(|(epoch, _)| epoch.0);
+			current_epoch.into_iter().flat_map(|current_epoch| {
+				let oldest = (current_epoch + 1).saturating_sub(T::EpochsToKeep::get().into());
+				(oldest..=current_epoch).map(ScEpochNumber)
+			})
+		}
+
+		fn record(epoch: ScEpochNumber, author: &T::BlockProducerId, stats: SlotStats) {
+			SlotStatistics::<T>::mutate(epoch, author, |total| total.add(&stats));
+		}
+
+		/// Removes statistics of epochs that are not retained at `epoch`, continuing from [NextEpochToPrune].
+		///
+		/// At most [Config::MaxPrunedEntriesPerBlock] entries are removed, with each visited epoch counted as at
+		/// least one entry. Removal of an epoch that was not completed is continued in the next block from
+		/// [PruningCursor]. Returns the number of entries counted against the limit.
+		fn prune(epoch: ScEpochNumber) -> u32 {
+			let oldest_to_keep = (epoch.0 + 1).saturating_sub(T::EpochsToKeep::get().into());
+			let limit = T::MaxPrunedEntriesPerBlock::get();
+			let mut next_epoch = NextEpochToPrune::<T>::get();
+			let mut cursor = PruningCursor::<T>::get();
+			let mut pruned = 0;
+			while next_epoch.0 < oldest_to_keep && pruned < limit {
+				let result = SlotStatistics::<T>::clear_prefix(
+					next_epoch,
+					limit - pruned,
+					cursor.as_deref(),
+				);
+				pruned = pruned.saturating_add(result.loops.max(1));
+				cursor = result.maybe_cursor;
+				if cursor.is_some() {
+					break;
+				}
+				next_epoch = ScEpochNumber(next_epoch.0 + 1);
+			}
+			if pruned > 0 {
+				NextEpochToPrune::<T>::put(next_epoch);
+				PruningCursor::<T>::set(cursor);
+			}
+			pruned
+		}
+	}
+}
+
+mod source_impls {
+	use super::*;
+	use pallet_session_validator_management as psvm;
+
+	impl<BlockProducerId, T> SlotAssignment<BlockProducerId> for RoundRobinFromCurrentCommittee<T>
+	where
+		T: psvm::Config,
+		psvm::CommitteeMemberOf<T>: Into<BlockProducerId>,
+	{
+		fn expected_author(slot: sp_consensus_slots::Slot) -> Option<BlockProducerId> {
+			let committee = psvm::Pallet::<T>::current_committee_storage().committee;
+			if committee.is_empty() {
+				return None;
+			}
+			let index = *slot % committee.len() as u64;
+			Some(committee.get(index as usize)?.clone().into())
+		}
+	}
+}
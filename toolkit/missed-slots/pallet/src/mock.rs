@@ -0,0 +1,146 @@
+use frame_support::parameter_types;
+use frame_support::sp_runtime::{
+	BuildStorage, Perbill,
+	traits::{BlakeTwo256, IdentityLookup},
+};
+use frame_support::traits::{ConstU16, ConstU32, ConstU64};
+use sidechain_domain::ScEpochNumber;
+use sp_consensus_slots::Slot;
+use sp_core::H256;
+
+type AccountId = u32;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+pub(crate) type BlockProducerId = u32;
+
+#[frame_support::pallet]
+pub mod mock_pallet {
+	use crate::mock::BlockProducerId;
+	use frame_support::pallet_prelude::*;
+	use sidechain_domain::ScEpochNumber;
+	use sp_consensus_slots::Slot;
+	use sp_std::vec::Vec;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {}
+
+	#[pallet::storage]
+	#[pallet::unbounded]
+	pub type Committee<T: Config> = StorageValue<_, Vec<BlockProducerId>, ValueQuery>;
+
+	#[pallet::storage]
+	pub type CurrentSlot<T: Config> = StorageValue<_, Slot, ValueQuery>;
+
+	#[pallet::storage]
+	pub type CurrentEpoch<T: Config> = StorageValue<_, ScEpochNumber, ValueQuery>;
+
+	impl<T: Config> crate::SlotAssignment<BlockProducerId> for Pallet<T> {
+		fn expected_author(slot: Slot) -> Option<BlockProducerId> {
+			let committee = Committee::<T>::get();
+			committee.get(*slot as usize % committee.len().max(1)).cloned()
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		pub fn set_committee(committee: Vec<BlockProducerId>) {
+			Committee::<T>::put(committee)
+		}
+		pub fn set_slot_and_epoch(slot: u64, epoch: u64) {
+			CurrentSlot::<T>::put(Slot::from(slot));
+			CurrentEpoch::<T>::put(ScEpochNumber(epoch));
+		}
+	}
+}
+
+frame_support::construct_runtime!(
+	pub enum Test {
+		System: frame_system,
+		MissedSlots: crate::pallet,
+		Mock: mock_pallet,
+	}
+);
+
+impl frame_system::Config for Test {
+	type BaseCallFilter = frame_support::traits::Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeCall = RuntimeCall;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type RuntimeEvent = RuntimeEvent;
+	type BlockHashCount = ConstU64<250>;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type ExtensionsWeightInfo = ();
+	type SS58Prefix = ConstU16<42>;
+	type OnSetCode = ();
+	type MaxConsumers = ConstU32<16>;
+	type Nonce = u64;
+	type Block = Block;
+	type RuntimeTask = RuntimeTask;
+	type SingleBlockMigrations = ();
+	type MultiBlockMigrator = ();
+	type PreInherents = ();
+	type PostInherents = ();
+	type PostTransactions = ();
+}
+
+parameter_types! {
+	pub const OfflineThreshold: Perbill = Perbill::from_percent(50);
+}
+
+pub(crate) const MAX_MISSED_SLOTS_PER_BLOCK: u32 = 10;
+pub(crate) const EPOCHS_TO_KEEP: u32 = 2;
+pub(crate) const MAX_PRUNED_ENTRIES_PER_BLOCK: u32 = 10;
+
+#[cfg(feature = "runtime-benchmarks")]
+pub struct MockBenchmarkHelper;
+
+#[cfg(feature = "runtime-benchmarks")]
+impl crate::benchmarking::BenchmarkHelper<Test> for MockBenchmarkHelper {
+	fn set_up_slot_assignment() {
+		Mock::set_committee(vec![1, 2, 3]);
+	}
+
+	fn block_producer(i: u32) -> BlockProducerId {
+		i
+	}
+}
+
+impl crate::pallet::Config for Test {
+	type BlockProducerId = BlockProducerId;
+	type SlotAssignment = Mock;
+	type MaxMissedSlotsPerBlock = ConstU32<MAX_MISSED_SLOTS_PER_BLOCK>;
+	type EpochsToKeep = ConstU32<EPOCHS_TO_KEEP>;
+	type MaxPrunedEntriesPerBlock = ConstU32<MAX_PRUNED_ENTRIES_PER_BLOCK>;
+	type OfflineThreshold = OfflineThreshold;
+	type WeightInfo = ();
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = MockBenchmarkHelper;
+
+	fn current_slot() -> Slot {
+		mock_pallet::CurrentSlot::<Test>::get()
+	}
+
+	fn current_epoch_number() -> ScEpochNumber {
+		mock_pallet::CurrentEpoch::<Test>::get()
+	}
+}
+
+impl mock_pallet::Config for Test {}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let storage = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+	sp_io::TestExternalities::new(storage)
+}
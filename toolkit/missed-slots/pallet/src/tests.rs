@@ -0,0 +1,157 @@
+use super::*;
+use frame_support::traits::Hooks;
+use mock::*;
+use sidechain_domain::ScEpochNumber;
+use sp_consensus_slots::Slot;
+use sp_missed_slots::SlotStats;
+
+fn produce_block(slot: u64, epoch: u64) {
+	Mock::set_slot_and_epoch(slot, epoch);
+	MissedSlots::on_initialize(1);
+}
+
+fn stats(epoch: u64) -> Vec<(BlockProducerId, SlotStats)> {
+	let mut stats = MissedSlots::slot_stats(ScEpochNumber(epoch));
+	stats.sort_by_key(|(block_producer, _)| *block_producer);
+	stats
+}
+
+fn stats_of(assigned: u32, missed: u32) -> SlotStats {
+	SlotStats { assigned, missed }
+}
+
+#[test]
+fn first_block_counts_only_its_own_slot() {
+	new_test_ext().execute_with(|| {
+		Mock::set_committee(vec![1, 2, 3]);
+
+		produce_block(10, 1);
+
+		assert_eq!(stats(1), vec![(2, stats_of(1, 0))]);
+		assert_eq!(LastProcessed::<Test>::get(), Some((ScEpochNumber(1), Slot::from(10))));
+	});
+}
+
+#[test]
+fn counts_slots_missed_since_previous_block() {
+	new_test_ext().execute_with(|| {
+		Mock::set_committee(vec![1, 2, 3]);
+
+		produce_block(10, 1);
+		produce_block(11, 1);
+		produce_block(16, 1);
+
+		assert_eq!(stats(1), vec![(1, stats_of(2, 2)), (2, stats_of(3, 1)), (3, stats_of(2, 1))]);
+	});
+}
+
+#[test]
+fn counts_at_most_max_missed_slots_per_block() {
+	new_test_ext().execute_with(|| {
+		Mock::set_committee(vec![1]);
+
+		produce_block(10, 1);
+		produce_block(100, 1);
+
+		assert_eq!(
+			stats(1),
+			vec![(1, stats_of(2 + MAX_MISSED_SLOTS_PER_BLOCK, MAX_MISSED_SLOTS_PER_BLOCK))]
+		);
+	});
+}
+
+#[test]
+fn skips_block_with_slot_not_after_last_processed_one() {
+	new_test_ext().execute_with(|| {
+		Mock::set_committee(vec![1, 2, 3]);
+
+		produce_block(10, 1);
+		produce_block(10, 1);
+
+		assert_eq!(stats(1), vec![(2, stats_of(1, 0))]);
+	});
+}
+
+#[test]
+fn prunes_statistics_of_epochs_no_longer_retained() {
+	new_test_ext().execute_with(|| {
+		Mock::set_committee(vec![1]);
+
+		produce_block(10, 1);
+		produce_block(20, 2);
+		assert_eq!(stats(1), vec![(1, stats_of(1, 0))]);
+
+		produce_block(30, 3);
+		assert_eq!(stats(1), vec![]);
+		assert_eq!(stats(2), vec![(1, stats_of(10, 9))]);
+
+		produce_block(100, 10);
+		assert_eq!(stats(2), vec![]);
+		assert_eq!(stats(3), vec![]);
+		assert_eq!(stats(10), vec![(1, stats_of(11, 10))]);
+	});
+}
+
+#[test]
+fn prunes_at_most_max_pruned_entries_per_block() {
+	let committee: Vec<BlockProducerId> = (0..2 * MAX_PRUNED_ENTRIES_PER_BLOCK).collect();
+	let mut ext = new_test_ext();
+	ext.execute_with(|| {
+		Mock::set_committee(committee.clone());
+		// every member is assigned a slot in epoch 1
+		for slot in committee.iter().map(|member| u64::from(*member) + 20) {
+			produce_block(slot, 1);
+		}
+		assert_eq!(stats(1).len(), committee.len());
+	});
+	// removal is limited only for entries in the backend, not in the overlay
+	ext.commit_all().unwrap();
+
+	ext.execute_with(|| {
+		produce_block(100, 3);
+		assert_eq!(stats(1).len(), committee.len() - MAX_PRUNED_ENTRIES_PER_BLOCK as usize);
+		assert_eq!(NextEpochToPrune::<Test>::get(), ScEpochNumber(1));
+		assert!(PruningCursor::<Test>::get().is_some());
+
+		produce_block(101, 3);
+		assert_eq!(stats(1), vec![]);
+		assert_eq!(NextEpochToPrune::<Test>::get(), ScEpochNumber(2));
+		assert_eq!(PruningCursor::<Test>::get(), None);
+	});
+}
+
+#[test]
+fn block_producer_is_chronically_offline_when_missing_threshold_share_of_slots() {
+	new_test_ext().execute_with(|| {
+		Mock::set_committee(vec![1, 2]);
+
+		// slots 10 and 12 produced by 1, slot 11 missed by 2
+		produce_block(10, 1);
+		produce_block(12, 1);
+		// slot 14 produced by 1, slots 13 and 15 missed by 2, slot 16 produced by 1
+		produce_block(14, 2);
+		produce_block(16, 2);
+
+		assert_eq!(MissedSlots::retained_slot_stats(&1), stats_of(4, 0));
+		assert_eq!(MissedSlots::retained_slot_stats(&2), stats_of(3, 3));
+		assert!(!MissedSlots::is_chronically_offline(&1));
+		assert!(MissedSlots::is_chronically_offline(&2));
+		assert!(!MissedSlots::is_chronically_offline(&3));
+		assert_eq!(MissedSlots::offline_block_producers(), vec![2]);
+	});
+}
+
+#[test]
+fn only_retained_epochs_are_used_to_determine_offline_block_producers() {
+	new_test_ext().execute_with(|| {
+		Mock::set_committee(vec![1, 2]);
+
+		produce_block(10, 1);
+		produce_block(12, 1);
+		produce_block(13, 3);
+		produce_block(14, 3);
+
+		assert_eq!(MissedSlots::retained_slot_stats(&2), stats_of(1, 0));
+		assert_eq!(MissedSlots::offline_block_producers(), vec![]);
+	});
+}
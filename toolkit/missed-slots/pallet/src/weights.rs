@@ -0,0 +1,73 @@
+//! Weights for pallet_missed_slots
+//!
+//! These weights are conservative estimates, to be replaced by the results of running the pallet's
+//! benchmarks. The cost of block initialization is dominated by the storage accesses for each missed slot
+//! and for each pruned statistics entry.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(missing_docs)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for pallet_missed_slots.
+pub trait WeightInfo {
+	fn on_initialize(m: u32, p: u32, ) -> Weight;
+}
+
+/// Weights for pallet_missed_slots using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: `MissedSlots::LastProcessed` (r:1 w:1)
+	/// Proof: `MissedSlots::LastProcessed` (`max_values`: Some(1), `max_size`: Some(16), added: 511, mode: `MaxEncodedLen`)
+	/// Storage: `MissedSlots::NextEpochToPrune` (r:1 w:1)
+	/// Proof: `MissedSlots::NextEpochToPrune` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `MissedSlots::PruningCursor` (r:1 w:1)
+	/// Proof: `MissedSlots::PruningCursor` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `SessionCommitteeManagement::CurrentCommittee` (r:1 w:0)
+	/// Proof: `SessionCommitteeManagement::CurrentCommittee` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `MissedSlots::SlotStatistics` (r:1 w:1)
+	/// Proof: `MissedSlots::SlotStatistics` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// The range of component `m` is `[0, 1000]`.
+	/// The range of component `p` is `[0, 100]`.
+	fn on_initialize(m: u32, p: u32, ) -> Weight {
+		Weight::from_parts(20_000_000, 10_000)
+			.saturating_add(Weight::from_parts(10_000_000, 5_000).saturating_mul(m.into()))
+			.saturating_add(Weight::from_parts(5_000_000, 0).saturating_mul(p.into()))
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(m.into())))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(p.into())))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(m.into())))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(p.into())))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	/// Storage: `MissedSlots::LastProcessed` (r:1 w:1)
+	/// Proof: `MissedSlots::LastProcessed` (`max_values`: Some(1), `max_size`: Some(16), added: 511, mode: `MaxEncodedLen`)
+	/// Storage: `MissedSlots::NextEpochToPrune` (r:1 w:1)
+	/// Proof: `MissedSlots::NextEpochToPrune` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `MissedSlots::PruningCursor` (r:1 w:1)
+	/// Proof: `MissedSlots::PruningCursor` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `SessionCommitteeManagement::CurrentCommittee` (r:1 w:0)
+	/// Proof: `SessionCommitteeManagement::CurrentCommittee` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `MissedSlots::SlotStatistics` (r:1 w:1)
+	/// Proof: `MissedSlots::SlotStatistics` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// The range of component `m` is `[0, 1000]`.
+	/// The range of component `p` is `[0, 100]`.
+	fn on_initialize(m: u32, p: u32, ) -> Weight {
+		Weight::from_parts(20_000_000, 10_000)
+			.saturating_add(Weight::from_parts(10_000_000, 5_000).saturating_mul(m.into()))
+			.saturating_add(Weight::from_parts(5_000_000, 0).saturating_mul(p.into()))
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().reads((2_u64).saturating_mul(m.into())))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(p.into())))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(m.into())))
+			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(p.into())))
+	}
+}
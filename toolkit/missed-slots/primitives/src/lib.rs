@@ -0,0 +1,60 @@
+//! Primitives of the missed slots feature.
+//!
+//! The missed slots feature counts slots assigned to each committee member of a Partner Chain and slots
+//! in which they failed to produce a block, per Partner Chain epoch. This crate contains the [SlotStats]
+//! type and the [MissedSlotsApi] runtime API exposing these statistics to the node.
+//! The feature is implemented by `pallet_missed_slots`.
+#![cfg_attr(not(feature = "std"), no_std)]
+#![deny(missing_docs)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use parity_scale_codec::{Codec, Decode, DecodeWithMemTracking, Encode, MaxEncodedLen};
+use scale_info::TypeInfo;
+use sidechain_domain::ScEpochNumber;
+
+/// Slot statistics of a single block producer in a single Partner Chain epoch
+#[derive(
+	Clone,
+	Copy,
+	Debug,
+	Default,
+	PartialEq,
+	Eq,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub struct SlotStats {
+	/// Number of slots assigned to the block producer
+	pub assigned: u32,
+	/// Number of assigned slots in which the block producer did not produce a block
+	pub missed: u32,
+}
+
+impl SlotStats {
+	/// Number of assigned slots in which the block producer produced a block
+	pub fn produced(&self) -> u32 {
+		self.assigned.saturating_sub(self.missed)
+	}
+
+	/// Adds statistics of `other` to `self`
+	pub fn add(&mut self, other: &Self) {
+		self.assigned = self.assigned.saturating_add(other.assigned);
+		self.missed = self.missed.saturating_add(other.missed);
+	}
+}
+
+sp_api::decl_runtime_apis! {
+	/// Runtime API exposing slot statistics of block producers
+	pub trait MissedSlotsApi<BlockProducerId: Codec> {
+		/// Returns slot statistics of all block producers that were assigned slots in `epoch`.
+		/// Statistics are only available for the epochs retained by the runtime.
+		fn get_slot_stats(epoch: ScEpochNumber) -> Vec<(BlockProducerId, SlotStats)>;
+		/// Returns block producers that are considered chronically offline by the runtime
+		fn get_offline_block_producers() -> Vec<BlockProducerId>;
+	}
+}
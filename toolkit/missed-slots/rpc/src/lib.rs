@@ -0,0 +1,135 @@
+//! Crate providing Json RPC methods for the Missed Slots feature of the Partner Chains Toolkit
+//!
+//! ## Contents
+//!
+//! This crate provides the [MissedSlotsRpcApiServer] trait defining the JsonRPC methods related to
+//! slots missed by block producers and its concrete implementation [MissedSlotsRpc]. The following
+//! methods are provided:
+//! - `pc_getSlotStats`
+//! - `pc_getOfflineBlockProducers`
+//!
+//! ## Usage - PC Builders
+//!
+//! To use the Json RPC service defined in this crate, first make your runtime implement
+//! [sp_missed_slots::MissedSlotsApi]. Eg. assuming the pallet `MissedSlots` in your runtime uses
+//! `BlockAuthor` as the block producer ID type, the following should be included in your
+//! `impl_runtime_apis` block:
+//! ```rust, ignore
+//! impl sp_missed_slots::MissedSlotsApi<Block, BlockAuthor> for Runtime {
+//! 	fn get_slot_stats(epoch: ScEpochNumber) -> Vec<(BlockAuthor, SlotStats)> {
+//! 		MissedSlots::slot_stats(epoch)
+//! 	}
+//! 	fn get_offline_block_producers() -> Vec<BlockAuthor> {
+//! 		MissedSlots::offline_block_producers()
+//! 	}
+//! }
+//! ```
+//!
+//! Afterwards, the [MissedSlotsRpc] Json RPC service can be added into the Json RPC stack of your node, eg.:
+//!
+//! ```rust
+//! use jsonrpsee::RpcModule;
+//! use std::sync::Arc;
+//! use sp_missed_slots::*;
+//! use pallet_missed_slots_rpc::*;
+//!
+//! fn create_rpc<C, Block, BlockProducerId>(client: Arc<C>) -> Result<RpcModule<()>, Box<dyn std::error::Error>>
+//! where
+//!   C: Send + Sync + 'static,
+//!   Block: sp_runtime::traits::Block,
+//!   BlockProducerId: parity_scale_codec::Codec + serde::Serialize + Send + Sync + Clone + 'static,
+//!   C: sp_api::ProvideRuntimeApi<Block>,
+//!   C: sp_blockchain::HeaderBackend<Block>,
+//!   C::Api: MissedSlotsApi<Block, BlockProducerId>
+//! {
+//!     let mut module = RpcModule::new(());
+//!     module.merge(MissedSlotsRpc::new(client.clone()).into_rpc())?;
+//!     // other RPC modules
+//!     Ok(module)
+//! }
+//! ```
+#![deny(missing_docs)]
+use derive_new::new;
+use jsonrpsee::{
+	core::{RpcResult, async_trait},
+	proc_macros::rpc,
+	types::{ErrorObject, ErrorObjectOwned},
+};
+use parity_scale_codec::Codec;
+use serde::{Deserialize, Serialize};
+use sidechain_domain::ScEpochNumber;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_missed_slots::MissedSlotsApi;
+use sp_runtime::traits::Block as BlockT;
+use std::sync::Arc;
+
+/// Json RPC methods related to the Missed Slots feature of Partner Chains Toolkit
+#[rpc(client, server, namespace = "pc")]
+pub trait MissedSlotsRpcApi<BlockProducerId> {
+	/// Returns slot statistics of block producers in the given Partner Chain epoch, read from the storage
+	/// of the current tip. Only epochs retained by the runtime are available.
+	#[method(name = "getSlotStats")]
+	fn get_slot_stats(&self, epoch_number: u64) -> RpcResult<Vec<SlotStatsEntry<BlockProducerId>>>;
+
+	/// Returns block producers that are considered chronically offline at the current tip.
+	#[method(name = "getOfflineBlockProducers")]
+	fn get_offline_block_producers(&self) -> RpcResult<Vec<BlockProducerId>>;
+}
+
+/// Slot statistics of a single block producer
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlotStatsEntry<BlockProducerId> {
+	/// Block producer the statistics are for
+	pub block_producer: BlockProducerId,
+	/// Number of slots assigned to the block producer
+	pub assigned_slots: u32,
+	/// Number of assigned slots in which the block producer did not produce a block
+	pub missed_slots: u32,
+}
+
+/// Concrete implementation of [MissedSlotsRpcApiServer] that uses [MissedSlotsApi] for querying runtime storage.
+#[derive(new)]
+pub struct MissedSlotsRpc<C, Block, BlockProducerId> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<(Block, BlockProducerId)>,
+}
+
+#[async_trait]
+impl<C, Block, BlockProducerId> MissedSlotsRpcApiServer<BlockProducerId>
+	for MissedSlotsRpc<C, Block, BlockProducerId>
+where
+	Block: BlockT,
+	BlockProducerId: Codec + Serialize + Clone + Send + Sync + 'static,
+	C: Send + Sync + 'static,
+	C: ProvideRuntimeApi<Block>,
+	C: HeaderBackend<Block>,
+	C::Api: MissedSlotsApi<Block, BlockProducerId>,
+{
+	fn get_slot_stats(&self, epoch_number: u64) -> RpcResult<Vec<SlotStatsEntry<BlockProducerId>>> {
+		let api = self.client.runtime_api();
+		let best_block = self.client.info().best_hash;
+		let stats = api
+			.get_slot_stats(best_block, ScEpochNumber(epoch_number))
+			.map_err(error_object_from)?;
+		Ok(stats
+			.into_iter()
+			.map(|(block_producer, stats)| SlotStatsEntry {
+				block_producer,
+				assigned_slots: stats.assigned,
+				missed_slots: stats.missed,
+			})
+			.collect())
+	}
+
+	fn get_offline_block_producers(&self) -> RpcResult<Vec<BlockProducerId>> {
+		let api = self.client.runtime_api();
+		let best_block = self.client.info().best_hash;
+		api.get_offline_block_producers(best_block).map_err(error_object_from)
+	}
+}
+
+fn error_object_from<T: std::fmt::Debug>(err: T) -> ErrorObjectOwned {
+	ErrorObject::owned::<u8>(-1, format!("{err:?}"), None)
+}
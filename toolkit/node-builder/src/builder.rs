@@ -0,0 +1,384 @@
+use crate::diagnostics::{InherentDiagnosticsConfig, InherentDiagnosticsHandler};
+use crate::features::*;
+use crate::inherent_data::{CreateInherentDataConfig, ProposalCIDP, VerifierCIDP};
+use authority_selection_inherents::AuthoritySelectionDataSource;
+use jsonrpsee::RpcModule;
+use pallet_sidechain_rpc::{SidechainRpc, SidechainRpcApiServer, SidechainRpcDataSource};
+use sc_client_api::{AuxStore, BlockOf, UsageProvider};
+use sc_consensus::{BlockImport, DefaultImportQueue};
+use sc_consensus_aura::{ImportQueueParams, StartAuraParams};
+use sc_partner_chains_consensus_aura::deferred_import::DeferredImportConfig;
+use sc_partner_chains_consensus_aura::import_queue::{
+	EquivocationReporter, import_queue_with_deferred_import,
+	import_queue_with_inherent_check_failure_handler,
+};
+use sidechain_mc_hash::{McHashDataSource, McHashInherentDigest, monitor::McReferenceMonitor};
+use sp_api::{ApiExt, CallApiAt, ProvideRuntimeApi};
+use sp_block_builder::BlockBuilder as BlockBuilderApi;
+use sp_block_participation::inherent_data::BlockParticipationDataSource;
+use sp_blockchain::{HeaderBackend, HeaderMetadata};
+use sp_consensus::{Environment, Error as ConsensusError, Proposer, SelectChain, SyncOracle};
+use sp_consensus_aura::{
+	AuraApi,
+	sr25519::{AuthorityId as AuraId, AuthorityPair as AuraPair},
+};
+use sp_governed_map::GovernedMapDataSource;
+use sp_partner_chains_bridge::TokenBridgeDataSource;
+use sp_partner_chains_consensus_aura::block_proposal::PartnerChainsProposerFactory;
+use sp_runtime::traits::{Block as BlockT, NumberFor};
+use sp_sidechain::{
+	GetEpochDurationApi, GetEpochScheduleApi, GetGenesisUtxo, GetMcReferencePolicyApi,
+};
+use std::{error::Error, future::Future, sync::Arc};
+
+/// Builder wiring Partner Chains components of a node
+///
+/// The builder is created with the components required by every Partner Chain: the node's client,
+/// [CreateInherentDataConfig] and a main chain reference data source. Optional features are enabled
+/// using `with_*` methods, after which the builder can be used to create:
+/// - inherent data provider factories for block proposal and verification ([Self::proposal_cidp]
+///   and [Self::verifier_cidp])
+/// - the Aura import queue ([Self::import_queue])
+/// - the Aura authoring task ([Self::start_aura])
+/// - RPC modules of the enabled features ([Self::rpc_module])
+///
+/// The builder is cheap to clone.
+pub struct PartnerChainsNodeBuilder<Block: BlockT, C> {
+	config: CreateInherentDataConfig,
+	client: Arc<C>,
+	mc_hash_data_source: Arc<dyn McHashDataSource + Send + Sync>,
+	features: Vec<Arc<dyn PartnerChainsFeature<Block, C>>>,
+	deferred_import: DeferredImportConfig,
+	inherent_diagnostics: Option<InherentDiagnosticsConfig<Block>>,
+	mc_reference_monitor: Option<Arc<McReferenceMonitor>>,
+}
+
+impl<Block: BlockT, C> Clone for PartnerChainsNodeBuilder<Block, C> {
+	fn clone(&self) -> Self {
+		Self {
+			config: self.config.clone(),
+			client: self.client.clone(),
+			mc_hash_data_source: self.mc_hash_data_source.clone(),
+			features: self.features.clone(),
+			deferred_import: self.deferred_import,
+			inherent_diagnostics: self.inherent_diagnostics.clone(),
+			mc_reference_monitor: self.mc_reference_monitor.clone(),
+		}
+	}
+}
+
+impl<Block: BlockT, C: 'static> PartnerChainsNodeBuilder<Block, C> {
+	/// Creates a new [PartnerChainsNodeBuilder] with no optional features enabled
+	pub fn new(
+		config: CreateInherentDataConfig,
+		client: Arc<C>,
+		mc_hash_data_source: Arc<dyn McHashDataSource + Send + Sync>,
+	) -> Self {
+		Self {
+			config,
+			client,
+			mc_hash_data_source,
+			features: vec![],
+			deferred_import: DeferredImportConfig::disabled(),
+			inherent_diagnostics: None,
+			mc_reference_monitor: None,
+		}
+	}
+
+	/// Enables a custom [PartnerChainsFeature]
+	///
+	/// Inherent data providers of features are created in the order in which they were enabled.
+	pub fn with_feature(mut self, feature: impl PartnerChainsFeature<Block, C> + 'static) -> Self {
+		self.features.push(Arc::new(feature));
+		self
+	}
+
+	/// Enables the committee selection feature. See [CommitteeSelectionFeature].
+	pub fn with_committee_selection<AuthorityId, AuthorityKeys>(
+		self,
+		data_source: Arc<dyn AuthoritySelectionDataSource + Send + Sync>,
+	) -> Self
+	where
+		CommitteeSelectionFeature<AuthorityId, AuthorityKeys>: PartnerChainsFeature<Block, C>,
+		AuthorityId: 'static,
+		AuthorityKeys: 'static,
+	{
+		self.with_feature(CommitteeSelectionFeature::<AuthorityId, AuthorityKeys>::new(data_source))
+	}
+
+	/// Enables the block participation feature. See [BlockParticipationFeature].
+	pub fn with_block_participation<BlockProducer, Delegator>(
+		self,
+		data_source: Arc<dyn BlockParticipationDataSource + Send + Sync>,
+	) -> Self
+	where
+		BlockParticipationFeature<BlockProducer, Delegator>: PartnerChainsFeature<Block, C>,
+		BlockProducer: 'static,
+		Delegator: 'static,
+	{
+		self.with_feature(BlockParticipationFeature::<BlockProducer, Delegator>::new(data_source))
+	}
+
+	/// Enables the governed map feature. See [GovernedMapFeature].
+	pub fn with_governed_map(
+		self,
+		data_source: Arc<dyn GovernedMapDataSource + Send + Sync>,
+	) -> Self
+	where
+		GovernedMapFeature: PartnerChainsFeature<Block, C>,
+	{
+		self.with_feature(GovernedMapFeature::new(data_source))
+	}
+
+	/// Enables the token bridge feature. See [BridgeFeature].
+	pub fn with_bridge<RecipientAddress: 'static>(
+		self,
+		data_source: Arc<dyn TokenBridgeDataSource<RecipientAddress> + Send + Sync>,
+	) -> Self
+	where
+		BridgeFeature<RecipientAddress>: PartnerChainsFeature<Block, C>,
+	{
+		self.with_feature(BridgeFeature::new(data_source))
+	}
+
+	/// Sets the configuration of deferred block verification used by [Self::import_queue].
+	/// Deferring is disabled if not set, because a deferred block holds up verification of all
+	/// blocks received after it.
+	pub fn with_deferred_import(mut self, deferred_import: DeferredImportConfig) -> Self {
+		self.deferred_import = deferred_import;
+		self
+	}
+
+	/// Enables diagnostics of blocks rejected by the inherent checks in [Self::import_queue].
+	/// See [crate::diagnostics].
+	pub fn with_inherent_diagnostics(
+		mut self,
+		inherent_diagnostics: InherentDiagnosticsConfig<Block>,
+	) -> Self {
+		self.inherent_diagnostics = Some(inherent_diagnostics);
+		self
+	}
+
+	/// Enables alerting when the main chain reference of proposed and imported blocks stalls.
+	/// See [McReferenceMonitor].
+	pub fn with_mc_reference_monitor(mut self, monitor: McReferenceMonitor) -> Self {
+		self.mc_reference_monitor = Some(Arc::new(monitor));
+		self
+	}
+
+	/// Returns the inherent data configuration of the builder
+	pub fn inherent_data_config(&self) -> &CreateInherentDataConfig {
+		&self.config
+	}
+
+	/// Creates the inherent data provider factory used for proposing blocks
+	pub fn proposal_cidp(&self) -> ProposalCIDP<Block, C> {
+		let cidp = ProposalCIDP::new(
+			self.config.clone(),
+			self.client.clone(),
+			self.mc_hash_data_source.clone(),
+			self.features.clone(),
+		);
+		match &self.mc_reference_monitor {
+			Some(monitor) => cidp.with_mc_reference_monitor(monitor.clone()),
+			None => cidp,
+		}
+	}
+
+	/// Creates the inherent data provider factory used for verifying imported blocks
+	pub fn verifier_cidp(&self) -> VerifierCIDP<Block, C> {
+		let cidp = VerifierCIDP::new(
+			self.config.clone(),
+			self.client.clone(),
+			self.mc_hash_data_source.clone(),
+			self.features.clone(),
+		);
+		match &self.mc_reference_monitor {
+			Some(monitor) => cidp.with_mc_reference_monitor(monitor.clone()),
+			None => cidp,
+		}
+	}
+
+	/// Creates the Aura import queue verifying blocks using [Self::verifier_cidp] and deferring
+	/// verification of blocks according to the configured [DeferredImportConfig]. Blocks rejected
+	/// by the inherent checks are diagnosed if enabled by [Self::with_inherent_diagnostics].
+	///
+	/// The `client` and `create_inherent_data_providers` fields of `params` are ignored and
+	/// replaced by the builder's client and [VerifierCIDP].
+	pub fn import_queue<I, S, R>(
+		&self,
+		params: ImportQueueParams<'_, Block, I, C, S, ()>,
+		equivocation_reporter: R,
+	) -> Result<DefaultImportQueue<Block>, ConsensusError>
+	where
+		C: ProvideRuntimeApi<Block>
+			+ BlockOf
+			+ Send
+			+ Sync
+			+ AuxStore
+			+ UsageProvider<Block>
+			+ HeaderBackend<Block>
+			+ HeaderMetadata<Block, Error = sp_blockchain::Error>,
+		C::Api: BlockBuilderApi<Block>
+			+ AuraApi<Block, AuraId>
+			+ ApiExt<Block>
+			+ GetMcReferencePolicyApi<Block>
+			+ GetEpochScheduleApi<Block>,
+		I: BlockImport<Block, Error = ConsensusError> + Send + Sync + 'static,
+		S: sp_core::traits::SpawnEssentialNamed,
+		R: EquivocationReporter<Block, AuraId> + 'static,
+	{
+		let ImportQueueParams {
+			block_import,
+			justification_import,
+			client: _,
+			create_inherent_data_providers: (),
+			spawner,
+			registry,
+			check_for_equivocation,
+			telemetry,
+			compatibility_mode,
+		} = params;
+		let params = ImportQueueParams {
+			block_import,
+			justification_import,
+			client: self.client.clone(),
+			create_inherent_data_providers: self.verifier_cidp(),
+			spawner,
+			registry,
+			check_for_equivocation,
+			telemetry,
+			compatibility_mode,
+		};
+		match self.inherent_diagnostics.clone() {
+			Some(inherent_diagnostics) => import_queue_with_inherent_check_failure_handler::<
+				AuraPair,
+				_,
+				_,
+				_,
+				_,
+				_,
+				McHashInherentDigest,
+				_,
+			>(
+				params,
+				equivocation_reporter,
+				self.deferred_import,
+				Arc::new(InherentDiagnosticsHandler::new(
+					self.client.clone(),
+					self.features.clone(),
+					inherent_diagnostics,
+				)),
+			),
+			None => {
+				import_queue_with_deferred_import::<AuraPair, _, _, _, _, _, McHashInherentDigest, _>(
+					params,
+					equivocation_reporter,
+					self.deferred_import,
+				)
+			},
+		}
+	}
+
+	/// Creates the Aura block authoring task proposing blocks with [Self::proposal_cidp]
+	///
+	/// The `client` and `create_inherent_data_providers` fields of `params` are ignored and
+	/// replaced by the builder's client and [ProposalCIDP]. The proposer factory passed in `params`
+	/// is wrapped in [PartnerChainsProposerFactory] adding the main chain reference to block headers.
+	pub fn start_aura<SC, I, PF, SO, L, BS, Err>(
+		&self,
+		params: StartAuraParams<C, SC, I, PF, SO, L, (), BS, NumberFor<Block>>,
+	) -> Result<impl Future<Output = ()> + use<SC, I, PF, SO, L, BS, Err, Block, C>, ConsensusError>
+	where
+		C: ProvideRuntimeApi<Block> + BlockOf + AuxStore + HeaderBackend<Block> + Send + Sync,
+		C::Api: AuraApi<Block, AuraId>,
+		SC: SelectChain<Block>,
+		I: BlockImport<Block> + Send + Sync + 'static,
+		PF: Environment<Block, Error = Err> + Send + Sync + 'static,
+		PF::Proposer: Proposer<Block, Error = Err>,
+		SO: SyncOracle + Send + Sync + Clone,
+		L: sc_consensus::JustificationSyncLink<Block>,
+		BS: sc_consensus_slots::BackoffAuthoringBlocksStrategy<NumberFor<Block>>
+			+ Send
+			+ Sync
+			+ 'static,
+		Err: std::error::Error + Send + From<ConsensusError> + 'static,
+	{
+		let StartAuraParams {
+			slot_duration,
+			client: _,
+			select_chain,
+			block_import,
+			proposer_factory,
+			sync_oracle,
+			justification_sync_link,
+			create_inherent_data_providers: (),
+			force_authoring,
+			backoff_authoring_blocks,
+			keystore,
+			block_proposal_slot_portion,
+			max_block_proposal_slot_portion,
+			telemetry,
+			compatibility_mode,
+		} = params;
+		let proposer_factory: PartnerChainsProposerFactory<_, _, McHashInherentDigest> =
+			PartnerChainsProposerFactory::new(proposer_factory);
+		sc_partner_chains_consensus_aura::start_aura::<
+			AuraPair,
+			_,
+			_,
+			_,
+			_,
+			_,
+			_,
+			_,
+			_,
+			_,
+			_,
+			McHashInherentDigest,
+		>(StartAuraParams {
+			slot_duration,
+			client: self.client.clone(),
+			select_chain,
+			block_import,
+			proposer_factory,
+			sync_oracle,
+			justification_sync_link,
+			create_inherent_data_providers: self.proposal_cidp(),
+			force_authoring,
+			backoff_authoring_blocks,
+			keystore,
+			block_proposal_slot_portion,
+			max_block_proposal_slot_portion,
+			telemetry,
+			compatibility_mode,
+		})
+	}
+
+	/// Creates an RPC module containing the sidechain RPC and RPC methods of all enabled features
+	pub fn rpc_module(
+		&self,
+		sidechain_rpc_data_source: Arc<dyn SidechainRpcDataSource + Send + Sync>,
+	) -> Result<RpcModule<()>, Box<dyn Error + Send + Sync>>
+	where
+		C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + CallApiAt<Block> + Send + Sync,
+		C::Api: GetGenesisUtxo<Block> + GetEpochDurationApi<Block> + GetEpochScheduleApi<Block>,
+	{
+		let mut module = RpcModule::new(());
+		module.merge(
+			SidechainRpc::<C, Block>::new(
+				self.client.clone(),
+				self.config.mc_epoch_config.clone(),
+				sidechain_rpc_data_source,
+				self.config.time_source.clone(),
+			)
+			.into_rpc(),
+		)?;
+		for feature in self.features.iter() {
+			if let Some(methods) = feature.rpc_methods(self.client.clone())? {
+				module.merge(methods)?;
+			}
+		}
+		Ok(module)
+	}
+}
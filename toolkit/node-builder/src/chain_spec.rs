@@ -0,0 +1,150 @@
+//! Partner Chains extension of the chain spec
+//!
+//! Node settings that have to be the same for all nodes of a Partner Chain network, but are not part of
+//! the runtime state, are stored in the chain spec as fields of [PartnerChainsChainSpecExtension]. Chain specs
+//! are created with the extension by using `GenericChainSpec<PartnerChainsChainSpecExtension>`, and the
+//! settings are read by the node using [FinalityConfig::from_chain_spec]. Chain specs that do not contain
+//! the extension fields, or are not using the extension at all, use the defaults.
+//!
+//! Example of the extension fields in a chain spec JSON:
+//! ```json
+//! "finality": {
+//!   "gossipDurationMillis": 333,
+//!   "justificationPeriod": 512,
+//!   "observerEnabled": false
+//! }
+//! ```
+use sc_chain_spec::{ChainSpec, ChainSpecExtension, get_extension};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Default interval of Grandpa gossip rounds, in milliseconds
+pub const DEFAULT_GOSSIP_DURATION_MILLIS: u64 = 333;
+
+/// Default minimal period of blocks for which Grandpa justifications are generated and stored
+pub const DEFAULT_JUSTIFICATION_PERIOD: u32 = 512;
+
+/// Grandpa finality settings of a Partner Chain
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FinalityConfig {
+	/// Interval of Grandpa gossip rounds, in milliseconds
+	pub gossip_duration_millis: u64,
+	/// Minimal period of blocks for which justifications are generated and stored
+	pub justification_period: u32,
+	/// Whether the Grandpa observer protocol is live in the network
+	pub observer_enabled: bool,
+}
+
+impl Default for FinalityConfig {
+	fn default() -> Self {
+		Self {
+			gossip_duration_millis: DEFAULT_GOSSIP_DURATION_MILLIS,
+			justification_period: DEFAULT_JUSTIFICATION_PERIOD,
+			observer_enabled: false,
+		}
+	}
+}
+
+impl FinalityConfig {
+	/// Returns the interval of Grandpa gossip rounds
+	pub fn gossip_duration(&self) -> Duration {
+		Duration::from_millis(self.gossip_duration_millis)
+	}
+
+	/// Checks that the settings can be used by Grandpa, ie. that the gossip duration and justification
+	/// period are not zero
+	pub fn validate(&self) -> Result<(), String> {
+		if self.gossip_duration_millis == 0 {
+			return Err("Finality gossip duration must be greater than 0".into());
+		}
+		if self.justification_period == 0 {
+			return Err("Finality justification period must be greater than 0".into());
+		}
+		Ok(())
+	}
+
+	/// Returns the finality settings stored in `chain_spec`, or the default ones if it does not contain them.
+	/// Returns an error if the stored settings are not valid.
+	pub fn from_chain_spec(chain_spec: &dyn ChainSpec) -> Result<Self, String> {
+		let config = get_extension::<Self>(chain_spec.extensions()).cloned().unwrap_or_default();
+		config.validate()?;
+		Ok(config)
+	}
+}
+
+/// Partner Chains chain spec extension
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ChainSpecExtension)]
+#[serde(rename_all = "camelCase")]
+pub struct PartnerChainsChainSpecExtension {
+	/// Grandpa finality settings
+	#[serde(default)]
+	pub finality: FinalityConfig,
+}
+
+impl PartnerChainsChainSpecExtension {
+	/// Creates a new [PartnerChainsChainSpecExtension] with given finality settings
+	pub fn new(finality: FinalityConfig) -> Self {
+		Self { finality }
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::json;
+
+	#[test]
+	fn missing_finality_settings_are_defaulted() {
+		let extension: PartnerChainsChainSpecExtension = serde_json::from_value(json!({})).unwrap();
+		assert_eq!(extension.finality, FinalityConfig::default());
+
+		let extension: PartnerChainsChainSpecExtension =
+			serde_json::from_value(json!({ "finality": { "justificationPeriod": 32 } })).unwrap();
+		assert_eq!(
+			extension.finality,
+			FinalityConfig { justification_period: 32, ..FinalityConfig::default() }
+		);
+	}
+
+	#[test]
+	fn zero_finality_settings_are_rejected() {
+		assert_eq!(FinalityConfig::default().validate(), Ok(()));
+		assert!(
+			FinalityConfig { gossip_duration_millis: 0, ..FinalityConfig::default() }
+				.validate()
+				.is_err()
+		);
+		assert!(
+			FinalityConfig { justification_period: 0, ..FinalityConfig::default() }
+				.validate()
+				.is_err()
+		);
+	}
+
+	#[test]
+	fn finality_settings_round_trip() {
+		let extension = PartnerChainsChainSpecExtension::new(FinalityConfig {
+			gossip_duration_millis: 1000,
+			justification_period: 64,
+			observer_enabled: true,
+		});
+
+		let value = serde_json::to_value(&extension).unwrap();
+
+		assert_eq!(
+			value,
+			json!({
+				"finality": {
+					"gossipDurationMillis": 1000,
+					"justificationPeriod": 64,
+					"observerEnabled": true
+				}
+			})
+		);
+		assert_eq!(
+			serde_json::from_value::<PartnerChainsChainSpecExtension>(value).unwrap(),
+			extension
+		);
+	}
+}
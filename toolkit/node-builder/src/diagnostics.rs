@@ -0,0 +1,526 @@
+//! Diagnostics of blocks rejected by the inherent checks of the runtime
+//!
+//! When inherents of an imported block are rejected, the runtime only reports the error variant, which is
+//! rarely enough to find the cause of the rejection. With diagnostics enabled by
+//! [PartnerChainsNodeBuilder::with_inherent_diagnostics], the inherent data computed locally for verification
+//! of the block is compared with the inherent payloads contained in the block by the features owning the
+//! rejected inherents. The resulting [InherentDiagnosticsReport] is logged and persisted as a JSON file in
+//! the [InherentDiagnosticsConfig::reports_dir] directory. Reports are written in the background, without
+//! delaying block import, and only the [InherentDiagnosticsConfig::max_reports] latest ones are kept.
+//!
+//! Inherent payloads are encoded in runtime specific extrinsics, so the node has to provide an
+//! [InherentExtrinsicDecoder] extracting them from the block.
+//!
+//! [PartnerChainsNodeBuilder::with_inherent_diagnostics]: crate::PartnerChainsNodeBuilder::with_inherent_diagnostics
+use crate::features::PartnerChainsFeature;
+use async_trait::async_trait;
+use sc_partner_chains_consensus_aura::import_queue::{
+	InherentCheckFailure, InherentCheckFailureHandler,
+};
+use serde::Serialize;
+use sp_core::bytes::to_hex;
+use sp_inherents::{InherentData, InherentIdentifier};
+use sp_partner_chains_bridge::TokenBridgeTransfersV1;
+use sp_runtime::traits::{Block as BlockT, Header};
+use sp_session_validator_management::CommitteeMember;
+use std::{
+	collections::BTreeSet,
+	fmt::Debug,
+	path::{Path, PathBuf},
+	sync::Arc,
+};
+
+/// Name of the directory in the node's base path in which diagnostic reports are stored
+pub const REPORTS_DIR: &str = "inherent_diagnostics";
+
+/// Default number of the latest diagnostic reports kept in the reports directory
+pub const DEFAULT_MAX_REPORTS: usize = 100;
+
+/// Extracts payloads of Partner Chains inherents from extrinsics of a runtime
+pub trait InherentExtrinsicDecoder<Block: BlockT>: Send + Sync {
+	/// Returns the identifier and the SCALE encoded payload of the inherent carried by `extrinsic`,
+	/// or [None] if `extrinsic` is not an inherent of a Partner Chains feature.
+	///
+	/// Payloads are expected in the format used by diagnostics of the respective feature:
+	/// - governed map: [sp_governed_map::GovernedMapInherentDataV1] with changes registered by the block
+	/// - token bridge: [sp_partner_chains_bridge::TokenBridgeTransfersV1] with transfers handled by the block
+	/// - committee selection: tuple of the [sidechain_domain::ScEpochNumber] and the list of
+	///   [sp_session_validator_management::CommitteeMember] set by the block
+	fn decode_inherent(
+		&self,
+		extrinsic: &Block::Extrinsic,
+	) -> Option<(InherentIdentifier, Vec<u8>)>;
+}
+
+/// Configuration of inherent diagnostics
+pub struct InherentDiagnosticsConfig<Block: BlockT> {
+	/// Directory in which diagnostic reports are stored
+	pub reports_dir: PathBuf,
+	/// Number of the latest reports kept in [Self::reports_dir], older ones are removed
+	pub max_reports: usize,
+	/// Decoder of inherent payloads contained in blocks
+	pub decoder: Arc<dyn InherentExtrinsicDecoder<Block>>,
+}
+
+impl<Block: BlockT> Clone for InherentDiagnosticsConfig<Block> {
+	fn clone(&self) -> Self {
+		Self {
+			reports_dir: self.reports_dir.clone(),
+			max_reports: self.max_reports,
+			decoder: self.decoder.clone(),
+		}
+	}
+}
+
+impl<Block: BlockT> InherentDiagnosticsConfig<Block> {
+	/// Creates a configuration storing up to [DEFAULT_MAX_REPORTS] reports in the [REPORTS_DIR] directory
+	/// of node's `base_path`
+	pub fn new(base_path: &Path, decoder: Arc<dyn InherentExtrinsicDecoder<Block>>) -> Self {
+		Self { reports_dir: base_path.join(REPORTS_DIR), max_reports: DEFAULT_MAX_REPORTS, decoder }
+	}
+
+	/// Sets the number of the latest reports kept in the reports directory
+	pub fn with_max_reports(self, max_reports: usize) -> Self {
+		Self { max_reports, ..self }
+	}
+}
+
+/// Rejected inherent of a block, passed to [PartnerChainsFeature::diagnose_inherent_failure]
+pub struct InherentFailure<'a, Block: BlockT> {
+	/// Hash of the parent of the rejected block
+	pub parent_hash: Block::Hash,
+	/// Identifier of the rejected inherent
+	pub identifier: InherentIdentifier,
+	/// SCALE encoded error returned by the runtime
+	pub error: &'a [u8],
+	/// Inherent data computed locally for verification of the block
+	pub local_data: &'a InherentData,
+	/// SCALE encoded payload of the inherent contained in the block, if the block contains it
+	pub block_payload: Option<&'a [u8]>,
+}
+
+/// Diagnosis of a rejected inherent provided by the feature owning it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InherentDiagnosis {
+	/// Human readable error returned by the runtime
+	pub error: String,
+	/// Human readable differences between the locally computed inherent data and the block's payload
+	pub differences: Vec<String>,
+}
+
+/// Diagnostic report of a block rejected by the inherent checks
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct InherentDiagnosticsReport {
+	/// Number of the rejected block
+	pub block_number: String,
+	/// Hash of the rejected block
+	pub block_hash: String,
+	/// Hash of the parent of the rejected block
+	pub parent_hash: String,
+	/// Reports of all rejected inherents of the block
+	pub inherents: Vec<InherentFailureReport>,
+}
+
+/// Diagnostic report of a single rejected inherent
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct InherentFailureReport {
+	/// Identifier of the inherent
+	pub identifier: String,
+	/// Error returned by the runtime, decoded if the inherent is owned by an enabled feature
+	pub error: String,
+	/// Hex encoded inherent data computed locally
+	pub local_data: Option<String>,
+	/// Hex encoded inherent payload contained in the block
+	pub block_payload: Option<String>,
+	/// Differences between the locally computed inherent data and the block's payload
+	pub differences: Vec<String>,
+}
+
+/// [InherentCheckFailureHandler] logging and persisting [InherentDiagnosticsReport]s
+pub(crate) struct InherentDiagnosticsHandler<Block: BlockT, C> {
+	client: Arc<C>,
+	features: Vec<Arc<dyn PartnerChainsFeature<Block, C>>>,
+	config: InherentDiagnosticsConfig<Block>,
+}
+
+impl<Block: BlockT, C> InherentDiagnosticsHandler<Block, C> {
+	pub(crate) fn new(
+		client: Arc<C>,
+		features: Vec<Arc<dyn PartnerChainsFeature<Block, C>>>,
+		config: InherentDiagnosticsConfig<Block>,
+	) -> Self {
+		Self { client, features, config }
+	}
+}
+
+/// Writes `report` to `reports_dir`, removing the oldest reports so that at most `max_reports` are kept
+fn write_report(
+	reports_dir: &Path,
+	max_reports: usize,
+	report: &InherentDiagnosticsReport,
+) -> std::io::Result<PathBuf> {
+	std::fs::create_dir_all(reports_dir)?;
+	let path = reports_dir.join(format!("{}-{}.json", report.block_number, report.block_hash));
+	let json = serde_json::to_vec_pretty(report).map_err(std::io::Error::other)?;
+	std::fs::write(&path, json)?;
+	remove_old_reports(reports_dir, max_reports)?;
+	Ok(path)
+}
+
+/// Removes the least recently written reports from `reports_dir`, keeping `max_reports` of them
+fn remove_old_reports(reports_dir: &Path, max_reports: usize) -> std::io::Result<()> {
+	let mut reports = vec![];
+	for entry in std::fs::read_dir(reports_dir)? {
+		let entry = entry?;
+		let path = entry.path();
+		if path.extension().is_some_and(|extension| extension == "json") {
+			reports.push((entry.metadata()?.modified()?, path));
+		}
+	}
+	if reports.len() > max_reports {
+		reports.sort();
+		for (_, path) in reports.iter().take(reports.len() - max_reports) {
+			std::fs::remove_file(path)?;
+		}
+	}
+	Ok(())
+}
+
+#[async_trait]
+impl<Block: BlockT, C: Send + Sync> InherentCheckFailureHandler<Block>
+	for InherentDiagnosticsHandler<Block, C>
+{
+	async fn handle_inherent_check_failure(&self, failure: InherentCheckFailure<'_, Block>) {
+		let report = create_report(
+			self.client.as_ref(),
+			&self.features,
+			self.config.decoder.as_ref(),
+			&failure,
+		);
+		for inherent in report.inherents.iter() {
+			log::warn!(
+				"🔎 Inherent {} of block {} rejected with {}. Differences: {:?}",
+				inherent.identifier,
+				report.block_hash,
+				inherent.error,
+				inherent.differences
+			);
+		}
+		let reports_dir = self.config.reports_dir.clone();
+		let max_reports = self.config.max_reports;
+		tokio::task::spawn_blocking(move || {
+			match write_report(&reports_dir, max_reports, &report) {
+				Ok(path) => {
+					log::warn!("🔎 Inherent diagnostics report written to {}", path.display())
+				},
+				Err(err) => log::error!("Failed to write inherent diagnostics report: {err}"),
+			}
+		});
+	}
+}
+
+/// Creates a diagnostic report of the inherent check `failure`, using the first feature
+/// that diagnoses each of the rejected inherents
+pub fn create_report<Block: BlockT, C>(
+	client: &C,
+	features: &[Arc<dyn PartnerChainsFeature<Block, C>>],
+	decoder: &dyn InherentExtrinsicDecoder<Block>,
+	failure: &InherentCheckFailure<'_, Block>,
+) -> InherentDiagnosticsReport {
+	let block_payloads: Vec<(InherentIdentifier, Vec<u8>)> = failure
+		.block
+		.extrinsics()
+		.iter()
+		.filter_map(|xt| decoder.decode_inherent(xt))
+		.collect();
+	let inherents = (failure.errors.iter())
+		.map(|(identifier, error)| {
+			let block_payload = block_payloads
+				.iter()
+				.find(|(payload_identifier, _)| payload_identifier == identifier)
+				.map(|(_, payload)| payload.as_slice());
+			let inherent_failure = InherentFailure {
+				parent_hash: failure.parent_hash,
+				identifier: *identifier,
+				error,
+				local_data: failure.inherent_data,
+				block_payload,
+			};
+			let diagnosis = features
+				.iter()
+				.find_map(|feature| feature.diagnose_inherent_failure(client, &inherent_failure))
+				.unwrap_or_else(|| InherentDiagnosis {
+					error: to_hex(error, false),
+					differences: vec![],
+				});
+			InherentFailureReport {
+				identifier: identifier_to_string(identifier),
+				error: diagnosis.error,
+				local_data: failure
+					.inherent_data
+					.get_data::<RawPayload>(identifier)
+					.ok()
+					.flatten()
+					.map(|RawPayload(data)| to_hex(&data, false)),
+				block_payload: block_payload.map(|payload| to_hex(payload, false)),
+				differences: diagnosis.differences,
+			}
+		})
+		.collect();
+	InherentDiagnosticsReport {
+		block_number: failure.block.header().number().to_string(),
+		block_hash: format!("{:?}", failure.block.hash()),
+		parent_hash: format!("{:?}", failure.parent_hash),
+		inherents,
+	}
+}
+
+/// Inherent data stored without decoding
+struct RawPayload(Vec<u8>);
+
+impl parity_scale_codec::Decode for RawPayload {
+	fn decode<I: parity_scale_codec::Input>(
+		input: &mut I,
+	) -> Result<Self, parity_scale_codec::Error> {
+		let mut data = vec![0u8; input.remaining_len()?.unwrap_or_default()];
+		input.read(&mut data)?;
+		Ok(Self(data))
+	}
+}
+
+fn identifier_to_string(identifier: &InherentIdentifier) -> String {
+	match std::str::from_utf8(identifier) {
+		Ok(identifier) => identifier.to_string(),
+		Err(_) => to_hex(identifier, false),
+	}
+}
+
+/// Decodes the SCALE encoded `error` of type `E` into a human readable form, falling back to hex
+pub(crate) fn decode_error<E: parity_scale_codec::Decode + Debug>(mut error: &[u8]) -> String {
+	match E::decode(&mut error) {
+		Ok(error) => format!("{error:?}"),
+		Err(_) => to_hex(error, false),
+	}
+}
+
+/// Compares inherent payloads present locally and in the block, using `differences` if both are present
+pub(crate) fn compare_payloads<L, B>(
+	local: Option<L>,
+	block: Option<B>,
+	differences: impl FnOnce(L, B) -> Vec<String>,
+) -> Vec<String> {
+	match (local, block) {
+		(Some(local), Some(block)) => differences(local, block),
+		(Some(_), None) => vec!["inherent is missing from the block".to_string()],
+		(None, Some(_)) => vec!["inherent is not expected in the block".to_string()],
+		(None, None) => vec![],
+	}
+}
+
+/// Lists elements of `local` missing from `block` and elements of `block` not present in `local`,
+/// described by `describe`
+pub(crate) fn list_differences<T: PartialEq>(
+	local: &[T],
+	block: &[T],
+	describe: impl Fn(&T) -> String,
+) -> Vec<String> {
+	let missing = local
+		.iter()
+		.filter(|item| !block.contains(item))
+		.map(|item| format!("missing from the block: {}", describe(item)));
+	let unexpected = block
+		.iter()
+		.filter(|item| !local.contains(item))
+		.map(|item| format!("not expected in the block: {}", describe(item)));
+	missing.chain(unexpected).collect()
+}
+
+/// Lists keys of the governed map whose changes differ between `local` and `block`
+pub(crate) fn governed_map_differences(
+	local: &sp_governed_map::GovernedMapInherentDataV1,
+	block: &sp_governed_map::GovernedMapInherentDataV1,
+) -> Vec<String> {
+	let describe = |change: Option<&Option<sidechain_domain::byte_string::ByteString>>| match change
+	{
+		None => "no change".to_string(),
+		Some(None) => "removal".to_string(),
+		Some(Some(value)) => format!("value {}", value.to_hex_string()),
+	};
+	let keys: BTreeSet<&String> = local.keys().chain(block.keys()).collect();
+	keys.into_iter()
+		.filter(|key| local.get(*key) != block.get(*key))
+		.map(|key| {
+			format!(
+				"key '{key}': expected {}, block contains {}",
+				describe(local.get(key)),
+				describe(block.get(key))
+			)
+		})
+		.collect()
+}
+
+/// Lists differences of the data checkpoint and the transfers between `local` and `block`
+pub(crate) fn bridge_differences<RecipientAddress: PartialEq + Debug>(
+	local: &TokenBridgeTransfersV1<RecipientAddress>,
+	block: &TokenBridgeTransfersV1<RecipientAddress>,
+) -> Vec<String> {
+	let mut differences = vec![];
+	if local.data_checkpoint != block.data_checkpoint {
+		differences.push(format!(
+			"data checkpoint: expected {:?}, block contains {:?}",
+			local.data_checkpoint, block.data_checkpoint
+		));
+	}
+	differences.extend(list_differences(&local.transfers, &block.transfers, |transfer| {
+		format!("transfer {transfer:?}")
+	}));
+	differences
+}
+
+/// Lists committee members differing between `local` and `block`
+pub(crate) fn committee_differences<AuthorityId, AuthorityKeys>(
+	local: &[CommitteeMember<AuthorityId, AuthorityKeys>],
+	block: &[CommitteeMember<AuthorityId, AuthorityKeys>],
+) -> Vec<String>
+where
+	AuthorityId: AsRef<[u8]> + Clone + PartialEq,
+	AuthorityKeys: PartialEq,
+{
+	let mut differences = list_differences(local, block, |member| {
+		format!("committee member {}", to_hex(member.authority_id().as_ref(), false))
+	});
+	if differences.is_empty() && local != block {
+		differences.push("committee members are in different order".to_string());
+	}
+	differences
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sidechain_domain::{McBlockNumber, UtxoId, byte_string::ByteString};
+	use sp_governed_map::GovernedMapInherentDataV1;
+	use sp_partner_chains_bridge::{BridgeDataCheckpoint, BridgeTransferV1};
+
+	#[test]
+	fn governed_map_differences_lists_only_differing_keys() {
+		let local = GovernedMapInherentDataV1::from([
+			("same".to_string(), Some(ByteString::from_hex_unsafe("01"))),
+			("changed".to_string(), Some(ByteString::from_hex_unsafe("02"))),
+			("removed".to_string(), None),
+		]);
+		let block = GovernedMapInherentDataV1::from([
+			("same".to_string(), Some(ByteString::from_hex_unsafe("01"))),
+			("changed".to_string(), Some(ByteString::from_hex_unsafe("03"))),
+			("extra".to_string(), Some(ByteString::from_hex_unsafe("04"))),
+		]);
+
+		assert_eq!(
+			governed_map_differences(&local, &block),
+			vec![
+				"key 'changed': expected value 0x02, block contains value 0x03",
+				"key 'extra': expected no change, block contains value 0x04",
+				"key 'removed': expected removal, block contains no change",
+			]
+		);
+	}
+
+	#[test]
+	fn bridge_differences_lists_checkpoint_and_transfers() {
+		let utxo_id = UtxoId::new([1; 32], 0);
+		let local = TokenBridgeTransfersV1 {
+			transfers: vec![
+				BridgeTransferV1::ReserveTransfer { token_amount: 10 },
+				BridgeTransferV1::UserTransfer { token_amount: 20, recipient: 1u8 },
+			],
+			data_checkpoint: BridgeDataCheckpoint::Block(McBlockNumber(5)),
+		};
+		let block = TokenBridgeTransfersV1 {
+			transfers: vec![
+				BridgeTransferV1::ReserveTransfer { token_amount: 10 },
+				BridgeTransferV1::InvalidTransfer { token_amount: 30, utxo_id },
+			],
+			data_checkpoint: BridgeDataCheckpoint::Utxo(utxo_id),
+		};
+
+		assert_eq!(
+			bridge_differences(&local, &block),
+			vec![
+				format!(
+					"data checkpoint: expected {:?}, block contains {:?}",
+					local.data_checkpoint, block.data_checkpoint
+				),
+				format!("missing from the block: transfer {:?}", local.transfers[1]),
+				format!("not expected in the block: transfer {:?}", block.transfers[1]),
+			]
+		);
+	}
+
+	#[test]
+	fn committee_differences_lists_differing_members() {
+		let member = |id: u8| CommitteeMember::permissioned([id; 2], ());
+
+		assert_eq!(
+			committee_differences(&[member(1), member(2)], &[member(2), member(3)]),
+			vec![
+				"missing from the block: committee member 0x0101",
+				"not expected in the block: committee member 0x0303",
+			]
+		);
+		assert_eq!(
+			committee_differences(&[member(1), member(2)], &[member(2), member(1)]),
+			vec!["committee members are in different order"]
+		);
+		assert!(committee_differences(&[member(1)], &[member(1)]).is_empty());
+	}
+
+	#[test]
+	fn list_differences_lists_missing_and_unexpected_items() {
+		assert_eq!(
+			list_differences(&[1, 2, 3], &[2, 3, 4], |n| n.to_string()),
+			vec!["missing from the block: 1", "not expected in the block: 4"]
+		);
+	}
+
+	#[test]
+	fn compare_payloads_reports_missing_and_unexpected_inherents() {
+		let no_differences = |_: u32, _: u32| vec![];
+		assert_eq!(
+			compare_payloads(Some(1), None, no_differences),
+			vec!["inherent is missing from the block"]
+		);
+		assert_eq!(
+			compare_payloads(None, Some(1), no_differences),
+			vec!["inherent is not expected in the block"]
+		);
+		assert!(compare_payloads::<u32, u32>(None, None, no_differences).is_empty());
+		assert_eq!(
+			compare_payloads(Some(1), Some(2), |l, b| vec![format!("{l} != {b}")]),
+			vec!["1 != 2"]
+		);
+	}
+
+	#[test]
+	fn write_report_keeps_only_max_reports() {
+		let reports_dir = tempfile::tempdir().unwrap();
+		let report = |block_number: u32| InherentDiagnosticsReport {
+			block_number: block_number.to_string(),
+			block_hash: format!("0x{block_number:064x}"),
+			parent_hash: format!("0x{:064x}", block_number - 1),
+			inherents: vec![],
+		};
+
+		for block_number in 1..=5 {
+			let path = write_report(reports_dir.path(), 3, &report(block_number)).unwrap();
+			assert!(path.exists());
+		}
+
+		assert_eq!(std::fs::read_dir(reports_dir.path()).unwrap().count(), 3);
+	}
+
+	#[test]
+	fn decode_error_falls_back_to_hex() {
+		assert_eq!(decode_error::<u16>(&[1, 0]), "1");
+		assert_eq!(decode_error::<u16>(&[1]), "0x01");
+	}
+}
@@ -0,0 +1,352 @@
+//! Partner Chains features that can be enabled in [PartnerChainsNodeBuilder]
+//!
+//! Each feature creates the inherent data provider of its pallet and, optionally, exposes RPC methods
+//! and diagnoses rejections of its inherent.
+//! Features not provided by the toolkit can be added by implementing [PartnerChainsFeature] and passing
+//! them to [PartnerChainsNodeBuilder::with_feature].
+//!
+//! [PartnerChainsNodeBuilder]: crate::PartnerChainsNodeBuilder
+//! [PartnerChainsNodeBuilder::with_feature]: crate::PartnerChainsNodeBuilder::with_feature
+#![allow(deprecated)]
+use crate::diagnostics::*;
+use crate::inherent_data::InherentDataContext;
+use async_trait::async_trait;
+use authority_selection_inherents::{
+	AriadneInherentDataProvider, AuthoritySelectionDataSource, AuthoritySelectionInputs,
+	CandidateValidationApi,
+};
+use jsonrpsee::Methods;
+use pallet_session_validator_management_rpc::{
+	SessionValidatorManagementRpc, SessionValidatorManagementRpcApiServer,
+};
+use parity_scale_codec::{Decode, Encode};
+use sidechain_domain::ScEpochNumber;
+use sp_api::ProvideRuntimeApi;
+use sp_block_participation::{
+	AsCardanoSPO, BlockParticipationApi, CardanoDelegator,
+	inherent_data::{BlockParticipationDataSource, BlockParticipationInherentDataProvider},
+};
+use sp_blockchain::HeaderBackend;
+use sp_consensus_aura::Slot;
+use sp_governed_map::{
+	GovernedMapDataSource, GovernedMapIDPApi, GovernedMapInherentDataProvider,
+	GovernedMapInherentDataV1,
+};
+use sp_inherents::InherentDataProvider;
+use sp_partner_chains_bridge::{
+	TokenBridgeDataSource, TokenBridgeIDPRuntimeApi, TokenBridgeInherentDataProvider,
+	TokenBridgeTransfersV1,
+};
+use sp_runtime::traits::{Block as BlockT, NumberFor};
+use sp_session_validator_management::{CommitteeMember, SessionValidatorManagementApi};
+use sp_session_validator_management_query::SessionValidatorManagementQuery;
+use sp_sidechain::{GetEpochScheduleApi, GetGenesisUtxo, GetSidechainStatus};
+use std::{error::Error, fmt::Debug, hash::Hash, marker::PhantomData, sync::Arc};
+
+/// Partner Chains feature that can be wired into a node by [PartnerChainsNodeBuilder]
+///
+/// [PartnerChainsNodeBuilder]: crate::PartnerChainsNodeBuilder
+#[async_trait]
+pub trait PartnerChainsFeature<Block: BlockT, C>: Send + Sync {
+	/// Creates the inherent data provider of the feature for a block being proposed or verified
+	async fn create_inherent_data_provider(
+		&self,
+		client: &C,
+		context: &InherentDataContext<Block>,
+	) -> Result<Box<dyn InherentDataProvider>, Box<dyn Error + Send + Sync>>;
+
+	/// Returns RPC methods exposed by the feature, if any
+	fn rpc_methods(
+		&self,
+		_client: Arc<C>,
+	) -> Result<Option<Methods>, Box<dyn Error + Send + Sync>> {
+		Ok(None)
+	}
+
+	/// Diagnoses the rejection of an inherent of an imported block
+	///
+	/// Returns [None] if the inherent is not owned by the feature.
+	fn diagnose_inherent_failure(
+		&self,
+		_client: &C,
+		_failure: &InherentFailure<'_, Block>,
+	) -> Option<InherentDiagnosis> {
+		None
+	}
+}
+
+/// Committee selection feature, providing Ariadne inherent data and the session validator management RPC
+pub struct CommitteeSelectionFeature<AuthorityId, AuthorityKeys> {
+	data_source: Arc<dyn AuthoritySelectionDataSource + Send + Sync>,
+	_marker: PhantomData<(AuthorityId, AuthorityKeys)>,
+}
+
+impl<AuthorityId, AuthorityKeys> CommitteeSelectionFeature<AuthorityId, AuthorityKeys> {
+	/// Creates a new [CommitteeSelectionFeature]
+	pub fn new(data_source: Arc<dyn AuthoritySelectionDataSource + Send + Sync>) -> Self {
+		Self { data_source, _marker: PhantomData }
+	}
+}
+
+#[async_trait]
+impl<Block, C, AuthorityId, AuthorityKeys> PartnerChainsFeature<Block, C>
+	for CommitteeSelectionFeature<AuthorityId, AuthorityKeys>
+where
+	Block: BlockT,
+	NumberFor<Block>: From<u32> + Into<u32>,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: sp_api::Core<Block>,
+	C::Api: SessionValidatorManagementApi<Block, AuthorityId, AuthorityKeys, ScEpochNumber>,
+	C::Api: GetEpochScheduleApi<Block>,
+	C::Api: GetGenesisUtxo<Block>,
+	C::Api: GetSidechainStatus<Block>,
+	C::Api: CandidateValidationApi<Block>,
+	AuthorityId: AsRef<[u8]> + Clone + PartialEq + Decode + Encode + Send + Sync + 'static,
+	AuthorityKeys: PartialEq + Decode + Encode + Send + Sync + 'static,
+{
+	async fn create_inherent_data_provider(
+		&self,
+		client: &C,
+		context: &InherentDataContext<Block>,
+	) -> Result<Box<dyn InherentDataProvider>, Box<dyn Error + Send + Sync>> {
+		let sc_epoch_schedule = client.runtime_api().get_epoch_schedule(context.parent_hash)?;
+		let provider = AriadneInherentDataProvider::new::<Block, AuthorityId, AuthorityKeys, C>(
+			client,
+			&sc_epoch_schedule,
+			&context.mc_epoch_config,
+			context.parent_hash,
+			context.timestamp.as_millis(),
+			self.data_source.as_ref(),
+			context.mc_epoch,
+		)
+		.await?;
+		Ok(Box::new(provider))
+	}
+
+	fn rpc_methods(&self, client: Arc<C>) -> Result<Option<Methods>, Box<dyn Error + Send + Sync>> {
+		let query = SessionValidatorManagementQuery::<C, Block, AuthorityId, AuthorityKeys>::new(
+			client,
+			self.data_source.clone(),
+		);
+		Ok(Some(SessionValidatorManagementRpc::new(Arc::new(query)).into_rpc().into()))
+	}
+
+	fn diagnose_inherent_failure(
+		&self,
+		client: &C,
+		failure: &InherentFailure<'_, Block>,
+	) -> Option<InherentDiagnosis> {
+		use sp_session_validator_management::{INHERENT_IDENTIFIER, InherentError};
+		if failure.identifier != INHERENT_IDENTIFIER {
+			return None;
+		}
+		let local_inputs = failure
+			.local_data
+			.get_data::<AuthoritySelectionInputs>(&INHERENT_IDENTIFIER)
+			.ok()
+			.flatten();
+		let block_committee = failure.block_payload.and_then(|mut payload| {
+			<(ScEpochNumber, Vec<CommitteeMember<AuthorityId, AuthorityKeys>>)>::decode(
+				&mut payload,
+			)
+			.ok()
+		});
+		let differences =
+			compare_payloads(local_inputs, block_committee, |inputs, (epoch, block_committee)| {
+				match expected_committee(client, failure.parent_hash, inputs, epoch) {
+					Ok(local_committee) => {
+						committee_differences(&local_committee, &block_committee)
+					},
+					Err(err) => vec![format!("failed to calculate the expected committee: {err}")],
+				}
+			});
+		Some(InherentDiagnosis { error: decode_error::<InherentError>(failure.error), differences })
+	}
+}
+
+/// Calculates the committee expected to be set for `epoch` by the block with `parent_hash`, the same
+/// way as the committee selection pallet does
+fn expected_committee<Block, C, AuthorityId, AuthorityKeys>(
+	client: &C,
+	parent_hash: Block::Hash,
+	inputs: AuthoritySelectionInputs,
+	epoch: ScEpochNumber,
+) -> Result<Vec<CommitteeMember<AuthorityId, AuthorityKeys>>, sp_api::ApiError>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block>,
+	C::Api: SessionValidatorManagementApi<Block, AuthorityId, AuthorityKeys, ScEpochNumber>,
+	AuthorityId: Decode + Encode,
+	AuthorityKeys: Decode + Encode,
+{
+	let api = client.runtime_api();
+	if let Some(committee) = api.calculate_committee(parent_hash, inputs, epoch)? {
+		return Ok(committee);
+	}
+	// Committee is kept unchanged when the calculation fails
+	Ok(match api.get_next_committee(parent_hash)? {
+		Some((_, committee)) => committee,
+		None => api.get_current_committee(parent_hash)?.1,
+	})
+}
+
+/// Block participation feature, providing aggregated block production data to the runtime
+pub struct BlockParticipationFeature<BlockProducer, Delegator> {
+	data_source: Arc<dyn BlockParticipationDataSource + Send + Sync>,
+	_marker: PhantomData<(BlockProducer, Delegator)>,
+}
+
+impl<BlockProducer, Delegator> BlockParticipationFeature<BlockProducer, Delegator> {
+	/// Creates a new [BlockParticipationFeature]
+	pub fn new(data_source: Arc<dyn BlockParticipationDataSource + Send + Sync>) -> Self {
+		Self { data_source, _marker: PhantomData }
+	}
+}
+
+#[async_trait]
+impl<Block, C, BlockProducer, Delegator> PartnerChainsFeature<Block, C>
+	for BlockParticipationFeature<BlockProducer, Delegator>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + Send + Sync,
+	C::Api: BlockParticipationApi<Block, BlockProducer, Slot>,
+	BlockProducer: AsCardanoSPO + Decode + Encode + Clone + Hash + Eq + Ord + Debug,
+	BlockProducer: Send + Sync + 'static,
+	Delegator: CardanoDelegator + Encode + Ord + Debug + Send + Sync + 'static,
+{
+	async fn create_inherent_data_provider(
+		&self,
+		client: &C,
+		context: &InherentDataContext<Block>,
+	) -> Result<Box<dyn InherentDataProvider>, Box<dyn Error + Send + Sync>> {
+		let provider =
+			BlockParticipationInherentDataProvider::<BlockProducer, Delegator, Slot>::new(
+				client,
+				self.data_source.as_ref(),
+				context.parent_hash,
+				context.slot,
+				&context.mc_epoch_config,
+			)
+			.await?;
+		Ok(Box::new(provider))
+	}
+}
+
+/// Governed map feature, providing changes of the governed map observed on Cardano
+pub struct GovernedMapFeature {
+	data_source: Arc<dyn GovernedMapDataSource + Send + Sync>,
+}
+
+impl GovernedMapFeature {
+	/// Creates a new [GovernedMapFeature]
+	pub fn new(data_source: Arc<dyn GovernedMapDataSource + Send + Sync>) -> Self {
+		Self { data_source }
+	}
+}
+
+#[async_trait]
+impl<Block, C> PartnerChainsFeature<Block, C> for GovernedMapFeature
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + Send + Sync,
+	C::Api: GovernedMapIDPApi<Block>,
+{
+	async fn create_inherent_data_provider(
+		&self,
+		client: &C,
+		context: &InherentDataContext<Block>,
+	) -> Result<Box<dyn InherentDataProvider>, Box<dyn Error + Send + Sync>> {
+		let provider = GovernedMapInherentDataProvider::new(
+			client,
+			context.parent_hash,
+			context.mc_hash.clone(),
+			context.previous_mc_hash.clone(),
+			self.data_source.as_ref(),
+		)
+		.await?;
+		Ok(Box::new(provider))
+	}
+
+	fn diagnose_inherent_failure(
+		&self,
+		_client: &C,
+		failure: &InherentFailure<'_, Block>,
+	) -> Option<InherentDiagnosis> {
+		use sp_governed_map::{INHERENT_IDENTIFIER, InherentError};
+		if failure.identifier != INHERENT_IDENTIFIER {
+			return None;
+		}
+		let local_changes = failure
+			.local_data
+			.get_data::<GovernedMapInherentDataV1>(&INHERENT_IDENTIFIER)
+			.ok()
+			.flatten();
+		let block_changes = failure
+			.block_payload
+			.and_then(|mut payload| GovernedMapInherentDataV1::decode(&mut payload).ok());
+		let differences = compare_payloads(local_changes, block_changes, |local, block| {
+			governed_map_differences(&local, &block)
+		});
+		Some(InherentDiagnosis { error: decode_error::<InherentError>(failure.error), differences })
+	}
+}
+
+/// Token bridge feature, providing token transfers observed on Cardano
+pub struct BridgeFeature<RecipientAddress> {
+	data_source: Arc<dyn TokenBridgeDataSource<RecipientAddress> + Send + Sync>,
+}
+
+impl<RecipientAddress> BridgeFeature<RecipientAddress> {
+	/// Creates a new [BridgeFeature]
+	pub fn new(
+		data_source: Arc<dyn TokenBridgeDataSource<RecipientAddress> + Send + Sync>,
+	) -> Self {
+		Self { data_source }
+	}
+}
+
+#[async_trait]
+impl<Block, C, RecipientAddress> PartnerChainsFeature<Block, C> for BridgeFeature<RecipientAddress>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + Send + Sync,
+	C::Api: TokenBridgeIDPRuntimeApi<Block>,
+	RecipientAddress: Encode + Decode + PartialEq + Debug + Send + Sync + 'static,
+{
+	async fn create_inherent_data_provider(
+		&self,
+		client: &C,
+		context: &InherentDataContext<Block>,
+	) -> Result<Box<dyn InherentDataProvider>, Box<dyn Error + Send + Sync>> {
+		let provider = TokenBridgeInherentDataProvider::<RecipientAddress>::new(
+			client,
+			context.parent_hash,
+			context.mc_hash.clone(),
+			self.data_source.as_ref(),
+		)
+		.await?;
+		Ok(Box::new(provider))
+	}
+
+	fn diagnose_inherent_failure(
+		&self,
+		_client: &C,
+		failure: &InherentFailure<'_, Block>,
+	) -> Option<InherentDiagnosis> {
+		use sp_partner_chains_bridge::{INHERENT_IDENTIFIER, InherentError};
+		if failure.identifier != INHERENT_IDENTIFIER {
+			return None;
+		}
+		let local_transfers = (failure.local_data)
+			.get_data::<TokenBridgeTransfersV1<RecipientAddress>>(&INHERENT_IDENTIFIER)
+			.ok()
+			.flatten();
+		let block_transfers = failure.block_payload.and_then(|mut payload| {
+			TokenBridgeTransfersV1::<RecipientAddress>::decode(&mut payload).ok()
+		});
+		let differences = compare_payloads(local_transfers, block_transfers, |local, block| {
+			bridge_differences(&local, &block)
+		});
+		Some(InherentDiagnosis { error: decode_error::<InherentError>(failure.error), differences })
+	}
+}
@@ -0,0 +1,432 @@
+//! Inherent data provider factories used for proposing and verifying Partner Chain blocks
+//!
+//! Both [ProposalCIDP] and [VerifierCIDP] create the inherent data providers that are required by every
+//! Partner Chain (Aura slot, timestamp and main chain reference) themselves and delegate creation of
+//! all other providers to the [PartnerChainsFeature]s that were enabled in [PartnerChainsNodeBuilder].
+//!
+//! [VerifierCIDP] additionally enforces the [McReferencePolicy] of the runtime on the main chain reference
+//! of verified blocks. Both factories report main chain references to a [McReferenceMonitor], if one is set.
+//!
+//! [PartnerChainsNodeBuilder]: crate::PartnerChainsNodeBuilder
+use crate::PartnerChainsFeature;
+use async_trait::async_trait;
+use sc_consensus_aura::{SlotDuration, find_pre_digest};
+use sidechain_domain::{
+	McBlockHash, McEpochNumber, McReferencePolicy, mainchain_epoch::MainchainEpochConfig,
+};
+use sidechain_mc_hash::{
+	McHashDataSource, McHashInherentDataProvider as McHashIDP, McHashInherentError,
+	monitor::McReferenceMonitor,
+};
+use sp_api::{ApiExt, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_consensus_aura::{
+	Slot, inherents::InherentDataProvider as AuraIDP, sr25519::AuthorityPair as AuraPair,
+};
+use sp_core::Pair;
+use sp_inherents::{CreateInherentDataProviders, InherentData, InherentDataProvider};
+use sp_partner_chains_consensus_aura::CurrentSlotProvider;
+use sp_runtime::traits::{Block as BlockT, Header, Zero};
+use sp_sidechain::{GetEpochScheduleApi, GetMcReferencePolicyApi};
+use sp_timestamp::{InherentDataProvider as TimestampIDP, Timestamp};
+use std::{error::Error, sync::Arc};
+use time_source::TimeSource;
+
+/// Configuration shared by inherent data provider factories
+#[derive(Clone)]
+pub struct CreateInherentDataConfig {
+	/// Cardano main chain epoch configuration
+	pub mc_epoch_config: MainchainEpochConfig,
+	/// Duration of Partner Chain slots
+	pub slot_duration: SlotDuration,
+	/// Source of the current time
+	pub time_source: Arc<dyn TimeSource + Send + Sync>,
+}
+
+impl CreateInherentDataConfig {
+	/// Creates a new [CreateInherentDataConfig]
+	pub fn new(
+		mc_epoch_config: MainchainEpochConfig,
+		slot_duration: SlotDuration,
+		time_source: Arc<dyn TimeSource + Send + Sync>,
+	) -> Self {
+		Self { mc_epoch_config, slot_duration, time_source }
+	}
+
+	/// Returns the starting timestamp of `slot`
+	pub fn slot_start_time(&self, slot: Slot) -> Timestamp {
+		Timestamp::new(self.slot_duration.as_millis() * u64::from(slot))
+	}
+
+	fn current_timestamp_and_slot(&self) -> (AuraIDP, TimestampIDP) {
+		let timestamp =
+			TimestampIDP::new(Timestamp::new(self.time_source.get_current_time_millis()));
+		let slot = AuraIDP::from_timestamp_and_slot_duration(*timestamp, self.slot_duration);
+		(slot, timestamp)
+	}
+}
+
+/// Data about the block being proposed or verified, passed to [PartnerChainsFeature]s when
+/// creating their inherent data providers
+#[derive(Clone, Debug)]
+pub struct InherentDataContext<Block: BlockT> {
+	/// Hash of the parent block
+	pub parent_hash: Block::Hash,
+	/// Slot of the block
+	pub slot: Slot,
+	/// Timestamp of the block
+	pub timestamp: Timestamp,
+	/// Cardano block referenced by the block
+	pub mc_hash: McBlockHash,
+	/// Cardano block referenced by the parent block. [None] for the block following genesis
+	pub previous_mc_hash: Option<McBlockHash>,
+	/// Cardano epoch of the block referenced by the block
+	pub mc_epoch: McEpochNumber,
+	/// Cardano main chain epoch configuration
+	pub mc_epoch_config: MainchainEpochConfig,
+}
+
+/// Inherent data provider combining inherent data providers of all enabled [PartnerChainsFeature]s
+#[derive(Default)]
+pub struct FeatureInherentDataProviders(Vec<Box<dyn InherentDataProvider>>);
+
+impl FeatureInherentDataProviders {
+	/// Creates a new [FeatureInherentDataProviders] from a list of inherent data providers
+	pub fn new(providers: Vec<Box<dyn InherentDataProvider>>) -> Self {
+		Self(providers)
+	}
+
+	/// Returns the number of inherent data providers
+	pub fn len(&self) -> usize {
+		self.0.len()
+	}
+
+	/// Returns true if there are no inherent data providers
+	pub fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+}
+
+#[async_trait]
+impl InherentDataProvider for FeatureInherentDataProviders {
+	async fn provide_inherent_data(
+		&self,
+		inherent_data: &mut InherentData,
+	) -> Result<(), sp_inherents::Error> {
+		for provider in self.0.iter() {
+			provider.provide_inherent_data(inherent_data).await?;
+		}
+		Ok(())
+	}
+
+	async fn try_handle_error(
+		&self,
+		identifier: &sp_inherents::InherentIdentifier,
+		error: &[u8],
+	) -> Option<Result<(), sp_inherents::Error>> {
+		for provider in self.0.iter() {
+			if let Some(result) = provider.try_handle_error(identifier, error).await {
+				return Some(result);
+			}
+		}
+		None
+	}
+}
+
+async fn create_feature_providers<Block: BlockT, C>(
+	features: &[Arc<dyn PartnerChainsFeature<Block, C>>],
+	client: &C,
+	context: &InherentDataContext<Block>,
+) -> Result<FeatureInherentDataProviders, Box<dyn Error + Send + Sync>> {
+	let mut providers = Vec::with_capacity(features.len());
+	for feature in features {
+		providers.push(feature.create_inherent_data_provider(client, context).await?);
+	}
+	Ok(FeatureInherentDataProviders::new(providers))
+}
+
+/// Inherent data provider factory used for proposing new blocks
+pub struct ProposalCIDP<Block: BlockT, C> {
+	config: CreateInherentDataConfig,
+	client: Arc<C>,
+	mc_hash_data_source: Arc<dyn McHashDataSource + Send + Sync>,
+	features: Vec<Arc<dyn PartnerChainsFeature<Block, C>>>,
+	mc_reference_monitor: Option<Arc<McReferenceMonitor>>,
+}
+
+impl<Block: BlockT, C> ProposalCIDP<Block, C> {
+	/// Creates a new [ProposalCIDP]
+	pub fn new(
+		config: CreateInherentDataConfig,
+		client: Arc<C>,
+		mc_hash_data_source: Arc<dyn McHashDataSource + Send + Sync>,
+		features: Vec<Arc<dyn PartnerChainsFeature<Block, C>>>,
+	) -> Self {
+		Self { config, client, mc_hash_data_source, features, mc_reference_monitor: None }
+	}
+
+	/// Sets the monitor observing main chain references of proposed blocks
+	pub fn with_mc_reference_monitor(mut self, monitor: Arc<McReferenceMonitor>) -> Self {
+		self.mc_reference_monitor = Some(monitor);
+		self
+	}
+}
+
+#[async_trait]
+impl<Block, C> CreateInherentDataProviders<Block, ()> for ProposalCIDP<Block, C>
+where
+	Block: BlockT,
+	C: HeaderBackend<Block> + Send + Sync + 'static,
+{
+	type InherentDataProviders = (AuraIDP, TimestampIDP, McHashIDP, FeatureInherentDataProviders);
+
+	async fn create_inherent_data_providers(
+		&self,
+		parent_hash: Block::Hash,
+		_extra_args: (),
+	) -> Result<Self::InherentDataProviders, Box<dyn Error + Send + Sync>> {
+		let Self { config, client, mc_hash_data_source, features, mc_reference_monitor } = self;
+
+		let (slot, timestamp) = config.current_timestamp_and_slot();
+		let parent_header = client.expect_header(parent_hash)?;
+
+		// note: We pass slot start time to `McHashIDP` instead of timestamp for backward compatibility
+		// with the old `McHashIDP` version that was slot-based.
+		let slot_start_timestamp = config.slot_start_time(*slot);
+		let mc_hash = McHashIDP::new_proposal(
+			parent_header,
+			mc_hash_data_source.as_ref(),
+			slot_start_timestamp,
+		)
+		.await?;
+		if let Some(monitor) = mc_reference_monitor {
+			monitor.observe(mc_hash.mc_block(), *timestamp);
+		}
+
+		let context = InherentDataContext {
+			parent_hash,
+			slot: *slot,
+			timestamp: *timestamp,
+			mc_hash: mc_hash.mc_hash(),
+			previous_mc_hash: mc_hash.previous_mc_hash(),
+			mc_epoch: mc_hash.mc_epoch(),
+			mc_epoch_config: config.mc_epoch_config.clone(),
+		};
+		let feature_providers =
+			create_feature_providers(features, client.as_ref(), &context).await?;
+
+		Ok((slot, timestamp, mc_hash, feature_providers))
+	}
+}
+
+/// Inherent data provider factory used for verifying imported blocks
+pub struct VerifierCIDP<Block: BlockT, C> {
+	config: CreateInherentDataConfig,
+	client: Arc<C>,
+	mc_hash_data_source: Arc<dyn McHashDataSource + Send + Sync>,
+	features: Vec<Arc<dyn PartnerChainsFeature<Block, C>>>,
+	mc_reference_monitor: Option<Arc<McReferenceMonitor>>,
+}
+
+impl<Block: BlockT, C> VerifierCIDP<Block, C> {
+	/// Creates a new [VerifierCIDP]
+	pub fn new(
+		config: CreateInherentDataConfig,
+		client: Arc<C>,
+		mc_hash_data_source: Arc<dyn McHashDataSource + Send + Sync>,
+		features: Vec<Arc<dyn PartnerChainsFeature<Block, C>>>,
+	) -> Self {
+		Self { config, client, mc_hash_data_source, features, mc_reference_monitor: None }
+	}
+
+	/// Sets the monitor observing main chain references of verified blocks
+	pub fn with_mc_reference_monitor(mut self, monitor: Arc<McReferenceMonitor>) -> Self {
+		self.mc_reference_monitor = Some(monitor);
+		self
+	}
+}
+
+impl<Block: BlockT, C: Send + Sync> CurrentSlotProvider for VerifierCIDP<Block, C> {
+	fn slot(&self) -> Slot {
+		*self.config.current_timestamp_and_slot().0
+	}
+}
+
+#[async_trait]
+impl<Block, C> CreateInherentDataProviders<Block, (Slot, McBlockHash)> for VerifierCIDP<Block, C>
+where
+	Block: BlockT,
+	C: HeaderBackend<Block> + ProvideRuntimeApi<Block> + Send + Sync + 'static,
+	C::Api: GetMcReferencePolicyApi<Block> + GetEpochScheduleApi<Block>,
+{
+	type InherentDataProviders = (TimestampIDP, FeatureInherentDataProviders);
+
+	async fn create_inherent_data_providers(
+		&self,
+		parent_hash: Block::Hash,
+		(verified_block_slot, mc_hash): (Slot, McBlockHash),
+	) -> Result<Self::InherentDataProviders, Box<dyn Error + Send + Sync>> {
+		let Self { config, client, mc_hash_data_source, features, mc_reference_monitor } = self;
+
+		// note: Because it's not exposed during block verification, we are approximating the block
+		// timestamp by the starting timestamp of the slots. This is also needed for backward compatibility
+		// of [McHashIDP] for chains that used the old slot-based version of it.
+		let timestamp = TimestampIDP::new(config.slot_start_time(verified_block_slot));
+
+		let parent_header = client.expect_header(parent_hash)?;
+		let parent_slot = slot_from_predigest::<Block>(&parent_header)?;
+		let parent_slot_timestamp = parent_slot.map(|slot| config.slot_start_time(slot));
+
+		let mc_state_reference = McHashIDP::new_verification(
+			parent_header,
+			parent_slot_timestamp,
+			*timestamp,
+			mc_hash.clone(),
+			mc_hash_data_source.as_ref(),
+		)
+		.await
+		.map_err(McHashInherentError::into_verification_error)?;
+
+		if let Some(parent_slot_timestamp) = parent_slot_timestamp {
+			verify_mc_reference_policy::<Block, _>(
+				client.as_ref(),
+				parent_hash,
+				parent_slot_timestamp,
+				*timestamp,
+				&mc_state_reference,
+				mc_hash_data_source.as_ref(),
+			)
+			.await?;
+		}
+		if let Some(monitor) = mc_reference_monitor {
+			monitor.observe(mc_state_reference.mc_block(), *timestamp);
+		}
+
+		let context = InherentDataContext {
+			parent_hash,
+			slot: verified_block_slot,
+			timestamp: *timestamp,
+			mc_hash,
+			previous_mc_hash: mc_state_reference.previous_mc_hash(),
+			mc_epoch: mc_state_reference.mc_epoch(),
+			mc_epoch_config: config.mc_epoch_config.clone(),
+		};
+		let feature_providers =
+			create_feature_providers(features, client.as_ref(), &context).await?;
+
+		Ok((timestamp, feature_providers))
+	}
+}
+
+/// Verifies the main chain reference of a block against the [McReferencePolicy] of the runtime.
+///
+/// Runtimes not exposing [GetMcReferencePolicyApi] are treated as having the default, disabled policy.
+async fn verify_mc_reference_policy<Block, C>(
+	client: &C,
+	parent_hash: Block::Hash,
+	parent_timestamp: Timestamp,
+	timestamp: Timestamp,
+	mc_state_reference: &McHashIDP,
+	mc_hash_data_source: &(dyn McHashDataSource + Send + Sync),
+) -> Result<(), Box<dyn Error + Send + Sync>>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block>,
+	C::Api: GetMcReferencePolicyApi<Block> + GetEpochScheduleApi<Block>,
+{
+	let (policy, starts_new_epoch) = {
+		let api = client.runtime_api();
+		let policy = if api.has_api::<dyn GetMcReferencePolicyApi<Block>>(parent_hash)? {
+			api.get_mc_reference_policy(parent_hash)?
+		} else {
+			McReferencePolicy::default()
+		};
+		let starts_new_epoch = if policy.min_advance_per_epoch > 0 {
+			let schedule = api.get_epoch_schedule(parent_hash)?;
+			let epoch_for = |timestamp: Timestamp| {
+				schedule.epoch_for_timestamp(timestamp.as_millis()).ok_or_else(|| {
+					format!(
+						"Timestamp {timestamp:?} is earlier than the start of the epoch schedule"
+					)
+				})
+			};
+			epoch_for(timestamp)? != epoch_for(parent_timestamp)?
+		} else {
+			false
+		};
+		(policy, starts_new_epoch)
+	};
+	mc_state_reference
+		.verify_reference_policy(&policy, timestamp, starts_new_epoch, mc_hash_data_source)
+		.await
+		.map_err(McHashInherentError::into_verification_error)
+}
+
+/// Returns the Aura slot of the block with `header`. [None] is returned for genesis.
+pub fn slot_from_predigest<Block: BlockT>(
+	header: &Block::Header,
+) -> Result<Option<Slot>, Box<dyn Error + Send + Sync>> {
+	if header.number().is_zero() {
+		// genesis block doesn't have a slot
+		Ok(None)
+	} else {
+		Ok(Some(find_pre_digest::<Block, <AuraPair as Pair>::Signature>(header)?))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_inherents::InherentIdentifier;
+
+	const FIRST_ID: InherentIdentifier = *b"first___";
+	const SECOND_ID: InherentIdentifier = *b"second__";
+
+	struct ConstantIDP(InherentIdentifier, u32);
+
+	#[async_trait]
+	impl InherentDataProvider for ConstantIDP {
+		async fn provide_inherent_data(
+			&self,
+			inherent_data: &mut InherentData,
+		) -> Result<(), sp_inherents::Error> {
+			inherent_data.put_data(self.0, &self.1)
+		}
+
+		async fn try_handle_error(
+			&self,
+			identifier: &InherentIdentifier,
+			_error: &[u8],
+		) -> Option<Result<(), sp_inherents::Error>> {
+			(*identifier == self.0)
+				.then(|| Err(sp_inherents::Error::Application(self.1.to_string().into())))
+		}
+	}
+
+	fn providers() -> FeatureInherentDataProviders {
+		FeatureInherentDataProviders::new(vec![
+			Box::new(ConstantIDP(FIRST_ID, 1)),
+			Box::new(ConstantIDP(SECOND_ID, 2)),
+		])
+	}
+
+	#[tokio::test]
+	async fn feature_providers_provide_inherent_data_of_all_providers() {
+		let mut inherent_data = InherentData::new();
+
+		providers().provide_inherent_data(&mut inherent_data).await.unwrap();
+
+		assert_eq!(inherent_data.get_data::<u32>(&FIRST_ID).unwrap(), Some(1));
+		assert_eq!(inherent_data.get_data::<u32>(&SECOND_ID).unwrap(), Some(2));
+	}
+
+	#[tokio::test]
+	async fn feature_providers_delegate_error_handling_to_matching_provider() {
+		let providers = providers();
+
+		let result = providers.try_handle_error(&SECOND_ID, &[]).await;
+		assert_eq!(result.unwrap().unwrap_err().to_string(), "2");
+
+		assert!(providers.try_handle_error(b"unknown_", &[]).await.is_none());
+	}
+}
@@ -0,0 +1,39 @@
+//! Builder wiring Partner Chains toolkit components into a Substrate node.
+//!
+//! Partner Chain nodes need inherent data providers, an import queue, a block proposer and RPC modules
+//! that are aware of the Cardano main chain and of the Partner Chains features used by their runtime.
+//! This crate provides [PartnerChainsNodeBuilder] which creates all of these, so that a node's service
+//! only needs to provide its configuration and data sources.
+//!
+//! # Usage
+//!
+//! ```rust,ignore
+//! let builder = PartnerChainsNodeBuilder::new(inherent_config, client.clone(), data_sources.mc_hash)
+//! 	.with_committee_selection::<CrossChainPublic, SessionKeys>(data_sources.authority_selection)
+//! 	.with_block_participation::<BlockAuthor, DelegatorKey>(data_sources.block_participation)
+//! 	.with_governed_map(data_sources.governed_map)
+//! 	.with_bridge::<AccountId>(data_sources.bridge);
+//!
+//! let import_queue = builder.import_queue(import_queue_params, equivocation_reporter)?;
+//! let aura = builder.start_aura(start_aura_params)?;
+//! let rpc_module = builder.rpc_module(data_sources.sidechain_rpc)?;
+//! ```
+//!
+//! Features that are not provided by the toolkit can be added by implementing [PartnerChainsFeature]
+//! and enabling them with [PartnerChainsNodeBuilder::with_feature].
+//!
+//! Diagnostics of imported blocks rejected by the inherent checks can be enabled with
+//! [PartnerChainsNodeBuilder::with_inherent_diagnostics], see [diagnostics].
+//!
+//! Grandpa finality settings of a network are read from the chain spec, see [chain_spec].
+#![deny(missing_docs)]
+
+mod builder;
+pub mod chain_spec;
+pub mod diagnostics;
+pub mod features;
+pub mod inherent_data;
+
+pub use builder::PartnerChainsNodeBuilder;
+pub use features::PartnerChainsFeature;
+pub use inherent_data::CreateInherentDataConfig;
@@ -605,6 +605,17 @@ pub(crate) mod config_fields {
 			_marker: PhantomData,
 		};
 
+	pub(crate) const FINALITY: ConfigFieldDefinition<
+		'static,
+		crate::create_chain_spec::FinalityConfig,
+	> = ConfigFieldDefinition {
+		config_file: ConfigFile::Chain,
+		path: &["finality"],
+		name: "finality configuration",
+		default: None,
+		_marker: PhantomData,
+	};
+
 	pub(crate) const CARDANO_SECURITY_PARAMETER: ConfigFieldDefinition<'static, u64> =
 		ConfigFieldDefinition {
 			config_file: ConfigFile::Chain,
@@ -5,6 +5,7 @@ use crate::runtime_bindings::PartnerChainRuntime;
 use crate::{CmdRun, config::config_fields};
 use anyhow::anyhow;
 use authority_selection_inherents::MaybeFromCandidateKeys;
+pub use partner_chains_node_builder::chain_spec::FinalityConfig;
 use sidechain_domain::{AssetName, MainchainAddress, PolicyId, ScEpochDuration, UtxoId};
 use sp_core::ecdsa;
 use sp_runtime::{AccountId32, DeserializeOwned};
@@ -77,6 +78,13 @@ impl<T: PartnerChainRuntime> CreateChainSpecCmd<T> {
 			"- asset policy ID: {}",
 			config.governed_map_asset_policy_id.clone().unwrap_or_default().to_hex_string()
 		));
+		context.print("Finality Configuration:");
+		context.print(&format!("- gossip duration: {}ms", config.finality.gossip_duration_millis));
+		context.print(&format!(
+			"- justification period: {} blocks",
+			config.finality.justification_period
+		));
+		context.print(&format!("- observer enabled: {}", config.finality.observer_enabled));
 		use colored::Colorize;
 		if config.initial_permissioned_candidates_parsed.is_empty() {
 			context.print("WARNING: The list of initial permissioned candidates is empty. Generated chain spec will not allow the chain to start.".red().to_string().as_str());
@@ -112,6 +120,7 @@ pub struct CreateChainSpecConfig<Keys> {
 	pub illiquid_circulation_supply_validator_address: MainchainAddress,
 	pub governed_map_validator_address: Option<MainchainAddress>,
 	pub governed_map_asset_policy_id: Option<PolicyId>,
+	pub finality: FinalityConfig,
 }
 
 impl<Keys: MaybeFromCandidateKeys> CreateChainSpecConfig<Keys> {
@@ -123,6 +132,11 @@ impl<Keys: MaybeFromCandidateKeys> CreateChainSpecConfig<Keys> {
 				.iter()
 				.map(TryFrom::try_from)
 				.collect::<Result<Vec<ParsedPermissionedCandidatesKeys<Keys>>, anyhow::Error>>()?;
+		let finality: FinalityConfig =
+			config_fields::FINALITY.load_from_file(c).unwrap_or_default();
+		finality
+			.validate()
+			.map_err(|e| anyhow!("Invalid finality configuration: {e}"))?;
 		Ok(Self {
 			bootnodes: load_config_field(c, &config_fields::BOOTNODES)?,
 			genesis_utxo: load_config_field(c, &config_fields::GENESIS_UTXO)?,
@@ -146,6 +160,7 @@ impl<Keys: MaybeFromCandidateKeys> CreateChainSpecConfig<Keys> {
 			governed_map_validator_address: config_fields::GOVERNED_MAP_VALIDATOR_ADDRESS
 				.load_from_file(c),
 			governed_map_asset_policy_id: config_fields::GOVERNED_MAP_POLICY_ID.load_from_file(c),
+			finality,
 		})
 	}
 
@@ -258,6 +273,7 @@ impl<T> Default for CreateChainSpecConfig<T> {
 			illiquid_circulation_supply_validator_address: Default::default(),
 			governed_map_validator_address: Default::default(),
 			governed_map_asset_policy_id: Default::default(),
+			finality: Default::default(),
 		}
 	}
 }
@@ -4,6 +4,9 @@ use crate::tests::runtime::{MockRuntime, TestSessionKeys};
 use crate::tests::{CHAIN_CONFIG_FILE_PATH, MockIO, MockIOContext};
 use crate::{CmdRun, KeyDefinition, verify_json};
 use colored::Colorize;
+use partner_chains_node_builder::chain_spec::{
+	DEFAULT_GOSSIP_DURATION_MILLIS, DEFAULT_JUSTIFICATION_PERIOD,
+};
 use sidechain_domain::ScEpochDuration;
 
 impl PartnerChainRuntime for MockRuntime {
@@ -19,6 +22,7 @@ impl PartnerChainRuntime for MockRuntime {
 			"sidechain": config.pallet_sidechain_config::<MockRuntime>(ScEpochDuration::from_millis(60_000)),
 			"governedMap":config.governed_map_config::<MockRuntime>(),
 			"bridge":config.bridge_config::<MockRuntime>(),
+			"finality": config.finality,
 		})
 	}
 
@@ -50,6 +54,44 @@ fn happy_path() {
 	verify_json!(mock_context, "chain-spec.json", generated_chain_spec())
 }
 
+#[test]
+fn uses_finality_configuration_from_config_file() {
+	let mut config = test_config_content();
+	config["finality"] = serde_json::json!({ "justificationPeriod": 64, "observerEnabled": true });
+	let mock_context = MockIOContext::new()
+		.with_json_file(CHAIN_CONFIG_FILE_PATH, config)
+		.with_expected_io(vec![
+			show_intro(),
+			show_chain_parameters_with_finality(DEFAULT_GOSSIP_DURATION_MILLIS, 64, true),
+			show_initial_permissioned_candidates(),
+			MockIO::prompt_yes_no("Do you want to continue?", true, true),
+			show_outro(),
+		]);
+	let result = create_chain_spec_cmd().run(&mock_context);
+	result.expect("should succeed");
+	let mut expected_chain_spec = generated_chain_spec();
+	expected_chain_spec["finality"] = serde_json::json!({
+		"gossipDurationMillis": DEFAULT_GOSSIP_DURATION_MILLIS,
+		"justificationPeriod": 64,
+		"observerEnabled": true
+	});
+	verify_json!(mock_context, "chain-spec.json", expected_chain_spec)
+}
+
+#[test]
+fn rejects_zero_finality_settings() {
+	for finality in [
+		serde_json::json!({ "justificationPeriod": 0 }),
+		serde_json::json!({ "gossipDurationMillis": 0 }),
+	] {
+		let mut config = test_config_content();
+		config["finality"] = finality;
+		let mock_context = MockIOContext::new().with_json_file(CHAIN_CONFIG_FILE_PATH, config);
+		let result = create_chain_spec_cmd().run(&mock_context);
+		result.expect_err("should return error");
+	}
+}
+
 #[test]
 fn shows_warning_when_initial_candidates_are_empty() {
 	let mock_context = MockIOContext::new()
@@ -170,6 +212,18 @@ fn show_intro() -> MockIO {
 }
 
 fn show_chain_parameters() -> MockIO {
+	show_chain_parameters_with_finality(
+		DEFAULT_GOSSIP_DURATION_MILLIS,
+		DEFAULT_JUSTIFICATION_PERIOD,
+		false,
+	)
+}
+
+fn show_chain_parameters_with_finality(
+	gossip_duration_millis: u64,
+	justification_period: u32,
+	observer_enabled: bool,
+) -> MockIO {
 	MockIO::Group(vec![
 		MockIO::print("Chain parameters:"),
 		MockIO::print(
@@ -200,6 +254,10 @@ fn show_chain_parameters() -> MockIO {
 		MockIO::print(
 			"- asset policy ID: 0xc814db91bfaf7f0078e2c69d13443ffc46c9957393174f7baa8d0000",
 		),
+		MockIO::print("Finality Configuration:"),
+		MockIO::print(&format!("- gossip duration: {gossip_duration_millis}ms")),
+		MockIO::print(&format!("- justification period: {justification_period} blocks")),
+		MockIO::print(&format!("- observer enabled: {observer_enabled}")),
 	])
 }
 
@@ -270,6 +328,11 @@ fn generated_chain_spec() -> serde_json::Value {
 					"token_policy_id": "0xada83ddd029614381f00e28de0922ab0dec6983ea9dd29ae20eef9b4",
 				},
 				"marker": null,
+			},
+			"finality": {
+				"gossipDurationMillis": DEFAULT_GOSSIP_DURATION_MILLIS,
+				"justificationPeriod": DEFAULT_JUSTIFICATION_PERIOD,
+				"observerEnabled": false
 			}
 		}
 	)
@@ -24,7 +24,7 @@ mod start_node;
 mod tests;
 
 use clap::Parser;
-pub use create_chain_spec::CreateChainSpecConfig;
+pub use create_chain_spec::{CreateChainSpecConfig, FinalityConfig};
 pub use io::DefaultCmdRunContext;
 use io::*;
 pub use keystore::{AURA, CROSS_CHAIN, GRANDPA, KeyDefinition};
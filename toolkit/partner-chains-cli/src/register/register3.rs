@@ -58,7 +58,8 @@ impl CmdRun for Register3Cmd {
 			stake_ownership: AdaBasedStaking {
 				pub_key: self.spo_public_key.clone(),
 				signature: self.spo_signature.clone(),
-			},
+			}
+			.into(),
 			partner_chain_pub_key: self.partner_chain_pub_key.clone(),
 			partner_chain_signature: self.partner_chain_signature.clone(),
 			own_pkh: payment_signing_key.to_pub_key_hash(),
@@ -459,7 +460,7 @@ mod tests {
 			stake_ownership: AdaBasedStaking {
 				pub_key: StakePoolPublicKey(hex!("cef2d1630c034d3b9034eb7903d61f419a3074a1ad01d4550cc72f2b733de6e7")),
 				signature: MainchainSignature(hex!("aaa39fbf163ed77c69820536f5dc22854e7e13f964f1e077efde0844a09bde64c1aab4d2b401e0fe39b43c91aa931cad26fa55c8766378462c06d86c85134801"))
-			},
+			}.into(),
 			partner_chain_pub_key: SidechainPublicKey(hex!("020a1091341fe5664bfa1782d5e04779689068c916b04cb365ec3153755684d9a1").to_vec()),
 			partner_chain_signature: SidechainSignature(hex!("cb6df9de1efca7a3998a8ead4e02159d5fa99c3e0d4fd6432667390bb4726854").to_vec()),
 			own_pkh: MainchainKeyHash(hex!("7fa48bb8fb5d6804fad26237738ce490d849e4567161e38ab8415ff3")),
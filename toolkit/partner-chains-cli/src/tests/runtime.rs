@@ -112,6 +112,7 @@ impl pallet_sidechain::Config for MockRuntime {
 		0
 	}
 	type OnNewEpoch = ();
+	type GovernanceOrigin = EnsureRoot<MockRuntime>;
 }
 
 impl pallet_partner_chains_bridge::Config for MockRuntime {
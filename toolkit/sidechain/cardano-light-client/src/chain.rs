@@ -0,0 +1,176 @@
+//! Chain of verified Cardano headers
+
+use crate::{
+	header::PraosHeader,
+	verifier::{EpochSnapshotSource, HeaderVerificationError, HeaderVerifier, VrfVerifier},
+};
+use log::debug;
+use sidechain_domain::{
+	MainchainBlock, MainchainKeyHash, McBlockHash, McSlotNumber,
+	mainchain_epoch::MainchainEpochConfig,
+};
+use std::collections::{BTreeMap, VecDeque};
+
+/// Error returned when extending or rolling back a [HeaderChain] fails
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum HeaderChainError {
+	/// Signals that the header is not valid
+	#[error("{0}")]
+	InvalidHeader(#[from] HeaderVerificationError),
+	/// Signals that the header's operational certificate counter is lower than previously seen
+	/// or increased by more than one
+	#[error("Header {0} operational certificate counter {1} is invalid, last seen counter is {2}")]
+	InvalidOpCertCounter(McBlockHash, u64, u64),
+	/// Signals that the rollback target is not among the retained headers
+	#[error("Rollback target {0} not found in the header chain")]
+	RollbackTargetNotFound(McBlockHash),
+	/// Signals that the rollback target is deeper than the security parameter
+	#[error("Rollback to {0} exceeds the security parameter")]
+	RollbackTooDeep(McBlockHash),
+}
+
+/// Chain of Cardano headers verified by the light client, starting from a trusted checkpoint.
+///
+/// Headers can be obtained from any, possibly untrusted, source, eg. the chain-sync protocol of a Cardano
+/// relay node, and are added to the chain using [HeaderChain::roll_forward] only if they are valid.
+pub struct HeaderChain<V> {
+	verifier: HeaderVerifier<V>,
+	snapshots: Box<dyn EpochSnapshotSource + Send + Sync>,
+	/// Retained headers, from the oldest to the tip
+	headers: VecDeque<PraosHeader>,
+	/// Operational certificate counters of pools before the oldest retained header
+	base_opcert_counters: BTreeMap<MainchainKeyHash, u64>,
+	/// Operational certificate counters of pools at the tip
+	opcert_counters: BTreeMap<MainchainKeyHash, u64>,
+	max_headers: usize,
+}
+
+impl<V: VrfVerifier> HeaderChain<V> {
+	/// Creates a new chain starting at the trusted `checkpoint` header.
+	///
+	/// At most `max_headers` latest headers are retained, which should be more than the security parameter.
+	/// Older headers are pruned and can not be found anymore.
+	pub fn new(
+		verifier: HeaderVerifier<V>,
+		snapshots: Box<dyn EpochSnapshotSource + Send + Sync>,
+		checkpoint: PraosHeader,
+		max_headers: usize,
+	) -> Self {
+		let security_parameter = verifier.params().security_parameter as usize;
+		let opcert_counters = BTreeMap::from([(
+			checkpoint.issuer_pool_id(),
+			checkpoint.operational_cert.sequence_number,
+		)]);
+		Self {
+			verifier,
+			snapshots,
+			headers: VecDeque::from([checkpoint]),
+			base_opcert_counters: BTreeMap::new(),
+			opcert_counters,
+			max_headers: max_headers.max(security_parameter + 1),
+		}
+	}
+
+	/// Returns the latest verified header
+	pub fn tip(&self) -> &PraosHeader {
+		self.headers.back().expect("chain always contains at least the checkpoint")
+	}
+
+	/// Returns the oldest retained header
+	pub fn oldest(&self) -> &PraosHeader {
+		self.headers.front().expect("chain always contains at least the checkpoint")
+	}
+
+	/// Returns the retained header with `hash`
+	pub fn find(&self, hash: &McBlockHash) -> Option<&PraosHeader> {
+		self.headers.iter().rev().find(|header| header.hash() == *hash)
+	}
+
+	/// Returns true if `header` has at least security parameter blocks on top of it
+	pub fn is_stable(&self, header: &PraosHeader) -> bool {
+		let security_parameter = self.verifier.params().security_parameter;
+		self.tip().block_number.0.saturating_sub(header.block_number.0) >= security_parameter
+	}
+
+	/// Returns retained stable headers, from the latest to the oldest
+	pub fn stable_headers(&self) -> impl Iterator<Item = &PraosHeader> {
+		self.headers.iter().rev().filter(|header| self.is_stable(header))
+	}
+
+	/// Verifies `header` and adds it on top of the chain
+	pub fn roll_forward(&mut self, header: PraosHeader) -> Result<(), HeaderChainError> {
+		let epoch = self.verifier.epoch_of(&header)?;
+		let snapshot = self
+			.snapshots
+			.epoch_snapshot(epoch)
+			.ok_or(HeaderVerificationError::SnapshotNotFound(epoch))?;
+		self.verifier.verify(&header, self.tip(), &snapshot)?;
+		apply_opcert_counter(&mut self.opcert_counters, &header)?;
+
+		debug!("🔗 Verified Cardano header {} at slot {}", header.hash(), header.slot.0);
+		self.headers.push_back(header);
+		while self.headers.len() > self.max_headers {
+			if let Some(pruned) = self.headers.pop_front() {
+				let pool_id = pruned.issuer_pool_id();
+				self.base_opcert_counters
+					.insert(pool_id, pruned.operational_cert.sequence_number);
+			}
+		}
+		Ok(())
+	}
+
+	/// Rolls the chain back to the header with `hash`, removing all headers after it
+	pub fn roll_back(&mut self, hash: &McBlockHash) -> Result<(), HeaderChainError> {
+		let target = self
+			.find(hash)
+			.ok_or_else(|| HeaderChainError::RollbackTargetNotFound(hash.clone()))?;
+		if self.is_stable(target) {
+			return Err(HeaderChainError::RollbackTooDeep(hash.clone()));
+		}
+		while self.tip().hash() != *hash {
+			self.headers.pop_back();
+		}
+		self.opcert_counters = self.base_opcert_counters.clone();
+		for header in self.headers.iter() {
+			apply_opcert_counter(&mut self.opcert_counters, header)?;
+		}
+		Ok(())
+	}
+
+	/// Returns the [MainchainBlock] of `header`, or [None] if its epoch or timestamp can not be computed
+	pub fn to_mainchain_block(&self, header: &PraosHeader) -> Option<MainchainBlock> {
+		let epoch_config = &self.verifier.params().epoch_config;
+		Some(MainchainBlock {
+			number: header.block_number,
+			hash: header.hash(),
+			epoch: self.verifier.epoch_of(header).ok()?,
+			slot: header.slot,
+			timestamp: slot_start_millis(epoch_config, header.slot)? / 1000,
+		})
+	}
+}
+
+fn apply_opcert_counter(
+	counters: &mut BTreeMap<MainchainKeyHash, u64>,
+	header: &PraosHeader,
+) -> Result<(), HeaderChainError> {
+	let pool_id = header.issuer_pool_id();
+	let counter = header.operational_cert.sequence_number;
+	match counters.get(&pool_id).copied() {
+		Some(last) if counter < last || counter > last + 1 => {
+			Err(HeaderChainError::InvalidOpCertCounter(header.hash(), counter, last))
+		},
+		_ => {
+			counters.insert(pool_id, counter);
+			Ok(())
+		},
+	}
+}
+
+fn slot_start_millis(epoch_config: &MainchainEpochConfig, slot: McSlotNumber) -> Option<u64> {
+	let slots_since_first = slot.0.checked_sub(epoch_config.first_slot_number)?;
+	Some(
+		epoch_config.first_epoch_timestamp_millis.unix_millis()
+			+ slots_since_first * epoch_config.slot_duration_millis.millis(),
+	)
+}
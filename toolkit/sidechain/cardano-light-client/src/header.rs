@@ -0,0 +1,186 @@
+//! Decoding of Cardano Praos block headers
+
+use minicbor::{Decoder, data::Type};
+use sidechain_domain::{
+	MainchainKeyHash, McBlockHash, McBlockNumber, McSlotNumber, StakePoolPublicKey, crypto::blake2b,
+};
+
+/// Error returned when decoding a [PraosHeader] fails
+#[derive(Debug, thiserror::Error)]
+pub enum HeaderDecodingError {
+	/// Signals that the header bytes are not valid CBOR or do not follow the header structure
+	#[error("Invalid header CBOR: {0}")]
+	Cbor(#[from] minicbor::decode::Error),
+	/// Signals that a CBOR array in the header has unexpected number of elements
+	#[error("Header {0} has {1:?} elements, expected {2}")]
+	InvalidArrayLength(&'static str, Option<u64>, u64),
+	/// Signals that a fixed-length header field has unexpected length
+	#[error("Header field {field} has length {actual}, expected {expected}")]
+	InvalidFieldLength {
+		/// Name of the field
+		field: &'static str,
+		/// Expected length of the field
+		expected: usize,
+		/// Actual length of the field
+		actual: usize,
+	},
+	/// Signals that there are bytes left after the decoded header
+	#[error("Unexpected {0} bytes after the header")]
+	TrailingBytes(usize),
+}
+
+/// Operational certificate of a Cardano block issuer, delegating block signing rights
+/// from the stake pool's cold key to a KES key
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OperationalCert {
+	/// KES verification key
+	pub hot_vkey: [u8; 32],
+	/// Counter of operational certificates issued by the stake pool
+	pub sequence_number: u64,
+	/// KES period from which the certificate is valid
+	pub kes_period: u64,
+	/// Signature of the certificate made with the stake pool's cold key
+	pub sigma: [u8; 64],
+}
+
+impl OperationalCert {
+	/// Returns the bytes signed by the stake pool's cold key
+	pub fn signable_bytes(&self) -> [u8; 48] {
+		let mut bytes = [0u8; 48];
+		bytes[..32].copy_from_slice(&self.hot_vkey);
+		bytes[32..40].copy_from_slice(&self.sequence_number.to_be_bytes());
+		bytes[40..].copy_from_slice(&self.kes_period.to_be_bytes());
+		bytes
+	}
+}
+
+/// Cardano block header of the Babbage and Conway eras, produced using Ouroboros Praos
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PraosHeader {
+	/// Block number
+	pub block_number: McBlockNumber,
+	/// Slot in which the block was produced
+	pub slot: McSlotNumber,
+	/// Hash of the previous block, [None] for the first block after genesis
+	pub prev_hash: Option<McBlockHash>,
+	/// Cold verification key of the stake pool that produced the block
+	pub issuer_vkey: [u8; 32],
+	/// VRF verification key of the stake pool that produced the block
+	pub vrf_vkey: [u8; 32],
+	/// VRF output proving the stake pool's slot leadership
+	pub vrf_output: Vec<u8>,
+	/// VRF proof of [Self::vrf_output]
+	pub vrf_proof: Vec<u8>,
+	/// Size of the block body in bytes
+	pub block_body_size: u64,
+	/// Hash of the block body
+	pub block_body_hash: [u8; 32],
+	/// Operational certificate of the block issuer
+	pub operational_cert: OperationalCert,
+	/// Protocol version, as major and minor version numbers
+	pub protocol_version: (u64, u64),
+	/// KES signature of the header body
+	pub body_signature: Vec<u8>,
+	hash: McBlockHash,
+	header_body_bytes: Vec<u8>,
+}
+
+impl PraosHeader {
+	/// Decodes a header from its CBOR bytes, as they are hashed to obtain the block hash.
+	///
+	/// Note that headers served by Cardano nodes through the chain-sync protocol are additionally
+	/// wrapped in an era tag, which has to be removed before decoding.
+	pub fn decode(bytes: &[u8]) -> Result<Self, HeaderDecodingError> {
+		let mut d = Decoder::new(bytes);
+		expect_array(&mut d, "header", 2)?;
+
+		let body_start = d.position();
+		expect_array(&mut d, "header body", 10)?;
+		let block_number = McBlockNumber(d.u32()?);
+		let slot = McSlotNumber(d.u64()?);
+		let prev_hash = match d.datatype()? {
+			Type::Null => {
+				d.null()?;
+				None
+			},
+			_ => Some(McBlockHash(fixed_bytes(&mut d, "prev_hash")?)),
+		};
+		let issuer_vkey = fixed_bytes(&mut d, "issuer_vkey")?;
+		let vrf_vkey = fixed_bytes(&mut d, "vrf_vkey")?;
+		expect_array(&mut d, "vrf result", 2)?;
+		let vrf_output = d.bytes()?.to_vec();
+		let vrf_proof = d.bytes()?.to_vec();
+		let block_body_size = d.u64()?;
+		let block_body_hash = fixed_bytes(&mut d, "block_body_hash")?;
+		expect_array(&mut d, "operational certificate", 4)?;
+		let operational_cert = OperationalCert {
+			hot_vkey: fixed_bytes(&mut d, "hot_vkey")?,
+			sequence_number: d.u64()?,
+			kes_period: d.u64()?,
+			sigma: fixed_bytes(&mut d, "sigma")?,
+		};
+		expect_array(&mut d, "protocol version", 2)?;
+		let protocol_version = (d.u64()?, d.u64()?);
+		let header_body_bytes = bytes[body_start..d.position()].to_vec();
+
+		let body_signature = d.bytes()?.to_vec();
+		if d.position() != bytes.len() {
+			return Err(HeaderDecodingError::TrailingBytes(bytes.len() - d.position()));
+		}
+
+		Ok(Self {
+			block_number,
+			slot,
+			prev_hash,
+			issuer_vkey,
+			vrf_vkey,
+			vrf_output,
+			vrf_proof,
+			block_body_size,
+			block_body_hash,
+			operational_cert,
+			protocol_version,
+			body_signature,
+			hash: McBlockHash(blake2b(bytes)),
+			header_body_bytes,
+		})
+	}
+
+	/// Returns the hash of the block, ie. the BLAKE2b-256 hash of the header bytes
+	pub fn hash(&self) -> McBlockHash {
+		self.hash.clone()
+	}
+
+	/// Returns the CBOR bytes of the header body, which are signed with the block issuer's KES key
+	pub fn header_body_bytes(&self) -> &[u8] {
+		&self.header_body_bytes
+	}
+
+	/// Returns the ID of the stake pool that produced the block, ie. the hash of its cold key
+	pub fn issuer_pool_id(&self) -> MainchainKeyHash {
+		StakePoolPublicKey(self.issuer_vkey).hash()
+	}
+}
+
+fn expect_array(
+	d: &mut Decoder,
+	name: &'static str,
+	expected: u64,
+) -> Result<(), HeaderDecodingError> {
+	match d.array()? {
+		Some(len) if len == expected => Ok(()),
+		len => Err(HeaderDecodingError::InvalidArrayLength(name, len, expected)),
+	}
+}
+
+fn fixed_bytes<const N: usize>(
+	d: &mut Decoder,
+	field: &'static str,
+) -> Result<[u8; N], HeaderDecodingError> {
+	let bytes = d.bytes()?;
+	bytes.try_into().map_err(|_| HeaderDecodingError::InvalidFieldLength {
+		field,
+		expected: N,
+		actual: bytes.len(),
+	})
+}
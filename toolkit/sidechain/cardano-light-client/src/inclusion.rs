@@ -0,0 +1,87 @@
+//! Proofs of inclusion of transactions in Cardano blocks, verifiable against block headers
+//!
+//! The body hash of a Cardano block is the hash of concatenated hashes of the four parts of the block body:
+//! transaction bodies, transaction witness sets, auxiliary data and indices of invalid transactions.
+//! A transaction is proven to be included in a block by the CBOR bytes of the block's transaction bodies
+//! and hashes of the other three parts.
+
+use crate::header::PraosHeader;
+use minicbor::{Decoder, data::Type};
+use sidechain_domain::{McTxHash, crypto::blake2b};
+
+/// Error returned when an inclusion proof is not valid
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum InclusionProofError {
+	/// Signals that the proof does not match the block body hash in the header
+	#[error("Inclusion proof does not match the block body hash")]
+	BodyHashMismatch,
+	/// Signals that the transaction bodies in the proof are not valid CBOR
+	#[error("Invalid transaction bodies CBOR: {0}")]
+	InvalidTransactionBodies(String),
+	/// Signals that the transaction is not among the transaction bodies in the proof
+	#[error("Transaction {0} not found in the block")]
+	TransactionNotFound(McTxHash),
+}
+
+/// Proof that a transaction is included in a Cardano block
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransactionInclusionProof {
+	/// CBOR bytes of the array of the block's transaction bodies
+	pub transaction_bodies: Vec<u8>,
+	/// Hash of the CBOR bytes of the block's transaction witness sets
+	pub witnesses_hash: [u8; 32],
+	/// Hash of the CBOR bytes of the block's auxiliary data
+	pub auxiliary_data_hash: [u8; 32],
+	/// Hash of the CBOR bytes of the block's invalid transactions
+	pub invalid_transactions_hash: [u8; 32],
+}
+
+impl TransactionInclusionProof {
+	/// Returns the block body hash committed to by this proof
+	pub fn block_body_hash(&self) -> [u8; 32] {
+		let mut bytes = [0u8; 128];
+		bytes[..32].copy_from_slice(&blake2b::<32>(&self.transaction_bodies));
+		bytes[32..64].copy_from_slice(&self.witnesses_hash);
+		bytes[64..96].copy_from_slice(&self.auxiliary_data_hash);
+		bytes[96..].copy_from_slice(&self.invalid_transactions_hash);
+		blake2b(&bytes)
+	}
+
+	/// Verifies that the transaction with `tx_hash` is included in the block with `header`
+	/// and returns the CBOR bytes of its body, eg. to inspect its outputs
+	pub fn verify<'a>(
+		&'a self,
+		header: &PraosHeader,
+		tx_hash: &McTxHash,
+	) -> Result<&'a [u8], InclusionProofError> {
+		if self.block_body_hash() != header.block_body_hash {
+			return Err(InclusionProofError::BodyHashMismatch);
+		}
+		self.transaction_body(tx_hash)
+	}
+
+	fn transaction_body(&self, tx_hash: &McTxHash) -> Result<&[u8], InclusionProofError> {
+		let invalid = |e: minicbor::decode::Error| {
+			InclusionProofError::InvalidTransactionBodies(e.to_string())
+		};
+		let bytes = &self.transaction_bodies;
+		let mut d = Decoder::new(bytes);
+		let len = d.array().map_err(invalid)?;
+		let mut index = 0;
+		loop {
+			match len {
+				Some(len) if index >= len => break,
+				None if d.datatype().map_err(invalid)? == Type::Break => break,
+				_ => {},
+			}
+			let start = d.position();
+			d.skip().map_err(invalid)?;
+			let body = &bytes[start..d.position()];
+			if blake2b::<32>(body) == tx_hash.0 {
+				return Ok(body);
+			}
+			index += 1;
+		}
+		Err(InclusionProofError::TransactionNotFound(*tx_hash))
+	}
+}
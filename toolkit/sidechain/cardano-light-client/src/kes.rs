@@ -0,0 +1,88 @@
+//! Verification of Cardano KES signatures
+//!
+//! Cardano uses the Sum6KES key evolving signature scheme, which is a binary tree of depth 6
+//! of Ed25519 keys, allowing the key to be evolved 64 times. A signature made in KES period `t`
+//! consists of the Ed25519 signature made with the `t`-th leaf key and the pairs of verification
+//! keys of the children of all nodes on the path from the root to the leaf.
+
+use sidechain_domain::crypto::blake2b;
+
+/// Depth of the KES scheme used by Cardano
+pub const KES_DEPTH: u32 = 6;
+
+/// Number of periods in which a Cardano KES key can be used
+pub const KES_PERIODS: u32 = 1 << KES_DEPTH;
+
+/// Length of a Cardano KES signature in bytes
+pub const KES_SIGNATURE_LEN: usize = ED25519_SIGNATURE_LEN + 2 * VKEY_LEN * KES_DEPTH as usize;
+
+const VKEY_LEN: usize = 32;
+const ED25519_SIGNATURE_LEN: usize = 64;
+
+/// Error returned when a KES signature is invalid
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum KesError {
+	/// Signals that the signature has unexpected length
+	#[error("KES signature has length {0}, expected {KES_SIGNATURE_LEN}")]
+	InvalidSignatureLength(usize),
+	/// Signals that the KES period is beyond the supported number of periods
+	#[error("KES period {0} exceeds the number of KES periods {KES_PERIODS}")]
+	PeriodTooBig(u32),
+	/// Signals that the verification keys in the signature do not hash to the verification key
+	#[error("KES verification key does not match the signature")]
+	VerificationKeyMismatch,
+	/// Signals that the Ed25519 signature is invalid
+	#[error("Invalid KES signature")]
+	InvalidSignature,
+}
+
+/// Verifies Sum6KES `signature` of `message` made in KES `period` by the key with `vkey`
+pub fn verify_kes_signature(
+	vkey: &[u8; 32],
+	period: u32,
+	message: &[u8],
+	signature: &[u8],
+) -> Result<(), KesError> {
+	if signature.len() != KES_SIGNATURE_LEN {
+		return Err(KesError::InvalidSignatureLength(signature.len()));
+	}
+	if period >= KES_PERIODS {
+		return Err(KesError::PeriodTooBig(period));
+	}
+	verify_sum(KES_DEPTH, vkey, period, message, signature)
+}
+
+fn verify_sum(
+	depth: u32,
+	vkey: &[u8; 32],
+	period: u32,
+	message: &[u8],
+	signature: &[u8],
+) -> Result<(), KesError> {
+	if depth == 0 {
+		let vkey = ed25519_zebra::VerificationKey::try_from(*vkey)
+			.map_err(|_| KesError::InvalidSignature)?;
+		let signature: [u8; ED25519_SIGNATURE_LEN] = signature
+			.try_into()
+			.map_err(|_| KesError::InvalidSignatureLength(signature.len()))?;
+		return vkey
+			.verify(&ed25519_zebra::Signature::from(signature), message)
+			.map_err(|_| KesError::InvalidSignature);
+	}
+
+	let (inner_signature, vkeys) = signature.split_at(signature.len() - 2 * VKEY_LEN);
+	if blake2b::<32>(vkeys) != *vkey {
+		return Err(KesError::VerificationKeyMismatch);
+	}
+	let (vkey_0, vkey_1) = vkeys.split_at(VKEY_LEN);
+	let half = 1 << (depth - 1);
+	if period < half {
+		verify_sum(depth - 1, &to_vkey(vkey_0), period, message, inner_signature)
+	} else {
+		verify_sum(depth - 1, &to_vkey(vkey_1), period - half, message, inner_signature)
+	}
+}
+
+fn to_vkey(bytes: &[u8]) -> [u8; 32] {
+	bytes.try_into().expect("split at VKEY_LEN")
+}
@@ -0,0 +1,127 @@
+//! Ouroboros Praos slot leadership check
+//!
+//! A stake pool with relative stake `σ` is a slot leader if its leader value `p`, derived from its VRF
+//! output for the slot, satisfies `p < 1 - (1 - f)^σ`, where `f` is the active slot coefficient.
+//! Like the Cardano ledger, the check is performed as `1 / (1 - p) < exp(-σ * ln(1 - f))` using fixed
+//! point arithmetic with 34 decimal digits and comparing against partial sums of the Taylor series of `exp`.
+
+use num_bigint::{BigInt, Sign};
+use num_traits::{One, Signed, Zero};
+use sidechain_domain::{McSlotNumber, crypto::blake2b};
+
+/// Fraction with [u64] numerator and denominator
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Fraction {
+	/// Numerator of the fraction
+	pub numerator: u64,
+	/// Denominator of the fraction
+	pub denominator: u64,
+}
+
+impl Fraction {
+	/// Creates a new fraction
+	pub fn new(numerator: u64, denominator: u64) -> Self {
+		Self { numerator, denominator }
+	}
+}
+
+/// Maximum number of Taylor series terms computed before giving up the comparison
+const MAX_TAYLOR_TERMS: u32 = 1000;
+
+/// Returns the VRF input for leader election in `slot` of an epoch with `epoch_nonce`
+pub fn vrf_input(slot: McSlotNumber, epoch_nonce: &[u8; 32]) -> [u8; 32] {
+	let mut bytes = [0u8; 40];
+	bytes[..8].copy_from_slice(&slot.0.to_be_bytes());
+	bytes[8..].copy_from_slice(epoch_nonce);
+	blake2b(&bytes)
+}
+
+/// Returns the leader value derived from the block's VRF output
+pub fn leader_value(vrf_output: &[u8]) -> [u8; 32] {
+	let mut bytes = Vec::with_capacity(vrf_output.len() + 1);
+	bytes.push(b'L');
+	bytes.extend_from_slice(vrf_output);
+	blake2b(&bytes)
+}
+
+/// Returns true if `leader_value` makes a stake pool with `relative_stake` a slot leader,
+/// given the `active_slot_coeff` of the chain
+pub fn is_slot_leader(
+	leader_value: &[u8; 32],
+	relative_stake: Fraction,
+	active_slot_coeff: Fraction,
+) -> bool {
+	if relative_stake.numerator == 0 || relative_stake.denominator == 0 {
+		return false;
+	}
+	let scale = scale();
+	let cert_nat_max = BigInt::one() << 256;
+	let leader_nat = BigInt::from_bytes_be(Sign::Plus, leader_value);
+
+	// p = leader_nat / 2^256, recip_q = 1 / (1 - p)
+	let p = &leader_nat * &scale / &cert_nat_max;
+	let one_minus_p = &scale - &p;
+	if one_minus_p.is_zero() {
+		return false;
+	}
+	let recip_q = &scale * &scale / one_minus_p;
+
+	// x = -σ * ln(1 - f)
+	let c = ln_one_minus(active_slot_coeff);
+	let sigma = fixed(relative_stake);
+	let x = -(sigma * c) / &scale;
+
+	matches!(taylor_exp_cmp(&(BigInt::from(3) * &scale), &recip_q, &x), Ordering::Below)
+}
+
+enum Ordering {
+	Above,
+	Below,
+	MaxReached,
+}
+
+fn scale() -> BigInt {
+	BigInt::from(10).pow(34)
+}
+
+fn fixed(fraction: Fraction) -> BigInt {
+	BigInt::from(fraction.numerator) * scale() / BigInt::from(fraction.denominator)
+}
+
+/// Computes `ln(1 - f)` as `-Σ f^k / k`
+fn ln_one_minus(f: Fraction) -> BigInt {
+	let scale = scale();
+	let f = fixed(f);
+	let mut term = f.clone();
+	let mut sum = BigInt::zero();
+	let mut k = 1u32;
+	while !term.is_zero() {
+		sum -= &term / BigInt::from(k);
+		term = &term * &f / &scale;
+		k += 1;
+	}
+	sum
+}
+
+/// Compares `cmp` with `exp(x)` using partial sums of the Taylor series with the error bounded
+/// using `bound_x`, as done by the Cardano ledger
+fn taylor_exp_cmp(bound_x: &BigInt, cmp: &BigInt, x: &BigInt) -> Ordering {
+	let scale = scale();
+	let mut err = x.clone();
+	let mut acc = scale.clone();
+	let mut divisor = BigInt::one();
+	for _ in 0..MAX_TAYLOR_TERMS {
+		divisor += 1;
+		let next_x = err.clone();
+		err = &err * x / &scale / &divisor;
+		let error_term = (&err * bound_x / &scale).abs();
+		acc += next_x;
+		if *cmp >= &acc + &error_term {
+			return Ordering::Above;
+		}
+		if *cmp < &acc - &error_term {
+			return Ordering::Below;
+		}
+	}
+	Ordering::MaxReached
+}
@@ -0,0 +1,74 @@
+//! Cardano header light client for Partner Chain nodes.
+//!
+//! # Purpose of this crate
+//!
+//! Partner Chain blocks reference stable Cardano blocks using main chain reference hashes, see `sidechain-mc-hash`.
+//! By default these references are validated by each node querying its own Cardano data source, eg. Db-Sync,
+//! which requires running a full Cardano stack and trusting it. This crate is the base of an alternative:
+//! validation of Cardano blocks from their headers alone, by verifying the Ouroboros Praos header chain:
+//! - linkage of headers, ie. previous hashes, block numbers and slots
+//! - slot leadership of the block issuers, using VRF proofs and stake distribution snapshots
+//! - operational certificates of the block issuers and their counters
+//! - KES signatures of the headers
+//!
+//! Verified headers can then be used to verify inclusion of transactions, eg. candidate registrations,
+//! using [TransactionInclusionProof].
+//!
+//! # Trust assumptions
+//!
+//! The light client trusts:
+//! - the checkpoint header from which the [HeaderChain] is started
+//! - the epoch nonces and stake distribution snapshots provided by [EpochSnapshotSource]
+//!
+//! Headers themselves can be obtained from any source, eg. Cardano relay nodes, as invalid headers are rejected.
+//!
+//! # VRF verification
+//!
+//! Cardano uses the ECVRF-ED25519-SHA512-Elligator2 VRF scheme, which is not implemented by this crate,
+//! nor anywhere else in Partner Chains. Header verification is therefore incomplete: slot leadership is
+//! only as sound as the [VrfVerifier] provided by the node. Until a verifier wrapping the VRF implementation
+//! of the `libsodium` fork used by Cardano, tested against real Cardano headers, is added, the crate is not
+//! published and does not implement `McHashDataSource`, so that headers it accepts can not be mistaken for
+//! trustlessly verified main chain references.
+//!
+//! # Testing
+//!
+//! Header decoding and verification are tested only with synthetic headers encoded according to
+//! the Babbage and Conway CDDL, signed with test keys, and with a mock [VrfVerifier]. They have not
+//! been tested against headers of a real Cardano network yet.
+//!
+//! # Usage
+//!
+//! ```rust,ignore
+//! let verifier = HeaderVerifier::new(chain_parameters, MyVrfVerifier);
+//! let mut chain = HeaderChain::new(
+//!     verifier,
+//!     Box::new(epoch_snapshots),
+//!     PraosHeader::decode(&checkpoint_header_bytes)?,
+//!     1000,
+//! );
+//!
+//! // headers received from Cardano relays
+//! chain.roll_forward(PraosHeader::decode(&header_bytes)?)?;
+//! ```
+
+#![deny(missing_docs)]
+
+pub mod chain;
+pub mod header;
+pub mod inclusion;
+pub mod kes;
+pub mod leader;
+pub mod verifier;
+
+#[cfg(test)]
+mod tests;
+
+pub use chain::{HeaderChain, HeaderChainError};
+pub use header::{HeaderDecodingError, OperationalCert, PraosHeader};
+pub use inclusion::{InclusionProofError, TransactionInclusionProof};
+pub use leader::Fraction;
+pub use verifier::{
+	ChainParameters, EpochSnapshot, EpochSnapshotSource, HeaderVerificationError, HeaderVerifier,
+	PoolSnapshot, VrfVerifier,
+};
@@ -0,0 +1,493 @@
+use crate::{
+	kes::{KES_DEPTH, KesError, verify_kes_signature},
+	leader::{is_slot_leader, leader_value, vrf_input},
+	*,
+};
+use minicbor::Encoder;
+use num_bigint::BigInt;
+use num_traits::One;
+use sidechain_domain::{
+	MainchainKeyHash, McBlockHash, McEpochNumber, McSlotNumber, McTxHash, StakePoolPublicKey,
+	crypto::blake2b,
+	mainchain_epoch::{Duration, MainchainEpochConfig, Timestamp},
+};
+use std::{collections::BTreeMap, convert::Infallible};
+
+type EncodeResult<T> = Result<T, minicbor::encode::Error<Infallible>>;
+
+const EPOCH_NONCE: [u8; 32] = [42; 32];
+
+/// VRF verifier accepting proofs equal to the VRF input, with the output being the hash of the proof
+struct MockVrfVerifier;
+
+impl VrfVerifier for MockVrfVerifier {
+	fn verify(&self, _vkey: &[u8; 32], input: &[u8; 32], proof: &[u8]) -> Option<Vec<u8>> {
+		(proof == input).then(|| blake2b::<64>(proof).to_vec())
+	}
+}
+
+fn chain_parameters() -> ChainParameters {
+	ChainParameters {
+		epoch_config: MainchainEpochConfig {
+			epoch_duration_millis: Duration::from_millis(100_000),
+			slot_duration_millis: Duration::from_millis(1000),
+			first_epoch_timestamp_millis: Timestamp::from_unix_millis(1_000_000),
+			first_epoch_number: 0,
+			first_slot_number: 0,
+		},
+		active_slot_coeff: Fraction::new(1, 20),
+		security_parameter: 2,
+		slots_per_kes_period: 100,
+		max_kes_evolutions: 62,
+	}
+}
+
+struct TestPool {
+	cold_key: ed25519_zebra::SigningKey,
+	kes_seeds: Vec<[u8; 32]>,
+	vrf_vkey: [u8; 32],
+	opcert_counter: u64,
+}
+
+impl TestPool {
+	fn new(seed: u8) -> Self {
+		Self {
+			cold_key: ed25519_zebra::SigningKey::from([seed; 32]),
+			kes_seeds: (0..(1u8 << KES_DEPTH)).map(|i| [seed.wrapping_add(i); 32]).collect(),
+			vrf_vkey: [seed.wrapping_add(100); 32],
+			opcert_counter: 0,
+		}
+	}
+
+	fn issuer_vkey(&self) -> [u8; 32] {
+		ed25519_zebra::VerificationKey::from(&self.cold_key).into()
+	}
+
+	fn pool_id(&self) -> MainchainKeyHash {
+		StakePoolPublicKey(self.issuer_vkey()).hash()
+	}
+
+	fn snapshot(&self) -> EpochSnapshot {
+		EpochSnapshot {
+			epoch_nonce: EPOCH_NONCE,
+			total_stake: 1000,
+			pools: BTreeMap::from([(
+				self.pool_id(),
+				PoolSnapshot { stake: 1000, vrf_key_hash: blake2b(&self.vrf_vkey) },
+			)]),
+		}
+	}
+}
+
+fn kes_vkey(depth: u32, seeds: &[[u8; 32]]) -> [u8; 32] {
+	if depth == 0 {
+		return ed25519_zebra::VerificationKey::from(&ed25519_zebra::SigningKey::from(seeds[0]))
+			.into();
+	}
+	let (left, right) = seeds.split_at(seeds.len() / 2);
+	blake2b(&[kes_vkey(depth - 1, left), kes_vkey(depth - 1, right)].concat())
+}
+
+fn kes_sign(depth: u32, seeds: &[[u8; 32]], period: u32, message: &[u8]) -> Vec<u8> {
+	if depth == 0 {
+		let signature: [u8; 64] = ed25519_zebra::SigningKey::from(seeds[0]).sign(message).into();
+		return signature.to_vec();
+	}
+	let half = 1 << (depth - 1);
+	let (left, right) = seeds.split_at(seeds.len() / 2);
+	let mut signature = if period < half {
+		kes_sign(depth - 1, left, period, message)
+	} else {
+		kes_sign(depth - 1, right, period - half, message)
+	};
+	signature.extend_from_slice(&kes_vkey(depth - 1, left));
+	signature.extend_from_slice(&kes_vkey(depth - 1, right));
+	signature
+}
+
+/// Returns the first slot not earlier than `from` in which a pool with all stake is the slot leader
+fn next_leader_slot(from: u64) -> u64 {
+	(from..)
+		.find(|slot| {
+			let output = blake2b::<64>(&vrf_input(McSlotNumber(*slot), &EPOCH_NONCE));
+			is_slot_leader(
+				&leader_value(&output),
+				Fraction::new(1, 1),
+				chain_parameters().active_slot_coeff,
+			)
+		})
+		.expect("leader slot exists")
+}
+
+struct HeaderParams {
+	block_number: u32,
+	slot: u64,
+	prev_hash: Option<McBlockHash>,
+	block_body_hash: [u8; 32],
+	kes_period_offset: u32,
+	vrf_proof: Option<Vec<u8>>,
+}
+
+impl HeaderParams {
+	fn child_of(parent: &PraosHeader) -> Self {
+		Self {
+			block_number: parent.block_number.0 + 1,
+			slot: next_leader_slot(parent.slot.0 + 1),
+			prev_hash: Some(parent.hash()),
+			block_body_hash: [0; 32],
+			kes_period_offset: 0,
+			vrf_proof: None,
+		}
+	}
+}
+
+fn encode_header(pool: &TestPool, params: HeaderParams) -> EncodeResult<Vec<u8>> {
+	let slot = McSlotNumber(params.slot);
+	let vrf_proof = params.vrf_proof.unwrap_or_else(|| vrf_input(slot, &EPOCH_NONCE).to_vec());
+	let vrf_output = blake2b::<64>(&vrf_input(slot, &EPOCH_NONCE));
+	let hot_vkey = kes_vkey(KES_DEPTH, &pool.kes_seeds);
+	let opcert = OperationalCert {
+		hot_vkey,
+		sequence_number: pool.opcert_counter,
+		kes_period: 0,
+		sigma: [0; 64],
+	};
+	let sigma: [u8; 64] = pool.cold_key.sign(&opcert.signable_bytes()).into();
+
+	let mut e = Encoder::new(Vec::new());
+	e.array(10)?.u32(params.block_number)?.u64(params.slot)?;
+	match params.prev_hash {
+		Some(hash) => e.bytes(&hash.0)?,
+		None => e.null()?,
+	};
+	e.bytes(&pool.issuer_vkey())?.bytes(&pool.vrf_vkey)?;
+	e.array(2)?.bytes(&vrf_output)?.bytes(&vrf_proof)?;
+	e.u64(1024)?.bytes(&params.block_body_hash)?;
+	e.array(4)?.bytes(&hot_vkey)?.u64(pool.opcert_counter)?.u64(0)?.bytes(&sigma)?;
+	e.array(2)?.u64(10)?.u64(0)?;
+	let body = e.into_writer();
+
+	let kes_period = (params.slot / chain_parameters().slots_per_kes_period) as u32;
+	let signature =
+		kes_sign(KES_DEPTH, &pool.kes_seeds, kes_period + params.kes_period_offset, &body);
+	let mut e = Encoder::new(Vec::new());
+	e.array(2)?;
+	e.writer_mut().extend_from_slice(&body);
+	e.bytes(&signature)?;
+	Ok(e.into_writer())
+}
+
+fn header(pool: &TestPool, params: HeaderParams) -> PraosHeader {
+	PraosHeader::decode(&encode_header(pool, params).unwrap()).unwrap()
+}
+
+fn checkpoint(pool: &TestPool) -> PraosHeader {
+	header(
+		pool,
+		HeaderParams {
+			block_number: 10,
+			slot: 5,
+			prev_hash: None,
+			block_body_hash: [0; 32],
+			kes_period_offset: 0,
+			vrf_proof: None,
+		},
+	)
+}
+
+fn header_chain(pool: &TestPool) -> HeaderChain<MockVrfVerifier> {
+	let snapshots: BTreeMap<McEpochNumber, EpochSnapshot> =
+		(0..100).map(|epoch| (McEpochNumber(epoch), pool.snapshot())).collect();
+	HeaderChain::new(
+		HeaderVerifier::new(chain_parameters(), MockVrfVerifier),
+		Box::new(snapshots),
+		checkpoint(pool),
+		100,
+	)
+}
+
+mod header_decoding {
+	use super::*;
+
+	#[test]
+	fn decodes_header_fields() {
+		let pool = TestPool::new(1);
+		let bytes = encode_header(
+			&pool,
+			HeaderParams {
+				block_number: 7,
+				slot: 123,
+				prev_hash: Some(McBlockHash([3; 32])),
+				block_body_hash: [4; 32],
+				kes_period_offset: 0,
+				vrf_proof: None,
+			},
+		)
+		.unwrap();
+
+		let header = PraosHeader::decode(&bytes).unwrap();
+
+		assert_eq!(header.block_number.0, 7);
+		assert_eq!(header.slot.0, 123);
+		assert_eq!(header.prev_hash, Some(McBlockHash([3; 32])));
+		assert_eq!(header.issuer_vkey, pool.issuer_vkey());
+		assert_eq!(header.block_body_hash, [4; 32]);
+		assert_eq!(header.protocol_version, (10, 0));
+		assert_eq!(header.issuer_pool_id(), pool.pool_id());
+		assert_eq!(header.hash(), McBlockHash(blake2b(&bytes)));
+	}
+
+	#[test]
+	fn rejects_trailing_bytes() {
+		let mut bytes = encode_header(
+			&TestPool::new(1),
+			HeaderParams::child_of(&checkpoint(&TestPool::new(1))),
+		)
+		.unwrap();
+		bytes.push(0);
+
+		assert!(matches!(PraosHeader::decode(&bytes), Err(HeaderDecodingError::TrailingBytes(1))));
+	}
+}
+
+mod kes {
+	use super::*;
+
+	#[test]
+	fn verifies_signatures_in_all_periods() {
+		let seeds: Vec<[u8; 32]> = (0..64u8).map(|i| [i; 32]).collect();
+		let vkey = kes_vkey(KES_DEPTH, &seeds);
+		for period in [0, 1, 31, 32, 63] {
+			let signature = kes_sign(KES_DEPTH, &seeds, period, b"message");
+			assert_eq!(verify_kes_signature(&vkey, period, b"message", &signature), Ok(()));
+		}
+	}
+
+	#[test]
+	fn rejects_signature_from_other_period() {
+		let seeds: Vec<[u8; 32]> = (0..64u8).map(|i| [i; 32]).collect();
+		let vkey = kes_vkey(KES_DEPTH, &seeds);
+		let signature = kes_sign(KES_DEPTH, &seeds, 5, b"message");
+
+		assert_eq!(
+			verify_kes_signature(&vkey, 6, b"message", &signature),
+			Err(KesError::InvalidSignature)
+		);
+	}
+
+	#[test]
+	fn rejects_signature_of_other_key() {
+		let seeds: Vec<[u8; 32]> = (0..64u8).map(|i| [i; 32]).collect();
+		let other_seeds: Vec<[u8; 32]> = (0..64u8).map(|i| [i + 1; 32]).collect();
+		let signature = kes_sign(KES_DEPTH, &other_seeds, 5, b"message");
+
+		assert_eq!(
+			verify_kes_signature(&kes_vkey(KES_DEPTH, &seeds), 5, b"message", &signature),
+			Err(KesError::VerificationKeyMismatch)
+		);
+	}
+}
+
+mod leader {
+	use super::*;
+
+	fn leader_value_of(numerator: u32, denominator: u32) -> [u8; 32] {
+		let value = (BigInt::one() << 256) * numerator / denominator;
+		let bytes = value.to_bytes_be().1;
+		let mut padded = [0u8; 32];
+		padded[32 - bytes.len()..].copy_from_slice(&bytes);
+		padded
+	}
+
+	#[test]
+	fn pool_with_all_stake_leads_active_slot_coeff_of_slots() {
+		let f = Fraction::new(1, 20);
+		assert!(is_slot_leader(&leader_value_of(49, 1000), Fraction::new(1, 1), f));
+		assert!(!is_slot_leader(&leader_value_of(51, 1000), Fraction::new(1, 1), f));
+	}
+
+	#[test]
+	fn leader_threshold_depends_on_relative_stake() {
+		let f = Fraction::new(1, 20);
+		// 1 - 0.95^0.5 ~= 0.0253
+		assert!(is_slot_leader(&leader_value_of(252, 10000), Fraction::new(1, 2), f));
+		assert!(!is_slot_leader(&leader_value_of(254, 10000), Fraction::new(1, 2), f));
+	}
+
+	#[test]
+	fn pool_without_stake_is_never_leader() {
+		assert!(!is_slot_leader(&[0; 32], Fraction::new(0, 1), Fraction::new(1, 20)));
+	}
+}
+
+mod header_chain {
+	use super::*;
+
+	#[test]
+	fn accepts_valid_headers() {
+		let pool = TestPool::new(1);
+		let mut chain = header_chain(&pool);
+
+		for _ in 0..3 {
+			let header = header(&pool, HeaderParams::child_of(chain.tip()));
+			chain.roll_forward(header.clone()).unwrap();
+			assert_eq!(chain.tip(), &header);
+		}
+	}
+
+	#[test]
+	fn rejects_header_not_referencing_tip() {
+		let pool = TestPool::new(1);
+		let mut chain = header_chain(&pool);
+		let params = HeaderParams {
+			prev_hash: Some(McBlockHash([9; 32])),
+			..HeaderParams::child_of(chain.tip())
+		};
+
+		assert!(matches!(
+			chain.roll_forward(header(&pool, params)),
+			Err(HeaderChainError::InvalidHeader(HeaderVerificationError::PrevHashMismatch(..)))
+		));
+	}
+
+	#[test]
+	fn rejects_header_with_invalid_vrf_proof() {
+		let pool = TestPool::new(1);
+		let mut chain = header_chain(&pool);
+		let params =
+			HeaderParams { vrf_proof: Some(vec![0; 80]), ..HeaderParams::child_of(chain.tip()) };
+
+		assert!(matches!(
+			chain.roll_forward(header(&pool, params)),
+			Err(HeaderChainError::InvalidHeader(HeaderVerificationError::InvalidVrfProof(..)))
+		));
+	}
+
+	#[test]
+	fn rejects_header_with_kes_signature_from_other_period() {
+		let pool = TestPool::new(1);
+		let mut chain = header_chain(&pool);
+		let params = HeaderParams { kes_period_offset: 1, ..HeaderParams::child_of(chain.tip()) };
+
+		assert!(matches!(
+			chain.roll_forward(header(&pool, params)),
+			Err(HeaderChainError::InvalidHeader(HeaderVerificationError::InvalidKesSignature(..)))
+		));
+	}
+
+	#[test]
+	fn rejects_header_from_pool_without_stake() {
+		let pool = TestPool::new(1);
+		let mut chain = header_chain(&pool);
+		let other_pool = TestPool::new(2);
+
+		assert!(matches!(
+			chain.roll_forward(header(&other_pool, HeaderParams::child_of(chain.tip()))),
+			Err(HeaderChainError::InvalidHeader(HeaderVerificationError::UnknownPool(..)))
+		));
+	}
+
+	#[test]
+	fn rejects_operational_certificate_counter_jump() {
+		let mut pool = TestPool::new(1);
+		let mut chain = header_chain(&pool);
+		pool.opcert_counter = 2;
+
+		assert!(matches!(
+			chain.roll_forward(header(&pool, HeaderParams::child_of(chain.tip()))),
+			Err(HeaderChainError::InvalidOpCertCounter(_, 2, 0))
+		));
+	}
+
+	#[test]
+	fn rolls_back_unstable_headers_only() {
+		let pool = TestPool::new(1);
+		let mut chain = header_chain(&pool);
+		let mut hashes = vec![chain.tip().hash()];
+		for _ in 0..3 {
+			chain.roll_forward(header(&pool, HeaderParams::child_of(chain.tip()))).unwrap();
+			hashes.push(chain.tip().hash());
+		}
+
+		assert_eq!(
+			chain.roll_back(&hashes[0]),
+			Err(HeaderChainError::RollbackTooDeep(hashes[0].clone()))
+		);
+		assert_eq!(chain.roll_back(&hashes[2]), Ok(()));
+		assert_eq!(chain.tip().hash(), hashes[2]);
+	}
+
+	#[test]
+	fn converts_verified_header_to_mainchain_block() {
+		let pool = TestPool::new(1);
+		let mut chain = header_chain(&pool);
+		chain.roll_forward(header(&pool, HeaderParams::child_of(chain.tip()))).unwrap();
+		let tip = chain.tip();
+
+		let block = chain.to_mainchain_block(tip).expect("block of verified header");
+
+		assert_eq!(block.hash, tip.hash());
+		assert_eq!(block.number, tip.block_number);
+		assert_eq!(block.slot, tip.slot);
+		assert_eq!(block.timestamp, 1000 + tip.slot.0);
+	}
+}
+
+mod inclusion {
+	use super::*;
+
+	fn transaction_bodies() -> EncodeResult<(Vec<u8>, Vec<u8>)> {
+		let mut e = Encoder::new(Vec::new());
+		e.map(1)?.u8(0)?.bytes(&[1, 2, 3])?;
+		let tx_body = e.into_writer();
+		let mut e = Encoder::new(Vec::new());
+		e.array(2)?;
+		e.writer_mut().extend_from_slice(&tx_body);
+		e.map(1)?.u8(0)?.bytes(&[4, 5, 6])?;
+		Ok((e.into_writer(), tx_body))
+	}
+
+	fn proof_and_header() -> (TransactionInclusionProof, PraosHeader, Vec<u8>) {
+		let (transaction_bodies, tx_body) = transaction_bodies().unwrap();
+		let proof = TransactionInclusionProof {
+			transaction_bodies,
+			witnesses_hash: [1; 32],
+			auxiliary_data_hash: [2; 32],
+			invalid_transactions_hash: [3; 32],
+		};
+		let pool = TestPool::new(1);
+		let params = HeaderParams {
+			block_body_hash: proof.block_body_hash(),
+			..HeaderParams::child_of(&checkpoint(&pool))
+		};
+		(proof, header(&pool, params), tx_body)
+	}
+
+	#[test]
+	fn returns_body_of_included_transaction() {
+		let (proof, header, tx_body) = proof_and_header();
+
+		assert_eq!(proof.verify(&header, &McTxHash(blake2b(&tx_body))), Ok(tx_body.as_slice()));
+	}
+
+	#[test]
+	fn rejects_transaction_not_in_block() {
+		let (proof, header, _) = proof_and_header();
+
+		assert_eq!(
+			proof.verify(&header, &McTxHash([7; 32])),
+			Err(InclusionProofError::TransactionNotFound(McTxHash([7; 32])))
+		);
+	}
+
+	#[test]
+	fn rejects_proof_not_matching_header() {
+		let (mut proof, header, tx_body) = proof_and_header();
+		proof.witnesses_hash = [0; 32];
+
+		assert_eq!(
+			proof.verify(&header, &McTxHash(blake2b(&tx_body))),
+			Err(InclusionProofError::BodyHashMismatch)
+		);
+	}
+}
@@ -0,0 +1,205 @@
+//! Verification of single Cardano Praos headers
+
+use crate::{
+	header::PraosHeader,
+	kes::{KesError, verify_kes_signature},
+	leader::{Fraction, is_slot_leader, leader_value, vrf_input},
+};
+use sidechain_domain::{
+	MainchainKeyHash, McBlockHash, McEpochNumber, crypto::blake2b,
+	mainchain_epoch::MainchainEpochConfig,
+};
+use std::collections::BTreeMap;
+
+/// Parameters of the Cardano chain required to verify its headers
+///
+/// Values for these parameters can be found in the `shelley-genesis.json` file used by the Cardano node.
+#[derive(Clone, Debug)]
+pub struct ChainParameters {
+	/// Cardano main chain epoch configuration
+	pub epoch_config: MainchainEpochConfig,
+	/// Expected fraction of Cardano slots that will have a block produced, eg. 1/20 for mainnet
+	pub active_slot_coeff: Fraction,
+	/// Cardano security parameter, ie. the number of confirmations needed to stabilize a block
+	pub security_parameter: u32,
+	/// Number of slots in a single KES period
+	pub slots_per_kes_period: u64,
+	/// Number of KES periods for which an operational certificate is valid
+	pub max_kes_evolutions: u64,
+}
+
+/// Stake of a stake pool in the stake distribution snapshot used for leader election
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PoolSnapshot {
+	/// Stake delegated to the pool, in Lovelace
+	pub stake: u64,
+	/// Hash of the pool's registered VRF verification key
+	pub vrf_key_hash: [u8; 32],
+}
+
+/// Epoch nonce and stake distribution snapshot used for leader election in a Cardano epoch
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EpochSnapshot {
+	/// Epoch nonce
+	pub epoch_nonce: [u8; 32],
+	/// Total active stake, in Lovelace
+	pub total_stake: u64,
+	/// Stake pools with non-zero stake, by pool ID
+	pub pools: BTreeMap<MainchainKeyHash, PoolSnapshot>,
+}
+
+/// Source of [EpochSnapshot]s for Cardano epochs
+///
+/// Note that the light client trusts the snapshots provided by this source. They can be obtained
+/// from a trusted Cardano node, or configured as checkpoints.
+pub trait EpochSnapshotSource {
+	/// Returns the snapshot used for leader election in `epoch`, if it is known
+	fn epoch_snapshot(&self, epoch: McEpochNumber) -> Option<EpochSnapshot>;
+}
+
+impl EpochSnapshotSource for BTreeMap<McEpochNumber, EpochSnapshot> {
+	fn epoch_snapshot(&self, epoch: McEpochNumber) -> Option<EpochSnapshot> {
+		self.get(&epoch).cloned()
+	}
+}
+
+/// Verifier of VRF proofs of the ECVRF-ED25519-SHA512-Elligator2 scheme used by Cardano
+pub trait VrfVerifier {
+	/// Verifies `proof` for `input` made with the key with `vkey` and returns the VRF output if the proof is valid
+	fn verify(&self, vkey: &[u8; 32], input: &[u8; 32], proof: &[u8]) -> Option<Vec<u8>>;
+}
+
+/// Error returned when a header is not valid
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum HeaderVerificationError {
+	/// Signals that the header does not reference its parent
+	#[error("Header {0} does not reference previous block {1}")]
+	PrevHashMismatch(McBlockHash, McBlockHash),
+	/// Signals that the header's block number does not follow its parent's
+	#[error("Header {0} has block number {1}, expected {2}")]
+	BlockNumberMismatch(McBlockHash, u32, u32),
+	/// Signals that the header's slot is not after its parent's
+	#[error("Header {0} has slot {1} not after its parent's slot {2}")]
+	SlotNotIncreasing(McBlockHash, u64, u64),
+	/// Signals that the epoch of the header's slot could not be determined
+	#[error("Could not determine epoch of header {0}: {1}")]
+	InvalidSlot(McBlockHash, String),
+	/// Signals that no snapshot is available for the header's epoch
+	#[error("No stake snapshot for epoch {0}")]
+	SnapshotNotFound(McEpochNumber),
+	/// Signals that the header's issuer has no stake in the epoch's snapshot
+	#[error("Header {0} issued by pool {1} not present in the stake snapshot")]
+	UnknownPool(McBlockHash, MainchainKeyHash),
+	/// Signals that the header's VRF key is not the key registered by the pool
+	#[error("Header {0} VRF key does not match the key registered by its issuer")]
+	VrfKeyMismatch(McBlockHash),
+	/// Signals that the header's VRF proof or output is invalid
+	#[error("Header {0} has invalid VRF proof")]
+	InvalidVrfProof(McBlockHash),
+	/// Signals that the header's VRF output does not make its issuer the slot leader
+	#[error("Header {0} issuer is not the leader of slot {1}")]
+	NotSlotLeader(McBlockHash, u64),
+	/// Signals that the header's operational certificate signature is invalid
+	#[error("Header {0} has invalid operational certificate signature")]
+	InvalidOpCertSignature(McBlockHash),
+	/// Signals that the header's operational certificate is not valid in the header's KES period
+	#[error(
+		"Header {0} operational certificate from KES period {1} is not valid in KES period {2}"
+	)]
+	OpCertExpired(McBlockHash, u64, u64),
+	/// Signals that the header's KES signature is invalid
+	#[error("Header {0} has invalid KES signature: {1}")]
+	InvalidKesSignature(McBlockHash, KesError),
+}
+
+/// Verifier of Cardano Praos headers
+pub struct HeaderVerifier<V> {
+	params: ChainParameters,
+	vrf_verifier: V,
+}
+
+impl<V: VrfVerifier> HeaderVerifier<V> {
+	/// Creates a new header verifier for a chain with `params`
+	pub fn new(params: ChainParameters, vrf_verifier: V) -> Self {
+		Self { params, vrf_verifier }
+	}
+
+	/// Returns parameters of the verified chain
+	pub fn params(&self) -> &ChainParameters {
+		&self.params
+	}
+
+	/// Returns the Cardano epoch of `header`
+	pub fn epoch_of(&self, header: &PraosHeader) -> Result<McEpochNumber, HeaderVerificationError> {
+		use sidechain_domain::mainchain_epoch::MainchainEpochDerivation;
+		self.params
+			.epoch_config
+			.epoch_for_slot(header.slot)
+			.map_err(|e| HeaderVerificationError::InvalidSlot(header.hash(), e.to_string()))
+	}
+
+	/// Verifies that `header` is a valid successor of `parent`, produced by a slot leader
+	/// according to `snapshot` of the header's epoch
+	pub fn verify(
+		&self,
+		header: &PraosHeader,
+		parent: &PraosHeader,
+		snapshot: &EpochSnapshot,
+	) -> Result<(), HeaderVerificationError> {
+		use HeaderVerificationError::*;
+		let hash = header.hash();
+
+		if header.prev_hash.as_ref() != Some(&parent.hash()) {
+			return Err(PrevHashMismatch(hash, parent.hash()));
+		}
+		let expected_block_number = parent.block_number.0 + 1;
+		if header.block_number.0 != expected_block_number {
+			return Err(BlockNumberMismatch(hash, header.block_number.0, expected_block_number));
+		}
+		if header.slot <= parent.slot {
+			return Err(SlotNotIncreasing(hash, header.slot.0, parent.slot.0));
+		}
+
+		let pool_id = header.issuer_pool_id();
+		let pool = snapshot.pools.get(&pool_id).ok_or(UnknownPool(hash.clone(), pool_id))?;
+		if blake2b::<32>(&header.vrf_vkey) != pool.vrf_key_hash {
+			return Err(VrfKeyMismatch(hash));
+		}
+		let input = vrf_input(header.slot, &snapshot.epoch_nonce);
+		match self.vrf_verifier.verify(&header.vrf_vkey, &input, &header.vrf_proof) {
+			Some(output) if output == header.vrf_output => {},
+			_ => return Err(InvalidVrfProof(hash)),
+		}
+		let relative_stake = Fraction::new(pool.stake, snapshot.total_stake);
+		if !is_slot_leader(
+			&leader_value(&header.vrf_output),
+			relative_stake,
+			self.params.active_slot_coeff,
+		) {
+			return Err(NotSlotLeader(hash, header.slot.0));
+		}
+
+		let opcert = &header.operational_cert;
+		let cold_key = ed25519_zebra::VerificationKey::try_from(header.issuer_vkey)
+			.map_err(|_| InvalidOpCertSignature(hash.clone()))?;
+		cold_key
+			.verify(&ed25519_zebra::Signature::from(opcert.sigma), &opcert.signable_bytes())
+			.map_err(|_| InvalidOpCertSignature(hash.clone()))?;
+
+		let kes_period = header.slot.0 / self.params.slots_per_kes_period;
+		if kes_period < opcert.kes_period
+			|| kes_period >= opcert.kes_period + self.params.max_kes_evolutions
+		{
+			return Err(OpCertExpired(hash, opcert.kes_period, kes_period));
+		}
+		let kes_evolution = u32::try_from(kes_period - opcert.kes_period)
+			.map_err(|_| OpCertExpired(hash.clone(), opcert.kes_period, kes_period))?;
+		verify_kes_signature(
+			&opcert.hot_vkey,
+			kes_evolution,
+			header.header_body_bytes(),
+			&header.body_signature,
+		)
+		.map_err(|e| InvalidKesSignature(hash, e))
+	}
+}
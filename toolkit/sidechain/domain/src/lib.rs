@@ -201,7 +201,7 @@ impl Display for McSlotNumber {
 }
 
 /// Data describing a Cardano block
-#[derive(Debug, Clone, PartialEq, Default)]
+#[derive(Debug, Clone, PartialEq, Default, Encode, Decode)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct MainchainBlock {
 	/// Block number
@@ -655,6 +655,183 @@ impl Default for ScEpochDuration {
 	}
 }
 
+/// Change of Partner Chain epoch duration scheduled for a future epoch
+#[derive(
+	Clone,
+	Copy,
+	Debug,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	TypeInfo,
+	MaxEncodedLen,
+	PartialEq,
+	Eq,
+)]
+pub struct ScEpochDurationChange {
+	/// First epoch with the new duration
+	pub epoch: ScEpochNumber,
+	/// New epoch duration
+	pub epoch_duration: ScEpochDuration,
+}
+
+/// Partner Chain epoch schedule, mapping timestamps to epoch numbers piecewise
+///
+/// Epochs starting from `first_epoch`, which starts at `first_epoch_start_millis`, have `epoch_duration`
+/// until the epoch of `next_change`, from which they have the changed duration. For a chain that never
+/// changed its epoch duration, `first_epoch` is 0 and starts at timestamp 0, ie. the epoch number is
+/// simply `timestamp / epoch_duration`.
+///
+/// Epochs before `first_epoch` are not covered by the schedule, as they are discarded when a change is
+/// applied by [ScEpochSchedule::with_change_applied_at]. Their timestamps have no epoch in the schedule.
+#[derive(
+	Clone,
+	Copy,
+	Debug,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	TypeInfo,
+	MaxEncodedLen,
+	PartialEq,
+	Eq,
+)]
+pub struct ScEpochSchedule {
+	/// First epoch of the current epoch duration
+	pub first_epoch: ScEpochNumber,
+	/// Starting timestamp of `first_epoch` in milliseconds
+	pub first_epoch_start_millis: u64,
+	/// Duration of epochs starting from `first_epoch`
+	pub epoch_duration: ScEpochDuration,
+	/// Scheduled change of epoch duration
+	pub next_change: Option<ScEpochDurationChange>,
+}
+
+impl Default for ScEpochSchedule {
+	fn default() -> Self {
+		Self::new(ScEpochDuration::default())
+	}
+}
+
+impl ScEpochSchedule {
+	/// Creates epoch schedule with constant `epoch_duration`
+	pub fn new(epoch_duration: ScEpochDuration) -> Self {
+		Self {
+			first_epoch: ScEpochNumber(0),
+			first_epoch_start_millis: 0,
+			epoch_duration,
+			next_change: None,
+		}
+	}
+
+	/// Returns the epoch of `timestamp_millis`, or [None] if it is earlier than the start of `first_epoch`
+	pub fn epoch_for_timestamp(&self, timestamp_millis: u64) -> Option<ScEpochNumber> {
+		let (first_epoch, first_epoch_start_millis, epoch_duration) = match self.next_change {
+			Some(change) if timestamp_millis >= self.base_epoch_start_millis(change.epoch) => {
+				(change.epoch, self.base_epoch_start_millis(change.epoch), change.epoch_duration)
+			},
+			_ => (self.first_epoch, self.first_epoch_start_millis, self.epoch_duration),
+		};
+		let epochs =
+			timestamp_millis.checked_sub(first_epoch_start_millis)? / epoch_duration.millis();
+		Some(ScEpochNumber(first_epoch.0 + epochs))
+	}
+
+	/// Returns the starting timestamp of `epoch` in milliseconds
+	///
+	/// `epoch` should not be earlier than `first_epoch`, whose starting timestamp is returned otherwise.
+	pub fn epoch_start_millis(&self, epoch: ScEpochNumber) -> u64 {
+		match self.next_change {
+			Some(change) if epoch >= change.epoch => {
+				self.base_epoch_start_millis(change.epoch)
+					+ (epoch.0 - change.epoch.0) * change.epoch_duration.millis()
+			},
+			_ => self.base_epoch_start_millis(epoch),
+		}
+	}
+
+	/// Returns the duration of `epoch`
+	pub fn epoch_duration_for(&self, epoch: ScEpochNumber) -> ScEpochDuration {
+		match self.next_change {
+			Some(change) if epoch >= change.epoch => change.epoch_duration,
+			_ => self.epoch_duration,
+		}
+	}
+
+	/// Returns the schedule with `next_change` applied, if it is in effect in `epoch`
+	///
+	/// The returned schedule starts at the epoch of the change and does not cover earlier epochs.
+	pub fn with_change_applied_at(&self, epoch: ScEpochNumber) -> Self {
+		match self.next_change {
+			Some(change) if epoch >= change.epoch => Self {
+				first_epoch: change.epoch,
+				first_epoch_start_millis: self.base_epoch_start_millis(change.epoch),
+				epoch_duration: change.epoch_duration,
+				next_change: None,
+			},
+			_ => *self,
+		}
+	}
+
+	fn base_epoch_start_millis(&self, epoch: ScEpochNumber) -> u64 {
+		self.first_epoch_start_millis
+			+ epoch.0.saturating_sub(self.first_epoch.0) * self.epoch_duration.millis()
+	}
+}
+
+/// Policy restricting Cardano blocks that can be used as main chain reference by Partner Chain blocks
+///
+/// Without it, block producers are only required to reference stable Cardano blocks not older than
+/// the one referenced by the parent block, which allows them to keep the chain pinned to an old Cardano
+/// state and delay committee or bridge updates. The default policy imposes no additional restrictions.
+#[derive(
+	Clone,
+	Copy,
+	Debug,
+	Default,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	TypeInfo,
+	MaxEncodedLen,
+	PartialEq,
+	Eq,
+)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct McReferencePolicy {
+	/// Maximum number of Cardano blocks by which the main chain reference can lag behind the latest
+	/// stable Cardano block. [None] disables the check. Zero is rejected by the Sidechain pallet.
+	pub max_lag_blocks: Option<u32>,
+	/// Minimum number of Cardano blocks by which the main chain reference of the first block of each
+	/// Partner Chain epoch must advance relative to the reference of its parent, unless it reaches the
+	/// latest stable Cardano block. Zero disables the check.
+	pub min_advance_per_epoch: u32,
+}
+
+impl McReferencePolicy {
+	/// Returns the lowest Cardano block number that can be referenced by a Partner Chain block, given the
+	/// `latest_stable` Cardano block number, the `parent_reference` block number and whether the block
+	/// `starts_new_epoch`
+	pub fn min_reference(
+		&self,
+		latest_stable: McBlockNumber,
+		parent_reference: Option<McBlockNumber>,
+		starts_new_epoch: bool,
+	) -> McBlockNumber {
+		let within_lag =
+			self.max_lag_blocks.map_or(0, |max_lag| latest_stable.0.saturating_sub(max_lag));
+		let advanced = match parent_reference {
+			Some(parent_reference) if starts_new_epoch => parent_reference
+				.0
+				.saturating_add(self.min_advance_per_epoch)
+				.min(latest_stable.0),
+			_ => 0,
+		};
+		McBlockNumber(within_lag.max(advanced))
+	}
+}
+
 #[derive(
 	Clone,
 	PartialEq,
@@ -1118,6 +1295,58 @@ impl CandidateRegistrations {
 	}
 }
 
+/// Configuration of token-based staking of a Partner Chain
+///
+/// Candidates registered with [StakeOwnership::TokenBased] are identified by their Partner Chain public key.
+/// Their stake is the amount of `staking_token` in UTXOs at `staking_address` whose datum is the bytes of that key,
+/// as of the last block of the data epoch.
+#[derive(
+	Clone, Debug, PartialEq, Eq, Encode, Decode, DecodeWithMemTracking, TypeInfo, MaxEncodedLen,
+)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TokenStakingConfig {
+	/// [MainchainAddress] at which the staking tokens are locked
+	pub staking_address: MainchainAddress,
+	/// Native asset used as stake
+	pub staking_token: AssetId,
+	/// Number of lovelace that one staking token is worth in committee selection.
+	///
+	/// Candidates with token-based stake compete for the same seats as the ones with ADA-based stake,
+	/// so the amount of staking tokens locked for them is multiplied by this factor to get their selection weight.
+	pub lovelace_per_token: u64,
+}
+
+/// Single registration of a candidate with [StakeOwnership::TokenBased] stake
+///
+/// Unlike [RegistrationData], it carries no main chain signature, because the Plutus datum of such registration
+/// does not contain one.
+#[derive(Debug, Clone, Encode, Decode, DecodeWithMemTracking, PartialEq, Eq, TypeInfo)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct TokenStakedRegistrationData {
+	/// UTXO that is an input parameter to the registration transaction
+	pub registration_utxo: UtxoId,
+	/// Signature confirming the registrant's ownership of the candidate's Partner Chain public key
+	pub sidechain_signature: SidechainSignature,
+	/// Information about the UTxO containing the registration data
+	pub utxo_info: UtxoInfo,
+	/// List of inputs to the registration transaction
+	pub tx_inputs: Vec<UtxoId>,
+	/// Additional keys of the candidate
+	pub keys: CandidateKeys,
+}
+
+/// Information about Registrations of an Authority Candidate with token-based stake at some block.
+#[derive(Debug, Clone, Encode, Decode, DecodeWithMemTracking, PartialEq, Eq, TypeInfo)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct TokenStakedCandidateRegistrations {
+	/// Partner Chain public key of the candidate
+	pub sidechain_pub_key: SidechainPublicKey,
+	/// List of registrations of the candidate
+	pub registrations: Vec<TokenStakedRegistrationData>,
+	/// Amount of staking tokens locked for the candidate, `None` if it is not known
+	pub stake: Option<StakeDelegation>,
+}
+
 /// Sr25519 public key used by Aura consensus algorithm. Not validated
 #[derive(
 	Clone, PartialEq, Eq, Encode, Decode, DecodeWithMemTracking, TypeInfo, PartialOrd, Ord, Hash,
@@ -1428,8 +1657,8 @@ impl FromStr for PermissionedCandidateData {
 /// Cardano SPO registration. This is a stripped-down version of [RegistrationData].
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct CandidateRegistration {
-	/// Information on ADA stake pool ownership
-	pub stake_ownership: AdaBasedStaking,
+	/// Information on stake ownership of the registering SPO or token staker
+	pub stake_ownership: StakeOwnership,
 	/// Registering SPO's sidechain public key
 	pub partner_chain_pub_key: SidechainPublicKey,
 	/// Signature confirming registering SPO's ownership of `partner_chain_pub_key`
@@ -1452,9 +1681,35 @@ impl CandidateRegistration {
 	}
 }
 
+/// Information on stake ownership of a registered candidate. Corresponds to Plutus type StakeOwnership.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum StakeOwnership {
+	/// Stake of the candidate is ADA delegated to their stake pool
+	AdaBased(AdaBasedStaking),
+	/// Stake of the candidate is the amount of staking tokens locked for them.
+	/// See [TokenStakingConfig] for how this stake is computed.
+	TokenBased,
+}
+
+impl StakeOwnership {
+	/// Returns the ADA stake pool ownership information, if the stake is ADA-based
+	pub fn ada_based(&self) -> Option<&AdaBasedStaking> {
+		match self {
+			Self::AdaBased(staking) => Some(staking),
+			Self::TokenBased => None,
+		}
+	}
+}
+
+impl From<AdaBasedStaking> for StakeOwnership {
+	fn from(staking: AdaBasedStaking) -> Self {
+		Self::AdaBased(staking)
+	}
+}
+
 /// Information on ADA stake pool ownership
 ///
-/// AdaBasedStaking is a variant of Plutus type StakeOwnership. The other variant, TokenBasedStaking, is not supported.
+/// AdaBasedStaking is a variant of Plutus type StakeOwnership.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct AdaBasedStaking {
 	/// Public key of the stake pool operator
@@ -1504,7 +1759,7 @@ impl alloc::fmt::Debug for DelegatorKey {
 }
 
 /// Amount of Lovelace staked by a Cardano delegator to a single stake pool
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Encode, Decode)]
 pub struct DelegatorStakeAmount(pub u64);
 
 impl<T: Into<u64>> From<T> for DelegatorStakeAmount {
@@ -1517,11 +1772,11 @@ impl<T: Into<u64>> From<T> for DelegatorStakeAmount {
 ///
 /// This mapping can be used to calculate relative share of the total delegation for the
 /// purpose of weighing during block producer selection.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Encode, Decode)]
 pub struct StakeDistribution(pub BTreeMap<MainchainKeyHash, PoolDelegation>);
 
 /// ADA delegation data for a single Cardano SPO
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Encode, Decode)]
 pub struct PoolDelegation {
 	/// Total amount delegated to the stake pool
 	pub total_stake: StakeDelegation,
@@ -1656,4 +1911,91 @@ mod tests {
 		let decoded = TestCandidateKeys::decode(&mut bytes).unwrap();
 		assert_eq!(keys, decoded)
 	}
+
+	fn schedule_with_change() -> ScEpochSchedule {
+		ScEpochSchedule {
+			next_change: Some(ScEpochDurationChange {
+				epoch: ScEpochNumber(10),
+				epoch_duration: ScEpochDuration::from_millis(500),
+			}),
+			..ScEpochSchedule::new(ScEpochDuration::from_millis(1000))
+		}
+	}
+
+	#[test]
+	fn epoch_schedule_without_change_divides_timestamp_by_duration() {
+		let schedule = ScEpochSchedule::new(ScEpochDuration::from_millis(1000));
+
+		assert_eq!(schedule.epoch_for_timestamp(0), Some(ScEpochNumber(0)));
+		assert_eq!(schedule.epoch_for_timestamp(12_999), Some(ScEpochNumber(12)));
+		assert_eq!(schedule.epoch_start_millis(ScEpochNumber(13)), 13_000);
+	}
+
+	#[test]
+	fn epoch_schedule_applies_scheduled_change_from_its_epoch() {
+		let schedule = schedule_with_change();
+
+		assert_eq!(schedule.epoch_for_timestamp(9_999), Some(ScEpochNumber(9)));
+		assert_eq!(schedule.epoch_for_timestamp(10_000), Some(ScEpochNumber(10)));
+		assert_eq!(schedule.epoch_for_timestamp(10_499), Some(ScEpochNumber(10)));
+		assert_eq!(schedule.epoch_for_timestamp(10_500), Some(ScEpochNumber(11)));
+		assert_eq!(schedule.epoch_start_millis(ScEpochNumber(9)), 9_000);
+		assert_eq!(schedule.epoch_start_millis(ScEpochNumber(12)), 11_000);
+		assert_eq!(schedule.epoch_duration_for(ScEpochNumber(9)).millis(), 1000);
+		assert_eq!(schedule.epoch_duration_for(ScEpochNumber(10)).millis(), 500);
+	}
+
+	#[test]
+	fn epoch_schedule_with_change_applied_maps_timestamps_the_same() {
+		let schedule = schedule_with_change();
+
+		assert_eq!(schedule.with_change_applied_at(ScEpochNumber(9)), schedule);
+
+		let applied = schedule.with_change_applied_at(ScEpochNumber(10));
+		assert_eq!(applied.first_epoch, ScEpochNumber(10));
+		assert_eq!(applied.first_epoch_start_millis, 10_000);
+		assert_eq!(applied.next_change, None);
+		for timestamp in [10_000, 10_499, 10_500, 123_456] {
+			assert_eq!(
+				applied.epoch_for_timestamp(timestamp),
+				schedule.epoch_for_timestamp(timestamp)
+			);
+		}
+	}
+
+	#[test]
+	fn epoch_schedule_with_change_applied_has_no_epoch_for_earlier_timestamps() {
+		let schedule = schedule_with_change();
+
+		let applied = schedule.with_change_applied_at(ScEpochNumber(10));
+
+		assert_eq!(schedule.epoch_for_timestamp(9_999), Some(ScEpochNumber(9)));
+		assert_eq!(applied.epoch_for_timestamp(9_999), None);
+		assert_eq!(applied.epoch_for_timestamp(0), None);
+	}
+
+	#[test]
+	fn default_mc_reference_policy_allows_any_reference() {
+		let policy = McReferencePolicy::default();
+
+		assert_eq!(policy.min_reference(McBlockNumber(100), Some(McBlockNumber(10)), true).0, 0);
+	}
+
+	#[test]
+	fn mc_reference_policy_limits_lag_behind_latest_stable_block() {
+		let policy = McReferencePolicy { max_lag_blocks: Some(30), min_advance_per_epoch: 0 };
+
+		assert_eq!(policy.min_reference(McBlockNumber(100), Some(McBlockNumber(10)), false).0, 70);
+		assert_eq!(policy.min_reference(McBlockNumber(20), None, false).0, 0);
+	}
+
+	#[test]
+	fn mc_reference_policy_requires_advance_at_epoch_start() {
+		let policy = McReferencePolicy { max_lag_blocks: None, min_advance_per_epoch: 20 };
+
+		assert_eq!(policy.min_reference(McBlockNumber(100), Some(McBlockNumber(10)), true).0, 30);
+		assert_eq!(policy.min_reference(McBlockNumber(100), Some(McBlockNumber(10)), false).0, 0);
+		assert_eq!(policy.min_reference(McBlockNumber(25), Some(McBlockNumber(10)), true).0, 25);
+		assert_eq!(policy.min_reference(McBlockNumber(100), None, true).0, 0);
+	}
 }
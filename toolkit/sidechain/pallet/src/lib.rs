@@ -24,8 +24,25 @@
 //!
 //! When producing blocks, Partner Chains divide time epochs which other Partner
 //! Chains features use as boundaries for some of their state transitions (eg. a Partner
-//! Chain block producing committees change at epoch boundaries). Epoch duration should
-//! be constant and immutable throughout a Partner Chain's lifetime.
+//! Chain block producing committees change at epoch boundaries).
+//!
+//! Epoch duration can be changed by the chain's governance using the
+//! [schedule_epoch_duration_change][Pallet::schedule_epoch_duration_change] extrinsic. The change
+//! takes effect from the start of a future epoch, and epoch numbers are computed piecewise
+//! according to the [ScEpochSchedule][sidechain_domain::ScEpochSchedule] stored by the pallet:
+//! epochs before the change keep their duration and numbering, and later epochs have the new
+//! duration. Node components that need to map timestamps to epochs should use the
+//! [GetEpochScheduleApi][sp_sidechain::GetEpochScheduleApi] runtime API. Once a change takes effect,
+//! the stored schedule no longer covers the epochs before it, so timestamps from before the change
+//! have to be mapped using the schedule read at a block from before the change.
+//!
+//! ## Main chain reference policy
+//!
+//! The pallet stores the [McReferencePolicy][sidechain_domain::McReferencePolicy] restricting how far
+//! behind Cardano the main chain references of Partner Chain blocks can be. It is enforced by nodes during
+//! block verification, which read it using the [GetMcReferencePolicyApi][sp_sidechain::GetMcReferencePolicyApi]
+//! runtime API. The policy imposes no restrictions by default and can be changed by the chain's governance
+//! using the [set_mc_reference_policy][Pallet::set_mc_reference_policy] extrinsic.
 //!
 //! # Usage
 //!
@@ -78,6 +95,7 @@
 //!         *pallet_aura::CurrentSlot::<Runtime>::get() * SLOT_DURATION
 //!     }
 //!     type OnNewEpoch = MyNewEpochHandler;
+//!     type GovernanceOrigin = EnsureRoot<AccountId>;
 //! }
 //! ```
 //! Optionally, a new epoch handler can be configured like in the example above. Partner Chains
@@ -135,13 +153,14 @@ pub use pallet::*;
 #[frame_support::pallet]
 pub mod pallet {
 	use frame_support::pallet_prelude::*;
-	use frame_system::pallet_prelude::BlockNumberFor;
+	use frame_system::pallet_prelude::{BlockNumberFor, OriginFor};
 	use sidechain_domain::ScEpochDuration;
 	use sidechain_domain::ScEpochNumber;
 	use sidechain_domain::UtxoId;
+	use sidechain_domain::{ScEpochDurationChange, ScEpochSchedule};
 	use sp_sidechain::OnNewEpoch;
 
-	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(2);
 
 	#[pallet::pallet]
 	#[pallet::storage_version(STORAGE_VERSION)]
@@ -157,20 +176,48 @@ pub mod pallet {
 		///
 		/// Warning: this function must be safe to call during block initialisation, which means that
 		/// in particular block timestamp stored in `pallet_timestamp` can not be used.
+		///
+		/// Weights of this pallet account for a single storage read by this function, eg. of the current
+		/// slot of `pallet_aura`.
 		fn reference_timestamp_millis() -> u64;
 
 		/// Handler that is called at initialization of the first block of a new Partner Chain epoch
 		type OnNewEpoch: OnNewEpoch;
+
+		/// Origin for governance extrinsic calls.
+		///
+		/// Typically the `EnsureRoot` type can be used unless a non-standard on-chain governance is used.
+		type GovernanceOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+	}
+
+	/// Error type used by this pallet's extrinsics
+	#[pallet::error]
+	pub enum Error<T> {
+		/// Signals that the epoch duration change is not scheduled for a future epoch
+		EpochNotInFuture,
+		/// Signals that the new epoch duration is zero
+		ZeroEpochDuration,
+		/// Signals that the main chain reference policy allows no lag behind the latest stable Cardano block,
+		/// which would reject blocks of producers whose view of Cardano is a single block behind
+		ZeroMcReferenceMaxLag,
 	}
 
 	/// Current epoch number
 	#[pallet::storage]
 	pub(super) type EpochNumber<T: Config> = StorageValue<_, ScEpochNumber, ValueQuery>;
 
-	/// Partner Chain epoch duration in milliseconds. This value must not be changed.
+	/// Partner Chain epoch duration in milliseconds.
 	#[pallet::storage]
+	#[deprecated(
+		since = "1.9.0",
+		note = "This storage is left for migration purposes and will be removed in later version. Use EpochSchedule instead."
+	)]
 	pub(crate) type EpochDurationMillis<T: Config> = StorageValue<_, ScEpochDuration, ValueQuery>;
 
+	/// Partner Chain epoch schedule, containing the current epoch duration and the scheduled change of it
+	#[pallet::storage]
+	pub(crate) type EpochSchedule<T: Config> = StorageValue<_, ScEpochSchedule, ValueQuery>;
+
 	/// Number of slots per epoch. Currently this value must not change for a running chain.
 	#[pallet::storage]
 	#[deprecated(
@@ -179,6 +226,11 @@ pub mod pallet {
 	)]
 	pub(crate) type SlotsPerEpoch<T: Config> = StorageValue<_, u32, ValueQuery>;
 
+	/// Policy restricting main chain references of Partner Chain blocks
+	#[pallet::storage]
+	pub(crate) type McReferencePolicy<T: Config> =
+		StorageValue<_, sidechain_domain::McReferencePolicy, ValueQuery>;
+
 	/// Genesis Cardano UTXO of the Partner Chain
 	///
 	/// This is the UTXO that is burned by the transaction that establishes Partner Chain
@@ -193,14 +245,86 @@ pub mod pallet {
 			GenesisUtxo::<T>::get()
 		}
 
-		/// Returns the Partner Chain epoch duration in milliseconds
+		/// Returns the duration of the current Partner Chain epoch in milliseconds
 		pub fn epoch_duration_millis() -> u64 {
-			EpochDurationMillis::<T>::get().millis()
+			let schedule = EpochSchedule::<T>::get();
+			schedule.epoch_duration_for(Self::current_epoch_in(&schedule)).millis()
+		}
+
+		/// Returns the Partner Chain epoch schedule
+		pub fn epoch_schedule() -> ScEpochSchedule {
+			EpochSchedule::<T>::get()
 		}
 
 		/// Returns current epoch number
 		pub fn current_epoch_number() -> ScEpochNumber {
-			ScEpochNumber(T::reference_timestamp_millis() / Self::epoch_duration_millis())
+			Self::current_epoch_in(&EpochSchedule::<T>::get())
+		}
+
+		/// Returns the epoch of the reference timestamp in `schedule`
+		///
+		/// Changes of the epoch schedule are applied only once their epoch has started, so the reference
+		/// timestamp is never earlier than the first epoch of the stored schedule.
+		fn current_epoch_in(schedule: &ScEpochSchedule) -> ScEpochNumber {
+			schedule
+				.epoch_for_timestamp(T::reference_timestamp_millis())
+				.unwrap_or(schedule.first_epoch)
+		}
+
+		/// Returns the main chain reference policy
+		pub fn mc_reference_policy() -> sidechain_domain::McReferencePolicy {
+			McReferencePolicy::<T>::get()
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Schedules a change of the Partner Chain epoch duration to `epoch_duration`, effective from the start of `epoch`.
+		///
+		/// `epoch` must be later than the current epoch. A change scheduled earlier but not yet in effect is replaced.
+		///
+		/// This extrinsic must be run either using `sudo` or some other chain governance mechanism.
+		#[pallet::call_index(0)]
+		// Reads the epoch schedule and the reference timestamp and writes the epoch schedule
+		#[pallet::weight(T::DbWeight::get().reads_writes(2, 1))]
+		pub fn schedule_epoch_duration_change(
+			origin: OriginFor<T>,
+			epoch: ScEpochNumber,
+			epoch_duration: ScEpochDuration,
+		) -> DispatchResult {
+			T::GovernanceOrigin::ensure_origin(origin)?;
+			ensure!(epoch_duration.millis() > 0, Error::<T>::ZeroEpochDuration);
+			let current_epoch = Self::current_epoch_number();
+			ensure!(epoch > current_epoch, Error::<T>::EpochNotInFuture);
+
+			let schedule = EpochSchedule::<T>::get().with_change_applied_at(current_epoch);
+			EpochSchedule::<T>::put(ScEpochSchedule {
+				next_change: Some(ScEpochDurationChange { epoch, epoch_duration }),
+				..schedule
+			});
+			log::info!(
+				"⏳ Scheduled epoch duration change to {} ms from epoch {epoch}",
+				epoch_duration.millis()
+			);
+			Ok(())
+		}
+
+		/// Sets the policy restricting main chain references of Partner Chain blocks, effective from the next block.
+		///
+		/// `max_lag_blocks` of the policy must not be zero.
+		///
+		/// This extrinsic must be run either using `sudo` or some other chain governance mechanism.
+		#[pallet::call_index(1)]
+		#[pallet::weight(T::DbWeight::get().writes(1))]
+		pub fn set_mc_reference_policy(
+			origin: OriginFor<T>,
+			policy: sidechain_domain::McReferencePolicy,
+		) -> DispatchResult {
+			T::GovernanceOrigin::ensure_origin(origin)?;
+			ensure!(policy.max_lag_blocks != Some(0), Error::<T>::ZeroMcReferenceMaxLag);
+			McReferencePolicy::<T>::put(policy);
+			log::info!("🔗 Main chain reference policy set to {policy:?}");
+			Ok(())
 		}
 	}
 
@@ -220,28 +344,38 @@ pub mod pallet {
 	impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
 		fn build(&self) {
 			GenesisUtxo::<T>::put(self.genesis_utxo);
-			EpochDurationMillis::<T>::put(self.epoch_duration);
+			EpochSchedule::<T>::put(ScEpochSchedule::new(self.epoch_duration));
 		}
 	}
 
 	#[pallet::hooks]
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
 		fn on_initialize(n: BlockNumberFor<T>) -> Weight {
-			let real_epoch = Self::current_epoch_number();
+			let schedule = EpochSchedule::<T>::get();
+			let real_epoch = Self::current_epoch_in(&schedule);
 
 			match EpochNumber::<T>::try_get().ok() {
 				Some(saved_epoch) if saved_epoch != real_epoch => {
 					log::info!("⏳ New epoch {real_epoch} starting at block {:?}", n);
 					EpochNumber::<T>::put(real_epoch);
+					let applied_schedule = schedule.with_change_applied_at(real_epoch);
+					if applied_schedule != schedule {
+						log::info!(
+							"⏳ Epoch duration changed to {} ms from epoch {}",
+							applied_schedule.epoch_duration.millis(),
+							applied_schedule.first_epoch
+						);
+						EpochSchedule::<T>::put(applied_schedule);
+					}
 					<T::OnNewEpoch as OnNewEpoch>::on_new_epoch(saved_epoch, real_epoch)
-						.saturating_add(T::DbWeight::get().reads_writes(2, 1))
+						.saturating_add(T::DbWeight::get().reads_writes(3, 2))
 				},
 				None => {
 					log::info!("⏳ Initial epoch {real_epoch} starting at block {:?}", n);
 					EpochNumber::<T>::put(real_epoch);
-					T::DbWeight::get().reads_writes(2, 1)
+					T::DbWeight::get().reads_writes(3, 1)
 				},
-				_ => T::DbWeight::get().reads_writes(2, 0),
+				_ => T::DbWeight::get().reads_writes(3, 0),
 			}
 		}
 	}
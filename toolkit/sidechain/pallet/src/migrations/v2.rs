@@ -0,0 +1,69 @@
+//! Storage migration of `pallet-sidechain` from storage version 1 to 2, allowing epoch duration changes
+//!
+//! This version change obsoletes the [EpochDurationMillis] storage which is now deprecated
+//! and will be removed in the future, and introduces a new storage [EpochSchedule]
+//! to replace it.
+
+/// Storage migration for chains using a constant epoch duration.
+///
+/// This migration sets the value in [EpochSchedule] to a schedule with the epoch duration
+/// stored in [EpochDurationMillis] and no scheduled changes, preserving numbering of epochs.
+pub type V1ToV2Migration<T> = frame_support::migrations::VersionedMigration<
+	1, // The migration will only execute when the on-chain storage version is 1
+	2, // The on-chain storage version will be set to 2 after the migration is complete
+	_impl::InnerMigrateV1ToV2<T>,
+	crate::pallet::Pallet<T>,
+	<T as frame_system::Config>::DbWeight,
+>;
+
+/// Private module to void leaking
+#[allow(deprecated)]
+mod _impl {
+	#[cfg(feature = "try-runtime")]
+	extern crate alloc;
+
+	use frame_support::traits::Get;
+	use frame_support::traits::UncheckedOnRuntimeUpgrade;
+	use sidechain_domain::ScEpochSchedule;
+
+	/// Helper type used internally by [V1ToV2Migration]
+	pub struct InnerMigrateV1ToV2<T: crate::Config>(core::marker::PhantomData<T>);
+
+	impl<T: crate::pallet::Config> UncheckedOnRuntimeUpgrade for InnerMigrateV1ToV2<T> {
+		fn on_runtime_upgrade() -> sp_runtime::Weight {
+			let epoch_duration = crate::EpochDurationMillis::<T>::take();
+			crate::EpochSchedule::<T>::put(ScEpochSchedule::new(epoch_duration));
+
+			log::info!(
+				"⬆️ Migrated pallet-sidechain to version 2, with epoch duration of {} ms",
+				epoch_duration.millis()
+			);
+
+			T::DbWeight::get().reads_writes(1, 2)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<alloc::vec::Vec<u8>, sp_runtime::TryRuntimeError> {
+			let epoch_duration = crate::EpochDurationMillis::<T>::get();
+
+			Ok(epoch_duration.millis().to_be_bytes().to_vec())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: alloc::vec::Vec<u8>) -> Result<(), sp_runtime::TryRuntimeError> {
+			let epoch_duration_millis = u64::from_be_bytes(state.try_into().unwrap());
+
+			let schedule = crate::EpochSchedule::<T>::get();
+
+			frame_support::ensure!(
+				schedule
+					== ScEpochSchedule::new(sidechain_domain::ScEpochDuration::from_millis(
+						epoch_duration_millis
+					)),
+				sp_runtime::TryRuntimeError::Corruption
+			);
+
+			Ok(())
+		}
+	}
+}
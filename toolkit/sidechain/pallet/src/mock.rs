@@ -27,7 +27,7 @@ pub(crate) mod mock_pallet {
 	pub trait Config: frame_system::Config {}
 
 	#[pallet::storage]
-	pub type CurrentEpoch<T: Config> = StorageValue<_, ScEpochNumber, ValueQuery>;
+	pub type CurrentTimestampMillis<T: Config> = StorageValue<_, u64, ValueQuery>;
 
 	#[pallet::storage]
 	pub type OnNewEpochCallCount<T: Config> = StorageValue<_, u32, ValueQuery>;
@@ -41,7 +41,11 @@ pub(crate) mod mock_pallet {
 
 	impl<T: Config> Pallet<T> {
 		pub fn set_epoch(epoch: u64) {
-			CurrentEpoch::<T>::put(ScEpochNumber(epoch))
+			Self::set_timestamp(epoch * super::EPOCH_DURATION_MILLIS)
+		}
+
+		pub fn set_timestamp(timestamp_millis: u64) {
+			CurrentTimestampMillis::<T>::put(timestamp_millis)
 		}
 	}
 }
@@ -92,9 +96,10 @@ impl frame_system::Config for Test {
 
 impl pallet::Config for Test {
 	fn reference_timestamp_millis() -> u64 {
-		mock_pallet::CurrentEpoch::<Test>::get().0 * EPOCH_DURATION_MILLIS
+		mock_pallet::CurrentTimestampMillis::<Test>::get()
 	}
 	type OnNewEpoch = Mock;
+	type GovernanceOrigin = frame_system::EnsureRoot<u64>;
 }
 
 pub fn new_test_ext() -> sp_io::TestExternalities {
@@ -1,6 +1,9 @@
-use frame_support::traits::Hooks;
+use frame_support::{assert_noop, assert_ok, traits::Hooks};
+use sidechain_domain::{McReferencePolicy, ScEpochDuration, ScEpochDurationChange, ScEpochNumber};
+use sp_runtime::DispatchError;
 
 use crate::mock::*;
+use crate::{EpochSchedule, Error};
 
 #[test]
 fn on_new_epoch_is_triggered_by_epoch_change() {
@@ -33,3 +36,142 @@ fn read_genesis_utxo() {
 		assert_eq!(params, crate::mock::MOCK_GENESIS_UTXO);
 	})
 }
+
+fn schedule_change(epoch: u64, epoch_duration_millis: u64) -> sp_runtime::DispatchResult {
+	Sidechain::schedule_epoch_duration_change(
+		RuntimeOrigin::root(),
+		ScEpochNumber(epoch),
+		ScEpochDuration::from_millis(epoch_duration_millis),
+	)
+}
+
+#[test]
+fn epoch_duration_change_is_applied_from_scheduled_epoch() {
+	new_test_ext().execute_with(|| {
+		Mock::set_epoch(2);
+		Sidechain::on_initialize(1);
+		assert_ok!(schedule_change(4, EPOCH_DURATION_MILLIS / 2));
+
+		Mock::set_timestamp(4 * EPOCH_DURATION_MILLIS - 1);
+		assert_eq!(Sidechain::current_epoch_number(), ScEpochNumber(3));
+		assert_eq!(Sidechain::epoch_duration_millis(), EPOCH_DURATION_MILLIS);
+
+		Mock::set_timestamp(4 * EPOCH_DURATION_MILLIS + EPOCH_DURATION_MILLIS / 2);
+		assert_eq!(Sidechain::current_epoch_number(), ScEpochNumber(5));
+		assert_eq!(Sidechain::epoch_duration_millis(), EPOCH_DURATION_MILLIS / 2);
+
+		Sidechain::on_initialize(2);
+		assert_eq!(mock_pallet::OnNewEpochCallCount::<Test>::get(), 1);
+		let schedule = EpochSchedule::<Test>::get();
+		assert_eq!(schedule.first_epoch, ScEpochNumber(4));
+		assert_eq!(schedule.first_epoch_start_millis, 4 * EPOCH_DURATION_MILLIS);
+		assert_eq!(schedule.next_change, None);
+		assert_eq!(Sidechain::current_epoch_number(), ScEpochNumber(5));
+	})
+}
+
+#[test]
+fn scheduling_epoch_duration_change_replaces_pending_change() {
+	new_test_ext().execute_with(|| {
+		Mock::set_epoch(2);
+		assert_ok!(schedule_change(4, 1000));
+		assert_ok!(schedule_change(5, 2000));
+
+		assert_eq!(
+			EpochSchedule::<Test>::get().next_change,
+			Some(ScEpochDurationChange {
+				epoch: ScEpochNumber(5),
+				epoch_duration: ScEpochDuration::from_millis(2000)
+			})
+		);
+	})
+}
+
+#[test]
+fn epoch_duration_change_must_be_scheduled_for_future_epoch() {
+	new_test_ext().execute_with(|| {
+		Mock::set_epoch(2);
+
+		assert_noop!(schedule_change(2, 1000), Error::<Test>::EpochNotInFuture);
+	})
+}
+
+#[test]
+fn epoch_duration_change_to_zero_is_rejected() {
+	new_test_ext().execute_with(|| {
+		Mock::set_epoch(2);
+
+		assert_noop!(schedule_change(3, 0), Error::<Test>::ZeroEpochDuration);
+	})
+}
+
+#[test]
+fn epoch_duration_change_requires_governance_origin() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Sidechain::schedule_epoch_duration_change(
+				RuntimeOrigin::signed(1),
+				ScEpochNumber(3),
+				ScEpochDuration::from_millis(1000),
+			),
+			DispatchError::BadOrigin
+		);
+	})
+}
+
+#[test]
+fn mc_reference_policy_is_set_by_governance() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(Sidechain::mc_reference_policy(), McReferencePolicy::default());
+		let policy = McReferencePolicy { max_lag_blocks: Some(100), min_advance_per_epoch: 10 };
+
+		assert_ok!(Sidechain::set_mc_reference_policy(RuntimeOrigin::root(), policy));
+
+		assert_eq!(Sidechain::mc_reference_policy(), policy);
+	})
+}
+
+#[test]
+fn mc_reference_policy_without_lag_is_rejected() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Sidechain::set_mc_reference_policy(
+				RuntimeOrigin::root(),
+				McReferencePolicy { max_lag_blocks: Some(0), min_advance_per_epoch: 0 }
+			),
+			Error::<Test>::ZeroMcReferenceMaxLag
+		);
+	})
+}
+
+#[test]
+fn mc_reference_policy_change_requires_governance_origin() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Sidechain::set_mc_reference_policy(
+				RuntimeOrigin::signed(1),
+				McReferencePolicy::default()
+			),
+			DispatchError::BadOrigin
+		);
+	})
+}
+
+#[test]
+#[allow(deprecated)]
+fn v2_migration_preserves_epoch_duration() {
+	use frame_support::traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion};
+	new_test_ext().execute_with(|| {
+		EpochSchedule::<Test>::kill();
+		crate::EpochDurationMillis::<Test>::put(ScEpochDuration::from_millis(
+			EPOCH_DURATION_MILLIS,
+		));
+		StorageVersion::new(1).put::<Sidechain>();
+
+		crate::migrations::v2::V1ToV2Migration::<Test>::on_runtime_upgrade();
+
+		assert_eq!(Sidechain::on_chain_storage_version(), StorageVersion::new(2));
+		assert_eq!(Sidechain::epoch_duration_millis(), EPOCH_DURATION_MILLIS);
+		assert!(!crate::EpochDurationMillis::<Test>::exists());
+	})
+}
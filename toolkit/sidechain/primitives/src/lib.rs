@@ -5,7 +5,7 @@
 use frame_support::pallet_prelude::Weight;
 use parity_scale_codec::{Decode, Encode};
 use scale_info::TypeInfo;
-use sidechain_domain::{ScEpochNumber, UtxoId};
+use sidechain_domain::{McReferencePolicy, ScEpochNumber, ScEpochSchedule, UtxoId};
 
 #[cfg(test)]
 mod tests;
@@ -66,10 +66,22 @@ mod api_declarations {
 
 		/// Runtime API for retrieving the Partner Chain's epoch duration
 		pub trait GetEpochDurationApi {
-			/// Returns Partner Chain epoch duration in milliseconds
+			/// Returns duration of the current Partner Chain epoch in milliseconds
 			fn get_epoch_duration_millis() -> u64;
 		}
 
+		/// Runtime API for retrieving the Partner Chain's epoch schedule
+		pub trait GetEpochScheduleApi {
+			/// Returns Partner Chain epoch schedule, including the scheduled epoch duration change if any
+			fn get_epoch_schedule() -> ScEpochSchedule;
+		}
+
+		/// Runtime API for retrieving the policy restricting main chain references of Partner Chain blocks
+		pub trait GetMcReferencePolicyApi {
+			/// Returns the main chain reference policy
+			fn get_mc_reference_policy() -> McReferencePolicy;
+		}
+
 		/// Runtime API for getting information about current Partner Chain slot and epoch
 		#[deprecated(since = "1.7.0", note = "Code that needs this data should define its own runtime API instead.")]
 		pub trait GetSidechainStatus {
@@ -4,7 +4,7 @@
 //!
 //! ## Implementing runtime APIs
 //!
-//! Your runtime should implement the [GetGenesisUtxo] and [GetEpochDurationApi] runtime APIs.
+//! Your runtime should implement the [GetGenesisUtxo], [GetEpochDurationApi] and [GetEpochScheduleApi] runtime APIs.
 //! For example, if your chain uses Aura for consensus, they may be implemented similar to this:
 //! ```rust,ignore
 //! impl sp_sidechain::GetGenesisUtxo<Block> for Runtime {
@@ -15,11 +15,20 @@
 //!
 //! impl sp_sidechain::GetEpochDurationApi<Block> for Runtime {
 //! 	fn get_epoch_duration_millis() -> u64 {
-//! 		BLOCKS_PER_EPOCH * MILLISECS_PER_BLOCK
+//! 		Sidechain::epoch_duration_millis()
+//! 	}
+//! }
+//!
+//! impl sp_sidechain::GetEpochScheduleApi<Block> for Runtime {
+//! 	fn get_epoch_schedule() -> ScEpochSchedule {
+//! 		Sidechain::epoch_schedule()
 //! 	}
 //! }
 //! ```
 //!
+//! For runtimes that do not implement [GetEpochScheduleApi] yet, epochs are computed using the
+//! constant epoch duration returned by [GetEpochDurationApi].
+//!
 //! ## Adding to the RPC stack
 //!
 //! Once the runtime APIs are in place, the RPC can be added to the node:
@@ -30,7 +39,7 @@
 //! # use sidechain_domain::mainchain_epoch::MainchainEpochConfig;
 //! # use sp_api::{ CallApiAt, ProvideRuntimeApi };
 //! # use sp_runtime::traits::Block as BlockT;
-//! # use sp_sidechain::{ GetEpochDurationApi, GetGenesisUtxo };
+//! # use sp_sidechain::{ GetEpochDurationApi, GetEpochScheduleApi, GetGenesisUtxo };
 //! # use std::sync::Arc;
 //! # use time_source::TimeSource;
 //! fn create_rpc<B: BlockT, C: Send + Sync + 'static>(
@@ -40,7 +49,7 @@
 //! ) -> Result<RpcModule<()>, Box<dyn std::error::Error + Send + Sync>>
 //! where
 //!     C: ProvideRuntimeApi<B> + GetBestHash<B> + CallApiAt<B>,
-//!     C::Api: GetGenesisUtxo<B> + GetEpochDurationApi<B>
+//!     C::Api: GetGenesisUtxo<B> + GetEpochDurationApi<B> + GetEpochScheduleApi<B>
 //! {
 //!
 //!     let mut module = RpcModule::new(());
@@ -74,6 +83,7 @@
 //!
 //! [GetGenesisUtxo]: sp_sidechain::GetGenesisUtxo
 //! [GetEpochDurationApi]: sp_sidechain::GetEpochDurationApi
+//! [GetEpochScheduleApi]: sp_sidechain::GetEpochScheduleApi
 #![deny(missing_docs)]
 use derive_new::new;
 use jsonrpsee::{
@@ -84,11 +94,11 @@ use jsonrpsee::{
 #[cfg(feature = "legacy-slotapi-compat")]
 use legacy_compat::slots::*;
 use sidechain_domain::mainchain_epoch::{MainchainEpochConfig, MainchainEpochDerivation};
-use sidechain_domain::{MainchainBlock, UtxoId};
-use sp_api::ProvideRuntimeApi;
-use sp_core::offchain::{Duration, Timestamp};
+use sidechain_domain::{MainchainBlock, ScEpochDuration, ScEpochSchedule, UtxoId};
+use sp_api::{ApiExt, ProvideRuntimeApi};
+use sp_core::offchain::Timestamp;
 use sp_runtime::traits::Block as BlockT;
-use sp_sidechain::{GetEpochDurationApi, GetGenesisUtxo};
+use sp_sidechain::{GetEpochDurationApi, GetEpochScheduleApi, GetGenesisUtxo};
 use std::sync::Arc;
 use time_source::*;
 use types::*;
@@ -154,11 +164,11 @@ impl<C, B> SidechainRpc<C, B> {
 
 /// Runtime client that can serve data to sidechain RPC
 trait SidechainRpcClient<Block: BlockT> {
-	/// Returns Partner Chain epoch duration
-	fn get_epoch_duration(
+	/// Returns Partner Chain epoch schedule
+	fn get_epoch_schedule(
 		&self,
 		best_block: Block::Hash,
-	) -> Result<Duration, Box<dyn std::error::Error + Send + Sync>>;
+	) -> Result<ScEpochSchedule, Box<dyn std::error::Error + Send + Sync>>;
 
 	/// Returns the Partner Chain's genesis UTXO
 	fn get_genesis_utxo(
@@ -177,20 +187,28 @@ where
 	Client: sp_api::CallApiAt<Block> + Send + Sync + 'static,
 	Client: ProvideRuntimeApi<Block>,
 	Client::Api: GetEpochDurationApi<Block>,
+	Client::Api: GetEpochScheduleApi<Block>,
 	Client::Api: GetGenesisUtxo<Block>,
 {
-	fn get_epoch_duration(
+	fn get_epoch_schedule(
 		&self,
 		best_block: Block::Hash,
-	) -> Result<Duration, Box<dyn std::error::Error + Send + Sync>> {
+	) -> Result<ScEpochSchedule, Box<dyn std::error::Error + Send + Sync>> {
 		#[cfg(feature = "legacy-slotapi-compat")]
 		if let Some(slot_config) = self.get_sc_slot_config(best_block) {
-			return Ok(Duration::from_millis(
+			return Ok(ScEpochSchedule::new(ScEpochDuration::from_millis(
 				u64::from(slot_config.slots_per_epoch) * slot_config.slot_duration_millis,
-			));
+			)));
 		}
 
-		Ok(Duration::from_millis(self.runtime_api().get_epoch_duration_millis(best_block)?))
+		let api = self.runtime_api();
+		if api.has_api::<dyn GetEpochScheduleApi<Block>>(best_block)? {
+			return Ok(api.get_epoch_schedule(best_block)?);
+		}
+
+		Ok(ScEpochSchedule::new(ScEpochDuration::from_millis(
+			api.get_epoch_duration_millis(best_block)?,
+		)))
 	}
 
 	fn get_genesis_utxo(
@@ -228,13 +246,21 @@ where
 
 		let current_timestamp = self.get_current_timestamp();
 
-		let sc_epoch_duration: Duration =
-			self.client.get_epoch_duration(best_block).map_err(error_object_from)?;
+		let sc_epoch_schedule =
+			self.client.get_epoch_schedule(best_block).map_err(error_object_from)?;
 
-		let current_sidechain_epoch = current_timestamp.unix_millis() / sc_epoch_duration.millis();
+		let current_sidechain_epoch = sc_epoch_schedule
+			.epoch_for_timestamp(current_timestamp.unix_millis())
+			.ok_or_else(|| {
+				error_object_from(format!(
+					"Current timestamp {} is earlier than the start of the epoch schedule",
+					current_timestamp.unix_millis()
+				))
+			})?;
 
-		let next_sidechain_epoch_timestamp =
-			Timestamp::from((current_sidechain_epoch + 1) * sc_epoch_duration.millis());
+		let next_sidechain_epoch_timestamp = Timestamp::from_unix_millis(
+			sc_epoch_schedule.epoch_start_millis(current_sidechain_epoch.next()),
+		);
 
 		let latest_mainchain_block =
 			self.data_source.get_latest_block_info().await.map_err(|err| {
@@ -250,7 +276,7 @@ where
 
 		Ok(GetStatusResponse {
 			sidechain: SidechainData {
-				epoch: current_sidechain_epoch,
+				epoch: current_sidechain_epoch.0,
 				#[cfg(feature = "legacy-slotapi-compat")]
 				slot: (self.client.get_maybe_slot_duration(best_block))
 					.map(|duration| current_timestamp.unix_millis() / duration),
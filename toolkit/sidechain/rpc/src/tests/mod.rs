@@ -25,6 +25,7 @@ mod get_status_tests {
 
 		let client = Arc::new(TestApi {
 			epoch_duration,
+			epoch_duration_change: None,
 			#[cfg(feature = "legacy-slotapi-compat")]
 			slot_duration: Some(slot_duration),
 		});
@@ -63,6 +64,37 @@ mod get_status_tests {
 		)
 	}
 
+	#[tokio::test]
+	async fn should_return_epoch_according_to_epoch_duration_change() {
+		use sidechain_domain::{ScEpochDurationChange, ScEpochNumber};
+		let current_time_millis: u64 = 1_200_000_000_000;
+		let epoch_duration = 6000;
+		let change_epoch = current_time_millis / epoch_duration - 10;
+		let client = Arc::new(TestApi {
+			epoch_duration,
+			epoch_duration_change: Some(ScEpochDurationChange {
+				epoch: ScEpochNumber(change_epoch),
+				epoch_duration: ScEpochDuration::from_millis(3000),
+			}),
+			..Default::default()
+		});
+		let api = SidechainRpc::<_, Block>::new(
+			client,
+			mock_mc_epoch_config(),
+			Arc::new(SidechainRpcDataSourceMock::new(mock_mainchain_block())),
+			Arc::new(MockedTimeSource { current_time_millis }),
+		);
+
+		let status_response = api.get_status().await.expect("should succeed");
+
+		// the last 10 epochs of 6000 ms are 20 epochs of 3000 ms after the change
+		assert_eq!(status_response.sidechain.epoch, change_epoch + 20);
+		assert_eq!(
+			status_response.sidechain.next_epoch_timestamp,
+			Timestamp::from_unix_millis(current_time_millis + 3000)
+		);
+	}
+
 	#[cfg(feature = "legacy-slotapi-compat")]
 	#[tokio::test]
 	async fn should_omit_slot_number_when_api_not_available_in_compat_mode() {
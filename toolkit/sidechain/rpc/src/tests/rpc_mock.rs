@@ -1,13 +1,13 @@
 use super::Block;
 use crate::{SidechainRpcClient, mock::mock_utxo_id};
-use sidechain_domain::UtxoId;
+use sidechain_domain::{ScEpochDuration, ScEpochDurationChange, ScEpochSchedule, UtxoId};
 use sp_blockchain::HeaderBackend;
-use sp_core::offchain::Duration;
 use sp_runtime::traits::{Block as BlockT, NumberFor, Zero};
 
 #[derive(Clone)]
 pub struct TestApi {
 	pub epoch_duration: u64,
+	pub epoch_duration_change: Option<ScEpochDurationChange>,
 	#[cfg(feature = "legacy-slotapi-compat")]
 	pub slot_duration: Option<u64>,
 }
@@ -16,6 +16,7 @@ impl Default for TestApi {
 	fn default() -> Self {
 		Self {
 			epoch_duration: 6000,
+			epoch_duration_change: None,
 			#[cfg(feature = "legacy-slotapi-compat")]
 			slot_duration: None,
 		}
@@ -23,11 +24,14 @@ impl Default for TestApi {
 }
 
 impl SidechainRpcClient<Block> for TestApi {
-	fn get_epoch_duration(
+	fn get_epoch_schedule(
 		&self,
 		_best_block: <Block as BlockT>::Hash,
-	) -> Result<sp_core::offchain::Duration, Box<dyn std::error::Error + Send + Sync>> {
-		Ok(Duration::from_millis(self.epoch_duration))
+	) -> Result<ScEpochSchedule, Box<dyn std::error::Error + Send + Sync>> {
+		Ok(ScEpochSchedule {
+			next_change: self.epoch_duration_change,
+			..ScEpochSchedule::new(ScEpochDuration::from_millis(self.epoch_duration))
+		})
 	}
 
 	fn get_genesis_utxo(
@@ -158,6 +158,13 @@
 //! }
 //! ```
 //!
+//! ## Main chain reference policy
+//!
+//! Verification by [McHashInherentDataProvider::new_verification] only requires the referenced Cardano block
+//! to be stable and not older than the one referenced by the parent block. Chains can restrict references further
+//! with [McReferencePolicy], read from the runtime, by calling [McHashInherentDataProvider::verify_reference_policy]
+//! on the verified provider. Nodes can also use [monitor::McReferenceMonitor] to alert when the reference stalls.
+//!
 //! [PartnerChainsProposer]: sp_partner_chains_consensus_aura::block_proposal::PartnerChainsProposer
 //! [PartnerChainsProposerFactory]: sp_partner_chains_consensus_aura::block_proposal::PartnerChainsProposerFactory
 
@@ -167,7 +174,7 @@ use async_trait::async_trait;
 use sidechain_domain::{byte_string::ByteString, *};
 use sp_blockchain::HeaderBackend;
 use sp_inherents::{InherentData, InherentDataProvider, InherentIdentifier};
-use sp_partner_chains_consensus_aura::inherent_digest::InherentDigest;
+use sp_partner_chains_consensus_aura::{InherentDataNotAvailable, inherent_digest::InherentDigest};
 use sp_runtime::{
 	DigestItem,
 	traits::{Block as BlockT, Header as HeaderT, Zero},
@@ -175,6 +182,7 @@ use sp_runtime::{
 use sp_timestamp::Timestamp;
 use std::{error::Error, ops::Deref};
 
+pub mod monitor;
 #[cfg(test)]
 mod test;
 
@@ -229,12 +237,23 @@ pub enum McHashInherentError {
 	/// Signals that a Cardano block referenced by a main chain reference hash could not be found
 	#[error("Main chain state {0} referenced in imported block at timestamp {1} not found")]
 	McStateReferenceInvalid(McBlockHash, Timestamp),
+	/// Signals that a Cardano block referenced by a main chain reference hash could not be found, but the
+	/// data source has not observed Cardano blocks up to the timestamp of the imported block yet
+	#[error(
+		"Main chain state {0} referenced in imported block at timestamp {1} not found, the data source has not observed main chain blocks up to this timestamp yet"
+	)]
+	McStateReferenceNotObservedYet(McBlockHash, Timestamp),
 	/// Signals that a main chain reference hash points to a Cardano block earlier than the one referenced
 	/// by the previous Partner Chain block
 	#[error(
 		"Main chain state {0} referenced in imported block at timestamp {1} corresponds to main chain block number which is lower than its parent's {2}<{3}"
 	)]
 	McStateReferenceRegressed(McBlockHash, Timestamp, McBlockNumber, McBlockNumber),
+	/// Signals that a main chain reference hash points to a Cardano block earlier than allowed by [McReferencePolicy]
+	#[error(
+		"Main chain state {0} referenced in imported block at timestamp {1} corresponds to main chain block number which is lower than required by the main chain reference policy {2}<{3}"
+	)]
+	McStateReferenceOutdated(McBlockHash, Timestamp, McBlockNumber, McBlockNumber),
 	/// Signals that a main chain reference hash is either missing from the block diget or can not be decoded
 	#[error("Failed to retrieve MC hash from digest: {0}")]
 	DigestError(String),
@@ -243,6 +262,24 @@ pub enum McHashInherentError {
 	StableBlockNotFoundByHash(McBlockHash),
 }
 
+impl McHashInherentError {
+	/// Returns true if the error may be resolved by retrying later, ie. the referenced Cardano block
+	/// may not be observed yet by the local data source
+	pub fn is_temporary(&self) -> bool {
+		matches!(self, McHashInherentError::McStateReferenceNotObservedYet(..))
+	}
+
+	/// Converts the error for use by verification inherent data providers factories, wrapping temporary
+	/// errors in [InherentDataNotAvailable] so that verification of the block can be deferred
+	pub fn into_verification_error(self) -> Box<dyn Error + Send + Sync> {
+		if self.is_temporary() {
+			Box::new(InherentDataNotAvailable(self.into()))
+		} else {
+			self.into()
+		}
+	}
+}
+
 impl From<MainchainBlock> for McHashInherentDataProvider {
 	fn from(mc_block: MainchainBlock) -> Self {
 		Self { mc_block, previous_mc_block: None }
@@ -298,6 +335,15 @@ pub trait McHashDataSource {
 		&self,
 		hash: McBlockHash,
 	) -> Result<Option<MainchainBlock>, Box<dyn std::error::Error + Send + Sync>>;
+
+	/// Returns the latest Cardano block observed by the data source, stable or not.
+	///
+	/// # Returns
+	/// * `Some(block)` - the latest observed block
+	/// * `None` - no block was observed yet
+	async fn get_tip(
+		&self,
+	) -> Result<Option<MainchainBlock>, Box<dyn std::error::Error + Send + Sync>>;
 }
 
 impl McHashInherentDataProvider {
@@ -358,7 +404,9 @@ impl McHashInherentDataProvider {
 	///
 	/// # Returns
 	/// This function will return an error if `mc_state_reference_hash` is not found or is before the block referenced by
-	/// the parent of the block being verified.
+	/// the parent of the block being verified. A reference that is not found is only reported as a temporary
+	/// [McHashInherentError::McStateReferenceNotObservedYet] error if the latest Cardano block observed by `block_source`
+	/// is older than `timestamp`, as every block stable at `timestamp` must have been observed otherwise.
 	///
 	/// Otherwise, the returned [McHashInherentDataProvider] instance will contain block data for `mc_state_reference_hash`.
 	pub async fn new_verification<Header>(
@@ -400,6 +448,51 @@ impl McHashInherentDataProvider {
 		}
 	}
 
+	/// Verifies that the main chain reference satisfies `policy`
+	///
+	/// # Arguments
+	/// - `policy`: main chain reference policy of the Partner Chain, read from the runtime at the parent block
+	/// - `timestamp`: timestamp of the block being verified
+	/// - `starts_new_epoch`: whether the block being verified is the first block of a Partner Chain epoch
+	/// - `data_source`: data source implementing [McHashDataSource]
+	///
+	/// The lag of the reference is measured against the latest stable block for `timestamp` observed by
+	/// `data_source`. A node whose data source lags behind the block producer's is therefore more lenient,
+	/// never stricter, than the block producer.
+	pub async fn verify_reference_policy(
+		&self,
+		policy: &McReferencePolicy,
+		timestamp: Timestamp,
+		starts_new_epoch: bool,
+		data_source: &(dyn McHashDataSource + Send + Sync),
+	) -> Result<(), McHashInherentError> {
+		if *policy == McReferencePolicy::default() {
+			return Ok(());
+		}
+		let Some(latest_stable_block) = data_source
+			.get_latest_stable_block_for(timestamp)
+			.await
+			.map_err(McHashInherentError::DataSourceError)?
+		else {
+			return Ok(());
+		};
+		let min_reference = policy.min_reference(
+			latest_stable_block.number,
+			self.previous_mc_block.as_ref().map(|block| block.number),
+			starts_new_epoch,
+		);
+		if self.mc_block.number < min_reference {
+			Err(McHashInherentError::McStateReferenceOutdated(
+				self.mc_hash(),
+				timestamp,
+				self.mc_block.number,
+				min_reference,
+			))
+		} else {
+			Ok(())
+		}
+	}
+
 	/// Returns the Cardano epoch containing the reference block
 	pub fn mc_epoch(&self) -> McEpochNumber {
 		self.mc_block.epoch
@@ -426,11 +519,19 @@ async fn get_mc_state_reference(
 	verified_block_mc_hash: McBlockHash,
 	data_source: &(dyn McHashDataSource + Send + Sync),
 ) -> Result<MainchainBlock, McHashInherentError> {
-	data_source
+	if let Some(block) = data_source
 		.get_stable_block_for(verified_block_mc_hash.clone(), timestamp)
 		.await
 		.map_err(McHashInherentError::DataSourceError)?
-		.ok_or(McHashInherentError::McStateReferenceInvalid(verified_block_mc_hash, timestamp))
+	{
+		return Ok(block);
+	}
+	let tip = data_source.get_tip().await.map_err(McHashInherentError::DataSourceError)?;
+	if tip.is_none_or(|tip| tip.timestamp.saturating_mul(1000) < timestamp.as_millis()) {
+		Err(McHashInherentError::McStateReferenceNotObservedYet(verified_block_mc_hash, timestamp))
+	} else {
+		Err(McHashInherentError::McStateReferenceInvalid(verified_block_mc_hash, timestamp))
+	}
 }
 
 #[async_trait::async_trait]
@@ -602,5 +703,11 @@ pub mod mock {
 				.cloned()
 				.or_else(|| self.unstable_blocks.iter().find(|b| b.hash == hash).cloned()))
 		}
+
+		async fn get_tip(
+			&self,
+		) -> Result<Option<MainchainBlock>, Box<dyn std::error::Error + Send + Sync>> {
+			Ok(self.unstable_blocks.last().or(self.stable_blocks.last()).cloned())
+		}
 	}
 }
@@ -0,0 +1,138 @@
+//! Monitoring of main chain reference progress
+//!
+//! A Partner Chain whose blocks keep referencing the same Cardano block does not observe any new Cardano
+//! state, which delays committee, bridge and other Cardano-driven updates. [McReferenceMonitor] tracks the
+//! main chain references of proposed and imported blocks and raises an alert when the reference does not
+//! advance for longer than the configured threshold.
+use sidechain_domain::McBlockNumber;
+use sp_timestamp::Timestamp;
+use std::{sync::Mutex, time::Duration};
+use substrate_prometheus_endpoint::{Gauge, PrometheusError, Registry, U64, register};
+
+/// Prometheus metrics exposed by [McReferenceMonitor]
+#[derive(Clone)]
+struct McReferenceMetrics {
+	reference_block_number: Gauge<U64>,
+	reference_stalled_seconds: Gauge<U64>,
+}
+
+impl McReferenceMetrics {
+	fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			reference_block_number: register(
+				Gauge::new(
+					"partner_chains_mc_reference_block_number",
+					"Cardano block number referenced by the latest Partner Chain block",
+				)?,
+				registry,
+			)?,
+			reference_stalled_seconds: register(
+				Gauge::new(
+					"partner_chains_mc_reference_stalled_seconds",
+					"Time for which the Cardano block referenced by Partner Chain blocks has not advanced",
+				)?,
+				registry,
+			)?,
+		})
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ReferenceState {
+	reference: McBlockNumber,
+	since: Timestamp,
+	alerted: bool,
+}
+
+/// Monitor raising an alert when the main chain reference of Partner Chain blocks stalls
+pub struct McReferenceMonitor {
+	stall_threshold: Duration,
+	metrics: Option<McReferenceMetrics>,
+	state: Mutex<Option<ReferenceState>>,
+}
+
+impl McReferenceMonitor {
+	/// Creates a monitor alerting when the reference does not advance for longer than `stall_threshold`
+	/// and registering its metrics in `registry`, if present
+	pub fn new(
+		stall_threshold: Duration,
+		registry: Option<&Registry>,
+	) -> Result<Self, PrometheusError> {
+		let metrics = registry.map(McReferenceMetrics::register).transpose()?;
+		Ok(Self { stall_threshold, metrics, state: Mutex::new(None) })
+	}
+
+	/// Records that a block with `timestamp` references Cardano block number `reference`.
+	///
+	/// Returns true if the reference is stalled, ie. it has not advanced for longer than the stall threshold.
+	pub fn observe(&self, reference: McBlockNumber, timestamp: Timestamp) -> bool {
+		let mut state = self.state.lock().expect("McReferenceMonitor lock is not poisoned");
+		let current = match *state {
+			Some(current) if current.reference >= reference => current,
+			Some(current) => {
+				if current.alerted {
+					log::info!(
+						"🔗 Main chain reference advanced to block {reference} after stalling"
+					);
+				}
+				ReferenceState { reference, since: timestamp, alerted: false }
+			},
+			None => ReferenceState { reference, since: timestamp, alerted: false },
+		};
+		let stalled_for =
+			Duration::from_millis(timestamp.as_millis().saturating_sub(*current.since));
+		let stalled = stalled_for > self.stall_threshold;
+		if stalled && !current.alerted {
+			log::warn!(
+				"⚠️ Main chain reference has not advanced from block {} for {}s. New Cardano state is not observed by the Partner Chain.",
+				current.reference,
+				stalled_for.as_secs()
+			);
+		}
+		*state = Some(ReferenceState { alerted: current.alerted || stalled, ..current });
+
+		if let Some(metrics) = &self.metrics {
+			metrics.reference_block_number.set(current.reference.0.into());
+			metrics.reference_stalled_seconds.set(stalled_for.as_secs());
+		}
+		stalled
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn monitor() -> McReferenceMonitor {
+		McReferenceMonitor::new(Duration::from_secs(60), None).unwrap()
+	}
+
+	#[test]
+	fn reference_advancing_is_not_stalled() {
+		let monitor = monitor();
+
+		assert!(!monitor.observe(McBlockNumber(10), Timestamp::new(0)));
+		assert!(!monitor.observe(McBlockNumber(11), Timestamp::new(50_000)));
+		assert!(!monitor.observe(McBlockNumber(12), Timestamp::new(100_000)));
+	}
+
+	#[test]
+	fn reference_not_advancing_beyond_threshold_is_stalled_until_it_advances() {
+		let monitor = monitor();
+
+		assert!(!monitor.observe(McBlockNumber(10), Timestamp::new(0)));
+		assert!(!monitor.observe(McBlockNumber(10), Timestamp::new(60_000)));
+		assert!(monitor.observe(McBlockNumber(10), Timestamp::new(60_001)));
+		assert!(monitor.observe(McBlockNumber(10), Timestamp::new(120_000)));
+		assert!(!monitor.observe(McBlockNumber(11), Timestamp::new(130_000)));
+	}
+
+	#[test]
+	fn reference_of_older_block_does_not_reset_stall() {
+		let monitor = monitor();
+
+		assert!(!monitor.observe(McBlockNumber(10), Timestamp::new(0)));
+		assert!(!monitor.observe(McBlockNumber(9), Timestamp::new(30_000)));
+		assert!(monitor.observe(McBlockNumber(10), Timestamp::new(90_000)));
+	}
+}
@@ -135,6 +135,139 @@ mod validation_tests {
 		assert_eq!(err.to_string(), StableBlockNotFoundByHash(mc_block_hash).to_string());
 	}
 
+	#[tokio::test]
+	async fn verification_is_deferred_if_data_source_has_not_observed_blocks_up_to_block_timestamp()
+	{
+		let mc_block_hash = McBlockHash([2; 32]);
+		let mc_hash_data_source = MockMcHashDataSource::new(vec![], vec![]);
+
+		let err = McHashInherentDataProvider::new_verification(
+			mock_header(McBlockHash([1; 32])),
+			Some(Timestamp::from(1000)),
+			30.into(),
+			mc_block_hash,
+			&mc_hash_data_source,
+		)
+		.await
+		.unwrap_err();
+
+		assert!(err.is_temporary());
+		assert!(err.into_verification_error().is::<InherentDataNotAvailable>());
+	}
+
+	#[tokio::test]
+	async fn verification_is_not_deferred_if_mc_state_reference_does_not_exist() {
+		let mc_block_hash = McBlockHash([2; 32]);
+		let mc_hash_data_source = MockMcHashDataSource::new(vec![mc_block(1)], vec![mc_block(3)]);
+
+		let err = McHashInherentDataProvider::new_verification(
+			mock_header(McBlockHash([1; 32])),
+			Some(Timestamp::from(1000)),
+			Timestamp::from(3000),
+			mc_block_hash.clone(),
+			&mc_hash_data_source,
+		)
+		.await
+		.unwrap_err();
+
+		assert_eq!(
+			err.to_string(),
+			McStateReferenceInvalid(mc_block_hash, Timestamp::from(3000)).to_string()
+		);
+		assert!(!err.is_temporary());
+		assert!(!err.into_verification_error().is::<InherentDataNotAvailable>());
+	}
+
+	#[test]
+	fn verification_is_not_deferred_if_mc_state_reference_regressed() {
+		let err = McStateReferenceRegressed(
+			McBlockHash([2; 32]),
+			30.into(),
+			McBlockNumber(0),
+			McBlockNumber(1),
+		);
+
+		assert!(!err.is_temporary());
+		assert!(!err.into_verification_error().is::<InherentDataNotAvailable>());
+	}
+
+	fn mc_block(number: u32) -> MainchainBlock {
+		MainchainBlock {
+			number: McBlockNumber(number),
+			hash: McBlockHash([number as u8; 32]),
+			epoch: McEpochNumber(1),
+			slot: McSlotNumber(number.into()),
+			timestamp: number.into(),
+		}
+	}
+
+	async fn verify_policy(
+		policy: McReferencePolicy,
+		reference: u32,
+		starts_new_epoch: bool,
+	) -> Result<(), McHashInherentError> {
+		let parent_reference = 10;
+		let latest_stable = 100;
+		let mc_hash_data_source = MockMcHashDataSource::from(vec![
+			mc_block(parent_reference),
+			mc_block(reference),
+			mc_block(latest_stable),
+		]);
+		let provider = McHashInherentDataProvider::new_verification(
+			mock_header(mc_block(parent_reference).hash),
+			Some(Timestamp::from(1000)),
+			Timestamp::from(2000),
+			mc_block(reference).hash,
+			&mc_hash_data_source,
+		)
+		.await
+		.unwrap();
+		provider
+			.verify_reference_policy(
+				&policy,
+				Timestamp::from(2000),
+				starts_new_epoch,
+				&mc_hash_data_source,
+			)
+			.await
+	}
+
+	#[tokio::test]
+	async fn default_reference_policy_accepts_any_stable_reference() {
+		assert!(verify_policy(McReferencePolicy::default(), 10, true).await.is_ok());
+	}
+
+	#[tokio::test]
+	async fn reference_policy_rejects_reference_lagging_too_much() {
+		let policy = McReferencePolicy { max_lag_blocks: Some(50), min_advance_per_epoch: 0 };
+
+		assert!(verify_policy(policy, 50, false).await.is_ok());
+		let err = verify_policy(policy, 49, false).await.unwrap_err();
+		assert_eq!(
+			err.to_string(),
+			McStateReferenceOutdated(
+				McBlockHash([49; 32]),
+				Timestamp::from(2000),
+				McBlockNumber(49),
+				McBlockNumber(50)
+			)
+			.to_string()
+		);
+		assert!(!err.is_temporary());
+	}
+
+	#[tokio::test]
+	async fn reference_policy_rejects_reference_not_advanced_at_epoch_start() {
+		let policy = McReferencePolicy { max_lag_blocks: None, min_advance_per_epoch: 5 };
+
+		assert!(verify_policy(policy, 15, true).await.is_ok());
+		assert!(verify_policy(policy, 14, false).await.is_ok());
+		assert!(matches!(
+			verify_policy(policy, 14, true).await,
+			Err(McStateReferenceOutdated(_, _, McBlockNumber(14), McBlockNumber(15)))
+		));
+	}
+
 	pub fn mock_header(mc_hash: McBlockHash) -> Header {
 		Header::new(
 			Default::default(),
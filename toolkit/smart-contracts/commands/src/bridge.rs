@@ -1,6 +1,7 @@
 use crate::{GenesisUtxo, PaymentFilePath, transaction_submitted_json};
 use partner_chains_cardano_offchain::bridge::{
-	create_validator_utxos, deposit_with_ics_spend, deposit_without_ics_input, init_ics_scripts,
+	create_validator_utxos, create_validator_utxos_in_batches, deposit_with_ics_spend,
+	deposit_without_ics_input, init_ics_scripts,
 };
 use sidechain_domain::AssetId;
 use sp_runtime::AccountId32;
@@ -70,12 +71,28 @@ pub struct BridgeCreateUtxosCmd {
 	#[arg(long)]
 	/// Number of UTXOs to create
 	amount: NonZero<u64>,
+	#[arg(long)]
+	/// If provided, UTXOs are created in chained transactions, each creating at most this number of UTXOs.
+	/// Requires single key governance.
+	batch_size: Option<NonZero<usize>>,
 }
 
 impl BridgeCreateUtxosCmd {
 	pub async fn execute(self) -> crate::SubCmdResult {
 		let payment_key = self.payment_key_file.read_key()?;
 		let client = self.common_arguments.get_ogmios_client().await?;
+		if let Some(batch_size) = self.batch_size {
+			let results = create_validator_utxos_in_batches(
+				self.genesis_utxo.into(),
+				self.amount,
+				batch_size,
+				&payment_key,
+				&client,
+				&self.common_arguments.retries(),
+			)
+			.await?;
+			return Ok(serde_json::json!(results));
+		}
 		let result = create_validator_utxos(
 			self.genesis_utxo.into(),
 			self.amount,
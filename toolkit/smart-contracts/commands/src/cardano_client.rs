@@ -0,0 +1,174 @@
+//! Client used by the commands to query Cardano and submit transactions.
+//!
+//! Dispatches to either Ogmios or a Blockfrost-compatible API, depending on the selected backend.
+use ogmios_client::{
+	OgmiosClientError,
+	blockfrost::BlockfrostClient,
+	chain_sync::Point,
+	jsonrpsee::OgmiosClients,
+	mempool::{QueryTransactionStatus, TransactionStatus},
+	query_ledger_state::{
+		EraSummary, OgmiosTip, ProtocolParametersResponse, QueryLedgerState, QueryUtxoByUtxoId,
+	},
+	query_network::{QueryNetwork, ShelleyGenesisConfigurationResponse},
+	transactions::{OgmiosEvaluateTransactionResponse, SubmitTransactionResponse, Transactions},
+	types::OgmiosUtxo,
+};
+use sidechain_domain::{McTxHash, UtxoId};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+/// Backend used for querying Cardano ledger state and submitting transactions
+pub enum CardanoBackend {
+	#[default]
+	/// Ogmios JSON-RPC server
+	Ogmios,
+	/// Blockfrost-compatible HTTP API, for example Blockfrost or Dolos MiniBF
+	Blockfrost,
+}
+
+/// Client of the selected [CardanoBackend]
+pub enum CardanoClient {
+	/// Ogmios client, either HTTP or WebSockets
+	Ogmios(OgmiosClients),
+	/// Blockfrost-compatible API client
+	Blockfrost(BlockfrostClient),
+}
+
+impl QueryLedgerState for CardanoClient {
+	async fn get_tip(&self) -> Result<OgmiosTip, OgmiosClientError> {
+		match self {
+			Self::Ogmios(client) => client.get_tip().await,
+			Self::Blockfrost(client) => client.get_tip().await,
+		}
+	}
+
+	async fn era_summaries(&self) -> Result<Vec<EraSummary>, OgmiosClientError> {
+		match self {
+			Self::Ogmios(client) => client.era_summaries().await,
+			Self::Blockfrost(client) => client.era_summaries().await,
+		}
+	}
+
+	async fn query_utxos(
+		&self,
+		addresses: &[String],
+	) -> Result<Vec<OgmiosUtxo>, OgmiosClientError> {
+		match self {
+			Self::Ogmios(client) => client.query_utxos(addresses).await,
+			Self::Blockfrost(client) => client.query_utxos(addresses).await,
+		}
+	}
+
+	async fn query_protocol_parameters(
+		&self,
+	) -> Result<ProtocolParametersResponse, OgmiosClientError> {
+		match self {
+			Self::Ogmios(client) => client.query_protocol_parameters().await,
+			Self::Blockfrost(client) => client.query_protocol_parameters().await,
+		}
+	}
+}
+
+impl QueryUtxoByUtxoId for CardanoClient {
+	async fn query_utxo_by_id(
+		&self,
+		utxo: UtxoId,
+	) -> Result<Option<OgmiosUtxo>, OgmiosClientError> {
+		match self {
+			Self::Ogmios(client) => client.query_utxo_by_id(utxo).await,
+			Self::Blockfrost(client) => client.query_utxo_by_id(utxo).await,
+		}
+	}
+}
+
+impl QueryNetwork for CardanoClient {
+	async fn shelley_genesis_configuration(
+		&self,
+	) -> Result<ShelleyGenesisConfigurationResponse, OgmiosClientError> {
+		match self {
+			Self::Ogmios(client) => client.shelley_genesis_configuration().await,
+			Self::Blockfrost(client) => client.shelley_genesis_configuration().await,
+		}
+	}
+
+	async fn get_block_height(&self) -> Result<u64, OgmiosClientError> {
+		match self {
+			Self::Ogmios(client) => client.get_block_height().await,
+			Self::Blockfrost(client) => client.get_block_height().await,
+		}
+	}
+
+	async fn get_transaction_block_height(
+		&self,
+		tx_hash: McTxHash,
+	) -> Result<Option<u64>, OgmiosClientError> {
+		match self {
+			Self::Ogmios(client) => client.get_transaction_block_height(tx_hash).await,
+			Self::Blockfrost(client) => client.get_transaction_block_height(tx_hash).await,
+		}
+	}
+}
+
+impl Transactions for CardanoClient {
+	async fn evaluate_transaction(
+		&self,
+		tx_bytes: &[u8],
+	) -> Result<Vec<OgmiosEvaluateTransactionResponse>, OgmiosClientError> {
+		match self {
+			Self::Ogmios(client) => client.evaluate_transaction(tx_bytes).await,
+			Self::Blockfrost(client) => client.evaluate_transaction(tx_bytes).await,
+		}
+	}
+
+	async fn evaluate_transaction_with_additional_utxos(
+		&self,
+		tx_bytes: &[u8],
+		additional_utxos: &[OgmiosUtxo],
+	) -> Result<Vec<OgmiosEvaluateTransactionResponse>, OgmiosClientError> {
+		match self {
+			Self::Ogmios(client) => {
+				client
+					.evaluate_transaction_with_additional_utxos(tx_bytes, additional_utxos)
+					.await
+			},
+			Self::Blockfrost(client) => {
+				client
+					.evaluate_transaction_with_additional_utxos(tx_bytes, additional_utxos)
+					.await
+			},
+		}
+	}
+
+	async fn submit_transaction(
+		&self,
+		tx_bytes: &[u8],
+	) -> Result<SubmitTransactionResponse, OgmiosClientError> {
+		match self {
+			Self::Ogmios(client) => client.submit_transaction(tx_bytes).await,
+			Self::Blockfrost(client) => client.submit_transaction(tx_bytes).await,
+		}
+	}
+}
+
+impl QueryTransactionStatus for CardanoClient {
+	async fn transaction_status(
+		&self,
+		tx_hash: McTxHash,
+	) -> Result<TransactionStatus, OgmiosClientError> {
+		match self {
+			Self::Ogmios(client) => client.transaction_status(tx_hash).await,
+			Self::Blockfrost(client) => client.transaction_status(tx_hash).await,
+		}
+	}
+
+	async fn transaction_status_since(
+		&self,
+		tx_hash: McTxHash,
+		since: Point,
+	) -> Result<TransactionStatus, OgmiosClientError> {
+		match self {
+			Self::Ogmios(client) => client.transaction_status_since(tx_hash, since).await,
+			Self::Blockfrost(client) => client.transaction_status_since(tx_hash, since).await,
+		}
+	}
+}
@@ -1,10 +1,11 @@
 use crate::{GenesisUtxo, PaymentFilePath};
 use partner_chains_cardano_offchain::governed_map::{
-	run_get, run_insert, run_list, run_remove, run_update,
+	run_get, run_insert, run_insert_many, run_list, run_remove, run_update,
 };
 use serde_json::json;
 use sidechain_domain::byte_string::ByteString;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::num::NonZero;
 
 #[derive(Clone, Debug, clap::Subcommand)]
 #[allow(clippy::large_enum_variant)]
@@ -15,6 +16,10 @@ pub enum GovernedMapCmd {
 	/// NOTE: In rare cases, race conditions may occur, and two inserts with the same key will both succeed.
 	/// In that case the second one in terms of block and transaction number is considered valid.
 	Insert(InsertCmd),
+	/// Inserts multiple key-value pairs into the Governed Map, using as few transactions as possible.
+	/// Keys that already have the same value are skipped. Fails if any key already has a different value.
+	/// Requires single key governance.
+	InsertMany(InsertManyCmd),
 	/// Updates a key-value pair in the Governed Map. If the key is missing it won't be inserted.
 	Update(UpdateCmd),
 	/// Removes a key-value pair from the Governed Map
@@ -30,6 +35,7 @@ impl GovernedMapCmd {
 	pub async fn execute(self) -> crate::SubCmdResult {
 		match self {
 			Self::Insert(cmd) => cmd.execute().await,
+			Self::InsertMany(cmd) => cmd.execute().await,
 			Self::Update(cmd) => cmd.execute().await,
 			Self::Remove(cmd) => cmd.execute().await,
 			Self::List(cmd) => cmd.execute().await,
@@ -77,6 +83,65 @@ impl InsertCmd {
 	}
 }
 
+#[derive(Clone, Debug, clap::Parser)]
+/// Command for inserting multiple key-value pairs into the Governed Map
+pub struct InsertManyCmd {
+	#[clap(flatten)]
+	common_arguments: crate::CommonArguments,
+	#[arg(long)]
+	/// Path to JSON file with an object mapping keys to hex encoded values,
+	/// in the same format as the output of the `list` command.
+	entries_file: String,
+	#[arg(long, default_value = "50")]
+	/// Maximum number of entries inserted in a single transaction.
+	batch_size: NonZero<usize>,
+	#[clap(flatten)]
+	/// Path to the payment key file
+	payment_key_file: PaymentFilePath,
+	#[clap(flatten)]
+	/// Genesis UTXO
+	genesis_utxo: GenesisUtxo,
+}
+
+impl InsertManyCmd {
+	/// Inserts key-value pairs from the entries file into the Governed Map.
+	pub async fn execute(self) -> crate::SubCmdResult {
+		let payment_key = self.payment_key_file.read_key()?;
+		let entries: BTreeMap<String, String> =
+			serde_json::from_reader(std::fs::File::open(&self.entries_file).map_err(|e| {
+				anyhow::anyhow!("Could not open entries file '{}': {e}", self.entries_file)
+			})?)?;
+		let entries = (entries.into_iter())
+			.map(|(key, value)| {
+				let value = ByteString::decode_hex(&value)
+					.map_err(|e| anyhow::anyhow!("Invalid value for key '{key}': {e}"))?;
+				Ok((key, value))
+			})
+			.collect::<anyhow::Result<Vec<(String, ByteString)>>>()?;
+
+		let client = self.common_arguments.get_ogmios_client().await?;
+
+		let results = run_insert_many(
+			self.genesis_utxo.into(),
+			entries.clone(),
+			self.batch_size,
+			&payment_key,
+			&client,
+			&self.common_arguments.retries(),
+		)
+		.await?;
+		let results: Vec<serde_json::Value> = results
+			.into_iter()
+			.map(|result| {
+				let mut json = json!(result);
+				json["key"] = json!(entries[result.operation].0);
+				json
+			})
+			.collect();
+		Ok(json!(results))
+	}
+}
+
 #[derive(Clone, Debug, clap::Parser)]
 /// Command for updating a existing key-value pair in the Governed Map
 pub struct UpdateCmd {
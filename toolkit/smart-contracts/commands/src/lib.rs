@@ -6,8 +6,8 @@
 //!
 //! Most type commands (usualy ending in "Cmd") take a [CommonArguments]
 //! struct as argument. It stores the information neccessary for connecting
-//! to the Ogmios server and retrying the operations like checking if a transaction
-//! is included in the blockchain.
+//! to the Ogmios server (or a Blockfrost-compatible API) and retrying the operations
+//! like checking if a transaction is included in the blockchain.
 //!
 //! ## Subcommands
 //!
@@ -22,9 +22,13 @@
 //!
 //! Most commands return result of [serde_json::Value].
 //! The returned value is printed to the ouptut at the end of the command execution.
-use ogmios_client::jsonrpsee::{OgmiosClients, client_for_url};
+use cardano_client::{CardanoBackend, CardanoClient};
+use ogmios_client::{blockfrost::BlockfrostClient, jsonrpsee::client_for_url};
 use partner_chains_cardano_offchain::{
-	await_tx::FixedDelayRetries,
+	await_tx::{
+		ConfirmationDepth, ExponentialBackoffRetries, FixedDelayRetries, OgmiosChainSync,
+		RetryStrategy,
+	},
 	cardano_keys::{CardanoKeyFileContent, CardanoPaymentSigningKey},
 	multisig::MultiSigSmartContractResult,
 };
@@ -34,6 +38,7 @@ use std::time::Duration;
 
 pub mod assemble_tx;
 pub mod bridge;
+pub mod cardano_client;
 pub mod d_parameter;
 pub mod get_scripts;
 pub mod governance;
@@ -42,6 +47,7 @@ pub mod permissioned_candidates;
 pub mod register;
 pub mod reserve;
 pub mod sign_tx;
+pub mod tx_status;
 pub mod versioning;
 
 #[derive(Clone, Debug, clap::Subcommand)]
@@ -56,6 +62,9 @@ pub enum SmartContractsCmd {
 	UpsertPermissionedCandidates(permissioned_candidates::UpsertPermissionedCandidatesCmd),
 	/// Register candidate
 	Register(register::RegisterCmd),
+	/// Register multiple candidates paid for by the same payment key, using as few transactions as possible.
+	/// Candidates already registered with the same keys are skipped.
+	RegisterMany(register::RegisterManyCmd),
 	/// Deregister candidate
 	Deregister(register::DeregisterCmd),
 	#[command(subcommand)]
@@ -76,6 +85,8 @@ pub enum SmartContractsCmd {
 	Bridge(bridge::BridgeCmd),
 	/// Upsert versioned smart contract
 	UpsertScript(versioning::UpsertScriptCmd),
+	/// Check the status of a submitted transaction
+	TxStatus(tx_status::TxStatusCmd),
 }
 
 #[derive(Clone, Debug, clap::Parser)]
@@ -86,32 +97,100 @@ pub struct CommonArguments {
 	/// URL of the Ogmios server
 	ogmios_url: String,
 	#[arg(default_value = "180", long, env)]
-	/// Timeout in seconds for Ogmios requests.
+	/// Timeout in seconds for Ogmios requests. Also used for Blockfrost requests.
 	ogmios_requests_timeout_seconds: u64,
+	#[arg(default_value = "ogmios", long, value_enum, env)]
+	/// Backend used for querying Cardano and submitting transactions
+	cardano_backend: CardanoBackend,
+	#[arg(long, env, required_if_eq("cardano_backend", "blockfrost"))]
+	/// URL of the Blockfrost-compatible API, for example `https://cardano-preview.blockfrost.io/api/v0`.
+	/// Required when `blockfrost` backend is selected.
+	blockfrost_url: Option<String>,
+	#[arg(long, env)]
+	/// Blockfrost project id. Not needed for self-hosted Blockfrost-compatible APIs, like Dolos MiniBF.
+	blockfrost_project_id: Option<String>,
 	#[arg(default_value = "5", long)]
 	/// Delay between retries in seconds. System will wait this long between
 	/// queries checking if transaction is included in the blockchain.
+	/// For `exponential-backoff` strategy it is the initial delay.
 	retry_delay_seconds: u64,
 	#[arg(default_value = "59", long)]
 	/// Number of retries. After transaction is submitted, system will try to check
 	/// if it's included in the blockchain this many times.
 	retry_count: usize,
+	#[arg(default_value = "fixed", long, value_enum)]
+	/// Strategy of delays between queries checking if transaction is included in the blockchain
+	retry_strategy: RetryStrategyKind,
+	#[arg(default_value = "60", long)]
+	/// Maximum delay between retries in seconds, used by `exponential-backoff` strategy
+	retry_max_delay_seconds: u64,
+	#[arg(default_value = "0", long)]
+	/// Number of blocks that have to be built on top of the block including the transaction,
+	/// before it is considered confirmed. While waiting, the tip is checked every `retry-delay-seconds`,
+	/// at most `retry-count` times. Rolled back transactions are detected and reported.
+	/// With Ogmios backend, the block including the transaction is found by following the chain,
+	/// which requires `ws` or `wss` Ogmios URL.
+	confirmations: u64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+/// Strategy of delays between checks if a transaction is included in the blockchain
+pub enum RetryStrategyKind {
+	#[default]
+	/// Constant delay between checks
+	Fixed,
+	/// Delay doubling after each check, up to the maximum, with random jitter
+	ExponentialBackoff,
 }
 
 impl CommonArguments {
-	/// Connects to the Ogmios server and returns a client
-	pub async fn get_ogmios_client(&self) -> crate::CmdResult<OgmiosClients> {
-		Ok(client_for_url(
-			&self.ogmios_url,
-			Duration::from_secs(self.ogmios_requests_timeout_seconds),
-		)
-		.await
-		.map_err(|e| format!("Failed to connect to Ogmios at {} with: {}", &self.ogmios_url, e))?)
+	/// Connects to the selected Cardano backend and returns a client
+	pub async fn get_ogmios_client(&self) -> crate::CmdResult<CardanoClient> {
+		let timeout = Duration::from_secs(self.ogmios_requests_timeout_seconds);
+		match self.cardano_backend {
+			CardanoBackend::Ogmios => {
+				Ok(CardanoClient::Ogmios(client_for_url(&self.ogmios_url, timeout).await.map_err(
+					|e| format!("Failed to connect to Ogmios at {} with: {}", &self.ogmios_url, e),
+				)?))
+			},
+			CardanoBackend::Blockfrost => {
+				let url = self
+					.blockfrost_url
+					.as_deref()
+					.ok_or("--blockfrost-url is required for blockfrost backend")?;
+				Ok(CardanoClient::Blockfrost(BlockfrostClient::new(
+					url,
+					timeout,
+					self.blockfrost_project_id.clone(),
+				)))
+			},
+		}
 	}
 
-	/// Builds a `FixedDelayRetries` instance for retrying failed operations
-	pub fn retries(&self) -> FixedDelayRetries {
-		FixedDelayRetries::new(Duration::from_secs(self.retry_delay_seconds), self.retry_count)
+	/// Builds the transaction awaiting strategy from the retry and confirmation arguments
+	pub fn retries(&self) -> ConfirmationDepth<RetryStrategy> {
+		let delay = Duration::from_secs(self.retry_delay_seconds);
+		let strategy = match self.retry_strategy {
+			RetryStrategyKind::Fixed => {
+				RetryStrategy::FixedDelay(FixedDelayRetries::new(delay, self.retry_count))
+			},
+			RetryStrategyKind::ExponentialBackoff => {
+				RetryStrategy::ExponentialBackoff(ExponentialBackoffRetries::new(
+					delay,
+					Duration::from_secs(self.retry_max_delay_seconds),
+					self.retry_count,
+				))
+			},
+		};
+		let confirmation_depth =
+			ConfirmationDepth::new(strategy, self.confirmations, delay, self.retry_count);
+		// Chain synchronization is available only over WebSockets
+		if self.cardano_backend == CardanoBackend::Ogmios && self.ogmios_url.starts_with("ws") {
+			let timeout = Duration::from_secs(self.ogmios_requests_timeout_seconds);
+			confirmation_depth.with_chain_sync(OgmiosChainSync::new(&self.ogmios_url, timeout))
+		} else {
+			confirmation_depth
+		}
 	}
 }
 
@@ -129,6 +208,7 @@ impl SmartContractsCmd {
 			Self::UpsertDParameter(cmd) => cmd.execute().await,
 			Self::UpsertPermissionedCandidates(cmd) => cmd.execute().await,
 			Self::Register(cmd) => cmd.execute().await,
+			Self::RegisterMany(cmd) => cmd.execute().await,
 			Self::Deregister(cmd) => cmd.execute().await,
 			Self::Reserve(cmd) => cmd.execute().await,
 			Self::AssembleAndSubmitTx(cmd) => cmd.execute().await,
@@ -136,6 +216,7 @@ impl SmartContractsCmd {
 			Self::GovernedMap(cmd) => cmd.execute().await,
 			Self::Bridge(cmd) => cmd.execute().await,
 			Self::UpsertScript(cmd) => cmd.execute().await,
+			Self::TxStatus(cmd) => cmd.execute().await,
 		}?;
 		println!("{}", result);
 		Ok(())
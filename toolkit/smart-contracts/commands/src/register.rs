@@ -1,9 +1,12 @@
 use crate::{GenesisUtxo, PaymentFilePath, option_to_json, transaction_submitted_json};
-use partner_chains_cardano_offchain::register::{run_deregister, run_register};
+use partner_chains_cardano_offchain::register::{run_deregister, run_register, run_register_many};
+use serde::Deserialize;
+use serde_json::json;
 use sidechain_domain::{
-	AdaBasedStaking, CandidateRegistration, MainchainSignature, PermissionedCandidateData,
-	SidechainSignature, StakePoolPublicKey, UtxoId,
+	AdaBasedStaking, CandidateRegistration, FromStrStdErr, MainchainKeyHash, MainchainSignature,
+	PermissionedCandidateData, SidechainSignature, StakeOwnership, StakePoolPublicKey, UtxoId,
 };
+use std::num::NonZero;
 
 /// Command for registering a candidate on the main chain
 #[derive(Clone, Debug, clap::Parser)]
@@ -25,12 +28,15 @@ pub struct RegisterCmd {
 	#[arg(long, alias = "sidechain-signature")]
 	/// Hex string of bytes of the registration message signature by partner-chain key, obtained by 'registration-signatures' command
 	partner_chain_signature: SidechainSignature,
-	#[arg(long)]
+	#[arg(long, required_unless_present = "token_based_staking")]
 	/// Hex string representing bytes of the Stake Pool Verification Key
-	spo_public_key: StakePoolPublicKey,
-	#[arg(long)]
+	spo_public_key: Option<StakePoolPublicKey>,
+	#[arg(long, required_unless_present = "token_based_staking")]
 	/// Hex string of bytes of the registration message signature by main chain key, obtained by 'registration-signatures' command
-	spo_signature: MainchainSignature,
+	spo_signature: Option<MainchainSignature>,
+	#[arg(long, conflicts_with_all = ["spo_public_key", "spo_signature"])]
+	/// Register a candidate with token-based stake, instead of stake delegated to a stake pool
+	token_based_staking: bool,
 }
 
 impl RegisterCmd {
@@ -38,11 +44,14 @@ impl RegisterCmd {
 	pub async fn execute(self) -> crate::SubCmdResult {
 		let payment_key = self.payment_key_file.read_key()?;
 		let client = self.common_arguments.get_ogmios_client().await?;
-		let candidate_registration = CandidateRegistration {
-			stake_ownership: AdaBasedStaking {
-				pub_key: self.spo_public_key,
-				signature: self.spo_signature,
+		let stake_ownership = match (self.spo_public_key, self.spo_signature) {
+			(Some(pub_key), Some(signature)) => {
+				StakeOwnership::AdaBased(AdaBasedStaking { pub_key, signature })
 			},
+			_ => StakeOwnership::TokenBased,
+		};
+		let candidate_registration = CandidateRegistration {
+			stake_ownership,
 			partner_chain_pub_key: self.partner_chain_public_keys.sidechain_public_key,
 			partner_chain_signature: self.partner_chain_signature,
 			own_pkh: payment_key.to_pub_key_hash(),
@@ -62,6 +71,122 @@ impl RegisterCmd {
 	}
 }
 
+#[derive(Clone, Debug, clap::Parser)]
+/// Command for registering multiple candidates on the main chain in chained transactions
+pub struct RegisterManyCmd {
+	#[clap(flatten)]
+	common_arguments: crate::CommonArguments,
+	#[clap(flatten)]
+	/// Genesis UTXO
+	genesis_utxo: GenesisUtxo,
+	#[arg(long)]
+	/// Path to JSON file with a list of registrations. Each registration is an object with fields named
+	/// like arguments of the `register` command: `registration_utxo`, `partner_chain_public_keys`,
+	/// `partner_chain_signature` and, unless the candidate uses token-based staking, `spo_public_key`
+	/// and `spo_signature`. Registration UTXOs have to be owned by the payment key.
+	registrations_file: String,
+	#[arg(long, default_value = "10")]
+	/// Maximum number of registrations in a single transaction.
+	batch_size: NonZero<usize>,
+	#[clap(flatten)]
+	/// Path to the payment key file
+	payment_key_file: PaymentFilePath,
+}
+
+/// Registration read from the registrations file of [RegisterManyCmd]
+#[derive(Clone, Debug, Deserialize)]
+struct RegistrationEntry {
+	registration_utxo: String,
+	partner_chain_public_keys: String,
+	partner_chain_signature: String,
+	spo_public_key: Option<String>,
+	spo_signature: Option<String>,
+}
+
+impl RegistrationEntry {
+	fn to_candidate_registration(
+		&self,
+		own_pkh: MainchainKeyHash,
+	) -> anyhow::Result<CandidateRegistration> {
+		let stake_ownership = match (&self.spo_public_key, &self.spo_signature) {
+			(Some(pub_key), Some(signature)) => StakeOwnership::AdaBased(AdaBasedStaking {
+				pub_key: parse_field("spo_public_key", pub_key)?,
+				signature: parse_field("spo_signature", signature)?,
+			}),
+			(None, None) => StakeOwnership::TokenBased,
+			_ => {
+				return Err(anyhow::anyhow!(
+					"Both 'spo_public_key' and 'spo_signature' have to be given for ADA-based staking"
+				));
+			},
+		};
+		let keys: PermissionedCandidateData =
+			parse_field("partner_chain_public_keys", &self.partner_chain_public_keys)?;
+		Ok(CandidateRegistration {
+			stake_ownership,
+			partner_chain_pub_key: keys.sidechain_public_key,
+			partner_chain_signature: parse_field(
+				"partner_chain_signature",
+				&self.partner_chain_signature,
+			)?,
+			own_pkh,
+			registration_utxo: parse_field("registration_utxo", &self.registration_utxo)?,
+			keys: keys.keys,
+		})
+	}
+}
+
+fn parse_field<T: FromStrStdErr>(field: &str, value: &str) -> anyhow::Result<T> {
+	T::from_str(value).map_err(|e| {
+		let e: Box<dyn std::error::Error + Send + Sync> = e.into();
+		anyhow::anyhow!("Invalid '{field}' value '{value}': {e}")
+	})
+}
+
+impl RegisterManyCmd {
+	/// Registers candidates from the registrations file on the main chain.
+	pub async fn execute(self) -> crate::SubCmdResult {
+		let payment_key = self.payment_key_file.read_key()?;
+		let entries: Vec<RegistrationEntry> = serde_json::from_reader(
+			std::fs::File::open(&self.registrations_file).map_err(|e| {
+				anyhow::anyhow!(
+					"Could not open registrations file '{}': {e}",
+					self.registrations_file
+				)
+			})?,
+		)?;
+		let registrations = (entries.iter())
+			.enumerate()
+			.map(|(i, entry)| {
+				entry
+					.to_candidate_registration(payment_key.to_pub_key_hash())
+					.map_err(|e| anyhow::anyhow!("Invalid registration {i}: {e}"))
+			})
+			.collect::<anyhow::Result<Vec<_>>>()?;
+
+		let client = self.common_arguments.get_ogmios_client().await?;
+
+		let results = run_register_many(
+			self.genesis_utxo.into(),
+			&registrations,
+			self.batch_size,
+			&payment_key,
+			&client,
+			&self.common_arguments.retries(),
+		)
+		.await?;
+		let results: Vec<serde_json::Value> = results
+			.into_iter()
+			.map(|result| {
+				let mut json = json!(result);
+				json["registration_utxo"] = json!(entries[result.operation].registration_utxo);
+				json
+			})
+			.collect();
+		Ok(json!(results))
+	}
+}
+
 #[derive(Clone, Debug, clap::Parser)]
 /// Command for deregistering a candidate on the main chain
 pub struct DeregisterCmd {
@@ -0,0 +1,139 @@
+use ogmios_client::{
+	chain_sync::Point,
+	mempool::{QueryTransactionStatus, TransactionStatus},
+};
+use serde_json::json;
+use sidechain_domain::McTxHash;
+
+#[derive(Clone, Debug, clap::Parser)]
+/// Command for checking the status of a submitted transaction.
+///
+/// Reports one of:
+/// - `in-mempool` - the transaction is waiting in the mempool to be included in a block,
+/// - `pending` - the transaction is included in a block, but has fewer than `--confirmations` blocks on top,
+/// - `confirmed` - the transaction is included in a block with at least `--confirmations` blocks on top,
+/// - `in-ledger` - the transaction is on the chain, but the number of blocks on top of it is not known,
+/// - `unknown` - the transaction is neither in the mempool nor on the chain.
+///
+/// With Ogmios backend, the depth of a transaction is known only when `--since-block` is given,
+/// and the transaction was included after that block. Otherwise a transaction is never reported
+/// as `pending` nor `confirmed`, and a transaction with spent first output is reported as `unknown`.
+pub struct TxStatusCmd {
+	#[clap(flatten)]
+	common_arguments: crate::CommonArguments,
+	#[arg(long)]
+	/// Hash of the transaction
+	tx_hash: McTxHash,
+	#[arg(long, value_parser = parse_block_point)]
+	/// Block preceding the transaction, in `SLOT:BLOCK_HASH` format, for example the tip of the chain
+	/// at the moment the transaction was submitted. With Ogmios backend, the chain is followed from
+	/// this block to find the block including the transaction, which requires `ws` or `wss` Ogmios URL.
+	/// All blocks after it are read, so it should be recent. Ignored by Blockfrost backend.
+	since_block: Option<Point>,
+}
+
+impl TxStatusCmd {
+	/// Queries the status of the transaction
+	pub async fn execute(self) -> crate::SubCmdResult {
+		let client = self.common_arguments.get_ogmios_client().await?;
+		let status = match self.since_block {
+			Some(since) => client.transaction_status_since(self.tx_hash, since).await?,
+			None => client.transaction_status(self.tx_hash).await?,
+		};
+		Ok(status_json(self.tx_hash, status, self.common_arguments.confirmations))
+	}
+}
+
+fn parse_block_point(s: &str) -> Result<Point, String> {
+	let (slot, id) = s.split_once(':').ok_or("expected SLOT:BLOCK_HASH")?;
+	let slot = slot.parse().map_err(|e| format!("invalid slot '{slot}': {e}"))?;
+	let id = (hex::decode(id).ok())
+		.and_then(|id| id.try_into().ok())
+		.ok_or_else(|| format!("invalid block hash '{id}', expected 32 bytes in hex"))?;
+	Ok(Point::Block { slot, id })
+}
+
+fn status_json(
+	tx_hash: McTxHash,
+	status: TransactionStatus,
+	confirmations: u64,
+) -> serde_json::Value {
+	let tx_hash = hex::encode(tx_hash.0);
+	match status {
+		TransactionStatus::InMempool => json!({ "tx_hash": tx_hash, "status": "in-mempool" }),
+		TransactionStatus::Confirmed { depth } if depth < confirmations => {
+			json!({ "tx_hash": tx_hash, "status": "pending", "depth": depth })
+		},
+		TransactionStatus::Confirmed { depth } => {
+			json!({ "tx_hash": tx_hash, "status": "confirmed", "depth": depth })
+		},
+		TransactionStatus::InLedger => json!({ "tx_hash": tx_hash, "status": "in-ledger" }),
+		TransactionStatus::Unknown => json!({ "tx_hash": tx_hash, "status": "unknown" }),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use hex_literal::hex;
+
+	const TX_HASH: McTxHash =
+		McTxHash(hex!("106b0d7d1544c97941777041699412fb7c8b94855210987327199620c0599580"));
+	const TX_HASH_HEX: &str = "106b0d7d1544c97941777041699412fb7c8b94855210987327199620c0599580";
+
+	#[test]
+	fn status_json_in_mempool() {
+		assert_eq!(
+			status_json(TX_HASH, TransactionStatus::InMempool, 2),
+			json!({ "tx_hash": TX_HASH_HEX, "status": "in-mempool" })
+		);
+	}
+
+	#[test]
+	fn status_json_pending_below_confirmations() {
+		assert_eq!(
+			status_json(TX_HASH, TransactionStatus::Confirmed { depth: 1 }, 2),
+			json!({ "tx_hash": TX_HASH_HEX, "status": "pending", "depth": 1 })
+		);
+	}
+
+	#[test]
+	fn status_json_confirmed_at_confirmations() {
+		assert_eq!(
+			status_json(TX_HASH, TransactionStatus::Confirmed { depth: 2 }, 2),
+			json!({ "tx_hash": TX_HASH_HEX, "status": "confirmed", "depth": 2 })
+		);
+		assert_eq!(
+			status_json(TX_HASH, TransactionStatus::Confirmed { depth: 1 }, 0),
+			json!({ "tx_hash": TX_HASH_HEX, "status": "confirmed", "depth": 1 })
+		);
+	}
+
+	#[test]
+	fn status_json_in_ledger_and_unknown() {
+		assert_eq!(
+			status_json(TX_HASH, TransactionStatus::InLedger, 2),
+			json!({ "tx_hash": TX_HASH_HEX, "status": "in-ledger" })
+		);
+		assert_eq!(
+			status_json(TX_HASH, TransactionStatus::Unknown, 2),
+			json!({ "tx_hash": TX_HASH_HEX, "status": "unknown" })
+		);
+	}
+
+	#[test]
+	fn parse_block_point_test() {
+		assert_eq!(
+			parse_block_point(
+				"73209500:a3d1bd7f2bfb8c5c4c6e1f8e6c1d5a3f72e0a6c7e9b4f5d8c2e1a0b9c8d7e6f5"
+			),
+			Ok(Point::Block {
+				slot: 73209500,
+				id: hex!("a3d1bd7f2bfb8c5c4c6e1f8e6c1d5a3f72e0a6c7e9b4f5d8c2e1a0b9c8d7e6f5")
+			})
+		);
+		assert!(parse_block_point("73209500").is_err());
+		assert!(parse_block_point("abc:a3d1").is_err());
+		assert!(parse_block_point("73209500:a3d1").is_err());
+	}
+}
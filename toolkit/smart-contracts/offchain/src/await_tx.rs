@@ -1,14 +1,22 @@
 use anyhow::anyhow;
-use ogmios_client::query_ledger_state::QueryUtxoByUtxoId;
+use ogmios_client::{
+	chain_sync::{ChainSync, TransactionInclusion},
+	jsonrpsee::{OgmiosClients, client_for_url},
+	query_ledger_state::QueryUtxoByUtxoId,
+	query_network::QueryNetwork,
+};
 use sidechain_domain::{McTxHash, UtxoId};
 use std::time::Duration;
-use tokio_retry::{Retry, strategy::FixedInterval};
+use tokio_retry::{
+	Retry,
+	strategy::{FixedInterval, jitter},
+};
 
 /// Trait for different strategies of waiting for a Cardano transaction to complete.
 pub trait AwaitTx {
 	#[allow(async_fn_in_trait)]
 	/// This is used for waiting until the output of a submitted transaction can be observed.
-	async fn await_tx_output<C: QueryUtxoByUtxoId>(
+	async fn await_tx_output<C: QueryUtxoByUtxoId + QueryNetwork>(
 		&self,
 		client: &C,
 		tx_hash: McTxHash,
@@ -34,39 +42,333 @@ impl FixedDelayRetries {
 }
 
 impl AwaitTx for FixedDelayRetries {
-	async fn await_tx_output<C: QueryUtxoByUtxoId>(
+	async fn await_tx_output<C: QueryUtxoByUtxoId + QueryNetwork>(
 		&self,
 		client: &C,
 		tx_hash: McTxHash,
 	) -> anyhow::Result<()> {
 		let strategy = FixedInterval::new(self.delay).take(self.retries);
-		let utxo_id = UtxoId::new(tx_hash.0, 0);
-		let _ = Retry::spawn(strategy, || async {
-			log::info!("Probing for transaction output '{}'", utxo_id);
-			let utxo = client.query_utxo_by_id(utxo_id).await.map_err(|_| ())?;
-			utxo.ok_or(())
+		probe_tx_output(strategy, client, tx_hash).await
+	}
+}
+
+/// Transaction awaiting strategy that doubles the delay after every retry, up to `max_delay`.
+/// Each delay is randomized to be between half and the whole of the computed delay.
+pub struct ExponentialBackoffRetries {
+	initial_delay: Duration,
+	max_delay: Duration,
+	retries: usize,
+}
+
+impl ExponentialBackoffRetries {
+	/// Constructs [ExponentialBackoffRetries] with `initial_delay`, `max_delay` and `retries` number of maximum retries.
+	pub fn new(initial_delay: Duration, max_delay: Duration, retries: usize) -> Self {
+		Self { initial_delay, max_delay, retries }
+	}
+
+	fn delays(&self) -> impl Iterator<Item = Duration> {
+		let (initial_delay, max_delay) = (self.initial_delay, self.max_delay);
+		(0..self.retries)
+			.map(move |n| initial_delay.saturating_mul(1 << n.min(31)).min(max_delay))
+			.map(|delay| delay / 2 + jitter(delay / 2))
+	}
+}
+
+impl AwaitTx for ExponentialBackoffRetries {
+	async fn await_tx_output<C: QueryUtxoByUtxoId + QueryNetwork>(
+		&self,
+		client: &C,
+		tx_hash: McTxHash,
+	) -> anyhow::Result<()> {
+		probe_tx_output(self.delays(), client, tx_hash).await
+	}
+}
+
+async fn probe_tx_output<C: QueryUtxoByUtxoId>(
+	strategy: impl Iterator<Item = Duration>,
+	client: &C,
+	tx_hash: McTxHash,
+) -> anyhow::Result<()> {
+	let utxo_id = UtxoId::new(tx_hash.0, 0);
+	let _ = Retry::spawn(strategy, || async {
+		log::info!("Probing for transaction output '{}'", utxo_id);
+		let utxo = client.query_utxo_by_id(utxo_id).await.map_err(|_| ())?;
+		utxo.ok_or(())
+	})
+	.await
+	.map_err(|_| {
+		anyhow!(
+			"Retries for confirmation of transaction '{}' exceeded the limit",
+			hex::encode(utxo_id.tx_hash.0)
+		)
+	})?;
+	log::info!("Transaction output '{}'", hex::encode(utxo_id.tx_hash.0));
+	Ok(())
+}
+
+/// Retry strategy selected at runtime.
+pub enum RetryStrategy {
+	/// See [FixedDelayRetries]
+	FixedDelay(FixedDelayRetries),
+	/// See [ExponentialBackoffRetries]
+	ExponentialBackoff(ExponentialBackoffRetries),
+}
+
+impl AwaitTx for RetryStrategy {
+	async fn await_tx_output<C: QueryUtxoByUtxoId + QueryNetwork>(
+		&self,
+		client: &C,
+		tx_hash: McTxHash,
+	) -> anyhow::Result<()> {
+		match self {
+			Self::FixedDelay(strategy) => strategy.await_tx_output(client, tx_hash).await,
+			Self::ExponentialBackoff(strategy) => strategy.await_tx_output(client, tx_hash).await,
+		}
+	}
+}
+
+/// Opens connections used by [ConfirmationDepth] to follow the chain with [ChainSync].
+pub trait ChainSyncConnector {
+	/// Client of the chain synchronization protocol
+	type Client: ChainSync;
+
+	#[allow(async_fn_in_trait)]
+	/// Opens a new connection. Chain synchronization is stateful, so every awaited transaction uses its own.
+	async fn connect(&self) -> anyhow::Result<Self::Client>;
+}
+
+/// [ChainSyncConnector] opening WebSockets connections to Ogmios
+pub struct OgmiosChainSync {
+	url: String,
+	timeout: Duration,
+}
+
+impl OgmiosChainSync {
+	/// Constructs [OgmiosChainSync] for Ogmios at `url`, which has to use `ws` or `wss` scheme.
+	pub fn new(url: &str, timeout: Duration) -> Self {
+		Self { url: url.to_string(), timeout }
+	}
+}
+
+impl ChainSyncConnector for OgmiosChainSync {
+	type Client = OgmiosClients;
+
+	async fn connect(&self) -> anyhow::Result<Self::Client> {
+		client_for_url(&self.url, self.timeout)
+			.await
+			.map_err(|e| anyhow!("Failed to connect to Ogmios at {} with: {}", self.url, e))
+	}
+}
+
+/// Transaction awaiting strategy that after the transaction output is observed using `await_output`,
+/// waits until `confirmations` blocks are built on top of the block including the transaction.
+///
+/// When a [ChainSyncConnector] is configured, the chain is followed from the tip observed before
+/// awaiting the output, and the block including the transaction is found among the followed blocks.
+/// Otherwise the block is queried with [QueryNetwork::get_transaction_block_height].
+/// In both cases, when the block including the transaction is rolled back, the transaction
+/// is awaited until it is included in another block, and an error is returned if it is not.
+///
+/// If the block including the transaction is not known, because the client does not index transactions,
+/// or because the transaction was included before the chain was followed, the tip at the moment
+/// the output is observed is used instead, and rollbacks are not detected. The transaction was included
+/// in that block or in one of its ancestors, so at least `confirmations` blocks are awaited in either case.
+pub struct ConfirmationDepth<A, S = OgmiosChainSync> {
+	await_output: A,
+	confirmations: u64,
+	poll_interval: Duration,
+	max_polls: usize,
+	chain_sync: Option<S>,
+}
+
+impl<A: AwaitTx> ConfirmationDepth<A> {
+	/// Constructs [ConfirmationDepth] that checks the tip every `poll_interval`, at most `max_polls` times.
+	pub fn new(
+		await_output: A,
+		confirmations: u64,
+		poll_interval: Duration,
+		max_polls: usize,
+	) -> Self {
+		Self { await_output, confirmations, poll_interval, max_polls, chain_sync: None }
+	}
+}
+
+impl<A: AwaitTx, S: ChainSyncConnector> ConfirmationDepth<A, S> {
+	/// Makes [ConfirmationDepth] find the block including the transaction by following the chain.
+	/// Blocks are awaited for at most `max_polls` times `poll_interval`.
+	pub fn with_chain_sync<S2: ChainSyncConnector>(
+		self,
+		chain_sync: S2,
+	) -> ConfirmationDepth<A, S2> {
+		let Self { await_output, confirmations, poll_interval, max_polls, .. } = self;
+		ConfirmationDepth {
+			await_output,
+			confirmations,
+			poll_interval,
+			max_polls,
+			chain_sync: Some(chain_sync),
+		}
+	}
+
+	async fn await_depth_following_chain<C: QueryUtxoByUtxoId + QueryNetwork>(
+		&self,
+		chain_sync: &S::Client,
+		mut inclusion: TransactionInclusion,
+		client: &C,
+		tx_hash: McTxHash,
+	) -> anyhow::Result<()> {
+		inclusion.sync_to_tip(chain_sync).await?;
+		if inclusion.inclusion_height().is_none() {
+			log::warn!(
+				"Transaction '{}' was included before the chain was followed, its rollbacks will not be detected",
+				hex::encode(tx_hash.0)
+			);
+			return self.await_depth_from_height(client, tx_hash, inclusion.tip().height()).await;
+		}
+		let max_polls = u32::try_from(self.max_polls).unwrap_or(u32::MAX);
+		let deadline = tokio::time::Instant::now() + self.poll_interval.saturating_mul(max_polls);
+		loop {
+			match inclusion.depth() {
+				Some(depth) => {
+					log::info!(
+						"Transaction '{}' has {} of {} confirmations",
+						hex::encode(tx_hash.0),
+						depth - 1,
+						self.confirmations
+					);
+					if depth > self.confirmations {
+						return Ok(());
+					}
+				},
+				None => log::warn!(
+					"Block including transaction '{}' has been rolled back",
+					hex::encode(tx_hash.0)
+				),
+			}
+			let next = tokio::time::timeout_at(deadline, inclusion.next(chain_sync)).await;
+			if next.is_err() {
+				return Err(match inclusion.depth() {
+					Some(_) => not_confirmed_error(tx_hash, self.confirmations),
+					None => rolled_back_error(tx_hash),
+				});
+			}
+			next??;
+		}
+	}
+
+	async fn await_depth_polling<C: QueryUtxoByUtxoId + QueryNetwork>(
+		&self,
+		client: &C,
+		tx_hash: McTxHash,
+	) -> anyhow::Result<()> {
+		if client.get_transaction_block_height(tx_hash).await?.is_none() {
+			log::warn!(
+				"Block including transaction '{}' is not known, rollbacks will not be detected",
+				hex::encode(tx_hash.0)
+			);
+			let tip_height = client.get_block_height().await?;
+			return self.await_depth_from_height(client, tx_hash, tip_height).await;
+		}
+		let mut is_rolled_back = false;
+		for _ in 0..self.max_polls {
+			tokio::time::sleep(self.poll_interval).await;
+			match client.get_transaction_block_height(tx_hash).await? {
+				Some(included_at) => {
+					is_rolled_back = false;
+					if self.has_confirmations(client, tx_hash, included_at).await? {
+						return Ok(());
+					}
+				},
+				None if !is_rolled_back => {
+					is_rolled_back = true;
+					log::warn!(
+						"Block including transaction '{}' has been rolled back",
+						hex::encode(tx_hash.0)
+					);
+				},
+				None => (),
+			}
+		}
+		Err(if is_rolled_back {
+			rolled_back_error(tx_hash)
+		} else {
+			not_confirmed_error(tx_hash, self.confirmations)
 		})
-		.await
-		.map_err(|_| {
-			anyhow!(
-				"Retries for confirmation of transaction '{}' exceeded the limit",
-				hex::encode(utxo_id.tx_hash.0)
-			)
-		})?;
-		log::info!("Transaction output '{}'", hex::encode(utxo_id.tx_hash.0));
-		Ok(())
+	}
+
+	async fn await_depth_from_height<C: QueryNetwork>(
+		&self,
+		client: &C,
+		tx_hash: McTxHash,
+		included_at: u64,
+	) -> anyhow::Result<()> {
+		for _ in 0..self.max_polls {
+			tokio::time::sleep(self.poll_interval).await;
+			if self.has_confirmations(client, tx_hash, included_at).await? {
+				return Ok(());
+			}
+		}
+		Err(not_confirmed_error(tx_hash, self.confirmations))
+	}
+
+	async fn has_confirmations<C: QueryNetwork>(
+		&self,
+		client: &C,
+		tx_hash: McTxHash,
+		included_at: u64,
+	) -> anyhow::Result<bool> {
+		let height = client.get_block_height().await?;
+		log::info!(
+			"Transaction '{}' has {} of {} confirmations",
+			hex::encode(tx_hash.0),
+			height.saturating_sub(included_at),
+			self.confirmations
+		);
+		Ok(height >= included_at + self.confirmations)
+	}
+}
+
+impl<A: AwaitTx, S: ChainSyncConnector> AwaitTx for ConfirmationDepth<A, S> {
+	async fn await_tx_output<C: QueryUtxoByUtxoId + QueryNetwork>(
+		&self,
+		client: &C,
+		tx_hash: McTxHash,
+	) -> anyhow::Result<()> {
+		if self.confirmations == 0 {
+			return self.await_output.await_tx_output(client, tx_hash).await;
+		}
+		let Some(connector) = &self.chain_sync else {
+			self.await_output.await_tx_output(client, tx_hash).await?;
+			return self.await_depth_polling(client, tx_hash).await;
+		};
+		let chain_sync = connector.connect().await?;
+		let inclusion = TransactionInclusion::start_at_tip(&chain_sync, tx_hash).await?;
+		self.await_output.await_tx_output(client, tx_hash).await?;
+		self.await_depth_following_chain(&chain_sync, inclusion, client, tx_hash).await
 	}
 }
 
+fn rolled_back_error(tx_hash: McTxHash) -> anyhow::Error {
+	anyhow!("Transaction '{}' has been rolled back", hex::encode(tx_hash.0))
+}
+
+fn not_confirmed_error(tx_hash: McTxHash, confirmations: u64) -> anyhow::Error {
+	anyhow!(
+		"Transaction '{}' did not reach {} confirmations within the polling limit",
+		hex::encode(tx_hash.0),
+		confirmations
+	)
+}
+
 #[cfg(test)]
 pub(crate) mod mock {
 	use super::AwaitTx;
-	use ogmios_client::query_ledger_state::QueryUtxoByUtxoId;
+	use ogmios_client::{query_ledger_state::QueryUtxoByUtxoId, query_network::QueryNetwork};
 
 	pub(crate) struct ImmediateSuccess;
 
 	impl AwaitTx for ImmediateSuccess {
-		async fn await_tx_output<Q: QueryUtxoByUtxoId>(
+		async fn await_tx_output<Q: QueryUtxoByUtxoId + QueryNetwork>(
 			&self,
 			_query: &Q,
 			_utxo_id: sidechain_domain::McTxHash,
@@ -78,19 +380,29 @@ pub(crate) mod mock {
 
 #[cfg(test)]
 mod tests {
-	use super::{AwaitTx, FixedDelayRetries};
+	use super::{
+		AwaitTx, ChainSyncConnector, ConfirmationDepth, ExponentialBackoffRetries,
+		FixedDelayRetries,
+	};
 	use ogmios_client::{
 		OgmiosClientError,
+		chain_sync::{
+			ChainSync, FindIntersectionResponse, NextBlockResponse, OgmiosBlock, OgmiosTransaction,
+			Point, Spends, Tip,
+		},
 		query_ledger_state::QueryUtxoByUtxoId,
+		query_network::{QueryNetwork, ShelleyGenesisConfigurationResponse},
 		types::{OgmiosTx, OgmiosUtxo},
 	};
 	use sidechain_domain::McTxHash;
-	use std::{cell::RefCell, time::Duration};
+	use std::{cell::RefCell, collections::VecDeque, time::Duration};
 
 	#[tokio::test]
 	async fn immediate_success() {
-		let mock =
-			MockQueryUtxoByUtxoId { responses: RefCell::new(vec![Ok(Some(awaited_utxo()))]) };
+		let mock = MockQueryUtxoByUtxoId {
+			responses: RefCell::new(vec![Ok(Some(awaited_utxo()))]),
+			..Default::default()
+		};
 		FixedDelayRetries::new(Duration::from_millis(1), 3)
 			.await_tx_output(&mock, awaited_tx_hash())
 			.await
@@ -101,6 +413,7 @@ mod tests {
 	async fn success_in_2nd_attempt() {
 		let mock = MockQueryUtxoByUtxoId {
 			responses: RefCell::new(vec![Ok(None), Ok(Some(awaited_utxo()))]),
+			..Default::default()
 		};
 		FixedDelayRetries::new(Duration::from_millis(1), 3)
 			.await_tx_output(&mock, awaited_tx_hash())
@@ -110,8 +423,10 @@ mod tests {
 
 	#[tokio::test]
 	async fn all_attempts_result_not_found() {
-		let mock =
-			MockQueryUtxoByUtxoId { responses: RefCell::new(vec![Ok(None), Ok(None), Ok(None)]) };
+		let mock = MockQueryUtxoByUtxoId {
+			responses: RefCell::new(vec![Ok(None), Ok(None), Ok(None)]),
+			..Default::default()
+		};
 		let result = FixedDelayRetries::new(Duration::from_millis(1), 2)
 			.await_tx_output(&mock, awaited_tx_hash())
 			.await;
@@ -126,6 +441,7 @@ mod tests {
 				Err(OgmiosClientError::RequestError("test error2".to_string())),
 				Err(OgmiosClientError::RequestError("test error3".to_string())),
 			]),
+			..Default::default()
 		};
 		let result = FixedDelayRetries::new(Duration::from_millis(1), 2)
 			.await_tx_output(&mock, awaited_tx_hash())
@@ -133,8 +449,182 @@ mod tests {
 		assert!(result.is_err())
 	}
 
+	#[tokio::test]
+	async fn exponential_backoff_success_in_2nd_attempt() {
+		let mock = MockQueryUtxoByUtxoId {
+			responses: RefCell::new(vec![Ok(Some(awaited_utxo())), Ok(None)]),
+			..Default::default()
+		};
+		ExponentialBackoffRetries::new(Duration::from_millis(1), Duration::from_millis(4), 3)
+			.await_tx_output(&mock, awaited_tx_hash())
+			.await
+			.unwrap();
+	}
+
+	#[test]
+	fn exponential_backoff_delays_grow_up_to_max_delay_with_jitter() {
+		let delays: Vec<Duration> =
+			ExponentialBackoffRetries::new(Duration::from_secs(1), Duration::from_secs(5), 5)
+				.delays()
+				.collect();
+		let expected_max = [1, 2, 4, 5, 5].map(Duration::from_secs);
+		assert_eq!(delays.len(), expected_max.len());
+		for (delay, max) in delays.into_iter().zip(expected_max) {
+			assert!(delay >= max / 2 && delay <= max, "{delay:?} not in [{:?}, {max:?}]", max / 2);
+		}
+	}
+
+	#[tokio::test]
+	async fn confirmation_depth_success() {
+		let mock = MockQueryUtxoByUtxoId {
+			responses: RefCell::new(vec![Ok(Some(awaited_utxo()))]),
+			block_heights: RefCell::new(vec![102, 101, 100]),
+			..Default::default()
+		};
+		confirmation_depth(3, 2, 5)
+			.await_tx_output(&mock, awaited_tx_hash())
+			.await
+			.unwrap();
+		assert!(mock.block_heights.borrow().is_empty());
+	}
+
+	#[tokio::test]
+	async fn confirmation_depth_counts_from_the_block_including_transaction() {
+		let mock = MockQueryUtxoByUtxoId {
+			responses: RefCell::new(vec![Ok(Some(awaited_utxo()))]),
+			block_heights: RefCell::new(vec![100]),
+			tx_block_heights: RefCell::new(vec![Some(98), Some(98)]),
+		};
+		confirmation_depth(3, 2, 1)
+			.await_tx_output(&mock, awaited_tx_hash())
+			.await
+			.unwrap();
+		assert!(mock.block_heights.borrow().is_empty());
+	}
+
+	#[tokio::test]
+	async fn confirmation_depth_does_not_consider_spent_output_rolled_back() {
+		let mock = MockQueryUtxoByUtxoId {
+			responses: RefCell::new(vec![Ok(None), Ok(None), Ok(Some(awaited_utxo()))]),
+			block_heights: RefCell::new(vec![100, 99]),
+			tx_block_heights: RefCell::new(vec![Some(98); 3]),
+		};
+		confirmation_depth(3, 2, 5)
+			.await_tx_output(&mock, awaited_tx_hash())
+			.await
+			.unwrap();
+	}
+
+	#[tokio::test]
+	async fn confirmation_depth_awaits_transaction_included_again_after_rollback() {
+		let mock = MockQueryUtxoByUtxoId {
+			responses: RefCell::new(vec![Ok(Some(awaited_utxo()))]),
+			block_heights: RefCell::new(vec![101]),
+			tx_block_heights: RefCell::new(vec![Some(99), None, Some(98)]),
+		};
+		confirmation_depth(3, 2, 5)
+			.await_tx_output(&mock, awaited_tx_hash())
+			.await
+			.unwrap();
+	}
+
+	#[tokio::test]
+	async fn confirmation_depth_reports_rollback() {
+		let mock = MockQueryUtxoByUtxoId {
+			responses: RefCell::new(vec![Ok(Some(awaited_utxo()))]),
+			tx_block_heights: RefCell::new(vec![None, None, Some(98)]),
+			..Default::default()
+		};
+		let result = confirmation_depth(2, 2, 2).await_tx_output(&mock, awaited_tx_hash()).await;
+		assert!(result.unwrap_err().to_string().contains("rolled back"));
+	}
+
+	#[tokio::test]
+	async fn confirmation_depth_polls_limit_exceeded() {
+		let mock = MockQueryUtxoByUtxoId {
+			responses: RefCell::new(vec![Ok(Some(awaited_utxo()))]),
+			block_heights: RefCell::new(vec![100, 100, 100]),
+			..Default::default()
+		};
+		let result = confirmation_depth(2, 1, 2).await_tx_output(&mock, awaited_tx_hash()).await;
+		assert!(result.is_err());
+	}
+
+	#[tokio::test]
+	async fn confirmation_depth_finds_block_including_transaction_following_chain() {
+		let mock = MockQueryUtxoByUtxoId {
+			responses: RefCell::new(vec![Ok(Some(awaited_utxo()))]),
+			..Default::default()
+		};
+		let chain_sync = MockChainSyncConnector::new(vec![
+			backward(100, 101),
+			forward(101, true, 101),
+			forward(102, false, 102),
+			forward(103, false, 103),
+		]);
+		confirmation_depth(3, 2, 5)
+			.with_chain_sync(chain_sync)
+			.await_tx_output(&mock, awaited_tx_hash())
+			.await
+			.unwrap();
+	}
+
+	#[tokio::test]
+	async fn confirmation_depth_following_chain_reports_rollback() {
+		let mock = MockQueryUtxoByUtxoId {
+			responses: RefCell::new(vec![Ok(Some(awaited_utxo()))]),
+			..Default::default()
+		};
+		let chain_sync = MockChainSyncConnector::new(vec![
+			backward(100, 101),
+			forward(101, true, 101),
+			backward(100, 100),
+			forward(101, false, 101),
+		]);
+		let result = confirmation_depth(3, 2, 5)
+			.with_chain_sync(chain_sync)
+			.await_tx_output(&mock, awaited_tx_hash())
+			.await;
+		assert!(result.unwrap_err().to_string().contains("rolled back"));
+	}
+
+	fn confirmation_depth(
+		retries: usize,
+		confirmations: u64,
+		max_polls: usize,
+	) -> ConfirmationDepth<FixedDelayRetries> {
+		ConfirmationDepth::new(
+			FixedDelayRetries::new(Duration::from_millis(1), retries),
+			confirmations,
+			Duration::from_millis(1),
+			max_polls,
+		)
+	}
+
+	#[derive(Default)]
 	struct MockQueryUtxoByUtxoId {
 		responses: RefCell<Vec<Result<Option<OgmiosUtxo>, OgmiosClientError>>>,
+		block_heights: RefCell<Vec<u64>>,
+		tx_block_heights: RefCell<Vec<Option<u64>>>,
+	}
+
+	impl QueryNetwork for MockQueryUtxoByUtxoId {
+		async fn shelley_genesis_configuration(
+			&self,
+		) -> Result<ShelleyGenesisConfigurationResponse, OgmiosClientError> {
+			unimplemented!()
+		}
+
+		async fn get_block_height(&self) -> Result<u64, OgmiosClientError> {
+			Ok(self.block_heights.borrow_mut().pop().unwrap())
+		}
+
+		async fn get_transaction_block_height(
+			&self,
+			_tx_hash: McTxHash,
+		) -> Result<Option<u64>, OgmiosClientError> {
+			Ok(self.tx_block_heights.borrow_mut().pop().flatten())
+		}
 	}
 
 	impl QueryUtxoByUtxoId for MockQueryUtxoByUtxoId {
@@ -150,6 +640,80 @@ mod tests {
 		}
 	}
 
+	struct MockChainSyncConnector {
+		client: RefCell<Option<MockChainSync>>,
+	}
+
+	impl MockChainSyncConnector {
+		fn new(next_blocks: Vec<NextBlockResponse>) -> Self {
+			let client = MockChainSync { next_blocks: RefCell::new(next_blocks.into()) };
+			Self { client: RefCell::new(Some(client)) }
+		}
+	}
+
+	impl ChainSyncConnector for MockChainSyncConnector {
+		type Client = MockChainSync;
+
+		async fn connect(&self) -> anyhow::Result<MockChainSync> {
+			Ok(self.client.borrow_mut().take().expect("connected once"))
+		}
+	}
+
+	struct MockChainSync {
+		next_blocks: RefCell<VecDeque<NextBlockResponse>>,
+	}
+
+	impl ChainSync for MockChainSync {
+		async fn find_intersection(
+			&self,
+			points: &[Point],
+		) -> Result<FindIntersectionResponse, OgmiosClientError> {
+			Ok(FindIntersectionResponse { intersection: points[0], tip: tip(100) })
+		}
+
+		/// Waits forever after all responses were returned, like Ogmios at the tip
+		async fn next_block(&self) -> Result<NextBlockResponse, OgmiosClientError> {
+			let next_block = self.next_blocks.borrow_mut().pop_front();
+			match next_block {
+				Some(next_block) => Ok(next_block),
+				None => std::future::pending().await,
+			}
+		}
+	}
+
+	fn tip(height: u64) -> Tip {
+		Tip::Block { slot: height * 10, id: [height as u8; 32], height }
+	}
+
+	fn forward(height: u64, includes_tx: bool, tip_height: u64) -> NextBlockResponse {
+		let transactions = if includes_tx {
+			vec![OgmiosTransaction {
+				id: awaited_tx_hash().0,
+				spends: Spends::Inputs,
+				inputs: vec![],
+				collaterals: vec![],
+				outputs: vec![],
+				references: vec![],
+			}]
+		} else {
+			vec![]
+		};
+		let block = OgmiosBlock {
+			block_type: "praos".to_string(),
+			era: "conway".to_string(),
+			id: [height as u8; 32],
+			ancestor: Some([height as u8 - 1; 32]),
+			height,
+			slot: Some(height * 10),
+			transactions,
+		};
+		NextBlockResponse::Forward { block, tip: tip(tip_height) }
+	}
+
+	fn backward(height: u64, tip_height: u64) -> NextBlockResponse {
+		NextBlockResponse::Backward { point: tip(height).point(), tip: tip(tip_height) }
+	}
+
 	fn awaited_tx_hash() -> McTxHash {
 		McTxHash([7u8; 32])
 	}
@@ -0,0 +1,510 @@
+//! Submission of bulk operations in chained transactions.
+//!
+//! Operations are packed greedily into transactions. Every transaction spends change outputs
+//! of the previous one, so it can be submitted without waiting for the previous one to be
+//! confirmed. If a transaction with the given number of operations cannot be built, because it
+//! exceeds the transaction size or execution units limit, the number of operations is halved,
+//! rounding up.
+//! After each submitted transaction the number of operations is doubled again, but it is kept
+//! below the smallest number of operations for which building a transaction failed, so the
+//! same limit is not hit repeatedly.
+//! Operation that cannot be submitted even alone is reported as failed and skipped. Because the
+//! failure was caused by this operation, the number of operations is reset to the maximum.
+use crate::{
+	await_tx::AwaitTx,
+	csl::{Costs, OgmiosUtxoExt, TransactionContext},
+	governance::GovernanceData,
+};
+use anyhow::anyhow;
+use cardano_serialization_lib::{Transaction, TransactionOutput};
+use ogmios_client::{
+	query_ledger_state::{ProtocolParametersResponse, QueryUtxoByUtxoId},
+	query_network::QueryNetwork,
+	transactions::Transactions,
+	types::{Asset, OgmiosTx, OgmiosUtxo, OgmiosValue},
+};
+use serde::Serialize;
+use sidechain_domain::{McTxHash, crypto::blake2b};
+use std::collections::HashMap;
+
+/// Outcome of a single operation of a bulk action.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct BatchOperationResult {
+	/// Index of the operation in the input.
+	pub operation: usize,
+	/// Hash of the transaction that included the operation.
+	/// Not present when the operation failed or when it did not require a transaction.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub tx_hash: Option<McTxHash>,
+	/// Reason why the operation was not included in any transaction.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
+}
+
+impl BatchOperationResult {
+	pub(crate) fn skipped(operation: usize) -> Self {
+		Self { operation, tx_hash: None, error: None }
+	}
+
+	pub(crate) fn submitted(operation: usize, tx_hash: McTxHash) -> Self {
+		Self { operation, tx_hash: Some(tx_hash), error: None }
+	}
+
+	fn failed(operation: usize, error: String) -> Self {
+		Self { operation, tx_hash: None, error: Some(error) }
+	}
+}
+
+/// Submits governance approved `operations` in chained transactions, each containing at most
+/// `max_batch_size` of them. See [submit_chained_in_batches].
+///
+/// Transactions are submitted and signed using the payment key, so the governance has to be
+/// a single key governance of this key.
+pub(crate) async fn submit_in_batches<Op, F, T, A>(
+	governance_data: &GovernanceData,
+	ctx: TransactionContext,
+	operations: &[Op],
+	max_batch_size: usize,
+	make_tx: F,
+	tx_name: &str,
+	client: &T,
+	await_tx: &A,
+) -> anyhow::Result<Vec<BatchOperationResult>>
+where
+	F: Fn(&[Op], Costs, &TransactionContext) -> anyhow::Result<Transaction>,
+	T: Transactions + QueryNetwork + QueryUtxoByUtxoId,
+	A: AwaitTx,
+{
+	if !governance_data.policy.is_single_key_policy_for(&ctx.payment_key_hash()) {
+		return Err(anyhow!(
+			"Batch submission of '{tx_name}' transactions requires single key governance of the payment key"
+		));
+	}
+	submit_chained_in_batches(ctx, operations, max_batch_size, make_tx, tx_name, client, await_tx)
+		.await
+}
+
+/// Submits `operations` in chained transactions, each containing at most `max_batch_size` of them.
+/// `make_tx` is used to build a transaction for a slice of operations.
+/// Returns result of every operation after all submitted transactions are observed on chain.
+///
+/// Transactions are submitted and signed using the payment key only.
+pub(crate) async fn submit_chained_in_batches<Op, F, T, A>(
+	ctx: TransactionContext,
+	operations: &[Op],
+	max_batch_size: usize,
+	make_tx: F,
+	tx_name: &str,
+	client: &T,
+	await_tx: &A,
+) -> anyhow::Result<Vec<BatchOperationResult>>
+where
+	F: Fn(&[Op], Costs, &TransactionContext) -> anyhow::Result<Transaction>,
+	T: Transactions + QueryNetwork + QueryUtxoByUtxoId,
+	A: AwaitTx,
+{
+	let mut ctx = ctx;
+	let mut results = Vec::with_capacity(operations.len());
+	// Transactions submitted but not awaited yet. Their outputs are not known to the ledger.
+	let mut unconfirmed_txs: Vec<McTxHash> = vec![];
+	let mut unconfirmed_utxos: Vec<OgmiosUtxo> = vec![];
+	let max_batch_size = max_batch_size.max(1);
+	let mut batch_size = max_batch_size;
+	// Smallest number of operations for which building a transaction failed
+	let mut failed_batch_size = max_batch_size.saturating_add(1);
+	let mut start = 0;
+	while start < operations.len() {
+		let end = operations.len().min(start + batch_size);
+		let batch = &operations[start..end];
+		let tx = match Costs::calculate_costs_with_additional_utxos(
+			|costs| make_tx(batch, costs, &ctx),
+			&unconfirmed_utxos,
+			client,
+		)
+		.await
+		.and_then(|tx| check_limits(tx, &ctx.protocol_parameters))
+		{
+			Ok(tx) => tx,
+			Err(e) if !unconfirmed_txs.is_empty() => {
+				// Evaluation of chained transactions is not supported by every backend,
+				// so the failure could be caused by unconfirmed inputs.
+				log::info!(
+					"Building '{tx_name}' transaction failed: {e}. Awaiting previous transactions before retrying."
+				);
+				await_all(&mut unconfirmed_txs, client, await_tx).await?;
+				unconfirmed_utxos.clear();
+				continue;
+			},
+			Err(e) if batch.len() > 1 => {
+				failed_batch_size = batch.len();
+				batch_size = batch.len().div_ceil(2);
+				log::info!(
+					"Building '{tx_name}' transaction with {} operations failed: {e}. Retrying with {batch_size}.",
+					batch.len()
+				);
+				continue;
+			},
+			Err(e) => {
+				log::warn!("Operation {start} could not be included in a transaction: {e}");
+				results.push(BatchOperationResult::failed(start, e.to_string()));
+				batch_size = max_batch_size;
+				failed_batch_size = max_batch_size.saturating_add(1);
+				start = end;
+				continue;
+			},
+		};
+		let signed_tx = ctx.sign(&tx).to_bytes();
+		match client.submit_transaction(&signed_tx).await {
+			Ok(response) => {
+				let tx_hash = McTxHash(response.transaction.id);
+				log::info!(
+					"'{tx_name}' transaction with {} operations submitted: {}",
+					batch.len(),
+					hex::encode(tx_hash.0)
+				);
+				let change_utxos = chain_context(&mut ctx, &tx)?;
+				unconfirmed_utxos.retain(|utxo| ctx.payment_key_utxos.contains(utxo));
+				unconfirmed_utxos.extend(change_utxos);
+				unconfirmed_txs.push(tx_hash);
+				results.extend((start..end).map(|i| BatchOperationResult::submitted(i, tx_hash)));
+				batch_size = batch_size.saturating_mul(2).min(failed_batch_size - 1);
+			},
+			Err(e) => {
+				log::warn!("Submit '{tx_name}' transaction request failed: {e}");
+				let error = e.to_string();
+				results
+					.extend((start..end).map(|i| BatchOperationResult::failed(i, error.clone())));
+			},
+		}
+		start = end;
+	}
+	await_all(&mut unconfirmed_txs, client, await_tx).await?;
+	Ok(results)
+}
+
+async fn await_all<T: QueryNetwork + QueryUtxoByUtxoId, A: AwaitTx>(
+	tx_hashes: &mut Vec<McTxHash>,
+	client: &T,
+	await_tx: &A,
+) -> anyhow::Result<()> {
+	for tx_hash in tx_hashes.drain(..) {
+		await_tx.await_tx_output(client, tx_hash).await?;
+	}
+	Ok(())
+}
+
+/// Fails if the transaction exceeds the maximum transaction size or execution units.
+fn check_limits(
+	tx: Transaction,
+	protocol_parameters: &ProtocolParametersResponse,
+) -> anyhow::Result<Transaction> {
+	let size = tx.to_bytes().len();
+	let max_size = protocol_parameters.max_transaction_size.bytes as usize;
+	if size > max_size {
+		return Err(anyhow!("Transaction size {size} exceeds the limit of {max_size} bytes"));
+	}
+	if let (Some(redeemers), Some(max_ex_units)) =
+		(tx.witness_set().redeemers(), &protocol_parameters.max_execution_units_per_transaction)
+	{
+		let (mut memory, mut cpu) = (0u64, 0u64);
+		for i in 0..redeemers.len() {
+			let ex_units = redeemers.get(i).ex_units();
+			memory = memory.saturating_add(ex_units.mem().into());
+			cpu = cpu.saturating_add(ex_units.steps().into());
+		}
+		if memory > max_ex_units.memory || cpu > max_ex_units.cpu {
+			return Err(anyhow!(
+				"Transaction execution units (memory: {memory}, cpu: {cpu}) exceed the limit (memory: {}, cpu: {})",
+				max_ex_units.memory,
+				max_ex_units.cpu
+			));
+		}
+	}
+	Ok(tx)
+}
+
+/// Updates payment UTXOs of `ctx` as if `tx` was already included in the ledger:
+/// spent inputs are removed and outputs at the change address are added.
+/// Returns the added UTXOs.
+fn chain_context(
+	ctx: &mut TransactionContext,
+	tx: &Transaction,
+) -> anyhow::Result<Vec<OgmiosUtxo>> {
+	let body = tx.body();
+	let spent: Vec<_> = body.inputs().into_iter().cloned().collect();
+	ctx.payment_key_utxos.retain(|utxo| !spent.contains(&utxo.to_csl_tx_input()));
+
+	let tx_hash: [u8; 32] = blake2b(body.to_bytes().as_ref());
+	let mut change_utxos = vec![];
+	for (index, output) in body.outputs().into_iter().enumerate() {
+		if output.address() == ctx.change_address {
+			change_utxos.push(to_ogmios_utxo(tx_hash, index.try_into()?, output)?);
+		}
+	}
+	ctx.payment_key_utxos.extend(change_utxos.iter().cloned());
+	Ok(change_utxos)
+}
+
+fn to_ogmios_utxo(
+	tx_hash: [u8; 32],
+	index: u16,
+	output: &TransactionOutput,
+) -> anyhow::Result<OgmiosUtxo> {
+	let amount = output.amount();
+	let mut native_tokens = HashMap::new();
+	if let Some(multiasset) = amount.multiasset() {
+		let policies = multiasset.keys();
+		for i in 0..policies.len() {
+			let policy = policies.get(i);
+			let Some(assets) = multiasset.get(&policy) else { continue };
+			let names = assets.keys();
+			let mut ogmios_assets = Vec::with_capacity(names.len());
+			for j in 0..names.len() {
+				let name = names.get(j);
+				let Some(amount) = assets.get(&name) else { continue };
+				ogmios_assets.push(Asset { name: name.name(), amount: amount.into() });
+			}
+			let policy_id: [u8; 28] = policy
+				.to_bytes()
+				.try_into()
+				.map_err(|_| anyhow!("CSL script hash should have 28 bytes"))?;
+			native_tokens.insert(policy_id, ogmios_assets);
+		}
+	}
+	Ok(OgmiosUtxo {
+		transaction: OgmiosTx { id: tx_hash },
+		index,
+		address: output.address().to_bech32(None)?,
+		value: OgmiosValue { lovelace: amount.coin().into(), native_tokens },
+		datum: output.plutus_data().map(|data| data.to_bytes().into()),
+		datum_hash: None,
+		script: None,
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::csl::TransactionOutputAmountBuilderExt;
+	use crate::{
+		await_tx::mock::ImmediateSuccess,
+		csl::{TransactionBuilderExt, get_builder_config},
+		governance::{GovernancePolicyScript, SimpleAtLeastN},
+		ogmios_mock::MockOgmiosClient,
+		test_values::*,
+	};
+	use cardano_serialization_lib::{
+		NetworkIdKind, TransactionBuilder, TransactionOutputBuilder, Value,
+	};
+	use ogmios_client::transactions::SubmitTransactionResponse;
+	use partner_chains_plutus_data::registered_candidates::candidate_registration_to_plutus_data;
+	use pretty_assertions::assert_eq;
+	use sidechain_domain::{
+		AdaBasedStaking, CandidateKeys, CandidateRegistration, MainchainKeyHash,
+		MainchainSignature, SidechainPublicKey, SidechainSignature, StakeOwnership, UtxoId,
+	};
+
+	fn single_key_governance() -> GovernanceData {
+		GovernanceData {
+			policy: GovernancePolicyScript::AtLeastNNativeScript(SimpleAtLeastN {
+				threshold: 1,
+				key_hashes: vec![test_payment_key().to_pub_key_hash().0],
+			}),
+			utxo: make_utxo(1, 0, 2_000_000, &test_validator().address(NetworkIdKind::Testnet)),
+		}
+	}
+
+	fn test_ctx() -> TransactionContext {
+		TransactionContext {
+			payment_key: test_payment_key(),
+			payment_key_utxos: vec![make_utxo(2, 0, 100_000_000, &payment_addr())],
+			network: NetworkIdKind::Testnet,
+			protocol_parameters: protocol_parameters(),
+			change_address: payment_addr(),
+		}
+	}
+
+	fn test_client() -> MockOgmiosClient {
+		MockOgmiosClient::new()
+			.with_evaluate_result(vec![])
+			.with_submit_result(SubmitTransactionResponse { transaction: OgmiosTx { id: [3; 32] } })
+	}
+
+	/// Transaction that pays `lovelace` to the validator address for each of `operations`.
+	fn payments_tx(
+		operations: &[u64],
+		_costs: Costs,
+		ctx: &TransactionContext,
+	) -> anyhow::Result<Transaction> {
+		let mut tx_builder = TransactionBuilder::new(&get_builder_config(ctx)?);
+		for lovelace in operations {
+			tx_builder.add_output(&TransactionOutput::new(
+				&test_validator().address(ctx.network),
+				&Value::new(&(*lovelace).into()),
+			))?;
+		}
+		Ok(tx_builder.balance_update_and_build(ctx)?)
+	}
+
+	#[tokio::test]
+	async fn packs_operations_into_chained_transactions() {
+		let results = submit_in_batches(
+			&single_key_governance(),
+			test_ctx(),
+			&[2_000_000, 3_000_000, 4_000_000],
+			2,
+			payments_tx,
+			"Payments",
+			&test_client(),
+			&ImmediateSuccess,
+		)
+		.await
+		.unwrap();
+		let tx_hash = McTxHash([3; 32]);
+		assert_eq!(
+			results,
+			vec![
+				BatchOperationResult::submitted(0, tx_hash),
+				BatchOperationResult::submitted(1, tx_hash),
+				BatchOperationResult::submitted(2, tx_hash),
+			]
+		);
+	}
+
+	#[tokio::test]
+	async fn halves_batch_and_reports_operations_that_do_not_fit() {
+		let results = submit_in_batches(
+			&single_key_governance(),
+			test_ctx(),
+			&[2_000_000, 1_000_000_000, 4_000_000],
+			3,
+			payments_tx,
+			"Payments",
+			&test_client(),
+			&ImmediateSuccess,
+		)
+		.await
+		.unwrap();
+		assert_eq!(results.len(), 3);
+		assert_eq!(results[0], BatchOperationResult::submitted(0, McTxHash([3; 32])));
+		assert_eq!(results[1].operation, 1);
+		assert_eq!(results[1].tx_hash, None);
+		assert!(results[1].error.is_some());
+		assert_eq!(results[2], BatchOperationResult::submitted(2, McTxHash([3; 32])));
+	}
+
+	fn candidate_registration(i: u8) -> CandidateRegistration {
+		CandidateRegistration {
+			stake_ownership: StakeOwnership::AdaBased(AdaBasedStaking {
+				pub_key: stake_pool_pub_key(),
+				signature: MainchainSignature([i; 64]),
+			}),
+			partner_chain_pub_key: SidechainPublicKey(vec![i; 33]),
+			partner_chain_signature: SidechainSignature(vec![i; 64]),
+			registration_utxo: UtxoId::new([i; 32], 0),
+			own_pkh: MainchainKeyHash([i; 28]),
+			keys: CandidateKeys(vec![]),
+		}
+	}
+
+	/// Transaction that creates a registration UTXO at the validator address for each of `registrations`.
+	fn registrations_tx(
+		registrations: &[CandidateRegistration],
+		_costs: Costs,
+		ctx: &TransactionContext,
+	) -> anyhow::Result<Transaction> {
+		let mut tx_builder = TransactionBuilder::new(&get_builder_config(ctx)?);
+		for registration in registrations {
+			let output = TransactionOutputBuilder::new()
+				.with_address(&test_validator().address(ctx.network))
+				.with_plutus_data(&candidate_registration_to_plutus_data(registration))
+				.next()?
+				.with_minimum_ada(ctx)?
+				.build()?;
+			tx_builder.add_output(&output)?;
+		}
+		Ok(tx_builder.balance_update_and_build(ctx)?)
+	}
+
+	#[tokio::test]
+	async fn splits_registrations_that_do_not_fit_in_one_transaction_and_regrows_batch() {
+		let registrations: Vec<_> = (1..=5).map(candidate_registration).collect();
+		let mut ctx = test_ctx();
+		// Only two registrations fit in a transaction
+		let two_registrations_size = registrations_tx(&registrations[..2], Costs::ZeroCosts, &ctx)
+			.unwrap()
+			.to_bytes()
+			.len();
+		ctx.protocol_parameters.max_transaction_size.bytes = (two_registrations_size + 16) as u32;
+		let client = test_client();
+
+		let results = submit_in_batches(
+			&single_key_governance(),
+			ctx,
+			&registrations,
+			5,
+			registrations_tx,
+			"Register",
+			&client,
+			&ImmediateSuccess,
+		)
+		.await
+		.unwrap();
+
+		let tx_hash = McTxHash([3; 32]);
+		assert_eq!(
+			results,
+			(0..5).map(|i| BatchOperationResult::submitted(i, tx_hash)).collect::<Vec<_>>()
+		);
+		let registrations_per_tx: Vec<usize> = client
+			.submitted_transactions()
+			.iter()
+			.map(|tx| Transaction::from_bytes(tx.clone()).unwrap().body().outputs().len() - 1)
+			.collect();
+		assert_eq!(registrations_per_tx, vec![2, 2, 1]);
+	}
+
+	#[tokio::test]
+	async fn fails_for_multisig_governance() {
+		let governance =
+			GovernanceData { policy: test_governance_policy(), utxo: single_key_governance().utxo };
+		let result = submit_in_batches(
+			&governance,
+			test_ctx(),
+			&[2_000_000],
+			1,
+			payments_tx,
+			"Payments",
+			&test_client(),
+			&ImmediateSuccess,
+		)
+		.await;
+		assert!(result.is_err());
+	}
+
+	#[test]
+	fn chain_context_replaces_spent_inputs_with_change() {
+		let mut ctx = test_ctx();
+		let tx = payments_tx(&[2_000_000], Costs::ZeroCosts, &ctx).unwrap();
+		let change_utxos = chain_context(&mut ctx, &tx).unwrap();
+
+		let tx_hash: [u8; 32] = blake2b(tx.body().to_bytes().as_ref());
+		assert_eq!(change_utxos.len(), 1);
+		assert_eq!(ctx.payment_key_utxos, change_utxos);
+		let change = &change_utxos[0];
+		assert_eq!(change.transaction.id, tx_hash);
+		assert_eq!(change.address, PAYMENT_ADDR);
+		let fee: u64 = tx.body().fee().into();
+		assert_eq!(change.value.lovelace, 100_000_000 - 2_000_000 - fee);
+	}
+
+	#[test]
+	fn check_limits_rejects_too_large_transaction() {
+		let ctx = test_ctx();
+		let tx = payments_tx(&[2_000_000], Costs::ZeroCosts, &ctx).unwrap();
+		let mut parameters = protocol_parameters();
+		parameters.max_transaction_size.bytes = 10;
+		assert!(check_limits(tx.clone(), &parameters).is_err());
+		assert!(check_limits(tx, &protocol_parameters()).is_ok());
+	}
+}
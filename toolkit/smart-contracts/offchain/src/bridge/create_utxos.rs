@@ -1,5 +1,6 @@
 use crate::{
 	await_tx::AwaitTx,
+	batch::{BatchOperationResult, submit_in_batches},
 	bridge::ICSData,
 	cardano_keys::CardanoPaymentSigningKey,
 	csl::{
@@ -48,6 +49,38 @@ pub async fn create_validator_utxos<
 	.await
 }
 
+/// Creates "blessed" UTXOs at the ICS (Bridge) validator in chained transactions,
+/// each creating at most `max_batch_size` UTXOs. Results are reported for each UTXO.
+/// Requires single key governance of the payment key.
+pub async fn create_validator_utxos_in_batches<
+	T: QueryLedgerState + Transactions + QueryNetwork + QueryUtxoByUtxoId,
+	A: AwaitTx,
+>(
+	genesis_utxo: UtxoId,
+	amount: NonZero<u64>,
+	max_batch_size: NonZero<usize>,
+	payment_key: &CardanoPaymentSigningKey,
+	client: &T,
+	await_tx: &A,
+) -> anyhow::Result<Vec<BatchOperationResult>> {
+	let payment_ctx = TransactionContext::for_payment_key(payment_key, client).await?;
+	let governance = GovernanceData::get(genesis_utxo, client).await?;
+	let ics_data = ICSData::get(genesis_utxo, &payment_ctx, client).await?;
+	let utxos = vec![(); usize::try_from(amount.get())?];
+
+	submit_in_batches(
+		&governance,
+		payment_ctx,
+		&utxos,
+		max_batch_size.get(),
+		|batch, costs, ctx| create_utxos_tx(batch.len() as u64, &ics_data, &governance, costs, ctx),
+		"Create Bridge UTXOs",
+		client,
+		await_tx,
+	)
+	.await
+}
+
 fn create_utxos_tx(
 	amount: u64,
 	ics_data: &ICSData,
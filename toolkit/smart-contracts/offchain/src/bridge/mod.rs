@@ -14,7 +14,7 @@ use cardano_serialization_lib::{
 	BigInt, ExUnits, JsError, PlutusData, PlutusScriptSource, PlutusWitness, Redeemer, RedeemerTag,
 	TxInputsBuilder,
 };
-pub use create_utxos::create_validator_utxos;
+pub use create_utxos::{create_validator_utxos, create_validator_utxos_in_batches};
 pub use deposit::{deposit_with_ics_spend, deposit_without_ics_input};
 pub use init::init_ics_scripts;
 use ogmios_client::{query_ledger_state::QueryLedgerState, types::OgmiosUtxo};
@@ -258,23 +258,47 @@ impl Costs {
 		make_tx: F,
 		client: &T,
 	) -> anyhow::Result<Transaction>
+	where
+		F: Fn(Costs) -> anyhow::Result<Transaction>,
+	{
+		Self::calculate_costs_with_additional_utxos(make_tx, &[], client).await
+	}
+
+	/// Creates a [Transaction] with correctly set script execution costs,
+	/// when the transaction spends outputs of transactions that are not yet in the ledger.
+	///
+	/// Arguments:
+	///  - `make_tx`: A function that takes a [Costs] value, and returns a [anyhow::Result<Transaction>].
+	///  - `additional_utxos`: UTXOs not present in the ledger, that can be spent by the transaction
+	///  - `client`: Ogmios client
+	pub(crate) async fn calculate_costs_with_additional_utxos<T: Transactions, F>(
+		make_tx: F,
+		additional_utxos: &[OgmiosUtxo],
+		client: &T,
+	) -> anyhow::Result<Transaction>
 	where
 		F: Fn(Costs) -> anyhow::Result<Transaction>,
 	{
 		// This double evaluation is needed to correctly set costs in some cases.
 		let tx = make_tx(Costs::ZeroCosts)?;
 		// stage 1
-		let costs = Self::from_ogmios(&tx, client).await?;
+		let costs = Self::from_ogmios(&tx, additional_utxos, client).await?;
 
 		let tx = make_tx(costs)?;
 		// stage 2
-		let costs = Self::from_ogmios(&tx, client).await?;
+		let costs = Self::from_ogmios(&tx, additional_utxos, client).await?;
 
 		make_tx(costs)
 	}
 
-	async fn from_ogmios<T: Transactions>(tx: &Transaction, client: &T) -> anyhow::Result<Costs> {
-		let evaluate_response = client.evaluate_transaction(&tx.to_bytes()).await?;
+	async fn from_ogmios<T: Transactions>(
+		tx: &Transaction,
+		additional_utxos: &[OgmiosUtxo],
+		client: &T,
+	) -> anyhow::Result<Costs> {
+		let evaluate_response = client
+			.evaluate_transaction_with_additional_utxos(&tx.to_bytes(), additional_utxos)
+			.await?;
 
 		let mut mints = HashMap::new();
 		let mut spends = HashMap::new();
@@ -1,3 +1,4 @@
+use crate::batch::{BatchOperationResult, submit_in_batches};
 use crate::csl::{
 	CostStore, Costs, InputsBuilderExt, NetworkTypeExt, TransactionBuilderExt, TransactionExt,
 	empty_asset_name, get_builder_config, unit_plutus_data,
@@ -25,6 +26,7 @@ use partner_chains_plutus_data::governed_map::{
 };
 use sidechain_domain::byte_string::ByteString;
 use sidechain_domain::{PolicyId, UtxoId};
+use std::num::NonZero;
 use std::ops::Neg;
 
 #[cfg(test)]
@@ -148,6 +150,123 @@ fn insert_key_value_tx(
 	Ok(tx_builder.balance_update_and_build(ctx)?.remove_native_script_witnesses())
 }
 
+/// Inserts multiple entries into the governed map, packing them into chained transactions
+/// of at most `max_batch_size` entries each.
+/// Entries with the `key` already set to the provided `value` are skipped.
+/// Fails without submitting any transaction if any `key` is already set to a different value.
+/// Requires single key governance of the payment key.
+pub async fn run_insert_many<
+	C: QueryLedgerState + QueryNetwork + Transactions + QueryUtxoByUtxoId,
+	A: AwaitTx,
+>(
+	genesis_utxo: UtxoId,
+	entries: Vec<(String, ByteString)>,
+	max_batch_size: NonZero<usize>,
+	payment_signing_key: &CardanoPaymentSigningKey,
+	ogmios_client: &C,
+	await_tx: &A,
+) -> anyhow::Result<Vec<BatchOperationResult>> {
+	let ctx = TransactionContext::for_payment_key(payment_signing_key, ogmios_client).await?;
+	let scripts = crate::scripts_data::governed_map_scripts(genesis_utxo, ctx.network)?;
+	let validator_utxos = ogmios_client.query_utxos(&[scripts.validator_address.clone()]).await?;
+	let current_entries: Vec<GovernedMapDatum> =
+		ogmios_utxos_to_governed_map_utxos(validator_utxos.into_iter(), scripts.policy_id())
+			.map(|(_, datum)| datum)
+			.collect();
+
+	let mut results = vec![];
+	let mut to_insert: Vec<(usize, String, ByteString)> = vec![];
+	for (i, (key, value)) in entries.into_iter().enumerate() {
+		match current_entries.iter().find(|datum| datum.key == key).map(|datum| &datum.value) {
+			Some(current_value) if *current_value != value => {
+				return Err(anyhow!("There is already a value stored for key '{key}'."));
+			},
+			Some(_) => {
+				log::info!(
+					"Value for key '{key}' is already set to the same value. Skipping insert."
+				);
+				results.push(BatchOperationResult::skipped(i));
+			},
+			None => match to_insert.iter().find(|(_, k, _)| *k == key) {
+				Some((_, _, v)) if *v != value => {
+					return Err(anyhow!(
+						"Key '{key}' is given more than once with different values."
+					));
+				},
+				Some(_) => results.push(BatchOperationResult::skipped(i)),
+				None => to_insert.push((i, key, value)),
+			},
+		}
+	}
+	if to_insert.is_empty() {
+		return Ok(results);
+	}
+	let governance_data = GovernanceData::get(genesis_utxo, ogmios_client).await?;
+	let inserted = submit_in_batches(
+		&governance_data,
+		ctx,
+		&to_insert,
+		max_batch_size.get(),
+		|entries, costs, ctx| {
+			insert_key_values_tx(
+				&scripts.validator,
+				&scripts.policy,
+				entries,
+				&governance_data,
+				costs,
+				ctx,
+			)
+		},
+		"Insert Key-Value pairs",
+		ogmios_client,
+		await_tx,
+	)
+	.await?;
+	results.extend(
+		inserted.into_iter().map(|result| BatchOperationResult {
+			operation: to_insert[result.operation].0,
+			..result
+		}),
+	);
+	results.sort_by_key(|result| result.operation);
+	Ok(results)
+}
+
+fn insert_key_values_tx(
+	validator: &PlutusScript,
+	policy: &PlutusScript,
+	entries: &[(usize, String, ByteString)],
+	governance_data: &GovernanceData,
+	costs: Costs,
+	ctx: &TransactionContext,
+) -> anyhow::Result<Transaction> {
+	let mut tx_builder = TransactionBuilder::new(&get_builder_config(ctx)?);
+	tx_builder.add_mint_script_tokens(
+		policy,
+		&empty_asset_name(),
+		&unit_plutus_data(),
+		&costs.get_mint(policy),
+		&Int::new(&(entries.len() as u64).into()),
+	)?;
+	for (_, key, value) in entries {
+		tx_builder.add_output_with_one_script_token(
+			validator,
+			policy,
+			&governed_map_datum_to_plutus_data(&GovernedMapDatum::new(key.clone(), value.clone())),
+			ctx,
+		)?;
+	}
+
+	let gov_tx_input = governance_data.utxo_id_as_tx_input();
+	tx_builder.add_mint_one_script_token_using_reference_script(
+		&governance_data.policy.script(),
+		&gov_tx_input,
+		&costs,
+	)?;
+
+	Ok(tx_builder.balance_update_and_build(ctx)?.remove_native_script_witnesses())
+}
+
 /// Updates an entry in the governed map.
 /// If `expected_current_value` is provided, the current `value` for the `key` must match it, otherwise the operation fails.
 /// If the `key` is not set, the operation fails.
@@ -1,4 +1,7 @@
-use super::{get_current_value, get_utxos_for_key, insert_key_value_tx, remove_key_value_tx};
+use super::{
+	get_current_value, get_utxos_for_key, insert_key_value_tx, insert_key_values_tx,
+	remove_key_value_tx,
+};
 use crate::csl::{TransactionContext, empty_asset_name};
 use crate::governance::GovernanceData;
 use crate::test_values::*;
@@ -144,6 +147,79 @@ mod governed_map_insert_tx_tests {
 	}
 }
 
+mod governed_map_insert_many_tx_tests {
+	use super::*;
+	use crate::csl::Costs;
+	use cardano_serialization_lib::Transaction;
+
+	fn test_costs() -> Costs {
+		Costs::new(
+			vec![
+				(
+					ScriptHash::from_bytes(token_policy_id().to_vec()).unwrap(),
+					ExUnits::new(&10000u32.into(), &200u32.into()),
+				),
+				(governance_script_hash(), ExUnits::new(&20000u32.into(), &400u32.into())),
+			]
+			.into_iter()
+			.collect(),
+			vec![].into_iter().collect(),
+		)
+	}
+
+	fn entries() -> Vec<(usize, String, ByteString)> {
+		vec![
+			(0, "key_1".to_string(), ByteString::from(vec![1u8])),
+			(3, "key_2".to_string(), ByteString::from(vec![2u8])),
+		]
+	}
+
+	fn governed_map_insert_many_tx_test() -> Transaction {
+		insert_key_values_tx(
+			&test_validator(),
+			&test_policy(),
+			&entries(),
+			&governance_data(),
+			test_costs(),
+			&test_tx_context(),
+		)
+		.expect("Test transaction should be constructed without error")
+	}
+
+	#[test]
+	fn mints_key_value_token_for_each_entry() {
+		let body = governed_map_insert_many_tx_test().body();
+
+		let key_value_token_mint_amount = body
+			.mint()
+			.expect("Should mint a token")
+			.get(&token_policy_id().into())
+			.and_then(|policy| policy.get(0))
+			.expect("The minted token should have the key value policy")
+			.get(&empty_asset_name())
+			.expect("The minted token should have an empty asset name");
+
+		assert_eq!(key_value_token_mint_amount, Int::new_i32(2));
+	}
+
+	#[test]
+	fn creates_output_with_datum_for_each_entry() {
+		let outputs = governed_map_insert_many_tx_test().body().outputs();
+
+		let datums: Vec<PlutusData> = (outputs.into_iter())
+			.filter(|o| o.address() == validator_addr())
+			.map(|o| o.plutus_data().expect("Utxo should have plutus data attached"))
+			.collect();
+
+		let expected: Vec<PlutusData> = (entries().into_iter())
+			.map(|(_, key, value)| {
+				governed_map_datum_to_plutus_data(&GovernedMapDatum::new(key, value))
+			})
+			.collect();
+		assert_eq!(datums, expected);
+	}
+}
+
 mod update_governed_map_tests {
 	use crate::csl::Costs;
 
@@ -5,6 +5,8 @@
 pub mod assemble_and_submit_tx;
 /// Primitives used for awaiting for tx being observed on the blockchain
 pub mod await_tx;
+/// Submission of bulk operations in chained transactions
+pub mod batch;
 /// Deposits to (in the future withdrawals from as well) bridge validator
 pub mod bridge;
 /// Parsing and wrapping of Cardano keys
@@ -155,7 +155,10 @@ fn save_wallet_file(wallet: &TemporaryWallet) -> Result<(), anyhow::Error> {
 	Ok(())
 }
 
-async fn transfer_to_temporary_wallet<T: Transactions + QueryUtxoByUtxoId, A: AwaitTx>(
+async fn transfer_to_temporary_wallet<
+	T: Transactions + QueryNetwork + QueryUtxoByUtxoId,
+	A: AwaitTx,
+>(
 	payment_ctx: TransactionContext,
 	address: &Address,
 	value: &Value,
@@ -7,6 +7,7 @@ use ogmios_client::{
 	transactions::{OgmiosEvaluateTransactionResponse, SubmitTransactionResponse, Transactions},
 	types::OgmiosUtxo,
 };
+use std::sync::{Arc, Mutex};
 
 #[derive(Clone, Default, Debug)]
 pub struct MockOgmiosClient {
@@ -14,6 +15,7 @@ pub struct MockOgmiosClient {
 	protocol_parameters: ProtocolParametersResponse,
 	evaluate_result: Option<Vec<OgmiosEvaluateTransactionResponse>>,
 	submit_result: Option<SubmitTransactionResponse>,
+	submitted_transactions: Arc<Mutex<Vec<Vec<u8>>>>,
 }
 
 impl MockOgmiosClient {
@@ -41,6 +43,11 @@ impl MockOgmiosClient {
 	pub fn with_submit_result(self, submit_result: SubmitTransactionResponse) -> Self {
 		Self { submit_result: Some(submit_result), ..self }
 	}
+
+	/// Returns bytes of all transactions submitted to the client
+	pub fn submitted_transactions(&self) -> Vec<Vec<u8>> {
+		self.submitted_transactions.lock().unwrap().clone()
+	}
 }
 
 impl QueryNetwork for MockOgmiosClient {
@@ -49,6 +56,10 @@ impl QueryNetwork for MockOgmiosClient {
 	) -> Result<ShelleyGenesisConfigurationResponse, ogmios_client::OgmiosClientError> {
 		Ok(Default::default())
 	}
+
+	async fn get_block_height(&self) -> Result<u64, OgmiosClientError> {
+		Ok(0)
+	}
 }
 
 impl Transactions for MockOgmiosClient {
@@ -62,13 +73,22 @@ impl Transactions for MockOgmiosClient {
 		Ok(self.evaluate_result.clone().unwrap())
 	}
 
+	async fn evaluate_transaction_with_additional_utxos(
+		&self,
+		tx_bytes: &[u8],
+		_additional_utxos: &[OgmiosUtxo],
+	) -> Result<Vec<OgmiosEvaluateTransactionResponse>, OgmiosClientError> {
+		self.evaluate_transaction(tx_bytes).await
+	}
+
 	async fn submit_transaction(
 		&self,
-		_tx_bytes: &[u8],
+		tx_bytes: &[u8],
 	) -> Result<
 		ogmios_client::transactions::SubmitTransactionResponse,
 		ogmios_client::OgmiosClientError,
 	> {
+		self.submitted_transactions.lock().unwrap().push(tx_bytes.to_vec());
 		Ok(self.submit_result.clone().unwrap())
 	}
 }
@@ -3,7 +3,11 @@ use crate::csl::TransactionOutputAmountBuilderExt;
 use crate::csl::{
 	CostStore, Costs, InputsBuilderExt, TransactionBuilderExt, TransactionContext, unit_plutus_data,
 };
-use crate::{await_tx::AwaitTx, plutus_script::PlutusScript};
+use crate::{
+	await_tx::AwaitTx,
+	batch::{BatchOperationResult, submit_chained_in_batches},
+	plutus_script::PlutusScript,
+};
 use anyhow::anyhow;
 use cardano_serialization_lib::{
 	PlutusData, Transaction, TransactionBuilder, TransactionOutputBuilder, TxInputsBuilder,
@@ -18,6 +22,7 @@ use partner_chains_plutus_data::registered_candidates::{
 	RegisterValidatorDatum, candidate_registration_to_plutus_data,
 };
 use sidechain_domain::*;
+use std::num::NonZero;
 
 /// Submits a transaction to register a registered candidate.
 /// Arguments:
@@ -46,7 +51,10 @@ pub async fn run_register<
 	let all_registration_utxos = client.query_utxos(&[validator_address]).await?;
 	let own_registrations = get_own_registrations(
 		candidate_registration.own_pkh,
-		candidate_registration.stake_ownership.pub_key.clone(),
+		candidate_registration
+			.stake_ownership
+			.ada_based()
+			.map(|staking| &staking.pub_key),
 		&all_registration_utxos,
 	);
 
@@ -88,6 +96,112 @@ pub async fn run_register<
 	Ok(Some(McTxHash(result.transaction.id)))
 }
 
+/// Registers multiple registered candidates, packing their registrations into chained transactions
+/// of at most `max_batch_size` registrations each. All transactions are paid for and signed by the
+/// payment key, so `own_pkh` of every registration has to be the hash of this key.
+/// Candidates already registered with the same keys are skipped.
+/// Fails without submitting any transaction if any registration is invalid.
+/// Arguments:
+///  - `genesis_utxo`: UTxO identifying the Partner Chain.
+///  - `candidate_registrations`: [CandidateRegistration] registration data of all candidates.
+///  - `max_batch_size`: maximum number of registrations in a single transaction.
+///  - `client`: Ogmios client.
+///  - `await_tx`: [AwaitTx] strategy.
+pub async fn run_register_many<
+	C: QueryLedgerState + QueryNetwork + QueryUtxoByUtxoId + Transactions,
+	A: AwaitTx,
+>(
+	genesis_utxo: UtxoId,
+	candidate_registrations: &[CandidateRegistration],
+	max_batch_size: NonZero<usize>,
+	payment_signing_key: &CardanoPaymentSigningKey,
+	client: &C,
+	await_tx: &A,
+) -> anyhow::Result<Vec<BatchOperationResult>> {
+	let ctx = TransactionContext::for_payment_key(payment_signing_key, client).await?;
+	let validator = crate::scripts_data::registered_candidates_scripts(genesis_utxo)?;
+	let validator_address = validator.address_bech32(ctx.network)?;
+	let all_registration_utxos = client.query_utxos(&[validator_address]).await?;
+	let own_pkh = payment_signing_key.to_pub_key_hash();
+
+	let mut results = vec![];
+	let mut to_register: Vec<PendingRegistration> = vec![];
+	for (i, candidate_registration) in candidate_registrations.iter().enumerate() {
+		if candidate_registration.own_pkh != own_pkh {
+			return Err(anyhow!("Registration {i} is not owned by the payment key"));
+		}
+		let stake_pub_key = candidate_registration
+			.stake_ownership
+			.ada_based()
+			.map(|staking| &staking.pub_key);
+		if to_register.iter().any(|pending| {
+			pending.registration.stake_ownership.ada_based().map(|staking| &staking.pub_key)
+				== stake_pub_key
+		}) {
+			return Err(anyhow!("Registration {i} has the same stake ownership as a previous one"));
+		}
+		let registration_utxo = ctx
+			.payment_key_utxos
+			.iter()
+			.find(|u| u.utxo_id() == candidate_registration.registration_utxo)
+			.ok_or_else(|| {
+				anyhow!("registration utxo of registration {i} not found at payment address")
+			})?;
+		let own_registrations =
+			get_own_registrations(own_pkh, stake_pub_key, &all_registration_utxos);
+		if own_registrations.iter().any(|(_, existing_registration)| {
+			candidate_registration.matches_keys(existing_registration)
+		}) {
+			log::info!("✅ Candidate of registration {i} already registered with same keys.");
+			results.push(BatchOperationResult::skipped(i));
+			continue;
+		}
+		to_register.push(PendingRegistration {
+			operation: i,
+			registration: candidate_registration.clone(),
+			registration_utxo: registration_utxo.clone(),
+			own_registration_utxos: own_registrations.into_iter().map(|r| r.0).collect(),
+		});
+	}
+	if to_register.is_empty() {
+		return Ok(results);
+	}
+
+	let registered = submit_chained_in_batches(
+		ctx,
+		&to_register,
+		max_batch_size.get(),
+		|batch, costs, ctx| {
+			// Registration UTXOs of other candidates must not be spent to balance the transaction
+			let mut ctx = ctx.clone();
+			ctx.payment_key_utxos.retain(|utxo| {
+				batch.iter().any(|pending| pending.registration_utxo == *utxo)
+					|| !to_register.iter().any(|pending| pending.registration_utxo == *utxo)
+			});
+			register_many_tx(&validator, batch, costs, &ctx)
+		},
+		"Register candidates",
+		client,
+		await_tx,
+	)
+	.await?;
+	results.extend(registered.into_iter().map(|result| BatchOperationResult {
+		operation: to_register[result.operation].operation,
+		..result
+	}));
+	results.sort_by_key(|result| result.operation);
+	Ok(results)
+}
+
+/// Registration to be submitted by [run_register_many]
+struct PendingRegistration {
+	/// Index of the registration in the input
+	operation: usize,
+	registration: CandidateRegistration,
+	registration_utxo: OgmiosUtxo,
+	own_registration_utxos: Vec<OgmiosUtxo>,
+}
+
 /// Submits a transaction to register a registered candidate.
 /// Arguments:
 ///  - `genesis_utxo`: UTxO identifying the Partner Chain.
@@ -109,7 +223,7 @@ pub async fn run_deregister<
 	let all_registration_utxos = client.query_utxos(&[validator_address]).await?;
 	let own_registrations = get_own_registrations(
 		payment_signing_key.to_pub_key_hash(),
-		stake_ownership_pub_key.clone(),
+		Some(&stake_ownership_pub_key),
 		&all_registration_utxos,
 	);
 
@@ -141,17 +255,19 @@ pub async fn run_deregister<
 	Ok(Some(McTxHash(result.transaction.id)))
 }
 
+/// Returns registrations of `own_pkh` with ADA-based stake of `spo_pub_key`,
+/// or with token-based stake if `spo_pub_key` is `None`.
 fn get_own_registrations(
 	own_pkh: MainchainKeyHash,
-	spo_pub_key: StakePoolPublicKey,
+	spo_pub_key: Option<&StakePoolPublicKey>,
 	validator_utxos: &[OgmiosUtxo],
 ) -> Vec<(OgmiosUtxo, CandidateRegistration)> {
 	let mut own_registrations = Vec::new();
 	for validator_utxo in validator_utxos {
 		match get_candidate_registration(validator_utxo.clone()) {
 			Ok(candidate_registration) => {
-				if candidate_registration.stake_ownership.pub_key == spo_pub_key
-					&& candidate_registration.own_pkh == own_pkh
+				if candidate_registration.stake_ownership.ada_based().map(|s| &s.pub_key)
+					== spo_pub_key && candidate_registration.own_pkh == own_pkh
 				{
 					own_registrations.push((validator_utxo.clone(), candidate_registration.clone()))
 				}
@@ -209,6 +325,47 @@ fn register_tx(
 	Ok(tx_builder.balance_update_and_build(ctx)?)
 }
 
+fn register_many_tx(
+	validator: &PlutusScript,
+	registrations: &[PendingRegistration],
+	costs: Costs,
+	ctx: &TransactionContext,
+) -> anyhow::Result<Transaction> {
+	let config = crate::csl::get_builder_config(ctx)?;
+	let mut tx_builder = TransactionBuilder::new(&config);
+
+	{
+		let spend_indices = costs.get_spend_indices();
+		let mut inputs = TxInputsBuilder::new();
+		let own_registration_utxos =
+			registrations.iter().flat_map(|pending| pending.own_registration_utxos.iter());
+		for (ix, own_registration_utxo) in own_registration_utxos.enumerate() {
+			inputs.add_script_utxo_input(
+				own_registration_utxo,
+				validator,
+				&register_redeemer_data(),
+				&costs.get_spend(*spend_indices.get(ix).unwrap_or(&0)),
+			)?;
+		}
+		let registration_utxos: Vec<OgmiosUtxo> =
+			registrations.iter().map(|pending| pending.registration_utxo.clone()).collect();
+		inputs.add_regular_inputs(&registration_utxos)?;
+		tx_builder.set_inputs(&inputs);
+	}
+
+	for pending in registrations {
+		let datum = candidate_registration_to_plutus_data(&pending.registration);
+		let amount_builder = TransactionOutputBuilder::new()
+			.with_address(&validator.address(ctx.network))
+			.with_plutus_data(&datum)
+			.next()?;
+		let output = amount_builder.with_minimum_ada(ctx)?.build()?;
+		tx_builder.add_output(&output)?;
+	}
+
+	Ok(tx_builder.balance_update_and_build(ctx)?)
+}
+
 fn deregister_tx(
 	validator: &PlutusScript,
 	own_registration_utxos: &[OgmiosUtxo],
@@ -255,7 +412,8 @@ mod tests {
 
 	use sidechain_domain::{
 		AdaBasedStaking, CandidateKeys, CandidateRegistration, MainchainKeyHash,
-		MainchainSignature, McTxHash, SidechainPublicKey, SidechainSignature, UtxoId, UtxoIndex,
+		MainchainSignature, McTxHash, SidechainPublicKey, SidechainSignature, StakeOwnership,
+		UtxoId, UtxoIndex,
 	};
 
 	fn sum_lovelace(utxos: &[OgmiosUtxo]) -> u64 {
@@ -270,10 +428,10 @@ mod tests {
 	}
 	fn candidate_registration(registration_utxo: UtxoId) -> CandidateRegistration {
 		CandidateRegistration {
-			stake_ownership: AdaBasedStaking {
+			stake_ownership: StakeOwnership::AdaBased(AdaBasedStaking {
 				pub_key: test_values::stake_pool_pub_key(),
 				signature: MainchainSignature([0u8; 64]),
-			},
+			}),
 			partner_chain_pub_key: SidechainPublicKey(Vec::new()),
 			partner_chain_signature: SidechainSignature(Vec::new()),
 			registration_utxo,
@@ -483,3 +641,167 @@ mod tests {
 		}
 	}
 }
+
+#[cfg(test)]
+mod register_many_tests {
+	use super::{BatchOperationResult, run_register_many};
+	use crate::{
+		await_tx::mock::ImmediateSuccess, csl::OgmiosUtxoExt, ogmios_mock::MockOgmiosClient,
+		test_values::*,
+	};
+	use cardano_serialization_lib::{NetworkIdKind, Transaction};
+	use ogmios_client::{
+		transactions::SubmitTransactionResponse,
+		types::{OgmiosTx, OgmiosUtxo},
+	};
+	use partner_chains_plutus_data::registered_candidates::candidate_registration_to_plutus_data;
+	use pretty_assertions::assert_eq;
+	use sidechain_domain::{
+		AdaBasedStaking, CandidateKeys, CandidateRegistration, MainchainSignature, McTxHash,
+		SidechainPublicKey, SidechainSignature, StakeOwnership, StakePoolPublicKey, UtxoId,
+	};
+	use std::num::NonZero;
+
+	const GENESIS_UTXO: UtxoId = UtxoId::new([7; 32], 0);
+
+	fn registration_utxo(i: u8) -> OgmiosUtxo {
+		make_utxo(10 + i, 0, 2_000_000, &payment_addr())
+	}
+
+	fn fee_utxo() -> OgmiosUtxo {
+		make_utxo(1, 0, 100_000_000, &payment_addr())
+	}
+
+	fn candidate_registration(i: u8) -> CandidateRegistration {
+		CandidateRegistration {
+			stake_ownership: StakeOwnership::AdaBased(AdaBasedStaking {
+				pub_key: StakePoolPublicKey([i; 32]),
+				signature: MainchainSignature([i; 64]),
+			}),
+			partner_chain_pub_key: SidechainPublicKey(vec![i; 33]),
+			partner_chain_signature: SidechainSignature(vec![i; 64]),
+			registration_utxo: registration_utxo(i).utxo_id(),
+			own_pkh: test_payment_key().to_pub_key_hash(),
+			keys: CandidateKeys(vec![]),
+		}
+	}
+
+	fn existing_registration_utxo(registration: &CandidateRegistration) -> OgmiosUtxo {
+		let validator = crate::scripts_data::registered_candidates_scripts(GENESIS_UTXO).unwrap();
+		OgmiosUtxo {
+			transaction: OgmiosTx { id: [20; 32] },
+			index: 0,
+			address: validator.address_bech32(NetworkIdKind::Testnet).unwrap(),
+			datum: Some(candidate_registration_to_plutus_data(registration).to_bytes().into()),
+			..Default::default()
+		}
+	}
+
+	fn test_client(utxos: Vec<OgmiosUtxo>) -> MockOgmiosClient {
+		MockOgmiosClient::new()
+			.with_utxos(utxos)
+			.with_protocol_parameters(protocol_parameters())
+			.with_evaluate_result(vec![])
+			.with_submit_result(SubmitTransactionResponse { transaction: OgmiosTx { id: [3; 32] } })
+	}
+
+	fn spent_utxos(tx: &[u8]) -> Vec<UtxoId> {
+		let tx = Transaction::from_bytes(tx.to_vec()).unwrap();
+		tx.body()
+			.inputs()
+			.into_iter()
+			.map(|input| {
+				UtxoId::new(
+					input.transaction_id().to_bytes().try_into().unwrap(),
+					input.index() as u16,
+				)
+			})
+			.collect()
+	}
+
+	#[tokio::test]
+	async fn registers_candidates_in_chained_batches() {
+		let registrations: Vec<_> = (0..3).map(candidate_registration).collect();
+		let client = test_client(vec![
+			fee_utxo(),
+			registration_utxo(0),
+			registration_utxo(1),
+			registration_utxo(2),
+		]);
+
+		let results = run_register_many(
+			GENESIS_UTXO,
+			&registrations,
+			NonZero::new(2).unwrap(),
+			&test_payment_key(),
+			&client,
+			&ImmediateSuccess,
+		)
+		.await
+		.unwrap();
+
+		let tx_hash = McTxHash([3; 32]);
+		assert_eq!(
+			results,
+			(0..3).map(|i| BatchOperationResult::submitted(i, tx_hash)).collect::<Vec<_>>()
+		);
+		let submitted = client.submitted_transactions();
+		assert_eq!(submitted.len(), 2);
+		let first_tx_inputs = spent_utxos(&submitted[0]);
+		assert!(first_tx_inputs.contains(&registration_utxo(0).utxo_id()));
+		assert!(first_tx_inputs.contains(&registration_utxo(1).utxo_id()));
+		assert!(!first_tx_inputs.contains(&registration_utxo(2).utxo_id()));
+		assert!(spent_utxos(&submitted[1]).contains(&registration_utxo(2).utxo_id()));
+	}
+
+	#[tokio::test]
+	async fn skips_candidates_already_registered_with_same_keys() {
+		let registrations: Vec<_> = (0..2).map(candidate_registration).collect();
+		let client = test_client(vec![
+			fee_utxo(),
+			registration_utxo(0),
+			registration_utxo(1),
+			existing_registration_utxo(&registrations[0]),
+		]);
+
+		let results = run_register_many(
+			GENESIS_UTXO,
+			&registrations,
+			NonZero::new(2).unwrap(),
+			&test_payment_key(),
+			&client,
+			&ImmediateSuccess,
+		)
+		.await
+		.unwrap();
+
+		assert_eq!(
+			results,
+			vec![
+				BatchOperationResult::skipped(0),
+				BatchOperationResult::submitted(1, McTxHash([3; 32])),
+			]
+		);
+		assert_eq!(client.submitted_transactions().len(), 1);
+	}
+
+	#[tokio::test]
+	async fn fails_for_registration_not_owned_by_payment_key() {
+		let mut registration = candidate_registration(0);
+		registration.own_pkh = payment_key().to_pub_key_hash();
+		let client = test_client(vec![fee_utxo(), registration_utxo(0)]);
+
+		let result = run_register_many(
+			GENESIS_UTXO,
+			&[registration],
+			NonZero::new(2).unwrap(),
+			&test_payment_key(),
+			&client,
+			&ImmediateSuccess,
+		)
+		.await;
+
+		assert!(result.is_err());
+		assert!(client.submitted_transactions().is_empty());
+	}
+}
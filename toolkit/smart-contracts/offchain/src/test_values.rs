@@ -9,6 +9,7 @@ use ogmios_client::{
 	query_ledger_state::{
 		PlutusCostModels, ProtocolParametersResponse, ReferenceScriptsCosts, ScriptExecutionPrices,
 	},
+	transactions::OgmiosBudget,
 	types::{NativeScript, OgmiosBytesSize, OgmiosScript, OgmiosTx, OgmiosUtxo, OgmiosValue},
 };
 use sidechain_domain::StakePoolPublicKey;
@@ -105,6 +106,7 @@ pub(crate) fn protocol_parameters() -> ProtocolParametersResponse {
 		max_collateral_inputs: 3,
 		collateral_percentage: 150,
 		min_fee_reference_scripts: ReferenceScriptsCosts { base: 15.0 },
+		max_execution_units_per_transaction: Some(OgmiosBudget::new(14000000, 10000000000)),
 	}
 }
 
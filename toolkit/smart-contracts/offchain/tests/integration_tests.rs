@@ -530,7 +530,7 @@ async fn await_ogmios(ogmios_port: u16) -> Result<OgmiosClients, String> {
 /// * addr_test1vzuasm5nqzh7n909f7wang7apjprpg29l2f9sk6shlt84rqep6nyc - has attached V-function script
 ///
 /// Its hash is 0xf8fbe7316561e57de9ecd1c86ee8f8b512a314ba86499ba9a584bfa8fe2edc8d
-async fn initial_transaction<T: Transactions + QueryUtxoByUtxoId>(
+async fn initial_transaction<T: Transactions + QueryNetwork + QueryUtxoByUtxoId>(
 	client: &T,
 ) -> Result<McTxHash, String> {
 	let signed_tx_bytes = hex!(
@@ -856,7 +856,8 @@ async fn run_register<T: QueryLedgerState + Transactions + QueryNetwork + QueryU
 			stake_ownership: AdaBasedStaking {
 				pub_key: EVE_PUBLIC_KEY,
 				signature: MainchainSignature([19u8; 64]),
-			},
+			}
+			.into(),
 			partner_chain_pub_key: SidechainPublicKey([20u8; 32].to_vec()),
 			partner_chain_signature: partnerchain_signature,
 			own_pkh: EVE_PUBLIC_KEY_HASH,
@@ -42,7 +42,7 @@ pub enum RegisterValidatorDatum {
 	/// Initial/legacy datum schema. If a datum doesn't contain a version, it is assumed to be V0
 	V0 {
 		/// Stake ownership information of registered candidate.
-		stake_ownership: AdaBasedStaking,
+		stake_ownership: StakeOwnership,
 		/// Sidechain public key of the candidate. See [SidechainPublicKey] for more details.
 		sidechain_pub_key: SidechainPublicKey,
 		/// Sidechain key signature of the registration message.
@@ -61,7 +61,7 @@ pub enum RegisterValidatorDatum {
 	/// V1 datum with support for generic keys
 	V1 {
 		/// Stake ownership information of registered candidate.
-		stake_ownership: AdaBasedStaking,
+		stake_ownership: StakeOwnership,
 		/// Sidechain public key of the candidate. See [SidechainPublicKey] for more details.
 		sidechain_pub_key: SidechainPublicKey,
 		/// Sidechain key signature of the registration message.
@@ -181,7 +181,7 @@ fn decode_v0_register_validator_datum(
 		.filter(|datum| datum.alternative().is_zero())
 		.filter(|datum| datum.data().len() >= 6)?
 		.data();
-	let stake_ownership = decode_ada_based_staking_datum(fields.get(0))?;
+	let stake_ownership = decode_stake_ownership_datum(fields.get(0))?;
 	let sidechain_pub_key = fields.get(1).as_bytes().map(SidechainPublicKey)?;
 	let sidechain_signature = fields.get(2).as_bytes().map(SidechainSignature)?;
 	let registration_utxo = decode_utxo_id_datum(fields.get(3))?;
@@ -210,7 +210,7 @@ fn decode_v1_register_validator_datum(
 		.filter(|datum| datum.data().len() == 5)?
 		.data();
 
-	let stake_ownership = decode_ada_based_staking_datum(fields.get(0))?;
+	let stake_ownership = decode_stake_ownership_datum(fields.get(0))?;
 	let sidechain_pub_key = fields.get(1).as_bytes().map(SidechainPublicKey)?;
 	let sidechain_signature = fields.get(2).as_bytes().map(SidechainSignature)?;
 	let registration_utxo = decode_utxo_id_datum(fields.get(3))?;
@@ -234,7 +234,7 @@ fn decode_legacy_register_validator_datum(datum: &PlutusData) -> Option<Register
 		.filter(|datum| datum.alternative().is_zero())
 		.filter(|datum| datum.data().len() >= 7)?
 		.data();
-	let stake_ownership = decode_ada_based_staking_datum(fields.get(0))?;
+	let stake_ownership = decode_stake_ownership_datum(fields.get(0))?;
 	let sidechain_pub_key = fields.get(1).as_bytes().map(SidechainPublicKey)?;
 	let sidechain_signature = fields.get(2).as_bytes().map(SidechainSignature)?;
 	let registration_utxo = decode_utxo_id_datum(fields.get(3))?;
@@ -252,15 +252,24 @@ fn decode_legacy_register_validator_datum(datum: &PlutusData) -> Option<Register
 	})
 }
 
-fn decode_ada_based_staking_datum(datum: PlutusData) -> Option<AdaBasedStaking> {
-	let fields = datum
-		.as_constr_plutus_data()
-		.filter(|datum| datum.alternative().is_zero())
-		.filter(|datum| datum.data().len() >= 2)?
-		.data();
-	let pub_key = TryFrom::try_from(fields.get(0).as_bytes()?).ok()?;
-	let signature = MainchainSignature(fields.get(1).as_bytes()?.try_into().ok()?);
-	Some(AdaBasedStaking { pub_key, signature })
+/// Decodes Plutus type StakeOwnership:
+/// ```text
+/// data StakeOwnership
+///   = AdaBasedStaking PubKey Signature
+///   | TokenBasedStaking
+/// ```
+fn decode_stake_ownership_datum(datum: PlutusData) -> Option<StakeOwnership> {
+	let constr = datum.as_constr_plutus_data()?;
+	let fields = constr.data();
+	match u64::from(constr.alternative()) {
+		0 if fields.len() >= 2 => {
+			let pub_key = TryFrom::try_from(fields.get(0).as_bytes()?).ok()?;
+			let signature = MainchainSignature(fields.get(1).as_bytes()?.try_into().ok()?);
+			Some(StakeOwnership::AdaBased(AdaBasedStaking { pub_key, signature }))
+		},
+		1 if fields.len() == 0 => Some(StakeOwnership::TokenBased),
+		_ => None,
+	}
 }
 fn decode_utxo_id_datum(datum: PlutusData) -> Option<UtxoId> {
 	let fields = datum
@@ -336,11 +345,17 @@ impl From<RegisterValidatorDatum> for PlutusData {
 	}
 }
 
-fn stake_ownership_to_plutus_data(v: AdaBasedStaking) -> PlutusData {
+fn stake_ownership_to_plutus_data(v: StakeOwnership) -> PlutusData {
 	let mut fields = PlutusList::new();
-	fields.add(&PlutusData::new_bytes(v.pub_key.0.to_vec()));
-	fields.add(&PlutusData::new_bytes(v.signature.0.to_vec()));
-	PlutusData::new_constr_plutus_data(&ConstrPlutusData::new(&BigNum::zero(), &fields))
+	let constructor = match v {
+		StakeOwnership::AdaBased(staking) => {
+			fields.add(&PlutusData::new_bytes(staking.pub_key.0.to_vec()));
+			fields.add(&PlutusData::new_bytes(staking.signature.0.to_vec()));
+			BigNum::zero()
+		},
+		StakeOwnership::TokenBased => BigNum::one(),
+	};
+	PlutusData::new_constr_plutus_data(&ConstrPlutusData::new(&constructor, &fields))
 }
 
 fn utxo_id_to_plutus_data(v: UtxoId) -> PlutusData {
@@ -365,10 +380,10 @@ mod tests {
 
 	fn test_datum_v0() -> RegisterValidatorDatum {
 		RegisterValidatorDatum::V0 {
-			stake_ownership: AdaBasedStaking {
+			stake_ownership: StakeOwnership::AdaBased(AdaBasedStaking {
 				pub_key: StakePoolPublicKey(hex!("bfbee74ab533f40979101057f96de62e95233f2a5216eb16b54106f09fd7350d")),
 				signature: MainchainSignature(hex!("28d1c3b7df297a60d24a3f88bc53d7029a8af35e8dd876764fd9e7a24203a3482a98263cc8ba2ddc7dc8e7faea31c2e7bad1f00e28c43bc863503e3172dc6b0a").into()),
-			},
+			}),
 			sidechain_pub_key: SidechainPublicKey(hex!("02fe8d1eb1bcb3432b1db5833ff5f2226d9cb5e65cee430558c18ed3a3c86ce1af").into()),
 			sidechain_signature: SidechainSignature(hex!("f8ec6c7f935d387aaa1693b3bf338cbb8f53013da8a5a234f9c488bacac01af259297e69aee0df27f553c0a1164df827d016125c16af93c99be2c19f36d2f66e").into()),
 			registration_utxo: UtxoId {
@@ -383,10 +398,10 @@ mod tests {
 
 	fn test_datum_v1() -> RegisterValidatorDatum {
 		RegisterValidatorDatum::V1 {
-			stake_ownership: AdaBasedStaking {
+			stake_ownership: StakeOwnership::AdaBased(AdaBasedStaking {
 				pub_key: StakePoolPublicKey(hex!("bfbee74ab533f40979101057f96de62e95233f2a5216eb16b54106f09fd7350d")),
 				signature: MainchainSignature(hex!("28d1c3b7df297a60d24a3f88bc53d7029a8af35e8dd876764fd9e7a24203a3482a98263cc8ba2ddc7dc8e7faea31c2e7bad1f00e28c43bc863503e3172dc6b0a").into()),
-			},
+			}),
 			sidechain_pub_key: SidechainPublicKey(hex!("02fe8d1eb1bcb3432b1db5833ff5f2226d9cb5e65cee430558c18ed3a3c86ce1af").into()),
 			sidechain_signature: SidechainSignature(hex!("f8ec6c7f935d387aaa1693b3bf338cbb8f53013da8a5a234f9c488bacac01af259297e69aee0df27f553c0a1164df827d016125c16af93c99be2c19f36d2f66e").into()),
 			registration_utxo: UtxoId {
@@ -528,4 +543,61 @@ mod tests {
 		let plutus_data: PlutusData = test_datum_v1().into();
 		assert_eq!(plutus_data_to_json(plutus_data), test_versioned_datum_v1_json())
 	}
+
+	fn test_datum_v1_token_based() -> RegisterValidatorDatum {
+		let RegisterValidatorDatum::V1 {
+			sidechain_pub_key,
+			sidechain_signature,
+			registration_utxo,
+			own_pkh,
+			keys,
+			..
+		} = test_datum_v1()
+		else {
+			unreachable!()
+		};
+		RegisterValidatorDatum::V1 {
+			stake_ownership: StakeOwnership::TokenBased,
+			sidechain_pub_key,
+			sidechain_signature,
+			registration_utxo,
+			own_pkh,
+			keys,
+		}
+	}
+
+	fn test_versioned_datum_v1_token_based_json() -> serde_json::Value {
+		let mut json = test_versioned_datum_v1_json();
+		json["list"][1]["fields"][0] = serde_json::json!({ "constructor": 1, "fields": [] });
+		json
+	}
+
+	#[test]
+	fn valid_v1_token_based_registration_from_plutus_data() {
+		let plutus_data = json_to_plutus_data(test_versioned_datum_v1_token_based_json());
+		assert_eq!(
+			RegisterValidatorDatum::try_from(plutus_data).unwrap(),
+			test_datum_v1_token_based()
+		)
+	}
+
+	#[test]
+	fn valid_v1_token_based_registration_to_plutus_data() {
+		let plutus_data: PlutusData = test_datum_v1_token_based().into();
+		assert_eq!(plutus_data_to_json(plutus_data), test_versioned_datum_v1_token_based_json())
+	}
+
+	#[test]
+	fn registration_with_unknown_stake_ownership_constructor_is_invalid() {
+		let mut json = test_versioned_datum_v1_json();
+		json["list"][1]["fields"][0]["constructor"] = 2.into();
+		assert!(RegisterValidatorDatum::try_from(json_to_plutus_data(json)).is_err())
+	}
+
+	#[test]
+	fn token_based_stake_ownership_with_fields_is_invalid() {
+		let mut json = test_versioned_datum_v1_json();
+		json["list"][1]["fields"][0]["constructor"] = 1.into();
+		assert!(RegisterValidatorDatum::try_from(json_to_plutus_data(json)).is_err())
+	}
 }
@@ -0,0 +1,778 @@
+//! Implementation of the ledger query and transaction traits over a Blockfrost-compatible HTTP API.
+//!
+//! Works with Blockfrost itself as well as with compatible servers, like Dolos MiniBF.
+//! Allows using the offchain code without access to an Ogmios instance.
+//!
+//! More information about Blockfrost API can be found at <https://docs.blockfrost.io/>
+
+use crate::{
+	OgmiosClientError,
+	mempool::{QueryTransactionStatus, TransactionStatus},
+	query_ledger_state::{
+		EpochBoundary, EpochParameters, EraSummary, OgmiosTip, PlutusCostModels,
+		ProtocolParametersResponse, QueryLedgerState, QueryUtxoByUtxoId, ReferenceScriptsCosts,
+		ScriptExecutionPrices,
+	},
+	query_network::{QueryNetwork, ShelleyGenesisConfigurationResponse},
+	transactions::{
+		OgmiosBudget, OgmiosEvaluateTransactionResponse, SubmitTransactionResponse, Transactions,
+	},
+	types::{
+		Asset, Datum, DatumHash, OgmiosBytesSize, OgmiosScript, OgmiosTx, OgmiosUtxo, OgmiosValue,
+		SlotLength, TimeSeconds,
+	},
+};
+use serde::{Deserialize, de::DeserializeOwned};
+use sidechain_domain::{McTxHash, NetworkType, UtxoId};
+use std::{collections::HashMap, str::FromStr, time::Duration};
+use ureq::{Agent, http::Response};
+
+/// Network magic of the Cardano mainnet
+const MAINNET_NETWORK_MAGIC: u32 = 764824073;
+
+/// Maximum number of items returned by a single page of a paginated endpoint.
+const PAGE_SIZE: usize = 100;
+
+/// Client of a Blockfrost-compatible HTTP API.
+#[derive(Clone)]
+pub struct BlockfrostClient {
+	agent: Agent,
+	addr: String,
+	project_id: Option<String>,
+}
+
+impl BlockfrostClient {
+	/// Creates a new client for the Blockfrost-compatible API available at `addr`.
+	///
+	/// `project_id` is sent in the `project_id` header and is required by the hosted Blockfrost API.
+	/// It can be omitted for self-hosted servers, like Dolos MiniBF.
+	pub fn new(addr: &str, timeout: Duration, project_id: Option<String>) -> Self {
+		let agent = Agent::config_builder()
+			.timeout_per_call(Some(timeout))
+			.http_status_as_error(false)
+			.build()
+			.into();
+		Self { agent, addr: addr.strip_suffix("/").unwrap_or(addr).to_string(), project_id }
+	}
+
+	fn url(&self, path: &str) -> String {
+		format!("{}/{}", self.addr, path)
+	}
+
+	fn with_auth<B>(&self, req: ureq::RequestBuilder<B>) -> ureq::RequestBuilder<B> {
+		match &self.project_id {
+			Some(project_id) => req.header("project_id", project_id),
+			None => req,
+		}
+	}
+
+	/// Performs a GET request. Returns `None` when the server responds with 404 Not Found.
+	async fn get<T: DeserializeOwned + std::fmt::Debug>(
+		&self,
+		path: &str,
+	) -> Result<Option<T>, OgmiosClientError> {
+		let url = self.url(path);
+		log::debug!("Blockfrost request: GET {url}");
+		let response = self
+			.with_auth(self.agent.get(&url))
+			.call()
+			.map_err(|e| OgmiosClientError::RequestError(e.to_string()))?;
+		let result = parse_optional_response(response);
+		log::debug!("Blockfrost response: {result:?}");
+		result
+	}
+
+	/// Performs a GET request with pagination, collecting all pages.
+	/// Returns empty vector when the server responds with 404 Not Found.
+	async fn get_all_pages<T: DeserializeOwned + std::fmt::Debug>(
+		&self,
+		path: &str,
+	) -> Result<Vec<T>, OgmiosClientError> {
+		let mut result = Vec::new();
+		for page in 1.. {
+			let items: Vec<T> = self
+				.get(&format!("{path}?count={PAGE_SIZE}&page={page}"))
+				.await?
+				.unwrap_or_default();
+			let is_last_page = items.len() < PAGE_SIZE;
+			result.extend(items);
+			if is_last_page {
+				break;
+			}
+		}
+		Ok(result)
+	}
+
+	/// Performs a POST request with CBOR content type.
+	async fn post_cbor<T: DeserializeOwned + std::fmt::Debug>(
+		&self,
+		path: &str,
+		body: Vec<u8>,
+	) -> Result<T, OgmiosClientError> {
+		let url = self.url(path);
+		log::debug!("Blockfrost request: POST {url}");
+		let response = self
+			.with_auth(self.agent.post(&url))
+			.header("Content-Type", "application/cbor")
+			.send(body)
+			.map_err(|e| OgmiosClientError::RequestError(e.to_string()))?;
+		let result = parse_optional_response(response).and_then(|opt| {
+			opt.ok_or_else(|| OgmiosClientError::RequestError(format!("'{url}' not found")))
+		});
+		log::debug!("Blockfrost response: {result:?}");
+		result
+	}
+
+	/// Performs a POST request with JSON body.
+	async fn post_json<T: DeserializeOwned + std::fmt::Debug>(
+		&self,
+		path: &str,
+		body: &serde_json::Value,
+	) -> Result<T, OgmiosClientError> {
+		let url = self.url(path);
+		log::debug!("Blockfrost request: POST {url}");
+		let response = self
+			.with_auth(self.agent.post(&url))
+			.send_json(body)
+			.map_err(|e| OgmiosClientError::RequestError(e.to_string()))?;
+		let result = parse_optional_response(response).and_then(|opt| {
+			opt.ok_or_else(|| OgmiosClientError::RequestError(format!("'{url}' not found")))
+		});
+		log::debug!("Blockfrost response: {result:?}");
+		result
+	}
+
+	async fn get_script(&self, script_hash: &str) -> Result<OgmiosScript, OgmiosClientError> {
+		let script: BlockfrostScript = self
+			.get(&format!("scripts/{script_hash}"))
+			.await?
+			.ok_or_else(|| not_found(&format!("script {script_hash}")))?;
+		let cbor: BlockfrostScriptCbor = self
+			.get(&format!("scripts/{script_hash}/cbor"))
+			.await?
+			.ok_or_else(|| not_found(&format!("cbor of script {script_hash}")))?;
+		let language = match script.script_type.as_str() {
+			"plutusV1" => "plutus:v1",
+			"plutusV2" => "plutus:v2",
+			"plutusV3" => "plutus:v3",
+			_ => "native",
+		};
+		let cbor = hex::decode(cbor.cbor.unwrap_or_default())
+			.map_err(|e| OgmiosClientError::ResponseError(e.to_string()))?;
+		Ok(OgmiosScript { language: language.to_string(), cbor, json: None })
+	}
+
+	async fn to_ogmios_utxo(
+		&self,
+		tx_hash: &str,
+		address: String,
+		output: BlockfrostOutput,
+	) -> Result<OgmiosUtxo, OgmiosClientError> {
+		let script = match &output.reference_script_hash {
+			Some(script_hash) => Some(self.get_script(script_hash).await?),
+			None => None,
+		};
+		Ok(OgmiosUtxo {
+			transaction: OgmiosTx { id: parse_hex_array(tx_hash)? },
+			index: output.output_index,
+			address,
+			value: to_ogmios_value(&output.amount)?,
+			datum: output.inline_datum.as_deref().map(parse_hex).transpose()?.map(Datum::from),
+			datum_hash: output
+				.data_hash
+				.as_deref()
+				.map(parse_hex_array)
+				.transpose()?
+				.map(DatumHash::from),
+			script,
+		})
+	}
+}
+
+impl QueryLedgerState for BlockfrostClient {
+	async fn get_tip(&self) -> Result<OgmiosTip, OgmiosClientError> {
+		let block: BlockfrostBlock =
+			self.get("blocks/latest").await?.ok_or_else(|| not_found("latest block"))?;
+		Ok(OgmiosTip { slot: block.slot.ok_or_else(|| not_found("slot of latest block"))? })
+	}
+
+	async fn era_summaries(&self) -> Result<Vec<EraSummary>, OgmiosClientError> {
+		let eras: Vec<BlockfrostEra> =
+			self.get("network/eras").await?.ok_or_else(|| not_found("network eras"))?;
+		Ok(eras.into_iter().map(EraSummary::from).collect())
+	}
+
+	async fn query_utxos(
+		&self,
+		addresses: &[String],
+	) -> Result<Vec<OgmiosUtxo>, OgmiosClientError> {
+		let mut utxos = Vec::new();
+		for address in addresses {
+			let address_utxos: Vec<BlockfrostAddressUtxo> =
+				self.get_all_pages(&format!("addresses/{address}/utxos")).await?;
+			for utxo in address_utxos {
+				utxos.push(
+					self.to_ogmios_utxo(&utxo.tx_hash, utxo.address.clone(), utxo.output).await?,
+				);
+			}
+		}
+		Ok(utxos)
+	}
+
+	async fn query_protocol_parameters(
+		&self,
+	) -> Result<ProtocolParametersResponse, OgmiosClientError> {
+		let params: BlockfrostProtocolParameters = self
+			.get("epochs/latest/parameters")
+			.await?
+			.ok_or_else(|| not_found("protocol parameters"))?;
+		params.try_into()
+	}
+}
+
+impl QueryUtxoByUtxoId for BlockfrostClient {
+	async fn query_utxo_by_id(
+		&self,
+		utxo: UtxoId,
+	) -> Result<Option<OgmiosUtxo>, OgmiosClientError> {
+		let tx_hash = hex::encode(utxo.tx_hash.0);
+		let Some(tx_utxos) = self.get::<BlockfrostTxUtxos>(&format!("txs/{tx_hash}/utxos")).await?
+		else {
+			return Ok(None);
+		};
+		let output = tx_utxos
+			.outputs
+			.into_iter()
+			.find(|o| o.output.output_index == utxo.index.0 && !o.collateral);
+		match output {
+			// Ogmios returns only unspent outputs, so spent ones are filtered out here as well
+			Some(output) if output.consumed_by_tx.is_none() => {
+				Ok(Some(self.to_ogmios_utxo(&tx_hash, output.address, output.output).await?))
+			},
+			_ => Ok(None),
+		}
+	}
+}
+
+impl QueryNetwork for BlockfrostClient {
+	async fn shelley_genesis_configuration(
+		&self,
+	) -> Result<ShelleyGenesisConfigurationResponse, OgmiosClientError> {
+		let genesis: BlockfrostGenesis =
+			self.get("genesis").await?.ok_or_else(|| not_found("genesis"))?;
+		genesis.try_into()
+	}
+
+	async fn get_block_height(&self) -> Result<u64, OgmiosClientError> {
+		let block: BlockfrostBlock =
+			self.get("blocks/latest").await?.ok_or_else(|| not_found("latest block"))?;
+		block.height.ok_or_else(|| not_found("height of latest block"))
+	}
+
+	async fn get_transaction_block_height(
+		&self,
+		tx_hash: McTxHash,
+	) -> Result<Option<u64>, OgmiosClientError> {
+		let tx: Option<BlockfrostTx> = self.get(&format!("txs/{}", hex::encode(tx_hash.0))).await?;
+		Ok(tx.map(|tx| tx.block_height))
+	}
+}
+
+impl Transactions for BlockfrostClient {
+	async fn evaluate_transaction(
+		&self,
+		tx_bytes: &[u8],
+	) -> Result<Vec<OgmiosEvaluateTransactionResponse>, OgmiosClientError> {
+		// Version 6 returns the result in the same format as Ogmios v6 `evaluateTransaction`
+		let response: BlockfrostEvaluateResponse = self
+			.post_cbor("utils/txs/evaluate?version=6", hex::encode(tx_bytes).into_bytes())
+			.await?;
+		response.into_result()
+	}
+
+	async fn evaluate_transaction_with_additional_utxos(
+		&self,
+		tx_bytes: &[u8],
+		additional_utxos: &[OgmiosUtxo],
+	) -> Result<Vec<OgmiosEvaluateTransactionResponse>, OgmiosClientError> {
+		if additional_utxos.is_empty() {
+			return self.evaluate_transaction(tx_bytes).await;
+		}
+		let additional_utxo_set = (additional_utxos.iter())
+			.map(to_additional_utxo_json)
+			.collect::<Result<Vec<_>, _>>()?;
+		let body = serde_json::json!({
+			"cbor": hex::encode(tx_bytes),
+			"additionalUtxoSet": additional_utxo_set,
+		});
+		let response: BlockfrostEvaluateResponse =
+			self.post_json("utils/txs/evaluate/utxos?version=6", &body).await?;
+		response.into_result()
+	}
+
+	async fn submit_transaction(
+		&self,
+		tx_bytes: &[u8],
+	) -> Result<SubmitTransactionResponse, OgmiosClientError> {
+		let tx_hash: String = self.post_cbor("tx/submit", tx_bytes.to_vec()).await?;
+		Ok(SubmitTransactionResponse { transaction: OgmiosTx { id: parse_hex_array(&tx_hash)? } })
+	}
+}
+
+impl QueryTransactionStatus for BlockfrostClient {
+	/// Blockfrost mempool contains only transactions submitted through the same Blockfrost instance.
+	async fn transaction_status(
+		&self,
+		tx_hash: McTxHash,
+	) -> Result<TransactionStatus, OgmiosClientError> {
+		if let Some(block_height) = self.get_transaction_block_height(tx_hash).await? {
+			let tip_height = self.get_block_height().await?;
+			let depth = tip_height.saturating_sub(block_height) + 1;
+			return Ok(TransactionStatus::Confirmed { depth });
+		}
+		let tx_hash = hex::encode(tx_hash.0);
+		let mempool_tx: Option<serde_json::Value> = self.get(&format!("mempool/{tx_hash}")).await?;
+		Ok(match mempool_tx {
+			Some(_) => TransactionStatus::InMempool,
+			None => TransactionStatus::Unknown,
+		})
+	}
+}
+
+fn parse_optional_response<T: DeserializeOwned>(
+	mut response: Response<ureq::Body>,
+) -> Result<Option<T>, OgmiosClientError> {
+	let status = response.status();
+	if status.as_u16() == 404 {
+		return Ok(None);
+	}
+	if !status.is_success() {
+		let body = response.body_mut().read_to_string().unwrap_or_default();
+		return Err(OgmiosClientError::RequestError(format!("HTTP status {status}: {body}")));
+	}
+	response
+		.body_mut()
+		.read_json()
+		.map(Some)
+		.map_err(|e| OgmiosClientError::ResponseError(e.to_string()))
+}
+
+fn not_found(what: &str) -> OgmiosClientError {
+	OgmiosClientError::ResponseError(format!("{what} not found"))
+}
+
+fn parse_hex(s: &str) -> Result<Vec<u8>, OgmiosClientError> {
+	hex::decode(s).map_err(|e| OgmiosClientError::ResponseError(format!("invalid hex '{s}': {e}")))
+}
+
+fn parse_hex_array<const N: usize>(s: &str) -> Result<[u8; N], OgmiosClientError> {
+	parse_hex(s)?
+		.try_into()
+		.map_err(|_| OgmiosClientError::ResponseError(format!("expected {N} bytes in '{s}'")))
+}
+
+fn parse_quantity(s: &str) -> Result<u64, OgmiosClientError> {
+	u64::from_str(s)
+		.map_err(|e| OgmiosClientError::ResponseError(format!("invalid quantity '{s}': {e}")))
+}
+
+/// Converts Blockfrost amounts, where `unit` is either `lovelace` or policy id concatenated with asset name.
+fn to_ogmios_value(amounts: &[BlockfrostAmount]) -> Result<OgmiosValue, OgmiosClientError> {
+	let mut lovelace = 0u64;
+	let mut native_tokens: HashMap<[u8; 28], Vec<Asset>> = HashMap::new();
+	for BlockfrostAmount { unit, quantity } in amounts {
+		let amount = parse_quantity(quantity)?;
+		if unit == "lovelace" {
+			lovelace = amount;
+		} else {
+			let unit = parse_hex(unit)?;
+			if unit.len() < 28 {
+				return Err(OgmiosClientError::ResponseError(format!(
+					"invalid asset unit '{}'",
+					hex::encode(&unit)
+				)));
+			}
+			let (policy_id, name) = unit.split_at(28);
+			let policy_id: [u8; 28] = policy_id.try_into().expect("split at 28 bytes");
+			native_tokens
+				.entry(policy_id)
+				.or_default()
+				.push(Asset { name: name.to_vec(), amount });
+		}
+	}
+	Ok(OgmiosValue { lovelace, native_tokens })
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct BlockfrostAmount {
+	unit: String,
+	quantity: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct BlockfrostOutput {
+	output_index: u16,
+	amount: Vec<BlockfrostAmount>,
+	data_hash: Option<String>,
+	inline_datum: Option<String>,
+	reference_script_hash: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct BlockfrostAddressUtxo {
+	address: String,
+	tx_hash: String,
+	#[serde(flatten)]
+	output: BlockfrostOutput,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct BlockfrostTxOutput {
+	address: String,
+	#[serde(default)]
+	collateral: bool,
+	consumed_by_tx: Option<String>,
+	#[serde(flatten)]
+	output: BlockfrostOutput,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct BlockfrostTxUtxos {
+	outputs: Vec<BlockfrostTxOutput>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct BlockfrostScript {
+	#[serde(rename = "type")]
+	script_type: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct BlockfrostScriptCbor {
+	cbor: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct BlockfrostTx {
+	block_height: u64,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct BlockfrostBlock {
+	slot: Option<u64>,
+	height: Option<u64>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct BlockfrostEraBoundary {
+	time: f64,
+	slot: u64,
+	epoch: u32,
+}
+
+impl From<BlockfrostEraBoundary> for EpochBoundary {
+	fn from(b: BlockfrostEraBoundary) -> Self {
+		Self { time: TimeSeconds { seconds: b.time as u64 }, slot: b.slot, epoch: b.epoch }
+	}
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct BlockfrostEraParameters {
+	epoch_length: u32,
+	/// Slot length in seconds
+	slot_length: f64,
+	safe_zone: u32,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct BlockfrostEra {
+	start: BlockfrostEraBoundary,
+	end: BlockfrostEraBoundary,
+	parameters: BlockfrostEraParameters,
+}
+
+impl From<BlockfrostEra> for EraSummary {
+	fn from(era: BlockfrostEra) -> Self {
+		Self {
+			start: era.start.into(),
+			end: era.end.into(),
+			parameters: EpochParameters {
+				epoch_length: era.parameters.epoch_length,
+				slot_length: SlotLength {
+					milliseconds: (era.parameters.slot_length * 1000.0).round() as u32,
+				},
+				safe_zone: era.parameters.safe_zone,
+			},
+		}
+	}
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct BlockfrostCostModels {
+	#[serde(rename = "PlutusV1", default)]
+	plutus_v1: Vec<i128>,
+	#[serde(rename = "PlutusV2", default)]
+	plutus_v2: Vec<i128>,
+	#[serde(rename = "PlutusV3", default)]
+	plutus_v3: Vec<i128>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct BlockfrostProtocolParameters {
+	min_fee_a: u32,
+	min_fee_b: u64,
+	key_deposit: String,
+	pool_deposit: String,
+	max_val_size: Option<String>,
+	max_tx_size: u32,
+	coins_per_utxo_size: Option<String>,
+	price_mem: Option<serde_json::Number>,
+	price_step: Option<serde_json::Number>,
+	cost_models_raw: Option<BlockfrostCostModels>,
+	max_collateral_inputs: Option<u32>,
+	collateral_percent: Option<u32>,
+	min_fee_ref_script_cost_per_byte: Option<serde_json::Number>,
+	max_tx_ex_mem: Option<String>,
+	max_tx_ex_steps: Option<String>,
+}
+
+impl TryFrom<BlockfrostProtocolParameters> for ProtocolParametersResponse {
+	type Error = OgmiosClientError;
+
+	fn try_from(p: BlockfrostProtocolParameters) -> Result<Self, Self::Error> {
+		let missing = |field: &str| not_found(&format!("protocol parameter '{field}'"));
+		let parse_ratio = |n: Option<serde_json::Number>, field: &str| {
+			let n = n.ok_or_else(|| missing(field))?;
+			decimal_to_ratio(&n.to_string())
+				.ok_or_else(|| OgmiosClientError::ResponseError(format!("invalid '{field}': {n}")))
+		};
+		let cost_models = p.cost_models_raw.ok_or_else(|| missing("cost_models_raw"))?;
+		let max_value_size = p.max_val_size.as_deref().ok_or_else(|| missing("max_val_size"))?;
+		let min_fee_reference_scripts = match p.min_fee_ref_script_cost_per_byte {
+			Some(n) => ReferenceScriptsCosts {
+				base: f64::from_str(&n.to_string())
+					.map_err(|e| OgmiosClientError::ResponseError(e.to_string()))?,
+			},
+			None => ReferenceScriptsCosts::default(),
+		};
+		let max_execution_units_per_transaction = match (p.max_tx_ex_mem, p.max_tx_ex_steps) {
+			(Some(memory), Some(cpu)) => {
+				Some(OgmiosBudget::new(parse_quantity(&memory)?, parse_quantity(&cpu)?))
+			},
+			_ => None,
+		};
+		Ok(Self {
+			min_fee_coefficient: p.min_fee_a,
+			min_fee_constant: OgmiosValue::new_lovelace(p.min_fee_b),
+			stake_pool_deposit: OgmiosValue::new_lovelace(parse_quantity(&p.pool_deposit)?),
+			stake_credential_deposit: OgmiosValue::new_lovelace(parse_quantity(&p.key_deposit)?),
+			max_value_size: OgmiosBytesSize { bytes: parse_quantity(max_value_size)? as u32 },
+			max_transaction_size: OgmiosBytesSize { bytes: p.max_tx_size },
+			min_utxo_deposit_coefficient: parse_quantity(
+				p.coins_per_utxo_size.as_deref().ok_or_else(|| missing("coins_per_utxo_size"))?,
+			)?,
+			script_execution_prices: ScriptExecutionPrices {
+				memory: parse_ratio(p.price_mem, "price_mem")?,
+				cpu: parse_ratio(p.price_step, "price_step")?,
+			},
+			plutus_cost_models: PlutusCostModels {
+				plutus_v1: cost_models.plutus_v1,
+				plutus_v2: cost_models.plutus_v2,
+				plutus_v3: cost_models.plutus_v3,
+			},
+			max_collateral_inputs: p
+				.max_collateral_inputs
+				.ok_or_else(|| missing("max_collateral_inputs"))?,
+			collateral_percentage: p
+				.collateral_percent
+				.ok_or_else(|| missing("collateral_percent"))?,
+			min_fee_reference_scripts,
+			max_execution_units_per_transaction,
+		})
+	}
+}
+
+/// Converts a decimal number, like `0.0577` or `7.21e-5`, to a ratio.
+fn decimal_to_ratio(decimal: &str) -> Option<fraction::Ratio<u64>> {
+	let (mantissa, exponent) = match decimal.split_once(['e', 'E']) {
+		Some((mantissa, exponent)) => (mantissa, exponent.parse::<i32>().ok()?),
+		None => (decimal, 0),
+	};
+	let (integer, fractional) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+	let numerator = format!("{integer}{fractional}").parse::<u64>().ok()?;
+	let scale = fractional.len() as i32 - exponent;
+	if scale >= 0 {
+		Some(fraction::Ratio::new(numerator, 10u64.checked_pow(scale as u32)?))
+	} else {
+		Some(fraction::Ratio::from_integer(
+			numerator.checked_mul(10u64.checked_pow((-scale) as u32)?)?,
+		))
+	}
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct BlockfrostGenesis {
+	active_slots_coefficient: serde_json::Number,
+	network_magic: u32,
+	epoch_length: u32,
+	/// Unix timestamp in seconds
+	system_start: i64,
+	/// Slot length in seconds
+	slot_length: u32,
+	security_param: u32,
+}
+
+impl TryFrom<BlockfrostGenesis> for ShelleyGenesisConfigurationResponse {
+	type Error = OgmiosClientError;
+
+	fn try_from(g: BlockfrostGenesis) -> Result<Self, Self::Error> {
+		Ok(Self {
+			network_magic: g.network_magic,
+			network: if g.network_magic == MAINNET_NETWORK_MAGIC {
+				NetworkType::Mainnet
+			} else {
+				NetworkType::Testnet
+			},
+			security_parameter: g.security_param,
+			active_slots_coefficient: fraction::Decimal::from_str(
+				&g.active_slots_coefficient.to_string(),
+			)
+			.map_err(|e| OgmiosClientError::ResponseError(e.to_string()))?,
+			epoch_length: g.epoch_length,
+			slot_length: SlotLength { milliseconds: g.slot_length * 1000 },
+			start_time: time::OffsetDateTime::from_unix_timestamp(g.system_start)
+				.map_err(|e| OgmiosClientError::ResponseError(e.to_string()))?,
+		})
+	}
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct BlockfrostEvaluateResponse {
+	result: Option<Vec<OgmiosEvaluateTransactionResponse>>,
+	error: Option<serde_json::Value>,
+}
+
+impl BlockfrostEvaluateResponse {
+	fn into_result(self) -> Result<Vec<OgmiosEvaluateTransactionResponse>, OgmiosClientError> {
+		match self {
+			Self { result: Some(result), .. } => Ok(result),
+			Self { error, .. } => Err(OgmiosClientError::RequestError(
+				error.map(|e| e.to_string()).unwrap_or_else(|| "unknown error".to_string()),
+			)),
+		}
+	}
+}
+
+/// Converts UTXO to the `[TxIn, TxOut]` pair of `additionalUtxoSet` of `/utils/txs/evaluate/utxos`,
+/// which uses the Ogmios v5 format.
+fn to_additional_utxo_json(utxo: &OgmiosUtxo) -> Result<serde_json::Value, OgmiosClientError> {
+	let assets: serde_json::Map<String, serde_json::Value> = (utxo.value.native_tokens.iter())
+		.flat_map(|(policy_id, assets)| {
+			assets.iter().map(move |asset| {
+				let unit = format!("{}.{}", hex::encode(policy_id), hex::encode(&asset.name));
+				(unit, serde_json::json!(asset.amount))
+			})
+		})
+		.collect();
+	let mut tx_out = serde_json::json!({
+		"address": utxo.address,
+		"value": { "coins": utxo.value.lovelace, "assets": assets },
+	});
+	if let Some(datum) = &utxo.datum {
+		tx_out["datum"] = serde_json::json!(hex::encode(&datum.bytes));
+	}
+	if let Some(datum_hash) = &utxo.datum_hash {
+		tx_out["datumHash"] = serde_json::json!(hex::encode(datum_hash.bytes));
+	}
+	if let Some(script) = &utxo.script {
+		if script.language == "native" {
+			return Err(OgmiosClientError::ParametersError(format!(
+				"native script in additional UTXO {utxo} is not supported"
+			)));
+		}
+		tx_out["script"] =
+			serde_json::json!({ script.language.clone(): hex::encode(&script.cbor) });
+	}
+	Ok(serde_json::json!([
+		{ "txId": hex::encode(utxo.transaction.id), "index": utxo.index },
+		tx_out,
+	]))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use fraction::Ratio;
+	use hex_literal::hex;
+
+	#[test]
+	fn decimal_to_ratio_test() {
+		assert_eq!(decimal_to_ratio("0.0577"), Some(Ratio::new(577, 10000)));
+		assert_eq!(decimal_to_ratio("7.21e-5"), Some(Ratio::new(721, 10000000)));
+		assert_eq!(decimal_to_ratio("1.5"), Some(Ratio::new(3, 2)));
+		assert_eq!(decimal_to_ratio("0.0"), Some(Ratio::from_integer(0)));
+		assert_eq!(decimal_to_ratio("44"), Some(Ratio::from_integer(44)));
+		assert_eq!(decimal_to_ratio("4.4E1"), Some(Ratio::from_integer(44)));
+		assert_eq!(decimal_to_ratio("abc"), None);
+	}
+
+	#[test]
+	fn to_ogmios_value_test() {
+		let amounts = vec![
+			BlockfrostAmount { unit: "lovelace".to_string(), quantity: "1500000".to_string() },
+			BlockfrostAmount {
+				unit: "e0d4479b3dbb53b1aecd48f7ef524a9cf166585923d91d9c72ed02cb61626364"
+					.to_string(),
+				quantity: "7".to_string(),
+			},
+		];
+		let value = to_ogmios_value(&amounts).unwrap();
+		assert_eq!(value.lovelace, 1500000);
+		assert_eq!(
+			value
+				.native_tokens
+				.get(&hex!("e0d4479b3dbb53b1aecd48f7ef524a9cf166585923d91d9c72ed02cb")),
+			Some(&vec![Asset { name: b"abcd".to_vec(), amount: 7 }])
+		);
+	}
+
+	#[test]
+	fn to_additional_utxo_json_test() {
+		let utxo = OgmiosUtxo {
+			transaction: OgmiosTx { id: [1; 32] },
+			index: 2,
+			address: "addr_test1vqezxrh24ts0775hulcg3ejcwj7hns8792vnn8met6z9gwsxt87zy".to_string(),
+			value: OgmiosValue {
+				lovelace: 1500000,
+				native_tokens: HashMap::from([(
+					hex!("e0d4479b3dbb53b1aecd48f7ef524a9cf166585923d91d9c72ed02cb"),
+					vec![Asset { name: b"abcd".to_vec(), amount: 7 }],
+				)]),
+			},
+			datum: Some(Datum { bytes: hex!("d87980").to_vec() }),
+			datum_hash: None,
+			script: Some(OgmiosScript {
+				language: "plutus:v2".to_string(),
+				cbor: hex!("4e4d01000033222220051200120011").to_vec(),
+				json: None,
+			}),
+		};
+		assert_eq!(
+			to_additional_utxo_json(&utxo).unwrap(),
+			serde_json::json!([
+				{ "txId": hex::encode([1; 32]), "index": 2 },
+				{
+					"address": "addr_test1vqezxrh24ts0775hulcg3ejcwj7hns8792vnn8met6z9gwsxt87zy",
+					"value": {
+						"coins": 1500000,
+						"assets": {
+							"e0d4479b3dbb53b1aecd48f7ef524a9cf166585923d91d9c72ed02cb.61626364": 7
+						}
+					},
+					"datum": "d87980",
+					"script": { "plutus:v2": "4e4d01000033222220051200120011" }
+				}
+			])
+		);
+	}
+}
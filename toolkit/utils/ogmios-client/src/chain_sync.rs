@@ -0,0 +1,580 @@
+//! Chain synchronization mini-protocol: `findIntersection` and `nextBlock`.
+//!
+//! The protocol is stateful: Ogmios keeps the position of the client on the chain per connection.
+//! Because of that it requires a WebSockets client, HTTP clients are not supported by Ogmios.
+//!
+//! Usual flow is to call [ChainSync::find_intersection] with known points, and then call
+//! [ChainSync::next_block] repeatedly. The first response after finding an intersection is always
+//! [NextBlockResponse::Backward] to the intersection point.
+//! After reaching the tip of the chain, `nextBlock` waits until a new block is available.
+//!
+//! [TransactionInclusion] uses the protocol to find the block including a transaction.
+
+use crate::{
+	ByNameParamsBuilder, OgmiosClient, OgmiosClientError, OgmiosParams,
+	types::{Datum, DatumHash, OgmiosScript, OgmiosTx, OgmiosUtxo, OgmiosValue},
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sidechain_domain::{McTxHash, UtxoId};
+use std::fmt::Debug;
+
+/// Trait that defines the methods of the chain synchronization mini-protocol.
+pub trait ChainSync {
+	#[allow(async_fn_in_trait)]
+	/// Sets the position of the client to the most recent of `points` that is on the chain.
+	/// Fails with JSON-RPC error when none of the points is on the chain.
+	async fn find_intersection(
+		&self,
+		points: &[Point],
+	) -> Result<FindIntersectionResponse, OgmiosClientError>;
+
+	#[allow(async_fn_in_trait)]
+	/// Returns the next block after the position of the client, or a rollback to a previous point.
+	async fn next_block(&self) -> Result<NextBlockResponse, OgmiosClientError>;
+}
+
+impl<T: OgmiosClient> ChainSync for T {
+	async fn find_intersection(
+		&self,
+		points: &[Point],
+	) -> Result<FindIntersectionResponse, OgmiosClientError> {
+		let params = ByNameParamsBuilder::new().insert("points", points)?.build();
+		self.request("findIntersection", params).await
+	}
+
+	async fn next_block(&self) -> Result<NextBlockResponse, OgmiosClientError> {
+		self.request("nextBlock", OgmiosParams::empty_by_name()).await
+	}
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+/// Point on the chain.
+pub enum Point {
+	/// The beginning of the chain, before the first block.
+	Origin,
+	/// Block at the given slot, with the given header hash.
+	Block {
+		/// Slot of the block.
+		slot: u64,
+		/// Header hash of the block.
+		id: [u8; 32],
+	},
+}
+
+impl Debug for Point {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Origin => f.write_str("Origin"),
+			Self::Block { slot, id } => f
+				.debug_struct("Block")
+				.field("slot", slot)
+				.field("id", &hex::encode(id))
+				.finish(),
+		}
+	}
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(untagged)]
+enum PointJson {
+	Origin(String),
+	Block {
+		slot: u64,
+		id: String,
+		#[serde(default, skip_serializing)]
+		height: Option<u64>,
+	},
+}
+
+impl Serialize for Point {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		match self {
+			Self::Origin => PointJson::Origin("origin".to_string()),
+			Self::Block { slot, id } => {
+				PointJson::Block { slot: *slot, id: hex::encode(id), height: None }
+			},
+		}
+		.serialize(serializer)
+	}
+}
+
+impl<'de> Deserialize<'de> for Point {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		Ok(Tip::deserialize(deserializer)?.point())
+	}
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+/// Tip of the chain, as reported by the chain synchronization responses.
+pub enum Tip {
+	/// The chain is empty.
+	Origin,
+	/// The most recent block of the chain.
+	Block {
+		/// Slot of the block.
+		slot: u64,
+		/// Header hash of the block.
+		id: [u8; 32],
+		/// Height of the block.
+		height: u64,
+	},
+}
+
+impl Tip {
+	/// Returns the point of the tip.
+	pub fn point(&self) -> Point {
+		match self {
+			Self::Origin => Point::Origin,
+			Self::Block { slot, id, .. } => Point::Block { slot: *slot, id: *id },
+		}
+	}
+
+	/// Returns the height of the tip. Returns 0 at the origin.
+	pub fn height(&self) -> u64 {
+		match self {
+			Self::Origin => 0,
+			Self::Block { height, .. } => *height,
+		}
+	}
+}
+
+impl Debug for Tip {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Origin => f.write_str("Origin"),
+			Self::Block { slot, id, height } => f
+				.debug_struct("Block")
+				.field("slot", slot)
+				.field("id", &hex::encode(id))
+				.field("height", height)
+				.finish(),
+		}
+	}
+}
+
+impl<'de> Deserialize<'de> for Tip {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		match PointJson::deserialize(deserializer)? {
+			PointJson::Origin(s) if s == "origin" => Ok(Self::Origin),
+			PointJson::Origin(s) => {
+				Err(serde::de::Error::custom(format!("expected 'origin', got '{s}'")))
+			},
+			PointJson::Block { slot, id, height } => {
+				let id = hex::decode(&id)
+					.map_err(serde::de::Error::custom)?
+					.try_into()
+					.map_err(|_| serde::de::Error::custom("expected 32 bytes of block id"))?;
+				Ok(Self::Block { slot, id, height: height.unwrap_or_default() })
+			},
+		}
+	}
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+/// Response of `findIntersection`.
+pub struct FindIntersectionResponse {
+	/// The most recent of the requested points that is on the chain.
+	pub intersection: Point,
+	/// The tip of the chain.
+	pub tip: Tip,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(tag = "direction", rename_all = "camelCase")]
+/// Response of `nextBlock`.
+pub enum NextBlockResponse {
+	/// A new block was added on top of the current position of the client.
+	Forward {
+		/// The new block.
+		block: OgmiosBlock,
+		/// The tip of the chain.
+		tip: Tip,
+	},
+	/// The chain switched to a fork, blocks after the `point` are no longer valid.
+	Backward {
+		/// Point to which the client has to roll back.
+		point: Point,
+		/// The tip of the chain.
+		tip: Tip,
+	},
+}
+
+impl NextBlockResponse {
+	/// Returns the tip of the chain.
+	pub fn tip(&self) -> Tip {
+		match self {
+			Self::Forward { tip, .. } => *tip,
+			Self::Backward { tip, .. } => *tip,
+		}
+	}
+}
+
+#[derive(Clone, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+/// Block of any era. Only the fields relevant for following the chain are present.
+pub struct OgmiosBlock {
+	/// Type of the block: `praos`, `bft` or `ebb` (Byron epoch boundary block).
+	#[serde(rename = "type")]
+	pub block_type: String,
+	/// Era of the block, for example `conway`.
+	pub era: String,
+	/// Header hash of the block.
+	#[serde(deserialize_with = "crate::types::parse_bytes_array")]
+	pub id: [u8; 32],
+	/// Header hash of the previous block, `None` for the first block of the chain.
+	#[serde(deserialize_with = "parse_ancestor")]
+	pub ancestor: Option<[u8; 32]>,
+	/// Height of the block.
+	pub height: u64,
+	/// Slot of the block. Byron epoch boundary blocks have no slot.
+	pub slot: Option<u64>,
+	/// Transactions of the block.
+	#[serde(default)]
+	pub transactions: Vec<OgmiosTransaction>,
+}
+
+impl OgmiosBlock {
+	/// Returns the point of this block. `None` for Byron epoch boundary blocks.
+	pub fn point(&self) -> Option<Point> {
+		self.slot.map(|slot| Point::Block { slot, id: self.id })
+	}
+}
+
+impl Debug for OgmiosBlock {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("OgmiosBlock")
+			.field("block_type", &self.block_type)
+			.field("era", &self.era)
+			.field("id", &hex::encode(self.id))
+			.field("ancestor", &self.ancestor.map(hex::encode))
+			.field("height", &self.height)
+			.field("slot", &self.slot)
+			.field("transactions", &self.transactions)
+			.finish()
+	}
+}
+
+fn parse_ancestor<'de, D: Deserializer<'de>>(
+	deserializer: D,
+) -> Result<Option<[u8; 32]>, D::Error> {
+	let ancestor = String::deserialize(deserializer)?;
+	if ancestor == "genesis" {
+		return Ok(None);
+	}
+	let bytes = hex::decode(ancestor).map_err(serde::de::Error::custom)?;
+	Ok(Some(bytes.try_into().map_err(|_| serde::de::Error::custom("expected 32 bytes"))?))
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+/// Which inputs are spent by a transaction.
+pub enum Spends {
+	#[default]
+	/// Transaction is valid, regular inputs are spent and outputs are created.
+	Inputs,
+	/// Phase-2 validation of the transaction failed, only collaterals are spent.
+	Collaterals,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+/// Transaction included in a block. Only the fields relevant for following UTXOs are present.
+pub struct OgmiosTransaction {
+	/// The transaction hash.
+	#[serde(deserialize_with = "crate::types::parse_bytes_array")]
+	pub id: [u8; 32],
+	/// Which inputs are spent by the transaction.
+	#[serde(default)]
+	pub spends: Spends,
+	/// Inputs of the transaction.
+	pub inputs: Vec<OgmiosTxInput>,
+	/// Collateral inputs of the transaction.
+	#[serde(default)]
+	pub collaterals: Vec<OgmiosTxInput>,
+	/// Outputs of the transaction.
+	#[serde(default)]
+	pub outputs: Vec<OgmiosTxOutput>,
+	/// Reference inputs of the transaction.
+	#[serde(default)]
+	pub references: Vec<OgmiosTxInput>,
+}
+
+impl OgmiosTransaction {
+	/// Returns the UTXOs spent by the transaction: inputs, or collaterals if the transaction failed.
+	pub fn spent_utxos(&self) -> Vec<UtxoId> {
+		match self.spends {
+			Spends::Inputs => self.inputs.iter().map(OgmiosTxInput::utxo_id).collect(),
+			Spends::Collaterals => self.collaterals.iter().map(OgmiosTxInput::utxo_id).collect(),
+		}
+	}
+
+	/// Returns the UTXOs created by the transaction. Empty if the transaction failed.
+	pub fn created_utxos(&self) -> Vec<OgmiosUtxo> {
+		if self.spends == Spends::Collaterals {
+			return Vec::new();
+		}
+		(self.outputs.iter().enumerate())
+			.map(|(index, output)| OgmiosUtxo {
+				transaction: OgmiosTx { id: self.id },
+				index: index as u16,
+				address: output.address.clone(),
+				value: output.value.clone(),
+				datum: output.datum.clone(),
+				datum_hash: output.datum_hash.clone(),
+				script: output.script.clone(),
+			})
+			.collect()
+	}
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+/// Reference to an output of a transaction.
+pub struct OgmiosTxInput {
+	/// The transaction that created the output.
+	pub transaction: OgmiosTx,
+	/// The index of the output within the transaction.
+	pub index: u16,
+}
+
+impl OgmiosTxInput {
+	/// Returns the UTXO ID.
+	pub fn utxo_id(&self) -> UtxoId {
+		UtxoId::new(self.transaction.id, self.index)
+	}
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+/// Output of a transaction.
+pub struct OgmiosTxOutput {
+	/// The Bech32 address of the output.
+	pub address: String,
+	/// The value of the output.
+	pub value: OgmiosValue,
+	/// The datum of the output.
+	pub datum: Option<Datum>,
+	/// The hash of the datum of the output.
+	pub datum_hash: Option<DatumHash>,
+	/// The reference script of the output.
+	pub script: Option<OgmiosScript>,
+}
+
+/// Follows the chain with [ChainSync], looking for the block including a transaction.
+///
+/// Only blocks after the starting point are inspected, so a transaction included in the starting block
+/// or before it is not found. Rollbacks of the block including the transaction are detected.
+/// The position of the chain synchronization client is changed by the tracker,
+/// so the client should not be used for anything else in the meantime.
+#[derive(Clone, Debug)]
+pub struct TransactionInclusion {
+	tx_hash: McTxHash,
+	/// Slot and height of the block including the transaction
+	included_in: Option<(u64, u64)>,
+	/// Point of the most recent block read by the tracker
+	position: Point,
+	/// Tip of the chain, as reported by the most recent response
+	tip: Tip,
+}
+
+impl TransactionInclusion {
+	/// Sets the position of `client` to the current tip of the chain and starts tracking the transaction.
+	pub async fn start_at_tip<C: ChainSync>(
+		client: &C,
+		tx_hash: McTxHash,
+	) -> Result<Self, OgmiosClientError> {
+		let FindIntersectionResponse { tip, .. } =
+			client.find_intersection(&[Point::Origin]).await?;
+		Self::start(client, tx_hash, tip.point()).await
+	}
+
+	/// Sets the position of `client` to `start` and starts tracking the transaction.
+	/// Fails when `start` is not on the chain.
+	pub async fn start<C: ChainSync>(
+		client: &C,
+		tx_hash: McTxHash,
+		start: Point,
+	) -> Result<Self, OgmiosClientError> {
+		let FindIntersectionResponse { intersection, .. } =
+			client.find_intersection(&[start]).await?;
+		if intersection != start {
+			return Err(OgmiosClientError::ResponseError(format!(
+				"Block {start:?} is not on the chain"
+			)));
+		}
+		// The first response after finding an intersection is always a roll back to it
+		let tip = client.next_block().await?.tip();
+		Ok(Self { tx_hash, included_in: None, position: start, tip })
+	}
+
+	/// Reads the next block or rollback. Waits for a new block when the tip was already reached.
+	pub async fn next<C: ChainSync>(&mut self, client: &C) -> Result<(), OgmiosClientError> {
+		match client.next_block().await? {
+			NextBlockResponse::Forward { block, tip } => {
+				if block.transactions.iter().any(|tx| tx.id == self.tx_hash.0) {
+					self.included_in = Some((block.slot.unwrap_or_default(), block.height));
+				}
+				self.position = block.point().unwrap_or(self.position);
+				self.tip = tip;
+			},
+			NextBlockResponse::Backward { point, tip } => {
+				let is_rolled_back = |(slot, _)| match point {
+					Point::Origin => true,
+					Point::Block { slot: rollback_slot, .. } => rollback_slot < slot,
+				};
+				if self.included_in.is_some_and(is_rolled_back) {
+					self.included_in = None;
+				}
+				self.position = point;
+				self.tip = tip;
+			},
+		}
+		Ok(())
+	}
+
+	/// Reads blocks until the tip of the chain is reached.
+	pub async fn sync_to_tip<C: ChainSync>(&mut self, client: &C) -> Result<(), OgmiosClientError> {
+		while !self.is_at_tip() {
+			self.next(client).await?;
+		}
+		Ok(())
+	}
+
+	/// Returns true if the most recent block read by the tracker is the tip of the chain.
+	pub fn is_at_tip(&self) -> bool {
+		self.position == self.tip.point()
+	}
+
+	/// Returns the height of the block including the transaction.
+	/// `None` if the block was not read yet, or if it was rolled back.
+	pub fn inclusion_height(&self) -> Option<u64> {
+		self.included_in.map(|(_, height)| height)
+	}
+
+	/// Returns the tip of the chain, as reported by the most recent response.
+	pub fn tip(&self) -> Tip {
+		self.tip
+	}
+
+	/// Returns the number of blocks on the chain starting from the block including the transaction.
+	pub fn depth(&self) -> Option<u64> {
+		self.inclusion_height()
+			.map(|height| self.tip.height().saturating_sub(height) + 1)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::{cell::RefCell, collections::VecDeque};
+
+	const TX_HASH: McTxHash = McTxHash([7; 32]);
+
+	struct MockChainSync {
+		tip: Tip,
+		next_blocks: RefCell<VecDeque<NextBlockResponse>>,
+	}
+
+	impl ChainSync for MockChainSync {
+		async fn find_intersection(
+			&self,
+			points: &[Point],
+		) -> Result<FindIntersectionResponse, OgmiosClientError> {
+			Ok(FindIntersectionResponse { intersection: points[0], tip: self.tip })
+		}
+
+		async fn next_block(&self) -> Result<NextBlockResponse, OgmiosClientError> {
+			Ok(self.next_blocks.borrow_mut().pop_front().expect("unexpected nextBlock request"))
+		}
+	}
+
+	fn tip(height: u64) -> Tip {
+		Tip::Block { slot: height * 10, id: [height as u8; 32], height }
+	}
+
+	fn forward(height: u64, tx_ids: &[[u8; 32]], tip_height: u64) -> NextBlockResponse {
+		let transactions = (tx_ids.iter())
+			.map(|id| OgmiosTransaction {
+				id: *id,
+				spends: Spends::Inputs,
+				inputs: vec![OgmiosTxInput { transaction: OgmiosTx { id: [1; 32] }, index: 0 }],
+				collaterals: vec![],
+				outputs: vec![],
+				references: vec![],
+			})
+			.collect();
+		let block = OgmiosBlock {
+			block_type: "praos".to_string(),
+			era: "conway".to_string(),
+			id: [height as u8; 32],
+			ancestor: Some([height as u8 - 1; 32]),
+			height,
+			slot: Some(height * 10),
+			transactions,
+		};
+		NextBlockResponse::Forward { block, tip: tip(tip_height) }
+	}
+
+	fn backward(height: u64, tip_height: u64) -> NextBlockResponse {
+		NextBlockResponse::Backward { point: tip(height).point(), tip: tip(tip_height) }
+	}
+
+	#[tokio::test]
+	async fn finds_block_including_transaction() {
+		let client = MockChainSync {
+			tip: tip(100),
+			next_blocks: RefCell::new(VecDeque::from(vec![
+				backward(100, 102),
+				forward(101, &[[1; 32], TX_HASH.0], 102),
+				forward(102, &[[2; 32]], 102),
+			])),
+		};
+		let mut inclusion = TransactionInclusion::start_at_tip(&client, TX_HASH).await.unwrap();
+		assert_eq!(inclusion.depth(), None);
+		inclusion.sync_to_tip(&client).await.unwrap();
+		assert!(inclusion.is_at_tip());
+		assert_eq!(inclusion.inclusion_height(), Some(101));
+		assert_eq!(inclusion.depth(), Some(2));
+	}
+
+	#[tokio::test]
+	async fn detects_rollback_of_block_including_transaction() {
+		let client = MockChainSync {
+			tip: tip(100),
+			next_blocks: RefCell::new(VecDeque::from(vec![
+				backward(100, 101),
+				forward(101, &[TX_HASH.0], 101),
+				backward(100, 100),
+				forward(101, &[[3; 32]], 101),
+				forward(102, &[TX_HASH.0], 102),
+			])),
+		};
+		let mut inclusion = TransactionInclusion::start_at_tip(&client, TX_HASH).await.unwrap();
+		inclusion.sync_to_tip(&client).await.unwrap();
+		assert_eq!(inclusion.inclusion_height(), Some(101));
+		inclusion.next(&client).await.unwrap();
+		assert_eq!(inclusion.inclusion_height(), None);
+		inclusion.next(&client).await.unwrap();
+		assert_eq!(inclusion.inclusion_height(), None);
+		inclusion.next(&client).await.unwrap();
+		assert_eq!(inclusion.inclusion_height(), Some(102));
+		assert_eq!(inclusion.depth(), Some(1));
+	}
+
+	#[tokio::test]
+	async fn rollback_after_block_including_transaction_keeps_it() {
+		let client = MockChainSync {
+			tip: tip(100),
+			next_blocks: RefCell::new(VecDeque::from(vec![
+				backward(100, 102),
+				forward(101, &[TX_HASH.0], 102),
+				forward(102, &[], 102),
+				backward(101, 101),
+			])),
+		};
+		let mut inclusion = TransactionInclusion::start_at_tip(&client, TX_HASH).await.unwrap();
+		inclusion.sync_to_tip(&client).await.unwrap();
+		inclusion.next(&client).await.unwrap();
+		assert_eq!(inclusion.inclusion_height(), Some(101));
+		assert_eq!(inclusion.depth(), Some(1));
+	}
+}
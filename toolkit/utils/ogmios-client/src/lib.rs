@@ -4,9 +4,18 @@
 //! It can be accessed via a HTTP or WebSocket connection.
 //!
 //! More information about Ogmios API can be found at <https://ogmios.dev/api/>
+//!
+//! Besides state queries and transactions, [chain_sync] allows following the chain block by block.
+//!
+//! With `blockfrost-client` feature enabled, the same query and transaction traits are also
+//! implemented over a Blockfrost-compatible HTTP API.
 
+#[cfg(feature = "blockfrost-client")]
+pub mod blockfrost;
+pub mod chain_sync;
 #[cfg(feature = "jsonrpsee-client")]
 pub mod jsonrpsee;
+pub mod mempool;
 pub mod query_ledger_state;
 pub mod query_network;
 pub mod transactions;
@@ -0,0 +1,194 @@
+//! Mempool monitoring mini-protocol and transaction status queries.
+//!
+//! The mempool monitoring protocol is stateful: a snapshot of the mempool is acquired
+//! with [MempoolMonitoring::acquire_mempool] and all subsequent queries are answered
+//! against that snapshot, until it is released or a new one is acquired.
+//! Because of that it requires a WebSockets client.
+
+use crate::{
+	ByNameParamsBuilder, OgmiosClient, OgmiosClientError, OgmiosParams,
+	chain_sync::{Point, TransactionInclusion},
+	query_ledger_state::QueryUtxoByUtxoId,
+	types::{OgmiosBytesSize, OgmiosTx},
+};
+use serde::Deserialize;
+use sidechain_domain::{McTxHash, UtxoId};
+
+/// Trait that defines the methods of the mempool monitoring mini-protocol.
+pub trait MempoolMonitoring {
+	#[allow(async_fn_in_trait)]
+	/// Acquires a snapshot of the mempool. Waits until the mempool changes if a snapshot was already acquired.
+	async fn acquire_mempool(&self) -> Result<AcquireMempoolResponse, OgmiosClientError>;
+
+	#[allow(async_fn_in_trait)]
+	/// Returns true if the transaction is present in the acquired mempool snapshot.
+	async fn has_transaction(&self, tx_hash: McTxHash) -> Result<bool, OgmiosClientError>;
+
+	#[allow(async_fn_in_trait)]
+	/// Returns the next transaction of the acquired mempool snapshot, or `None` if all were already returned.
+	async fn next_transaction(&self) -> Result<Option<OgmiosTx>, OgmiosClientError>;
+
+	#[allow(async_fn_in_trait)]
+	/// Returns the size and capacity of the acquired mempool snapshot.
+	async fn size_of_mempool(&self) -> Result<MempoolSizeAndCapacity, OgmiosClientError>;
+
+	#[allow(async_fn_in_trait)]
+	/// Releases the acquired mempool snapshot.
+	async fn release_mempool(&self) -> Result<(), OgmiosClientError>;
+}
+
+impl<T: OgmiosClient> MempoolMonitoring for T {
+	async fn acquire_mempool(&self) -> Result<AcquireMempoolResponse, OgmiosClientError> {
+		self.request("acquireMempool", OgmiosParams::empty_by_name()).await
+	}
+
+	async fn has_transaction(&self, tx_hash: McTxHash) -> Result<bool, OgmiosClientError> {
+		let params = ByNameParamsBuilder::new().insert("id", hex::encode(tx_hash.0))?.build();
+		self.request("hasTransaction", params).await
+	}
+
+	async fn next_transaction(&self) -> Result<Option<OgmiosTx>, OgmiosClientError> {
+		let response: NextTransactionResponse =
+			self.request("nextTransaction", OgmiosParams::empty_by_name()).await?;
+		Ok(response.transaction)
+	}
+
+	async fn size_of_mempool(&self) -> Result<MempoolSizeAndCapacity, OgmiosClientError> {
+		self.request("sizeOfMempool", OgmiosParams::empty_by_name()).await
+	}
+
+	async fn release_mempool(&self) -> Result<(), OgmiosClientError> {
+		let _: serde_json::Value =
+			self.request("releaseMempool", OgmiosParams::empty_by_name()).await?;
+		Ok(())
+	}
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+/// Response of `acquireMempool`.
+pub struct AcquireMempoolResponse {
+	/// Slot at which the mempool snapshot was taken.
+	pub slot: u64,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+struct NextTransactionResponse {
+	transaction: Option<OgmiosTx>,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+/// Size and capacity of the mempool.
+pub struct MempoolSizeAndCapacity {
+	/// Maximum size of the mempool.
+	pub max_capacity: OgmiosBytesSize,
+	/// Current size of the mempool.
+	pub current_size: OgmiosBytesSize,
+	/// Number of transactions in the mempool.
+	pub transactions: MempoolTransactionsCount,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+/// Number of transactions in the mempool.
+pub struct MempoolTransactionsCount {
+	/// The number of transactions.
+	pub count: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+/// Status of a submitted transaction.
+pub enum TransactionStatus {
+	/// The transaction is in the mempool of the node, waiting to be included in a block.
+	InMempool,
+	/// The transaction is included in a block.
+	Confirmed {
+		/// Number of blocks on the chain starting from the block including the transaction.
+		depth: u64,
+	},
+	/// An output of the transaction is present in the ledger state at the tip, but the block
+	/// including the transaction, and so the number of its confirmations, is not known.
+	InLedger,
+	/// The transaction is neither in the mempool nor on the chain.
+	Unknown,
+}
+
+/// Trait for querying the status of a submitted transaction.
+pub trait QueryTransactionStatus {
+	#[allow(async_fn_in_trait)]
+	/// Returns the status of the transaction with the given hash.
+	async fn transaction_status(
+		&self,
+		tx_hash: McTxHash,
+	) -> Result<TransactionStatus, OgmiosClientError>;
+
+	#[allow(async_fn_in_trait)]
+	/// Returns the status of the transaction with the given hash, that was submitted after the block at `since`,
+	/// for example after the tip of the chain at the moment of submission.
+	/// Clients that find the block including the transaction by following the chain start from `since`.
+	/// The default implementation ignores `since`.
+	async fn transaction_status_since(
+		&self,
+		tx_hash: McTxHash,
+		_since: Point,
+	) -> Result<TransactionStatus, OgmiosClientError> {
+		self.transaction_status(tx_hash).await
+	}
+}
+
+impl<T: OgmiosClient> QueryTransactionStatus for T {
+	/// Ogmios does not index transactions, so the status is found by checking the mempool,
+	/// and then the first output of the transaction.
+	/// Because of that, transaction with spent first output is reported as [TransactionStatus::Unknown],
+	/// and transaction with unspent first output as [TransactionStatus::InLedger], never as confirmed.
+	/// Use [QueryTransactionStatus::transaction_status_since] to get the depth of the transaction.
+	async fn transaction_status(
+		&self,
+		tx_hash: McTxHash,
+	) -> Result<TransactionStatus, OgmiosClientError> {
+		if is_in_mempool(self, tx_hash).await? {
+			return Ok(TransactionStatus::InMempool);
+		}
+		first_output_status(self, tx_hash).await
+	}
+
+	/// Follows the chain from `since` to the tip, looking for the block including the transaction.
+	/// When it is found, the transaction is reported as [TransactionStatus::Confirmed] with its depth.
+	/// Otherwise, the status is found like in [QueryTransactionStatus::transaction_status].
+	/// Requires WebSockets client. All blocks after `since` are read, so `since` should be recent.
+	async fn transaction_status_since(
+		&self,
+		tx_hash: McTxHash,
+		since: Point,
+	) -> Result<TransactionStatus, OgmiosClientError> {
+		if is_in_mempool(self, tx_hash).await? {
+			return Ok(TransactionStatus::InMempool);
+		}
+		let mut inclusion = TransactionInclusion::start(self, tx_hash, since).await?;
+		inclusion.sync_to_tip(self).await?;
+		match inclusion.depth() {
+			Some(depth) => Ok(TransactionStatus::Confirmed { depth }),
+			None => first_output_status(self, tx_hash).await,
+		}
+	}
+}
+
+async fn is_in_mempool<T: OgmiosClient>(
+	client: &T,
+	tx_hash: McTxHash,
+) -> Result<bool, OgmiosClientError> {
+	client.acquire_mempool().await?;
+	let in_mempool = client.has_transaction(tx_hash).await;
+	client.release_mempool().await?;
+	in_mempool
+}
+
+async fn first_output_status<T: OgmiosClient>(
+	client: &T,
+	tx_hash: McTxHash,
+) -> Result<TransactionStatus, OgmiosClientError> {
+	let first_output = client.query_utxo_by_id(UtxoId::new(tx_hash.0, 0)).await?;
+	Ok(match first_output {
+		Some(_) => TransactionStatus::InLedger,
+		None => TransactionStatus::Unknown,
+	})
+}
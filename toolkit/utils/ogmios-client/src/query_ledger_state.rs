@@ -2,6 +2,7 @@
 
 use crate::{
 	ByNameParamsBuilder, OgmiosClient, OgmiosClientError, OgmiosParams,
+	transactions::OgmiosBudget,
 	types::{OgmiosBytesSize, OgmiosUtxo, OgmiosValue, SlotLength, TimeSeconds},
 };
 use serde::Deserialize;
@@ -154,6 +155,8 @@ pub struct ProtocolParametersResponse {
 	pub collateral_percentage: u32,
 	/// Cost of reference scripts.
 	pub min_fee_reference_scripts: ReferenceScriptsCosts,
+	/// Maximum execution units of all scripts in a single transaction.
+	pub max_execution_units_per_transaction: Option<OgmiosBudget>,
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Default)]
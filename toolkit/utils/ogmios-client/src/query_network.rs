@@ -4,7 +4,7 @@ use crate::{OgmiosClient, OgmiosClientError, OgmiosParams, types::SlotLength};
 use fraction::Decimal;
 use serde::Deserialize;
 use serde_json::Value;
-use sidechain_domain::NetworkType;
+use sidechain_domain::{McTxHash, NetworkType};
 use std::collections::HashMap;
 
 /// Trait that defines the methods for querying the network.
@@ -14,6 +14,25 @@ pub trait QueryNetwork {
 	async fn shelley_genesis_configuration(
 		&self,
 	) -> Result<ShelleyGenesisConfigurationResponse, OgmiosClientError>;
+
+	#[allow(async_fn_in_trait)]
+	/// Returns the height of the most recent block of the chain. Returns 0 at the origin.
+	async fn get_block_height(&self) -> Result<u64, OgmiosClientError> {
+		Err(OgmiosClientError::RequestError(
+			"Block height query is not supported by this client".to_string(),
+		))
+	}
+
+	#[allow(async_fn_in_trait)]
+	/// Returns the height of the block including the transaction.
+	/// Returns `None` if the transaction is not on the chain, or if the backend does not index transactions.
+	/// The default implementation is for backends that do not index transactions.
+	async fn get_transaction_block_height(
+		&self,
+		_tx_hash: McTxHash,
+	) -> Result<Option<u64>, OgmiosClientError> {
+		Ok(None)
+	}
 }
 
 impl<T: OgmiosClient> QueryNetwork for T {
@@ -25,6 +44,19 @@ impl<T: OgmiosClient> QueryNetwork for T {
 		self.request("queryNetwork/genesisConfiguration", OgmiosParams::ByName(params))
 			.await
 	}
+
+	async fn get_block_height(&self) -> Result<u64, OgmiosClientError> {
+		// Ogmios returns either a number or "origin" string.
+		let height: Value = self
+			.request("queryNetwork/blockHeight", OgmiosParams::empty_positional())
+			.await?;
+		match height {
+			Value::String(s) if s == "origin" => Ok(0),
+			height => height.as_u64().ok_or_else(|| {
+				OgmiosClientError::ResponseError(format!("Invalid block height: {height}"))
+			}),
+		}
+	}
 }
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
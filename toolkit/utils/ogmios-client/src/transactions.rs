@@ -1,14 +1,15 @@
 //! Requests to evalute and submit transactions via Ogmios`.
 
-use crate::{ByNameParamsBuilder, OgmiosClient, OgmiosClientError, types::OgmiosTx};
+use crate::{
+	ByNameParamsBuilder, OgmiosClient, OgmiosClientError,
+	types::{OgmiosTx, OgmiosUtxo},
+};
 use serde::Deserialize;
 
 /// Trait that defines the methods for evaluating and submitting transactions via Ogmios.
 pub trait Transactions {
 	/// Evaluates a transaction.
 	///
-	/// Does not support additional UTXO inputs yet.
-	///
 	/// Parameters:
 	/// - `tx_bytes: &[u8]` - CBOR-serialized transaction
 	#[allow(async_fn_in_trait)]
@@ -17,6 +18,29 @@ pub trait Transactions {
 		tx_bytes: &[u8],
 	) -> Result<Vec<OgmiosEvaluateTransactionResponse>, OgmiosClientError>;
 
+	/// Evaluates a transaction that spends or references outputs not yet present in the ledger,
+	/// for example outputs of a transaction that was submitted but is not confirmed yet.
+	///
+	/// Default implementation supports only empty `additional_utxos`.
+	///
+	/// Parameters:
+	/// - `tx_bytes: &[u8]` - CBOR-serialized transaction
+	/// - `additional_utxos: &[OgmiosUtxo]` - UTXOs used to resolve inputs that are not in the ledger
+	#[allow(async_fn_in_trait)]
+	async fn evaluate_transaction_with_additional_utxos(
+		&self,
+		tx_bytes: &[u8],
+		additional_utxos: &[OgmiosUtxo],
+	) -> Result<Vec<OgmiosEvaluateTransactionResponse>, OgmiosClientError> {
+		if additional_utxos.is_empty() {
+			self.evaluate_transaction(tx_bytes).await
+		} else {
+			Err(OgmiosClientError::ParametersError(
+				"Additional UTXOs are not supported by this client".to_string(),
+			))
+		}
+	}
+
 	/// Submits a signed transaction.
 	///
 	/// Parameters:
@@ -33,9 +57,19 @@ impl<T: OgmiosClient> Transactions for T {
 		&self,
 		tx_bytes: &[u8],
 	) -> Result<Vec<OgmiosEvaluateTransactionResponse>, OgmiosClientError> {
+		self.evaluate_transaction_with_additional_utxos(tx_bytes, &[]).await
+	}
+
+	async fn evaluate_transaction_with_additional_utxos(
+		&self,
+		tx_bytes: &[u8],
+		additional_utxos: &[OgmiosUtxo],
+	) -> Result<Vec<OgmiosEvaluateTransactionResponse>, OgmiosClientError> {
+		let additional_utxos: Vec<serde_json::Value> =
+			additional_utxos.iter().map(OgmiosUtxo::to_ogmios_json).collect();
 		let params = ByNameParamsBuilder::new()
 			.insert("transaction", serde_json::json!({"cbor": hex::encode(tx_bytes)}))?
-			.insert("additionalUtxo", serde_json::json!([]))?
+			.insert("additionalUtxo", additional_utxos)?
 			.build();
 		self.request("evaluateTransaction", params).await
 	}
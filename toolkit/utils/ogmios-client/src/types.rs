@@ -52,6 +52,38 @@ impl OgmiosUtxo {
 	pub fn utxo_id(&self) -> UtxoId {
 		UtxoId::new(self.transaction.id, self.index)
 	}
+
+	/// Returns JSON representation of the UTXO in the format used by Ogmios.
+	pub fn to_ogmios_json(&self) -> serde_json::Value {
+		let mut value = serde_json::Map::new();
+		value.insert("ada".to_string(), serde_json::json!({ "lovelace": self.value.lovelace }));
+		for (policy_id, assets) in self.value.native_tokens.iter() {
+			let assets: serde_json::Map<String, serde_json::Value> = assets
+				.iter()
+				.map(|asset| (hex::encode(&asset.name), serde_json::json!(asset.amount)))
+				.collect();
+			value.insert(hex::encode(policy_id), serde_json::Value::Object(assets));
+		}
+		let mut utxo = serde_json::json!({
+			"transaction": { "id": hex::encode(self.transaction.id) },
+			"index": self.index,
+			"address": self.address,
+			"value": value,
+		});
+		if let Some(datum) = &self.datum {
+			utxo["datum"] = serde_json::json!(hex::encode(&datum.bytes));
+		}
+		if let Some(datum_hash) = &self.datum_hash {
+			utxo["datumHash"] = serde_json::json!(hex::encode(datum_hash.bytes));
+		}
+		if let Some(script) = &self.script {
+			utxo["script"] = serde_json::json!({
+				"language": script.language,
+				"cbor": hex::encode(&script.cbor),
+			});
+		}
+		utxo
+	}
 }
 
 impl core::fmt::Display for OgmiosUtxo {
@@ -0,0 +1,313 @@
+#![cfg(feature = "blockfrost-client")]
+
+use fraction::{Decimal, Fraction};
+use hex_literal::hex;
+use ogmios_client::{
+	blockfrost::BlockfrostClient,
+	mempool::{QueryTransactionStatus, TransactionStatus},
+	query_ledger_state::{
+		EpochBoundary, EpochParameters, EraSummary, QueryLedgerState, QueryUtxoByUtxoId,
+	},
+	query_network::QueryNetwork,
+	transactions::{
+		OgmiosBudget, OgmiosEvaluateTransactionResponse, OgmiosValidatorIndex, Transactions,
+	},
+	types::{Asset, OgmiosTx, SlotLength, TimeSeconds},
+};
+use serde_json::json;
+use sidechain_domain::{McTxHash, NetworkType, UtxoId};
+use std::time::Duration;
+
+mod http_server;
+
+fn client_for(addr: std::net::SocketAddr) -> BlockfrostClient {
+	BlockfrostClient::new(&format!("http://{addr}/"), Duration::from_secs(5), Some("pid".into()))
+}
+
+#[tokio::test]
+async fn get_tip() {
+	let (addr, _) = http_server::for_responses(vec![(
+		"/blocks/latest",
+		200,
+		json!({"hash": "aa", "slot": 42000, "height": 100}),
+	)])
+	.unwrap();
+	let client = client_for(addr);
+	let tip = client.get_tip().await.unwrap();
+	assert_eq!(tip.slot, 42000);
+	assert_eq!(client.get_block_height().await.unwrap(), 100);
+}
+
+#[tokio::test]
+async fn era_summaries() {
+	let (addr, _) = http_server::for_responses(vec![(
+		"/network/eras",
+		200,
+		json!([{
+			"start": { "time": 0, "slot": 0, "epoch": 0 },
+			"end": { "time": 1728000, "slot": 86400, "epoch": 4 },
+			"parameters": { "epoch_length": 21600, "slot_length": 20, "safe_zone": 4320 }
+		}]),
+	)])
+	.unwrap();
+	let eras = client_for(addr).era_summaries().await.unwrap();
+	assert_eq!(
+		eras,
+		vec![EraSummary {
+			start: EpochBoundary { time: TimeSeconds { seconds: 0 }, slot: 0, epoch: 0 },
+			end: EpochBoundary { time: TimeSeconds { seconds: 1728000 }, slot: 86400, epoch: 4 },
+			parameters: EpochParameters {
+				epoch_length: 21600,
+				slot_length: SlotLength { milliseconds: 20000 },
+				safe_zone: 4320
+			}
+		}]
+	);
+}
+
+#[tokio::test]
+async fn query_utxos() {
+	let (addr, requests) = http_server::for_responses(vec![(
+		"/addresses/addr_test1/utxos?count=100&page=1",
+		200,
+		json!([{
+			"address": "addr_test1",
+			"tx_hash": "106b0d7d1544c97941777041699412fb7c8b94855210987327199620c0599580",
+			"output_index": 1,
+			"amount": [
+				{ "unit": "lovelace", "quantity": "1356118" },
+				{ "unit": "e0d4479b3dbb53b1aecd48f7ef524a9cf166585923d91d9c72ed02cbaabb", "quantity": "5" }
+			],
+			"block": "bb",
+			"data_hash": "c248757d390181c517a5beadc9c3fe64bf821d3e889a963fc717003ec248757d",
+			"inline_datum": "d8799fff",
+			"reference_script_hash": null
+		}]),
+	)])
+	.unwrap();
+	let utxos = client_for(addr).query_utxos(&["addr_test1".to_string()]).await.unwrap();
+	assert_eq!(utxos.len(), 1);
+	let utxo = &utxos[0];
+	assert_eq!(
+		utxo.transaction,
+		OgmiosTx { id: hex!("106b0d7d1544c97941777041699412fb7c8b94855210987327199620c0599580") }
+	);
+	assert_eq!(utxo.index, 1);
+	assert_eq!(utxo.value.lovelace, 1356118);
+	assert_eq!(
+		utxo.value
+			.native_tokens
+			.get(&hex!("e0d4479b3dbb53b1aecd48f7ef524a9cf166585923d91d9c72ed02cb")),
+		Some(&vec![Asset { name: hex!("aabb").to_vec(), amount: 5 }])
+	);
+	assert_eq!(utxo.datum, Some(hex!("d8799fff").to_vec().into()));
+	assert_eq!(requests.lock().unwrap()[0].headers.get("project_id"), Some(&"pid".to_string()));
+}
+
+#[tokio::test]
+async fn query_utxos_of_unknown_address_returns_empty_vec() {
+	let (addr, _) = http_server::for_responses(vec![]).unwrap();
+	let utxos = client_for(addr).query_utxos(&["addr_test1".to_string()]).await.unwrap();
+	assert_eq!(utxos, vec![]);
+}
+
+#[tokio::test]
+async fn query_utxo_by_id() {
+	let (addr, _) = http_server::for_responses(vec![(
+		"/txs/106b0d7d1544c97941777041699412fb7c8b94855210987327199620c0599580/utxos",
+		200,
+		json!({
+			"hash": "106b0d7d1544c97941777041699412fb7c8b94855210987327199620c0599580",
+			"inputs": [],
+			"outputs": [
+				{
+					"address": "addr_test1",
+					"amount": [{ "unit": "lovelace", "quantity": "1000" }],
+					"output_index": 0,
+					"collateral": false,
+					"consumed_by_tx": "aa"
+				},
+				{
+					"address": "addr_test2",
+					"amount": [{ "unit": "lovelace", "quantity": "2000" }],
+					"output_index": 1,
+					"collateral": false,
+					"consumed_by_tx": null
+				}
+			]
+		}),
+	)])
+	.unwrap();
+	let client = client_for(addr);
+	let tx_hash = hex!("106b0d7d1544c97941777041699412fb7c8b94855210987327199620c0599580");
+
+	let spent = client.query_utxo_by_id(UtxoId::new(tx_hash, 0)).await.unwrap();
+	assert_eq!(spent, None);
+
+	let unspent = client.query_utxo_by_id(UtxoId::new(tx_hash, 1)).await.unwrap().unwrap();
+	assert_eq!(unspent.address, "addr_test2");
+	assert_eq!(unspent.value.lovelace, 2000);
+
+	let unknown = client.query_utxo_by_id(UtxoId::new([1; 32], 0)).await.unwrap();
+	assert_eq!(unknown, None);
+}
+
+#[tokio::test]
+async fn query_protocol_parameters() {
+	let (addr, _) = http_server::for_responses(vec![(
+		"/epochs/latest/parameters",
+		200,
+		json!({
+			"epoch": 500,
+			"min_fee_a": 44,
+			"min_fee_b": 155381,
+			"key_deposit": "2000000",
+			"pool_deposit": "500000000",
+			"max_val_size": "5000",
+			"max_tx_size": 16384,
+			"coins_per_utxo_size": "4310",
+			"price_mem": 0.0577,
+			"price_step": 0.0000721,
+			"cost_models_raw": { "PlutusV1": [1, 2], "PlutusV2": [3], "PlutusV3": [4, 5, 6] },
+			"max_collateral_inputs": 3,
+			"collateral_percent": 150,
+			"min_fee_ref_script_cost_per_byte": 15
+		}),
+	)])
+	.unwrap();
+	let params = client_for(addr).query_protocol_parameters().await.unwrap();
+	assert_eq!(params.min_fee_coefficient, 44);
+	assert_eq!(params.min_fee_constant.lovelace, 155381);
+	assert_eq!(params.stake_pool_deposit.lovelace, 500000000);
+	assert_eq!(params.stake_credential_deposit.lovelace, 2000000);
+	assert_eq!(params.max_value_size.bytes, 5000);
+	assert_eq!(params.max_transaction_size.bytes, 16384);
+	assert_eq!(params.min_utxo_deposit_coefficient, 4310);
+	assert_eq!(params.script_execution_prices.memory, fraction::Ratio::new(577, 10000));
+	assert_eq!(params.script_execution_prices.cpu, fraction::Ratio::new(721, 10000000));
+	assert_eq!(params.plutus_cost_models.plutus_v3, vec![4, 5, 6]);
+	assert_eq!(params.max_collateral_inputs, 3);
+	assert_eq!(params.collateral_percentage, 150);
+	assert_eq!(params.min_fee_reference_scripts.base, 15.0);
+}
+
+#[tokio::test]
+async fn shelley_genesis_configuration() {
+	let (addr, _) = http_server::for_responses(vec![(
+		"/genesis",
+		200,
+		json!({
+			"active_slots_coefficient": 0.05,
+			"update_quorum": 5,
+			"max_lovelace_supply": "45000000000000000",
+			"network_magic": 2,
+			"epoch_length": 86400,
+			"system_start": 1666656000,
+			"slots_per_kes_period": 129600,
+			"slot_length": 1,
+			"max_kes_evolutions": 62,
+			"security_param": 432
+		}),
+	)])
+	.unwrap();
+	let genesis = client_for(addr).shelley_genesis_configuration().await.unwrap();
+	assert_eq!(genesis.network_magic, 2);
+	assert_eq!(genesis.network, NetworkType::Testnet);
+	assert_eq!(genesis.security_parameter, 432);
+	assert_eq!(
+		genesis.active_slots_coefficient,
+		Decimal::from_fraction(Fraction::new(1u64, 20u64))
+	);
+	assert_eq!(genesis.epoch_length, 86400);
+	assert_eq!(genesis.slot_length, SlotLength { milliseconds: 1000 });
+	assert_eq!(genesis.start_time.unix_timestamp(), 1666656000);
+}
+
+#[tokio::test]
+async fn submit_transaction() {
+	let (addr, requests) = http_server::for_responses(vec![(
+		"/tx/submit",
+		200,
+		json!("106b0d7d1544c97941777041699412fb7c8b94855210987327199620c0599580"),
+	)])
+	.unwrap();
+	let response = client_for(addr).submit_transaction(&hex!("aabbccdd")).await.unwrap();
+	assert_eq!(
+		response.transaction,
+		OgmiosTx { id: hex!("106b0d7d1544c97941777041699412fb7c8b94855210987327199620c0599580") }
+	);
+	let request = requests.lock().unwrap()[0].clone();
+	assert_eq!(request.method, "POST");
+	assert_eq!(request.path, "/tx/submit");
+	assert_eq!(request.headers.get("content-type"), Some(&"application/cbor".to_string()));
+	assert_eq!(request.body, hex!("aabbccdd").to_vec());
+}
+
+#[tokio::test]
+async fn submit_transaction_error() {
+	let (addr, _) = http_server::for_responses(vec![(
+		"/tx/submit",
+		400,
+		json!({ "status_code": 400, "error": "Bad Request", "message": "ValueNotConservedUTxO" }),
+	)])
+	.unwrap();
+	let error = client_for(addr).submit_transaction(&hex!("aabbccdd")).await.unwrap_err();
+	assert!(error.to_string().contains("ValueNotConservedUTxO"));
+}
+
+#[tokio::test]
+async fn evaluate_transaction() {
+	let (addr, requests) = http_server::for_responses(vec![(
+		"/utils/txs/evaluate?version=6",
+		200,
+		json!({
+			"jsonrpc": "2.0",
+			"method": "evaluateTransaction",
+			"result": [{
+				"validator": { "index": 0, "purpose": "mint" },
+				"budget": { "memory": 1000, "cpu": 2000 }
+			}]
+		}),
+	)])
+	.unwrap();
+	let response = client_for(addr).evaluate_transaction(&hex!("aabbccdd")).await.unwrap();
+	assert_eq!(
+		response,
+		vec![OgmiosEvaluateTransactionResponse {
+			validator: OgmiosValidatorIndex::new(0, "mint"),
+			budget: OgmiosBudget::new(1000, 2000)
+		}]
+	);
+	assert_eq!(requests.lock().unwrap()[0].body, b"aabbccdd".to_vec());
+}
+
+#[tokio::test]
+async fn transaction_status() {
+	let tx_hash =
+		McTxHash(hex!("106b0d7d1544c97941777041699412fb7c8b94855210987327199620c0599580"));
+	let in_mempool = McTxHash([1; 32]);
+	let unknown = McTxHash([2; 32]);
+	let (addr, _) = http_server::for_responses(vec![
+		(
+			"/txs/106b0d7d1544c97941777041699412fb7c8b94855210987327199620c0599580",
+			200,
+			json!({ "hash": "106b0d7d1544c97941777041699412fb7c8b94855210987327199620c0599580", "block_height": 98 }),
+		),
+		("/blocks/latest", 200, json!({"hash": "aa", "slot": 42000, "height": 100})),
+		(
+			"/mempool/0101010101010101010101010101010101010101010101010101010101010101",
+			200,
+			json!({ "tx": { "hash": "0101010101010101010101010101010101010101010101010101010101010101" } }),
+		),
+	])
+	.unwrap();
+	let client = client_for(addr);
+	assert_eq!(
+		client.transaction_status(tx_hash).await.unwrap(),
+		TransactionStatus::Confirmed { depth: 3 }
+	);
+	assert_eq!(client.transaction_status(in_mempool).await.unwrap(), TransactionStatus::InMempool);
+	assert_eq!(client.transaction_status(unknown).await.unwrap(), TransactionStatus::Unknown);
+	assert_eq!(client.get_transaction_block_height(tx_hash).await.unwrap(), Some(98));
+	assert_eq!(client.get_transaction_block_height(unknown).await.unwrap(), None);
+}
@@ -0,0 +1,172 @@
+#![cfg(feature = "jsonrpsee-client")]
+
+use hex_literal::hex;
+use jsonrpsee::types::{ErrorCode, ErrorObject};
+use ogmios_client::{
+	chain_sync::{ChainSync, FindIntersectionResponse, NextBlockResponse, Point, Tip},
+	jsonrpsee::client_for_url,
+	types::OgmiosValue,
+};
+use serde_json::{Value, json};
+use sidechain_domain::UtxoId;
+use std::{
+	collections::VecDeque,
+	sync::{Arc, Mutex},
+	time::Duration,
+};
+
+mod server;
+
+const NEXT_BLOCK_FORWARD: &str = include_str!("fixtures/next_block_forward.json");
+
+const TIP: Tip = Tip::Block {
+	slot: 73209600,
+	id: hex!("9a0e5f7a3b1d2c4e6f8091a2b3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d5e6"),
+	height: 2891377,
+};
+
+const INTERSECTION: Point = Point::Block {
+	slot: 73209500,
+	id: hex!("a3d1bd7f2bfb8c5c4c6e1f8e6c1d5a3f72e0a6c7e9b4f5d8c2e1a0b9c8d7e6f5"),
+};
+
+fn tip_json() -> Value {
+	json!({
+		"slot": 73209600,
+		"id": "9a0e5f7a3b1d2c4e6f8091a2b3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d5e6",
+		"height": 2891377
+	})
+}
+
+fn intersection_json() -> Value {
+	json!({
+		"slot": 73209500,
+		"id": "a3d1bd7f2bfb8c5c4c6e1f8e6c1d5a3f72e0a6c7e9b4f5d8c2e1a0b9c8d7e6f5"
+	})
+}
+
+#[tokio::test]
+async fn follow_chain_from_intersection() {
+	let next_blocks = Arc::new(Mutex::new(VecDeque::from(vec![
+		json!({ "direction": "backward", "point": intersection_json(), "tip": tip_json() }),
+		serde_json::from_str::<Value>(NEXT_BLOCK_FORWARD).unwrap(),
+		json!({ "direction": "backward", "point": intersection_json(), "tip": tip_json() }),
+	])));
+	let address = server::for_methods(vec![
+		(
+			"findIntersection",
+			Box::new(|params| {
+				let points = params
+					.parse()
+					.ok()
+					.and_then(|params: Value| params.pointer("/points").cloned())
+					.filter(|points| *points == json!([intersection_json(), "origin"]))
+					.ok_or(ErrorObject::owned(
+						ErrorCode::InvalidParams.code(),
+						"invalid points parameter",
+						None::<()>,
+					))?;
+				Ok(json!({ "intersection": points[0], "tip": tip_json() }))
+			}),
+		),
+		(
+			"nextBlock",
+			Box::new(move |_| {
+				Ok(next_blocks.lock().unwrap().pop_front().expect("unexpected nextBlock request"))
+			}),
+		),
+	])
+	.await
+	.unwrap();
+	let client = client_for_url(&format!("ws://{address}"), Duration::from_secs(5))
+		.await
+		.unwrap();
+
+	let intersection = client.find_intersection(&[INTERSECTION, Point::Origin]).await.unwrap();
+	assert_eq!(intersection, FindIntersectionResponse { intersection: INTERSECTION, tip: TIP });
+
+	let rollback = client.next_block().await.unwrap();
+	assert_eq!(rollback, NextBlockResponse::Backward { point: INTERSECTION, tip: TIP });
+
+	let NextBlockResponse::Forward { block, tip } = client.next_block().await.unwrap() else {
+		panic!("expected forward response");
+	};
+	assert_eq!(tip, TIP);
+	assert_eq!(block.height, 2891374);
+	assert_eq!(
+		block.ancestor,
+		Some(hex!("a3d1bd7f2bfb8c5c4c6e1f8e6c1d5a3f72e0a6c7e9b4f5d8c2e1a0b9c8d7e6f5"))
+	);
+	assert_eq!(
+		block.point(),
+		Some(Point::Block {
+			slot: 73209532,
+			id: hex!("4e5b8b5c7d2ab7c5e4cf6be8bd0c5dcf1b9e1c1c1e0f9b4bd04a08c4a52f3a91")
+		})
+	);
+	assert_eq!(block.transactions.len(), 2);
+
+	let valid_tx = &block.transactions[0];
+	assert_eq!(
+		valid_tx.spent_utxos(),
+		vec![UtxoId::new(
+			hex!("f5f1bdfad3eb4d67d2fc36f36f47fc2938cf6f001689184ab320735a28642cf2"),
+			3
+		)]
+	);
+	let created = valid_tx.created_utxos();
+	assert_eq!(created.len(), 2);
+	assert_eq!(
+		created[1].utxo_id(),
+		UtxoId::new(hex!("106b0d7d1544c97941777041699412fb7c8b94855210987327199620c0599580"), 1)
+	);
+	assert_eq!(created[1].value, OgmiosValue::new_lovelace(99000000));
+	assert_eq!(created[0].datum, Some(hex!("d8799fff").to_vec().into()));
+
+	let failed_tx = &block.transactions[1];
+	assert_eq!(
+		failed_tx.spent_utxos(),
+		vec![UtxoId::new(
+			hex!("f5f1bdfad3eb4d67d2fc36f36f47fc2938cf6f001689184ab320735a28642cf2"),
+			5
+		)]
+	);
+	assert_eq!(failed_tx.created_utxos(), vec![]);
+
+	let rollback = client.next_block().await.unwrap();
+	assert_eq!(rollback.tip(), TIP);
+}
+
+#[tokio::test]
+async fn find_intersection_at_origin() {
+	let address = server::for_single_test("findIntersection", |params| {
+		let points: Value = params.parse()?;
+		assert_eq!(points, json!({ "points": ["origin"] }));
+		Ok(json!({ "intersection": "origin", "tip": "origin" }))
+	})
+	.await
+	.unwrap();
+	let client = client_for_url(&format!("ws://{address}"), Duration::from_secs(5))
+		.await
+		.unwrap();
+	let intersection = client.find_intersection(&[Point::Origin]).await.unwrap();
+	assert_eq!(
+		intersection,
+		FindIntersectionResponse { intersection: Point::Origin, tip: Tip::Origin }
+	);
+	assert_eq!(intersection.tip.height(), 0);
+}
+
+#[tokio::test]
+async fn find_intersection_not_found() {
+	let address = server::for_single_test("findIntersection", |_| {
+		Err(ErrorObject::owned(1000, "No intersection found.", Some(json!({ "tip": tip_json() }))))
+	})
+	.await
+	.unwrap();
+	let client = client_for_url(&format!("ws://{address}"), Duration::from_secs(5))
+		.await
+		.unwrap();
+	let error = client.find_intersection(&[INTERSECTION]).await.unwrap_err();
+	assert!(error.to_string().contains("No intersection found"));
+}
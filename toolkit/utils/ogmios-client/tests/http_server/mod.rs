@@ -0,0 +1,83 @@
+use std::{
+	collections::HashMap,
+	io::{BufRead, BufReader, Read, Write},
+	net::{SocketAddr, TcpListener, TcpStream},
+	sync::{Arc, Mutex},
+};
+
+/// Request received by the mock server
+#[derive(Clone, Debug)]
+pub struct RecordedRequest {
+	pub method: String,
+	pub path: String,
+	pub headers: HashMap<String, String>,
+	pub body: Vec<u8>,
+}
+
+/// Minimal HTTP/1.1 server serving canned responses for the given paths.
+/// Unknown paths result in 404 Not Found.
+/// Runs on a dedicated thread, because the tested client performs blocking requests.
+pub fn for_responses(
+	responses: Vec<(&'static str, u16, serde_json::Value)>,
+) -> anyhow::Result<(SocketAddr, Arc<Mutex<Vec<RecordedRequest>>>)> {
+	let listener = TcpListener::bind("127.0.0.1:0")?;
+	let addr = listener.local_addr()?;
+	let recorded = Arc::new(Mutex::new(Vec::new()));
+	let recorded_clone = recorded.clone();
+	let responses: HashMap<String, (u16, serde_json::Value)> = responses
+		.into_iter()
+		.map(|(path, status, body)| (path.to_string(), (status, body)))
+		.collect();
+	// It will stop when test main exists.
+	std::thread::spawn(move || {
+		for stream in listener.incoming().flatten() {
+			let _ = handle(stream, &responses, &recorded_clone);
+		}
+	});
+	Ok((addr, recorded))
+}
+
+fn handle(
+	mut stream: TcpStream,
+	responses: &HashMap<String, (u16, serde_json::Value)>,
+	recorded: &Mutex<Vec<RecordedRequest>>,
+) -> std::io::Result<()> {
+	let mut reader = BufReader::new(stream.try_clone()?);
+	let mut request_line = String::new();
+	reader.read_line(&mut request_line)?;
+	let mut parts = request_line.split_whitespace();
+	let method = parts.next().unwrap_or_default().to_string();
+	let path = parts.next().unwrap_or_default().to_string();
+	let mut headers = HashMap::new();
+	loop {
+		let mut line = String::new();
+		reader.read_line(&mut line)?;
+		let line = line.trim_end();
+		if line.is_empty() {
+			break;
+		}
+		if let Some((name, value)) = line.split_once(':') {
+			headers.insert(name.trim().to_lowercase(), value.trim().to_string());
+		}
+	}
+	let content_length =
+		headers.get("content-length").and_then(|l| l.parse::<usize>().ok()).unwrap_or(0);
+	let mut body = vec![0u8; content_length];
+	reader.read_exact(&mut body)?;
+	recorded
+		.lock()
+		.unwrap()
+		.push(RecordedRequest { method, path: path.clone(), headers, body });
+
+	let (status, body) = responses
+		.get(&path)
+		.cloned()
+		.unwrap_or((404, serde_json::json!({"status_code": 404, "message": "Not Found"})));
+	let body = body.to_string();
+	write!(
+		stream,
+		"HTTP/1.1 {status} Status\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+		body.len()
+	)?;
+	stream.flush()
+}
@@ -0,0 +1,203 @@
+#![cfg(feature = "jsonrpsee-client")]
+
+use hex_literal::hex;
+use jsonrpsee::types::{ErrorCode, ErrorObject};
+use ogmios_client::{
+	chain_sync::Point,
+	jsonrpsee::client_for_url,
+	mempool::{
+		AcquireMempoolResponse, MempoolMonitoring, MempoolSizeAndCapacity,
+		MempoolTransactionsCount, QueryTransactionStatus, TransactionStatus,
+	},
+	types::{OgmiosBytesSize, OgmiosTx},
+};
+use serde_json::{Value, json};
+use sidechain_domain::McTxHash;
+use std::{
+	collections::VecDeque,
+	sync::{Arc, Mutex},
+	time::Duration,
+};
+
+mod server;
+
+const TX_HASH: McTxHash =
+	McTxHash(hex!("106b0d7d1544c97941777041699412fb7c8b94855210987327199620c0599580"));
+
+fn expect_tx_id(params: jsonrpsee::types::Params) -> Result<(), ErrorObject<'static>> {
+	let _ = params
+		.parse()
+		.ok()
+		.and_then(|params: Value| params.pointer("/id").cloned())
+		.filter(|id| {
+			*id == json!("106b0d7d1544c97941777041699412fb7c8b94855210987327199620c0599580")
+		})
+		.ok_or(ErrorObject::owned(
+			ErrorCode::InvalidParams.code(),
+			"invalid id parameter",
+			None::<()>,
+		))?;
+	Ok(())
+}
+
+async fn client_for(methods: Vec<(&'static str, server::Handler)>) -> impl MempoolMonitoring {
+	let address = server::for_methods(methods).await.unwrap();
+	client_for_url(&format!("ws://{address}"), Duration::from_secs(5))
+		.await
+		.unwrap()
+}
+
+#[tokio::test]
+async fn monitor_mempool() {
+	let next_transactions = Arc::new(Mutex::new(VecDeque::from(vec![
+		json!({ "transaction": { "id": "106b0d7d1544c97941777041699412fb7c8b94855210987327199620c0599580" } }),
+		json!({ "transaction": null }),
+	])));
+	let client = client_for(vec![
+		("acquireMempool", Box::new(|_| Ok(json!({ "acquired": "mempool", "slot": 1234 })))),
+		(
+			"hasTransaction",
+			Box::new(|params| {
+				expect_tx_id(params)?;
+				Ok(json!(true))
+			}),
+		),
+		(
+			"nextTransaction",
+			Box::new(move |_| Ok(next_transactions.lock().unwrap().pop_front().unwrap())),
+		),
+		("releaseMempool", Box::new(|_| Ok(json!({ "released": "mempool" })))),
+	])
+	.await;
+
+	assert_eq!(client.acquire_mempool().await.unwrap(), AcquireMempoolResponse { slot: 1234 });
+	assert!(client.has_transaction(TX_HASH).await.unwrap());
+	assert_eq!(client.next_transaction().await.unwrap(), Some(OgmiosTx { id: TX_HASH.0 }));
+	assert_eq!(client.next_transaction().await.unwrap(), None);
+	client.release_mempool().await.unwrap();
+}
+
+#[tokio::test]
+async fn size_of_mempool() {
+	let address = server::for_single_test("sizeOfMempool", |_| {
+		Ok(json!({
+			"maxCapacity": { "bytes": 180224 },
+			"currentSize": { "bytes": 1024 },
+			"transactions": { "count": 1 }
+		}))
+	})
+	.await
+	.unwrap();
+	let client = client_for_url(&format!("ws://{address}"), Duration::from_secs(5))
+		.await
+		.unwrap();
+	assert_eq!(
+		client.size_of_mempool().await.unwrap(),
+		MempoolSizeAndCapacity {
+			max_capacity: OgmiosBytesSize { bytes: 180224 },
+			current_size: OgmiosBytesSize { bytes: 1024 },
+			transactions: MempoolTransactionsCount { count: 1 }
+		}
+	);
+}
+
+fn status_handlers(in_mempool: bool, utxos: Value) -> Vec<(&'static str, server::Handler)> {
+	vec![
+		("acquireMempool", Box::new(|_| Ok(json!({ "acquired": "mempool", "slot": 1234 })))),
+		(
+			"hasTransaction",
+			Box::new(move |params| {
+				expect_tx_id(params)?;
+				Ok(json!(in_mempool))
+			}),
+		),
+		("releaseMempool", Box::new(|_| Ok(json!({ "released": "mempool" })))),
+		("queryLedgerState/utxo", Box::new(move |_| Ok(utxos.clone()))),
+	]
+}
+
+async fn status_for(in_mempool: bool, utxos: Value) -> TransactionStatus {
+	let address = server::for_methods(status_handlers(in_mempool, utxos)).await.unwrap();
+	let client = client_for_url(&format!("ws://{address}"), Duration::from_secs(5))
+		.await
+		.unwrap();
+	client.transaction_status(TX_HASH).await.unwrap()
+}
+
+#[tokio::test]
+async fn transaction_status_in_mempool() {
+	assert_eq!(status_for(true, json!([])).await, TransactionStatus::InMempool);
+}
+
+#[tokio::test]
+async fn transaction_status_in_ledger() {
+	let utxos = json!([{
+		"transaction": { "id": "106b0d7d1544c97941777041699412fb7c8b94855210987327199620c0599580" },
+		"index": 0,
+		"address": "addr_test1vqezxrh24ts0775hulcg3ejcwj7hns8792vnn8met6z9gwsxt87zy",
+		"value": { "ada": { "lovelace": 1000000 } }
+	}]);
+	assert_eq!(status_for(false, utxos).await, TransactionStatus::InLedger);
+}
+
+#[tokio::test]
+async fn transaction_status_unknown() {
+	assert_eq!(status_for(false, json!([])).await, TransactionStatus::Unknown);
+}
+
+const NEXT_BLOCK_FORWARD: &str = include_str!("fixtures/next_block_forward.json");
+
+const SINCE: Point = Point::Block {
+	slot: 73209500,
+	id: hex!("a3d1bd7f2bfb8c5c4c6e1f8e6c1d5a3f72e0a6c7e9b4f5d8c2e1a0b9c8d7e6f5"),
+};
+
+fn tip_json() -> Value {
+	json!({
+		"slot": 73209600,
+		"id": "9a0e5f7a3b1d2c4e6f8091a2b3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d5e6",
+		"height": 2891377
+	})
+}
+
+#[tokio::test]
+async fn transaction_status_since_reports_depth() {
+	let since_json = json!({
+		"slot": 73209500,
+		"id": "a3d1bd7f2bfb8c5c4c6e1f8e6c1d5a3f72e0a6c7e9b4f5d8c2e1a0b9c8d7e6f5"
+	});
+	let tip_block = json!({
+		"direction": "forward",
+		"block": {
+			"type": "praos",
+			"era": "conway",
+			"id": "9a0e5f7a3b1d2c4e6f8091a2b3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d5e6",
+			"ancestor": "4e5b8b5c7d2ab7c5e4cf6be8bd0c5dcf1b9e1c1c1e0f9b4bd04a08c4a52f3a91",
+			"height": 2891377,
+			"slot": 73209600,
+			"transactions": []
+		},
+		"tip": tip_json()
+	});
+	let next_blocks = Arc::new(Mutex::new(VecDeque::from(vec![
+		json!({ "direction": "backward", "point": since_json.clone(), "tip": tip_json() }),
+		serde_json::from_str::<Value>(NEXT_BLOCK_FORWARD).unwrap(),
+		tip_block,
+	])));
+	let find_intersection: server::Handler =
+		Box::new(move |_| Ok(json!({ "intersection": since_json.clone(), "tip": tip_json() })));
+	let next_block: server::Handler = Box::new(move |_| {
+		Ok(next_blocks.lock().unwrap().pop_front().expect("unexpected nextBlock request"))
+	});
+	let mut handlers = status_handlers(false, json!([]));
+	handlers.push(("findIntersection", find_intersection));
+	handlers.push(("nextBlock", next_block));
+	let address = server::for_methods(handlers).await.unwrap();
+	let client = client_for_url(&format!("ws://{address}"), Duration::from_secs(5))
+		.await
+		.unwrap();
+	assert_eq!(
+		client.transaction_status_since(TX_HASH, SINCE).await.unwrap(),
+		TransactionStatus::Confirmed { depth: 4 }
+	);
+}
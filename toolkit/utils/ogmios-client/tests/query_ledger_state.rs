@@ -7,6 +7,7 @@ use ogmios_client::{
 		EpochBoundary, EpochParameters, EraSummary, PlutusCostModels, ProtocolParametersResponse,
 		QueryLedgerState, QueryUtxoByUtxoId, ReferenceScriptsCosts, ScriptExecutionPrices,
 	},
+	transactions::OgmiosBudget,
 	types::{Asset, OgmiosBytesSize, OgmiosTx, OgmiosUtxo, OgmiosValue, SlotLength, TimeSeconds},
 };
 use serde_json::json;
@@ -165,6 +166,10 @@ async fn protocol_parameters() {
 			"base": 10.0,
 			"range": 0,
 			"multiplier": 2.0
+		  },
+		  "maxExecutionUnitsPerTransaction": {
+			"memory": 14000000,
+			"cpu": 1000000000
 		  }
 		}))
 	})
@@ -196,7 +201,8 @@ async fn protocol_parameters() {
 			},
 			max_collateral_inputs: 3,
 			collateral_percentage: 150,
-			min_fee_reference_scripts: ReferenceScriptsCosts { base: 10.0 }
+			min_fee_reference_scripts: ReferenceScriptsCosts { base: 10.0 },
+			max_execution_units_per_transaction: Some(OgmiosBudget::new(14000000, 1000000000)),
 		}
 	);
 }
@@ -61,3 +61,25 @@ async fn shelley_genesis_configuration() {
 		}
 	)
 }
+
+#[tokio::test]
+async fn get_block_height() {
+	let address = server::for_single_test("queryNetwork/blockHeight", |_| Ok(json!(1234567)))
+		.await
+		.unwrap();
+	let client = client_for_url(&format!("ws://{address}"), Duration::from_secs(5))
+		.await
+		.unwrap();
+	assert_eq!(client.get_block_height().await.unwrap(), 1234567);
+}
+
+#[tokio::test]
+async fn get_block_height_at_origin() {
+	let address = server::for_single_test("queryNetwork/blockHeight", |_| Ok(json!("origin")))
+		.await
+		.unwrap();
+	let client = client_for_url(&format!("ws://{address}"), Duration::from_secs(5))
+		.await
+		.unwrap();
+	assert_eq!(client.get_block_height().await.unwrap(), 0);
+}
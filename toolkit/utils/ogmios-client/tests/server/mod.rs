@@ -21,3 +21,23 @@ where
 	tokio::spawn(handle.stopped());
 	Ok(addr)
 }
+
+#[allow(dead_code)]
+pub type Handler = Box<dyn Fn(Params) -> Result<Value, ErrorObjectOwned> + Send + Sync>;
+
+/// Server handling multiple methods, for tests of stateful protocols.
+#[allow(dead_code)]
+pub async fn for_methods(methods: Vec<(&'static str, Handler)>) -> anyhow::Result<SocketAddr> {
+	let server = Server::builder().build("127.0.0.1:0".parse::<SocketAddr>()?).await?;
+	let mut module = RpcModule::new(());
+	for (method, handler) in methods {
+		module.register_method(method, move |params: Params, _ctx: &(), _e: &Extensions| {
+			handler(params)
+		})?;
+	}
+	let addr = server.local_addr()?;
+	let handle = server.start(module);
+	// It will stop when test main exists.
+	tokio::spawn(handle.stopped());
+	Ok(addr)
+}
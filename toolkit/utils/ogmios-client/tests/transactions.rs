@@ -8,7 +8,7 @@ use ogmios_client::{
 		OgmiosBudget, OgmiosEvaluateTransactionResponse, OgmiosValidatorIndex,
 		SubmitTransactionResponse, Transactions,
 	},
-	types::OgmiosTx,
+	types::{Asset, Datum, OgmiosTx, OgmiosUtxo, OgmiosValue},
 };
 use serde_json::json;
 use std::time::Duration;
@@ -50,6 +50,68 @@ async fn evaluate_transaction() {
 	)
 }
 
+#[tokio::test]
+async fn evaluate_transaction_with_additional_utxos() {
+	let address = server::for_single_test("evaluateTransaction", |req| {
+		let expected_params = json!({
+			"transaction": { "cbor": "aabbccdd" },
+			"additionalUtxo": [{
+				"transaction": { "id": "e4891cd4e45c320301fff691bbd1bee0cf4484fc2ddc26c08c555d08efbb7d6b" },
+				"index": 1,
+				"address": "addr_test1",
+				"value": {
+					"ada": { "lovelace": 2000000 },
+					"e0d4479b3dbb53b1aecd48f7ef524a9cf166585923d91d9c72ed02cb": { "aabb": 5 }
+				},
+				"datum": "d8799fff"
+			}]
+		});
+		let params_value: serde_json::Value = req.parse().unwrap();
+		if params_value == expected_params {
+			Ok(json!([{
+			  "validator": {
+				"index": 0,
+				"purpose": "spend"
+			  },
+			  "budget": {
+				"memory": 202586,
+				"cpu": 43653414
+			  }
+			}]))
+		} else {
+			Err(ErrorCode::InvalidParams.into())
+		}
+	})
+	.await
+	.unwrap();
+	let client = client_for_url(&format!("ws://{address}"), Duration::from_secs(5))
+		.await
+		.unwrap();
+	let additional_utxo = OgmiosUtxo {
+		transaction: OgmiosTx {
+			id: hex!("e4891cd4e45c320301fff691bbd1bee0cf4484fc2ddc26c08c555d08efbb7d6b"),
+		},
+		index: 1,
+		address: "addr_test1".into(),
+		value: OgmiosValue {
+			lovelace: 2000000,
+			native_tokens: [(
+				hex!("e0d4479b3dbb53b1aecd48f7ef524a9cf166585923d91d9c72ed02cb"),
+				vec![Asset { name: hex!("aabb").to_vec(), amount: 5 }],
+			)]
+			.into(),
+		},
+		datum: Some(Datum { bytes: hex!("d8799fff").to_vec() }),
+		datum_hash: None,
+		script: None,
+	};
+	let response = client
+		.evaluate_transaction_with_additional_utxos(&hex!("aabbccdd"), &[additional_utxo])
+		.await
+		.unwrap();
+	assert_eq!(response[0].budget, OgmiosBudget { memory: 202586, cpu: 43653414 })
+}
+
 #[tokio::test]
 async fn submit_transaction() {
 	let address = server::for_single_test("submitTransaction", |req| {